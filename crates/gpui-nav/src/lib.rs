@@ -62,7 +62,10 @@
 
 pub mod context;
 mod navigator;
+#[cfg(test)]
+mod navigator_tests;
 mod screen;
+mod tab_navigator;
 
 #[cfg(test)]
 mod tests;
@@ -70,6 +73,7 @@ mod tests;
 pub use context::ScreenContext;
 pub use navigator::Navigator;
 pub use screen::Screen;
+pub use tab_navigator::TabNavigator;
 
 /// Prelude module for convenient imports
 ///
@@ -78,9 +82,9 @@ pub use screen::Screen;
 /// ```rust
 /// use gpui_nav::prelude::*;
 ///
-/// // Now you have access to Navigator, Screen, and ScreenContext
+/// // Now you have access to Navigator, Screen, ScreenContext and TabNavigator
 /// ```
 pub mod prelude {
     /// Convenient re-exports of commonly used gpui-nav types
-    pub use crate::{Navigator, Screen, ScreenContext};
+    pub use crate::{Navigator, Screen, ScreenContext, TabNavigator};
 }
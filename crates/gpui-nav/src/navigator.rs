@@ -1,5 +1,8 @@
 use crate::screen::Screen;
-use gpui::{AnyView, AppContext, Context, Entity};
+use gpui::{
+    AnyView, AppContext, Context, Entity, EntityId, InteractiveElement, IntoElement, ParentElement,
+    Styled, div, hsla,
+};
 
 /// A navigation stack that manages screen transitions.
 ///
@@ -19,6 +22,12 @@ use gpui::{AnyView, AppContext, Context, Entity};
 pub struct Navigator {
     stack: Vec<AnyView>,
     history: Vec<&'static str>,
+    /// Screens presented over the stack, the last one on top.
+    modals: Vec<AnyView>,
+    modal_history: Vec<&'static str>,
+    /// Screens popped or replaced, kept to be restored, the most recent last.
+    cache: Vec<(&'static str, AnyView)>,
+    cache_size: usize,
 }
 
 impl Navigator {
@@ -37,6 +46,95 @@ impl Navigator {
         Self {
             stack: Vec::new(),
             history: Vec::new(),
+            modals: Vec::new(),
+            modal_history: Vec::new(),
+            cache: Vec::new(),
+            cache_size: 0,
+        }
+    }
+
+    /// Keeps up to `size` screens once popped or replaced, so that
+    /// [`push_or_restore`](Self::push_or_restore) brings them back with their state,
+    /// like their scroll position or what was typed in their forms.
+    ///
+    /// Screens are recreated when pushed again by default. When the cache is full, the
+    /// screen left the longest ago is dropped.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use gpui_nav::Navigator;
+    ///
+    /// let navigator = Navigator::new().with_cache_size(4);
+    /// assert_eq!(navigator.cache_size(), 4);
+    /// ```
+    #[must_use]
+    pub fn with_cache_size(mut self, size: usize) -> Self {
+        self.cache_size = size;
+        self
+    }
+
+    /// Returns the number of screens kept once left, 0 when none are.
+    #[must_use]
+    pub fn cache_size(&self) -> usize {
+        self.cache_size
+    }
+
+    /// Returns the IDs of the screens kept to be restored, the most recently left last.
+    pub fn cached_ids(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.cache.iter().map(|(id, _)| *id)
+    }
+
+    /// Drops the screens kept to be restored.
+    pub fn clear_cache(&mut self) {
+        self.cache.clear();
+    }
+
+    /// Pushes the screen last left with this ID as it was, or the one built by `build`
+    /// when none was kept.
+    ///
+    /// Returns `true` if the screen was restored from the cache.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// // The settings form keeps what was typed if it was left before
+    /// navigator.push_or_restore("settings", || SettingsScreen::new(ctx.weak_entity()), cx);
+    /// ```
+    pub fn push_or_restore<S: Screen, T: 'static>(
+        &mut self,
+        id: &'static str,
+        build: impl FnOnce() -> S,
+        cx: &mut Context<T>,
+    ) -> bool {
+        match self
+            .cache
+            .iter()
+            .rposition(|(cached_id, _)| *cached_id == id)
+        {
+            Some(index) => {
+                let (id, view) = self.cache.remove(index);
+                self.stack.push(view);
+                self.history.push(id);
+                cx.notify();
+                true
+            }
+            None => {
+                self.push(build(), cx);
+                false
+            }
+        }
+    }
+
+    /// Keeps a screen that left the stack, if the cache is enabled.
+    fn cache_screen(&mut self, id: &'static str, view: AnyView) {
+        if self.cache_size == 0 {
+            return;
+        }
+        self.cache.retain(|(cached_id, _)| *cached_id != id);
+        self.cache.push((id, view));
+        if self.cache.len() > self.cache_size {
+            self.cache.remove(0);
         }
     }
 
@@ -76,9 +174,10 @@ impl Navigator {
     /// }
     /// ```
     pub fn pop<T: 'static>(&mut self, cx: &mut Context<T>) -> bool {
-        if self.stack.len() > 1 {
-            self.stack.pop();
-            self.history.pop();
+        if self.stack.len() > 1
+            && let (Some(view), Some(id)) = (self.stack.pop(), self.history.pop())
+        {
+            self.cache_screen(id, view);
             cx.notify();
             true
         } else {
@@ -100,16 +199,96 @@ impl Navigator {
     /// }
     /// ```
     pub fn replace<S: Screen, T: 'static>(&mut self, screen: S, cx: &mut Context<T>) -> bool {
-        if self.stack.is_empty() {
-            false
-        } else {
-            self.stack.pop();
-            self.history.pop();
+        if let (Some(view), Some(id)) = (self.stack.pop(), self.history.pop()) {
+            self.cache_screen(id, view);
             self.push(screen, cx);
             true
+        } else {
+            false
         }
     }
 
+    /// Presents a screen modally, over the stack and the screens already presented.
+    ///
+    /// Modal screens are tracked separately from the stack: pushing, popping or
+    /// replacing screens leaves them presented, and they are reported by
+    /// [`modal_history`](Self::modal_history) instead of [`history`](Self::history).
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// app.navigator.present(ConfirmScreen::new(ctx.weak_entity()), cx);
+    /// ```
+    pub fn present<S: Screen, T: 'static>(&mut self, screen: S, cx: &mut Context<T>) {
+        let screen_id = screen.id();
+        let entity: Entity<S> = cx.new(|_| screen);
+        self.modals.push(entity.into());
+        self.modal_history.push(screen_id);
+        cx.notify();
+    }
+
+    /// Dismisses the modal screen on top.
+    ///
+    /// Returns `true` if a screen was dismissed, `false` if none is presented.
+    pub fn dismiss<T: 'static>(&mut self, cx: &mut Context<T>) -> bool {
+        if self.modals.pop().is_some() {
+            self.modal_history.pop();
+            cx.notify();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns the modal screen on top, if any.
+    #[must_use]
+    pub fn presented(&self) -> Option<&AnyView> {
+        self.modals.last()
+    }
+
+    /// Returns the IDs of the presented screens, from the first one presented.
+    #[must_use]
+    pub fn modal_history(&self) -> &[&'static str] {
+        &self.modal_history
+    }
+
+    /// Returns whether a modal screen is presented.
+    #[must_use]
+    pub fn is_presenting(&self) -> bool {
+        !self.modals.is_empty()
+    }
+
+    /// Returns the modal screen on top over a scrim covering its parent, to render
+    /// above the current screen. The parent must be positioned, e.g. `relative()`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// div()
+    ///     .relative()
+    ///     .children(app.navigator.current().cloned())
+    ///     .children(app.navigator.modal_layer())
+    /// ```
+    #[must_use]
+    pub fn modal_layer(&self) -> Option<impl IntoElement> {
+        let modal = self.modals.last()?.clone();
+
+        Some(
+            div()
+                .absolute()
+                .top_0()
+                .left_0()
+                .size_full()
+                .flex()
+                .items_center()
+                .justify_center()
+                .bg(hsla(0., 0., 0., 0.4))
+                // The screens below don't receive the clicks
+                .occlude()
+                .child(modal),
+        )
+    }
+
     /// Returns a reference to the current screen, if any.
     #[must_use]
     pub fn current(&self) -> Option<&AnyView> {
@@ -122,12 +301,28 @@ impl Navigator {
         &self.history
     }
 
-    /// Clears the entire stack and pushes a new root screen.
+    /// Returns the screens of the stack from the root, as their ID and the ID of their
+    /// entity.
+    ///
+    /// Screens pushed several times share an ID but not an entity, which tells them
+    /// apart when debugging a navigation flow.
+    pub fn entries(&self) -> impl Iterator<Item = (&'static str, EntityId)> + '_ {
+        self.history
+            .iter()
+            .copied()
+            .zip(self.stack.iter().map(AnyView::entity_id))
+    }
+
+    /// Clears the entire stack and pushes a new root screen. Presented screens are
+    /// dismissed as well, and the screens kept by the cache dropped.
     ///
     /// Useful for logout flows or resetting the app state.
     pub fn clear_and_push<S: Screen, T: 'static>(&mut self, screen: S, cx: &mut Context<T>) {
         self.stack.clear();
         self.history.clear();
+        self.modals.clear();
+        self.modal_history.clear();
+        self.cache.clear();
         self.push(screen, cx);
     }
 
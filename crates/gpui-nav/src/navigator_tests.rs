@@ -0,0 +1,210 @@
+use std::{cell::Cell, rc::Rc};
+
+use gpui::prelude::*;
+use gpui::{Entity, EntityId, TestAppContext, Window, div};
+
+use crate::{Navigator, Screen, TabNavigator};
+
+struct TestScreen {
+    id: &'static str,
+}
+
+impl Screen for TestScreen {
+    fn id(&self) -> &'static str {
+        self.id
+    }
+}
+
+impl Render for TestScreen {
+    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+        div().child(self.id)
+    }
+}
+
+/// Stands for the app state owning the navigator, whose context the navigator notifies.
+struct Host {
+    navigator: Navigator,
+}
+
+fn host(navigator: Navigator, cx: &mut TestAppContext) -> Entity<Host> {
+    cx.new(|_| Host { navigator })
+}
+
+fn push(host: &Entity<Host>, id: &'static str, cx: &mut TestAppContext) {
+    host.update(cx, |host, cx| host.navigator.push(TestScreen { id }, cx));
+}
+
+/// ID read from the entity of the current screen, rather than from the history.
+fn current_id(host: &Entity<Host>, cx: &mut TestAppContext) -> Option<&'static str> {
+    cx.update(|cx| {
+        let view = host.read(cx).navigator.current()?.clone();
+        Some(view.downcast::<TestScreen>().ok()?.read(cx).id)
+    })
+}
+
+fn current_entity(host: &Entity<Host>, cx: &mut TestAppContext) -> Option<EntityId> {
+    cx.update(|cx| {
+        host.read(cx)
+            .navigator
+            .current()
+            .map(|view| view.entity_id())
+    })
+}
+
+fn history(host: &Entity<Host>, cx: &mut TestAppContext) -> Vec<&'static str> {
+    cx.update(|cx| host.read(cx).navigator.history().to_vec())
+}
+
+#[gpui::test]
+fn test_push_and_pop(cx: &mut TestAppContext) {
+    let host = host(Navigator::new(), cx);
+
+    push(&host, "home", cx);
+    push(&host, "settings", cx);
+    assert_eq!(current_id(&host, cx), Some("settings"));
+    assert_eq!(history(&host, cx), ["home", "settings"]);
+
+    assert!(host.update(cx, |host, cx| host.navigator.pop(cx)));
+    assert_eq!(current_id(&host, cx), Some("home"));
+    assert_eq!(history(&host, cx), ["home"]);
+
+    // The root screen stays
+    assert!(!host.update(cx, |host, cx| host.navigator.pop(cx)));
+    assert_eq!(current_id(&host, cx), Some("home"));
+}
+
+#[gpui::test]
+fn test_replace_and_clear_and_push(cx: &mut TestAppContext) {
+    let host = host(Navigator::new(), cx);
+    assert!(!host.update(cx, |host, cx| {
+        host.navigator.replace(TestScreen { id: "login" }, cx)
+    }));
+    assert!(host.read_with(cx, |host, _| host.navigator.is_empty()));
+
+    push(&host, "home", cx);
+    push(&host, "login", cx);
+    assert!(host.update(cx, |host, cx| {
+        host.navigator.replace(TestScreen { id: "profile" }, cx)
+    }));
+    assert_eq!(current_id(&host, cx), Some("profile"));
+    assert_eq!(history(&host, cx), ["home", "profile"]);
+
+    host.update(cx, |host, cx| {
+        host.navigator
+            .clear_and_push(TestScreen { id: "welcome" }, cx)
+    });
+    assert_eq!(current_id(&host, cx), Some("welcome"));
+    assert_eq!(history(&host, cx), ["welcome"]);
+    assert!(!host.read_with(cx, |host, _| host.navigator.can_go_back()));
+}
+
+#[gpui::test]
+fn test_navigation_notifies_the_owner(cx: &mut TestAppContext) {
+    let host = host(Navigator::new(), cx);
+    let notifications = Rc::new(Cell::new(0));
+    cx.update(|cx| {
+        let notifications = notifications.clone();
+        cx.observe(&host, move |_, _| {
+            notifications.set(notifications.get() + 1)
+        })
+        .detach();
+    });
+
+    push(&host, "home", cx);
+    push(&host, "settings", cx);
+    host.update(cx, |host, cx| host.navigator.pop(cx));
+    assert_eq!(notifications.get(), 3);
+
+    // Nothing changes, so nothing to render again
+    host.update(cx, |host, cx| host.navigator.pop(cx));
+    host.update(cx, |host, cx| host.navigator.dismiss(cx));
+    assert_eq!(notifications.get(), 3);
+}
+
+#[gpui::test]
+fn test_modals_are_kept_apart_from_the_stack(cx: &mut TestAppContext) {
+    let host = host(Navigator::new(), cx);
+    push(&host, "home", cx);
+
+    host.update(cx, |host, cx| {
+        host.navigator.present(TestScreen { id: "confirm" }, cx)
+    });
+    push(&host, "settings", cx);
+    assert_eq!(history(&host, cx), ["home", "settings"]);
+    host.read_with(cx, |host, _| {
+        assert!(host.navigator.is_presenting());
+        assert_eq!(host.navigator.modal_history(), ["confirm"]);
+    });
+
+    assert!(host.update(cx, |host, cx| host.navigator.dismiss(cx)));
+    assert_eq!(current_id(&host, cx), Some("settings"));
+    assert!(!host.read_with(cx, |host, _| host.navigator.is_presenting()));
+}
+
+#[gpui::test]
+fn test_cached_screens_are_restored(cx: &mut TestAppContext) {
+    let host = host(Navigator::new().with_cache_size(1), cx);
+    push(&host, "home", cx);
+    push(&host, "settings", cx);
+    let settings = current_entity(&host, cx);
+
+    host.update(cx, |host, cx| host.navigator.pop(cx));
+    let cached = host.read_with(cx, |host, _| {
+        host.navigator.cached_ids().collect::<Vec<_>>()
+    });
+    assert_eq!(cached, ["settings"]);
+    let restored = host.update(cx, |host, cx| {
+        host.navigator
+            .push_or_restore("settings", || TestScreen { id: "settings" }, cx)
+    });
+    assert!(restored);
+    assert_eq!(current_entity(&host, cx), settings);
+    assert_eq!(history(&host, cx), ["home", "settings"]);
+
+    // Without a cache, the screen is built again
+    let host = self::host(Navigator::new(), cx);
+    push(&host, "home", cx);
+    push(&host, "settings", cx);
+    let settings = current_entity(&host, cx);
+    host.update(cx, |host, cx| host.navigator.pop(cx));
+    let restored = host.update(cx, |host, cx| {
+        host.navigator
+            .push_or_restore("settings", || TestScreen { id: "settings" }, cx)
+    });
+    assert!(!restored);
+    assert_ne!(current_entity(&host, cx), settings);
+}
+
+#[gpui::test]
+fn test_tabs_keep_their_stacks(cx: &mut TestAppContext) {
+    let tabs = cx.new(|_| TabNavigator::new());
+    let (home, settings) = tabs.update(cx, |tabs, _| {
+        (
+            tabs.add_tab(Navigator::new()),
+            tabs.add_tab(Navigator::new()),
+        )
+    });
+
+    tabs.update(cx, |tabs, cx| {
+        tabs.push(TestScreen { id: "home" }, cx);
+        tabs.push(TestScreen { id: "document" }, cx);
+        assert!(tabs.switch_tab(settings, cx));
+        tabs.push(TestScreen { id: "settings" }, cx);
+        assert!(!tabs.switch_tab(2, cx));
+    });
+    tabs.read_with(cx, |tabs, _| {
+        assert_eq!(tabs.active_index(), settings);
+        assert_eq!(
+            tabs.active().map(Navigator::history),
+            Some(&["settings"][..])
+        );
+    });
+
+    tabs.update(cx, |tabs, cx| tabs.switch_tab(home, cx));
+    tabs.read_with(cx, |tabs, _| {
+        assert_eq!(
+            tabs.active().map(Navigator::history),
+            Some(&["home", "document"][..])
+        );
+    });
+}
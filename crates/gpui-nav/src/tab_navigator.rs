@@ -0,0 +1,135 @@
+use crate::{navigator::Navigator, screen::Screen};
+use gpui::{AnyView, Context};
+
+/// Parallel navigation stacks, one per tab, of which one is shown at a time.
+///
+/// Switching tabs keeps the stack of every tab as it was, like the tab bars at the
+/// bottom or on the side of an app.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let mut tabs = TabNavigator::new();
+/// let home = tabs.add_tab(Navigator::new());
+/// let settings = tabs.add_tab(Navigator::new());
+///
+/// tabs.push(HomeScreen::new(ctx.weak_entity()), cx);
+/// tabs.switch_tab(settings, cx);
+/// ```
+pub struct TabNavigator {
+    tabs: Vec<Navigator>,
+    active: usize,
+}
+
+impl TabNavigator {
+    /// Creates a tab navigator without any tab.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use gpui_nav::TabNavigator;
+    ///
+    /// let tabs = TabNavigator::new();
+    /// assert!(tabs.is_empty());
+    /// ```
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            tabs: Vec::new(),
+            active: 0,
+        }
+    }
+
+    /// Adds a tab with its own stack and returns its index. The first tab added is the
+    /// active one.
+    pub fn add_tab(&mut self, navigator: Navigator) -> usize {
+        self.tabs.push(navigator);
+        self.tabs.len() - 1
+    }
+
+    /// Shows the tab at `index`, with its stack as it was left.
+    ///
+    /// Returns `false` if there is no such tab.
+    pub fn switch_tab<T: 'static>(&mut self, index: usize, cx: &mut Context<T>) -> bool {
+        if index >= self.tabs.len() {
+            return false;
+        }
+        if self.active != index {
+            self.active = index;
+            cx.notify();
+        }
+        true
+    }
+
+    /// Returns the index of the tab shown.
+    #[must_use]
+    pub fn active_index(&self) -> usize {
+        self.active
+    }
+
+    /// Returns the stack of the tab shown, if any.
+    #[must_use]
+    pub fn active(&self) -> Option<&Navigator> {
+        self.tabs.get(self.active)
+    }
+
+    /// Returns the stack of the tab shown, to navigate in it.
+    pub fn active_mut(&mut self) -> Option<&mut Navigator> {
+        self.tabs.get_mut(self.active)
+    }
+
+    /// Returns the stack of the tab at `index`, shown or not.
+    #[must_use]
+    pub fn tab(&self, index: usize) -> Option<&Navigator> {
+        self.tabs.get(index)
+    }
+
+    /// Returns the stack of the tab at `index`, to navigate in it while it isn't shown.
+    pub fn tab_mut(&mut self, index: usize) -> Option<&mut Navigator> {
+        self.tabs.get_mut(index)
+    }
+
+    /// Returns the current screen of the tab shown, if any.
+    #[must_use]
+    pub fn current(&self) -> Option<&AnyView> {
+        self.active()?.current()
+    }
+
+    /// Pushes a screen onto the stack of the tab shown.
+    ///
+    /// Returns `false` if there is no tab to push it onto.
+    pub fn push<S: Screen, T: 'static>(&mut self, screen: S, cx: &mut Context<T>) -> bool {
+        match self.active_mut() {
+            Some(navigator) => {
+                navigator.push(screen, cx);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Pops the current screen of the tab shown.
+    ///
+    /// Returns `true` if a screen was popped.
+    pub fn pop<T: 'static>(&mut self, cx: &mut Context<T>) -> bool {
+        self.active_mut().is_some_and(|navigator| navigator.pop(cx))
+    }
+
+    /// Returns the number of tabs.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.tabs.len()
+    }
+
+    /// Returns whether there is no tab.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.tabs.is_empty()
+    }
+}
+
+impl Default for TabNavigator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -13,6 +13,27 @@ mod tests {
         assert!(navigator.history().is_empty());
     }
 
+    #[test]
+    fn test_navigator_new_has_no_entries() {
+        let navigator = Navigator::new();
+        assert_eq!(navigator.entries().count(), 0);
+    }
+
+    #[test]
+    fn test_navigator_new_has_no_modals() {
+        let navigator = Navigator::new();
+        assert!(!navigator.is_presenting());
+        assert!(navigator.presented().is_none());
+        assert!(navigator.modal_history().is_empty());
+    }
+
+    #[test]
+    fn test_navigator_new_has_no_cache() {
+        let navigator = Navigator::new();
+        assert_eq!(navigator.cache_size(), 0);
+        assert_eq!(navigator.cached_ids().count(), 0);
+    }
+
     #[test]
     fn test_navigator_default() {
         let navigator = Navigator::default();
@@ -21,6 +42,21 @@ mod tests {
         assert!(!navigator.can_go_back());
     }
 
+    #[test]
+    fn test_tab_navigator_add_tab() {
+        let mut tabs = TabNavigator::new();
+        assert!(tabs.is_empty());
+        assert!(tabs.active().is_none());
+
+        assert_eq!(tabs.add_tab(Navigator::new()), 0);
+        assert_eq!(tabs.add_tab(Navigator::default()), 1);
+        assert_eq!(tabs.len(), 2);
+        assert_eq!(tabs.active_index(), 0);
+        assert!(tabs.tab(1).is_some_and(Navigator::is_empty));
+        assert!(tabs.tab(2).is_none());
+        assert!(tabs.current().is_none());
+    }
+
     #[test]
     fn test_screen_id() {
         struct TestScreen {
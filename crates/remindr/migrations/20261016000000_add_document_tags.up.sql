@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS document_tags (
+    document_id INTEGER NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+    tag TEXT NOT NULL,
+    PRIMARY KEY (document_id, tag)
+);
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS reminders (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    document_id INTEGER NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+    title TEXT NOT NULL,
+    due_at INTEGER NOT NULL,
+    recurrence TEXT,
+    snoozed_until INTEGER,
+    completed BOOLEAN NOT NULL DEFAULT 0
+);
@@ -0,0 +1,24 @@
+CREATE TABLE IF NOT EXISTS tasks (
+    document_id INTEGER NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+    block_id TEXT NOT NULL,
+    content TEXT NOT NULL,
+    due_date TEXT,
+    PRIMARY KEY (document_id, block_id)
+);
+
+-- Index the to-do blocks of existing documents, the due date being the first @YYYY-MM-DD mention
+INSERT OR IGNORE INTO tasks (document_id, block_id, content, due_date)
+SELECT
+    documents.id,
+    json_extract(block.value, '$.id'),
+    COALESCE(json_extract(block.value, '$.metadata.content'), ''),
+    CASE
+        WHEN instr(json_extract(block.value, '$.metadata.content'), '@') > 0 THEN date(substr(
+            json_extract(block.value, '$.metadata.content'),
+            instr(json_extract(block.value, '$.metadata.content'), '@') + 1,
+            10
+        ))
+    END
+FROM documents, json_each(documents.content) AS block
+WHERE json_extract(block.value, '$.type') = 'todo'
+    AND COALESCE(json_extract(block.value, '$.metadata.status'), 'todo') != 'done';
@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS attachments (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    file_name TEXT NOT NULL,
+    size INTEGER NOT NULL,
+    data BLOB NOT NULL
+);
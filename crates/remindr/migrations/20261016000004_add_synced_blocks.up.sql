@@ -0,0 +1,4 @@
+CREATE TABLE IF NOT EXISTS synced_blocks (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    content TEXT NOT NULL DEFAULT ''
+);
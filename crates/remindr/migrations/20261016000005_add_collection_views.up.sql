@@ -0,0 +1,4 @@
+CREATE TABLE IF NOT EXISTS collection_views (
+    folder_id INTEGER PRIMARY KEY REFERENCES folders(id) ON DELETE CASCADE,
+    view JSONB NOT NULL DEFAULT '{}'
+);
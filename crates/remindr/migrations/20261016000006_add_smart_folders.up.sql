@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS smart_folders (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    query TEXT NOT NULL DEFAULT ''
+);
@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS activity (
+    day TEXT NOT NULL,
+    document_id INTEGER NOT NULL,
+    blocks_created INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (day, document_id)
+);
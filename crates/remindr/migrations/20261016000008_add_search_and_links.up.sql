@@ -0,0 +1,29 @@
+-- Text of the documents, the rowid being the document id
+CREATE VIRTUAL TABLE IF NOT EXISTS documents_fts USING fts5(title, body);
+
+-- Documents opened by the link blocks of a document
+CREATE TABLE IF NOT EXISTS links (
+    source_id INTEGER NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+    target_id INTEGER NOT NULL,
+    PRIMARY KEY (source_id, target_id)
+);
+
+CREATE INDEX IF NOT EXISTS links_target ON links (target_id);
+
+-- Index the existing documents, later saves being indexed in the background
+INSERT INTO documents_fts (rowid, title, body)
+SELECT
+    documents.id,
+    documents.title,
+    COALESCE((
+        SELECT group_concat(json_extract(block.value, '$.metadata.content'), char(10))
+        FROM json_each(documents.content) AS block
+        WHERE json_type(block.value, '$.metadata.content') = 'text'
+    ), '')
+FROM documents;
+
+INSERT OR IGNORE INTO links (source_id, target_id)
+SELECT documents.id, json_extract(block.value, '$.metadata.document_id')
+FROM documents, json_each(documents.content) AS block
+WHERE json_extract(block.value, '$.type') = 'link'
+    AND json_extract(block.value, '$.metadata.document_id') IS NOT NULL;
@@ -0,0 +1,4 @@
+CREATE TABLE IF NOT EXISTS folder_templates (
+    folder_id INTEGER PRIMARY KEY REFERENCES folders(id) ON DELETE CASCADE,
+    template_id INTEGER NOT NULL REFERENCES documents(id) ON DELETE CASCADE
+);
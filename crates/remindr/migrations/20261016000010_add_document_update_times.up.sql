@@ -0,0 +1,2 @@
+-- Seconds since the epoch of the last save of each document, NULL until it is saved again
+ALTER TABLE documents ADD COLUMN updated_at INTEGER;
@@ -0,0 +1,160 @@
+use std::{path::PathBuf, time::Duration};
+
+use anyhow::bail;
+use gpui::{App, AsyncApp, Global, Window};
+use smol::Timer;
+
+use crate::{
+    app::states::{
+        error_state::{AppError, ErrorKind},
+        repository_state::RepositoryState,
+        settings_state::{BackupSettings, Settings},
+        toast_state::Toast,
+    },
+    infrastructure::backup::{self, Backup},
+};
+
+/// Time between two looks at whether a backup is due.
+const CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// State of the backups, shown in Settings > Data.
+#[derive(Default)]
+pub struct BackupState {
+    /// Newest backup of the backup directory.
+    pub last: Option<Backup>,
+    pub running: bool,
+    /// Failure of the last backup, cleared once one succeeds.
+    pub error: Option<String>,
+}
+
+impl Global for BackupState {}
+
+/// Backs up the database, or the vault folder, whenever the last backup of the directory of
+/// the settings is older than their frequency. A failure is reported once, until a backup
+/// succeeds again.
+pub fn watch(cx: &mut App) {
+    cx.set_global(BackupState::default());
+
+    cx.spawn(async move |cx| {
+        let mut failing = false;
+
+        loop {
+            let settings = cx.update(|cx| {
+                cx.try_global::<Settings>()
+                    .map(|settings| settings.backup.clone())
+                    .filter(|backup| backup.is_configured())
+            });
+            let Some(settings) = settings else {
+                Timer::after(CHECK_INTERVAL).await;
+                continue;
+            };
+
+            let directory = PathBuf::from(settings.directory.trim());
+            let last = smol::unblock(move || backup::list_backups(&directory))
+                .await
+                .ok()
+                .and_then(|backups| backups.into_iter().next());
+            let is_due = last.as_ref().is_none_or(|last| {
+                let age = chrono::Utc::now().timestamp() - last.created_at;
+                age >= settings.frequency.interval().as_secs() as i64
+            });
+            cx.update(|cx| cx.global_mut::<BackupState>().last = last);
+
+            if is_due {
+                match run(settings, cx).await {
+                    Ok(_) => failing = false,
+                    Err(e) => {
+                        tracing::error!("failed to back up: {:?}", e);
+                        if !failing {
+                            AppError::new(ErrorKind::Backup, "Failed to back up", &e).report();
+                        }
+                        failing = true;
+                    }
+                }
+            }
+
+            Timer::after(CHECK_INTERVAL).await;
+        }
+    })
+    .detach();
+}
+
+/// Backs up right away, from Settings > Data, whether or not automatic backups are on.
+pub fn back_up_now(window: &mut Window, cx: &mut App) {
+    let settings = cx.global::<Settings>().backup.clone();
+    if settings.directory.trim().is_empty() {
+        window.push_notification("Choose a backup folder first", cx);
+        return;
+    }
+    let window_handle = window.window_handle();
+
+    cx.spawn(async move |cx| {
+        let result = run(settings, cx).await;
+
+        cx.update_window(window_handle, |_, window, cx| match result {
+            Ok(backup) => {
+                Toast::success("Backup created")
+                    .action("Show", move |_, cx| cx.reveal_path(&backup.path))
+                    .show(window, cx);
+            }
+            Err(e) => {
+                tracing::error!("failed to back up: {:?}", e);
+                Toast::error("Failed to back up").show(window, cx);
+            }
+        })?;
+
+        Ok::<_, anyhow::Error>(())
+    })
+    .detach();
+}
+
+/// Backs up to the directory of the settings and deletes the backups past the ones kept.
+async fn run(settings: BackupSettings, cx: &mut AsyncApp) -> anyhow::Result<Backup> {
+    let Some((pool, vault_root)) = cx.update(|cx| {
+        if cx.global::<BackupState>().running {
+            return None;
+        }
+        let repositories = cx.try_global::<RepositoryState>()?;
+        let sources = (
+            repositories.pool.clone(),
+            repositories
+                .vault
+                .as_ref()
+                .map(|vault| vault.root().to_path_buf()),
+        );
+        cx.global_mut::<BackupState>().running = true;
+        cx.refresh_windows();
+        Some(sources)
+    }) else {
+        bail!("A backup is already running or the database isn't open yet");
+    };
+
+    let directory = PathBuf::from(settings.directory.trim());
+    let result = async {
+        let backup = match vault_root {
+            Some(root) => {
+                let directory = directory.clone();
+                smol::unblock(move || backup::back_up_vault(&root, &directory)).await?
+            }
+            None => backup::back_up_database(&pool, &directory).await?,
+        };
+        let keep = settings.keep as usize;
+        smol::unblock(move || backup::prune(&directory, keep)).await?;
+        Ok(backup)
+    }
+    .await;
+
+    cx.update(|cx| {
+        let state = cx.global_mut::<BackupState>();
+        state.running = false;
+        match &result {
+            Ok(backup) => {
+                state.last = Some(backup.clone());
+                state.error = None;
+            }
+            Err(e) => state.error = Some(format!("{:#}", e)),
+        }
+        cx.refresh_windows();
+    });
+    result
+}
@@ -0,0 +1,257 @@
+use std::{collections::HashSet, path::PathBuf, rc::Rc};
+
+use anyhow::{Error, bail};
+use gpui::prelude::FluentBuilder;
+use gpui::{App, IntoElement, ParentElement, Styled, Window, div, px};
+use gpui_component::{
+    ActiveTheme, Sizable, StyledExt, WindowExt,
+    button::{Button, ButtonVariants},
+    h_flex,
+    label::Label,
+    scroll::ScrollableElement,
+    v_flex,
+};
+use serde_json::Value;
+
+use crate::{
+    app::{
+        components::nodes::attachment::data::format_size,
+        states::{
+            deleted_blocks_state::DeletedBlocksState, document_state::DocumentState,
+            progress_state::ProgressTask, repository_state::RepositoryState,
+            settings_state::Settings, toast_state::Toast,
+        },
+    },
+    domain::database::attachment::AttachmentModel,
+    infrastructure::{backup, repositories::attachment_repository::AttachmentRepository},
+};
+
+/// Lists the stored attachments that no block refers to anymore, in saved documents, synced
+/// blocks, open tabs, blocks that can still be put back after their deletion or the latest
+/// backup, and offers to delete them. Nothing is deleted until confirmed.
+pub fn find_unused_attachments(window: &mut Window, cx: &mut App) {
+    let Some(repositories) = cx.try_global::<RepositoryState>() else {
+        return;
+    };
+    let documents = repositories.documents.clone();
+    let synced_blocks = repositories.synced_blocks.clone();
+    let attachments = repositories.attachments.clone();
+    let pool = repositories.pool.clone();
+    // Tabs can hold blocks that aren't saved yet, and deleted blocks can be put back
+    let mut kept_blocks = cx.update_global::<DocumentState, _>(|state, cx| state.opened_blocks(cx));
+    kept_blocks.extend(
+        cx.global::<DeletedBlocksState>()
+            .blocks()
+            .iter()
+            .map(|block| block.data.clone()),
+    );
+    let backup_directory = cx.global::<Settings>().backup.directory.trim().to_string();
+    let window_handle = window.window_handle();
+
+    cx.spawn(async move |cx| {
+        let task = cx.update(|cx| ProgressTask::start("Looking for unused attachments", false, cx));
+
+        let result = async {
+            let mut referenced = HashSet::new();
+            for block in &kept_blocks {
+                collect_attachment_ids(block, &mut referenced);
+            }
+            // Restoring a document from the latest backup would bring its attachments back
+            if !backup_directory.is_empty() {
+                let directory = PathBuf::from(backup_directory);
+                let latest = smol::unblock(move || backup::list_backups(&directory))
+                    .await?
+                    .into_iter()
+                    .next();
+                if let Some(latest) = latest {
+                    for content in backup::backed_up_contents(&latest, &pool).await? {
+                        if let Ok(content) = serde_json::from_str::<Value>(&content) {
+                            collect_attachment_ids(&content, &mut referenced);
+                        }
+                    }
+                }
+            }
+            for document in documents.get_stored_documents().await? {
+                // A document that can't be read may refer to any attachment
+                let Ok(content) = serde_json::from_str::<Value>(&document.content) else {
+                    bail!(
+                        "\"{}\" can't be read, run the integrity check first",
+                        document.title
+                    );
+                };
+                collect_attachment_ids(&content, &mut referenced);
+            }
+            for block in synced_blocks.get_synced_blocks().await? {
+                let Ok(content) = serde_json::from_str::<Value>(&block.content) else {
+                    bail!("A synced block can't be read");
+                };
+                collect_attachment_ids(&content, &mut referenced);
+            }
+
+            let unused = attachments
+                .get_attachments()
+                .await?
+                .into_iter()
+                .filter(|attachment| !referenced.contains(&attachment.id))
+                .collect::<Vec<_>>();
+            Ok::<_, Error>(unused)
+        }
+        .await;
+
+        cx.update(|cx| task.finish(cx));
+        cx.update_window(window_handle, |_, window, cx| match result {
+            Ok(unused) => show_report(unused, attachments, window, cx),
+            Err(e) => {
+                tracing::error!("failed to look for unused attachments: {:?}", e);
+                Toast::error(format!("Failed to look for unused attachments: {}", e))
+                    .show(window, cx);
+            }
+        })?;
+
+        Ok::<_, Error>(())
+    })
+    .detach();
+}
+
+/// Adds the attachments of the attachment blocks found anywhere in `value` to `ids`.
+fn collect_attachment_ids(value: &Value, ids: &mut HashSet<i32>) {
+    match value {
+        Value::Array(values) => {
+            for value in values {
+                collect_attachment_ids(value, ids);
+            }
+        }
+        Value::Object(object) => {
+            if object.get("type").and_then(Value::as_str) == Some("attachment")
+                && let Some(id) = value["metadata"]["attachment_id"].as_i64()
+            {
+                ids.insert(id as i32);
+            }
+            for value in object.values() {
+                collect_attachment_ids(value, ids);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn show_report(
+    unused: Vec<AttachmentModel>,
+    attachments: AttachmentRepository,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let total = unused.iter().map(|attachment| attachment.size).sum::<i64>();
+    let summary = match unused.len() {
+        0 => "Every attachment is used by a block.".to_string(),
+        1 => format!(
+            "1 attachment isn't used by any block ({}).",
+            format_size(total)
+        ),
+        count => format!(
+            "{} attachments aren't used by any block ({} in total).",
+            count,
+            format_size(total)
+        ),
+    };
+    let unused = Rc::new(unused);
+
+    window.open_dialog(cx, move |dialog, _, cx| {
+        let fg = cx.theme().foreground;
+        let muted_fg = cx.theme().muted_foreground;
+        let border = cx.theme().border;
+
+        let files = unused.iter().map(|attachment| {
+            h_flex()
+                .justify_between()
+                .gap_2()
+                .py_1()
+                .border_t_1()
+                .border_color(border)
+                .child(
+                    div()
+                        .text_sm()
+                        .text_color(fg)
+                        .truncate()
+                        .child(attachment.file_name.clone()),
+                )
+                .child(
+                    div()
+                        .flex_shrink_0()
+                        .text_xs()
+                        .text_color(muted_fg)
+                        .child(format_size(attachment.size)),
+                )
+        });
+
+        let ids = unused
+            .iter()
+            .map(|attachment| attachment.id)
+            .collect::<Vec<_>>();
+        let attachments = attachments.clone();
+
+        dialog
+            .w(px(480.))
+            .title(
+                v_flex()
+                    .text_sm()
+                    .font_semibold()
+                    .child("Unused attachments"),
+            )
+            .overlay_closable(true)
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(Label::new(summary.clone()).text_sm())
+                    .when(!unused.is_empty(), |el| {
+                        el.child(
+                            v_flex()
+                                .max_h(px(320.))
+                                .overflow_y_scrollbar()
+                                .children(files),
+                        )
+                        .child(
+                            h_flex().justify_end().child(
+                                Button::new("delete-unused-attachments")
+                                    .small()
+                                    .danger()
+                                    .label(match ids.len() {
+                                        1 => "Delete 1 file".to_string(),
+                                        count => format!("Delete {} files", count),
+                                    })
+                                    .on_click(move |_, window, cx| {
+                                        delete(ids.clone(), attachments.clone(), window, cx);
+                                    }),
+                            ),
+                        )
+                    }),
+            )
+    });
+}
+
+fn delete(ids: Vec<i32>, attachments: AttachmentRepository, window: &mut Window, cx: &mut App) {
+    let window_handle = window.window_handle();
+    let count = ids.len();
+
+    cx.spawn(async move |cx| {
+        let result = attachments.delete_attachments(ids).await;
+
+        cx.update_window(window_handle, |_, window, cx| match result {
+            Ok(()) => {
+                window.close_dialog(cx);
+                Toast::success(match count {
+                    1 => "Deleted 1 unused attachment".to_string(),
+                    count => format!("Deleted {} unused attachments", count),
+                })
+                .show(window, cx);
+            }
+            Err(e) => {
+                tracing::error!("failed to delete unused attachments: {:?}", e);
+                Toast::error(format!("Failed to delete the attachments: {}", e)).show(window, cx);
+            }
+        })?;
+
+        Ok::<_, Error>(())
+    })
+    .detach();
+}
@@ -0,0 +1,196 @@
+use std::{path::PathBuf, rc::Rc};
+
+use anyhow::Error;
+use gpui::prelude::FluentBuilder;
+use gpui::{App, Entity, IntoElement, ParentElement, Styled, Window, div, px};
+use gpui_component::{
+    ActiveTheme, Sizable, StyledExt, WindowExt,
+    button::{Button, ButtonVariants},
+    h_flex,
+    label::Label,
+    scroll::ScrollableElement,
+    v_flex,
+};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::{
+    app::{
+        components::document_preview::DocumentPreview,
+        date_format::DateFormat,
+        states::{
+            node_state::NodeState, progress_state::ProgressTask, repository_state::RepositoryState,
+            settings_state::Settings, toast_state::Toast,
+        },
+    },
+    domain::database::document::block_versions,
+    infrastructure::backup,
+};
+
+/// Lists the earlier versions of a block kept in the backups of its document, any of which
+/// can replace the block without touching the rest of the document.
+pub fn show_block_history(
+    block_id: Uuid,
+    state: Entity<NodeState>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let Some(repositories) = cx.try_global::<RepositoryState>() else {
+        return;
+    };
+    let pool = repositories.pool.clone();
+    let documents = repositories.documents.clone();
+    let Some(document_id) = state.read(cx).document_id else {
+        Toast::error("Only blocks of documents have a history").show(window, cx);
+        return;
+    };
+    let Some(element) = state
+        .read(cx)
+        .get_current_nodes(block_id)
+        .map(|node| node.element.clone())
+    else {
+        return;
+    };
+    let backup_directory = cx.global::<Settings>().backup.directory.trim().to_string();
+    if backup_directory.is_empty() {
+        Toast::error("Choose a backup folder in Settings > Data to keep the history of blocks")
+            .show(window, cx);
+        return;
+    }
+
+    let current = element.get_data(cx);
+    let window_handle = window.window_handle();
+
+    cx.spawn(async move |cx| {
+        let task = cx.update(|cx| ProgressTask::start("Looking for earlier versions", false, cx));
+
+        let result = async {
+            let location = documents.document_location(document_id).await?;
+            let directory = PathBuf::from(backup_directory);
+            let backups = smol::unblock(move || backup::list_backups(&directory)).await?;
+
+            // Backups are listed newest first
+            let mut snapshots = Vec::new();
+            for backup in &backups {
+                if let Ok(Some(content)) = backup::backed_up_content(backup, &location, &pool).await
+                    && let Ok(content) = serde_json::from_str::<Value>(&content)
+                {
+                    snapshots.push((backup.created_at, content));
+                }
+            }
+
+            Ok::<_, Error>(block_versions(&block_id.to_string(), &current, &snapshots))
+        }
+        .await;
+
+        cx.update(|cx| task.finish(cx));
+        cx.update_window(window_handle, |_, window, cx| match result {
+            Ok(versions) => show_versions(versions, block_id, state, window, cx),
+            Err(e) => {
+                tracing::error!("failed to read the history of a block: {:?}", e);
+                Toast::error(format!("Failed to read the history of the block: {}", e))
+                    .show(window, cx);
+            }
+        })?;
+
+        Ok::<_, Error>(())
+    })
+    .detach();
+}
+
+fn show_versions(
+    versions: Vec<(i64, Value)>,
+    block_id: Uuid,
+    state: Entity<NodeState>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let versions = Rc::new(versions);
+
+    window.open_dialog(cx, move |dialog, _, cx| {
+        let muted_fg = cx.theme().muted_foreground;
+        let border = cx.theme().border;
+        let format = DateFormat::new(cx);
+
+        let rows = versions
+            .iter()
+            .enumerate()
+            .map(|(index, (time, data))| {
+                let data = data.clone();
+                let state = state.clone();
+
+                h_flex()
+                    .gap_2()
+                    .py_2()
+                    .border_t_1()
+                    .border_color(border)
+                    .child(
+                        v_flex()
+                            .flex_1()
+                            .min_w_0()
+                            .gap_1()
+                            .child(
+                                Label::new(format!("Backup from {}", format.relative(*time)))
+                                    .text_xs()
+                                    .text_color(muted_fg),
+                            )
+                            .children(DocumentPreview::render_block(&data, cx)),
+                    )
+                    .child(
+                        Button::new(("restore-block-version", index))
+                            .xsmall()
+                            .outline()
+                            .label("Restore")
+                            .on_click(move |_, window, cx| {
+                                restore(data.clone(), block_id, state.clone(), window, cx);
+                            }),
+                    )
+            })
+            .collect::<Vec<_>>();
+
+        dialog
+            .w(px(480.))
+            .title(v_flex().text_sm().font_semibold().child("Block history"))
+            .overlay_closable(true)
+            .child(
+                v_flex()
+                    .gap_2()
+                    .when(rows.is_empty(), |el| {
+                        el.child(
+                            Label::new("The backups have no other version of this block.")
+                                .text_sm()
+                                .text_color(muted_fg),
+                        )
+                    })
+                    .when(!rows.is_empty(), |el| {
+                        el.child(
+                            div()
+                                .max_h(px(360.))
+                                .overflow_y_scrollbar()
+                                .child(v_flex().children(rows)),
+                        )
+                    }),
+            )
+    });
+}
+
+/// Replaces the block with an earlier version of it, keeping its id.
+fn restore(
+    data: Value,
+    block_id: Uuid,
+    state: Entity<NodeState>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    if state.read(cx).get_current_nodes(block_id).is_none() {
+        Toast::error("The block was deleted since").show(window, cx);
+        return;
+    }
+
+    window.close_dialog(cx);
+    state.update(cx, |node_state, cx| {
+        let node = node_state.parse_node(&data, &state, window, cx);
+        node_state.replace_node(block_id, &node, cx);
+    });
+    Toast::success("Block restored").show(window, cx);
+}
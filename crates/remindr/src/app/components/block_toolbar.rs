@@ -0,0 +1,186 @@
+use gpui::prelude::FluentBuilder;
+use gpui::{
+    App, Entity, IntoElement, ParentElement, RenderOnce, SharedString, Styled, Window, div, px,
+};
+use gpui_component::{
+    ActiveTheme, Icon, Sizable,
+    button::{Button, ButtonVariants},
+    h_flex,
+    menu::{DropdownMenu as _, PopupMenuItem},
+};
+use uuid::Uuid;
+
+use crate::app::{
+    components::{nodes::node::RemindrNode, rich_text::RichTextStyle},
+    states::{node_state::NodeState, settings_state::Settings},
+};
+
+/// Quick action available in the block toolbar.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ToolbarAction {
+    Bold,
+    Italic,
+    Underline,
+    Strikethrough,
+    Code,
+    TurnInto,
+}
+
+impl ToolbarAction {
+    pub const ALL: [ToolbarAction; 6] = [
+        ToolbarAction::Bold,
+        ToolbarAction::Italic,
+        ToolbarAction::Underline,
+        ToolbarAction::Strikethrough,
+        ToolbarAction::Code,
+        ToolbarAction::TurnInto,
+    ];
+
+    /// Identifier stored in the settings when the action is hidden.
+    pub fn id(&self) -> &'static str {
+        match self {
+            ToolbarAction::Bold => "bold",
+            ToolbarAction::Italic => "italic",
+            ToolbarAction::Underline => "underline",
+            ToolbarAction::Strikethrough => "strikethrough",
+            ToolbarAction::Code => "code",
+            ToolbarAction::TurnInto => "turn_into",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ToolbarAction::Bold => "Bold",
+            ToolbarAction::Italic => "Italic",
+            ToolbarAction::Underline => "Underline",
+            ToolbarAction::Strikethrough => "Strikethrough",
+            ToolbarAction::Code => "Code",
+            ToolbarAction::TurnInto => "Turn into",
+        }
+    }
+
+    fn icon_path(&self) -> &'static str {
+        match self {
+            ToolbarAction::Bold => "icons/bold.svg",
+            ToolbarAction::Italic => "icons/italic.svg",
+            ToolbarAction::Underline => "icons/underline.svg",
+            ToolbarAction::Strikethrough => "icons/strikethrough.svg",
+            ToolbarAction::Code => "icons/braces.svg",
+            ToolbarAction::TurnInto => "icons/pilcrow.svg",
+        }
+    }
+
+    fn style(&self) -> Option<RichTextStyle> {
+        match self {
+            ToolbarAction::Bold => Some(RichTextStyle::Bold),
+            ToolbarAction::Italic => Some(RichTextStyle::Italic),
+            ToolbarAction::Underline => Some(RichTextStyle::Underline),
+            ToolbarAction::Strikethrough => Some(RichTextStyle::Strikethrough),
+            ToolbarAction::Code => Some(RichTextStyle::Code),
+            ToolbarAction::TurnInto => None,
+        }
+    }
+}
+
+/// Floating toolbar shown over a block while it is hovered or has selected text.
+///
+/// Inline styles act on the current selection of the block, so they are only offered for
+/// blocks holding rich text.
+#[derive(IntoElement)]
+pub struct BlockToolbar {
+    node: RemindrNode,
+    state: Entity<NodeState>,
+}
+
+impl BlockToolbar {
+    pub fn new(node: RemindrNode, state: Entity<NodeState>) -> Self {
+        Self { node, state }
+    }
+
+    /// Whether the toolbar should stay visible without hovering, i.e. when the block has a
+    /// non-empty text selection.
+    pub fn has_selection(node: &RemindrNode, cx: &App) -> bool {
+        node.element.rich_text_state(cx).is_some_and(|state| {
+            let (start, end) = state.read(cx).selection().normalized();
+            start != end
+        })
+    }
+
+    fn button_id(node_id: Uuid, action: ToolbarAction) -> SharedString {
+        SharedString::from(format!("block-toolbar-{}-{}", action.id(), node_id))
+    }
+}
+
+impl RenderOnce for BlockToolbar {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let hidden = &cx.global::<Settings>().editor.hidden_toolbar_actions;
+        let actions = ToolbarAction::ALL
+            .into_iter()
+            .filter(|action| !hidden.iter().any(|id| id == action.id()))
+            .collect::<Vec<_>>();
+
+        let rich_text_state = self.node.element.rich_text_state(cx);
+        let menu_items = self.node.element.menu_items(cx);
+        let node_id = self.node.id;
+
+        let style_buttons = actions
+            .iter()
+            .filter_map(|action| {
+                let style = action.style()?;
+                let rich_text_state = rich_text_state.clone()?;
+
+                Some(
+                    Button::new(Self::button_id(node_id, *action))
+                        .ghost()
+                        .xsmall()
+                        .icon(Icon::default().path(action.icon_path()))
+                        .tooltip(action.label())
+                        .on_click(move |_, _, cx| {
+                            rich_text_state.update(cx, |state, cx| state.apply_style(style, cx));
+                        }),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let turn_into = (actions.contains(&ToolbarAction::TurnInto) && !menu_items.is_empty())
+            .then(|| {
+                let state = self.state.clone();
+
+                Button::new(Self::button_id(node_id, ToolbarAction::TurnInto))
+                    .ghost()
+                    .xsmall()
+                    .icon(Icon::default().path(ToolbarAction::TurnInto.icon_path()))
+                    .tooltip(ToolbarAction::TurnInto.label())
+                    .dropdown_menu(move |menu, _, _| {
+                        let mut menu = menu.min_w(px(160.));
+                        for item in &menu_items {
+                            let state = state.clone();
+                            let action = item.action.clone();
+                            menu = menu.item(
+                                PopupMenuItem::new(item.label.clone())
+                                    .icon(Icon::default().path(item.icon_path))
+                                    .on_click(move |_, window, cx| action(&state, window, cx)),
+                            );
+                        }
+                        menu
+                    })
+            });
+
+        if style_buttons.is_empty() && turn_into.is_none() {
+            return div().into_any_element();
+        }
+
+        h_flex()
+            .gap_0p5()
+            .p_0p5()
+            .rounded(cx.theme().radius)
+            .border_1()
+            .border_color(cx.theme().border)
+            .bg(cx.theme().popover)
+            .text_color(cx.theme().popover_foreground)
+            .shadow_md()
+            .children(style_buttons)
+            .when_some(turn_into, |this, button| this.child(button))
+            .into_any_element()
+    }
+}
@@ -12,7 +12,10 @@ use crate::{
     LoadingState,
     app::{
         components::nodes::{element::RemindrElement, node::RemindrNode},
-        states::document_state::DocumentState,
+        states::{
+            document_state::DocumentState,
+            node_state::{DocumentEvent, NodeState},
+        },
     },
 };
 
@@ -39,6 +42,15 @@ impl CodeWindow {
                 RemindrElement::Text(node) => to_string_pretty(&node.read(cx).data).unwrap(),
                 RemindrElement::Heading(node) => to_string_pretty(&node.read(cx).data).unwrap(),
                 RemindrElement::Divider(node) => to_string_pretty(&node.read(cx).data).unwrap(),
+                RemindrElement::List(node) => to_string_pretty(&node.read(cx).data).unwrap(),
+                RemindrElement::Quote(node) => to_string_pretty(&node.read(cx).data).unwrap(),
+                RemindrElement::Todo(node) => to_string_pretty(&node.read(cx).data).unwrap(),
+                RemindrElement::Attachment(node) => to_string_pretty(&node.read(cx).data).unwrap(),
+                RemindrElement::Drawing(node) => to_string_pretty(&node.read(cx).data).unwrap(),
+                RemindrElement::Equation(node) => to_string_pretty(&node.read(cx).data).unwrap(),
+                RemindrElement::SyncedBlock(node) => to_string_pretty(&node.read(cx).data).unwrap(),
+                RemindrElement::Link(node) => to_string_pretty(&node.read(cx).data).unwrap(),
+                RemindrElement::Unsupported(node) => to_string_pretty(&node.read(cx).data).unwrap(),
             };
             buffer.push_str(&node_json);
             buffer.push('\n');
@@ -49,24 +61,22 @@ impl CodeWindow {
 
     fn get_current_buffer(&self, cx: &App) -> Option<String> {
         cx.read_global::<DocumentState, _>(|state, cx| {
-            state
-                .documents
-                .iter()
-                .find(|d| d.uid == self.document_id)
-                .and_then(|doc| {
-                    if let LoadingState::Loaded(content) = &doc.state {
-                        Some(Self::build_code_buffer(
-                            content.renderer.read(cx).state.read(cx).get_nodes(),
-                            cx,
-                        ))
-                    } else {
-                        None
-                    }
-                })
+            state.find_document(self.document_id).and_then(|doc| {
+                if let LoadingState::Loaded(content) = &doc.state {
+                    Some(Self::build_code_buffer(
+                        content.renderer.read(cx).state.read(cx).get_nodes(),
+                        cx,
+                    ))
+                } else {
+                    None
+                }
+            })
         })
     }
 
-    pub fn open(title: String, document_id: i32, nodes: Vec<RemindrNode>, cx: &mut App) {
+    /// Opens a window showing the JSON of the blocks of a document, kept up to date as
+    /// `state` reports changes.
+    pub fn open(title: String, document_id: i32, state: Entity<NodeState>, cx: &mut App) {
         let window_size = size(px(600.), px(500.));
         let window_bounds = Bounds::centered(None, window_size, cx);
 
@@ -91,7 +101,7 @@ impl CodeWindow {
 
             let window = cx
                 .open_window(options, |window, cx| {
-                    let editor_buffer = Self::build_code_buffer(&nodes, cx);
+                    let editor_buffer = Self::build_code_buffer(state.read(cx).get_nodes(), cx);
                     let editor_state = cx.new(|cx| {
                         InputState::new(window, cx)
                             .code_editor("json")
@@ -104,7 +114,7 @@ impl CodeWindow {
                             .default_value(editor_buffer.clone())
                     });
                     let code_window = cx.new(|cx| {
-                        cx.observe_global::<DocumentState>(|_this: &mut CodeWindow, cx| {
+                        cx.subscribe(&state, |_: &mut CodeWindow, _, _: &DocumentEvent, cx| {
                             cx.notify();
                         })
                         .detach();
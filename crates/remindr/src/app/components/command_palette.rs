@@ -0,0 +1,369 @@
+use std::rc::Rc;
+
+use gpui::prelude::FluentBuilder;
+use gpui::{
+    App, AppContext, Context, Entity, FocusHandle, Focusable, InteractiveElement, IntoElement,
+    KeyBinding, KeyDownEvent, MouseButton, ParentElement, Render, SharedString,
+    StatefulInteractiveElement, Styled, Window, actions, div, px,
+};
+use gpui_component::{
+    ActiveTheme, Icon, Sizable, WindowExt,
+    input::{Input, InputEvent, InputState, MoveDown, MoveUp},
+    label::Label,
+    v_flex,
+};
+
+use crate::app::{
+    components::{
+        deleted_blocks_panel,
+        demo_document::CreateDemoDocument,
+        import_dialog::{self, ImportSource},
+        nav_inspector::ToggleNavInspector,
+        notification_center::NotificationCenter,
+        reminder_dialog::ReminderDialog,
+        replace_dialog::ReplaceDialog,
+        settings_dialog::SettingsDialog,
+        sidebar, template_picker, vault_git,
+    },
+    screens::{
+        activity_screen::OpenActivity,
+        board_screen::OpenBoard,
+        calendar_screen::{self, OpenCalendar},
+        logs_screen::OpenLogs,
+        reminders_screen::OpenReminders,
+        tasks_screen::OpenTasks,
+    },
+    states::{document_state::DocumentState, repository_state::RepositoryState},
+};
+
+actions!(command_palette, [ToggleCommandPalette]);
+
+pub fn init(cx: &mut App) {
+    cx.bind_keys([KeyBinding::new(
+        "secondary-shift-p",
+        ToggleCommandPalette,
+        None,
+    )]);
+}
+
+type CommandHandler = Rc<dyn Fn(&mut Window, &mut App) + 'static>;
+
+#[derive(Clone)]
+struct PaletteCommand {
+    label: SharedString,
+    icon_path: &'static str,
+    handler: CommandHandler,
+}
+
+impl PaletteCommand {
+    fn new(
+        label: impl Into<SharedString>,
+        icon_path: &'static str,
+        handler: impl Fn(&mut Window, &mut App) + 'static,
+    ) -> Self {
+        Self {
+            label: label.into(),
+            icon_path,
+            handler: Rc::new(handler),
+        }
+    }
+}
+
+/// Searchable list of the application commands, opened with `secondary-shift-p`.
+pub struct CommandPalette {
+    focus_handle: FocusHandle,
+    search_input: Entity<InputState>,
+    commands: Vec<PaletteCommand>,
+    selected_index: usize,
+}
+
+impl CommandPalette {
+    pub fn open(window: &mut Window, cx: &mut App) {
+        let palette = cx.new(|cx| CommandPalette::new(window, cx));
+
+        window.open_dialog(cx, move |dialog, _, _| {
+            dialog
+                .w(px(480.))
+                .p_1()
+                .close_button(false)
+                .overlay_closable(true)
+                .child(palette.clone())
+        });
+    }
+
+    fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let search_input =
+            cx.new(|cx| InputState::new(window, cx).placeholder("Type a command..."));
+
+        cx.subscribe_in(
+            &search_input,
+            window,
+            |this, _, event: &InputEvent, window, cx| match event {
+                InputEvent::Change => {
+                    this.selected_index = 0;
+                    cx.notify();
+                }
+                InputEvent::PressEnter { .. } => this.confirm_selection(window, cx),
+                _ => {}
+            },
+        )
+        .detach();
+
+        search_input.update(cx, |input, cx| {
+            input.focus(window, cx);
+        });
+
+        Self {
+            focus_handle: cx.focus_handle(),
+            search_input,
+            commands: Self::available_commands(window, cx),
+            selected_index: 0,
+        }
+    }
+
+    fn available_commands(window: &Window, cx: &App) -> Vec<PaletteCommand> {
+        let mut commands = vec![
+            PaletteCommand::new("Open settings", "icons/settings.svg", SettingsDialog::open),
+            PaletteCommand::new(
+                "Show notifications",
+                "icons/triangle-alert.svg",
+                NotificationCenter::open,
+            ),
+            PaletteCommand::new(
+                "Find and replace in all documents…",
+                "icons/search.svg",
+                ReplaceDialog::open,
+            ),
+            PaletteCommand::new(
+                "Show recently deleted blocks",
+                "icons/undo-2.svg",
+                deleted_blocks_panel::open,
+            ),
+            PaletteCommand::new("Open board", "icons/kanban.svg", |window, cx| {
+                window.dispatch_action(Box::new(OpenBoard), cx);
+            }),
+            PaletteCommand::new("Open calendar", "icons/calendar.svg", |window, cx| {
+                window.dispatch_action(Box::new(OpenCalendar), cx);
+            }),
+            PaletteCommand::new(
+                "Export calendar (.ics)",
+                "icons/calendar.svg",
+                calendar_screen::export_calendar,
+            ),
+            PaletteCommand::new(
+                "Import from Notion export…",
+                "icons/download.svg",
+                |window, cx| import_dialog::import(ImportSource::Notion, window, cx),
+            ),
+            PaletteCommand::new(
+                "Import markdown folder (Obsidian)…",
+                "icons/download.svg",
+                |window, cx| import_dialog::import(ImportSource::Markdown, window, cx),
+            ),
+            PaletteCommand::new(
+                "Import Evernote or Apple Notes (.enex)…",
+                "icons/download.svg",
+                |window, cx| import_dialog::import(ImportSource::Enex, window, cx),
+            ),
+            PaletteCommand::new(
+                "Import OPML outline…",
+                "icons/download.svg",
+                |window, cx| import_dialog::import(ImportSource::Opml, window, cx),
+            ),
+            PaletteCommand::new(
+                "Export folder tree (OPML)",
+                "icons/folder-open.svg",
+                sidebar::export_folder_tree,
+            ),
+            PaletteCommand::new("Open reminders", "icons/bell.svg", |window, cx| {
+                window.dispatch_action(Box::new(OpenReminders), cx);
+            }),
+            PaletteCommand::new("Open my tasks", "icons/square-check.svg", |window, cx| {
+                window.dispatch_action(Box::new(OpenTasks), cx);
+            }),
+            PaletteCommand::new("Open activity", "icons/chart-column.svg", |window, cx| {
+                window.dispatch_action(Box::new(OpenActivity), cx);
+            }),
+            PaletteCommand::new("Open logs", "icons/file-text.svg", |window, cx| {
+                window.dispatch_action(Box::new(OpenLogs), cx);
+            }),
+            PaletteCommand::new(
+                "Toggle navigation inspector",
+                "icons/app-window.svg",
+                |window, cx| {
+                    window.dispatch_action(Box::new(ToggleNavInspector), cx);
+                },
+            ),
+            PaletteCommand::new(
+                "Create demo document",
+                "icons/sparkles.svg",
+                |window, cx| {
+                    window.dispatch_action(Box::new(CreateDemoDocument), cx);
+                },
+            ),
+        ];
+
+        let window_id = window.window_handle().window_id();
+        let current_document = cx
+            .try_global::<DocumentState>()
+            .and_then(|state| state.workspace(window_id))
+            .and_then(|workspace| workspace.get_current_document())
+            .map(|document| (document.uid, document.title.clone()));
+
+        if let Some((uid, title)) = current_document.clone() {
+            commands.push(PaletteCommand::new(
+                "Insert template…",
+                "icons/file.svg",
+                template_picker::open,
+            ));
+            commands.push(PaletteCommand::new(
+                "Add reminder to current document",
+                "icons/bell-plus.svg",
+                move |window, cx| ReminderDialog::open(uid, title.clone(), window, cx),
+            ));
+            commands.push(PaletteCommand::new(
+                "Export current document as Word (.docx)",
+                "icons/download.svg",
+                move |window, cx| sidebar::export_document_as_docx(uid, window, cx),
+            ));
+        }
+
+        let has_git = cx
+            .try_global::<RepositoryState>()
+            .and_then(|state| state.vault.as_ref())
+            .is_some_and(|vault| vault.git().is_some());
+
+        if has_git {
+            commands.push(PaletteCommand::new(
+                "Git: Commit vault changes",
+                "icons/git-commit-horizontal.svg",
+                vault_git::commit_changes,
+            ));
+
+            if let Some((uid, title)) = current_document {
+                commands.push(PaletteCommand::new(
+                    "Git: Show changes in current document",
+                    "icons/file-diff.svg",
+                    move |window, cx| vault_git::show_document_diff(uid, title.clone(), window, cx),
+                ));
+            }
+        }
+
+        commands
+    }
+
+    fn filtered_commands(&self, cx: &App) -> Vec<PaletteCommand> {
+        let search = self.search_input.read(cx).value().to_lowercase();
+
+        self.commands
+            .iter()
+            .filter(|command| search.is_empty() || command.label.to_lowercase().contains(&search))
+            .cloned()
+            .collect()
+    }
+
+    fn move_selection(&mut self, delta: isize, cx: &mut Context<Self>) {
+        let count = self.filtered_commands(cx).len();
+        if count == 0 {
+            return;
+        }
+
+        self.selected_index =
+            (self.selected_index as isize + delta).rem_euclid(count as isize) as usize;
+        cx.notify();
+    }
+
+    fn confirm_selection(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(command) = self.filtered_commands(cx).get(self.selected_index) {
+            Self::run(command.handler.clone(), window, cx);
+        }
+    }
+
+    fn run(handler: CommandHandler, window: &mut Window, cx: &mut App) {
+        window.close_dialog(cx);
+        handler(window, cx);
+    }
+}
+
+impl Focusable for CommandPalette {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for CommandPalette {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let commands = self.filtered_commands(cx);
+        let selected_index = self.selected_index;
+
+        v_flex()
+            .track_focus(&self.focus_handle)
+            .w_full()
+            .gap_1()
+            .on_action(cx.listener(|this, _: &MoveUp, _, cx| {
+                this.move_selection(-1, cx);
+            }))
+            .on_action(cx.listener(|this, _: &MoveDown, _, cx| {
+                this.move_selection(1, cx);
+            }))
+            .on_key_down(cx.listener(|_, event: &KeyDownEvent, window, cx| {
+                if event.keystroke.key.as_str() == "escape" {
+                    window.close_dialog(cx);
+                    cx.stop_propagation();
+                }
+            }))
+            .child(
+                Input::new(&self.search_input)
+                    .text_sm()
+                    .appearance(false)
+                    .prefix(
+                        Icon::default()
+                            .path("icons/search.svg")
+                            .small()
+                            .text_color(cx.theme().muted_foreground),
+                    ),
+            )
+            .when(commands.is_empty(), |this| {
+                this.child(
+                    div().px_2().py_2().child(
+                        Label::new("No matching commands")
+                            .text_sm()
+                            .text_color(cx.theme().muted_foreground),
+                    ),
+                )
+            })
+            .children(commands.into_iter().enumerate().map(|(index, command)| {
+                let is_selected = index == selected_index;
+                let handler = command.handler.clone();
+
+                div()
+                    .id(("command", index))
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .px_2()
+                    .py_1()
+                    .rounded_md()
+                    .cursor_pointer()
+                    .when(is_selected, |this| this.bg(cx.theme().accent.opacity(0.5)))
+                    .hover(|this| this.bg(cx.theme().accent.opacity(0.5)))
+                    .on_mouse_down(MouseButton::Left, |_, _, cx| {
+                        cx.stop_propagation();
+                    })
+                    .on_click(move |_, window, cx| {
+                        Self::run(handler.clone(), window, cx);
+                    })
+                    .child(
+                        Icon::default()
+                            .path(command.icon_path)
+                            .size_4()
+                            .text_color(cx.theme().muted_foreground),
+                    )
+                    .child(
+                        Label::new(command.label)
+                            .text_sm()
+                            .text_color(cx.theme().foreground),
+                    )
+            }))
+    }
+}
@@ -1,20 +1,35 @@
 use std::rc::Rc;
 
+use anyhow::Error;
 use gpui::{
-    App, ClickEvent, Element, IntoElement, ParentElement, Styled, Window, prelude::FluentBuilder,
+    App, AppContext, ClickEvent, Context, Element, Entity, FocusHandle, InteractiveElement,
+    IntoElement, KeyDownEvent, ParentElement, Render, Styled, Task, Window, prelude::FluentBuilder,
     px,
 };
 use gpui_component::{
-    ActiveTheme, Sizable, StyledExt, WindowExt,
+    ActiveTheme, Disableable, Sizable, StyledExt, WindowExt,
     button::{Button, ButtonVariants},
+    input::{Input, InputEvent, InputState},
     v_flex,
 };
 
 /// 确认对话框的回调函数类型
 type ConfirmCallback = Rc<dyn Fn(&mut Window, &mut App) -> bool + 'static>;
 
+type AsyncConfirmCallback = Rc<dyn Fn(&mut Window, &mut App) -> Task<Result<(), Error>> + 'static>;
+
+type CancelCallback = Rc<dyn Fn(&mut Window, &mut App) + 'static>;
+
+#[derive(Clone)]
+enum OnConfirm {
+    Sync(ConfirmCallback),
+    Async(AsyncConfirmCallback),
+}
+
 /// A confirmation dialog for dangerous actions (e.g., file deletion).
 ///
+/// Enter confirms and Escape cancels.
+///
 /// # Example
 ///
 /// ```rust
@@ -33,7 +48,9 @@ pub struct ConfirmDialog {
     confirm_text: String,
     cancel_text: String,
     is_danger: bool,
-    on_confirm: Option<ConfirmCallback>,
+    required_text: Option<String>,
+    on_confirm: Option<OnConfirm>,
+    on_cancel: Option<CancelCallback>,
 }
 
 impl ConfirmDialog {
@@ -45,7 +62,9 @@ impl ConfirmDialog {
             confirm_text: "Confirm".into(),
             cancel_text: "Cancel".into(),
             is_danger: false,
+            required_text: None,
             on_confirm: None,
+            on_cancel: None,
         }
     }
 
@@ -73,27 +92,63 @@ impl ConfirmDialog {
         self
     }
 
+    /// Require typing `text` before confirming, for irreversible actions.
+    /// Implies [`ConfirmDialog::danger`].
+    pub fn destructive_requires_typing(mut self, text: impl Into<String>) -> Self {
+        self.required_text = Some(text.into());
+        self.is_danger = true;
+        self
+    }
+
     /// Set the callback for when the user confirms.
     /// Return `true` to close the dialog, `false` to keep it open.
     pub fn on_confirm<F>(mut self, callback: F) -> Self
     where
         F: Fn(&mut Window, &mut App) -> bool + 'static,
     {
-        self.on_confirm = Some(Rc::new(callback));
+        self.on_confirm = Some(OnConfirm::Sync(Rc::new(callback)));
+        self
+    }
+
+    /// Set a callback for when the user confirms that runs a task. The confirm button
+    /// shows a spinner until it is done, then the dialog closes, or shows the error and
+    /// stays open if it failed.
+    pub fn on_confirm_async<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&mut Window, &mut App) -> Task<Result<(), Error>> + 'static,
+    {
+        self.on_confirm = Some(OnConfirm::Async(Rc::new(callback)));
+        self
+    }
+
+    /// Set the callback for when the user clicks the cancel button.
+    pub fn on_cancel<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&mut Window, &mut App) + 'static,
+    {
+        self.on_cancel = Some(Rc::new(callback));
         self
     }
 
     /// Open the confirmation dialog.
     pub fn open(self, window: &mut Window, cx: &mut App) {
-        let on_confirm = self.on_confirm;
-        let message = self.message;
         let title = self.title;
         let confirm_text = self.confirm_text;
         let cancel_text = self.cancel_text;
         let is_danger = self.is_danger;
+        let content = cx.new(|cx| {
+            ConfirmDialogContent::new(
+                self.message,
+                self.required_text,
+                self.on_confirm,
+                self.on_cancel,
+                window,
+                cx,
+            )
+        });
 
-        window.open_dialog(cx, move |dialog, _window, cx| {
-            let on_confirm_clone = on_confirm.clone();
+        window.open_dialog(cx, move |dialog, _window, _cx| {
+            let content_clone = content.clone();
             let cancel_text_clone = cancel_text.clone();
             let confirm_text_clone = confirm_text.clone();
 
@@ -105,19 +160,23 @@ impl ConfirmDialog {
                 .title(v_flex().text_sm().font_semibold().child(title.clone()))
                 .close_button(false)
                 .overlay_closable(true)
-                .footer(move |_ok_btn, _cancel_btn, _window, _cx| {
-                    let on_confirm = on_confirm_clone.clone();
+                .footer(move |_ok_btn, _cancel_btn, _window, cx| {
+                    let content = content_clone.clone();
                     let cancel_text = cancel_text_clone.clone();
                     let confirm_text = confirm_text_clone.clone();
+                    let pending = content.read(cx).pending;
+                    let can_confirm = content.read(cx).can_confirm(cx);
 
                     vec![
                         Button::new("cancel")
                             .small()
                             .ghost()
                             .label(cancel_text)
+                            .disabled(pending)
                             .on_click({
+                                let content = content.clone();
                                 move |_: &ClickEvent, window: &mut Window, cx: &mut App| {
-                                    window.close_dialog(cx);
+                                    content.update(cx, |content, cx| content.cancel(window, cx));
                                 }
                             })
                             .into_element()
@@ -127,29 +186,168 @@ impl ConfirmDialog {
                             .when(is_danger, |btn| btn.danger())
                             .when(!is_danger, |btn| btn.primary())
                             .label(confirm_text)
+                            .loading(pending)
+                            .disabled(!can_confirm)
                             .on_click({
-                                let on_confirm = on_confirm.clone();
                                 move |_: &ClickEvent, window: &mut Window, cx: &mut App| {
-                                    let should_close = if let Some(ref callback) = on_confirm {
-                                        callback(window, cx)
-                                    } else {
-                                        true
-                                    };
-                                    if should_close {
-                                        window.close_dialog(cx);
-                                    }
+                                    content.update(cx, |content, cx| content.confirm(window, cx));
                                 }
                             })
                             .into_element()
                             .into_any(),
                     ]
                 })
-                .child(
+                .child(content.clone())
+        });
+    }
+}
+
+/// Message of the dialog and state of the confirmation, shared with the footer buttons.
+struct ConfirmDialogContent {
+    message: Option<String>,
+    required_text: Option<String>,
+    /// Where `required_text` is typed.
+    input: Option<Entity<InputState>>,
+    on_confirm: Option<OnConfirm>,
+    on_cancel: Option<CancelCallback>,
+    /// An async confirmation is running.
+    pending: bool,
+    /// Why the last async confirmation failed.
+    error: Option<String>,
+    focus_handle: FocusHandle,
+}
+
+impl ConfirmDialogContent {
+    fn new(
+        message: Option<String>,
+        required_text: Option<String>,
+        on_confirm: Option<OnConfirm>,
+        on_cancel: Option<CancelCallback>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let focus_handle = cx.focus_handle();
+        let input =
+            required_text.as_ref().map(|text| {
+                let input = cx.new(|cx| InputState::new(window, cx).placeholder(text.clone()));
+                cx.subscribe_in(&input, window, |this, _, event: &InputEvent, window, cx| {
+                    match event {
+                        InputEvent::Change => cx.notify(),
+                        InputEvent::PressEnter { .. } => this.confirm(window, cx),
+                        _ => {}
+                    }
+                })
+                .detach();
+                input
+            });
+
+        match &input {
+            Some(input) => input.update(cx, |input, cx| input.focus(window, cx)),
+            None => focus_handle.focus(window, cx),
+        }
+
+        Self {
+            message,
+            required_text,
+            input,
+            on_confirm,
+            on_cancel,
+            pending: false,
+            error: None,
+            focus_handle,
+        }
+    }
+
+    fn can_confirm(&self, cx: &App) -> bool {
+        let typed = match (&self.required_text, &self.input) {
+            (Some(text), Some(input)) => input.read(cx).value().trim() == text.as_str(),
+            _ => true,
+        };
+        !self.pending && typed
+    }
+
+    fn confirm(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.can_confirm(cx) {
+            return;
+        }
+
+        match self.on_confirm.clone() {
+            None => window.close_dialog(cx),
+            Some(OnConfirm::Sync(callback)) => {
+                if callback(window, cx) {
+                    window.close_dialog(cx);
+                }
+            }
+            Some(OnConfirm::Async(callback)) => {
+                let task = callback(window, cx);
+                let window_handle = window.window_handle();
+                self.pending = true;
+                self.error = None;
+                cx.notify();
+
+                cx.spawn(async move |this, cx| {
+                    let result = task.await;
+                    cx.update_window(window_handle, |_, window, cx| {
+                        this.update(cx, |this, cx| {
+                            this.pending = false;
+                            match result {
+                                Ok(()) => window.close_dialog(cx),
+                                Err(e) => this.error = Some(e.to_string()),
+                            }
+                            cx.notify();
+                        })
+                    })??;
+
+                    Ok::<_, anyhow::Error>(())
+                })
+                .detach();
+            }
+        }
+    }
+
+    fn cancel(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.pending {
+            return;
+        }
+        if let Some(callback) = &self.on_cancel {
+            callback(window, cx);
+        }
+        window.close_dialog(cx);
+    }
+}
+
+impl Render for ConfirmDialogContent {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .gap_2()
+            .track_focus(&self.focus_handle)
+            .on_key_down(cx.listener(|this, event: &KeyDownEvent, window, cx| {
+                match event.keystroke.key.as_str() {
+                    "enter" if this.input.is_none() => this.confirm(window, cx),
+                    "escape" => this.cancel(window, cx),
+                    _ => return,
+                }
+                cx.stop_propagation();
+            }))
+            .child(
+                v_flex()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .when_some(self.message.clone(), |this, msg| this.child(msg))
+                    .when_some(self.required_text.clone(), |this, text| {
+                        this.child(format!("Type {} to confirm.", text))
+                    }),
+            )
+            .when_some(self.input.clone(), |this, input| {
+                this.child(Input::new(&input).small())
+            })
+            .when_some(self.error.clone(), |this, error| {
+                this.child(
                     v_flex()
                         .text_xs()
-                        .text_color(cx.theme().muted_foreground)
-                        .when_some(message.clone(), |this, msg| this.child(msg)),
+                        .text_color(cx.theme().danger)
+                        .child(error),
                 )
-        });
+            })
     }
 }
@@ -0,0 +1,351 @@
+use gpui::prelude::FluentBuilder;
+use gpui::{
+    AnyElement, App, AppContext, ClickEvent, Context, Entity, Hsla, InteractiveElement,
+    IntoElement, ParentElement, Render, SharedString, StatefulInteractiveElement, Styled, Window,
+    div, px,
+};
+use gpui_component::{
+    ActiveTheme, Icon, IconName, Sizable, StyledExt, WindowExt,
+    button::{Button, ButtonVariants},
+    h_flex,
+    label::Label,
+    scroll::ScrollableElement,
+    v_flex,
+};
+use serde_json::Value;
+
+use crate::{
+    Utils, app::states::document_state::DocumentState, domain::database::document::DocumentModel,
+};
+
+/// A block of the document as it exists in both versions, in one of them only, or with
+/// different contents in each.
+struct ConflictRow {
+    local: Option<Value>,
+    external: Option<Value>,
+    keep_local: bool,
+    keep_external: bool,
+}
+
+impl ConflictRow {
+    fn new(local: Option<Value>, external: Option<Value>) -> Self {
+        Self {
+            keep_local: local.is_some(),
+            keep_external: local.is_none(),
+            local,
+            external,
+        }
+    }
+
+    fn is_unchanged(&self) -> bool {
+        self.local.is_some() && self.local == self.external
+    }
+}
+
+/// Side-by-side merge of a document edited both in Remindr and outside of it.
+///
+/// Each differing block can be taken from either version, or from both. Saving is paused
+/// for the document until one of the resolutions is applied.
+pub struct ConflictDialog {
+    document: DocumentModel,
+    external_title: String,
+    rows: Vec<ConflictRow>,
+}
+
+impl ConflictDialog {
+    pub fn open(local: DocumentModel, external: DocumentModel, window: &mut Window, cx: &mut App) {
+        let view = cx.new(|_| ConflictDialog::new(local, external));
+
+        window.open_dialog(cx, move |dialog, _, _| {
+            let view = view.clone();
+
+            dialog
+                .w(px(960.))
+                .title(
+                    v_flex()
+                        .text_sm()
+                        .font_semibold()
+                        .child("This document was changed outside Remindr"),
+                )
+                .close_button(false)
+                .overlay_closable(false)
+                .keyboard(false)
+                .footer(move |_, _, _, _| {
+                    vec![
+                        resolution_button("keep-local", "Keep mine", &view, |this| {
+                            this.version_content(true)
+                        }),
+                        resolution_button("keep-external", "Take theirs", &view, |this| {
+                            this.version_content(false)
+                        }),
+                        resolution_button("apply-merge", "Apply merge", &view, |this| {
+                            this.merged_content()
+                        })
+                        .primary(),
+                    ]
+                    .into_iter()
+                    .map(IntoElement::into_any_element)
+                    .collect()
+                })
+                .child(view.clone())
+        });
+    }
+
+    fn new(local: DocumentModel, external: DocumentModel) -> Self {
+        let local_blocks = local.content.as_array().cloned().unwrap_or_default();
+        let external_blocks = external.content.as_array().cloned().unwrap_or_default();
+
+        Self {
+            rows: align_blocks(local_blocks, external_blocks),
+            external_title: external.title,
+            document: local,
+        }
+    }
+
+    /// Content of one of the two versions, unmerged.
+    fn version_content(&self, local: bool) -> Vec<Value> {
+        self.rows
+            .iter()
+            .filter_map(|row| if local { &row.local } else { &row.external }.clone())
+            .collect()
+    }
+
+    /// Content made of the blocks picked in each version.
+    fn merged_content(&self) -> Vec<Value> {
+        let mut content = Vec::new();
+
+        for row in &self.rows {
+            if row.is_unchanged() {
+                content.extend(row.local.clone());
+                continue;
+            }
+
+            if row.keep_local {
+                content.extend(row.local.clone());
+            }
+            if row.keep_external
+                && let Some(mut block) = row.external.clone()
+            {
+                // Both versions of the block are kept, so the copy needs its own id
+                if row.keep_local
+                    && row.local.is_some()
+                    && let Some(object) = block.as_object_mut()
+                {
+                    object.insert(
+                        "id".to_string(),
+                        Value::String(Utils::generate_uuid().to_string()),
+                    );
+                }
+                content.push(block);
+            }
+        }
+
+        content
+    }
+
+    fn toggle(&mut self, index: usize, local: bool, cx: &mut Context<Self>) {
+        if let Some(row) = self.rows.get_mut(index) {
+            if local {
+                row.keep_local = !row.keep_local;
+            } else {
+                row.keep_external = !row.keep_external;
+            }
+            cx.notify();
+        }
+    }
+
+    fn resolved_document(&self, content: Vec<Value>) -> DocumentModel {
+        DocumentModel {
+            content: Value::from_iter(content),
+            ..self.document.clone()
+        }
+    }
+
+    fn render_block(
+        &self,
+        index: usize,
+        local: bool,
+        block: Option<&Value>,
+        selected: bool,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let Some(block) = block else {
+            return div().flex_1().into_any_element();
+        };
+
+        let id = if local {
+            "local-block"
+        } else {
+            "external-block"
+        };
+        let accent = cx.theme().primary;
+        let border: Hsla = if selected { accent } else { cx.theme().border };
+
+        h_flex()
+            .id((id, index))
+            .flex_1()
+            .min_w_0()
+            .gap_2()
+            .items_start()
+            .p_2()
+            .rounded_md()
+            .border_1()
+            .border_color(border)
+            .cursor_pointer()
+            .when(!selected, |this| this.opacity(0.6))
+            .hover(|this| this.bg(cx.theme().accent.opacity(0.3)))
+            .on_click(cx.listener(move |this, _: &ClickEvent, _, cx| {
+                this.toggle(index, local, cx);
+            }))
+            .child(div().flex_shrink_0().size_4().when(selected, |this| {
+                this.child(Icon::new(IconName::Check).small().text_color(accent))
+            }))
+            .child(
+                Label::new(block_preview(block))
+                    .text_sm()
+                    .text_color(cx.theme().foreground),
+            )
+            .into_any_element()
+    }
+}
+
+impl Render for ConflictDialog {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let muted = cx.theme().muted_foreground;
+
+        let rows = (0..self.rows.len())
+            .map(|index| {
+                let row = &self.rows[index];
+                if row.is_unchanged() {
+                    return div()
+                        .px_2()
+                        .py_1()
+                        .child(
+                            Label::new(block_preview(row.local.as_ref().unwrap()))
+                                .text_sm()
+                                .text_color(muted),
+                        )
+                        .into_any_element();
+                }
+
+                let (local, external) = (row.local.clone(), row.external.clone());
+                let (keep_local, keep_external) = (row.keep_local, row.keep_external);
+
+                h_flex()
+                    .gap_3()
+                    .items_stretch()
+                    .child(self.render_block(index, true, local.as_ref(), keep_local, cx))
+                    .child(self.render_block(index, false, external.as_ref(), keep_external, cx))
+                    .into_any_element()
+            })
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .gap_2()
+            .child(
+                Label::new("Pick the blocks to keep from each version.")
+                    .text_xs()
+                    .text_color(muted),
+            )
+            .child(
+                h_flex()
+                    .gap_3()
+                    .text_xs()
+                    .font_semibold()
+                    .text_color(muted)
+                    .child(
+                        div()
+                            .flex_1()
+                            .child(format!("Your version: {}", self.document.title)),
+                    )
+                    .child(
+                        div()
+                            .flex_1()
+                            .child(format!("Version on disk: {}", self.external_title)),
+                    ),
+            )
+            .child(
+                v_flex()
+                    .max_h(px(480.))
+                    .gap_1()
+                    .overflow_y_scrollbar()
+                    .children(rows),
+            )
+    }
+}
+
+fn resolution_button(
+    id: &'static str,
+    label: &'static str,
+    view: &Entity<ConflictDialog>,
+    content: impl Fn(&ConflictDialog) -> Vec<Value> + 'static,
+) -> Button {
+    let view = view.clone();
+
+    Button::new(id).small().label(label).on_click(
+        move |_: &ClickEvent, window: &mut Window, cx: &mut App| {
+            let dialog = view.read(cx);
+            let document = dialog.resolved_document(content(dialog));
+
+            window.close_dialog(cx);
+            DocumentState::resolve_conflict(document, cx);
+        },
+    )
+}
+
+/// Pairs the blocks of both versions by id, keeping the local order and placing blocks
+/// only found in the external version after the block preceding them there.
+fn align_blocks(local: Vec<Value>, external: Vec<Value>) -> Vec<ConflictRow> {
+    let mut matched = vec![false; external.len()];
+
+    let mut rows = local
+        .into_iter()
+        .map(|block| {
+            let index = block_id(&block).and_then(|id| {
+                external
+                    .iter()
+                    .position(|other| block_id(other) == Some(id))
+            });
+            let external_block = index.map(|index| {
+                matched[index] = true;
+                external[index].clone()
+            });
+            ConflictRow::new(Some(block), external_block)
+        })
+        .collect::<Vec<_>>();
+
+    let mut insert_at = 0;
+    for (index, block) in external.iter().enumerate() {
+        if matched[index] {
+            insert_at = rows
+                .iter()
+                .position(|row| row.external.as_ref().and_then(block_id) == block_id(block))
+                .map_or(insert_at, |position| position + 1);
+        } else {
+            rows.insert(insert_at, ConflictRow::new(None, Some(block.clone())));
+            insert_at += 1;
+        }
+    }
+
+    rows
+}
+
+fn block_id(block: &Value) -> Option<&str> {
+    block.get("id").and_then(Value::as_str)
+}
+
+fn block_preview(block: &Value) -> SharedString {
+    let content = block
+        .get("metadata")
+        .and_then(|metadata| metadata.get("content"))
+        .and_then(Value::as_str)
+        .filter(|content| !content.trim().is_empty());
+
+    match content {
+        Some(content) => SharedString::from(content.to_string()),
+        None => {
+            let node_type = block.get("type").and_then(Value::as_str).unwrap_or("block");
+            SharedString::from(format!("({})", node_type))
+        }
+    }
+}
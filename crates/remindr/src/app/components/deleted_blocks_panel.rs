@@ -0,0 +1,162 @@
+use gpui::prelude::FluentBuilder;
+use gpui::{App, BorrowAppContext, IntoElement, ParentElement, Styled, Window, div, px};
+use gpui_component::{
+    ActiveTheme, Sizable, StyledExt, WindowExt,
+    button::{Button, ButtonVariants},
+    h_flex,
+    label::Label,
+    scroll::ScrollableElement,
+    v_flex,
+};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::{
+    LoadingState, Utils,
+    app::{
+        components::document_preview::DocumentPreview,
+        states::{
+            deleted_blocks_state::DeletedBlocksState, document_state::DocumentState,
+            toast_state::Toast,
+        },
+    },
+};
+
+/// Lists the blocks deleted during this session, newest first, each of which can be put
+/// back below the block being edited in the current document.
+pub fn open(window: &mut Window, cx: &mut App) {
+    window.open_dialog(cx, |dialog, _, cx| {
+        let fg = cx.theme().foreground;
+        let muted_fg = cx.theme().muted_foreground;
+        let border = cx.theme().border;
+        let deleted = cx.global::<DeletedBlocksState>().blocks();
+
+        let blocks = deleted
+            .iter()
+            .enumerate()
+            .map(|(ix, block)| {
+                let preview = DocumentPreview::render_block(&block.data, cx).unwrap_or_else(|| {
+                    div()
+                        .text_xs()
+                        .italic()
+                        .text_color(fg)
+                        .child(
+                            block
+                                .data
+                                .get("type")
+                                .and_then(Value::as_str)
+                                .unwrap_or("Block")
+                                .to_string(),
+                        )
+                        .into_any_element()
+                });
+
+                h_flex()
+                    .gap_2()
+                    .py_1()
+                    .border_t_1()
+                    .border_color(border)
+                    .child(div().flex_1().min_w_0().overflow_hidden().child(preview))
+                    .child(
+                        div()
+                            .flex_shrink_0()
+                            .text_xs()
+                            .text_color(muted_fg)
+                            .child(block.deleted_at.format("%H:%M").to_string()),
+                    )
+                    .child(
+                        Button::new(("restore-deleted-block", ix))
+                            .xsmall()
+                            .ghost()
+                            .label("Insert")
+                            .on_click(move |_, window, cx| restore(ix, window, cx)),
+                    )
+            })
+            .collect::<Vec<_>>();
+
+        dialog
+            .w(px(480.))
+            .title(
+                v_flex()
+                    .text_sm()
+                    .font_semibold()
+                    .child("Recently deleted blocks"),
+            )
+            .overlay_closable(true)
+            .child(
+                v_flex()
+                    .gap_2()
+                    .when(blocks.is_empty(), |el| {
+                        el.child(
+                            Label::new("No block was deleted during this session.")
+                                .text_sm()
+                                .text_color(muted_fg),
+                        )
+                    })
+                    .when(!blocks.is_empty(), |el| {
+                        el.child(
+                            v_flex()
+                                .max_h(px(360.))
+                                .overflow_y_scrollbar()
+                                .children(blocks),
+                        )
+                        .child(
+                            h_flex().justify_end().child(
+                                Button::new("clear-deleted-blocks")
+                                    .small()
+                                    .ghost()
+                                    .label("Clear")
+                                    .on_click(|_, _, cx| {
+                                        cx.update_global::<DeletedBlocksState, _>(|state, _| {
+                                            state.clear()
+                                        });
+                                    }),
+                            ),
+                        )
+                    }),
+            )
+    });
+}
+
+/// Inserts the deleted block at `index` below the block that last had the focus in the
+/// current document of the window.
+fn restore(index: usize, window: &mut Window, cx: &mut App) {
+    let window_id = window.window_handle().window_id();
+    let state = cx
+        .global::<DocumentState>()
+        .workspace(window_id)
+        .and_then(|workspace| workspace.get_current_document())
+        .and_then(|document| match &document.state {
+            LoadingState::Loaded(content) => Some(content.renderer.read(cx).state.clone()),
+            _ => None,
+        });
+    let Some(state) = state else {
+        Toast::error("Open a document to insert the block in").show(window, cx);
+        return;
+    };
+    let Some(block) = cx.update_global::<DeletedBlocksState, _>(|deleted, _| deleted.take(index))
+    else {
+        return;
+    };
+
+    let mut data = block.data;
+    // The block may have been put back already, e.g. through an older copy
+    let id_taken = data
+        .get("id")
+        .and_then(Value::as_str)
+        .and_then(|id| Uuid::parse_str(id).ok())
+        .is_some_and(|id| state.read(cx).get_current_nodes(id).is_some());
+    if id_taken && let Some(object) = data.as_object_mut() {
+        object.insert(
+            "id".to_string(),
+            Value::String(Utils::generate_uuid().to_string()),
+        );
+    }
+
+    window.close_dialog(cx);
+    state.update(cx, |node_state, cx| {
+        let node = node_state.parse_node(&data, &state, window, cx);
+        node_state.insert_node_at_cursor(&node, cx);
+        node.element.focus_end(window, cx);
+    });
+}
@@ -0,0 +1,217 @@
+use std::f32::consts::TAU;
+
+use chrono::{Days, Local};
+use gpui::{App, AppContext, Entity, SharedString, actions};
+use serde::Serialize;
+use serde_json::{Value, to_value};
+
+use crate::{
+    Utils,
+    app::{
+        components::nodes::{
+            attachment::data::{AttachmentMetadata, AttachmentNodeData},
+            divider::data::DividerNodeData,
+            drawing::data::{DrawingMetadata, DrawingNodeData, Stroke},
+            equation::data::{EquationMetadata, EquationNodeData},
+            heading::data::{HeadingMetadata, HeadingNodeData},
+            list::data::{ListMetadata, ListNodeData},
+            quote::data::{QuoteMetadata, QuoteNodeData},
+            synced_block::data::{SyncedBlockMetadata, SyncedBlockNodeData},
+            text::data::{TextMetadata, TextNodeData},
+            todo::data::{TodoMetadata, TodoNodeData, TodoStatus},
+        },
+        states::{
+            app_state::AppState,
+            error_state::{AppError, ErrorKind},
+            repository_state::RepositoryState,
+        },
+    },
+    domain::database::{
+        document::DocumentModel,
+        task::{DATE_MENTION_FORMAT, DATE_MENTION_PREFIX},
+    },
+};
+
+actions!(demo_document, [CreateDemoDocument]);
+
+const DEMO_TITLE: &str = "Remindr demo";
+
+/// Creates a document using every block type and opens it, to try the themes or discover
+/// the editor.
+pub fn create(app_state: Entity<AppState>, cx: &mut App) {
+    let repository = cx.global::<RepositoryState>().documents.clone();
+
+    cx.spawn(async move |cx| {
+        let document = DocumentModel {
+            id: 0,
+            title: DEMO_TITLE.to_string(),
+            content: Value::Array(demo_nodes()),
+            folder_id: None,
+        };
+
+        match repository.insert_document(document).await {
+            Ok(id) => {
+                cx.update(|cx| {
+                    app_state.update(cx, |app_state, cx| {
+                        app_state.open_document(id, DEMO_TITLE.to_string(), None, cx);
+                    });
+                });
+            }
+            Err(e) => {
+                AppError::new(ErrorKind::Storage, "Couldn't create the demo document", &e).report()
+            }
+        }
+    })
+    .detach();
+}
+
+/// Blocks of the demo document, built from the node data so they follow their format.
+fn demo_nodes() -> Vec<Value> {
+    let due_date = Local::now()
+        .date_naive()
+        .checked_add_days(Days::new(3))
+        .unwrap_or_default()
+        .format(DATE_MENTION_FORMAT);
+    let mention = format!("{}{}", DATE_MENTION_PREFIX, due_date);
+
+    vec![
+        heading(1, "Welcome to Remindr"),
+        text(
+            "This document shows every kind of block. Type / in an empty block to insert one, \
+             or start a line with # , - , 1. , > or [] to change its type.",
+        ),
+        heading(2, "Text"),
+        text("Select some text to make it bold, italic, underlined or struck through."),
+        node(QuoteNodeData::new(
+            Utils::generate_uuid(),
+            "quote".to_string(),
+            QuoteMetadata {
+                content: "Quotes stand out from the text around them.".into(),
+            },
+        )),
+        list(false, "Bulleted lists group related ideas"),
+        list(false, "Start a line with - to make one"),
+        list(true, "Numbered lists keep steps in order"),
+        list(true, "Each item is numbered automatically"),
+        heading(2, "Tasks"),
+        todo(
+            TodoStatus::Todo,
+            format!("Mention a date like {} to see it in the calendar", mention),
+        ),
+        todo(
+            TodoStatus::InProgress,
+            "Move a to-do between columns on the board".to_string(),
+        ),
+        todo(TodoStatus::Done, "Open the demo document".to_string()),
+        node(DividerNodeData::new(
+            Utils::generate_uuid(),
+            "divider".to_string(),
+        )),
+        heading(3, "Equations"),
+        node(EquationNodeData::new(
+            Utils::generate_uuid(),
+            "equation".to_string(),
+            EquationMetadata {
+                content: "\\sum_{k=1}^{n} k = \\frac{n(n+1)}{2}".to_string(),
+            },
+        )),
+        heading(3, "Drawings"),
+        node(DrawingNodeData::new(
+            Utils::generate_uuid(),
+            "drawing".to_string(),
+            DrawingMetadata {
+                strokes: demo_strokes(),
+            },
+        )),
+        heading(3, "Attachments and synced blocks"),
+        text("Pick a file to attach below, or share the same content between documents."),
+        node(AttachmentNodeData::new(
+            Utils::generate_uuid(),
+            "attachment".to_string(),
+            AttachmentMetadata::default(),
+        )),
+        node(SyncedBlockNodeData::new(
+            Utils::generate_uuid(),
+            "synced_block".to_string(),
+            SyncedBlockMetadata::default(),
+        )),
+    ]
+}
+
+/// A sine wave over a circle, in the colors of the drawing palette.
+fn demo_strokes() -> Vec<Stroke> {
+    const STEPS: usize = 48;
+
+    let wave = (0..=STEPS)
+        .map(|step| {
+            let x = step as f32 / STEPS as f32;
+            (0.05 + x * 0.9, 0.5 - (x * TAU).sin() * 0.3)
+        })
+        .collect();
+    let circle = (0..=STEPS)
+        .map(|step| {
+            let angle = step as f32 / STEPS as f32 * TAU;
+            (0.5 + angle.cos() * 0.2, 0.5 + angle.sin() * 0.35)
+        })
+        .collect();
+
+    vec![
+        Stroke {
+            color: 0x3b82f6,
+            width: 3.0,
+            points: wave,
+        },
+        Stroke {
+            color: 0xef4444,
+            width: 2.0,
+            points: circle,
+        },
+    ]
+}
+
+fn node(data: impl Serialize) -> Value {
+    to_value(data).unwrap()
+}
+
+fn heading(level: u32, content: &'static str) -> Value {
+    node(HeadingNodeData::new(
+        Utils::generate_uuid(),
+        "heading".to_string(),
+        HeadingMetadata {
+            content: content.into(),
+            level,
+        },
+    ))
+}
+
+fn text(content: &'static str) -> Value {
+    node(TextNodeData::new(
+        Utils::generate_uuid(),
+        "text".to_string(),
+        TextMetadata {
+            content: content.into(),
+        },
+    ))
+}
+
+fn list(ordered: bool, content: &'static str) -> Value {
+    node(ListNodeData::new(
+        Utils::generate_uuid(),
+        "list".to_string(),
+        ListMetadata {
+            content: content.into(),
+            ordered,
+        },
+    ))
+}
+
+fn todo(status: TodoStatus, content: String) -> Value {
+    node(TodoNodeData::new(
+        Utils::generate_uuid(),
+        "todo".to_string(),
+        TodoMetadata {
+            content: SharedString::from(content),
+            status,
+        },
+    ))
+}
@@ -0,0 +1,226 @@
+use gpui::prelude::FluentBuilder;
+use gpui::{
+    AnyElement, AnyView, App, AppContext, BorrowAppContext, Context, FontWeight, IntoElement,
+    ParentElement, Render, Styled, Window, div, px,
+};
+use gpui_component::{ActiveTheme, StyledExt, h_flex, label::Label, v_flex};
+use serde_json::Value;
+
+use crate::app::states::{
+    preview_state::{CachedPreview, PreviewState},
+    repository_state::RepositoryState,
+};
+
+/// Read-only card showing the first blocks of a document, displayed when hovering a
+/// reference to it.
+pub struct DocumentPreview {
+    preview: Option<CachedPreview>,
+    failed: bool,
+}
+
+impl DocumentPreview {
+    /// Builds the tooltip of an element referencing a document. The card shows up after
+    /// the usual tooltip delay.
+    pub fn tooltip(document_id: i32) -> impl Fn(&mut Window, &mut App) -> AnyView + 'static {
+        move |_, cx| cx.new(|cx| DocumentPreview::new(document_id, cx)).into()
+    }
+
+    fn new(document_id: i32, cx: &mut Context<Self>) -> Self {
+        let cached = cx
+            .try_global::<PreviewState>()
+            .and_then(|state| state.get(document_id))
+            .cloned();
+
+        if cached.is_none() {
+            let documents = cx.global::<RepositoryState>().documents.clone();
+            cx.spawn(async move |this, cx| {
+                let result = documents.get_document_by_id(document_id).await;
+
+                this.update(cx, |this, cx| {
+                    match result {
+                        Ok(document) => {
+                            this.preview = cx.update_global::<PreviewState, _>(|state, _| {
+                                state.insert(document_id, document.title, &document.content);
+                                state.get(document_id).cloned()
+                            });
+                        }
+                        Err(e) => {
+                            tracing::error!("failed to load document: {:?}", e);
+                            this.failed = true;
+                        }
+                    }
+                    cx.notify();
+                })?;
+
+                Ok::<_, anyhow::Error>(())
+            })
+            .detach();
+        }
+
+        Self {
+            preview: cached,
+            failed: false,
+        }
+    }
+
+    /// Compact rendering of a block, none for a block with nothing to show.
+    pub fn render_block(block: &Value, cx: &App) -> Option<AnyElement> {
+        let metadata = block.get("metadata");
+        let content = metadata
+            .and_then(|metadata| metadata.get("content"))
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        let foreground = cx.theme().foreground;
+        let muted = cx.theme().muted_foreground;
+
+        let element = match block.get("type").and_then(Value::as_str)? {
+            "heading" => div()
+                .text_sm()
+                .font_weight(FontWeight::SEMIBOLD)
+                .text_color(foreground)
+                .child(content)
+                .into_any_element(),
+            "list" => {
+                let ordered = metadata
+                    .and_then(|metadata| metadata.get("ordered"))
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false);
+                h_flex()
+                    .gap_1()
+                    .items_start()
+                    .text_xs()
+                    .text_color(foreground)
+                    .child(if ordered { "1." } else { "•" })
+                    .child(content)
+                    .into_any_element()
+            }
+            "quote" => div()
+                .pl_2()
+                .border_l_2()
+                .border_color(cx.theme().border)
+                .text_xs()
+                .italic()
+                .text_color(muted)
+                .child(content)
+                .into_any_element(),
+            "todo" => {
+                let done = metadata
+                    .and_then(|metadata| metadata.get("status"))
+                    .and_then(Value::as_str)
+                    == Some("done");
+                h_flex()
+                    .gap_1()
+                    .items_start()
+                    .text_xs()
+                    .text_color(if done { muted } else { foreground })
+                    .child(if done { "☑" } else { "☐" })
+                    .child(content)
+                    .into_any_element()
+            }
+            "attachment" => {
+                let file_name = metadata
+                    .and_then(|metadata| metadata.get("file_name"))
+                    .and_then(Value::as_str)
+                    .filter(|file_name| !file_name.is_empty())?
+                    .to_string();
+                h_flex()
+                    .gap_1()
+                    .text_xs()
+                    .text_color(muted)
+                    .child("📎")
+                    .child(file_name)
+                    .into_any_element()
+            }
+            "drawing" => div()
+                .text_xs()
+                .italic()
+                .text_color(muted)
+                .child("Drawing")
+                .into_any_element(),
+            "equation" => div()
+                .text_xs()
+                .font_family("monospace")
+                .text_color(foreground)
+                .child(content)
+                .into_any_element(),
+            "link" => {
+                let title = metadata
+                    .and_then(|metadata| metadata.get("title"))
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string();
+                h_flex()
+                    .gap_1()
+                    .text_xs()
+                    .text_color(foreground)
+                    .child("↗")
+                    .child(title)
+                    .into_any_element()
+            }
+            "divider" => div()
+                .h(px(1.))
+                .my_1()
+                .bg(cx.theme().border)
+                .into_any_element(),
+            _ if content.is_empty() => return None,
+            _ => div()
+                .text_xs()
+                .text_color(foreground)
+                .child(content)
+                .into_any_element(),
+        };
+
+        Some(element)
+    }
+}
+
+impl Render for DocumentPreview {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let muted = cx.theme().muted_foreground;
+
+        let body = match &self.preview {
+            Some(preview) => {
+                let blocks = preview
+                    .blocks
+                    .iter()
+                    .filter_map(|block| Self::render_block(block, cx))
+                    .collect::<Vec<_>>();
+
+                v_flex()
+                    .gap_1()
+                    .child(
+                        Label::new(preview.title.clone())
+                            .text_sm()
+                            .font_semibold()
+                            .text_color(cx.theme().foreground),
+                    )
+                    .when(blocks.is_empty(), |this| {
+                        this.child(Label::new("Empty document").text_xs().text_color(muted))
+                    })
+                    .children(blocks)
+            }
+            None => v_flex().child(
+                Label::new(if self.failed {
+                    "Preview unavailable"
+                } else {
+                    "Loading..."
+                })
+                .text_xs()
+                .text_color(muted),
+            ),
+        };
+
+        div()
+            .w(px(300.))
+            .max_h(px(240.))
+            .overflow_hidden()
+            .p_3()
+            .rounded(cx.theme().radius)
+            .border_1()
+            .border_color(cx.theme().border)
+            .bg(cx.theme().popover)
+            .shadow_lg()
+            .child(body)
+    }
+}
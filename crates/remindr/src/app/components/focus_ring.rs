@@ -0,0 +1,16 @@
+use gpui::{Hsla, InteractiveElement, Styled, transparent_black};
+
+/// Makes a custom row reachable with Tab, and activated with Enter or Space like a click.
+pub trait FocusRingExt: InteractiveElement + Styled + Sized {
+    /// Shows a ring of `color`, the `ring` color of the theme, around the element while
+    /// it is focused with the keyboard. The border is always there so that the layout
+    /// doesn't move.
+    fn focus_ring(self, color: Hsla) -> Self {
+        self.tab_index(0)
+            .border_1()
+            .border_color(transparent_black())
+            .focus_visible(move |style| style.border_color(color))
+    }
+}
+
+impl<E: InteractiveElement + Styled> FocusRingExt for E {}
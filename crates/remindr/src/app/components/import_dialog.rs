@@ -0,0 +1,160 @@
+use std::path::Path;
+
+use anyhow::Error;
+use gpui::prelude::FluentBuilder;
+use gpui::{App, ParentElement, PathPromptOptions, Styled, Window, div, px};
+use gpui_component::{
+    ActiveTheme, StyledExt, WindowExt, label::Label, scroll::ScrollableElement, v_flex,
+};
+
+use crate::{
+    app::states::{
+        progress_state::ProgressTask, repository_state::RepositoryState, toast_state::Toast,
+    },
+    infrastructure::import::{
+        Import, ImportReport, enex::read_enex, notion::read_notion_export,
+        obsidian::read_markdown_folder, opml::read_opml, save_import,
+    },
+};
+
+/// App the documents are imported from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImportSource {
+    /// "Markdown & CSV" export of a workspace, as a zip file.
+    Notion,
+    /// Folder of markdown files, like an Obsidian vault.
+    Markdown,
+    /// Notebook exported by Evernote or Apple Notes, as an `.enex` file.
+    Enex,
+    /// Outline of an outliner, or the folder tree exported by Remindr.
+    Opml,
+}
+
+impl ImportSource {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ImportSource::Notion => "Notion",
+            ImportSource::Markdown => "a markdown folder",
+            ImportSource::Enex => "Evernote",
+            ImportSource::Opml => "an OPML outline",
+        }
+    }
+
+    fn prompt(&self) -> PathPromptOptions {
+        match self {
+            ImportSource::Notion | ImportSource::Enex | ImportSource::Opml => PathPromptOptions {
+                files: true,
+                directories: false,
+                multiple: false,
+                prompt: Some("Import".into()),
+            },
+            ImportSource::Markdown => PathPromptOptions {
+                files: false,
+                directories: true,
+                multiple: false,
+                prompt: Some("Import".into()),
+            },
+        }
+    }
+
+    /// Reads the picked file or folder. Blocks while reading it.
+    fn read(&self, path: &Path) -> Result<Import, Error> {
+        match self {
+            ImportSource::Notion => read_notion_export(path),
+            ImportSource::Markdown => read_markdown_folder(path),
+            ImportSource::Enex => read_enex(path),
+            ImportSource::Opml => read_opml(path),
+        }
+    }
+}
+
+/// Asks for the file to import from `source`, stores its documents in a new folder and
+/// shows what couldn't be converted.
+pub fn import(source: ImportSource, window: &mut Window, cx: &mut App) {
+    let Some(repositories) = cx.try_global::<RepositoryState>() else {
+        return;
+    };
+    let documents = repositories.documents.clone();
+    let folders = repositories.folders.clone();
+    let attachments = repositories.attachments.clone();
+
+    let paths = cx.prompt_for_paths(source.prompt());
+    let window_handle = window.window_handle();
+
+    cx.spawn(async move |cx| {
+        let Ok(Ok(Some(paths))) = paths.await else {
+            return Ok(());
+        };
+        let Some(path) = paths.into_iter().next() else {
+            return Ok(());
+        };
+
+        let task = cx.update(|cx| {
+            ProgressTask::start(format!("Importing from {}", source.label()), false, cx)
+        });
+
+        let result = async {
+            let import = cx
+                .background_executor()
+                .spawn(async move { source.read(&path) })
+                .await?;
+            save_import(import, &documents, &folders, &attachments).await
+        }
+        .await;
+
+        cx.update(|cx| task.finish(cx));
+        cx.update_window(window_handle, |_, window, cx| match result {
+            Ok(report) => show_report(source, report, window, cx),
+            Err(e) => {
+                tracing::error!("failed to import from {}: {:?}", source.label(), e);
+                Toast::error(format!("Failed to import from {}: {}", source.label(), e))
+                    .show(window, cx);
+            }
+        })?;
+
+        Ok::<_, Error>(())
+    })
+    .detach();
+}
+
+/// Summary of a finished import, listing the content that couldn't be converted.
+fn show_report(source: ImportSource, report: ImportReport, window: &mut Window, cx: &mut App) {
+    let summary = format!(
+        "Imported {} documents in {} folders from {}.",
+        report.documents,
+        report.folders,
+        source.label()
+    );
+    let warnings = report.warnings;
+
+    window.open_dialog(cx, move |dialog, _, cx| {
+        let muted_fg = cx.theme().muted_foreground;
+
+        dialog
+            .w(px(480.))
+            .title(v_flex().text_sm().font_semibold().child("Import finished"))
+            .overlay_closable(true)
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(Label::new(summary.clone()).text_sm())
+                    .when(!warnings.is_empty(), |el| {
+                        el.child(
+                            Label::new("Some content couldn't be converted:")
+                                .text_xs()
+                                .text_color(muted_fg),
+                        )
+                        .child(
+                            v_flex().max_h(px(320.)).overflow_y_scrollbar().children(
+                                warnings.iter().map(|warning| {
+                                    div()
+                                        .text_xs()
+                                        .text_color(muted_fg)
+                                        .child(format!("• {}", warning))
+                                }),
+                            ),
+                        )
+                    }),
+            )
+    });
+}
@@ -0,0 +1,316 @@
+use std::{path::PathBuf, rc::Rc, sync::Arc};
+
+use anyhow::Error;
+use gpui::prelude::FluentBuilder;
+use gpui::{App, IntoElement, ParentElement, SharedString, Styled, Window, div, px};
+use gpui_component::{
+    ActiveTheme, Sizable, StyledExt, WindowExt,
+    button::{Button, ButtonVariants},
+    h_flex,
+    label::Label,
+    scroll::ScrollableElement,
+    v_flex,
+};
+use serde_json::Value;
+use sqlx::SqlitePool;
+
+use crate::{
+    app::{
+        components::nodes::node::{ParseMode, content_problems},
+        date_format::DateFormat,
+        states::{
+            document_state::DocumentState, progress_state::ProgressTask,
+            repository_state::RepositoryState, settings_state::Settings, toast_state::Toast,
+        },
+    },
+    domain::{
+        database::document::{DocumentLocation, StoredDocument},
+        ports::DocumentRepositoryPort,
+    },
+    infrastructure::{
+        backup::{self, Backup},
+        database,
+        vault::file_vault::FileVault,
+    },
+};
+
+/// Document with blocks that can't be shown, with the newest backup holding a copy whose
+/// blocks all can.
+struct BrokenDocument {
+    document: StoredDocument,
+    problems: Vec<String>,
+    /// Whether the editor opens it, showing the broken blocks as placeholders.
+    opens: bool,
+    backup: Option<Backup>,
+}
+
+struct IntegrityReport {
+    /// Problems reported by SQLite itself.
+    database: Vec<String>,
+    checked: usize,
+    broken: Vec<BrokenDocument>,
+}
+
+/// Checks the database file and that the blocks of every document parse into nodes, then
+/// lists the broken documents with the backups they can be restored from.
+pub fn check_integrity(window: &mut Window, cx: &mut App) {
+    let Some(repositories) = cx.try_global::<RepositoryState>() else {
+        return;
+    };
+    let pool = repositories.pool.clone();
+    let documents = repositories.documents.clone();
+    let vault = repositories.vault.clone();
+    let backup_directory = cx.global::<Settings>().backup.directory.trim().to_string();
+    let window_handle = window.window_handle();
+
+    cx.spawn(async move |cx| {
+        let task = cx.update(|cx| ProgressTask::start("Checking the data", false, cx));
+
+        let result = async {
+            let database = database::integrity_check(&pool).await?;
+            let stored = documents.get_stored_documents().await?;
+            let backups = if backup_directory.is_empty() {
+                Vec::new()
+            } else {
+                let directory = PathBuf::from(backup_directory);
+                smol::unblock(move || backup::list_backups(&directory))
+                    .await
+                    .unwrap_or_default()
+            };
+
+            let checked = stored.len();
+            let mut broken = Vec::new();
+            for document in stored {
+                let problems = parse_problems(&document.content, ParseMode::Strict);
+                if problems.is_empty() {
+                    continue;
+                }
+
+                // Backups are listed newest first
+                let mut restorable = None;
+                for backup in &backups {
+                    if let Ok(Some(content)) =
+                        backup::backed_up_content(backup, &document.location, &pool).await
+                        && parse_problems(&content, ParseMode::Strict).is_empty()
+                    {
+                        restorable = Some(backup.clone());
+                        break;
+                    }
+                }
+                let opens = parse_problems(&document.content, ParseMode::Lenient).is_empty();
+                broken.push(BrokenDocument {
+                    document,
+                    problems,
+                    opens,
+                    backup: restorable,
+                });
+            }
+
+            Ok::<_, Error>(IntegrityReport {
+                database,
+                checked,
+                broken,
+            })
+        }
+        .await;
+
+        cx.update(|cx| task.finish(cx));
+        cx.update_window(window_handle, |_, window, cx| match result {
+            Ok(report) => show_report(report, pool, documents, vault, window, cx),
+            Err(e) => {
+                tracing::error!("failed to check the data: {:?}", e);
+                Toast::error(format!("Failed to check the data: {}", e)).show(window, cx);
+            }
+        })?;
+
+        Ok::<_, Error>(())
+    })
+    .detach();
+}
+
+/// Why the stored content of a document can't be shown fully, none when it can.
+fn parse_problems(content: &str, mode: ParseMode) -> Vec<String> {
+    match serde_json::from_str::<Value>(content) {
+        Ok(content) => content_problems(&content, mode),
+        Err(e) => vec![format!("the content isn't valid JSON ({})", e)],
+    }
+}
+
+fn show_report(
+    report: IntegrityReport,
+    pool: SqlitePool,
+    documents: Arc<dyn DocumentRepositoryPort>,
+    vault: Option<FileVault>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let summary = match (report.database.is_empty(), report.broken.len()) {
+        (true, 0) => format!("Checked {} documents, no problems found.", report.checked),
+        (false, 0) => format!(
+            "The database file has problems, the {} documents open.",
+            report.checked
+        ),
+        (_, broken) => format!(
+            "{} of the {} documents have blocks that can't be shown.",
+            broken, report.checked
+        ),
+    };
+    let report = Rc::new(report);
+
+    window.open_dialog(cx, move |dialog, _, cx| {
+        let fg = cx.theme().foreground;
+        let muted_fg = cx.theme().muted_foreground;
+        let border = cx.theme().border;
+        let format = DateFormat::new(cx);
+
+        let documents = report.broken.iter().enumerate().map(|(index, broken)| {
+            let action = match &broken.backup {
+                Some(backup) => {
+                    let backup = backup.clone();
+                    let location = broken.document.location.clone();
+                    let title = broken.document.title.clone();
+                    let pool = pool.clone();
+                    let documents = documents.clone();
+                    let vault = vault.clone();
+
+                    Button::new(SharedString::from(format!("restore-document-{}", index)))
+                        .xsmall()
+                        .outline()
+                        .label(format!(
+                            "Restore backup ({})",
+                            format.relative(backup.created_at)
+                        ))
+                        .on_click(move |_, window, cx| {
+                            restore(
+                                backup.clone(),
+                                location.clone(),
+                                title.clone(),
+                                pool.clone(),
+                                documents.clone(),
+                                vault.clone(),
+                                window,
+                                cx,
+                            );
+                        })
+                        .into_any_element()
+                }
+                None => Label::new("No backup to restore from")
+                    .text_xs()
+                    .text_color(muted_fg)
+                    .into_any_element(),
+            };
+
+            v_flex()
+                .gap_1()
+                .py_2()
+                .border_t_1()
+                .border_color(border)
+                .child(
+                    h_flex()
+                        .justify_between()
+                        .items_center()
+                        .gap_2()
+                        .child(
+                            Label::new(broken.document.title.clone())
+                                .text_sm()
+                                .font_semibold()
+                                .text_color(fg),
+                        )
+                        .child(action),
+                )
+                .child(
+                    Label::new(if broken.opens {
+                        "Opens with the broken blocks shown as unsupported"
+                    } else {
+                        "Can't be opened"
+                    })
+                    .text_xs()
+                    .text_color(muted_fg),
+                )
+                .children(broken.problems.iter().take(3).map(|problem| {
+                    div()
+                        .text_xs()
+                        .text_color(muted_fg)
+                        .child(format!("• {}", problem))
+                }))
+        });
+
+        dialog
+            .w(px(520.))
+            .title(v_flex().text_sm().font_semibold().child("Integrity check"))
+            .overlay_closable(true)
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(Label::new(summary.clone()).text_sm())
+                    .when(!report.database.is_empty(), |el| {
+                        el.child(
+                            Label::new("SQLite reports problems in the database file:")
+                                .text_xs()
+                                .text_color(muted_fg),
+                        )
+                        .children(report.database.iter().take(10).map(|problem| {
+                            div()
+                                .text_xs()
+                                .text_color(muted_fg)
+                                .child(format!("• {}", problem))
+                        }))
+                    })
+                    .when(!report.broken.is_empty(), |el| {
+                        el.child(
+                            v_flex()
+                                .max_h(px(360.))
+                                .overflow_y_scrollbar()
+                                .children(documents),
+                        )
+                    }),
+            )
+    });
+}
+
+fn restore(
+    backup: Backup,
+    location: DocumentLocation,
+    title: String,
+    pool: SqlitePool,
+    documents: Arc<dyn DocumentRepositoryPort>,
+    vault: Option<FileVault>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let window_handle = window.window_handle();
+
+    cx.spawn(async move |cx| {
+        let result = async {
+            let id = backup::restore_document(&backup, &location, &pool, vault.as_ref()).await?;
+            // Written outside the repository, so the search and tasks wouldn't know
+            documents.reindex_document(id).await?;
+            Ok::<_, Error>(id)
+        }
+        .await;
+
+        if let Ok(id) = result {
+            cx.update(|cx| {
+                cx.update_global::<DocumentState, _>(|state, _| {
+                    state.discard_changes(id);
+                    state.reload_documents(&[id]);
+                });
+                cx.refresh_windows();
+            });
+        }
+
+        cx.update_window(window_handle, |_, window, cx| match result {
+            Ok(_) => {
+                window.close_dialog(cx);
+                Toast::success(format!("\"{}\" was restored", title)).show(window, cx);
+            }
+            Err(e) => {
+                tracing::error!("failed to restore a document: {:?}", e);
+                Toast::error(format!("Failed to restore \"{}\": {}", title, e)).show(window, cx);
+            }
+        })?;
+
+        Ok::<_, Error>(())
+    })
+    .detach();
+}
@@ -0,0 +1,184 @@
+use std::rc::Rc;
+
+use gpui::prelude::FluentBuilder;
+use gpui::{
+    App, AppContext, Context, Entity, InteractiveElement, IntoElement, ParentElement, Render,
+    SharedString, StatefulInteractiveElement, Styled, Window, div, px,
+};
+use gpui_component::{
+    ActiveTheme, Icon, Sizable, StyledExt, WindowExt,
+    input::{Input, InputEvent, InputState},
+    label::Label,
+    scroll::ScrollableElement,
+    v_flex,
+};
+
+use crate::{
+    app::{components::quick_switcher::fuzzy_score, states::repository_state::RepositoryState},
+    domain::database::document::DocumentModel,
+};
+
+type SelectCallback = Rc<dyn Fn(DocumentModel, &mut Window, &mut App) + 'static>;
+
+/// Picks the document another one is merged into.
+pub struct MergeDialog {
+    search_input: Entity<InputState>,
+    /// Every document but the merged one, by title.
+    documents: Vec<DocumentModel>,
+    on_select: SelectCallback,
+}
+
+impl MergeDialog {
+    pub fn open(
+        source_id: i32,
+        source_title: String,
+        on_select: impl Fn(DocumentModel, &mut Window, &mut App) + 'static,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let on_select: SelectCallback = Rc::new(on_select);
+        let view = cx.new(|cx| MergeDialog::new(source_id, on_select, window, cx));
+
+        window.open_dialog(cx, move |dialog, _, _| {
+            dialog
+                .w(px(420.))
+                .title(
+                    v_flex()
+                        .text_sm()
+                        .font_semibold()
+                        .child(format!("Merge \"{}\" into…", source_title)),
+                )
+                .overlay_closable(true)
+                .child(view.clone())
+        });
+    }
+
+    fn new(
+        source_id: i32,
+        on_select: SelectCallback,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let search_input =
+            cx.new(|cx| InputState::new(window, cx).placeholder("Search documents..."));
+
+        cx.subscribe_in(
+            &search_input,
+            window,
+            |this, _, event: &InputEvent, window, cx| match event {
+                InputEvent::Change => cx.notify(),
+                InputEvent::PressEnter { .. } => {
+                    if let Some(document) = this.filtered_documents(cx).first() {
+                        let document = (*document).clone();
+                        this.select(document, window, cx);
+                    }
+                }
+                _ => {}
+            },
+        )
+        .detach();
+
+        search_input.update(cx, |input, cx| {
+            input.focus(window, cx);
+        });
+
+        let repository = cx.global::<RepositoryState>().documents.clone();
+        cx.spawn(async move |this, cx| {
+            let mut documents = repository.get_documents().await?;
+            documents.retain(|document| document.id != source_id);
+            documents.sort_by_key(|document| document.title.to_lowercase());
+
+            this.update(cx, |this, cx| {
+                this.documents = documents;
+                cx.notify();
+            })?;
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+
+        Self {
+            search_input,
+            documents: Vec::new(),
+            on_select,
+        }
+    }
+
+    fn filtered_documents(&self, cx: &App) -> Vec<&DocumentModel> {
+        let search = self.search_input.read(cx).value();
+        if search.trim().is_empty() {
+            return self.documents.iter().collect();
+        }
+
+        let mut matches = self
+            .documents
+            .iter()
+            .filter_map(|document| Some((fuzzy_score(&search, &document.title)?, document)))
+            .collect::<Vec<_>>();
+        matches.sort_by(|(a, _), (b, _)| b.cmp(a));
+        matches.into_iter().map(|(_, document)| document).collect()
+    }
+
+    fn select(&mut self, document: DocumentModel, window: &mut Window, cx: &mut Context<Self>) {
+        window.close_dialog(cx);
+        (self.on_select)(document, window, cx);
+    }
+}
+
+impl Render for MergeDialog {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let documents = self
+            .filtered_documents(cx)
+            .into_iter()
+            .cloned()
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .gap_2()
+            .child(
+                Input::new(&self.search_input).small().prefix(
+                    Icon::default()
+                        .path("icons/search.svg")
+                        .small()
+                        .text_color(cx.theme().muted_foreground),
+                ),
+            )
+            .child(
+                v_flex()
+                    .max_h(px(320.))
+                    .overflow_y_scrollbar()
+                    .when(documents.is_empty(), |this| {
+                        this.child(
+                            div().px_2().py_2().child(
+                                Label::new("No matching documents")
+                                    .text_sm()
+                                    .text_color(cx.theme().muted_foreground),
+                            ),
+                        )
+                    })
+                    .children(documents.into_iter().map(|document| {
+                        let title = if document.title.is_empty() {
+                            "Untitled".to_string()
+                        } else {
+                            document.title.clone()
+                        };
+
+                        div()
+                            .id(("merge-target", document.id as usize))
+                            .px_2()
+                            .py_1()
+                            .rounded_md()
+                            .cursor_pointer()
+                            .hover(|this| this.bg(cx.theme().accent.opacity(0.5)))
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                this.select(document.clone(), window, cx);
+                            }))
+                            .child(
+                                Label::new(SharedString::from(title))
+                                    .text_sm()
+                                    .text_color(cx.theme().foreground),
+                            )
+                    })),
+            )
+    }
+}
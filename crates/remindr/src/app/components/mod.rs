@@ -1,10 +1,34 @@
+pub mod attachment_cleanup_dialog;
+pub mod block_history_dialog;
+pub mod block_toolbar;
 pub mod code_window;
+pub mod command_palette;
 pub mod confirm_dialog;
+pub mod conflict_dialog;
+pub mod deleted_blocks_panel;
+pub mod demo_document;
+pub mod document_preview;
+pub mod focus_ring;
+pub mod import_dialog;
+pub mod integrity_dialog;
+pub mod merge_dialog;
+pub mod nav_inspector;
 pub mod node_config_menu;
 pub mod node_renderer;
 pub mod nodes;
+pub mod notification_center;
+pub mod progress_panel;
+pub mod quick_capture;
+pub mod quick_switcher;
+pub mod replace_dialog;
+pub mod reminder_dialog;
 pub mod rich_text;
 pub mod settings_dialog;
 pub mod sidebar;
 pub mod slash_menu;
+pub mod smart_folder_dialog;
+pub mod tag_dialog;
+pub mod template_picker;
 pub mod title_bar;
+pub mod unlock_window;
+pub mod vault_git;
@@ -0,0 +1,135 @@
+use gpui::{
+    App, Entity, IntoElement, KeyBinding, ParentElement, RenderOnce, SharedString, Styled, Window,
+    actions, div, prelude::FluentBuilder, px,
+};
+use gpui_component::{ActiveTheme, StyledExt, h_flex, v_flex};
+use gpui_router::RouterState;
+
+use crate::app::states::app_state::AppState;
+
+actions!(nav_inspector, [ToggleNavInspector]);
+
+pub fn init(cx: &mut App) {
+    cx.bind_keys([KeyBinding::new(
+        "secondary-alt-shift-n",
+        ToggleNavInspector,
+        None,
+    )]);
+}
+
+/// Developer overlay listing the navigation stack of a window, the screens presented
+/// over it and the state of the router, toggled with `secondary-alt-shift-n`.
+#[derive(IntoElement)]
+pub struct NavInspector {
+    app_state: Entity<AppState>,
+}
+
+impl NavInspector {
+    pub fn new(app_state: Entity<AppState>) -> Self {
+        Self { app_state }
+    }
+}
+
+impl RenderOnce for NavInspector {
+    fn render(self, _: &mut Window, cx: &mut App) -> impl IntoElement {
+        let muted_fg = cx.theme().muted_foreground;
+        let navigator = &self.app_state.read(cx).navigator;
+        let depth = navigator.len();
+        let screens = navigator
+            .entries()
+            .enumerate()
+            .map(|(ix, (id, entity_id))| {
+                h_flex()
+                    .gap_2()
+                    .when(ix + 1 == depth, |this| this.font_semibold())
+                    .child(div().w(px(16.)).text_color(muted_fg).child(ix.to_string()))
+                    .child(div().flex_1().child(id))
+                    .child(
+                        div()
+                            .text_color(muted_fg)
+                            .child(format!("entity {}", entity_id)),
+                    )
+            })
+            .collect::<Vec<_>>();
+        let modals = navigator.modal_history().to_vec();
+
+        let (pathname, pattern, mut params) = match cx.try_global::<RouterState>() {
+            Some(router) => (
+                router.location.pathname.clone(),
+                router
+                    .path_match
+                    .as_ref()
+                    .map(|path_match| path_match.pattern.clone()),
+                router
+                    .params
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect::<Vec<_>>(),
+            ),
+            None => (SharedString::from("-"), None, Vec::new()),
+        };
+        params.sort();
+
+        v_flex()
+            .absolute()
+            .bottom_4()
+            .left_4()
+            .w(px(320.))
+            .p_3()
+            .gap_2()
+            .rounded_lg()
+            .border_1()
+            .border_color(cx.theme().border)
+            .bg(cx.theme().popover)
+            .shadow_lg()
+            .text_xs()
+            .font_family("monospace")
+            .child(
+                h_flex()
+                    .justify_between()
+                    .font_semibold()
+                    .child("Navigation")
+                    .child(
+                        div()
+                            .text_color(muted_fg)
+                            .child(format!("{} screen(s)", depth)),
+                    ),
+            )
+            .child(v_flex().gap_0p5().children(screens))
+            .when(!modals.is_empty(), |this| {
+                this.child(
+                    v_flex()
+                        .gap_0p5()
+                        .pt_2()
+                        .border_t_1()
+                        .border_color(cx.theme().border)
+                        .child(div().font_semibold().child("Presented"))
+                        .children(modals.into_iter().enumerate().map(|(ix, id)| {
+                            h_flex()
+                                .gap_2()
+                                .child(div().w(px(16.)).text_color(muted_fg).child(ix.to_string()))
+                                .child(id)
+                        })),
+                )
+            })
+            .child(
+                v_flex()
+                    .gap_0p5()
+                    .pt_2()
+                    .border_t_1()
+                    .border_color(cx.theme().border)
+                    .child(div().font_semibold().child("Router"))
+                    .child(format!("location  {}", pathname))
+                    .child(format!(
+                        "pattern   {}",
+                        pattern.unwrap_or_else(|| "-".into())
+                    ))
+                    .when(params.is_empty(), |this| this.child("params    -"))
+                    .children(
+                        params
+                            .into_iter()
+                            .map(|(key, value)| format!("params    {} = {}", key, value)),
+                    ),
+            )
+    }
+}
@@ -2,14 +2,20 @@ use gpui::prelude::FluentBuilder;
 use gpui::{
     App, AppContext, Context, Corner, EmptyView, Entity, FocusHandle, Focusable, Hsla,
     InteractiveElement, IntoElement, KeyDownEvent, MouseButton, ParentElement, Render, RenderOnce,
-    StatefulInteractiveElement, Styled, Window, div, px,
+    SharedString, StatefulInteractiveElement, Styled, Window, div, px,
+};
+use gpui_component::{
+    ActiveTheme, Icon, Selectable, label::Label, popover::Popover, tooltip::Tooltip,
 };
-use gpui_component::{ActiveTheme, Icon, Selectable, label::Label, popover::Popover};
 use uuid::Uuid;
 
 use crate::app::{
-    components::{node_renderer::DraggableInfo, nodes::menu_provider::NodeMenuItem},
-    states::node_state::NodeState,
+    components::{
+        block_history_dialog::show_block_history, node_renderer::DraggableInfo,
+        nodes::menu_provider::NodeMenuItem,
+    },
+    date_format::DateFormat,
+    states::node_state::{BlockTimes, NodeState},
 };
 
 const DESTRUCTIVE_COLOR: Hsla = Hsla {
@@ -25,6 +31,9 @@ pub struct NodeConfigMenu {
     pub dragged_info: DraggableInfo,
     pub open: bool,
     pub focus_handle: FocusHandle,
+    /// Item picked by Enter, moved with the arrow keys. The history and delete items come
+    /// last.
+    highlighted: Option<usize>,
 }
 
 impl NodeConfigMenu {
@@ -37,11 +46,19 @@ impl NodeConfigMenu {
             open: false,
             dragged_info,
             focus_handle: cx.focus_handle(),
+            highlighted: None,
         }
     }
 
+    /// Opens the menu with its first item highlighted.
+    pub fn open_with_keyboard(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.set_open(true, window, cx);
+        self.highlighted = Some(0);
+    }
+
     fn set_open(&mut self, open: bool, window: &mut Window, cx: &mut Context<Self>) {
         self.open = open;
+        self.highlighted = None;
         if open {
             let focus_handle = self.focus_handle.clone();
             self.state.update(cx, |_, cx| {
@@ -51,14 +68,80 @@ impl NodeConfigMenu {
         cx.notify();
     }
 
+    fn menu_items(&self, cx: &App) -> Vec<NodeMenuItem> {
+        self.state
+            .read(cx)
+            .get_current_nodes(self.related_id)
+            .map(|node| node.element.menu_items(cx))
+            .unwrap_or_default()
+    }
+
+    fn on_key_down(&mut self, event: &KeyDownEvent, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.open {
+            return;
+        }
+
+        let items = self.menu_items(cx);
+        // The history and delete items follow the node items
+        let count = items.len() + 2;
+        let highlighted = self.highlighted;
+
+        match event.keystroke.key.as_str() {
+            "up" => {
+                self.highlighted = Some(highlighted.map_or(count - 1, |i| (i + count - 1) % count));
+            }
+            "down" => {
+                self.highlighted = Some(highlighted.map_or(0, |i| (i + 1) % count));
+            }
+            "enter" => {
+                let Some(index) = highlighted else {
+                    return;
+                };
+                match items.get(index) {
+                    Some(item) => {
+                        (item.action)(&self.state, window, cx);
+                        self.set_open(false, window, cx);
+                    }
+                    None if index == items.len() => self.show_history(window, cx),
+                    None => self.delete_node(window, cx),
+                }
+            }
+            "escape" => {
+                self.set_open(false, window, cx);
+                self.focus_block(window, cx);
+            }
+            _ => return,
+        }
+
+        cx.stop_propagation();
+        cx.notify();
+    }
+
+    /// Gives the focus back to the block the menu belongs to.
+    fn focus_block(&self, window: &mut Window, cx: &mut Context<Self>) {
+        let element = self
+            .state
+            .read(cx)
+            .get_current_nodes(self.related_id)
+            .map(|node| node.element.clone());
+        if let Some(element) = element {
+            element.focus_end(window, cx);
+        }
+    }
+
     fn delete_node(&mut self, _window: &mut Window, cx: &mut Context<Self>) {
-        self.state.update(cx, |state, _| {
-            state.remove_node(self.related_id);
+        self.state.update(cx, |state, cx| {
+            state.remove_node(self.related_id, cx);
         });
         self.open = false;
         cx.notify();
     }
 
+    fn show_history(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.set_open(false, window, cx);
+        show_block_history(self.related_id, self.state.clone(), window, cx);
+    }
+
     fn render_section_label(
         &self,
         label: &'static str,
@@ -71,7 +154,36 @@ impl NodeConfigMenu {
         )
     }
 
-    fn render_delete_item(&self, cx: &mut Context<Self>) -> impl IntoElement {
+    fn render_history_item(&self, highlighted: bool, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .id("node-history")
+            .flex()
+            .items_center()
+            .gap_2()
+            .w_full()
+            .px_2()
+            .py_0p5()
+            .rounded_md()
+            .cursor_pointer()
+            .hover(|this| this.bg(cx.theme().accent.opacity(0.5)))
+            .when(highlighted, |this| this.bg(cx.theme().accent.opacity(0.5)))
+            .on_click(cx.listener(|this, _, window, cx| {
+                this.show_history(window, cx);
+            }))
+            .child(
+                Icon::default()
+                    .path("icons/file-diff.svg")
+                    .size_4()
+                    .text_color(cx.theme().foreground),
+            )
+            .child(
+                Label::new("History")
+                    .text_sm()
+                    .text_color(cx.theme().foreground),
+            )
+    }
+
+    fn render_delete_item(&self, highlighted: bool, cx: &mut Context<Self>) -> impl IntoElement {
         div()
             .id("delete-node")
             .flex()
@@ -83,6 +195,7 @@ impl NodeConfigMenu {
             .rounded_md()
             .cursor_pointer()
             .hover(|this| this.bg(DESTRUCTIVE_COLOR.opacity(0.15)))
+            .when(highlighted, |this| this.bg(DESTRUCTIVE_COLOR.opacity(0.15)))
             .on_click(cx.listener(|this, _, window, cx| {
                 this.delete_node(window, cx);
             }))
@@ -96,6 +209,25 @@ impl NodeConfigMenu {
     }
 }
 
+/// Describes when a block was created and last edited, e.g. "Created 3 days ago, edited 2
+/// hours ago".
+fn times_label(times: BlockTimes, format: &DateFormat) -> Option<String> {
+    let created = times
+        .created_at
+        .map(|created_at| format.relative(created_at));
+    let edited = times
+        .updated_at
+        .filter(|updated_at| Some(*updated_at) != times.created_at)
+        .map(|updated_at| format.relative(updated_at));
+
+    match (created, edited) {
+        (Some(created), Some(edited)) => Some(format!("Created {}, edited {}", created, edited)),
+        (Some(created), None) => Some(format!("Created {}", created)),
+        (None, Some(edited)) => Some(format!("Edited {}", edited)),
+        (None, None) => None,
+    }
+}
+
 impl Focusable for NodeConfigMenu {
     fn focus_handle(&self, _: &App) -> FocusHandle {
         self.focus_handle.clone()
@@ -104,21 +236,32 @@ impl Focusable for NodeConfigMenu {
 
 impl Render for NodeConfigMenu {
     fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        let node_menu_items: Vec<NodeMenuItem> = self
-            .state
-            .read(cx)
-            .get_current_nodes(self.related_id)
-            .map(|node| node.element.menu_items(cx))
-            .unwrap_or_default();
+        let node_menu_items = self.menu_items(cx);
+        let item_count = node_menu_items.len();
 
         let has_node_items = !node_menu_items.is_empty();
+        let times_label = self
+            .state
+            .read(cx)
+            .block_times(self.related_id)
+            .and_then(|times| times_label(times, &DateFormat::new(cx)));
         let is_dragging = self.state.read(cx).is_dragging;
+        let kind = self
+            .state
+            .read(cx)
+            .get_current_nodes(self.related_id)
+            .map(|node| node.element.label())
+            .unwrap_or("Block");
+        let handle_label =
+            SharedString::from(format!("{kind} block: drag to move, click for options"));
 
         let rendered_items: Vec<NodeMenuItemElement> = node_menu_items
             .into_iter()
-            .map(|item| NodeMenuItemElement {
+            .enumerate()
+            .map(|(index, item)| NodeMenuItemElement {
                 item,
                 state: self.state.clone(),
+                highlighted: self.highlighted == Some(index),
             })
             .collect();
 
@@ -136,6 +279,9 @@ impl Render for NodeConfigMenu {
                     .text_color(cx.theme().accent_foreground.opacity(0.5)),
             )
             .when(is_dragging, |this| this.cursor_move())
+            .when(!is_dragging && !self.open, |this| {
+                this.tooltip(move |window, cx| Tooltip::new(handle_label.clone()).build(window, cx))
+            })
             .on_drag(self.dragged_info.clone(), {
                 let state = self.state.clone();
                 move |element, _, _window: &mut Window, cx: &mut App| {
@@ -152,13 +298,7 @@ impl Render for NodeConfigMenu {
         div()
             .relative()
             .track_focus(&self.focus_handle)
-            .on_key_down(cx.listener(|this, event: &KeyDownEvent, _, cx| {
-                if event.keystroke.key.as_str() == "escape" && this.open {
-                    this.open = false;
-                    cx.notify();
-                    cx.stop_propagation();
-                }
-            }))
+            .on_key_down(cx.listener(Self::on_key_down))
             .child(drag_button)
             .child(
                 Popover::new("contextual-node-popover")
@@ -184,7 +324,30 @@ impl Render for NodeConfigMenu {
                                     .children(rendered_items)
                             })
                             .child(self.render_section_label("Actions", cx))
-                            .child(self.render_delete_item(cx)),
+                            .child(
+                                self.render_history_item(self.highlighted == Some(item_count), cx),
+                            )
+                            .child(
+                                self.render_delete_item(
+                                    self.highlighted == Some(item_count + 1),
+                                    cx,
+                                ),
+                            )
+                            .when_some(times_label, |el, label| {
+                                el.child(
+                                    div()
+                                        .mt_1()
+                                        .px_2()
+                                        .pt_1()
+                                        .border_t_1()
+                                        .border_color(cx.theme().border)
+                                        .child(
+                                            Label::new(label)
+                                                .text_xs()
+                                                .text_color(cx.theme().muted_foreground),
+                                        ),
+                                )
+                            }),
                     ),
             )
     }
@@ -216,6 +379,7 @@ impl RenderOnce for Empty {
 struct NodeMenuItemElement {
     item: NodeMenuItem,
     state: Entity<NodeState>,
+    highlighted: bool,
 }
 
 impl RenderOnce for NodeMenuItemElement {
@@ -234,6 +398,9 @@ impl RenderOnce for NodeMenuItemElement {
             .rounded_md()
             .cursor_pointer()
             .hover(|this| this.bg(cx.theme().accent.opacity(0.5)))
+            .when(self.highlighted, |this| {
+                this.bg(cx.theme().accent.opacity(0.5))
+            })
             .on_mouse_down(MouseButton::Left, move |_, window, cx| {
                 (action)(&state, window, cx);
             })
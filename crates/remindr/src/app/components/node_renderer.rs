@@ -1,28 +1,84 @@
+use std::collections::HashSet;
+
 use gpui::prelude::FluentBuilder;
 use gpui::{
-    App, AppContext, ClickEvent, Context, DragMoveEvent, Entity, InteractiveElement, IntoElement,
-    ParentElement, Render, SharedString, StatefulInteractiveElement, Styled, Window, div, px,
+    App, AppContext, BorrowAppContext, ClickEvent, Context, DragMoveEvent, Entity, ExternalPaths,
+    InteractiveElement, IntoElement, KeyBinding, KeyDownEvent, MouseButton, ParentElement, Render,
+    SharedString, StatefulInteractiveElement, Styled, Subscription, Window, WindowId, actions, div,
+    px,
 };
-use gpui_component::{ActiveTheme, Icon, IconName};
+use gpui_component::{ActiveTheme, Icon, IconName, WindowExt, tooltip::Tooltip};
 use serde_json::Value;
 use uuid::Uuid;
 
-use crate::app::{
-    components::{
-        node_config_menu::NodeConfigMenu,
-        nodes::{
-            element::{NodePayload, RemindrElement},
-            text::data::TextMetadata,
+use crate::{
+    app::{
+        components::{
+            block_toolbar::BlockToolbar,
+            node_config_menu::NodeConfigMenu,
+            nodes::{
+                attachment::data::AttachmentMetadata,
+                element::{NodePayload, RemindrElement},
+                heading::data::HeadingMetadata,
+                link::data::LinkMetadata,
+                list::data::ListMetadata,
+                text::data::TextMetadata,
+                todo::data::TodoMetadata,
+            },
+            rich_text::SelectAll,
+            slash_menu::{SlashMenu, SlashMenuMode},
+        },
+        states::{
+            document_state::DocumentState,
+            error_state::{AppError, ErrorKind},
+            node_state::{DocumentEvent, MovingElement, NodeState},
+            repository_state::RepositoryState,
+            settings_state::{FirstBlockType, Settings},
         },
-        slash_menu::{SlashMenu, SlashMenuMode},
     },
-    states::node_state::{MovingElement, NodeState},
+    domain::database::document::DocumentModel,
 };
 
+actions!(
+    node_renderer,
+    [
+        MoveBlockUp,
+        MoveBlockDown,
+        DuplicateBlockUp,
+        DuplicateBlockDown,
+        ExtractBlocks,
+        OpenBlockMenu,
+        OpenInsertMenu
+    ]
+);
+
+const CONTEXT: &str = "Block";
+
+pub fn init(cx: &mut App) {
+    cx.bind_keys([
+        KeyBinding::new("alt-up", MoveBlockUp, Some(CONTEXT)),
+        KeyBinding::new("alt-down", MoveBlockDown, Some(CONTEXT)),
+        KeyBinding::new("alt-shift-up", DuplicateBlockUp, Some(CONTEXT)),
+        KeyBinding::new("alt-shift-down", DuplicateBlockDown, Some(CONTEXT)),
+        KeyBinding::new("secondary-alt-e", ExtractBlocks, Some(CONTEXT)),
+        // Menus of the block gutter, otherwise only reachable with the mouse
+        KeyBinding::new("secondary-.", OpenBlockMenu, Some(CONTEXT)),
+        KeyBinding::new("secondary-/", OpenInsertMenu, Some(CONTEXT)),
+        // Blocks without text select themselves right away
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-a", SelectAll, Some(CONTEXT)),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-a", SelectAll, Some(CONTEXT)),
+    ]);
+}
+
 pub struct NodeRenderer {
     pub state: Entity<NodeState>,
     insert_menu: Entity<SlashMenu>,
     config_menus: Vec<Entity<NodeConfigMenu>>,
+    /// Window showing the document, whose opened copy is the one saved.
+    window_id: WindowId,
+    _document_events: Subscription,
 }
 
 #[derive(Clone)]
@@ -31,25 +87,57 @@ pub struct DraggableInfo {
 }
 
 impl NodeRenderer {
-    pub fn new(nodes: Vec<Value>, window: &mut Window, cx: &mut App) -> Self {
+    pub fn new(nodes: Vec<Value>, window: &mut Window, cx: &mut Context<Self>) -> Self {
         let state = cx.new(|_| NodeState::default());
+        let first_block = match cx.try_global::<Settings>() {
+            Some(settings) if nodes.is_empty() => {
+                first_block_payload(settings.editor.first_block())
+            }
+            _ => None,
+        };
 
         state.update(cx, |this, cx| {
             for value in nodes.into_iter() {
                 let node = this.parse_node(&value, &state, window, cx);
+                let data = node.element.get_data(cx);
+                this.record_times(node.id, &value, data);
                 this.push_node(&node);
             }
+
+            // Only saved once edited, like any other new block
+            if let Some(payload) = first_block {
+                this.push_node(&RemindrElement::create_node(payload, &state, window, cx));
+            }
         });
 
         let insert_menu = cx.new(|cx| {
             SlashMenu::new(Uuid::nil(), &state, window, cx).with_mode(SlashMenuMode::InsertAfter)
         });
+        let document_events = cx.subscribe(&state, Self::on_document_event);
 
         Self {
             state,
             insert_menu,
             config_menus: Vec::new(),
+            window_id: window.window_handle().window_id(),
+            _document_events: document_events,
+        }
+    }
+
+    /// Saves the document once its blocks change, whichever window is current by then.
+    fn on_document_event(
+        &mut self,
+        state: Entity<NodeState>,
+        _: &DocumentEvent,
+        cx: &mut Context<Self>,
+    ) {
+        let window_id = self.window_id;
+        if let Some(uid) = state.read(cx).document_id {
+            cx.update_global::<DocumentState, _>(|documents, cx| {
+                documents.mark_document_changed(uid, window_id, cx);
+            });
         }
+        cx.notify();
     }
 
     fn get_or_create_config_menu(
@@ -78,21 +166,149 @@ impl NodeRenderer {
     }
 
     fn on_drop(this: &mut Self, node_id: Uuid, direction: MovingElement, cx: &mut Context<Self>) {
-        this.state.update(cx, |state, _| {
+        this.state.update(cx, |state, cx| {
             if let Some(dragging_id) = state.dragging_id {
                 let elements = state.get_nodes();
                 let from_index = elements.iter().position(|e| e.id == dragging_id).unwrap();
 
                 let target_index = elements.iter().position(|e| e.id == node_id).unwrap();
-                state.drop_element_by_index(from_index, target_index, direction);
+                state.drop_element_by_index(from_index, target_index, direction, cx);
             }
         });
     }
 
-    fn on_drag_move(
+    /// Moves the block holding the focus, which keeps it since its node is not rebuilt.
+    fn move_block(&mut self, node_id: Uuid, up: bool, cx: &mut Context<Self>) {
+        self.state
+            .update(cx, |state, cx| state.move_node(node_id, up, cx));
+    }
+
+    fn duplicate_block(
+        &mut self,
+        node_id: Uuid,
+        below: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let state = self.state.clone();
+        self.state.update(cx, |this, cx| {
+            this.duplicate_node(node_id, below, &state, window, cx);
+        });
+    }
+
+    /// Moves the selected blocks, or this one when it isn't selected, into a new document
+    /// of the same folder, leaves a link to it in their place and opens it.
+    fn extract_blocks(&mut self, node_id: Uuid, window: &mut Window, cx: &mut Context<Self>) {
+        let nodes = self.state.read(cx).blocks_with(node_id);
+        if nodes.is_empty() {
+            return;
+        }
+
+        let ids = nodes.iter().map(|node| node.id).collect::<HashSet<_>>();
+        let blocks = nodes
+            .iter()
+            .map(|node| node.element.get_data(cx))
+            .collect::<Vec<_>>();
+        let title = extracted_title(&blocks);
+        let window_handle = window.window_handle();
+        let window_id = window_handle.window_id();
+        let folder_id = cx
+            .global::<DocumentState>()
+            .workspace(window_id)
+            .and_then(|workspace| workspace.get_current_document())
+            .and_then(|document| document.folder_id);
+        let repository = cx.global::<RepositoryState>().documents.clone();
+        let state = self.state.clone();
+
+        cx.spawn(async move |_, cx| {
+            let document = DocumentModel {
+                id: 0,
+                title: title.clone(),
+                content: Value::Array(blocks),
+                folder_id,
+            };
+            let result = repository.insert_document(document).await;
+
+            cx.update_window(window_handle, |_, window, cx| {
+                let id = match result {
+                    Ok(id) => id,
+                    Err(e) => {
+                        AppError::new(
+                            ErrorKind::Storage,
+                            "Couldn't extract the blocks to a new document",
+                            &e,
+                        )
+                        .report();
+                        return;
+                    }
+                };
+
+                let link = RemindrElement::create_node(
+                    NodePayload::Link(LinkMetadata {
+                        document_id: id,
+                        title: title.clone(),
+                        heading: None,
+                    }),
+                    &state,
+                    window,
+                    cx,
+                );
+                // The source is saved through the events of its blocks
+                state.update(cx, |state, cx| state.replace_nodes(&ids, &link, cx));
+
+                cx.update_global::<DocumentState, _>(|state, _| {
+                    state.mark_recent(id);
+                    state
+                        .workspace_mut(window_id)
+                        .open_document_in_folder(id, title, folder_id);
+                });
+            })?;
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
+    /// Select all, after the text of the block was selected: selects the block, then every
+    /// block of the document.
+    fn select_blocks(&mut self, node_id: Uuid, cx: &mut Context<Self>) {
+        self.state
+            .update(cx, |state, _| state.escalate_selection(node_id));
+        cx.notify();
+    }
+
+    /// While blocks are selected, deletes them on backspace and unselects them on any
+    /// other key, which then goes to the focused block as usual.
+    fn on_key_down(&mut self, event: &KeyDownEvent, _: &mut Window, cx: &mut Context<Self>) {
+        if !self.state.read(cx).has_selection() {
+            return;
+        }
+
+        let keystroke = &event.keystroke;
+        match keystroke.key.as_str() {
+            "backspace" | "delete" => {
+                self.state.update(cx, |state, cx| state.remove_selected(cx));
+                cx.stop_propagation();
+            }
+            "escape" => {
+                self.state.update(cx, |state, _| state.clear_selection());
+                cx.stop_propagation();
+                cx.notify();
+            }
+            "a" if keystroke.modifiers.secondary() => {}
+            "e" if keystroke.modifiers.secondary() && keystroke.modifiers.alt => {}
+            _ => {
+                self.state.update(cx, |state, _| state.clear_selection());
+                cx.notify();
+            }
+        }
+    }
+
+    /// Shows where a block, or files dragged from the file manager, would be dropped.
+    fn on_drag_move<T: 'static>(
         node_id: Uuid,
         this: &mut Self,
-        event: &DragMoveEvent<DraggableInfo>,
+        event: &DragMoveEvent<T>,
         cx: &mut Context<Self>,
     ) {
         this.state.update(cx, |state, _| {
@@ -119,6 +335,108 @@ impl NodeRenderer {
         });
     }
 
+    /// Stores files dropped from the file manager on a block as attachment blocks, placed
+    /// above or below it like a dragged block would be.
+    fn on_drop_files(
+        &mut self,
+        node_id: Uuid,
+        paths: &ExternalPaths,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let below = self.state.update(cx, |state, _| {
+            let zone = state
+                .hovered_drop_zone
+                .clone()
+                .filter(|(id, _)| *id == node_id);
+            state.stop_drag();
+            !matches!(zone, Some((_, MovingElement::After)))
+        });
+        let paths = paths
+            .paths()
+            .iter()
+            .filter(|path| path.is_file())
+            .cloned()
+            .collect::<Vec<_>>();
+        if paths.is_empty() {
+            return;
+        }
+
+        let repository = cx.global::<RepositoryState>().attachments.clone();
+        let state = self.state.clone();
+        let window_handle = window.window_handle();
+
+        cx.spawn(async move |_, cx| {
+            let mut attachments = Vec::new();
+            let mut failed = 0;
+            for path in paths {
+                let file_name = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                let result = async {
+                    let data = cx
+                        .background_executor()
+                        .spawn(async move { std::fs::read(&path) })
+                        .await?;
+                    repository.insert_attachment(file_name, data).await
+                }
+                .await;
+
+                match result {
+                    Ok(attachment) => attachments.push(attachment),
+                    Err(e) => {
+                        tracing::error!("failed to store a dropped file: {:?}", e);
+                        failed += 1;
+                    }
+                }
+            }
+
+            cx.update_window(window_handle, |_, window, cx| {
+                if failed > 0 {
+                    window.push_notification(
+                        format!("Failed to attach {} of the dropped files", failed),
+                        cx,
+                    );
+                }
+                if attachments.is_empty() {
+                    return;
+                }
+
+                state.update(cx, |node_state, cx| {
+                    let Some(index) = node_state
+                        .get_nodes()
+                        .iter()
+                        .position(|node| node.id == node_id)
+                    else {
+                        return;
+                    };
+
+                    let index = if below { index + 1 } else { index };
+                    for (offset, attachment) in attachments.into_iter().enumerate() {
+                        let node = RemindrElement::create_node(
+                            NodePayload::Attachment((
+                                AttachmentMetadata {
+                                    attachment_id: Some(attachment.id),
+                                    file_name: attachment.file_name,
+                                    size: attachment.size,
+                                },
+                                false,
+                            )),
+                            &state,
+                            window,
+                            cx,
+                        );
+                        node_state.insert_node_at(index + offset, &node, cx);
+                    }
+                });
+            })?;
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
     fn on_create_text_zone(
         this: &mut Self,
         _: &ClickEvent,
@@ -147,22 +465,88 @@ impl Render for NodeRenderer {
             let state = self.state.read(cx);
             (state.is_dragging, state.hovered_drop_zone.clone())
         };
+        let show_toolbar = cx.global::<Settings>().editor.hover_toolbar && !is_dragging;
 
         let children = nodes.into_iter().map(|node| {
+            let toolbar = show_toolbar.then(|| {
+                let pinned = BlockToolbar::has_selection(&node, cx);
+
+                div()
+                    .absolute()
+                    .top(px(-16.))
+                    .right_0()
+                    .when(!pinned, |this| {
+                        this.invisible()
+                            .group_hover("drag_element", |this| this.visible())
+                    })
+                    .child(BlockToolbar::new(node.clone(), self.state.clone()))
+            });
+
+            let node_id = node.id;
+            let is_selected = self.state.read(cx).is_selected(node_id);
+            // Kept visible while one of its menus is open, as it may have been opened with
+            // the keyboard
+            let gutter_pinned = self.get_or_create_config_menu(node_id, cx).read(cx).open || {
+                let insert_menu = self.insert_menu.read(cx);
+                insert_menu.open && insert_menu.related_id() == node_id
+            };
+
             div()
                 .group("drag_element")
+                .key_context(CONTEXT)
+                .on_action(cx.listener(move |this, _: &MoveBlockUp, _, cx| {
+                    this.move_block(node_id, true, cx);
+                }))
+                .on_action(cx.listener(move |this, _: &MoveBlockDown, _, cx| {
+                    this.move_block(node_id, false, cx);
+                }))
+                .on_action(cx.listener(move |this, _: &DuplicateBlockUp, window, cx| {
+                    this.duplicate_block(node_id, false, window, cx);
+                }))
+                .on_action(
+                    cx.listener(move |this, _: &DuplicateBlockDown, window, cx| {
+                        this.duplicate_block(node_id, true, window, cx);
+                    }),
+                )
+                .on_action(cx.listener(move |this, _: &ExtractBlocks, window, cx| {
+                    this.extract_blocks(node_id, window, cx);
+                }))
+                .on_action(cx.listener(move |this, _: &SelectAll, _, cx| {
+                    this.select_blocks(node_id, cx);
+                }))
+                .on_action(cx.listener(move |this, _: &OpenBlockMenu, window, cx| {
+                    let menu = this.get_or_create_config_menu(node_id, cx);
+                    menu.update(cx, |menu, cx| menu.open_with_keyboard(window, cx));
+                    cx.notify();
+                }))
+                .on_action(cx.listener(move |this, _: &OpenInsertMenu, window, cx| {
+                    this.open_insert_menu(node_id, window, cx);
+                    cx.notify();
+                }))
                 .on_drag_move(cx.listener(
                     move |this: &mut Self, event: &DragMoveEvent<DraggableInfo>, _, cx| {
                         Self::on_drag_move(node.id, this, event, cx);
                     },
                 ))
+                .on_drag_move(cx.listener(
+                    move |this: &mut Self, event: &DragMoveEvent<ExternalPaths>, _, cx| {
+                        Self::on_drag_move(node.id, this, event, cx);
+                    },
+                ))
+                .on_drop(
+                    cx.listener(move |this: &mut Self, paths: &ExternalPaths, window, cx| {
+                        this.on_drop_files(node_id, paths, window, cx);
+                    }),
+                )
                 .relative()
                 .flex()
                 .items_start()
                 .child(
                     div()
-                        .invisible()
-                        .group_hover("drag_element", |this| this.visible())
+                        .when(!gutter_pinned, |this| {
+                            this.invisible()
+                                .group_hover("drag_element", |this| this.visible())
+                        })
                         .absolute()
                         .left_0()
                         .top_3()
@@ -187,6 +571,11 @@ impl Render for NodeRenderer {
                                         .size_5()
                                         .text_color(cx.theme().accent_foreground.opacity(0.5)),
                                 )
+                                .when(!show_menu_here, |this| {
+                                    this.tooltip(|window, cx| {
+                                        Tooltip::new("Insert a block below").build(window, cx)
+                                    })
+                                })
                                 .on_click(cx.listener({
                                     let node_id = node.id;
                                     move |this, _, window, cx| {
@@ -202,7 +591,12 @@ impl Render for NodeRenderer {
                         .relative()
                         .ml_12()
                         .w_full()
+                        .when(is_selected, |this| {
+                            this.rounded(cx.theme().radius)
+                                .bg(cx.theme().accent.opacity(0.4))
+                        })
                         .child(node.element.clone())
+                        .children(toolbar)
                         .tab_index(0)
                         .when_some(
                             match hovered_drop_zone {
@@ -259,14 +653,58 @@ impl Render for NodeRenderer {
                 })
         });
 
-        div().w_full().children(children).child(
-            div()
-                .id("add_element")
-                .cursor_pointer()
-                .ml_12()
-                .h_20()
-                .w_full()
-                .on_click(cx.listener(Self::on_create_text_zone)),
-        )
+        div()
+            .w_full()
+            .capture_key_down(cx.listener(Self::on_key_down))
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|this, _, _, cx| {
+                    if this.state.read(cx).has_selection() {
+                        this.state.update(cx, |state, _| state.clear_selection());
+                        cx.notify();
+                    }
+                }),
+            )
+            .children(children)
+            .child(
+                div()
+                    .id("add_element")
+                    .cursor_pointer()
+                    .ml_12()
+                    .h_20()
+                    .w_full()
+                    .on_click(cx.listener(Self::on_create_text_zone)),
+            )
     }
 }
+
+/// Block an empty document starts with, unfocused so the title can be typed first.
+fn first_block_payload(block: FirstBlockType) -> Option<NodePayload> {
+    let payload = match block {
+        FirstBlockType::None => return None,
+        FirstBlockType::Text => NodePayload::Text((TextMetadata::default(), false)),
+        FirstBlockType::Heading => NodePayload::Heading((
+            HeadingMetadata {
+                level: 2,
+                ..Default::default()
+            },
+            false,
+        )),
+        FirstBlockType::Todo => NodePayload::Todo((TodoMetadata::default(), false)),
+        FirstBlockType::List => NodePayload::List((ListMetadata::default(), false)),
+    };
+
+    Some(payload)
+}
+
+/// Title of a document made of extracted blocks: the first line of the first block with
+/// text.
+fn extracted_title(blocks: &[Value]) -> String {
+    blocks
+        .iter()
+        .filter_map(|block| block.get("metadata")?.get("content")?.as_str())
+        .filter_map(|content| content.lines().map(str::trim).find(|line| !line.is_empty()))
+        .next()
+        .map(|line| line.chars().take(80).collect())
+        .unwrap_or_else(|| "Untitled".to_string())
+}
@@ -0,0 +1,248 @@
+use std::path::Path;
+
+use anyhow::Error;
+use gpui::{
+    App, Context, Entity, InteractiveElement, IntoElement, ParentElement, PathPromptOptions,
+    Render, StatefulInteractiveElement, Styled, Window, div, px,
+};
+use gpui_component::{
+    ActiveTheme, Icon, Sizable, WindowExt,
+    button::{Button, ButtonVariants},
+    h_flex,
+    label::Label,
+    v_flex,
+};
+use serde_json::{Value, from_value};
+
+use crate::app::{
+    components::nodes::{
+        attachment::data::{AttachmentMetadata, AttachmentNodeData},
+        menu_provider::{NodeMenuItem, NodeMenuProvider},
+    },
+    states::{
+        node_state::NodeState, repository_state::RepositoryState, settings_state::EditorSettings,
+    },
+};
+
+pub struct AttachmentNode {
+    pub state: Entity<NodeState>,
+    pub data: AttachmentNodeData,
+    /// Set while the file picker is shown or the picked file is being stored.
+    picking: bool,
+}
+
+impl AttachmentNode {
+    pub fn parse(
+        data: &Value,
+        state: &Entity<NodeState>,
+        _: &mut Window,
+        _: &mut Context<Self>,
+    ) -> Result<Self, Error> {
+        let data = from_value::<AttachmentNodeData>(data.clone())?;
+
+        Ok(Self {
+            state: state.clone(),
+            data,
+            picking: false,
+        })
+    }
+
+    /// Asks for a file and stores it as the attachment of this block.
+    pub fn pick_file(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.picking {
+            return;
+        }
+        self.picking = true;
+        cx.notify();
+
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: Some("Attach".into()),
+        });
+        let repository = cx.global::<RepositoryState>().attachments.clone();
+        let window_handle = window.window_handle();
+
+        cx.spawn(async move |this, cx| {
+            let path = match paths.await {
+                Ok(Ok(Some(paths))) => paths.into_iter().next(),
+                _ => None,
+            };
+            let Some(path) = path else {
+                this.update(cx, |this, cx| {
+                    this.picking = false;
+                    cx.notify();
+                })?;
+                return Ok(());
+            };
+
+            let file_name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let result = async {
+                let data = cx
+                    .background_executor()
+                    .spawn(async move { std::fs::read(&path) })
+                    .await?;
+                repository.insert_attachment(file_name, data).await
+            }
+            .await;
+
+            cx.update_window(window_handle, |_, window, cx| {
+                let attachment = match result {
+                    Ok(attachment) => Some(attachment),
+                    Err(e) => {
+                        tracing::error!("failed to store attachment: {:?}", e);
+                        window.push_notification("Failed to attach the file", cx);
+                        None
+                    }
+                };
+
+                let _ = this.update(cx, |this, cx| {
+                    this.picking = false;
+                    if let Some(attachment) = attachment {
+                        this.data.metadata = AttachmentMetadata {
+                            attachment_id: Some(attachment.id),
+                            file_name: attachment.file_name,
+                            size: attachment.size,
+                        };
+                        let node_id = this.data.id;
+                        this.state
+                            .update(cx, |state, cx| state.node_changed(node_id, cx));
+                    }
+                    cx.notify();
+                });
+            })?;
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
+    /// Opens the attached file with the default application of the system.
+    fn open(&self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(attachment_id) = self.data.metadata.attachment_id else {
+            return;
+        };
+
+        let repository = cx.global::<RepositoryState>().attachments.clone();
+        let window_handle = window.window_handle();
+
+        cx.spawn(async move |_, cx| {
+            let result = repository.attachment_path(attachment_id).await;
+
+            cx.update_window(window_handle, |_, window, cx| match result {
+                Ok(path) => cx.open_with_system(&path),
+                Err(e) => {
+                    tracing::error!("failed to open attachment: {:?}", e);
+                    window.push_notification("The attached file could not be found", cx);
+                }
+            })?;
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+}
+
+impl NodeMenuProvider for AttachmentNode {
+    fn menu_items(&self, _cx: &App) -> Vec<NodeMenuItem> {
+        vec![]
+    }
+}
+
+impl Render for AttachmentNode {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let container = div()
+            .min_w(px(820.0))
+            .w_full()
+            .my(EditorSettings::block_margin(cx, false));
+
+        if self.data.metadata.attachment_id.is_none() {
+            return container.child(
+                h_flex()
+                    .gap_2()
+                    .px_3()
+                    .py_2()
+                    .rounded(cx.theme().radius)
+                    .border_1()
+                    .border_color(cx.theme().border)
+                    .child(
+                        Icon::default()
+                            .path("icons/paperclip.svg")
+                            .small()
+                            .text_color(cx.theme().muted_foreground),
+                    )
+                    .child(
+                        Label::new(if self.picking {
+                            "Attaching..."
+                        } else {
+                            "No file attached"
+                        })
+                        .text_sm()
+                        .text_color(cx.theme().muted_foreground),
+                    )
+                    .child(div().flex_1())
+                    .child(
+                        Button::new("attachment-pick")
+                            .ghost()
+                            .small()
+                            .label("Choose a file")
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.pick_file(window, cx);
+                            })),
+                    ),
+            );
+        }
+
+        let metadata = &self.data.metadata;
+
+        container.child(
+            h_flex()
+                .id("attachment")
+                .gap_3()
+                .px_3()
+                .py_2()
+                .rounded(cx.theme().radius)
+                .border_1()
+                .border_color(cx.theme().border)
+                .cursor_pointer()
+                .hover(|this| this.bg(cx.theme().accent.opacity(0.3)))
+                .on_click(cx.listener(|this, _, window, cx| this.open(window, cx)))
+                .child(
+                    Icon::default()
+                        .path(file_icon(&metadata.file_name))
+                        .size_5()
+                        .text_color(cx.theme().muted_foreground),
+                )
+                .child(
+                    v_flex()
+                        .min_w_0()
+                        .child(
+                            Label::new(metadata.file_name.clone())
+                                .text_sm()
+                                .text_color(cx.theme().foreground),
+                        )
+                        .child(
+                            Label::new(metadata.formatted_size())
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground),
+                        ),
+                ),
+        )
+    }
+}
+
+/// Icon of a file, from its extension.
+fn file_icon(file_name: &str) -> &'static str {
+    let extension = Path::new(file_name)
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase());
+
+    match extension.as_deref() {
+        Some("txt" | "md" | "pdf" | "doc" | "docx" | "odt" | "rtf") => "icons/file-text.svg",
+        _ => "icons/file.svg",
+    }
+}
@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttachmentNodeData {
+    pub id: Uuid,
+
+    #[serde(rename = "type")]
+    pub node_type: String,
+
+    pub metadata: AttachmentMetadata,
+}
+
+impl AttachmentNodeData {
+    pub fn new(id: Uuid, node_type: String, metadata: AttachmentMetadata) -> Self {
+        Self {
+            id,
+            node_type,
+            metadata,
+        }
+    }
+}
+
+/// File shown by an attachment block. `attachment_id` is unset until a file is picked.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct AttachmentMetadata {
+    pub attachment_id: Option<i32>,
+    #[serde(default)]
+    pub file_name: String,
+    /// Size in bytes.
+    #[serde(default)]
+    pub size: i64,
+}
+
+impl AttachmentMetadata {
+    /// Human readable size, e.g. `1.2 MB`.
+    pub fn formatted_size(&self) -> String {
+        format_size(self.size)
+    }
+}
+
+/// Human readable size of `size` bytes, e.g. `1.2 MB`.
+pub fn format_size(size: i64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+
+    let mut size = size.max(0) as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} {}", size, UNITS[unit])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
@@ -0,0 +1,2 @@
+pub mod attachment_node;
+pub mod data;
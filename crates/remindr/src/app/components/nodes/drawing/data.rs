@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DrawingNodeData {
+    pub id: Uuid,
+
+    #[serde(rename = "type")]
+    pub node_type: String,
+
+    pub metadata: DrawingMetadata,
+}
+
+impl DrawingNodeData {
+    pub fn new(id: Uuid, node_type: String, metadata: DrawingMetadata) -> Self {
+        Self {
+            id,
+            node_type,
+            metadata,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct DrawingMetadata {
+    #[serde(default)]
+    pub strokes: Vec<Stroke>,
+}
+
+/// Freehand line of a drawing. Points are relative to the canvas size, between 0 and 1,
+/// so the drawing scales with the block.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Stroke {
+    /// RGB color, e.g. `0xef4444`.
+    pub color: u32,
+    pub width: f32,
+    pub points: Vec<(f32, f32)>,
+}
+
+impl Stroke {
+    /// Whether the stroke passes within `radius` of `point`. Both are relative to the
+    /// canvas, the radius being given for each axis.
+    pub fn is_near(&self, point: (f32, f32), radius: (f32, f32)) -> bool {
+        self.points.iter().any(|(x, y)| {
+            let (dx, dy) = ((x - point.0) / radius.0, (y - point.1) / radius.1);
+            dx * dx + dy * dy <= 1.0
+        })
+    }
+}
@@ -0,0 +1,324 @@
+use anyhow::Error;
+use gpui::prelude::FluentBuilder;
+use gpui::{
+    App, Bounds, Context, Entity, Hsla, InteractiveElement, IntoElement, MouseButton,
+    MouseDownEvent, MouseMoveEvent, MouseUpEvent, ParentElement, PathBuilder, Pixels, Point,
+    Render, SharedString, StatefulInteractiveElement, Styled, Window, canvas, div, point, px, rgb,
+    size,
+};
+use gpui_component::{
+    ActiveTheme, Icon, Selectable, Sizable,
+    button::{Button, ButtonVariants},
+    h_flex, v_flex,
+};
+use serde_json::{Value, from_value};
+
+use crate::app::{
+    components::nodes::{
+        drawing::data::{DrawingNodeData, Stroke},
+        menu_provider::{NodeMenuItem, NodeMenuProvider},
+    },
+    states::{node_state::NodeState, settings_state::EditorSettings},
+};
+
+const CANVAS_HEIGHT: f32 = 320.0;
+const PEN_WIDTH: f32 = 2.0;
+const ERASER_RADIUS: f32 = 8.0;
+
+/// Pen colors offered by the toolbar.
+const COLORS: [u32; 5] = [0x1f2937, 0xef4444, 0xf59e0b, 0x22c55e, 0x3b82f6];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DrawingTool {
+    Pen,
+    Eraser,
+}
+
+pub struct DrawingNode {
+    pub state: Entity<NodeState>,
+    pub data: DrawingNodeData,
+    tool: DrawingTool,
+    color: u32,
+    /// Stroke being drawn, added to the drawing when the mouse is released.
+    current: Option<Stroke>,
+    erasing: bool,
+    /// Whether the ongoing eraser drag removed any stroke.
+    erased: bool,
+    /// Canvas bounds of the last frame, to map mouse positions.
+    bounds: Option<Bounds<Pixels>>,
+}
+
+impl DrawingNode {
+    pub fn parse(
+        data: &Value,
+        state: &Entity<NodeState>,
+        _: &mut Window,
+        _: &mut Context<Self>,
+    ) -> Result<Self, Error> {
+        let data = from_value::<DrawingNodeData>(data.clone())?;
+
+        Ok(Self {
+            state: state.clone(),
+            data,
+            tool: DrawingTool::Pen,
+            color: COLORS[0],
+            current: None,
+            erasing: false,
+            erased: false,
+            bounds: None,
+        })
+    }
+
+    /// Position relative to the canvas, between 0 and 1 on each axis.
+    fn relative_position(&self, position: Point<Pixels>) -> Option<(f32, f32)> {
+        let bounds = self.bounds?;
+        if bounds.size.width <= px(0.) || bounds.size.height <= px(0.) {
+            return None;
+        }
+
+        let x = (position.x - bounds.origin.x) / bounds.size.width;
+        let y = (position.y - bounds.origin.y) / bounds.size.height;
+        Some((x.clamp(0., 1.), y.clamp(0., 1.)))
+    }
+
+    fn on_mouse_down(&mut self, event: &MouseDownEvent, _: &mut Window, cx: &mut Context<Self>) {
+        let Some(position) = self.relative_position(event.position) else {
+            return;
+        };
+        cx.stop_propagation();
+
+        match self.tool {
+            DrawingTool::Pen => {
+                self.current = Some(Stroke {
+                    color: self.color,
+                    width: PEN_WIDTH,
+                    points: vec![position],
+                });
+            }
+            DrawingTool::Eraser => {
+                self.erasing = true;
+                self.erased = false;
+                self.erase_at(position);
+            }
+        }
+        cx.notify();
+    }
+
+    fn on_mouse_move(&mut self, event: &MouseMoveEvent, _: &mut Window, cx: &mut Context<Self>) {
+        if self.current.is_none() && !self.erasing {
+            return;
+        }
+        let Some(position) = self.relative_position(event.position) else {
+            return;
+        };
+
+        if let Some(stroke) = self.current.as_mut() {
+            if stroke.points.last() != Some(&position) {
+                stroke.points.push(position);
+            }
+        } else {
+            self.erase_at(position);
+        }
+        cx.notify();
+    }
+
+    fn on_mouse_up(&mut self, _: &MouseUpEvent, _: &mut Window, cx: &mut Context<Self>) {
+        let mut changed = false;
+        if let Some(stroke) = self.current.take() {
+            self.data.metadata.strokes.push(stroke);
+            changed = true;
+        }
+        if self.erasing {
+            self.erasing = false;
+            changed |= self.erased;
+        }
+
+        if changed {
+            self.mark_changed(cx);
+        }
+        cx.notify();
+    }
+
+    /// Removes the strokes passing under the eraser.
+    fn erase_at(&mut self, position: (f32, f32)) {
+        let Some(bounds) = self.bounds else {
+            return;
+        };
+        let radius = (
+            px(ERASER_RADIUS) / bounds.size.width,
+            px(ERASER_RADIUS) / bounds.size.height,
+        );
+
+        let count = self.data.metadata.strokes.len();
+        self.data
+            .metadata
+            .strokes
+            .retain(|stroke| !stroke.is_near(position, radius));
+        self.erased |= self.data.metadata.strokes.len() != count;
+    }
+
+    fn clear(&mut self, cx: &mut Context<Self>) {
+        if self.data.metadata.strokes.is_empty() {
+            return;
+        }
+
+        self.data.metadata.strokes.clear();
+        self.mark_changed(cx);
+        cx.notify();
+    }
+
+    fn mark_changed(&self, cx: &mut Context<Self>) {
+        let node_id = self.data.id;
+        self.state
+            .update(cx, |state, cx| state.node_changed(node_id, cx));
+    }
+
+    fn render_toolbar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        h_flex()
+            .gap_1()
+            .child(
+                Button::new("drawing-pen")
+                    .ghost()
+                    .xsmall()
+                    .icon(Icon::default().path("icons/pencil-line.svg"))
+                    .tooltip("Pen")
+                    .selected(self.tool == DrawingTool::Pen)
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.tool = DrawingTool::Pen;
+                        cx.notify();
+                    })),
+            )
+            .child(
+                Button::new("drawing-eraser")
+                    .ghost()
+                    .xsmall()
+                    .icon(Icon::default().path("icons/eraser.svg"))
+                    .tooltip("Eraser")
+                    .selected(self.tool == DrawingTool::Eraser)
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.tool = DrawingTool::Eraser;
+                        cx.notify();
+                    })),
+            )
+            .child(div().w(px(1.)).h_4().mx_1().bg(cx.theme().border))
+            .children(COLORS.into_iter().map(|color| {
+                let selected = self.tool == DrawingTool::Pen && self.color == color;
+
+                div()
+                    .id(SharedString::from(format!("drawing-color-{:06x}", color)))
+                    .size_4()
+                    .rounded_full()
+                    .cursor_pointer()
+                    .bg(rgb(color))
+                    .border_2()
+                    .border_color(if selected {
+                        cx.theme().ring
+                    } else {
+                        cx.theme().transparent
+                    })
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        this.tool = DrawingTool::Pen;
+                        this.color = color;
+                        cx.notify();
+                    }))
+            }))
+            .child(div().flex_1())
+            .child(
+                Button::new("drawing-clear")
+                    .ghost()
+                    .xsmall()
+                    .icon(Icon::default().path("icons/trash-2.svg"))
+                    .tooltip("Clear drawing")
+                    .on_click(cx.listener(|this, _, _, cx| this.clear(cx))),
+            )
+    }
+}
+
+impl NodeMenuProvider for DrawingNode {
+    fn menu_items(&self, _cx: &App) -> Vec<NodeMenuItem> {
+        vec![]
+    }
+}
+
+impl Render for DrawingNode {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let entity = cx.entity();
+        let strokes = self
+            .data
+            .metadata
+            .strokes
+            .iter()
+            .chain(self.current.as_ref())
+            .cloned()
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .min_w(px(820.0))
+            .w_full()
+            .my(EditorSettings::block_margin(cx, false))
+            .gap_1()
+            .child(self.render_toolbar(cx))
+            .child(
+                div()
+                    .w_full()
+                    .h(px(CANVAS_HEIGHT))
+                    .rounded(cx.theme().radius)
+                    .border_1()
+                    .border_color(cx.theme().border)
+                    .bg(cx.theme().background)
+                    .overflow_hidden()
+                    .when(self.tool == DrawingTool::Pen, |this| {
+                        this.cursor_crosshair()
+                    })
+                    .on_mouse_down(MouseButton::Left, cx.listener(Self::on_mouse_down))
+                    .on_mouse_move(cx.listener(Self::on_mouse_move))
+                    .on_mouse_up(MouseButton::Left, cx.listener(Self::on_mouse_up))
+                    .on_mouse_up_out(MouseButton::Left, cx.listener(Self::on_mouse_up))
+                    .child(
+                        canvas(
+                            move |bounds, _, cx| {
+                                entity.update(cx, |this, _| this.bounds = Some(bounds));
+                            },
+                            move |bounds, _, window, _| {
+                                for stroke in &strokes {
+                                    paint_stroke(stroke, bounds, window);
+                                }
+                            },
+                        )
+                        .size_full(),
+                    ),
+            )
+    }
+}
+
+fn paint_stroke(stroke: &Stroke, bounds: Bounds<Pixels>, window: &mut Window) {
+    let color = Hsla::from(rgb(stroke.color));
+    let mut points = stroke
+        .points
+        .iter()
+        .map(|(x, y)| bounds.origin + point(bounds.size.width * *x, bounds.size.height * *y));
+    let Some(first) = points.next() else {
+        return;
+    };
+
+    // A single click leaves a dot
+    if stroke.points.len() == 1 {
+        window.paint_quad(
+            gpui::fill(
+                Bounds::centered_at(first, size(px(stroke.width), px(stroke.width))),
+                color,
+            )
+            .corner_radii(px(stroke.width / 2.)),
+        );
+        return;
+    }
+
+    let mut builder = PathBuilder::stroke(px(stroke.width));
+    builder.move_to(first);
+    for point in points {
+        builder.line_to(point);
+    }
+    if let Ok(path) = builder.build() {
+        window.paint_path(path, color);
+    }
+}
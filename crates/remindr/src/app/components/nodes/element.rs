@@ -1,19 +1,55 @@
 use crate::{
     Utils,
     app::{
-        components::nodes::{
-            divider::{data::DividerNodeData, divider_node::DividerNode},
-            heading::{
-                data::{HeadingMetadata, HeadingNodeData},
-                heading_node::HeadingNode,
+        components::{
+            nodes::{
+                attachment::{
+                    attachment_node::AttachmentNode,
+                    data::{AttachmentMetadata, AttachmentNodeData},
+                },
+                divider::{data::DividerNodeData, divider_node::DividerNode},
+                drawing::{
+                    data::{DrawingMetadata, DrawingNodeData},
+                    drawing_node::DrawingNode,
+                },
+                equation::{
+                    data::{EquationMetadata, EquationNodeData},
+                    equation_node::EquationNode,
+                },
+                heading::{
+                    data::{HeadingMetadata, HeadingNodeData},
+                    heading_node::HeadingNode,
+                },
+                link::{
+                    data::{LinkMetadata, LinkNodeData},
+                    link_node::LinkNode,
+                },
+                list::{
+                    data::{ListMetadata, ListNodeData},
+                    list_node::ListNode,
+                },
+                menu_provider::{NodeMenuItem, NodeMenuProvider},
+                node::RemindrNode,
+                quote::{
+                    data::{QuoteMetadata, QuoteNodeData},
+                    quote_node::QuoteNode,
+                },
+                synced_block::{
+                    data::{SyncedBlockMetadata, SyncedBlockNodeData},
+                    synced_block_node::SyncedBlockNode,
+                },
+                text::{
+                    data::{TextMetadata, TextNodeData},
+                    text_node::TextNode,
+                },
+                textual_node::TextualNode,
+                todo::{
+                    data::{TodoMetadata, TodoNodeData},
+                    todo_node::TodoNode,
+                },
+                unsupported::unsupported_node::UnsupportedNode,
             },
-            menu_provider::{NodeMenuItem, NodeMenuProvider},
-            node::RemindrNode,
-            text::{
-                data::{TextMetadata, TextNodeData},
-                text_node::TextNode,
-            },
-            textual_node::TextualNode,
+            rich_text::RichTextState,
         },
         states::node_state::NodeState,
     },
@@ -24,6 +60,14 @@ use serde_json::{Value, to_value};
 pub enum NodePayload {
     Text((TextMetadata, bool)),
     Heading((HeadingMetadata, bool)),
+    List((ListMetadata, bool)),
+    Quote((QuoteMetadata, bool)),
+    Todo((TodoMetadata, bool)),
+    Attachment((AttachmentMetadata, bool)),
+    Drawing(DrawingMetadata),
+    Equation((EquationMetadata, bool)),
+    SyncedBlock(SyncedBlockMetadata),
+    Link(LinkMetadata),
     Divider,
 }
 
@@ -32,6 +76,15 @@ pub enum RemindrElement {
     Text(Entity<TextNode>),
     Divider(Entity<DividerNode>),
     Heading(Entity<HeadingNode>),
+    List(Entity<ListNode>),
+    Quote(Entity<QuoteNode>),
+    Todo(Entity<TodoNode>),
+    Attachment(Entity<AttachmentNode>),
+    Drawing(Entity<DrawingNode>),
+    Equation(Entity<EquationNode>),
+    SyncedBlock(Entity<SyncedBlockNode>),
+    Link(Entity<LinkNode>),
+    Unsupported(Entity<UnsupportedNode>),
 }
 
 impl RemindrElement {
@@ -40,6 +93,37 @@ impl RemindrElement {
             RemindrElement::Text(text) => to_value(text.read(cx).data.clone()).unwrap(),
             RemindrElement::Divider(divider) => to_value(divider.read(cx).data.clone()).unwrap(),
             RemindrElement::Heading(heading) => to_value(heading.read(cx).data.clone()).unwrap(),
+            RemindrElement::List(list) => to_value(list.read(cx).data.clone()).unwrap(),
+            RemindrElement::Quote(quote) => to_value(quote.read(cx).data.clone()).unwrap(),
+            RemindrElement::Todo(todo) => to_value(todo.read(cx).data.clone()).unwrap(),
+            RemindrElement::Attachment(attachment) => {
+                to_value(attachment.read(cx).data.clone()).unwrap()
+            }
+            RemindrElement::Drawing(drawing) => to_value(drawing.read(cx).data.clone()).unwrap(),
+            RemindrElement::Equation(equation) => to_value(equation.read(cx).data.clone()).unwrap(),
+            RemindrElement::SyncedBlock(synced_block) => {
+                to_value(synced_block.read(cx).data.clone()).unwrap()
+            }
+            RemindrElement::Link(link) => to_value(link.read(cx).data.clone()).unwrap(),
+            RemindrElement::Unsupported(unsupported) => unsupported.read(cx).data.clone(),
+        }
+    }
+
+    /// Name of the kind of block, used to describe its gutter.
+    pub fn label(&self) -> &'static str {
+        match self {
+            RemindrElement::Text(_) => "Text",
+            RemindrElement::Divider(_) => "Divider",
+            RemindrElement::Heading(_) => "Heading",
+            RemindrElement::List(_) => "List",
+            RemindrElement::Quote(_) => "Quote",
+            RemindrElement::Todo(_) => "To-do",
+            RemindrElement::Attachment(_) => "File",
+            RemindrElement::Drawing(_) => "Drawing",
+            RemindrElement::Equation(_) => "Equation",
+            RemindrElement::SyncedBlock(_) => "Synced block",
+            RemindrElement::Link(_) => "Link",
+            RemindrElement::Unsupported(_) => "Unsupported block",
         }
     }
 
@@ -48,6 +132,53 @@ impl RemindrElement {
             RemindrElement::Text(text) => text.read(cx).menu_items(cx),
             RemindrElement::Divider(divider) => divider.read(cx).menu_items(cx),
             RemindrElement::Heading(heading) => heading.read(cx).menu_items(cx),
+            RemindrElement::List(list) => list.read(cx).menu_items(cx),
+            RemindrElement::Quote(quote) => quote.read(cx).menu_items(cx),
+            RemindrElement::Todo(todo) => todo.read(cx).menu_items(cx),
+            RemindrElement::Attachment(attachment) => attachment.read(cx).menu_items(cx),
+            RemindrElement::Drawing(drawing) => drawing.read(cx).menu_items(cx),
+            RemindrElement::Equation(equation) => equation.read(cx).menu_items(cx),
+            RemindrElement::SyncedBlock(synced_block) => synced_block.read(cx).menu_items(cx),
+            RemindrElement::Link(link) => link.read(cx).menu_items(cx),
+            RemindrElement::Unsupported(unsupported) => unsupported.read(cx).menu_items(cx),
+        }
+    }
+
+    /// Returns the rich text state backing this element, if it is edited through `RichText`.
+    pub fn rich_text_state(&self, cx: &App) -> Option<Entity<RichTextState>> {
+        match self {
+            RemindrElement::Text(text) => Some(text.read(cx).rich_text_state.clone()),
+            RemindrElement::List(list) => Some(list.read(cx).rich_text_state.clone()),
+            RemindrElement::Quote(quote) => Some(quote.read(cx).rich_text_state.clone()),
+            RemindrElement::Todo(todo) => Some(todo.read(cx).rich_text_state.clone()),
+            RemindrElement::Heading(_)
+            | RemindrElement::Divider(_)
+            | RemindrElement::Attachment(_)
+            | RemindrElement::Drawing(_)
+            | RemindrElement::Equation(_)
+            | RemindrElement::SyncedBlock(_)
+            | RemindrElement::Link(_)
+            | RemindrElement::Unsupported(_) => None,
+        }
+    }
+
+    /// Focuses the element and places the cursor at the end of its content.
+    pub fn focus_end(&self, window: &mut Window, cx: &mut App) {
+        if let RemindrElement::Heading(element) = self {
+            let input = element.read(cx).input_state().clone();
+            input.update(cx, |input, cx| {
+                input.focus(window, cx);
+                input.set_cursor_position(
+                    gpui_component::input::Position::new(u32::MAX, u32::MAX),
+                    window,
+                    cx,
+                );
+            });
+        } else if let Some(rich_text) = self.rich_text_state(cx) {
+            rich_text.update(cx, |state, cx| {
+                state.focus(window, cx);
+                state.move_to_end(cx);
+            });
         }
     }
 
@@ -93,6 +224,98 @@ impl RemindrElement {
 
                 RemindrElement::Text(element)
             }
+            NodePayload::List((payload, is_focus)) => {
+                let data = to_value(ListNodeData::new(id, "list".to_string(), payload)).unwrap();
+
+                let element = cx.new(|cx| ListNode::parse(&data, state, window, cx).unwrap());
+                if is_focus {
+                    element.update(cx, |this, cx| {
+                        this.focus(window, cx);
+                    });
+                }
+
+                RemindrElement::List(element)
+            }
+            NodePayload::Quote((payload, is_focus)) => {
+                let data = to_value(QuoteNodeData::new(id, "quote".to_string(), payload)).unwrap();
+
+                let element = cx.new(|cx| QuoteNode::parse(&data, state, window, cx).unwrap());
+                if is_focus {
+                    element.update(cx, |this, cx| {
+                        this.focus(window, cx);
+                    });
+                }
+
+                RemindrElement::Quote(element)
+            }
+            NodePayload::Todo((payload, is_focus)) => {
+                let data = to_value(TodoNodeData::new(id, "todo".to_string(), payload)).unwrap();
+
+                let element = cx.new(|cx| TodoNode::parse(&data, state, window, cx).unwrap());
+                if is_focus {
+                    element.update(cx, |this, cx| {
+                        this.focus(window, cx);
+                    });
+                }
+
+                RemindrElement::Todo(element)
+            }
+            NodePayload::Attachment((payload, pick_file)) => {
+                let data = to_value(AttachmentNodeData::new(
+                    id,
+                    "attachment".to_string(),
+                    payload,
+                ))
+                .unwrap();
+
+                let element = cx.new(|cx| AttachmentNode::parse(&data, state, window, cx).unwrap());
+                // A new attachment block asks for its file right away
+                if pick_file {
+                    element.update(cx, |this, cx| {
+                        this.pick_file(window, cx);
+                    });
+                }
+
+                RemindrElement::Attachment(element)
+            }
+            NodePayload::Drawing(payload) => {
+                let data =
+                    to_value(DrawingNodeData::new(id, "drawing".to_string(), payload)).unwrap();
+                let element = cx.new(|cx| DrawingNode::parse(&data, state, window, cx).unwrap());
+
+                RemindrElement::Drawing(element)
+            }
+            NodePayload::Equation((payload, is_focus)) => {
+                let data =
+                    to_value(EquationNodeData::new(id, "equation".to_string(), payload)).unwrap();
+
+                let element = cx.new(|cx| EquationNode::parse(&data, state, window, cx).unwrap());
+                if is_focus {
+                    element.update(cx, |this, cx| {
+                        this.edit(window, cx);
+                    });
+                }
+
+                RemindrElement::Equation(element)
+            }
+            NodePayload::SyncedBlock(payload) => {
+                let data = to_value(SyncedBlockNodeData::new(
+                    id,
+                    "synced_block".to_string(),
+                    payload,
+                ))
+                .unwrap();
+                let element =
+                    cx.new(|cx| SyncedBlockNode::parse(&data, state, window, cx).unwrap());
+
+                RemindrElement::SyncedBlock(element)
+            }
+            NodePayload::Link(payload) => {
+                let data = to_value(LinkNodeData::new(id, "link".to_string(), payload)).unwrap();
+                let element = cx.new(|cx| LinkNode::parse(&data, window, cx).unwrap());
+
+                RemindrElement::Link(element)
+            }
             NodePayload::Divider => {
                 let data = to_value(DividerNodeData::new(id, "divider".to_string())).unwrap();
                 let element = cx.new(|cx| DividerNode::parse(&data, window, cx).unwrap());
@@ -112,6 +335,15 @@ impl RenderOnce for RemindrElement {
             RemindrElement::Text(element) => element.clone().into_any_element(),
             RemindrElement::Divider(element) => element.clone().into_any_element(),
             RemindrElement::Heading(element) => element.clone().into_any_element(),
+            RemindrElement::List(element) => element.clone().into_any_element(),
+            RemindrElement::Quote(element) => element.clone().into_any_element(),
+            RemindrElement::Todo(element) => element.clone().into_any_element(),
+            RemindrElement::Attachment(element) => element.clone().into_any_element(),
+            RemindrElement::Drawing(element) => element.clone().into_any_element(),
+            RemindrElement::Equation(element) => element.clone().into_any_element(),
+            RemindrElement::SyncedBlock(element) => element.clone().into_any_element(),
+            RemindrElement::Link(element) => element.clone().into_any_element(),
+            RemindrElement::Unsupported(element) => element.clone().into_any_element(),
         }
     }
 }
@@ -123,6 +355,15 @@ impl Render for RemindrElement {
             RemindrElement::Text(element) => element.clone().into_any_element(),
             RemindrElement::Divider(element) => element.clone().into_any_element(),
             RemindrElement::Heading(element) => element.clone().into_any_element(),
+            RemindrElement::List(element) => element.clone().into_any_element(),
+            RemindrElement::Quote(element) => element.clone().into_any_element(),
+            RemindrElement::Todo(element) => element.clone().into_any_element(),
+            RemindrElement::Attachment(element) => element.clone().into_any_element(),
+            RemindrElement::Drawing(element) => element.clone().into_any_element(),
+            RemindrElement::Equation(element) => element.clone().into_any_element(),
+            RemindrElement::SyncedBlock(element) => element.clone().into_any_element(),
+            RemindrElement::Link(element) => element.clone().into_any_element(),
+            RemindrElement::Unsupported(element) => element.clone().into_any_element(),
         }
     }
 }
@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EquationNodeData {
+    pub id: Uuid,
+
+    #[serde(rename = "type")]
+    pub node_type: String,
+
+    pub metadata: EquationMetadata,
+}
+
+impl EquationNodeData {
+    pub fn new(id: Uuid, node_type: String, metadata: EquationMetadata) -> Self {
+        Self {
+            id,
+            node_type,
+            metadata,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct EquationMetadata {
+    /// LaTeX source of the formula.
+    #[serde(default)]
+    pub content: String,
+}
@@ -0,0 +1,223 @@
+use anyhow::Error;
+use gpui::{
+    AnyElement, App, AppContext, Context, Entity, Hsla, InteractiveElement, IntoElement,
+    ParentElement, Pixels, Render, StatefulInteractiveElement, Styled, Window, div, px,
+};
+use gpui_component::{
+    ActiveTheme, Icon, Sizable, h_flex,
+    input::{Input, InputEvent, InputState},
+    label::Label,
+    v_flex,
+};
+use serde_json::{Value, from_value};
+
+use crate::app::{
+    components::nodes::{
+        equation::{
+            data::EquationNodeData,
+            math::{self, MathNode},
+        },
+        menu_provider::{NodeMenuItem, NodeMenuProvider},
+    },
+    states::{node_state::NodeState, settings_state::EditorSettings},
+};
+
+const FONT_SIZE: f32 = 18.0;
+
+pub struct EquationNode {
+    pub state: Entity<NodeState>,
+    pub data: EquationNodeData,
+    input_state: Entity<InputState>,
+    /// Whether the source is shown for editing.
+    editing: bool,
+}
+
+impl EquationNode {
+    pub fn parse(
+        data: &Value,
+        state: &Entity<NodeState>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Result<Self, Error> {
+        let data = from_value::<EquationNodeData>(data.clone())?;
+
+        let input_state = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("E = mc^2")
+                .default_value(data.metadata.content.clone())
+        });
+
+        cx.subscribe_in(&input_state, window, {
+            move |this, input_state, event: &InputEvent, _, cx| match event {
+                InputEvent::Change => {
+                    this.data.metadata.content = input_state.read(cx).value().to_string();
+                    let node_id = this.data.id;
+                    this.state
+                        .update(cx, |state, cx| state.node_changed(node_id, cx));
+                    cx.notify();
+                }
+                InputEvent::Blur | InputEvent::PressEnter { .. } => {
+                    this.editing = false;
+                    cx.notify();
+                }
+                InputEvent::Focus => {}
+            }
+        })
+        .detach();
+
+        Ok(Self {
+            state: state.clone(),
+            data,
+            input_state,
+            editing: false,
+        })
+    }
+
+    /// Shows the source of the formula and focuses it.
+    pub fn edit(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.editing = true;
+        self.input_state.update(cx, |input_state, cx| {
+            input_state.focus(window, cx);
+        });
+        cx.notify();
+    }
+
+    fn render_formula(&self, cx: &mut Context<Self>) -> AnyElement {
+        let content = self.data.metadata.content.trim();
+        if content.is_empty() {
+            return Label::new("Empty equation")
+                .text_sm()
+                .italic()
+                .text_color(cx.theme().muted_foreground)
+                .into_any_element();
+        }
+
+        match math::parse(content) {
+            Ok(formula) => div()
+                .flex()
+                .justify_center()
+                .text_color(cx.theme().foreground)
+                .child(render_math(&formula, px(FONT_SIZE), cx.theme().foreground))
+                .into_any_element(),
+            // Falls back to the source when the formula can't be parsed
+            Err(e) => v_flex()
+                .items_center()
+                .gap_1()
+                .child(
+                    div()
+                        .font_family("monospace")
+                        .text_sm()
+                        .text_color(cx.theme().foreground)
+                        .child(content.to_string()),
+                )
+                .child(
+                    h_flex()
+                        .gap_1()
+                        .text_xs()
+                        .text_color(cx.theme().danger)
+                        .child(Icon::default().path("icons/triangle-alert.svg").xsmall())
+                        .child(e.to_string()),
+                )
+                .into_any_element(),
+        }
+    }
+}
+
+impl NodeMenuProvider for EquationNode {
+    fn menu_items(&self, _cx: &App) -> Vec<NodeMenuItem> {
+        vec![]
+    }
+}
+
+impl Render for EquationNode {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let container = v_flex()
+            .min_w(px(820.0))
+            .w_full()
+            .my(EditorSettings::block_margin(cx, false))
+            .gap_2();
+
+        if self.editing {
+            return container
+                .child(Input::new(&self.input_state).small())
+                .child(self.render_formula(cx));
+        }
+
+        container.child(
+            div()
+                .id("equation")
+                .w_full()
+                .py_2()
+                .rounded(cx.theme().radius)
+                .cursor_pointer()
+                .hover(|this| this.bg(cx.theme().accent.opacity(0.3)))
+                .on_click(cx.listener(|this, _, window, cx| this.edit(window, cx)))
+                .child(self.render_formula(cx)),
+        )
+    }
+}
+
+/// Lays out a formula with nested flex boxes, scripts and fractions being drawn smaller.
+fn render_math(node: &MathNode, size: Pixels, color: Hsla) -> AnyElement {
+    let element = div().text_size(size);
+
+    match node {
+        MathNode::Variable(c) => element.italic().child(c.to_string()).into_any_element(),
+        MathNode::Symbol(symbol) => element
+            .px(size * 0.1)
+            .child(symbol.clone())
+            .into_any_element(),
+        MathNode::Text(text) => element.child(text.clone()).into_any_element(),
+        MathNode::Space(width) => element.w(size * *width).into_any_element(),
+        MathNode::Row(nodes) => element
+            .flex()
+            .items_center()
+            .children(nodes.iter().map(|node| render_math(node, size, color)))
+            .into_any_element(),
+        MathNode::Fraction(numerator, denominator) => element
+            .flex()
+            .flex_col()
+            .items_center()
+            .px(size * 0.1)
+            .child(render_math(numerator, size * 0.85, color))
+            .child(div().w_full().h(px(1.)).bg(color))
+            .child(render_math(denominator, size * 0.85, color))
+            .into_any_element(),
+        MathNode::Sqrt(radicand) => element
+            .flex()
+            .items_center()
+            .child("√")
+            .child(
+                div()
+                    .border_t_1()
+                    .border_color(color)
+                    .child(render_math(radicand, size, color)),
+            )
+            .into_any_element(),
+        MathNode::Scripts {
+            base,
+            superscript,
+            subscript,
+        } => {
+            let script_size = size * 0.7;
+            // An empty slot keeps a lone script above or below the middle of the base
+            let script = |script: &Option<Box<MathNode>>| match script {
+                Some(script) => render_math(script, script_size, color),
+                None => div().h(script_size).into_any_element(),
+            };
+
+            element
+                .flex()
+                .items_center()
+                .child(render_math(base, size, color))
+                .child(
+                    div()
+                        .flex()
+                        .flex_col()
+                        .child(script(superscript))
+                        .child(script(subscript)),
+                )
+                .into_any_element()
+        }
+    }
+}
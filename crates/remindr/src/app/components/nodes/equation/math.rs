@@ -0,0 +1,309 @@
+use anyhow::{Error, anyhow, bail};
+
+/// Layout tree of a parsed formula.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MathNode {
+    /// Single letter, shown in italics.
+    Variable(char),
+    /// Digits, operators and symbols, shown upright.
+    Symbol(String),
+    /// Text from `\text{...}` or an operator name like `\sin`.
+    Text(String),
+    Space(f32),
+    Row(Vec<MathNode>),
+    Fraction(Box<MathNode>, Box<MathNode>),
+    Sqrt(Box<MathNode>),
+    Scripts {
+        base: Box<MathNode>,
+        superscript: Option<Box<MathNode>>,
+        subscript: Option<Box<MathNode>>,
+    },
+}
+
+/// Parses a LaTeX formula. Supports fractions, square roots, scripts, groups, `\text`, greek
+/// letters and the common operators and relations.
+pub fn parse(source: &str) -> Result<MathNode, Error> {
+    let mut parser = Parser {
+        chars: source.chars().collect(),
+        position: 0,
+    };
+
+    let row = parser.parse_row()?;
+    if parser.position < parser.chars.len() {
+        bail!("Unexpected '}}'");
+    }
+
+    Ok(MathNode::Row(row))
+}
+
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.position += 1;
+        }
+    }
+
+    /// Parses nodes until the end of the source or a closing brace, left unconsumed.
+    fn parse_row(&mut self) -> Result<Vec<MathNode>, Error> {
+        let mut row = Vec::new();
+
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                None | Some('}') => return Ok(row),
+                Some('^' | '_') => {
+                    // Scripts apply to the previous node, or to nothing at the start
+                    let base = row.pop().unwrap_or(MathNode::Row(Vec::new()));
+                    row.push(self.parse_scripts(base)?);
+                }
+                Some(_) => {
+                    if let Some(node) = self.parse_atom()? {
+                        row.push(node);
+                    }
+                }
+            }
+        }
+    }
+
+    fn parse_scripts(&mut self, base: MathNode) -> Result<MathNode, Error> {
+        let (base, mut superscript, mut subscript) = match base {
+            MathNode::Scripts {
+                base,
+                superscript,
+                subscript,
+            } => (base, superscript, subscript),
+            base => (Box::new(base), None, None),
+        };
+
+        while let Some(marker @ ('^' | '_')) = self.peek() {
+            self.position += 1;
+            let script = Box::new(self.parse_argument()?);
+            let (slot, name) = if marker == '^' {
+                (&mut superscript, "superscript")
+            } else {
+                (&mut subscript, "subscript")
+            };
+            if slot.is_some() {
+                bail!("Double {}", name);
+            }
+            *slot = Some(script);
+            self.skip_whitespace();
+        }
+
+        Ok(MathNode::Scripts {
+            base,
+            superscript,
+            subscript,
+        })
+    }
+
+    /// Parses the argument of a command or a script: a group or a single atom.
+    fn parse_argument(&mut self) -> Result<MathNode, Error> {
+        self.skip_whitespace();
+        match self.peek() {
+            None | Some('}') => bail!("Missing argument"),
+            Some('^' | '_') => bail!("Unexpected script"),
+            Some(_) => loop {
+                if let Some(node) = self.parse_atom()? {
+                    return Ok(node);
+                }
+                self.skip_whitespace();
+            },
+        }
+    }
+
+    /// Parses a single node. Returns `None` for commands producing nothing, like `\left`.
+    fn parse_atom(&mut self) -> Result<Option<MathNode>, Error> {
+        let Some(c) = self.peek() else {
+            bail!("Missing argument");
+        };
+        self.position += 1;
+
+        let node = match c {
+            '{' => {
+                let row = self.parse_row()?;
+                if self.peek() != Some('}') {
+                    bail!("Missing '}}'");
+                }
+                self.position += 1;
+                MathNode::Row(row)
+            }
+            '\\' => return self.parse_command(),
+            c if c.is_alphabetic() => MathNode::Variable(c),
+            '-' => MathNode::Symbol("−".to_string()),
+            '*' => MathNode::Symbol("∗".to_string()),
+            c => MathNode::Symbol(c.to_string()),
+        };
+
+        Ok(Some(node))
+    }
+
+    fn parse_command(&mut self) -> Result<Option<MathNode>, Error> {
+        let start = self.position;
+        while self.peek().is_some_and(|c| c.is_ascii_alphabetic()) {
+            self.position += 1;
+        }
+
+        // Single character commands: escapes and spacing
+        if self.position == start {
+            let Some(c) = self.peek() else {
+                bail!("Incomplete command");
+            };
+            self.position += 1;
+            return Ok(Some(match c {
+                ',' => MathNode::Space(0.17),
+                ':' | '>' => MathNode::Space(0.22),
+                ';' => MathNode::Space(0.28),
+                ' ' => MathNode::Space(0.33),
+                '!' => return Ok(None),
+                '\\' => bail!("Line breaks are not supported"),
+                c => MathNode::Symbol(c.to_string()),
+            }));
+        }
+
+        let name: String = self.chars[start..self.position].iter().collect();
+        let node = match name.as_str() {
+            "frac" | "dfrac" | "tfrac" => {
+                let numerator = self.parse_argument()?;
+                let denominator = self.parse_argument()?;
+                MathNode::Fraction(Box::new(numerator), Box::new(denominator))
+            }
+            "sqrt" => MathNode::Sqrt(Box::new(self.parse_argument()?)),
+            "text" | "mathrm" | "operatorname" => MathNode::Text(self.parse_text()?),
+            "left" | "right" | "displaystyle" => return Ok(None),
+            "quad" => MathNode::Space(1.0),
+            "qquad" => MathNode::Space(2.0),
+            name if OPERATOR_NAMES.contains(&name) => MathNode::Text(name.to_string()),
+            name => match symbol(name) {
+                Some(symbol) => MathNode::Symbol(symbol.to_string()),
+                None => return Err(anyhow!("Unknown command \\{}", name)),
+            },
+        };
+
+        Ok(Some(node))
+    }
+
+    /// Reads the raw content of a `{...}` argument.
+    fn parse_text(&mut self) -> Result<String, Error> {
+        self.skip_whitespace();
+        if self.peek() != Some('{') {
+            bail!("Missing argument");
+        }
+        self.position += 1;
+
+        let start = self.position;
+        let mut depth = 0;
+        loop {
+            match self.peek() {
+                None => bail!("Missing '}}'"),
+                Some('{') => depth += 1,
+                Some('}') if depth == 0 => break,
+                Some('}') => depth -= 1,
+                Some(_) => {}
+            }
+            self.position += 1;
+        }
+
+        let text = self.chars[start..self.position].iter().collect();
+        self.position += 1;
+        Ok(text)
+    }
+}
+
+/// Functions written upright, like `\sin x`.
+const OPERATOR_NAMES: [&str; 18] = [
+    "sin", "cos", "tan", "cot", "sec", "csc", "arcsin", "arccos", "arctan", "sinh", "cosh", "tanh",
+    "log", "ln", "exp", "lim", "max", "min",
+];
+
+fn symbol(name: &str) -> Option<&'static str> {
+    let symbol = match name {
+        "alpha" => "α",
+        "beta" => "β",
+        "gamma" => "γ",
+        "delta" => "δ",
+        "epsilon" | "varepsilon" => "ε",
+        "zeta" => "ζ",
+        "eta" => "η",
+        "theta" | "vartheta" => "θ",
+        "iota" => "ι",
+        "kappa" => "κ",
+        "lambda" => "λ",
+        "mu" => "μ",
+        "nu" => "ν",
+        "xi" => "ξ",
+        "pi" => "π",
+        "rho" => "ρ",
+        "sigma" => "σ",
+        "tau" => "τ",
+        "upsilon" => "υ",
+        "phi" | "varphi" => "φ",
+        "chi" => "χ",
+        "psi" => "ψ",
+        "omega" => "ω",
+        "Gamma" => "Γ",
+        "Delta" => "Δ",
+        "Theta" => "Θ",
+        "Lambda" => "Λ",
+        "Xi" => "Ξ",
+        "Pi" => "Π",
+        "Sigma" => "Σ",
+        "Phi" => "Φ",
+        "Psi" => "Ψ",
+        "Omega" => "Ω",
+        "times" => "×",
+        "cdot" => "·",
+        "div" => "÷",
+        "pm" => "±",
+        "mp" => "∓",
+        "leq" | "le" => "≤",
+        "geq" | "ge" => "≥",
+        "neq" | "ne" => "≠",
+        "approx" => "≈",
+        "equiv" => "≡",
+        "sim" => "∼",
+        "propto" => "∝",
+        "infty" => "∞",
+        "sum" => "∑",
+        "prod" => "∏",
+        "int" => "∫",
+        "oint" => "∮",
+        "partial" => "∂",
+        "nabla" => "∇",
+        "to" | "rightarrow" => "→",
+        "leftarrow" => "←",
+        "Rightarrow" | "implies" => "⇒",
+        "Leftarrow" => "⇐",
+        "Leftrightarrow" | "iff" => "⇔",
+        "in" => "∈",
+        "notin" => "∉",
+        "subset" => "⊂",
+        "subseteq" => "⊆",
+        "cup" => "∪",
+        "cap" => "∩",
+        "forall" => "∀",
+        "exists" => "∃",
+        "emptyset" => "∅",
+        "neg" => "¬",
+        "wedge" | "land" => "∧",
+        "vee" | "lor" => "∨",
+        "cdots" => "⋯",
+        "ldots" | "dots" => "…",
+        "langle" => "⟨",
+        "rangle" => "⟩",
+        "lbrace" => "{",
+        "rbrace" => "}",
+        _ => return None,
+    };
+
+    Some(symbol)
+}
@@ -0,0 +1,3 @@
+pub mod data;
+pub mod equation_node;
+pub mod math;
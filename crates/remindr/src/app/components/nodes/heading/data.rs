@@ -2,6 +2,13 @@ use gpui::SharedString;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+/// Heading levels offered by the editor, with their icon.
+pub const HEADING_LEVELS: [(u32, &str); 3] = [
+    (1, "icons/heading-1.svg"),
+    (2, "icons/heading-2.svg"),
+    (3, "icons/heading-3.svg"),
+];
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HeadingNodeData {
     pub id: Uuid,
@@ -28,6 +35,13 @@ pub struct HeadingMetadata {
     pub level: u32,
 }
 
+impl HeadingMetadata {
+    /// Level within the supported ones, deeper headings showing as the last level.
+    pub fn level(&self) -> u32 {
+        self.level.clamp(1, HEADING_LEVELS.len() as u32)
+    }
+}
+
 impl Default for HeadingMetadata {
     fn default() -> Self {
         Self {
@@ -1,9 +1,14 @@
 use anyhow::{Error, Ok};
+use gpui::prelude::FluentBuilder;
 use gpui::{
-    App, AppContext, BorrowAppContext, Context, Entity, IntoElement, ParentElement, Render,
-    SharedString, Styled, Window, div, px, transparent_white,
+    App, AppContext, Context, Entity, IntoElement, ParentElement, Render, SharedString, Styled,
+    Window, div, px, transparent_white,
+};
+use gpui_component::{
+    ActiveTheme,
+    input::{Input, InputEvent, InputState},
+    label::Label,
 };
-use gpui_component::input::{Input, InputEvent, InputState};
 use serde_json::{Value, from_value, to_value};
 use uuid::Uuid;
 
@@ -13,7 +18,7 @@ use crate::{
         components::{
             nodes::{
                 element::{NodePayload, RemindrElement},
-                heading::data::HeadingNodeData,
+                heading::data::{HEADING_LEVELS, HeadingNodeData},
                 menu_provider::{NodeMenuItem, NodeMenuProvider},
                 node::RemindrNode,
                 text::{
@@ -24,7 +29,10 @@ use crate::{
             },
             slash_menu::{SlashMenu, SlashMenuDismissEvent},
         },
-        states::{document_state::DocumentState, node_state::NodeState},
+        states::{
+            node_state::NodeState,
+            settings_state::{BlockFontSizes, Settings},
+        },
     },
 };
 
@@ -106,11 +114,11 @@ impl HeadingNode {
         }
     }
 
-    pub fn set_level(&mut self, level: u32, window: &mut Window, cx: &mut Context<Self>) {
+    pub fn set_level(&mut self, level: u32, cx: &mut Context<Self>) {
         self.data.metadata.level = level;
-        cx.update_global::<DocumentState, _>(|state, app| {
-            state.mark_changed(window, app);
-        });
+        let node_id = self.data.id;
+        self.state
+            .update(cx, |state, cx| state.node_changed(node_id, cx));
         cx.notify();
     }
 }
@@ -174,36 +182,11 @@ impl TextualNodeDelegate for HeadingNode {
                 state.update(cx, |state, inner_cx| {
                     if !state.get_nodes().is_empty() {
                         let previous_element = state.get_previous_node(node_id);
-                        state.remove_node(node_id);
+                        state.remove_node(node_id, inner_cx);
 
                         if let Some(previous_element) = previous_element {
-                            if let RemindrElement::Text(element) = previous_element.element.clone()
-                            {
-                                let rich_text = element.read(inner_cx).rich_text_state().clone();
-                                rich_text.update(inner_cx, |state, cx| {
-                                    state.focus(window, cx);
-                                    state.move_to_end(cx);
-                                });
-                            }
-
-                            if let RemindrElement::Heading(element) =
-                                previous_element.element.clone()
-                            {
-                                let input = element.read(inner_cx).input_state().clone();
-                                input.update(inner_cx, |input, inner_cx| {
-                                    input.focus(window, inner_cx);
-                                    input.set_cursor_position(
-                                        gpui_component::input::Position::new(u32::MAX, u32::MAX),
-                                        window,
-                                        inner_cx,
-                                    );
-                                });
-                            }
+                            previous_element.element.focus_end(window, inner_cx);
                         }
-
-                        inner_cx.update_global::<DocumentState, _>(|state, app_cx| {
-                            state.mark_changed(window, app_cx);
-                        });
                     }
                 });
             }
@@ -242,16 +225,13 @@ impl TextualNodeDelegate for HeadingNode {
 
                     let node = RemindrNode::new(id, RemindrElement::Text(element));
 
-                    state.insert_node_after(node_id, &node);
-                    inner_cx.update_global::<DocumentState, _>(|state, app| {
-                        state.mark_changed(window, app);
-                    });
+                    state.insert_node_after(node_id, &node, inner_cx);
                 });
             }
             TextualNodeEvent::Change(_) => {
-                cx.update_global::<DocumentState, _>(|state, app_cx| {
-                    state.mark_changed(window, app_cx);
-                });
+                let node_id = self.data.id;
+                self.state
+                    .update(cx, |state, cx| state.node_changed(node_id, cx));
             }
             _ => {}
         }
@@ -261,7 +241,7 @@ impl TextualNodeDelegate for HeadingNode {
 impl NodeMenuProvider for HeadingNode {
     fn menu_items(&self, _cx: &App) -> Vec<NodeMenuItem> {
         let node_id = self.data.id;
-        let current_level = self.data.metadata.level;
+        let current_level = self.data.metadata.level();
         let content = self.data.metadata.content.clone();
 
         let mut items = Vec::new();
@@ -288,16 +268,13 @@ impl NodeMenuProvider for HeadingNode {
                         window,
                         cx,
                     );
-                    state.replace_node(node_id, &node);
+                    state.replace_node(node_id, &node, cx);
                 });
             },
         ));
 
         // Add heading level options (excluding current level)
-        let levels: Vec<(u32, &'static str)> =
-            vec![(2, "icons/heading-2.svg"), (3, "icons/heading-3.svg")];
-
-        for (level, icon) in levels {
+        for (level, icon) in HEADING_LEVELS {
             if level == current_level {
                 continue;
             }
@@ -306,7 +283,7 @@ impl NodeMenuProvider for HeadingNode {
                 format!("heading-level-{}", level),
                 format!("Heading {}", level),
                 icon,
-                move |state, window, cx| {
+                move |state, _, cx| {
                     let heading_entity = {
                         let node = state.read(cx).get_current_nodes(node_id);
                         node.and_then(|n| {
@@ -320,7 +297,7 @@ impl NodeMenuProvider for HeadingNode {
 
                     if let Some(heading) = heading_entity {
                         heading.update(cx, |this, cx| {
-                            this.set_level(level, window, cx);
+                            this.set_level(level, cx);
                         });
                     }
                 },
@@ -332,24 +309,32 @@ impl NodeMenuProvider for HeadingNode {
 }
 
 impl Render for HeadingNode {
-    fn render(&mut self, _: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let level = self.data.metadata.level();
+        let settings = cx.try_global::<Settings>();
+        let font_size = settings
+            .map(|s| s.editor.block_font_sizes.clone())
+            .unwrap_or_else(BlockFontSizes::default)
+            .heading(level);
+
+        let state = self.state.read(cx);
+        let number = state
+            .document_id
+            .filter(|id| settings.is_some_and(|s| s.editor.numbers_headings(*id)))
+            .and_then(|_| state.heading_number(self.data.id, level, cx));
+
         let input = Input::new(&self.input_state)
             .bordered(false)
-            .bg(transparent_white());
-
-        let sized_input = match self.data.metadata.level {
-            1 => input.text_3xl(),
-            2 => input.text_2xl(),
-            3 => input.text_xl(),
-            4 => input.text_lg(),
-            5 => input.text_base(),
-            _ => input.text_sm(),
-        };
+            .bg(transparent_white())
+            .text_size(px(font_size))
+            .when_some(number, |this, number| {
+                this.prefix(Label::new(number).text_color(cx.theme().muted_foreground))
+            });
 
         div()
             .min_w(px(820.0))
             .w_full()
-            .child(sized_input)
+            .child(input)
             .child(self.menu.clone())
     }
 }
@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkNodeData {
+    pub id: Uuid,
+
+    #[serde(rename = "type")]
+    pub node_type: String,
+
+    pub metadata: LinkMetadata,
+}
+
+impl LinkNodeData {
+    pub fn new(id: Uuid, node_type: String, metadata: LinkMetadata) -> Self {
+        Self {
+            id,
+            node_type,
+            metadata,
+        }
+    }
+}
+
+/// Document opened by a link block. `title` is its title when the link was made, shown
+/// without loading the document.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct LinkMetadata {
+    pub document_id: i32,
+    #[serde(default)]
+    pub title: String,
+    /// Heading block of the document the link leads to, none for the top of it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heading: Option<Uuid>,
+}
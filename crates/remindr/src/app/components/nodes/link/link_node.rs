@@ -0,0 +1,86 @@
+use anyhow::Error;
+use gpui::{
+    App, BorrowAppContext, Context, InteractiveElement, IntoElement, ParentElement, Render,
+    StatefulInteractiveElement, Styled, Window, div, px,
+};
+use gpui_component::{ActiveTheme, Icon, Sizable, h_flex, label::Label};
+use serde_json::{Value, from_value};
+
+use crate::app::{
+    components::nodes::{
+        link::data::LinkNodeData,
+        menu_provider::{NodeMenuItem, NodeMenuProvider},
+    },
+    states::{document_state::DocumentState, settings_state::EditorSettings},
+};
+
+pub struct LinkNode {
+    pub data: LinkNodeData,
+}
+
+impl LinkNode {
+    pub fn parse(data: &Value, _: &mut Window, _: &mut Context<Self>) -> Result<Self, Error> {
+        let data = from_value::<LinkNodeData>(data.clone())?;
+
+        Ok(Self { data })
+    }
+
+    /// Opens the linked document in a tab of this window, at the linked heading if any.
+    fn open(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let window_id = window.window_handle().window_id();
+        let metadata = self.data.metadata.clone();
+
+        cx.update_global::<DocumentState, _>(|state, _| {
+            state.mark_recent(metadata.document_id);
+            let workspace = state.workspace_mut(window_id);
+            workspace.open_document_in_folder(metadata.document_id, metadata.title, None);
+            if let Some(heading) = metadata.heading {
+                workspace.focus_block(metadata.document_id, heading);
+            }
+        });
+    }
+}
+
+impl NodeMenuProvider for LinkNode {
+    fn menu_items(&self, _cx: &App) -> Vec<NodeMenuItem> {
+        vec![]
+    }
+}
+
+impl Render for LinkNode {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let title = if self.data.metadata.title.is_empty() {
+            "Untitled".to_string()
+        } else {
+            self.data.metadata.title.clone()
+        };
+
+        div()
+            .min_w(px(820.0))
+            .w_full()
+            .my(EditorSettings::block_margin(cx, true))
+            .child(
+                h_flex()
+                    .id("link")
+                    .gap_2()
+                    .px_2()
+                    .py_1()
+                    .rounded(cx.theme().radius)
+                    .cursor_pointer()
+                    .hover(|this| this.bg(cx.theme().accent.opacity(0.3)))
+                    .on_click(cx.listener(|this, _, window, cx| this.open(window, cx)))
+                    .child(
+                        Icon::default()
+                            .path("icons/file-text.svg")
+                            .small()
+                            .text_color(cx.theme().muted_foreground),
+                    )
+                    .child(
+                        Label::new(title)
+                            .text_sm()
+                            .underline()
+                            .text_color(cx.theme().foreground),
+                    ),
+            )
+    }
+}
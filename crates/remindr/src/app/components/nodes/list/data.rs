@@ -0,0 +1,32 @@
+use gpui::SharedString;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListNodeData {
+    pub id: Uuid,
+
+    #[serde(rename = "type")]
+    pub node_type: String,
+
+    pub metadata: ListMetadata,
+}
+
+impl ListNodeData {
+    pub fn new(id: Uuid, node_type: String, metadata: ListMetadata) -> Self {
+        Self {
+            id,
+            node_type,
+            metadata,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ListMetadata {
+    pub content: SharedString,
+
+    /// Numbered list item when true, bulleted otherwise.
+    #[serde(default)]
+    pub ordered: bool,
+}
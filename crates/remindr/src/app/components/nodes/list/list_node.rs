@@ -0,0 +1,334 @@
+use anyhow::{Error, Ok};
+use gpui::{
+    App, AppContext, Context, Entity, IntoElement, ParentElement, Render, SharedString, Styled,
+    Window, div, px,
+};
+use gpui_component::ActiveTheme;
+use serde_json::{Value, from_value};
+
+use crate::app::{
+    components::{
+        nodes::{
+            element::{NodePayload, RemindrElement},
+            list::data::{ListMetadata, ListNodeData},
+            menu_provider::{NodeMenuItem, NodeMenuProvider},
+            text::data::TextMetadata,
+            textual_node::paste_blocks,
+        },
+        rich_text::{RichTextEvent, RichTextState, RichTextView},
+        slash_menu::{SlashMenu, SlashMenuDismissEvent},
+    },
+    states::{
+        completion_state::CompletionState,
+        node_state::NodeState,
+        settings_state::{EditorSettings, Settings},
+    },
+};
+
+pub struct ListNode {
+    pub state: Entity<NodeState>,
+    pub data: ListNodeData,
+    pub rich_text_state: Entity<RichTextState>,
+    menu: Entity<SlashMenu>,
+    is_focus: bool,
+}
+
+impl ListNode {
+    pub fn parse(
+        data: &Value,
+        state: &Entity<NodeState>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Result<Self, Error> {
+        let data = from_value::<ListNodeData>(data.clone())?;
+
+        let rich_text_state = cx.new(|cx| {
+            let mut state = RichTextState::new(window, cx);
+            if !data.metadata.content.is_empty() {
+                state.set_content(data.metadata.content.to_string(), cx);
+            }
+            state
+        });
+
+        cx.subscribe_in(&rich_text_state, window, {
+            move |this, _, ev: &RichTextEvent, window, cx| match ev {
+                RichTextEvent::Focus => {
+                    this.is_focus = true;
+                    let id = this.data.id;
+                    this.state.update(cx, |state, _| state.set_last_focused(id));
+                }
+                RichTextEvent::Blur => this.is_focus = false,
+                RichTextEvent::Change(content) => {
+                    this.handle_content_change(content.clone(), window, cx)
+                }
+                RichTextEvent::Enter => this.handle_enter(window, cx),
+                RichTextEvent::Backspace => this.handle_backspace(window, cx),
+                RichTextEvent::Slash(selection) => this.handle_slash(selection.clone(), window, cx),
+                RichTextEvent::PasteBlocks(blocks) => {
+                    let replace = this.data.metadata.content.is_empty();
+                    paste_blocks(this.data.id, blocks, replace, &this.state, window, cx);
+                }
+                RichTextEvent::Delete | RichTextEvent::Tab | RichTextEvent::Space => {}
+            }
+        })
+        .detach();
+
+        let menu = cx.new(|cx| SlashMenu::new(data.id, state, window, cx));
+
+        cx.subscribe_in(&menu, window, {
+            move |this, _, event: &SlashMenuDismissEvent, window, cx| {
+                if event.restore_focus {
+                    let rich_text_state = this.rich_text_state.clone();
+                    cx.defer_in(window, move |_, window, cx| {
+                        rich_text_state.update(cx, |state, cx| {
+                            state.focus(window, cx);
+                        });
+                    });
+                }
+            }
+        })
+        .detach();
+
+        Ok(Self {
+            state: state.clone(),
+            data,
+            rich_text_state,
+            menu,
+            is_focus: false,
+        })
+    }
+
+    fn handle_content_change(
+        &mut self,
+        content: SharedString,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.data.metadata.content = content;
+        let node_id = self.data.id;
+        self.state
+            .update(cx, |state, cx| state.node_changed(node_id, cx));
+        CompletionState::refresh_suggestion(&self.rich_text_state, cx);
+    }
+
+    fn handle_slash(
+        &mut self,
+        selection: Option<SharedString>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.is_focus && !self.menu.read(cx).open {
+            self.menu.update(cx, |menu, cx| {
+                menu.set_selection(selection);
+                menu.set_open(true, window, cx);
+            });
+        }
+    }
+
+    fn handle_backspace(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let content = self.rich_text_state.read(cx).content().to_string();
+        if content.is_empty() {
+            self.turn_into_text(window, cx);
+        }
+    }
+
+    fn handle_enter(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.menu.read(cx).open {
+            return;
+        }
+
+        let content = self.rich_text_state.read(cx).content().trim().to_string();
+
+        // Pressing Enter on an empty item leaves the list
+        if content.is_empty() {
+            self.turn_into_text(window, cx);
+            return;
+        }
+
+        self.data.metadata.content = SharedString::from(content);
+        self.is_focus = false;
+
+        let ordered = self.data.metadata.ordered;
+        let kind = if ordered {
+            "numbered_list"
+        } else {
+            "bulleted_list"
+        };
+        let continues = cx
+            .try_global::<Settings>()
+            .is_none_or(|settings| settings.editor.continues_block(&["list", kind]));
+        let payload = if continues {
+            NodePayload::List((
+                ListMetadata {
+                    ordered,
+                    ..Default::default()
+                },
+                true,
+            ))
+        } else {
+            NodePayload::Text((TextMetadata::default(), true))
+        };
+
+        self.state.update(cx, |state, cx| {
+            let node = RemindrElement::create_node(payload, &self.state, window, cx);
+            state.insert_node_after(self.data.id, &node, cx);
+        });
+    }
+
+    fn turn_into_text(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let node_id = self.data.id;
+        let content = self.rich_text_state.read(cx).content().to_string();
+        let state = self.state.clone();
+
+        state.update(cx, |node_state, cx| {
+            let node = RemindrElement::create_node_with_id(
+                node_id,
+                NodePayload::Text((
+                    TextMetadata {
+                        content: SharedString::from(content),
+                    },
+                    true,
+                )),
+                &state,
+                window,
+                cx,
+            );
+            node_state.replace_node(node_id, &node, cx);
+        });
+    }
+
+    /// Position of this item within the run of consecutive numbered items it belongs to.
+    fn ordinal(&self, cx: &App) -> usize {
+        let nodes = self.state.read(cx).get_nodes();
+        let Some(index) = nodes.iter().position(|node| node.id == self.data.id) else {
+            return 1;
+        };
+
+        let preceding = nodes[..index]
+            .iter()
+            .rev()
+            .take_while(|node| match &node.element {
+                RemindrElement::List(list) => list.read(cx).data.metadata.ordered,
+                _ => false,
+            })
+            .count();
+
+        preceding + 1
+    }
+
+    pub fn focus(&self, window: &mut Window, cx: &mut App) {
+        self.rich_text_state.update(cx, |state, cx| {
+            state.focus(window, cx);
+        });
+    }
+}
+
+impl NodeMenuProvider for ListNode {
+    fn menu_items(&self, _cx: &App) -> Vec<NodeMenuItem> {
+        let node_id = self.data.id;
+        let content = self.data.metadata.content.clone();
+        let ordered = self.data.metadata.ordered;
+
+        let text_content = content.clone();
+        let mut items = vec![NodeMenuItem::new(
+            "transform-to-text",
+            "Text",
+            "icons/pilcrow.svg",
+            move |state, window, cx| {
+                let state_clone = state.clone();
+                let content = text_content.clone();
+                state.update(cx, |state, cx| {
+                    let node = RemindrElement::create_node_with_id(
+                        node_id,
+                        NodePayload::Text((TextMetadata { content }, true)),
+                        &state_clone,
+                        window,
+                        cx,
+                    );
+                    state.replace_node(node_id, &node, cx);
+                });
+            },
+        )];
+
+        let (id, label, icon) = if ordered {
+            (
+                "transform-to-bulleted-list",
+                "Bulleted list",
+                "icons/list.svg",
+            )
+        } else {
+            (
+                "transform-to-numbered-list",
+                "Numbered list",
+                "icons/list-ordered.svg",
+            )
+        };
+
+        items.push(NodeMenuItem::new(
+            id,
+            label,
+            icon,
+            move |state, window, cx| {
+                let state_clone = state.clone();
+                let content = content.clone();
+                state.update(cx, |state, cx| {
+                    let node = RemindrElement::create_node_with_id(
+                        node_id,
+                        NodePayload::List((
+                            ListMetadata {
+                                content,
+                                ordered: !ordered,
+                            },
+                            true,
+                        )),
+                        &state_clone,
+                        window,
+                        cx,
+                    );
+                    state.replace_node(node_id, &node, cx);
+                });
+            },
+        ));
+
+        items
+    }
+}
+
+impl Render for ListNode {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let text_font_size = cx
+            .try_global::<Settings>()
+            .map(|s| s.editor.block_font_sizes.text);
+
+        let marker = if self.data.metadata.ordered {
+            format!("{}.", self.ordinal(cx))
+        } else {
+            "•".to_string()
+        };
+
+        let container = div()
+            .min_w(px(820.0))
+            .w_full()
+            .my(EditorSettings::block_margin(cx, true))
+            .flex()
+            .items_start();
+
+        let container = if let Some(size) = text_font_size {
+            container.text_size(px(size))
+        } else {
+            container
+        };
+
+        container
+            .child(
+                div()
+                    .ml_3()
+                    .w_6()
+                    .flex_shrink_0()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(marker),
+            )
+            .child(RichTextView::new(self.rich_text_state.clone()).flex_1())
+            .child(self.menu.clone())
+    }
+}
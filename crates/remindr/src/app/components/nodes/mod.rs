@@ -1,7 +1,17 @@
+pub mod attachment;
 pub mod divider;
+pub mod drawing;
 pub mod element;
+pub mod equation;
 pub mod heading;
+pub mod link;
+pub mod list;
 pub mod menu_provider;
+pub mod migration;
 pub mod node;
+pub mod quote;
+pub mod synced_block;
 pub mod text;
 pub mod textual_node;
+pub mod todo;
+pub mod unsupported;
@@ -1,7 +1,14 @@
 use serde::{Deserialize, Serialize};
+use serde_json::{Value, from_value};
 use uuid::Uuid;
 
-use crate::app::components::nodes::element::RemindrElement;
+use crate::app::components::nodes::{
+    attachment::data::AttachmentNodeData, divider::data::DividerNodeData,
+    drawing::data::DrawingNodeData, element::RemindrElement, equation::data::EquationNodeData,
+    heading::data::HeadingNodeData, link::data::LinkNodeData, list::data::ListNodeData,
+    migration::migrate_block, quote::data::QuoteNodeData, synced_block::data::SyncedBlockNodeData,
+    text::data::TextNodeData, todo::data::TodoNodeData,
+};
 
 #[derive(Clone)]
 pub struct RemindrNode {
@@ -29,4 +36,67 @@ pub enum RemindrNodeType {
     Text,
     Divider,
     Heading,
+    List,
+    Quote,
+    Todo,
+    Attachment,
+    Drawing,
+    Equation,
+    SyncedBlock,
+    Link,
+}
+
+/// How blocks that don't parse into their node are treated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseMode {
+    /// Every block has to parse, e.g. to find the documents with broken blocks.
+    Strict,
+    /// Broken or unknown blocks show as "Unsupported block" placeholders, as the editor
+    /// opens them, so only a content that isn't a list of blocks is a problem.
+    Lenient,
+}
+
+/// Why the blocks of a document can't be opened, e.g. "block 3: missing field `content`",
+/// none when every block parses into its node or, in lenient mode, when the document opens.
+pub fn content_problems(content: &Value, mode: ParseMode) -> Vec<String> {
+    let Some(blocks) = content.as_array() else {
+        return vec!["the content isn't a list of blocks".to_string()];
+    };
+    if mode == ParseMode::Lenient {
+        return Vec::new();
+    }
+
+    blocks
+        .iter()
+        .enumerate()
+        .filter_map(|(index, block)| {
+            block_problem(block).map(|problem| format!("block {}: {}", index + 1, problem))
+        })
+        .collect()
+}
+
+/// Why a block doesn't parse into its node, none when it does.
+pub fn block_problem(value: &Value) -> Option<String> {
+    check_node(value).err().map(|e| e.to_string())
+}
+
+fn check_node(value: &Value) -> Result<(), serde_json::Error> {
+    // Blocks of older versions are upgraded when opened
+    let mut value = value.clone();
+    migrate_block(&mut value);
+
+    let node = from_value::<PartialRemindrNode>(value.clone())?;
+    match node.node_type {
+        RemindrNodeType::Text => from_value::<TextNodeData>(value).map(drop),
+        RemindrNodeType::Divider => from_value::<DividerNodeData>(value).map(drop),
+        RemindrNodeType::Heading => from_value::<HeadingNodeData>(value).map(drop),
+        RemindrNodeType::List => from_value::<ListNodeData>(value).map(drop),
+        RemindrNodeType::Quote => from_value::<QuoteNodeData>(value).map(drop),
+        RemindrNodeType::Todo => from_value::<TodoNodeData>(value).map(drop),
+        RemindrNodeType::Attachment => from_value::<AttachmentNodeData>(value).map(drop),
+        RemindrNodeType::Drawing => from_value::<DrawingNodeData>(value).map(drop),
+        RemindrNodeType::Equation => from_value::<EquationNodeData>(value).map(drop),
+        RemindrNodeType::SyncedBlock => from_value::<SyncedBlockNodeData>(value).map(drop),
+        RemindrNodeType::Link => from_value::<LinkNodeData>(value).map(drop),
+    }
 }
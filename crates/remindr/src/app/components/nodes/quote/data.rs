@@ -0,0 +1,28 @@
+use gpui::SharedString;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuoteNodeData {
+    pub id: Uuid,
+
+    #[serde(rename = "type")]
+    pub node_type: String,
+
+    pub metadata: QuoteMetadata,
+}
+
+impl QuoteNodeData {
+    pub fn new(id: Uuid, node_type: String, metadata: QuoteMetadata) -> Self {
+        Self {
+            id,
+            node_type,
+            metadata,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct QuoteMetadata {
+    pub content: SharedString,
+}
@@ -0,0 +1,223 @@
+use anyhow::{Error, Ok};
+use gpui::{
+    App, AppContext, Context, Entity, IntoElement, ParentElement, Render, SharedString, Styled,
+    Window, div, px,
+};
+use gpui_component::ActiveTheme;
+use serde_json::{Value, from_value};
+
+use crate::app::{
+    components::{
+        nodes::{
+            element::{NodePayload, RemindrElement},
+            menu_provider::{NodeMenuItem, NodeMenuProvider},
+            quote::data::QuoteNodeData,
+            text::data::TextMetadata,
+            textual_node::paste_blocks,
+        },
+        rich_text::{RichTextEvent, RichTextState, RichTextView},
+        slash_menu::{SlashMenu, SlashMenuDismissEvent},
+    },
+    states::{
+        completion_state::CompletionState,
+        node_state::NodeState,
+        settings_state::{EditorSettings, Settings},
+    },
+};
+
+pub struct QuoteNode {
+    pub state: Entity<NodeState>,
+    pub data: QuoteNodeData,
+    pub rich_text_state: Entity<RichTextState>,
+    menu: Entity<SlashMenu>,
+    is_focus: bool,
+}
+
+impl QuoteNode {
+    pub fn parse(
+        data: &Value,
+        state: &Entity<NodeState>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Result<Self, Error> {
+        let data = from_value::<QuoteNodeData>(data.clone())?;
+
+        let rich_text_state = cx.new(|cx| {
+            let mut state = RichTextState::new(window, cx);
+            if !data.metadata.content.is_empty() {
+                state.set_content(data.metadata.content.to_string(), cx);
+            }
+            state
+        });
+
+        cx.subscribe_in(&rich_text_state, window, {
+            move |this, _, ev: &RichTextEvent, window, cx| match ev {
+                RichTextEvent::Focus => {
+                    this.is_focus = true;
+                    let id = this.data.id;
+                    this.state.update(cx, |state, _| state.set_last_focused(id));
+                }
+                RichTextEvent::Blur => this.is_focus = false,
+                RichTextEvent::Change(content) => {
+                    this.data.metadata.content = content.clone();
+                    let node_id = this.data.id;
+                    this.state
+                        .update(cx, |state, cx| state.node_changed(node_id, cx));
+                    CompletionState::refresh_suggestion(&this.rich_text_state, cx);
+                }
+                RichTextEvent::Enter => this.handle_enter(window, cx),
+                RichTextEvent::Backspace => this.handle_backspace(window, cx),
+                RichTextEvent::Slash(selection) => this.handle_slash(selection.clone(), window, cx),
+                RichTextEvent::PasteBlocks(blocks) => {
+                    let replace = this.data.metadata.content.is_empty();
+                    paste_blocks(this.data.id, blocks, replace, &this.state, window, cx);
+                }
+                RichTextEvent::Delete | RichTextEvent::Tab | RichTextEvent::Space => {}
+            }
+        })
+        .detach();
+
+        let menu = cx.new(|cx| SlashMenu::new(data.id, state, window, cx));
+
+        cx.subscribe_in(&menu, window, {
+            move |this, _, event: &SlashMenuDismissEvent, window, cx| {
+                if event.restore_focus {
+                    let rich_text_state = this.rich_text_state.clone();
+                    cx.defer_in(window, move |_, window, cx| {
+                        rich_text_state.update(cx, |state, cx| {
+                            state.focus(window, cx);
+                        });
+                    });
+                }
+            }
+        })
+        .detach();
+
+        Ok(Self {
+            state: state.clone(),
+            data,
+            rich_text_state,
+            menu,
+            is_focus: false,
+        })
+    }
+
+    fn handle_slash(
+        &mut self,
+        selection: Option<SharedString>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.is_focus && !self.menu.read(cx).open {
+            self.menu.update(cx, |menu, cx| {
+                menu.set_selection(selection);
+                menu.set_open(true, window, cx);
+            });
+        }
+    }
+
+    fn handle_backspace(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let content = self.rich_text_state.read(cx).content().to_string();
+        if !content.is_empty() {
+            return;
+        }
+
+        // An empty quote falls back to a plain text block
+        let node_id = self.data.id;
+        let state = self.state.clone();
+        state.update(cx, |node_state, cx| {
+            let node = RemindrElement::create_node_with_id(
+                node_id,
+                NodePayload::Text((TextMetadata::default(), true)),
+                &state,
+                window,
+                cx,
+            );
+            node_state.replace_node(node_id, &node, cx);
+        });
+    }
+
+    fn handle_enter(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.menu.read(cx).open {
+            return;
+        }
+
+        let content = self.rich_text_state.read(cx).content().trim().to_string();
+        self.data.metadata.content = SharedString::from(content);
+        self.is_focus = false;
+
+        self.state.update(cx, |state, cx| {
+            let node = RemindrElement::create_node(
+                NodePayload::Text((TextMetadata::default(), true)),
+                &self.state,
+                window,
+                cx,
+            );
+            state.insert_node_after(self.data.id, &node, cx);
+        });
+    }
+
+    pub fn focus(&self, window: &mut Window, cx: &mut App) {
+        self.rich_text_state.update(cx, |state, cx| {
+            state.focus(window, cx);
+        });
+    }
+}
+
+impl NodeMenuProvider for QuoteNode {
+    fn menu_items(&self, _cx: &App) -> Vec<NodeMenuItem> {
+        let node_id = self.data.id;
+        let content = self.data.metadata.content.clone();
+
+        vec![NodeMenuItem::new(
+            "transform-to-text",
+            "Text",
+            "icons/pilcrow.svg",
+            move |state, window, cx| {
+                let state_clone = state.clone();
+                let content = content.clone();
+                state.update(cx, |state, cx| {
+                    let node = RemindrElement::create_node_with_id(
+                        node_id,
+                        NodePayload::Text((TextMetadata { content }, true)),
+                        &state_clone,
+                        window,
+                        cx,
+                    );
+                    state.replace_node(node_id, &node, cx);
+                });
+            },
+        )]
+    }
+}
+
+impl Render for QuoteNode {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let text_font_size = cx
+            .try_global::<Settings>()
+            .map(|s| s.editor.block_font_sizes.text);
+
+        let container = div()
+            .min_w(px(820.0))
+            .w_full()
+            .my(EditorSettings::block_margin(cx, false));
+
+        let container = if let Some(size) = text_font_size {
+            container.text_size(px(size))
+        } else {
+            container
+        };
+
+        container
+            .child(
+                div()
+                    .ml_3()
+                    .pl_3()
+                    .border_l_2()
+                    .border_color(cx.theme().border)
+                    .text_color(cx.theme().muted_foreground)
+                    .child(RichTextView::new(self.rich_text_state.clone())),
+            )
+            .child(self.menu.clone())
+    }
+}
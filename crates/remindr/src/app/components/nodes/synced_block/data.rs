@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncedBlockNodeData {
+    pub id: Uuid,
+
+    #[serde(rename = "type")]
+    pub node_type: String,
+
+    pub metadata: SyncedBlockMetadata,
+}
+
+impl SyncedBlockNodeData {
+    pub fn new(id: Uuid, node_type: String, metadata: SyncedBlockMetadata) -> Self {
+        Self {
+            id,
+            node_type,
+            metadata,
+        }
+    }
+}
+
+/// Reference to the shared content of a synced block. `content` is a copy of it kept for
+/// previews and exports, the shared one being read from the synced blocks storage.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SyncedBlockMetadata {
+    pub synced_block_id: Option<i32>,
+    #[serde(default)]
+    pub content: String,
+}
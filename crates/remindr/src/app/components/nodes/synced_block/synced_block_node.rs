@@ -0,0 +1,299 @@
+use anyhow::Error;
+use gpui::{
+    App, AppContext, BorrowAppContext, Context, Entity, InteractiveElement, IntoElement,
+    ParentElement, Render, SharedString, StatefulInteractiveElement, Styled, Window, div, px,
+    transparent_white,
+};
+use gpui_component::{
+    ActiveTheme, Icon, Sizable, WindowExt,
+    button::{Button, ButtonVariants},
+    h_flex,
+    input::{Input, InputEvent, InputState},
+    label::Label,
+    v_flex,
+};
+use serde_json::{Value, from_value};
+
+use crate::{
+    app::{
+        components::nodes::{
+            element::{NodePayload, RemindrElement},
+            menu_provider::{NodeMenuItem, NodeMenuProvider},
+            synced_block::data::SyncedBlockNodeData,
+            text::data::TextMetadata,
+        },
+        states::{
+            node_state::NodeState, repository_state::RepositoryState,
+            settings_state::EditorSettings, synced_block_state::SyncedBlockState,
+        },
+    },
+    domain::database::synced_block::SyncedBlockModel,
+};
+
+pub struct SyncedBlockNode {
+    pub state: Entity<NodeState>,
+    pub data: SyncedBlockNodeData,
+    input_state: Entity<InputState>,
+    /// Existing synced blocks offered while no block is picked.
+    choices: Vec<SyncedBlockModel>,
+}
+
+impl SyncedBlockNode {
+    pub fn parse(
+        data: &Value,
+        state: &Entity<NodeState>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Result<Self, Error> {
+        let data = from_value::<SyncedBlockNodeData>(data.clone())?;
+
+        let input_state = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("Shared content")
+                .default_value(data.metadata.content.clone())
+                .auto_grow(1, f32::INFINITY as usize)
+                .soft_wrap(true)
+        });
+
+        cx.subscribe_in(&input_state, window, {
+            move |this, _, event: &InputEvent, _, cx| {
+                if let InputEvent::Change = event {
+                    this.handle_change(cx);
+                }
+            }
+        })
+        .detach();
+
+        // Picks up the edits made to the same block in other documents
+        cx.observe_global_in::<SyncedBlockState>(window, |this, window, cx| {
+            this.sync(window, cx);
+        })
+        .detach();
+
+        match data.metadata.synced_block_id {
+            Some(id) => SyncedBlockState::load(id, cx),
+            None => Self::load_choices(cx),
+        }
+
+        Ok(Self {
+            state: state.clone(),
+            data,
+            input_state,
+            choices: Vec::new(),
+        })
+    }
+
+    fn load_choices(cx: &mut Context<Self>) {
+        let repository = cx.global::<RepositoryState>().synced_blocks.clone();
+        cx.spawn(async move |this, cx| {
+            let choices = repository.get_synced_blocks().await?;
+            this.update(cx, |this, cx| {
+                this.choices = choices;
+                cx.notify();
+            })?;
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
+    fn handle_change(&mut self, cx: &mut Context<Self>) {
+        let content = self.input_state.read(cx).value().to_string();
+        if content == self.data.metadata.content {
+            return;
+        }
+
+        self.data.metadata.content = content.clone();
+        if let Some(id) = self.data.metadata.synced_block_id {
+            SyncedBlockState::update(id, content, cx);
+        }
+        self.mark_changed(cx);
+    }
+
+    /// Shows the shared content when it was changed from another instance of the block.
+    fn sync(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(id) = self.data.metadata.synced_block_id else {
+            return;
+        };
+        let Some(content) = cx.global::<SyncedBlockState>().get(id) else {
+            return;
+        };
+        if content == self.data.metadata.content {
+            return;
+        }
+
+        let content = content.to_string();
+        self.data.metadata.content = content.clone();
+        self.input_state.update(cx, |input_state, cx| {
+            input_state.set_value(content, window, cx);
+        });
+        cx.notify();
+    }
+
+    /// Creates a new synced block from the content typed so far.
+    fn create(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let repository = cx.global::<RepositoryState>().synced_blocks.clone();
+        let content = self.data.metadata.content.clone();
+        let window_handle = window.window_handle();
+
+        cx.spawn(async move |this, cx| {
+            let result = repository.insert_synced_block(content).await;
+
+            cx.update_window(window_handle, |_, window, cx| match result {
+                Ok(block) => {
+                    let _ = this.update(cx, |this, cx| this.pick(block, window, cx));
+                }
+                Err(e) => {
+                    tracing::error!("failed to create synced block: {:?}", e);
+                    window.push_notification("Failed to create the synced block", cx);
+                }
+            })?;
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
+    fn pick(&mut self, block: SyncedBlockModel, window: &mut Window, cx: &mut Context<Self>) {
+        self.data.metadata.synced_block_id = Some(block.id);
+        self.data.metadata.content = block.content.clone();
+        self.choices.clear();
+
+        let content = block.content.clone();
+        self.input_state.update(cx, |input_state, cx| {
+            input_state.set_value(content, window, cx);
+        });
+        cx.update_global::<SyncedBlockState, _>(|state, _| state.insert(block));
+        self.mark_changed(cx);
+        cx.notify();
+    }
+
+    fn mark_changed(&self, cx: &mut Context<Self>) {
+        let node_id = self.data.id;
+        self.state
+            .update(cx, |state, cx| state.node_changed(node_id, cx));
+    }
+
+    fn render_picker(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .gap_2()
+            .px_3()
+            .py_2()
+            .rounded(cx.theme().radius)
+            .border_1()
+            .border_color(cx.theme().border)
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        Icon::default()
+                            .path("icons/refresh-cw.svg")
+                            .small()
+                            .text_color(cx.theme().muted_foreground),
+                    )
+                    .child(
+                        Label::new("Synced block")
+                            .text_sm()
+                            .text_color(cx.theme().muted_foreground),
+                    )
+                    .child(div().flex_1())
+                    .child(
+                        Button::new("synced-block-create")
+                            .ghost()
+                            .small()
+                            .label("New synced block")
+                            .on_click(cx.listener(|this, _, window, cx| this.create(window, cx))),
+                    ),
+            )
+            .children(self.choices.iter().map(|block| {
+                let choice = block.clone();
+                let preview = block.content.lines().next().unwrap_or_default().to_string();
+
+                h_flex()
+                    .id(SharedString::from(format!("synced-block-{}", block.id)))
+                    .gap_2()
+                    .px_2()
+                    .py_1()
+                    .rounded(cx.theme().radius)
+                    .cursor_pointer()
+                    .hover(|this| this.bg(cx.theme().accent.opacity(0.3)))
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.pick(choice.clone(), window, cx);
+                    }))
+                    .child(
+                        div()
+                            .flex_1()
+                            .min_w_0()
+                            .truncate()
+                            .text_sm()
+                            .text_color(cx.theme().foreground)
+                            .child(if preview.is_empty() {
+                                "Empty synced block".to_string()
+                            } else {
+                                preview
+                            }),
+                    )
+            }))
+    }
+}
+
+impl NodeMenuProvider for SyncedBlockNode {
+    fn menu_items(&self, _cx: &App) -> Vec<NodeMenuItem> {
+        let node_id = self.data.id;
+        let content = self.data.metadata.content.clone();
+
+        // Unsyncing keeps a copy of the content in a plain text block
+        vec![NodeMenuItem::new(
+            "unsync",
+            "Unsync",
+            "icons/pilcrow.svg",
+            move |state, window, cx| {
+                let state_clone = state.clone();
+                let content = SharedString::from(content.clone());
+                state.update(cx, |state, cx| {
+                    let node = RemindrElement::create_node_with_id(
+                        node_id,
+                        NodePayload::Text((TextMetadata { content }, true)),
+                        &state_clone,
+                        window,
+                        cx,
+                    );
+                    state.replace_node(node_id, &node, cx);
+                });
+            },
+        )]
+    }
+}
+
+impl Render for SyncedBlockNode {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let container = div()
+            .min_w(px(820.0))
+            .w_full()
+            .my(EditorSettings::block_margin(cx, false));
+
+        if self.data.metadata.synced_block_id.is_none() {
+            return container.child(self.render_picker(cx));
+        }
+
+        container.child(
+            v_flex()
+                .pl_3()
+                .border_l_2()
+                .border_color(cx.theme().primary)
+                .child(
+                    h_flex()
+                        .gap_1()
+                        .text_xs()
+                        .text_color(cx.theme().muted_foreground)
+                        .child(Icon::default().path("icons/refresh-cw.svg").xsmall())
+                        .child("Synced block"),
+                )
+                .child(
+                    Input::new(&self.input_state)
+                        .bordered(false)
+                        .bg(transparent_white()),
+                ),
+        )
+    }
+}
@@ -1,7 +1,7 @@
 use anyhow::{Error, Ok};
 use gpui::{
-    App, AppContext, BorrowAppContext, Context, Entity, IntoElement, ParentElement, Render,
-    SharedString, Styled, Window, div, px,
+    App, AppContext, Context, Entity, IntoElement, ParentElement, Render, SharedString, Styled,
+    Window, div, px,
 };
 use serde_json::{Value, from_value};
 
@@ -9,14 +9,17 @@ use crate::app::{
     components::{
         nodes::{
             element::{NodePayload, RemindrElement},
-            heading::data::HeadingMetadata,
+            heading::data::{HEADING_LEVELS, HeadingMetadata},
             menu_provider::{NodeMenuItem, NodeMenuProvider},
             text::data::{TextMetadata, TextNodeData},
+            textual_node::{MarkdownShortcut, paste_blocks},
         },
         rich_text::{RichTextEvent, RichTextState, RichTextView},
         slash_menu::{SlashMenu, SlashMenuDismissEvent},
     },
-    states::{document_state::DocumentState, node_state::NodeState},
+    states::{
+        completion_state::CompletionState, node_state::NodeState, settings_state::EditorSettings,
+    },
 };
 
 pub struct TextNode {
@@ -54,7 +57,11 @@ impl TextNode {
                 RichTextEvent::Enter => this.handle_enter(window, cx),
                 RichTextEvent::Backspace => this.handle_backspace(window, cx),
                 RichTextEvent::Delete => this.handle_delete(window, cx),
-                RichTextEvent::Slash => this.handle_slash(window, cx),
+                RichTextEvent::Slash(selection) => this.handle_slash(selection.clone(), window, cx),
+                RichTextEvent::PasteBlocks(blocks) => {
+                    let replace = this.data.metadata.content.is_empty();
+                    paste_blocks(this.data.id, blocks, replace, &this.state, window, cx);
+                }
                 RichTextEvent::Tab | RichTextEvent::Space => {}
             }
         })
@@ -85,8 +92,10 @@ impl TextNode {
         })
     }
 
-    fn handle_focus(&mut self, _window: &mut Window, _cx: &mut Context<Self>) {
+    fn handle_focus(&mut self, _window: &mut Window, cx: &mut Context<Self>) {
         self.is_focus = true;
+        let id = self.data.id;
+        self.state.update(cx, |state, _| state.set_last_focused(id));
     }
 
     fn handle_blur(&mut self, _window: &mut Window, _cx: &mut Context<Self>) {
@@ -103,19 +112,30 @@ impl TextNode {
 
         if old_content.is_empty() && content.is_empty() {
             self.handle_empty(window, cx);
+        } else if let Some(shortcut) =
+            MarkdownShortcut::detect(&content).filter(|shortcut| shortcut.is_enabled(cx))
+        {
+            shortcut.apply(self.data.id, &self.state, window, cx);
         } else {
             self.data.metadata.content = content;
-            cx.update_global::<DocumentState, _>(|state, app_cx| {
-                state.mark_changed(window, app_cx);
-            });
+            let node_id = self.data.id;
+            self.state
+                .update(cx, |state, cx| state.node_changed(node_id, cx));
+            CompletionState::refresh_suggestion(&self.rich_text_state, cx);
         }
     }
 
-    fn handle_slash(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+    fn handle_slash(
+        &mut self,
+        selection: Option<SharedString>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
         if self.is_focus {
             let menu_open = self.menu.read(cx).open;
             if !menu_open {
                 self.menu.update(cx, |menu, cx| {
+                    menu.set_selection(selection);
                     menu.set_open(true, window, cx);
                 });
             }
@@ -140,34 +160,11 @@ impl TextNode {
         state.update(cx, |state, inner_cx| {
             if !state.get_nodes().is_empty() {
                 let previous_element = state.get_previous_node(node_id);
-                state.remove_node(node_id);
+                state.remove_node(node_id, inner_cx);
 
                 if let Some(previous_element) = previous_element {
-                    if let RemindrElement::Text(element) = previous_element.element.clone() {
-                        let rich_text = element.read(inner_cx).rich_text_state.clone();
-                        rich_text.update(inner_cx, |state, cx| {
-                            state.focus(window, cx);
-                            state.move_to_end(cx);
-                        });
-                    }
-
-                    if let RemindrElement::Heading(element) = previous_element.element.clone() {
-                        element.update(inner_cx, |heading, inner_cx| {
-                            heading.input_state.update(inner_cx, |input, inner_cx| {
-                                input.focus(window, inner_cx);
-                                input.set_cursor_position(
-                                    gpui_component::input::Position::new(u32::MAX, u32::MAX),
-                                    window,
-                                    inner_cx,
-                                );
-                            });
-                        });
-                    }
+                    previous_element.element.focus_end(window, inner_cx);
                 }
-
-                inner_cx.update_global::<DocumentState, _>(|state, app_cx| {
-                    state.mark_changed(window, app_cx);
-                });
             }
         });
     }
@@ -184,20 +181,23 @@ impl TextNode {
         self.is_focus = false;
 
         self.state.update(cx, |state, cx| {
-            state.insert_node_after(
-                self.data.id,
-                &RemindrElement::create_node(
-                    NodePayload::Text((TextMetadata::default(), true)),
-                    &self.state,
-                    window,
-                    cx,
-                ),
+            let node = RemindrElement::create_node(
+                NodePayload::Text((TextMetadata::default(), true)),
+                &self.state,
+                window,
+                cx,
             );
+            state.insert_node_after(self.data.id, &node, cx);
         });
+    }
 
-        cx.update_global::<DocumentState, _>(|state, app_cx| {
-            state.mark_changed(window, app_cx);
+    /// Replaces the content programmatically, e.g. when text is streamed into the block.
+    pub fn set_text(&mut self, content: SharedString, cx: &mut Context<Self>) {
+        self.rich_text_state.update(cx, |state, cx| {
+            state.set_content(content.to_string(), cx);
         });
+        self.data.metadata.content = content;
+        cx.notify();
     }
 
     pub fn rich_text_state(&self) -> &Entity<RichTextState> {
@@ -222,10 +222,7 @@ impl NodeMenuProvider for TextNode {
         let node_id = self.data.id;
         let content = self.data.metadata.content.clone();
 
-        let levels: Vec<(u32, &'static str)> =
-            vec![(2, "icons/heading-2.svg"), (3, "icons/heading-3.svg")];
-
-        levels
+        HEADING_LEVELS
             .into_iter()
             .map(|(level, icon)| {
                 let content = content.clone();
@@ -250,7 +247,7 @@ impl NodeMenuProvider for TextNode {
                                 window,
                                 cx,
                             );
-                            state.replace_node(node_id, &node);
+                            state.replace_node(node_id, &node, cx);
                         });
                     },
                 )
@@ -265,7 +262,10 @@ impl Render for TextNode {
             .try_global::<crate::app::states::settings_state::Settings>()
             .map(|s| s.editor.block_font_sizes.text);
 
-        let container = div().min_w(px(820.0)).w_full().my_2();
+        let container = div()
+            .min_w(px(820.0))
+            .w_full()
+            .my(EditorSettings::block_margin(cx, false));
 
         let container = if let Some(size) = text_font_size {
             container.text_size(px(size))
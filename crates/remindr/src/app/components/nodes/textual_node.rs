@@ -1,8 +1,51 @@
 use gpui::{App, Context, Entity, SharedString, Window};
 use gpui_component::input::{InputState, Position};
+use serde_json::Value;
 use uuid::Uuid;
 
-use crate::app::{components::slash_menu::SlashMenu, states::node_state::NodeState};
+use crate::app::{
+    components::{
+        nodes::{
+            element::{NodePayload, RemindrElement},
+            heading::data::HeadingMetadata,
+            list::data::ListMetadata,
+            quote::data::QuoteMetadata,
+            text::data::TextMetadata,
+            todo::data::TodoMetadata,
+        },
+        slash_menu::SlashMenu,
+    },
+    states::{node_state::NodeState, settings_state::Settings},
+};
+
+/// Inserts blocks pasted in the node `node_id` after it and focuses the last one. The node
+/// is replaced when `replace` is set, e.g. when it was empty.
+pub fn paste_blocks(
+    node_id: Uuid,
+    blocks: &[Value],
+    replace: bool,
+    state: &Entity<NodeState>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    state.update(cx, |node_state, cx| {
+        let mut previous_id = node_id;
+        let mut last = None;
+        for value in blocks {
+            let node = node_state.parse_node(value, state, window, cx);
+            node_state.insert_node_after(previous_id, &node, cx);
+            previous_id = node.id;
+            last = Some(node);
+        }
+
+        if replace && last.is_some() {
+            node_state.remove_node(node_id, cx);
+        }
+        if let Some(last) = last {
+            last.element.focus_end(window, cx);
+        }
+    });
+}
 
 /// Events emitted by a TextualNode during user interaction.
 #[derive(Debug, Clone)]
@@ -109,6 +152,9 @@ pub trait TextualNodeDelegate: TextualNode + Sized {
     /// Handles focus event: updates the focused state and emits the Focus event.
     fn handle_focus(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         self.set_focused(true);
+        let id = self.node_id();
+        self.node_state()
+            .update(cx, |state, _| state.set_last_focused(id));
         self.on_textual_event(TextualNodeEvent::Focus, window, cx);
     }
 
@@ -118,3 +164,153 @@ pub trait TextualNodeDelegate: TextualNode + Sized {
         self.on_textual_event(TextualNodeEvent::Blur, window, cx);
     }
 }
+
+/// Block conversions triggered by typing a markdown prefix into an empty node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarkdownShortcut {
+    /// `# `, `## ` or `### `
+    Heading(u32),
+    /// `- ` or `* `
+    BulletedList,
+    /// `1. `
+    NumberedList,
+    /// `---`
+    Divider,
+    /// `> `
+    Quote,
+    /// `[] ` or `[ ] `
+    Todo,
+}
+
+impl MarkdownShortcut {
+    /// Detects a shortcut when the whole content of a node is exactly a markdown prefix.
+    pub fn detect(content: &str) -> Option<Self> {
+        match content {
+            "# " => Some(Self::Heading(1)),
+            "## " => Some(Self::Heading(2)),
+            "### " => Some(Self::Heading(3)),
+            "- " | "* " => Some(Self::BulletedList),
+            "1. " => Some(Self::NumberedList),
+            "---" => Some(Self::Divider),
+            "> " => Some(Self::Quote),
+            "[] " | "[ ] " => Some(Self::Todo),
+            _ => None,
+        }
+    }
+
+    /// Returns the block identifiers that can disable this shortcut in the editor settings.
+    fn block_ids(&self) -> Vec<String> {
+        match self {
+            Self::Heading(level) => vec!["heading".to_string(), format!("heading_{}", level)],
+            Self::BulletedList => vec!["list".to_string(), "bulleted_list".to_string()],
+            Self::NumberedList => vec!["list".to_string(), "numbered_list".to_string()],
+            Self::Divider => vec!["divider".to_string()],
+            Self::Quote => vec!["quote".to_string()],
+            Self::Todo => vec!["todo".to_string()],
+        }
+    }
+
+    /// Returns whether the target block type is enabled in the settings.
+    pub fn is_enabled(&self, cx: &App) -> bool {
+        let Some(settings) = cx.try_global::<Settings>() else {
+            return true;
+        };
+
+        !self
+            .block_ids()
+            .iter()
+            .any(|id| settings.editor.disabled_blocks.contains(id))
+    }
+
+    fn payload(&self) -> NodePayload {
+        match *self {
+            Self::Heading(level) => NodePayload::Heading((
+                HeadingMetadata {
+                    level,
+                    content: SharedString::default(),
+                },
+                true,
+            )),
+            Self::BulletedList => NodePayload::List((ListMetadata::default(), true)),
+            Self::NumberedList => NodePayload::List((
+                ListMetadata {
+                    ordered: true,
+                    ..Default::default()
+                },
+                true,
+            )),
+            Self::Divider => NodePayload::Divider,
+            Self::Quote => NodePayload::Quote((QuoteMetadata::default(), true)),
+            Self::Todo => NodePayload::Todo((TodoMetadata::default(), true)),
+        }
+    }
+
+    /// Replaces the node `node_id` with the block this shortcut stands for.
+    ///
+    /// The new block keeps the node id so its position in the document is preserved.
+    /// A divider is followed by a fresh text node that receives the focus.
+    pub fn apply(
+        &self,
+        node_id: Uuid,
+        state: &Entity<NodeState>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let payload = self.payload();
+        let is_divider = *self == Self::Divider;
+
+        state.update(cx, |node_state, cx| {
+            let node = RemindrElement::create_node_with_id(node_id, payload, state, window, cx);
+            node_state.replace_node(node_id, &node, cx);
+
+            if is_divider {
+                let text = RemindrElement::create_node(
+                    NodePayload::Text((TextMetadata::default(), true)),
+                    state,
+                    window,
+                    cx,
+                );
+                node_state.insert_node_after(node_id, &text, cx);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_each_prefix() {
+        let cases = [
+            ("# ", MarkdownShortcut::Heading(1)),
+            ("## ", MarkdownShortcut::Heading(2)),
+            ("### ", MarkdownShortcut::Heading(3)),
+            ("- ", MarkdownShortcut::BulletedList),
+            ("* ", MarkdownShortcut::BulletedList),
+            ("1. ", MarkdownShortcut::NumberedList),
+            ("---", MarkdownShortcut::Divider),
+            ("> ", MarkdownShortcut::Quote),
+            ("[] ", MarkdownShortcut::Todo),
+            ("[ ] ", MarkdownShortcut::Todo),
+        ];
+
+        for (content, shortcut) in cases {
+            assert_eq!(
+                MarkdownShortcut::detect(content),
+                Some(shortcut),
+                "{:?}",
+                content
+            );
+        }
+    }
+
+    #[test]
+    fn ignores_near_misses() {
+        for content in [
+            "", "#", "#x", "# x", "####", "-", "1)", "1) ", "2. ", "--", ">x", "[x] ",
+        ] {
+            assert_eq!(MarkdownShortcut::detect(content), None, "{:?}", content);
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use gpui::SharedString;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, from_value, to_value};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TodoNodeData {
+    pub id: Uuid,
+
+    #[serde(rename = "type")]
+    pub node_type: String,
+
+    pub metadata: TodoMetadata,
+}
+
+impl TodoNodeData {
+    pub fn new(id: Uuid, node_type: String, metadata: TodoMetadata) -> Self {
+        Self {
+            id,
+            node_type,
+            metadata,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct TodoMetadata {
+    pub content: SharedString,
+    #[serde(default)]
+    pub status: TodoStatus,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TodoStatus {
+    #[default]
+    Todo,
+    InProgress,
+    Done,
+}
+
+impl TodoStatus {
+    pub const ALL: [TodoStatus; 3] = [TodoStatus::Todo, TodoStatus::InProgress, TodoStatus::Done];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TodoStatus::Todo => "To do",
+            TodoStatus::InProgress => "In progress",
+            TodoStatus::Done => "Done",
+        }
+    }
+
+    pub fn icon_path(&self) -> &'static str {
+        match self {
+            TodoStatus::Todo => "icons/square.svg",
+            TodoStatus::InProgress => "icons/square-dot.svg",
+            TodoStatus::Done => "icons/square-check.svg",
+        }
+    }
+
+    /// Status reached by clicking the checkbox of a block: open tasks are completed and
+    /// completed ones are reopened.
+    pub fn toggled(&self) -> Self {
+        match self {
+            TodoStatus::Todo | TodoStatus::InProgress => TodoStatus::Done,
+            TodoStatus::Done => TodoStatus::Todo,
+        }
+    }
+}
+
+/// To-do blocks found in the content of a document.
+pub fn todo_blocks(content: &Value) -> impl Iterator<Item = TodoNodeData> + '_ {
+    content
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|block| block.get("type").and_then(Value::as_str) == Some("todo"))
+        .filter_map(|block| from_value::<TodoNodeData>(block.clone()).ok())
+}
+
+/// Sets the status of a to-do block in the content of a document. Returns whether the block
+/// was found.
+pub fn set_todo_status(content: &mut Value, block_id: Uuid, status: TodoStatus) -> bool {
+    let Some(block) = content.as_array_mut().into_iter().flatten().find(|block| {
+        block.get("id").and_then(Value::as_str) == Some(block_id.to_string().as_str())
+    }) else {
+        return false;
+    };
+    let Some(metadata) = block.get_mut("metadata").and_then(Value::as_object_mut) else {
+        return false;
+    };
+
+    match to_value(status) {
+        Ok(status) => {
+            metadata.insert("status".to_string(), status);
+            true
+        }
+        Err(_) => false,
+    }
+}
@@ -0,0 +1,323 @@
+use anyhow::{Error, Ok};
+use gpui::prelude::FluentBuilder;
+use gpui::{
+    App, AppContext, BorrowAppContext, Context, Entity, InteractiveElement, IntoElement,
+    ParentElement, Render, SharedString, StatefulInteractiveElement, Styled, Task, Window, div, px,
+};
+use gpui_component::{ActiveTheme, Icon};
+use serde_json::{Value, from_value};
+use uuid::Uuid;
+
+use crate::app::{
+    components::{
+        nodes::{
+            element::{NodePayload, RemindrElement},
+            menu_provider::{NodeMenuItem, NodeMenuProvider},
+            text::data::TextMetadata,
+            textual_node::paste_blocks,
+            todo::data::{TodoMetadata, TodoNodeData, TodoStatus, set_todo_status},
+        },
+        rich_text::{RichTextEvent, RichTextState, RichTextView},
+        slash_menu::{SlashMenu, SlashMenuDismissEvent},
+    },
+    states::{
+        completion_state::CompletionState,
+        document_state::DocumentState,
+        node_state::NodeState,
+        repository_state::RepositoryState,
+        settings_state::{EditorSettings, Settings},
+    },
+};
+
+pub struct TodoNode {
+    pub state: Entity<NodeState>,
+    pub data: TodoNodeData,
+    pub rich_text_state: Entity<RichTextState>,
+    menu: Entity<SlashMenu>,
+    is_focus: bool,
+}
+
+impl TodoNode {
+    pub fn parse(
+        data: &Value,
+        state: &Entity<NodeState>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Result<Self, Error> {
+        let data = from_value::<TodoNodeData>(data.clone())?;
+
+        let rich_text_state = cx.new(|cx| {
+            let mut state = RichTextState::new(window, cx);
+            if !data.metadata.content.is_empty() {
+                state.set_content(data.metadata.content.to_string(), cx);
+            }
+            state
+        });
+
+        cx.subscribe_in(&rich_text_state, window, {
+            move |this, _, ev: &RichTextEvent, window, cx| match ev {
+                RichTextEvent::Focus => {
+                    this.is_focus = true;
+                    let id = this.data.id;
+                    this.state.update(cx, |state, _| state.set_last_focused(id));
+                }
+                RichTextEvent::Blur => this.is_focus = false,
+                RichTextEvent::Change(content) => {
+                    this.data.metadata.content = content.clone();
+                    let node_id = this.data.id;
+                    this.state
+                        .update(cx, |state, cx| state.node_changed(node_id, cx));
+                    CompletionState::refresh_suggestion(&this.rich_text_state, cx);
+                }
+                RichTextEvent::Enter => this.handle_enter(window, cx),
+                RichTextEvent::Backspace => this.handle_backspace(window, cx),
+                RichTextEvent::Slash(selection) => this.handle_slash(selection.clone(), window, cx),
+                RichTextEvent::PasteBlocks(blocks) => {
+                    let replace = this.data.metadata.content.is_empty();
+                    paste_blocks(this.data.id, blocks, replace, &this.state, window, cx);
+                }
+                RichTextEvent::Delete | RichTextEvent::Tab | RichTextEvent::Space => {}
+            }
+        })
+        .detach();
+
+        let menu = cx.new(|cx| SlashMenu::new(data.id, state, window, cx));
+
+        cx.subscribe_in(&menu, window, {
+            move |this, _, event: &SlashMenuDismissEvent, window, cx| {
+                if event.restore_focus {
+                    let rich_text_state = this.rich_text_state.clone();
+                    cx.defer_in(window, move |_, window, cx| {
+                        rich_text_state.update(cx, |state, cx| {
+                            state.focus(window, cx);
+                        });
+                    });
+                }
+            }
+        })
+        .detach();
+
+        Ok(Self {
+            state: state.clone(),
+            data,
+            rich_text_state,
+            menu,
+            is_focus: false,
+        })
+    }
+
+    fn handle_slash(
+        &mut self,
+        selection: Option<SharedString>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.is_focus && !self.menu.read(cx).open {
+            self.menu.update(cx, |menu, cx| {
+                menu.set_selection(selection);
+                menu.set_open(true, window, cx);
+            });
+        }
+    }
+
+    fn handle_backspace(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let content = self.rich_text_state.read(cx).content().to_string();
+        if content.is_empty() {
+            self.turn_into_text(window, cx);
+        }
+    }
+
+    fn handle_enter(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.menu.read(cx).open {
+            return;
+        }
+
+        let content = self.rich_text_state.read(cx).content().trim().to_string();
+
+        // Pressing Enter on an empty task leaves the checklist
+        if content.is_empty() {
+            self.turn_into_text(window, cx);
+            return;
+        }
+
+        self.data.metadata.content = SharedString::from(content);
+        self.is_focus = false;
+
+        let continues = cx
+            .try_global::<Settings>()
+            .is_none_or(|settings| settings.editor.continues_block(&["todo"]));
+        let payload = if continues {
+            NodePayload::Todo((TodoMetadata::default(), true))
+        } else {
+            NodePayload::Text((TextMetadata::default(), true))
+        };
+
+        self.state.update(cx, |state, cx| {
+            let node = RemindrElement::create_node(payload, &self.state, window, cx);
+            state.insert_node_after(self.data.id, &node, cx);
+        });
+    }
+
+    fn turn_into_text(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let node_id = self.data.id;
+        let content = self.rich_text_state.read(cx).content().to_string();
+        let state = self.state.clone();
+
+        state.update(cx, |node_state, cx| {
+            let node = RemindrElement::create_node_with_id(
+                node_id,
+                NodePayload::Text((
+                    TextMetadata {
+                        content: SharedString::from(content),
+                    },
+                    true,
+                )),
+                &state,
+                window,
+                cx,
+            );
+            node_state.replace_node(node_id, &node, cx);
+        });
+    }
+
+    pub fn set_status(&mut self, status: TodoStatus, cx: &mut Context<Self>) {
+        self.data.metadata.status = status;
+        let node_id = self.data.id;
+        self.state
+            .update(cx, |state, cx| state.node_changed(node_id, cx));
+        cx.notify();
+    }
+
+    /// Sets the status of a to-do block of a document from outside of its editor. Where the
+    /// document is loaded, the block is changed in the editor so that it's saved along with
+    /// the unsaved edits rather than overwritten by them. Otherwise it's changed in the
+    /// storage and the windows showing the document reload it.
+    pub fn set_document_status(
+        document_id: i32,
+        block_id: Uuid,
+        status: TodoStatus,
+        cx: &mut App,
+    ) -> Task<Result<(), Error>> {
+        if let Some((_, state)) = cx.global::<DocumentState>().loaded_nodes(document_id, cx) {
+            let todo =
+                state
+                    .read(cx)
+                    .get_current_nodes(block_id)
+                    .and_then(|node| match &node.element {
+                        RemindrElement::Todo(todo) => Some(todo.clone()),
+                        _ => None,
+                    });
+            if let Some(todo) = todo {
+                todo.update(cx, |todo, cx| todo.set_status(status, cx));
+            }
+            return Task::ready(Ok(()));
+        }
+
+        let repository = cx.global::<RepositoryState>().documents.clone();
+        cx.spawn(async move |cx| {
+            let mut document = repository.get_document_by_id(document_id).await?;
+            if !set_todo_status(&mut document.content, block_id, status) {
+                return Ok(());
+            }
+            repository.update_document(document).await?;
+
+            cx.update(|cx| {
+                cx.update_global::<DocumentState, _>(|state, _| {
+                    state.reload_documents(&[document_id]);
+                });
+                cx.refresh_windows();
+            });
+            Ok(())
+        })
+    }
+
+    pub fn focus(&self, window: &mut Window, cx: &mut App) {
+        self.rich_text_state.update(cx, |state, cx| {
+            state.focus(window, cx);
+        });
+    }
+}
+
+impl NodeMenuProvider for TodoNode {
+    fn menu_items(&self, _cx: &App) -> Vec<NodeMenuItem> {
+        let node_id = self.data.id;
+        let content = self.data.metadata.content.clone();
+
+        vec![NodeMenuItem::new(
+            "transform-to-text",
+            "Text",
+            "icons/pilcrow.svg",
+            move |state, window, cx| {
+                let state_clone = state.clone();
+                let content = content.clone();
+                state.update(cx, |state, cx| {
+                    let node = RemindrElement::create_node_with_id(
+                        node_id,
+                        NodePayload::Text((TextMetadata { content }, true)),
+                        &state_clone,
+                        window,
+                        cx,
+                    );
+                    state.replace_node(node_id, &node, cx);
+                });
+            },
+        )]
+    }
+}
+
+impl Render for TodoNode {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let text_font_size = cx
+            .try_global::<Settings>()
+            .map(|s| s.editor.block_font_sizes.text);
+
+        let status = self.data.metadata.status;
+        let is_done = status == TodoStatus::Done;
+
+        let container = div()
+            .min_w(px(820.0))
+            .w_full()
+            .my(EditorSettings::block_margin(cx, true))
+            .flex()
+            .items_start();
+
+        let container = if let Some(size) = text_font_size {
+            container.text_size(px(size))
+        } else {
+            container
+        };
+
+        container
+            .child(
+                div()
+                    .id("todo-status")
+                    .ml_3()
+                    .w_6()
+                    .pt_0p5()
+                    .flex_shrink_0()
+                    .cursor_pointer()
+                    .child(
+                        Icon::default()
+                            .path(status.icon_path())
+                            .size_4()
+                            .text_color(if is_done {
+                                cx.theme().primary
+                            } else {
+                                cx.theme().muted_foreground
+                            }),
+                    )
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        this.set_status(status.toggled(), cx);
+                    })),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .when(is_done, |this| {
+                        this.line_through().text_color(cx.theme().muted_foreground)
+                    })
+                    .child(RichTextView::new(self.rich_text_state.clone())),
+            )
+            .child(self.menu.clone())
+    }
+}
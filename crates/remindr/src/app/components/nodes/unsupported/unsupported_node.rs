@@ -0,0 +1,71 @@
+use gpui::{App, Context, IntoElement, ParentElement, Render, Styled, Window, div, px};
+use gpui_component::{ActiveTheme, Icon, Sizable, h_flex, label::Label, v_flex};
+use serde_json::Value;
+
+use crate::app::{
+    components::nodes::menu_provider::{NodeMenuItem, NodeMenuProvider},
+    states::settings_state::EditorSettings,
+};
+
+/// Placeholder for a block this version can't show, e.g. of a type added by a newer one.
+/// Its JSON is kept as it was loaded and saved back unchanged.
+pub struct UnsupportedNode {
+    pub data: Value,
+    /// Why the block couldn't be parsed into its node.
+    pub reason: String,
+}
+
+impl UnsupportedNode {
+    pub fn new(data: Value, reason: String) -> Self {
+        Self { data, reason }
+    }
+
+    fn block_type(&self) -> &str {
+        self.data["type"].as_str().unwrap_or("unknown")
+    }
+}
+
+impl NodeMenuProvider for UnsupportedNode {
+    fn menu_items(&self, _cx: &App) -> Vec<NodeMenuItem> {
+        vec![]
+    }
+}
+
+impl Render for UnsupportedNode {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .min_w(px(820.0))
+            .w_full()
+            .my(EditorSettings::block_margin(cx, true))
+            .child(
+                v_flex()
+                    .gap_0p5()
+                    .px_2()
+                    .py_1()
+                    .rounded(cx.theme().radius)
+                    .border_1()
+                    .border_color(cx.theme().border)
+                    .bg(cx.theme().muted.opacity(0.3))
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(
+                                Icon::default()
+                                    .path("icons/triangle-alert.svg")
+                                    .small()
+                                    .text_color(cx.theme().muted_foreground),
+                            )
+                            .child(
+                                Label::new(format!("Unsupported block ({})", self.block_type()))
+                                    .text_sm()
+                                    .text_color(cx.theme().foreground),
+                            ),
+                    )
+                    .child(
+                        Label::new(format!("Kept as is and saved unchanged: {}", self.reason))
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground),
+                    ),
+            )
+    }
+}
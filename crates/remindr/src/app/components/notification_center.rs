@@ -0,0 +1,156 @@
+use gpui::prelude::FluentBuilder;
+use gpui::{
+    AnyElement, App, AppContext, ClipboardItem, Context, FontWeight, IntoElement, ParentElement,
+    Render, Styled, Window, div, px,
+};
+use gpui_component::{
+    ActiveTheme, Sizable, WindowExt,
+    button::{Button, ButtonVariants},
+    h_flex,
+    label::Label,
+    scroll::ScrollableElement,
+    v_flex,
+};
+
+use crate::app::{
+    date_format::DateFormat,
+    states::error_state::{AppError, ErrorState},
+};
+
+/// Errors reported by the storage, the settings and the sync, with their details and a
+/// retry when the failed operation can be run again.
+pub struct NotificationCenter;
+
+impl NotificationCenter {
+    pub fn open(window: &mut Window, cx: &mut App) {
+        let center = cx.new(NotificationCenter::new);
+
+        window.open_dialog(cx, move |dialog, _, _| {
+            dialog
+                .title("Notifications")
+                .w(px(560.))
+                .overlay_closable(true)
+                .child(center.clone())
+        });
+    }
+
+    fn new(cx: &mut Context<Self>) -> Self {
+        cx.observe_global::<ErrorState>(|_, cx| cx.notify())
+            .detach();
+
+        Self
+    }
+
+    fn render_error(&self, error: &AppError, cx: &mut Context<Self>) -> AnyElement {
+        let id = error.id;
+        let details = error.details.clone();
+
+        v_flex()
+            .gap_1()
+            .px_3()
+            .py_2()
+            .rounded(cx.theme().radius)
+            .border_1()
+            .border_color(cx.theme().border)
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        div()
+                            .flex_1()
+                            .min_w_0()
+                            .text_sm()
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .text_color(cx.theme().danger)
+                            .child(error.message.clone()),
+                    )
+                    .child(
+                        Label::new(format!(
+                            "{} · {}",
+                            error.kind.label(),
+                            DateFormat::new(cx).precise_time(error.time.time())
+                        ))
+                        .text_xs()
+                        .text_color(cx.theme().muted_foreground),
+                    ),
+            )
+            .child(
+                div()
+                    .max_h(px(96.))
+                    .overflow_y_scrollbar()
+                    .font_family(cx.theme().mono_font_family.clone())
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(error.details.clone()),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .justify_end()
+                    .when(error.retry.is_some(), |this| {
+                        this.child(
+                            Button::new(("retry-error", id))
+                                .xsmall()
+                                .primary()
+                                .label("Retry")
+                                .on_click(move |_, _, cx| ErrorState::retry(id, cx)),
+                        )
+                    })
+                    .child(
+                        Button::new(("copy-error", id))
+                            .xsmall()
+                            .ghost()
+                            .label("Copy details")
+                            .on_click(move |_, _, cx| {
+                                cx.write_to_clipboard(ClipboardItem::new_string(details.clone()));
+                            }),
+                    )
+                    .child(
+                        Button::new(("dismiss-error", id))
+                            .xsmall()
+                            .ghost()
+                            .label("Dismiss")
+                            .on_click(move |_, _, cx| {
+                                cx.update_global::<ErrorState, _>(|state, _| state.dismiss(id));
+                            }),
+                    ),
+            )
+            .into_any_element()
+    }
+}
+
+impl Render for NotificationCenter {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let errors = cx
+            .global::<ErrorState>()
+            .errors()
+            .cloned()
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .gap_2()
+            .max_h(px(480.))
+            .overflow_y_scrollbar()
+            .when(errors.is_empty(), |this| {
+                this.child(
+                    Label::new("No errors reported.")
+                        .text_sm()
+                        .text_color(cx.theme().muted_foreground),
+                )
+            })
+            .when(!errors.is_empty(), |this| {
+                this.child(
+                    h_flex().justify_end().child(
+                        Button::new("clear-errors")
+                            .xsmall()
+                            .ghost()
+                            .label("Clear all")
+                            .on_click(|_, _, cx| {
+                                cx.update_global::<ErrorState, _>(|state, _| state.clear());
+                            }),
+                    ),
+                )
+            })
+            .children(errors.iter().map(|error| self.render_error(error, cx)))
+    }
+}
@@ -0,0 +1,100 @@
+use gpui::{
+    Context, IntoElement, ParentElement, Render, Styled, Window, div, prelude::FluentBuilder, px,
+    relative,
+};
+use gpui_component::{
+    ActiveTheme, Icon, IconName, Sizable, StyledExt,
+    button::{Button, ButtonVariants},
+    h_flex, v_flex,
+};
+
+use crate::app::states::progress_state::ProgressState;
+
+/// Long operations running in the app, shown at the bottom right of the window.
+pub struct ProgressPanel;
+
+impl ProgressPanel {
+    pub fn new(cx: &mut Context<Self>) -> Self {
+        cx.observe_global::<ProgressState>(|_, cx| cx.notify())
+            .detach();
+
+        Self
+    }
+}
+
+impl Render for ProgressPanel {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let tasks = cx
+            .try_global::<ProgressState>()
+            .map(|state| state.tasks().to_vec())
+            .unwrap_or_default();
+
+        v_flex()
+            .absolute()
+            .bottom_4()
+            .right_4()
+            .w(px(280.))
+            .gap_2()
+            .children(tasks.into_iter().map(|task| {
+                let id = task.id;
+                let cancelled = task.is_cancelled();
+
+                v_flex()
+                    .gap_2()
+                    .p_3()
+                    .rounded_md()
+                    .border_1()
+                    .border_color(cx.theme().border)
+                    .bg(cx.theme().popover)
+                    .shadow_md()
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .text_sm()
+                            .child(
+                                div()
+                                    .flex_1()
+                                    .min_w_0()
+                                    .truncate()
+                                    .font_medium()
+                                    .child(task.title.clone()),
+                            )
+                            .child(
+                                div()
+                                    .text_xs()
+                                    .text_color(cx.theme().muted_foreground)
+                                    .child(match (cancelled, task.percent) {
+                                        (true, _) => "Cancelling…".to_string(),
+                                        (false, Some(percent)) => format!("{:.0}%", percent),
+                                        (false, None) => String::new(),
+                                    }),
+                            )
+                            .when(task.cancelled.is_some() && !cancelled, |this| {
+                                this.child(
+                                    Button::new(("cancel-progress", id))
+                                        .icon(Icon::new(IconName::Close))
+                                        .ghost()
+                                        .xsmall()
+                                        .cursor_pointer()
+                                        .tooltip("Cancel")
+                                        .on_click(move |_, _, cx| ProgressState::cancel(id, cx)),
+                                )
+                            }),
+                    )
+                    .child(
+                        div()
+                            .h_1()
+                            .w_full()
+                            .rounded_full()
+                            .bg(cx.theme().muted)
+                            .child(
+                                div()
+                                    .h_full()
+                                    .rounded_full()
+                                    .bg(cx.theme().primary)
+                                    .w(relative(task.percent.unwrap_or(0.0) / 100.0)),
+                            ),
+                    )
+            }))
+    }
+}
@@ -0,0 +1,191 @@
+use anyhow::Error;
+use gpui::{
+    AnyWindowHandle, App, AppContext, BorrowAppContext, Bounds, Context, Entity, Global,
+    InteractiveElement, IntoElement, KeyBinding, KeyDownEvent, ParentElement, Render, SharedString,
+    Styled, Task, Window, WindowBounds, WindowKind, WindowOptions, actions, px, size,
+};
+use gpui_component::{
+    ActiveTheme, Root, Sizable, StyledExt,
+    input::{Input, InputEvent, InputState},
+    label::Label,
+    v_flex,
+};
+use serde_json::Value;
+
+use crate::{
+    app::{
+        apply_theme,
+        states::{document_state::DocumentState, repository_state::RepositoryState},
+    },
+    infrastructure::inbox::{INBOX_TITLE, append_blocks_to_inbox, find_inbox, text_block},
+};
+
+actions!(quick_capture, [QuickCapture]);
+
+pub fn init(cx: &mut App) {
+    cx.bind_keys([KeyBinding::new("secondary-shift-space", QuickCapture, None)]);
+    cx.on_action(|_: &QuickCapture, cx| QuickCaptureWindow::open(cx));
+}
+
+/// The open quick capture window, brought back to the front rather than opened twice when
+/// both the app and the system-wide shortcut ask for it.
+#[derive(Clone, Copy)]
+struct OpenCaptureWindow(AnyWindowHandle);
+
+impl Global for OpenCaptureWindow {}
+
+/// Small floating window to jot a note down without going through the main window.
+///
+/// The note is appended as a text block to the "Inbox" document, which is created when
+/// missing.
+pub struct QuickCaptureWindow {
+    input: Entity<InputState>,
+    saving: bool,
+    error: Option<SharedString>,
+}
+
+impl QuickCaptureWindow {
+    pub fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let input = cx.new(|cx| InputState::new(window, cx).placeholder("Jot something down..."));
+
+        cx.subscribe_in(&input, window, |this, _, event: &InputEvent, window, cx| {
+            if let InputEvent::PressEnter { .. } = event {
+                this.submit(window, cx);
+            }
+        })
+        .detach();
+
+        input.update(cx, |input, cx| {
+            input.focus(window, cx);
+        });
+
+        Self {
+            input,
+            saving: false,
+            error: None,
+        }
+    }
+
+    pub fn open(cx: &mut App) {
+        // Captures are only stored once the repositories are available
+        if !cx.has_global::<RepositoryState>() {
+            return;
+        }
+        if let Some(OpenCaptureWindow(window)) = cx.try_global::<OpenCaptureWindow>().copied()
+            && window
+                .update(cx, |_, window, _| window.activate_window())
+                .is_ok()
+        {
+            return;
+        }
+
+        let window_bounds = Bounds::centered(None, size(px(420.), px(120.)), cx);
+        let options = WindowOptions {
+            window_bounds: Some(WindowBounds::Windowed(window_bounds)),
+            is_resizable: false,
+            kind: WindowKind::PopUp,
+            titlebar: None,
+            ..Default::default()
+        };
+
+        let window = cx.open_window(options, |window, cx| {
+            let view = cx.new(|cx| QuickCaptureWindow::new(window, cx));
+            cx.new(|cx| Root::new(view, window, cx))
+        });
+
+        match window {
+            Ok(window) => {
+                cx.set_global(OpenCaptureWindow(window.into()));
+                let _ = window.update(cx, |_, window, cx| {
+                    window.activate_window();
+                    window.set_window_title("Quick capture");
+                    apply_theme(window, cx);
+                });
+            }
+            Err(e) => tracing::error!("failed to open window: {:?}", e),
+        }
+    }
+
+    fn submit(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let text = self.input.read(cx).value().trim().to_string();
+        if self.saving || text.is_empty() {
+            return;
+        }
+
+        self.saving = true;
+        self.error = None;
+        cx.notify();
+
+        let capture = capture_blocks(vec![text_block(text)], cx);
+        let window_handle = window.window_handle();
+
+        cx.spawn(async move |this, cx| {
+            let result = capture.await;
+
+            cx.update_window(window_handle, |_, window, cx| match result {
+                Ok(_) => window.remove_window(),
+                Err(e) => {
+                    tracing::error!("failed to save capture: {:?}", e);
+                    let _ = this.update(cx, |this, cx| {
+                        this.saving = false;
+                        this.error = Some(SharedString::from(e.to_string()));
+                        cx.notify();
+                    });
+                }
+            })?;
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+}
+
+impl Render for QuickCaptureWindow {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .size_full()
+            .justify_center()
+            .gap_2()
+            .px_4()
+            .bg(cx.theme().background)
+            .on_key_down(|event: &KeyDownEvent, window, _| {
+                if event.keystroke.key.as_str() == "escape" {
+                    window.remove_window();
+                }
+            })
+            .child(
+                Label::new(format!("Quick capture to \"{}\"", INBOX_TITLE))
+                    .text_xs()
+                    .font_semibold()
+                    .text_color(cx.theme().muted_foreground),
+            )
+            .child(Input::new(&self.input).small())
+            .children(
+                self.error
+                    .clone()
+                    .map(|error| Label::new(error).text_xs().text_color(cx.theme().danger)),
+            )
+    }
+}
+
+/// Appends blocks to the inbox document and returns its id. When the inbox is loaded in a
+/// window, the blocks go through its editor, otherwise they are written to the storage and
+/// the windows showing the inbox reload it.
+pub fn capture_blocks(blocks: Vec<Value>, cx: &mut App) -> Task<Result<i32, Error>> {
+    let documents = cx.global::<RepositoryState>().documents.clone();
+
+    cx.spawn(async move |cx| {
+        if let Some(inbox) = find_inbox(&documents).await?
+            && cx.update(|cx| DocumentState::append_to_loaded(inbox.id, &blocks, cx))
+        {
+            return Ok(inbox.id);
+        }
+
+        let inbox_id = append_blocks_to_inbox(&documents, blocks).await?;
+        cx.update(|cx| {
+            cx.update_global::<DocumentState, _>(|state, _| state.reload_documents(&[inbox_id]));
+            cx.refresh_windows();
+        });
+        Ok(inbox_id)
+    })
+}
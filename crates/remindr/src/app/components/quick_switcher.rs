@@ -0,0 +1,314 @@
+use gpui::prelude::FluentBuilder;
+use gpui::{
+    App, AppContext, Context, Entity, FocusHandle, Focusable, InteractiveElement, IntoElement,
+    KeyBinding, KeyDownEvent, ParentElement, Render, SharedString, Styled, Window, actions, div,
+    px,
+};
+use gpui_component::{
+    ActiveTheme, Icon, Sizable, WindowExt, h_flex,
+    input::{Input, InputEvent, InputState, MoveDown, MoveUp},
+    label::Label,
+    v_flex,
+};
+
+use crate::{
+    app::states::{
+        app_state::AppState, deletion_state::DeletionState, document_state::DocumentState,
+        repository_state::RepositoryState,
+    },
+    domain::database::document::DocumentModel,
+};
+
+actions!(quick_switcher, [ToggleQuickSwitcher]);
+
+/// Maximum number of documents listed at once.
+const MAX_RESULTS: usize = 12;
+
+/// Number of lines shown in the preview of the highlighted document.
+const PREVIEW_LINES: usize = 6;
+
+pub fn init(cx: &mut App) {
+    cx.bind_keys([KeyBinding::new("secondary-o", ToggleQuickSwitcher, None)]);
+}
+
+/// Jumps to a document by typing part of its title, opened with `secondary-o`.
+///
+/// Titles are matched fuzzily and recently opened documents come first. Unlike the full
+/// text search, only titles are looked at.
+pub struct QuickSwitcher {
+    focus_handle: FocusHandle,
+    search_input: Entity<InputState>,
+    app_state: Entity<AppState>,
+    /// All documents, most recently opened first.
+    documents: Vec<DocumentModel>,
+    selected_index: usize,
+}
+
+impl QuickSwitcher {
+    pub fn open(app_state: Entity<AppState>, window: &mut Window, cx: &mut App) {
+        let switcher = cx.new(|cx| QuickSwitcher::new(app_state, window, cx));
+
+        window.open_dialog(cx, move |dialog, _, _| {
+            dialog
+                .w(px(720.))
+                .p_1()
+                .close_button(false)
+                .overlay_closable(true)
+                .child(switcher.clone())
+        });
+    }
+
+    fn new(app_state: Entity<AppState>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let search_input =
+            cx.new(|cx| InputState::new(window, cx).placeholder("Go to document..."));
+
+        cx.subscribe_in(
+            &search_input,
+            window,
+            |this, _, event: &InputEvent, window, cx| match event {
+                InputEvent::Change => {
+                    this.selected_index = 0;
+                    cx.notify();
+                }
+                InputEvent::PressEnter { .. } => this.confirm_selection(window, cx),
+                _ => {}
+            },
+        )
+        .detach();
+
+        search_input.update(cx, |input, cx| {
+            input.focus(window, cx);
+        });
+
+        let repository = cx.global::<RepositoryState>().documents.clone();
+        cx.spawn(async move |this, cx| {
+            let mut documents = repository.get_documents().await?;
+
+            this.update(cx, |this, cx| {
+                let deletions = cx.global::<DeletionState>();
+                documents.retain(|document| !deletions.is_pending(document.id));
+                let recent = cx.global::<DocumentState>().recent_documents();
+                documents.sort_by(|a, b| a.title.to_lowercase().cmp(&b.title.to_lowercase()));
+                documents.sort_by_key(|document| {
+                    recent
+                        .iter()
+                        .position(|id| *id == document.id)
+                        .unwrap_or(usize::MAX)
+                });
+
+                this.documents = documents;
+                cx.notify();
+            })?;
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+
+        Self {
+            focus_handle: cx.focus_handle(),
+            search_input,
+            app_state,
+            documents: Vec::new(),
+            selected_index: 0,
+        }
+    }
+
+    fn filtered_documents(&self, cx: &App) -> Vec<&DocumentModel> {
+        let search = self.search_input.read(cx).value();
+        if search.trim().is_empty() {
+            return self.documents.iter().take(MAX_RESULTS).collect();
+        }
+
+        let mut matches = self
+            .documents
+            .iter()
+            .filter_map(|document| Some((fuzzy_score(&search, &document.title)?, document)))
+            .collect::<Vec<_>>();
+
+        // The sort is stable, so equally good matches stay ordered by recency
+        matches.sort_by(|(a, _), (b, _)| b.cmp(a));
+        matches
+            .into_iter()
+            .take(MAX_RESULTS)
+            .map(|(_, document)| document)
+            .collect()
+    }
+
+    fn move_selection(&mut self, delta: isize, cx: &mut Context<Self>) {
+        let count = self.filtered_documents(cx).len();
+        if count == 0 {
+            return;
+        }
+
+        self.selected_index =
+            (self.selected_index as isize + delta).rem_euclid(count as isize) as usize;
+        cx.notify();
+    }
+
+    fn confirm_selection(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(document) = self
+            .filtered_documents(cx)
+            .get(self.selected_index)
+            .map(|document| (document.id, document.title.clone(), document.folder_id))
+        else {
+            return;
+        };
+
+        window.close_dialog(cx);
+
+        let (id, title, folder_id) = document;
+        self.app_state.update(cx, |app_state, cx| {
+            app_state.open_document(id, title, folder_id, cx);
+        });
+    }
+}
+
+impl Focusable for QuickSwitcher {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for QuickSwitcher {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let documents = self.filtered_documents(cx);
+        let selected_index = self.selected_index.min(documents.len().saturating_sub(1));
+        let preview = documents
+            .get(selected_index)
+            .map(|document| preview_lines(document))
+            .unwrap_or_default();
+
+        v_flex()
+            .track_focus(&self.focus_handle)
+            .w_full()
+            .gap_1()
+            .on_action(cx.listener(|this, _: &MoveUp, _, cx| {
+                this.move_selection(-1, cx);
+            }))
+            .on_action(cx.listener(|this, _: &MoveDown, _, cx| {
+                this.move_selection(1, cx);
+            }))
+            .on_key_down(cx.listener(|_, event: &KeyDownEvent, window, cx| {
+                if event.keystroke.key.as_str() == "escape" {
+                    window.close_dialog(cx);
+                    cx.stop_propagation();
+                }
+            }))
+            .child(
+                Input::new(&self.search_input)
+                    .text_sm()
+                    .appearance(false)
+                    .prefix(
+                        Icon::default()
+                            .path("icons/file-text.svg")
+                            .small()
+                            .text_color(cx.theme().muted_foreground),
+                    ),
+            )
+            .child(
+                h_flex()
+                    .items_start()
+                    .gap_2()
+                    .child(
+                        v_flex()
+                            .w(px(320.))
+                            .flex_shrink_0()
+                            .gap_1()
+                            .when(documents.is_empty(), |this| {
+                                this.child(
+                                    div().px_2().py_2().child(
+                                        Label::new("No matching documents")
+                                            .text_sm()
+                                            .text_color(cx.theme().muted_foreground),
+                                    ),
+                                )
+                            })
+                            .children(documents.iter().enumerate().map(|(index, document)| {
+                                let title = if document.title.is_empty() {
+                                    "Untitled"
+                                } else {
+                                    document.title.as_str()
+                                };
+
+                                div()
+                                    .px_2()
+                                    .py_1()
+                                    .rounded_md()
+                                    .when(index == selected_index, |this| {
+                                        this.bg(cx.theme().accent.opacity(0.5))
+                                    })
+                                    .child(
+                                        Label::new(SharedString::from(title.to_string()))
+                                            .text_sm()
+                                            .text_color(cx.theme().foreground),
+                                    )
+                            })),
+                    )
+                    .child(
+                        v_flex()
+                            .flex_1()
+                            .min_w_0()
+                            .gap_1()
+                            .px_3()
+                            .py_1()
+                            .border_l_1()
+                            .border_color(cx.theme().border)
+                            .when(preview.is_empty() && !documents.is_empty(), |this| {
+                                this.child(
+                                    Label::new("Empty document")
+                                        .text_xs()
+                                        .text_color(cx.theme().muted_foreground),
+                                )
+                            })
+                            .children(preview.into_iter().map(|line| {
+                                Label::new(line)
+                                    .text_xs()
+                                    .text_color(cx.theme().muted_foreground)
+                            })),
+                    ),
+            )
+    }
+}
+
+/// Scores how well `title` matches `query` when the query characters are found in order in
+/// the title, ignoring case. Consecutive characters and characters starting a word score
+/// higher.
+pub fn fuzzy_score(query: &str, title: &str) -> Option<usize> {
+    let title = title.to_lowercase().chars().collect::<Vec<_>>();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous: Option<usize> = None;
+
+    for query_char in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let index = position + title[position..].iter().position(|c| *c == query_char)?;
+
+        score += 1;
+        if previous.is_some_and(|previous| previous + 1 == index) {
+            score += 4;
+        }
+        if index == 0 || !title[index - 1].is_alphanumeric() {
+            score += 2;
+        }
+
+        previous = Some(index);
+        position = index + 1;
+    }
+
+    Some(score)
+}
+
+/// First non-empty text lines of a document.
+fn preview_lines(document: &DocumentModel) -> Vec<SharedString> {
+    document
+        .content
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|node| node.get("metadata")?.get("content")?.as_str())
+        .flat_map(str::lines)
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .take(PREVIEW_LINES)
+        .map(|line| SharedString::from(line.to_string()))
+        .collect()
+}
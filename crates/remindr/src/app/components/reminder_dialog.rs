@@ -0,0 +1,214 @@
+use chrono::{Duration, Local, NaiveDateTime, TimeZone};
+use gpui::prelude::FluentBuilder;
+use gpui::{
+    App, AppContext, ClickEvent, Context, Entity, IntoElement, ParentElement, Render, SharedString,
+    Styled, Window, px,
+};
+use gpui_component::{
+    ActiveTheme, Selectable, Sizable, StyledExt, WindowExt,
+    button::{Button, ButtonVariants},
+    h_flex,
+    input::{Input, InputState},
+    label::Label,
+    v_flex,
+};
+
+use crate::{
+    app::states::reminder_state::ReminderState,
+    domain::database::reminder::{Recurrence, ReminderModel},
+};
+
+/// Format of the due date typed in the dialog.
+const DUE_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum RecurrenceChoice {
+    Once,
+    Daily,
+    Weekly,
+    Monthly,
+    Custom,
+}
+
+impl RecurrenceChoice {
+    const ALL: [RecurrenceChoice; 5] = [
+        RecurrenceChoice::Once,
+        RecurrenceChoice::Daily,
+        RecurrenceChoice::Weekly,
+        RecurrenceChoice::Monthly,
+        RecurrenceChoice::Custom,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            RecurrenceChoice::Once => "Once",
+            RecurrenceChoice::Daily => "Daily",
+            RecurrenceChoice::Weekly => "Weekly",
+            RecurrenceChoice::Monthly => "Monthly",
+            RecurrenceChoice::Custom => "Cron",
+        }
+    }
+}
+
+/// Creates a reminder for a document, optionally repeating.
+pub struct ReminderDialog {
+    document_id: i32,
+    title_input: Entity<InputState>,
+    due_input: Entity<InputState>,
+    cron_input: Entity<InputState>,
+    recurrence: RecurrenceChoice,
+    error: Option<SharedString>,
+}
+
+impl ReminderDialog {
+    pub fn open(document_id: i32, document_title: String, window: &mut Window, cx: &mut App) {
+        let view = cx.new(|cx| ReminderDialog::new(document_id, document_title, window, cx));
+
+        window.open_dialog(cx, move |dialog, _, _| {
+            let view = view.clone();
+
+            dialog
+                .w(px(420.))
+                .title(v_flex().text_sm().font_semibold().child("New reminder"))
+                .footer(move |_, _, _, _| {
+                    let view = view.clone();
+                    vec![
+                        Button::new("create-reminder")
+                            .small()
+                            .primary()
+                            .label("Add reminder")
+                            .on_click(move |_: &ClickEvent, window, cx| {
+                                view.update(cx, |this, cx| this.submit(window, cx));
+                            })
+                            .into_any_element(),
+                    ]
+                })
+                .child(view.clone())
+        });
+    }
+
+    fn new(
+        document_id: i32,
+        document_title: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let default_due = (Local::now() + Duration::hours(1))
+            .format(DUE_FORMAT)
+            .to_string();
+
+        let title_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("Reminder")
+                .default_value(document_title)
+        });
+        let due_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("YYYY-MM-DD HH:MM")
+                .default_value(default_due)
+        });
+        let cron_input = cx.new(|cx| InputState::new(window, cx).placeholder("0 9 * * 1-5"));
+
+        title_input.update(cx, |input, cx| {
+            input.focus(window, cx);
+        });
+
+        Self {
+            document_id,
+            title_input,
+            due_input,
+            cron_input,
+            recurrence: RecurrenceChoice::Once,
+            error: None,
+        }
+    }
+
+    fn recurrence(&self, cx: &App) -> Result<Option<Recurrence>, String> {
+        let recurrence = match self.recurrence {
+            RecurrenceChoice::Once => return Ok(None),
+            RecurrenceChoice::Daily => Recurrence::Daily,
+            RecurrenceChoice::Weekly => Recurrence::Weekly,
+            RecurrenceChoice::Monthly => Recurrence::Monthly,
+            RecurrenceChoice::Custom => Recurrence::parse(&self.cron_input.read(cx).value())
+                .map_err(|e| format!("Invalid cron expression: {}", e))?,
+        };
+
+        Ok(Some(recurrence))
+    }
+
+    fn submit(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let title = self.title_input.read(cx).value().trim().to_string();
+        let due = self.due_input.read(cx).value().trim().to_string();
+
+        let due_at = NaiveDateTime::parse_from_str(&due, DUE_FORMAT)
+            .ok()
+            .and_then(|due| Local.from_local_datetime(&due).earliest());
+        let result = match (title.is_empty(), due_at) {
+            (true, _) => Err("The reminder needs a title".to_string()),
+            (_, None) => Err("Use the format YYYY-MM-DD HH:MM".to_string()),
+            (_, Some(due_at)) => self.recurrence(cx).map(|recurrence| (due_at, recurrence)),
+        };
+
+        match result {
+            Ok((due_at, recurrence)) => {
+                window.close_dialog(cx);
+                ReminderState::create(
+                    ReminderModel {
+                        id: 0,
+                        document_id: self.document_id,
+                        title,
+                        due_at: due_at.timestamp(),
+                        recurrence: recurrence.map(|recurrence| recurrence.to_rule()),
+                        snoozed_until: None,
+                        completed: false,
+                    },
+                    cx,
+                );
+            }
+            Err(error) => {
+                self.error = Some(error.into());
+                cx.notify();
+            }
+        }
+    }
+}
+
+impl Render for ReminderDialog {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let muted = cx.theme().muted_foreground;
+
+        v_flex()
+            .gap_2()
+            .child(Input::new(&self.title_input).small())
+            .child(Label::new("Due").text_xs().text_color(muted))
+            .child(Input::new(&self.due_input).small())
+            .child(Label::new("Repeat").text_xs().text_color(muted))
+            .child(
+                h_flex()
+                    .gap_1()
+                    .children(RecurrenceChoice::ALL.into_iter().map(|choice| {
+                        Button::new(SharedString::from(format!("recurrence-{}", choice.label())))
+                            .xsmall()
+                            .outline()
+                            .label(choice.label())
+                            .selected(self.recurrence == choice)
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.recurrence = choice;
+                                cx.notify();
+                            }))
+                    })),
+            )
+            .when(self.recurrence == RecurrenceChoice::Custom, |this| {
+                this.child(Input::new(&self.cron_input).small()).child(
+                    Label::new("minute hour day month weekday, e.g. 0 9 * * 1-5")
+                        .text_xs()
+                        .text_color(muted),
+                )
+            })
+            .children(
+                self.error
+                    .clone()
+                    .map(|error| Label::new(error).text_xs().text_color(cx.theme().danger)),
+            )
+    }
+}
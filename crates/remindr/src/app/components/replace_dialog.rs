@@ -0,0 +1,570 @@
+use std::collections::HashSet;
+
+use anyhow::{Error, bail};
+use gpui::prelude::FluentBuilder;
+use gpui::{
+    App, AppContext, BorrowAppContext, Context, Entity, IntoElement, ParentElement, Render,
+    SharedString, Styled, Window, div, px,
+};
+use gpui_component::{
+    ActiveTheme, Disableable, Sizable, StyledExt, WindowExt,
+    button::{Button, ButtonVariants},
+    checkbox::Checkbox,
+    h_flex,
+    input::{Input, InputEvent, InputState},
+    label::Label,
+    scroll::ScrollableElement,
+    v_flex,
+};
+use serde_json::Value;
+
+use crate::{
+    app::states::{
+        deletion_state::DeletionState,
+        document_state::{DocumentState, PersistenceState},
+        repository_state::RepositoryState,
+        toast_state::Toast,
+    },
+    domain::database::document::{TextMatch, find_matches, replace_matches},
+};
+
+/// Matches of the searched text in one document.
+struct DocumentMatches {
+    id: i32,
+    title: String,
+    matches: Vec<TextMatch>,
+}
+
+/// Replacement applied to a document, kept to undo it.
+struct AppliedReplace {
+    id: i32,
+    title: String,
+    count: usize,
+    before: Value,
+    after: Value,
+    undone: bool,
+}
+
+/// Finds a text in the blocks of every document and replaces the selected occurrences.
+///
+/// All matches are previewed grouped by document before anything is written, and each
+/// modified document can then be restored on its own as long as it wasn't edited since.
+pub struct ReplaceDialog {
+    find_input: Entity<InputState>,
+    replace_input: Entity<InputState>,
+    /// Text the results were found for.
+    needle: String,
+    /// Whether replacements follow the case of each match, which finds them in any case.
+    case_pattern: bool,
+    /// Case pattern option the results were found with.
+    results_case_pattern: bool,
+    results: Vec<DocumentMatches>,
+    selected: HashSet<(i32, TextMatch)>,
+    applied: Vec<AppliedReplace>,
+    busy: bool,
+}
+
+impl ReplaceDialog {
+    pub fn open(window: &mut Window, cx: &mut App) {
+        if !cx.has_global::<RepositoryState>() {
+            return;
+        }
+        let view = cx.new(|cx| ReplaceDialog::new(window, cx));
+
+        window.open_dialog(cx, move |dialog, _, _| {
+            dialog
+                .w(px(560.))
+                .title(
+                    v_flex()
+                        .text_sm()
+                        .font_semibold()
+                        .child("Find and replace in all documents"),
+                )
+                .overlay_closable(true)
+                .child(view.clone())
+        });
+    }
+
+    fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let find_input = cx.new(|cx| InputState::new(window, cx).placeholder("Find"));
+        let replace_input = cx.new(|cx| InputState::new(window, cx).placeholder("Replace with"));
+
+        cx.subscribe_in(
+            &find_input,
+            window,
+            |this, _, event: &InputEvent, window, cx| {
+                if let InputEvent::PressEnter { .. } = event {
+                    this.search(window, cx);
+                }
+            },
+        )
+        .detach();
+
+        find_input.update(cx, |input, cx| {
+            input.focus(window, cx);
+        });
+
+        Self {
+            find_input,
+            replace_input,
+            needle: String::new(),
+            case_pattern: false,
+            results_case_pattern: false,
+            results: Vec::new(),
+            selected: HashSet::new(),
+            applied: Vec::new(),
+            busy: false,
+        }
+    }
+
+    fn search(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let needle = self.find_input.read(cx).value().to_string();
+        if self.busy || needle.is_empty() {
+            return;
+        }
+
+        self.busy = true;
+        cx.notify();
+
+        let documents = cx.global::<RepositoryState>().documents.clone();
+        let case_pattern = self.case_pattern;
+        let window_handle = window.window_handle();
+
+        cx.spawn(async move |this, cx| {
+            let result = documents.get_documents().await.map(|documents| {
+                documents
+                    .into_iter()
+                    .map(|document| DocumentMatches {
+                        matches: find_matches(&document.content, &needle, case_pattern),
+                        id: document.id,
+                        title: document.title,
+                    })
+                    .filter(|document| !document.matches.is_empty())
+                    .collect::<Vec<_>>()
+            });
+
+            cx.update_window(window_handle, |_, window, cx| {
+                let _ = this.update(cx, |this, cx| {
+                    this.busy = false;
+                    match result {
+                        Ok(mut results) => {
+                            let deletions = cx.global::<DeletionState>();
+                            results.retain(|document| !deletions.is_pending(document.id));
+                            this.selected = results
+                                .iter()
+                                .flat_map(|document| {
+                                    document
+                                        .matches
+                                        .iter()
+                                        .map(|text_match| (document.id, text_match.clone()))
+                                })
+                                .collect();
+                            this.results = results;
+                            this.needle = needle;
+                            this.results_case_pattern = case_pattern;
+                        }
+                        Err(e) => {
+                            tracing::error!("failed to search documents: {:?}", e);
+                            Toast::error(format!("Failed to search the documents: {}", e))
+                                .show(window, cx);
+                        }
+                    }
+                    cx.notify();
+                });
+            })?;
+
+            Ok::<_, Error>(())
+        })
+        .detach();
+    }
+
+    fn apply(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.busy || self.selected.is_empty() {
+            return;
+        }
+
+        // Open tabs with unsaved edits would overwrite the replacements on their next save
+        let (pending, ready): (Vec<_>, Vec<_>) = {
+            let state = cx.global::<DocumentState>();
+            self.results
+                .iter()
+                .filter(|document| self.selected.iter().any(|(id, _)| *id == document.id))
+                .partition(|document| state.persistence(document.id) == PersistenceState::Pending)
+        };
+        let targets = ready
+            .into_iter()
+            .map(|document| {
+                let matches = document
+                    .matches
+                    .iter()
+                    .filter(|text_match| {
+                        self.selected
+                            .contains(&(document.id, (*text_match).clone()))
+                    })
+                    .cloned()
+                    .collect::<Vec<_>>();
+                (document.id, document.title.clone(), matches)
+            })
+            .collect::<Vec<_>>();
+        let skipped = pending.len();
+
+        self.busy = true;
+        cx.notify();
+
+        let documents = cx.global::<RepositoryState>().documents.clone();
+        let case_pattern = self.results_case_pattern;
+        let replacement = self.replace_input.read(cx).value().to_string();
+        let window_handle = window.window_handle();
+
+        cx.spawn(async move |this, cx| {
+            let mut applied = Vec::new();
+            let mut failed = None;
+            for (id, title, matches) in targets {
+                let result = async {
+                    let mut document = documents.get_document_by_id(id).await?;
+                    let before = document.content.clone();
+                    let count = replace_matches(
+                        &mut document.content,
+                        &replacement,
+                        &matches,
+                        case_pattern,
+                    );
+                    if count == 0 {
+                        return Ok(None);
+                    }
+                    let after = document.content.clone();
+                    documents.update_document(document).await?;
+                    Ok::<_, Error>(Some(AppliedReplace {
+                        id,
+                        title: title.clone(),
+                        count,
+                        before,
+                        after,
+                        undone: false,
+                    }))
+                }
+                .await;
+
+                match result {
+                    Ok(Some(replace)) => applied.push(replace),
+                    Ok(None) => {}
+                    Err(e) => {
+                        tracing::error!("failed to replace in \"{}\": {:?}", title, e);
+                        failed = Some(format!("Failed to replace in \"{}\": {}", title, e));
+                        break;
+                    }
+                }
+            }
+
+            cx.update_window(window_handle, |_, window, cx| {
+                let uids = applied.iter().map(|replace| replace.id).collect::<Vec<_>>();
+                cx.update_global::<DocumentState, _>(|state, _| state.reload_documents(&uids));
+                cx.refresh_windows();
+
+                let count = applied.iter().map(|replace| replace.count).sum::<usize>();
+                match failed {
+                    Some(error) => Toast::error(error).show(window, cx),
+                    None => Toast::success(replaced_summary(count, applied.len(), skipped))
+                        .show(window, cx),
+                }
+
+                let _ = this.update(cx, |this, cx| {
+                    this.busy = false;
+                    // Offsets of the remaining matches may have moved
+                    this.results.clear();
+                    this.selected.clear();
+                    this.applied.extend(applied);
+                    cx.notify();
+                });
+            })?;
+
+            Ok::<_, Error>(())
+        })
+        .detach();
+    }
+
+    fn undo(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(replace) = self.applied.get(index).filter(|replace| !replace.undone) else {
+            return;
+        };
+        if cx.global::<DocumentState>().persistence(replace.id) == PersistenceState::Pending {
+            Toast::error(format!(
+                "\"{}\" has unsaved changes, undo once it's saved",
+                replace.title
+            ))
+            .show(window, cx);
+            return;
+        }
+
+        let documents = cx.global::<RepositoryState>().documents.clone();
+        let (id, before, after) = (replace.id, replace.before.clone(), replace.after.clone());
+        let window_handle = window.window_handle();
+
+        cx.spawn(async move |this, cx| {
+            let result = async {
+                let mut document = documents.get_document_by_id(id).await?;
+                if document.content != after {
+                    bail!("the document was edited since");
+                }
+                document.content = before;
+                documents.update_document(document).await
+            }
+            .await;
+
+            cx.update_window(window_handle, |_, window, cx| match result {
+                Ok(()) => {
+                    cx.update_global::<DocumentState, _>(|state, _| state.reload_documents(&[id]));
+                    cx.refresh_windows();
+                    let _ = this.update(cx, |this, cx| {
+                        if let Some(replace) = this.applied.get_mut(index) {
+                            replace.undone = true;
+                        }
+                        cx.notify();
+                    });
+                }
+                Err(e) => {
+                    tracing::error!("failed to undo replacement: {:?}", e);
+                    Toast::error(format!("Can't undo the replacement: {}", e)).show(window, cx);
+                }
+            })?;
+
+            Ok::<_, Error>(())
+        })
+        .detach();
+    }
+
+    fn set_selected(&mut self, id: i32, matches: Vec<TextMatch>, checked: bool) {
+        for text_match in matches {
+            if checked {
+                self.selected.insert((id, text_match));
+            } else {
+                self.selected.remove(&(id, text_match));
+            }
+        }
+    }
+
+    fn render_results(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let fg = cx.theme().foreground;
+        let muted_fg = cx.theme().muted_foreground;
+        let border = cx.theme().border;
+        let highlight = cx.theme().primary;
+
+        let documents = self.results.iter().map(|document| {
+            let id = document.id;
+            let all = document.matches.clone();
+            let all_selected = all
+                .iter()
+                .all(|text_match| self.selected.contains(&(id, text_match.clone())));
+
+            let matches = document.matches.iter().enumerate().map(|(ix, text_match)| {
+                let key = (id, text_match.clone());
+                let checked = self.selected.contains(&key);
+                let text_match = text_match.clone();
+
+                h_flex()
+                    .gap_2()
+                    .pl_4()
+                    .py_0p5()
+                    .child(
+                        Checkbox::new(SharedString::from(format!("replace-match-{}-{}", id, ix)))
+                            .checked(checked)
+                            .xsmall()
+                            .on_click(cx.listener(move |this, checked: &bool, _, cx| {
+                                this.set_selected(id, vec![text_match.clone()], *checked);
+                                cx.notify();
+                            })),
+                    )
+                    .child(
+                        h_flex()
+                            .flex_1()
+                            .min_w_0()
+                            .text_xs()
+                            .text_color(muted_fg)
+                            .child(div().truncate().child(key.1.before.clone()))
+                            .child(
+                                div()
+                                    .flex_shrink_0()
+                                    .font_semibold()
+                                    .text_color(highlight)
+                                    .child(key.1.text.clone()),
+                            )
+                            .child(div().truncate().child(key.1.after.clone())),
+                    )
+            });
+
+            v_flex()
+                .py_1()
+                .border_t_1()
+                .border_color(border)
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(
+                            Checkbox::new(("replace-document", id as usize))
+                                .checked(all_selected)
+                                .xsmall()
+                                .on_click(cx.listener(move |this, checked: &bool, _, cx| {
+                                    this.set_selected(id, all.clone(), *checked);
+                                    cx.notify();
+                                })),
+                        )
+                        .child(
+                            div()
+                                .flex_1()
+                                .text_sm()
+                                .text_color(fg)
+                                .truncate()
+                                .child(document.title.clone()),
+                        )
+                        .child(
+                            div()
+                                .text_xs()
+                                .text_color(muted_fg)
+                                .child(document.matches.len().to_string()),
+                        ),
+                )
+                .children(matches)
+        });
+
+        v_flex()
+            .max_h(px(320.))
+            .overflow_y_scrollbar()
+            .children(documents)
+    }
+
+    fn render_applied(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let fg = cx.theme().foreground;
+        let muted_fg = cx.theme().muted_foreground;
+        let border = cx.theme().border;
+
+        v_flex()
+            .gap_1()
+            .child(
+                Label::new("Replaced")
+                    .text_xs()
+                    .font_semibold()
+                    .text_color(muted_fg),
+            )
+            .children(self.applied.iter().enumerate().map(|(ix, replace)| {
+                h_flex()
+                    .justify_between()
+                    .gap_2()
+                    .py_1()
+                    .border_t_1()
+                    .border_color(border)
+                    .child(
+                        div()
+                            .flex_1()
+                            .text_sm()
+                            .text_color(fg)
+                            .truncate()
+                            .child(replace.title.clone()),
+                    )
+                    .child(div().flex_shrink_0().text_xs().text_color(muted_fg).child(
+                        match replace.count {
+                            1 => "1 replacement".to_string(),
+                            count => format!("{} replacements", count),
+                        },
+                    ))
+                    .child(
+                        Button::new(("undo-replace", ix))
+                            .xsmall()
+                            .ghost()
+                            .label(if replace.undone { "Undone" } else { "Undo" })
+                            .disabled(replace.undone || self.busy)
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                this.undo(ix, window, cx);
+                            })),
+                    )
+            }))
+    }
+}
+
+impl Render for ReplaceDialog {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let selected = self.selected.len();
+        let total = self
+            .results
+            .iter()
+            .map(|document| document.matches.len())
+            .sum::<usize>();
+
+        v_flex()
+            .gap_2()
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(div().flex_1().child(Input::new(&self.find_input).small()))
+                    .child(
+                        Button::new("find-matches")
+                            .small()
+                            .label("Find")
+                            .disabled(self.busy)
+                            .on_click(cx.listener(|this, _, window, cx| this.search(window, cx))),
+                    ),
+            )
+            .child(Input::new(&self.replace_input).small())
+            .child(
+                Checkbox::new("match-case-pattern")
+                    .checked(self.case_pattern)
+                    .small()
+                    .label("Match case pattern")
+                    .disabled(self.busy)
+                    .on_click(cx.listener(|this, checked: &bool, window, cx| {
+                        this.case_pattern = *checked;
+                        // Matches are found in any case with the option, so search again
+                        this.search(window, cx);
+                        cx.notify();
+                    })),
+            )
+            .when(!self.needle.is_empty(), |el| {
+                el.child(
+                    Label::new(match (total, self.results.len()) {
+                        (0, _) => "No matches".to_string(),
+                        (1, _) => "1 match in 1 document".to_string(),
+                        (total, 1) => format!("{} matches in 1 document", total),
+                        (total, documents) => {
+                            format!("{} matches in {} documents", total, documents)
+                        }
+                    })
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground),
+                )
+            })
+            .when(!self.results.is_empty(), |el| {
+                el.child(self.render_results(cx)).child(
+                    h_flex().justify_end().child(
+                        Button::new("replace-selected")
+                            .small()
+                            .primary()
+                            .label(match selected {
+                                1 => "Replace 1 match".to_string(),
+                                count => format!("Replace {} matches", count),
+                            })
+                            .disabled(self.busy || selected == 0)
+                            .on_click(cx.listener(|this, _, window, cx| this.apply(window, cx))),
+                    ),
+                )
+            })
+            .when(!self.applied.is_empty(), |el| {
+                el.child(self.render_applied(cx))
+            })
+    }
+}
+
+fn replaced_summary(count: usize, documents: usize, skipped: usize) -> String {
+    let mut summary = match (count, documents) {
+        (1, _) => "Replaced 1 match".to_string(),
+        (count, 1) => format!("Replaced {} matches in 1 document", count),
+        (count, documents) => format!("Replaced {} matches in {} documents", count, documents),
+    };
+    match skipped {
+        0 => {}
+        1 => summary.push_str(", 1 document with unsaved changes was skipped"),
+        skipped => summary.push_str(&format!(
+            ", {} documents with unsaved changes were skipped",
+            skipped
+        )),
+    }
+    summary
+}
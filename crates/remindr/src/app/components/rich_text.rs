@@ -1,5 +1,6 @@
 use std::{ops::Range, time::Duration};
 
+use chrono::Local;
 use gpui::{
     App, Bounds, ClipboardItem, Context, ElementInputHandler, Entity, EntityInputHandler,
     EventEmitter, FocusHandle, Focusable, FontStyle, FontWeight, HighlightStyle,
@@ -10,8 +11,22 @@ use gpui::{
 };
 use gpui_component::{ActiveTheme, menu::ContextMenuExt};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use smol::Timer;
 
+use crate::{
+    LoadingState,
+    app::{
+        date_format::DateFormat,
+        states::{
+            document_state::DocumentState,
+            settings_state::{EditorSettings, Settings, Snippet},
+        },
+    },
+    domain::database::document::expand_placeholders,
+    infrastructure::{clipboard, import::html::html_to_blocks},
+};
+
 // Actions for keyboard handling
 actions!(
     rich_text,
@@ -169,7 +184,10 @@ pub enum RichTextEvent {
     Backspace,
     Delete,
     Space,
-    Slash,
+    /// `/` was typed, with the text that was selected, which stays in place before the `/`.
+    Slash(Option<SharedString>),
+    /// Rich text pasted from another app, converted to blocks to insert after this one.
+    PasteBlocks(Vec<Value>),
 }
 
 /// Selection in the text
@@ -313,6 +331,10 @@ pub struct RichTextState {
     history_index: usize,
     marked_range: Option<Range<usize>>,
     wrapped_line_count: usize,
+    /// Ghost text displayed after the cursor, accepted with Tab.
+    suggestion: Option<String>,
+    /// Cursor and length of the content when typewriter scrolling last centered the caret.
+    followed_caret: Option<(usize, usize)>,
 }
 
 impl EventEmitter<RichTextEvent> for RichTextState {}
@@ -336,6 +358,8 @@ impl RichTextState {
             wrapped_line_count: 1,
             history_index: 0,
             marked_range: None,
+            suggestion: None,
+            followed_caret: None,
         }
     }
 
@@ -346,6 +370,8 @@ impl RichTextState {
 
     fn on_blur(&mut self, _window: &mut Window, cx: &mut Context<Self>) {
         self.blink_cursor.stop(cx);
+        self.suggestion = None;
+        self.followed_caret = None;
         cx.emit(RichTextEvent::Blur);
     }
 
@@ -385,7 +411,7 @@ impl RichTextState {
         &self,
         point: Point<Pixels>,
         window: &mut Window,
-        _cx: &mut Context<Self>,
+        cx: &mut Context<Self>,
     ) -> usize {
         if self.content.is_empty() {
             return 0;
@@ -393,7 +419,7 @@ impl RichTextState {
 
         let text_style = window.text_style();
         let font_size = text_style.font_size.to_pixels(window.rem_size());
-        let line_height = font_size * 1.5;
+        let line_height = font_size * EditorSettings::line_height(cx);
 
         // Get wrap width from bounds
         let wrap_width = self.last_bounds.map(|b| b.size.width).unwrap_or(px(1000.0));
@@ -654,7 +680,66 @@ impl RichTextState {
         self.focus_handle.focus(window, cx);
     }
 
+    pub fn suggestion(&self) -> Option<&str> {
+        self.suggestion.as_deref()
+    }
+
+    pub fn set_suggestion(&mut self, suggestion: Option<String>, cx: &mut Context<Self>) {
+        let suggestion = suggestion.filter(|s| !s.is_empty());
+        if self.suggestion != suggestion {
+            self.suggestion = suggestion;
+            cx.notify();
+        }
+    }
+
+    /// Inserts the pending suggestion at the cursor. Returns false when there was none.
+    fn accept_suggestion(&mut self, cx: &mut Context<Self>) -> bool {
+        let at_end = self.selection.is_empty() && self.selection.head() == self.content.len();
+        match self.suggestion.take() {
+            Some(suggestion) if at_end => {
+                self.insert_text(&suggestion, cx);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Replaces the snippet trigger typed right before the cursor with its expansion.
+    /// Returns false when there was none.
+    fn expand_snippet(&mut self, cx: &mut Context<Self>) -> bool {
+        if !self.selection.is_empty() {
+            return false;
+        }
+        let end = self.selection.head();
+        let Some(snippet) = cx
+            .try_global::<Settings>()
+            .and_then(|settings| Snippet::ending(&settings.snippets, &self.content[..end]))
+            .cloned()
+        else {
+            return false;
+        };
+
+        let format = DateFormat::new(cx);
+        let now = Local::now();
+        let variables = [
+            ("date", format.date(now.date_naive())),
+            ("time", format.time(now.time())),
+        ];
+        let (expansion, cursor) = expand_placeholders(&snippet.expansion, &variables);
+
+        let start = end - snippet.trigger.len();
+        self.selection = Selection::new(start, end);
+        self.insert_text(&expansion, cx);
+        if let Some(cursor) = cursor {
+            self.selection = Selection::cursor(start + cursor);
+        }
+        true
+    }
+
     fn push_history(&mut self) {
+        // Any edit invalidates the current suggestion
+        self.suggestion = None;
+
         // Remove future history if we're not at the end
         if self.history_index < self.history.len() - 1 {
             self.history.truncate(self.history_index + 1);
@@ -671,6 +756,7 @@ impl RichTextState {
     }
 
     fn undo(&mut self, cx: &mut Context<Self>) {
+        self.suggestion = None;
         if self.history_index > 0 {
             self.history_index -= 1;
             let (content, spans, selection) = self.history[self.history_index].clone();
@@ -683,6 +769,7 @@ impl RichTextState {
     }
 
     fn redo(&mut self, cx: &mut Context<Self>) {
+        self.suggestion = None;
         if self.history_index < self.history.len() - 1 {
             self.history_index += 1;
             let (content, spans, selection) = self.history[self.history_index].clone();
@@ -949,6 +1036,10 @@ impl RichTextState {
         cx.notify();
     }
 
+    pub fn is_all_selected(&self) -> bool {
+        self.selection.normalized() == (0, self.content.len())
+    }
+
     fn select_all(&mut self, cx: &mut Context<Self>) {
         self.selection = Selection::new(0, self.content.len());
         cx.notify();
@@ -975,6 +1066,16 @@ impl RichTextState {
     }
 
     fn paste(&mut self, cx: &mut Context<Self>) {
+        // HTML copied from a browser or an office app keeps its structure, unless it is a
+        // single paragraph which is inserted as text
+        if let Some(blocks) = clipboard::read_html()
+            .map(|html| html_to_blocks(&html))
+            .filter(|blocks| blocks.len() > 1 || blocks.iter().any(|block| block["type"] != "text"))
+        {
+            cx.emit(RichTextEvent::PasteBlocks(blocks));
+            return;
+        }
+
         if let Some(clipboard) = cx.read_from_clipboard()
             && let Some(text) = clipboard.text()
         {
@@ -1396,10 +1497,49 @@ impl Styled for RichTextView {
     }
 }
 
+/// Scrolls the current document of the window so that the caret, at `caret_y` in the
+/// window, is in the middle of it. Only done once the caret moved, so the document can
+/// still be scrolled away with the mouse.
+fn follow_caret(state: &Entity<RichTextState>, caret_y: Pixels, window: &mut Window, cx: &mut App) {
+    let caret = {
+        let state = state.read(cx);
+        (state.selection.head(), state.content.len())
+    };
+    if state.read(cx).followed_caret == Some(caret) {
+        return;
+    }
+    state.update(cx, |state, _| state.followed_caret = Some(caret));
+
+    let window_id = window.window_handle().window_id();
+    let Some(scroll_handle) = cx
+        .try_global::<DocumentState>()
+        .and_then(|documents| documents.workspace(window_id))
+        .and_then(|workspace| workspace.get_current_document())
+        .and_then(|document| match &document.state {
+            LoadingState::Loaded(content) => Some(content.scroll_handle.clone()),
+            _ => None,
+        })
+    else {
+        return;
+    };
+
+    let viewport = scroll_handle.bounds();
+    let mut offset = scroll_handle.offset();
+    offset.y = offset.y - (caret_y - viewport.center().y);
+    let max_offset = scroll_handle.max_offset().height;
+    if offset.y > px(0.) {
+        offset.y = px(0.);
+    } else if offset.y < -max_offset {
+        offset.y = -max_offset;
+    }
+    scroll_handle.set_offset(offset);
+    window.refresh();
+}
+
 impl RenderOnce for RichTextView {
     fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
         let content = self.state.read(cx).content.clone();
-        let highlights = self.state.read(cx).build_highlights(cx);
+        let mut highlights = self.state.read(cx).build_highlights(cx);
         let selection = self.state.read(cx).selection;
         let focus_handle = self.state.read(cx).focus_handle.clone();
         let is_focused = focus_handle.is_focused(window);
@@ -1411,7 +1551,11 @@ impl RenderOnce for RichTextView {
 
         // Cursor position for IME/input handling
         let cursor_pos = selection.head().min(content.len());
-        let line_height = font_size * 1.5;
+        let line_height_factor = EditorSettings::line_height(cx);
+        let line_height = font_size * line_height_factor;
+        let typewriter_scrolling = cx
+            .try_global::<Settings>()
+            .is_some_and(|settings| settings.editor.typewriter_scrolling);
 
         let state = self.state.clone();
         let style = self.style;
@@ -1453,7 +1597,11 @@ impl RenderOnce for RichTextView {
             .on_action({
                 let state = state.clone();
                 move |_: &Tab, _, cx| {
-                    state.update(cx, |_s, cx| cx.emit(RichTextEvent::Tab));
+                    state.update(cx, |s, cx| {
+                        if !s.accept_suggestion(cx) && !s.expand_snippet(cx) {
+                            cx.emit(RichTextEvent::Tab);
+                        }
+                    });
                 }
             })
             .on_action({
@@ -1469,8 +1617,12 @@ impl RenderOnce for RichTextView {
                 let state = state.clone();
                 move |_: &Slash, _, cx| {
                     state.update(cx, |s, cx| {
+                        let (start, end) = s.selection.normalized();
+                        let selection = (start < end)
+                            .then(|| SharedString::from(s.content[start..end].to_string()));
+                        s.selection = Selection::cursor(end);
                         s.insert_text("/", cx);
-                        cx.emit(RichTextEvent::Slash);
+                        cx.emit(RichTextEvent::Slash(selection));
                     });
                 }
             })
@@ -1537,7 +1689,12 @@ impl RenderOnce for RichTextView {
             .on_action({
                 let state = state.clone();
                 move |_: &SelectAll, _, cx| {
-                    state.update(cx, |s, cx| s.select_all(cx));
+                    // Once the whole text is selected, the block itself gets selected
+                    if state.read(cx).is_all_selected() {
+                        cx.propagate();
+                    } else {
+                        state.update(cx, |s, cx| s.select_all(cx));
+                    }
                 }
             })
             .on_action({
@@ -1643,6 +1800,7 @@ impl RenderOnce for RichTextView {
             .w_full()
             .cursor_text()
             .min_h(line_height)
+            .line_height(line_height)
             .relative()
             // Selection and cursor overlay (absolute positioned, painted first as background)
             .child({
@@ -1664,7 +1822,7 @@ impl RenderOnce for RichTextView {
                     move |bounds, _, window, cx| {
                         let text_style = window.text_style();
                         let font_size = text_style.font_size.to_pixels(window.rem_size());
-                        let line_height = font_size * 1.5;
+                        let line_height = font_size * line_height_factor;
 
                         // Paint selection and cursor using shape_text for accurate positioning
                         let wrap_width = bounds.size.width;
@@ -1770,6 +1928,22 @@ impl RenderOnce for RichTextView {
                                     }
                                 }
                             }
+
+                            // Followed even while the cursor blinks off
+                            if typewriter_scrolling
+                                && is_focused_for_overlay
+                                && selection_for_overlay.is_empty()
+                                && let Some(cursor_pos) = wrapped.iter().find_map(|line| {
+                                    line.position_for_index(cursor_pos_for_overlay, line_height)
+                                })
+                            {
+                                follow_caret(
+                                    &state_for_overlay,
+                                    bounds.top() + cursor_pos.y + line_height / 2.,
+                                    window,
+                                    cx,
+                                );
+                            }
                         }
                     },
                 )
@@ -1781,7 +1955,25 @@ impl RenderOnce for RichTextView {
             // Text content using StyledText (determines the height)
             .child({
                 let state_for_input = state.clone();
-                let display_content: SharedString = if content.is_empty() {
+
+                // Ghost suggestion is rendered after the content when the cursor sits at the end
+                let suggestion = self
+                    .state
+                    .read(cx)
+                    .suggestion()
+                    .filter(|_| is_focused && selection.is_empty() && cursor_pos == content.len())
+                    .map(str::to_string);
+
+                let display_content: SharedString = if let Some(suggestion) = suggestion {
+                    highlights.push((
+                        content.len()..content.len() + suggestion.len(),
+                        HighlightStyle {
+                            color: Some(theme.muted_foreground),
+                            ..Default::default()
+                        },
+                    ));
+                    format!("{}{}", content, suggestion).into()
+                } else if content.is_empty() {
                     " ".into()
                 } else {
                     content.clone().into()
@@ -1,16 +1,35 @@
-use crate::app::{
-    apply_theme,
-    states::settings_state::{Settings, ThemeMode},
+use crate::{
+    app::{
+        apply_theme,
+        backup::{self, BackupState},
+        components::{
+            attachment_cleanup_dialog, block_toolbar::ToolbarAction, confirm_dialog::ConfirmDialog,
+            integrity_dialog,
+        },
+        date_format::DateFormat,
+        remindr::Remindr,
+        states::{
+            progress_state::ProgressTask,
+            recovery_journal::RecoveryJournal,
+            repository_state::RepositoryState,
+            settings_state::{
+                ApiSettings, BackupFrequency, ClockFormat, DateOrder, DateSettings, FirstBlockType,
+                FirstWeekday, NewBlockType, Settings, Snippet, ThemeMode,
+            },
+        },
+    },
+    infrastructure::{database, vault::file_vault},
 };
+use anyhow::Error;
 use gpui::prelude::FluentBuilder;
 use gpui::{
-    App, AppContext, BorrowAppContext, Bounds, Context, Corner, ElementId, Entity, Hsla,
-    InteractiveElement, IntoElement, ParentElement, Pixels, Render, RenderOnce, SharedString, Size,
-    StatefulInteractiveElement, Styled, TitlebarOptions, Window, WindowBounds, WindowId,
-    WindowKind, WindowOptions, div, point, px, relative, size,
+    App, AppContext, BorrowAppContext, Bounds, ClipboardItem, Context, Corner, ElementId, Entity,
+    Hsla, InteractiveElement, IntoElement, ParentElement, PathPromptOptions, Pixels, Render,
+    RenderOnce, SharedString, Size, StatefulInteractiveElement, Styled, TitlebarOptions, Window,
+    WindowBounds, WindowId, WindowKind, WindowOptions, div, point, px, relative, size,
 };
 use gpui_component::{
-    ActiveTheme, Disableable, Icon, IconName, Root, Sizable, StyledExt,
+    ActiveTheme, Disableable, Icon, IconName, Root, Selectable, Sizable, StyledExt, WindowExt,
     button::{Button, ButtonVariants},
     h_flex,
     input::{Input, InputEvent, InputState, NumberInput, NumberInputEvent},
@@ -35,6 +54,18 @@ enum SettingsSection {
     Appearance,
     Editor,
     Blocks,
+    Snippets,
+    Assistant,
+    Security,
+    Storage,
+    Data,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum EncryptionAction {
+    Encrypt,
+    ChangePassphrase,
+    Decrypt,
 }
 
 struct NodeComponent {
@@ -63,6 +94,48 @@ const NODE_COMPONENTS: &[NodeComponent] = &[
         description: "Horizontal separator line.",
         icon_path: "icons/separator-horizontal.svg",
     },
+    NodeComponent {
+        id: "list",
+        label: "List",
+        description: "Bulleted and numbered list items.",
+        icon_path: "icons/list.svg",
+    },
+    NodeComponent {
+        id: "quote",
+        label: "Quote",
+        description: "Indented block for quotations.",
+        icon_path: "icons/text-quote.svg",
+    },
+    NodeComponent {
+        id: "todo",
+        label: "To-do",
+        description: "Task with a checkbox and a status.",
+        icon_path: "icons/square-check.svg",
+    },
+    NodeComponent {
+        id: "attachment",
+        label: "File",
+        description: "Attached file opened with its default application.",
+        icon_path: "icons/paperclip.svg",
+    },
+    NodeComponent {
+        id: "drawing",
+        label: "Drawing",
+        description: "Freehand sketch drawn with a pen and an eraser.",
+        icon_path: "icons/brush.svg",
+    },
+    NodeComponent {
+        id: "equation",
+        label: "Equation",
+        description: "Math formula written in LaTeX.",
+        icon_path: "icons/sigma.svg",
+    },
+    NodeComponent {
+        id: "synced_block",
+        label: "Synced block",
+        description: "Content shared between documents, edited everywhere at once.",
+        icon_path: "icons/refresh-cw.svg",
+    },
 ];
 
 struct HeadingLevel {
@@ -72,6 +145,11 @@ struct HeadingLevel {
 }
 
 const HEADING_LEVELS: &[HeadingLevel] = &[
+    HeadingLevel {
+        id: "heading_1",
+        label: "Heading 1",
+        icon_path: "icons/heading-1.svg",
+    },
     HeadingLevel {
         id: "heading_2",
         label: "Heading 2",
@@ -91,12 +169,28 @@ pub struct SettingsWindow {
     ui_font_size_input: Entity<InputState>,
     editor_font_size_input: Entity<InputState>,
     zoom_input: Entity<InputState>,
+    line_height_input: Entity<InputState>,
+    block_spacing_input: Entity<InputState>,
+    autosave_delay_input: Entity<InputState>,
     h1_font_size_input: Entity<InputState>,
     h2_font_size_input: Entity<InputState>,
     h3_font_size_input: Entity<InputState>,
     text_font_size_input: Entity<InputState>,
     light_theme_search: Entity<InputState>,
     dark_theme_search: Entity<InputState>,
+    assistant_endpoint_input: Entity<InputState>,
+    assistant_api_key_input: Entity<InputState>,
+    assistant_model_input: Entity<InputState>,
+    email_server_input: Entity<InputState>,
+    email_username_input: Entity<InputState>,
+    email_password_input: Entity<InputState>,
+    email_folder_input: Entity<InputState>,
+    backups_kept_input: Entity<InputState>,
+    snippet_trigger_input: Entity<InputState>,
+    snippet_expansion_input: Entity<InputState>,
+    passphrase_input: Entity<InputState>,
+    passphrase_confirm_input: Entity<InputState>,
+    encryption_in_progress: bool,
 }
 
 impl SettingsWindow {
@@ -121,6 +215,24 @@ impl SettingsWindow {
             state
         });
 
+        let line_height_input = cx.new(|cx| {
+            let mut state = InputState::new(window, cx);
+            state.set_value(format!("{}", settings.editor.line_height), window, cx);
+            state
+        });
+
+        let block_spacing_input = cx.new(|cx| {
+            let mut state = InputState::new(window, cx);
+            state.set_value(format!("{}", settings.editor.block_spacing), window, cx);
+            state
+        });
+
+        let autosave_delay_input = cx.new(|cx| {
+            let mut state = InputState::new(window, cx);
+            state.set_value(format!("{}", settings.editor.autosave_delay), window, cx);
+            state
+        });
+
         let block_sizes = &settings.editor.block_font_sizes;
 
         let h1_font_size_input = cx.new(|cx| {
@@ -176,6 +288,39 @@ impl SettingsWindow {
         })
         .detach();
 
+        cx.subscribe_in(
+            &line_height_input,
+            window,
+            |this, _, event: &InputEvent, _, cx| {
+                if let InputEvent::Change = event {
+                    this.on_line_height_changed(cx);
+                }
+            },
+        )
+        .detach();
+
+        cx.subscribe_in(
+            &block_spacing_input,
+            window,
+            |this, _, event: &InputEvent, _, cx| {
+                if let InputEvent::Change = event {
+                    this.on_block_spacing_changed(cx);
+                }
+            },
+        )
+        .detach();
+
+        cx.subscribe_in(
+            &autosave_delay_input,
+            window,
+            |this, _, event: &InputEvent, _, cx| {
+                if let InputEvent::Change = event {
+                    this.on_autosave_delay_changed(cx);
+                }
+            },
+        )
+        .detach();
+
         cx.subscribe_in(
             &ui_font_size_input,
             window,
@@ -239,6 +384,69 @@ impl SettingsWindow {
         )
         .detach();
 
+        cx.subscribe_in(
+            &line_height_input,
+            window,
+            |this, _, event: &NumberInputEvent, window, cx| {
+                let NumberInputEvent::Step(action) = event;
+                this.step_input(
+                    &this.line_height_input.clone(),
+                    action,
+                    StepInputParams {
+                        step: 0.1,
+                        min: 1.0,
+                        max: 2.5,
+                    },
+                    window,
+                    cx,
+                );
+                this.on_line_height_changed(cx);
+            },
+        )
+        .detach();
+
+        cx.subscribe_in(
+            &block_spacing_input,
+            window,
+            |this, _, event: &NumberInputEvent, window, cx| {
+                let NumberInputEvent::Step(action) = event;
+                this.step_input(
+                    &this.block_spacing_input.clone(),
+                    action,
+                    StepInputParams {
+                        step: 1.0,
+                        min: 0.0,
+                        max: 32.0,
+                    },
+                    window,
+                    cx,
+                );
+                this.on_block_spacing_changed(cx);
+            },
+        )
+        .detach();
+
+        cx.subscribe_in(
+            &autosave_delay_input,
+            window,
+            |this, _, event: &NumberInputEvent, window, cx| {
+                let NumberInputEvent::Step(action) = event;
+                this.step_input(
+                    &this.autosave_delay_input.clone(),
+                    action,
+                    StepInputParams {
+                        step: 0.5,
+                        min: 0.5,
+                        max: 10.0,
+                    },
+                    window,
+                    cx,
+                );
+                this.on_autosave_delay_changed(cx);
+            },
+        )
+        .detach();
+
         // Block font size subscriptions
         for (input, block_key) in [
             (&h1_font_size_input, "heading_1"),
@@ -310,6 +518,129 @@ impl SettingsWindow {
         )
         .detach();
 
+        let assistant = &settings.assistant;
+        let assistant_endpoint_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("https://api.openai.com/v1")
+                .default_value(assistant.endpoint.clone())
+        });
+        let assistant_api_key_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("sk-...")
+                .masked(true)
+                .default_value(assistant.api_key.clone())
+        });
+        let assistant_model_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("gpt-4o-mini")
+                .default_value(assistant.model.clone())
+        });
+
+        for input in [
+            &assistant_endpoint_input,
+            &assistant_api_key_input,
+            &assistant_model_input,
+        ] {
+            cx.subscribe_in(input, window, |this, _, event: &InputEvent, _, cx| {
+                if let InputEvent::Change = event {
+                    this.on_assistant_changed(cx);
+                }
+            })
+            .detach();
+        }
+
+        let email = &settings.email;
+        let email_server_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("imap.example.com")
+                .default_value(email.server.clone())
+        });
+        let email_username_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("me@example.com")
+                .default_value(email.username.clone())
+        });
+        let email_password_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("Password")
+                .masked(true)
+                .default_value(email.password.clone())
+        });
+        let email_folder_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("INBOX")
+                .default_value(email.folder.clone())
+        });
+
+        for input in [
+            &email_server_input,
+            &email_username_input,
+            &email_password_input,
+            &email_folder_input,
+        ] {
+            cx.subscribe_in(input, window, |this, _, event: &InputEvent, _, cx| {
+                if let InputEvent::Change = event {
+                    this.on_email_changed(cx);
+                }
+            })
+            .detach();
+        }
+
+        let backups_kept_input = cx.new(|cx| {
+            let mut state = InputState::new(window, cx);
+            state.set_value(format!("{}", settings.backup.keep), window, cx);
+            state
+        });
+
+        cx.subscribe_in(
+            &backups_kept_input,
+            window,
+            |this, _, event: &InputEvent, _, cx| {
+                if let InputEvent::Change = event {
+                    this.on_backups_kept_changed(cx);
+                }
+            },
+        )
+        .detach();
+
+        cx.subscribe_in(
+            &backups_kept_input,
+            window,
+            |this, _, event: &NumberInputEvent, window, cx| {
+                let NumberInputEvent::Step(action) = event;
+                this.step_input(
+                    &this.backups_kept_input.clone(),
+                    action,
+                    StepInputParams {
+                        step: 1.0,
+                        min: 1.0,
+                        max: 100.0,
+                    },
+                    window,
+                    cx,
+                );
+                this.on_backups_kept_changed(cx);
+            },
+        )
+        .detach();
+
+        let snippet_trigger_input =
+            cx.new(|cx| InputState::new(window, cx).placeholder("Trigger, e.g. ;sig"));
+        let snippet_expansion_input = cx.new(|cx| {
+            InputState::new(window, cx).placeholder("Expansion, e.g. Best regards, {{cursor}}")
+        });
+
+        let passphrase_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("New passphrase")
+                .masked(true)
+        });
+        let passphrase_confirm_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("Confirm passphrase")
+                .masked(true)
+        });
+
         // Re-render when global settings change (e.g. from file watcher)
         cx.observe_global::<Settings>(|_this, cx| {
             cx.notify();
@@ -321,12 +652,28 @@ impl SettingsWindow {
             ui_font_size_input,
             editor_font_size_input,
             zoom_input,
+            line_height_input,
+            block_spacing_input,
+            autosave_delay_input,
             h1_font_size_input,
             h2_font_size_input,
             h3_font_size_input,
             text_font_size_input,
             light_theme_search,
             dark_theme_search,
+            assistant_endpoint_input,
+            assistant_api_key_input,
+            assistant_model_input,
+            email_server_input,
+            email_username_input,
+            email_password_input,
+            email_folder_input,
+            backups_kept_input,
+            snippet_trigger_input,
+            snippet_expansion_input,
+            passphrase_input,
+            passphrase_confirm_input,
+            encryption_in_progress: false,
         }
     }
 
@@ -386,6 +733,39 @@ impl SettingsWindow {
         }
     }
 
+    fn on_line_height_changed(&self, cx: &mut Context<Self>) {
+        let value = self.line_height_input.read(cx).value();
+        if let Ok(line_height) = value.parse::<f32>() {
+            let line_height = line_height.clamp(1.0, 2.5);
+            cx.update_global::<Settings, _>(|settings, _| {
+                settings.editor.line_height = line_height;
+                settings.save();
+            });
+        }
+    }
+
+    fn on_block_spacing_changed(&self, cx: &mut Context<Self>) {
+        let value = self.block_spacing_input.read(cx).value();
+        if let Ok(spacing) = value.parse::<f32>() {
+            let spacing = spacing.clamp(0.0, 32.0);
+            cx.update_global::<Settings, _>(|settings, _| {
+                settings.editor.block_spacing = spacing;
+                settings.save();
+            });
+        }
+    }
+
+    fn on_autosave_delay_changed(&self, cx: &mut Context<Self>) {
+        let value = self.autosave_delay_input.read(cx).value();
+        if let Ok(delay) = value.parse::<f32>() {
+            let delay = delay.clamp(0.5, 10.0);
+            cx.update_global::<Settings, _>(|settings, _| {
+                settings.editor.autosave_delay = delay;
+                settings.save();
+            });
+        }
+    }
+
     fn on_block_font_size_changed(block_key: &str, this: &Self, cx: &mut Context<Self>) {
         let input = match block_key {
             "heading_1" => &this.h1_font_size_input,
@@ -409,66 +789,506 @@ impl SettingsWindow {
         }
     }
 
-    pub fn open(cx: &mut App) {
-        // If a settings window already exists, focus it
-        if let Some(window_id) = *SETTINGS_WINDOW.lock().unwrap() {
-            for window in cx.windows() {
-                if window.window_id() == window_id {
-                    let _ = window.update(cx, |_, window, _| {
-                        window.activate_window();
-                    });
-                    return;
-                }
-            }
-            // Window no longer exists, clear the handle
-            *SETTINGS_WINDOW.lock().unwrap() = None;
-        }
+    fn on_assistant_changed(&self, cx: &mut Context<Self>) {
+        let endpoint = self
+            .assistant_endpoint_input
+            .read(cx)
+            .value()
+            .trim()
+            .to_string();
+        let api_key = self
+            .assistant_api_key_input
+            .read(cx)
+            .value()
+            .trim()
+            .to_string();
+        let model = self
+            .assistant_model_input
+            .read(cx)
+            .value()
+            .trim()
+            .to_string();
+
+        cx.update_global::<Settings, _>(move |settings, _| {
+            settings.assistant.endpoint = endpoint;
+            settings.assistant.api_key = api_key;
+            settings.assistant.model = model;
+            settings.save();
+        });
+    }
 
-        let window_size = size(px(860.), px(600.));
-        let window_bounds = Bounds::centered(None, window_size, cx);
+    fn on_email_changed(&self, cx: &mut Context<Self>) {
+        let value = |input: &Entity<InputState>, cx: &Context<Self>| {
+            input.read(cx).value().trim().to_string()
+        };
+        let server = value(&self.email_server_input, cx);
+        let username = value(&self.email_username_input, cx);
+        let password = self.email_password_input.read(cx).value().to_string();
+        let folder = value(&self.email_folder_input, cx);
+
+        cx.update_global::<Settings, _>(move |settings, _| {
+            settings.email.server = server;
+            settings.email.username = username;
+            settings.email.password = password;
+            settings.email.folder = folder;
+            settings.save();
+        });
+    }
 
-        cx.spawn(async move |cx| {
-            let options = WindowOptions {
-                window_bounds: Some(WindowBounds::Windowed(window_bounds)),
-                window_min_size: Some(Size {
-                    width: px(640.),
-                    height: px(400.),
-                }),
-                kind: WindowKind::Normal,
-                titlebar: Some(TitlebarOptions {
-                    appears_transparent: true,
-                    title: Some("Settings".into()),
-                    traffic_light_position: Some(point(px(9.0), px(9.0))),
-                }),
-                ..Default::default()
-            };
+    /// Adds the snippet typed in the inputs, replacing the one with the same trigger.
+    fn add_snippet(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let trigger = self
+            .snippet_trigger_input
+            .read(cx)
+            .value()
+            .trim()
+            .to_string();
+        let expansion = self.snippet_expansion_input.read(cx).value().to_string();
+
+        if trigger.is_empty() || trigger.contains(char::is_whitespace) {
+            window.push_notification("A trigger is a single word".to_string(), cx);
+            return;
+        }
+        if expansion.is_empty() {
+            window.push_notification("The expansion can't be empty".to_string(), cx);
+            return;
+        }
 
-            let window = cx
-                .open_window(options, |window, cx| {
-                    let view = cx.new(|cx| SettingsWindow::new(window, cx));
-                    cx.new(|cx| Root::new(view, window, cx))
-                })
-                .expect("failed to open settings window");
+        cx.update_global::<Settings, _>(|settings, _| {
+            settings
+                .snippets
+                .retain(|snippet| snippet.trigger != trigger);
+            settings.snippets.push(Snippet { trigger, expansion });
+            settings.save();
+        });
+        for input in [&self.snippet_trigger_input, &self.snippet_expansion_input] {
+            input.update(cx, |input, cx| input.set_value("", window, cx));
+        }
+        cx.notify();
+    }
 
-            *SETTINGS_WINDOW.lock().unwrap() = Some(window.window_id());
+    fn on_backups_kept_changed(&self, cx: &mut Context<Self>) {
+        let value = self.backups_kept_input.read(cx).value();
+        if let Ok(keep) = value.parse::<u32>() {
+            let keep = keep.clamp(1, 100);
+            cx.update_global::<Settings, _>(|settings, _| {
+                settings.backup.keep = keep;
+                settings.save();
+            });
+        }
+    }
 
-            window
-                .update(cx, |_, window, _| {
-                    window.activate_window();
-                    window.set_window_title("Settings");
-                    window.set_background_appearance(gpui::WindowBackgroundAppearance::Opaque);
-                })
-                .expect("failed to update settings window");
+    fn apply_encryption(
+        &mut self,
+        action: EncryptionAction,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.encryption_in_progress {
+            return;
+        }
 
-            Ok::<_, anyhow::Error>(())
-        })
-        .detach();
-    }
+        let passphrase = self.passphrase_input.read(cx).value().to_string();
+        let confirmation = self.passphrase_confirm_input.read(cx).value().to_string();
 
-    fn open_settings_json() {
-        if let Some(home) = dirs::home_dir() {
-            let config_path = if cfg!(target_os = "linux") || cfg!(target_os = "macos") {
-                home.join(".config").join("remindr")
+        if action != EncryptionAction::Decrypt {
+            if passphrase.chars().count() < database::MIN_PASSPHRASE_LEN {
+                window.push_notification(
+                    format!(
+                        "The passphrase must be at least {} characters long",
+                        database::MIN_PASSPHRASE_LEN
+                    ),
+                    cx,
+                );
+                return;
+            }
+            if passphrase != confirmation {
+                window.push_notification("The passphrases do not match".to_string(), cx);
+                return;
+            }
+        }
+
+        let Some(pool) = cx
+            .try_global::<RepositoryState>()
+            .map(|state| state.pool.clone())
+        else {
+            return;
+        };
+        let database_path = match Remindr::new().database_path() {
+            Ok(path) => path,
+            Err(e) => {
+                window.push_notification(format!("Encryption failed: {}", e), cx);
+                return;
+            }
+        };
+        let pool_config = cx.global::<Settings>().database.pool_config();
+
+        self.encryption_in_progress = true;
+        cx.notify();
+
+        let title = match action {
+            EncryptionAction::Encrypt => "Encrypting the database",
+            EncryptionAction::ChangePassphrase => "Changing the passphrase",
+            EncryptionAction::Decrypt => "Removing the encryption",
+        };
+        let task = ProgressTask::start(title, false, cx);
+        let window_handle = window.window_handle();
+
+        cx.spawn(async move |this, cx| {
+            let result = match action {
+                EncryptionAction::Encrypt => {
+                    database::export(&pool, &database_path, Some(&passphrase), &pool_config)
+                        .await
+                        .map(Some)
+                }
+                EncryptionAction::ChangePassphrase => {
+                    database::change_passphrase(&pool, &passphrase)
+                        .await
+                        .map(|_| None)
+                }
+                EncryptionAction::Decrypt => {
+                    database::export(&pool, &database_path, None, &pool_config)
+                        .await
+                        .map(Some)
+                }
+            };
+            cx.update(|cx| task.finish(cx));
+
+            cx.update_window(window_handle, |_, window, cx| {
+                match result {
+                    Ok(reopened) => {
+                        if let Some(pool) = reopened {
+                            let repositories =
+                                RepositoryState::for_settings(pool, cx.try_global::<Settings>());
+                            cx.set_global(repositories);
+                        }
+                        cx.update_global::<Settings, _>(|settings, _| {
+                            settings.security.encrypted = action != EncryptionAction::Decrypt;
+                            settings.save();
+                        });
+                        if action == EncryptionAction::Encrypt {
+                            RecoveryJournal::clear();
+                        }
+
+                        let message = match action {
+                            EncryptionAction::Encrypt => "Database encrypted",
+                            EncryptionAction::ChangePassphrase => "Passphrase changed",
+                            EncryptionAction::Decrypt => "Encryption removed",
+                        };
+                        window.push_notification(message.to_string(), cx);
+                    }
+                    Err(e) => {
+                        tracing::error!("encryption change failed: {:?}", e);
+                        window.push_notification(format!("Encryption failed: {}", e), cx);
+                    }
+                }
+
+                let _ = this.update(cx, |this, cx| {
+                    this.encryption_in_progress = false;
+                    for input in [&this.passphrase_input, &this.passphrase_confirm_input] {
+                        input.update(cx, |input, cx| {
+                            input.set_value("", window, cx);
+                        });
+                    }
+                    cx.notify();
+                });
+            })?;
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
+    fn choose_vault_folder(&mut self, cx: &mut Context<Self>) {
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: false,
+            directories: true,
+            multiple: false,
+            prompt: Some("Use as vault".into()),
+        });
+
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(paths))) = paths.await else {
+                return Ok(());
+            };
+            let Some(path) = paths.into_iter().next() else {
+                return Ok(());
+            };
+
+            this.update(cx, |_, cx| {
+                cx.update_global::<Settings, _>(|settings, _| {
+                    settings.vault.path = path.to_string_lossy().to_string();
+                    settings.save();
+                });
+                cx.notify();
+            })?;
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
+    fn choose_backup_folder(&mut self, cx: &mut Context<Self>) {
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: false,
+            directories: true,
+            multiple: false,
+            prompt: Some("Back up here".into()),
+        });
+
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(paths))) = paths.await else {
+                return Ok(());
+            };
+            let Some(path) = paths.into_iter().next() else {
+                return Ok(());
+            };
+
+            this.update(cx, |_, cx| {
+                cx.update_global::<Settings, _>(|settings, _| {
+                    settings.backup.directory = path.to_string_lossy().to_string();
+                    settings.save();
+                });
+                cx.notify();
+            })?;
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
+    /// Writes the settings in a folder picked by the user.
+    fn export_settings(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let json = match cx.global::<Settings>().to_export_json() {
+            Ok(json) => json,
+            Err(e) => {
+                window.push_notification(format!("Failed to export the settings: {}", e), cx);
+                return;
+            }
+        };
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: false,
+            directories: true,
+            multiple: false,
+            prompt: Some("Export here".into()),
+        });
+        let window_handle = window.window_handle();
+
+        cx.spawn(async move |_, cx| {
+            let Ok(Ok(Some(paths))) = paths.await else {
+                return Ok(());
+            };
+            let Some(directory) = paths.into_iter().next() else {
+                return Ok(());
+            };
+
+            let path = file_vault::unique_path(&directory, "remindr-settings", Some("json"));
+            let message = match std::fs::write(&path, json) {
+                Ok(()) => format!("Settings exported to {}", path.display()),
+                Err(e) => {
+                    tracing::error!("failed to export settings: {:?}", e);
+                    format!("Failed to export the settings: {}", e)
+                }
+            };
+            cx.update_window(window_handle, |_, window, cx| {
+                window.push_notification(message, cx);
+            })?;
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
+    /// Replaces the settings with the ones of a file picked by the user, if they are valid.
+    fn import_settings(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: Some("Import".into()),
+        });
+        let window_handle = window.window_handle();
+
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(paths))) = paths.await else {
+                return Ok(());
+            };
+            let Some(path) = paths.into_iter().next() else {
+                return Ok(());
+            };
+            let json = std::fs::read_to_string(&path);
+
+            cx.update_window(window_handle, |_, window, cx| {
+                let imported = json
+                    .map_err(Error::from)
+                    .and_then(|json| cx.global::<Settings>().imported(&json));
+
+                match imported {
+                    Ok(settings) => {
+                        let _ =
+                            this.update(cx, |this, cx| this.apply_settings(settings, window, cx));
+                        window.push_notification("Settings imported".to_string(), cx);
+                    }
+                    Err(e) => {
+                        tracing::error!("failed to import settings: {:?}", e);
+                        window.push_notification(format!("Invalid settings file: {:#}", e), cx);
+                    }
+                }
+            })?;
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
+    fn reset_settings(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let this = cx.entity();
+
+        ConfirmDialog::new("Reset Settings")
+            .message(
+                "Reset every setting to its default value? The encryption and the vault are kept.",
+            )
+            .confirm_text("Reset")
+            .danger()
+            .on_confirm(move |window, cx| {
+                let settings = cx.global::<Settings>().reset();
+                this.update(cx, |this, cx| this.apply_settings(settings, window, cx));
+                true
+            })
+            .open(window, cx);
+    }
+
+    /// Replaces every setting and shows the new values in the inputs.
+    fn apply_settings(&mut self, settings: Settings, window: &mut Window, cx: &mut Context<Self>) {
+        let values = [
+            (
+                &self.ui_font_size_input,
+                settings.appearance.ui_font_size.to_string(),
+            ),
+            (
+                &self.editor_font_size_input,
+                settings.editor.font_size.to_string(),
+            ),
+            (&self.zoom_input, settings.editor.zoom.to_string()),
+            (
+                &self.line_height_input,
+                settings.editor.line_height.to_string(),
+            ),
+            (
+                &self.block_spacing_input,
+                settings.editor.block_spacing.to_string(),
+            ),
+            (
+                &self.autosave_delay_input,
+                settings.editor.autosave_delay.to_string(),
+            ),
+            (
+                &self.h1_font_size_input,
+                (settings.editor.block_font_sizes.heading_1 as i32).to_string(),
+            ),
+            (
+                &self.h2_font_size_input,
+                (settings.editor.block_font_sizes.heading_2 as i32).to_string(),
+            ),
+            (
+                &self.h3_font_size_input,
+                (settings.editor.block_font_sizes.heading_3 as i32).to_string(),
+            ),
+            (
+                &self.text_font_size_input,
+                (settings.editor.block_font_sizes.text as i32).to_string(),
+            ),
+            (
+                &self.assistant_endpoint_input,
+                settings.assistant.endpoint.clone(),
+            ),
+            (
+                &self.assistant_api_key_input,
+                settings.assistant.api_key.clone(),
+            ),
+            (
+                &self.assistant_model_input,
+                settings.assistant.model.clone(),
+            ),
+            (&self.email_server_input, settings.email.server.clone()),
+            (&self.email_username_input, settings.email.username.clone()),
+            (&self.email_password_input, settings.email.password.clone()),
+            (&self.email_folder_input, settings.email.folder.clone()),
+            (&self.backups_kept_input, settings.backup.keep.to_string()),
+        ];
+
+        cx.update_global::<Settings, _>(|current, _| {
+            *current = settings;
+            current.save();
+        });
+        for (input, value) in values {
+            input.update(cx, |input, cx| input.set_value(value, window, cx));
+        }
+
+        apply_theme(window, cx);
+        cx.notify();
+    }
+
+    pub fn open(cx: &mut App) {
+        // If a settings window already exists, focus it
+        if let Some(window_id) = *SETTINGS_WINDOW.lock().unwrap() {
+            for window in cx.windows() {
+                if window.window_id() == window_id {
+                    let _ = window.update(cx, |_, window, _| {
+                        window.activate_window();
+                    });
+                    return;
+                }
+            }
+            // Window no longer exists, clear the handle
+            *SETTINGS_WINDOW.lock().unwrap() = None;
+        }
+
+        let window_size = size(px(860.), px(600.));
+        let window_bounds = Bounds::centered(None, window_size, cx);
+
+        cx.spawn(async move |cx| {
+            let options = WindowOptions {
+                window_bounds: Some(WindowBounds::Windowed(window_bounds)),
+                window_min_size: Some(Size {
+                    width: px(640.),
+                    height: px(400.),
+                }),
+                kind: WindowKind::Normal,
+                titlebar: Some(TitlebarOptions {
+                    appears_transparent: true,
+                    title: Some("Settings".into()),
+                    traffic_light_position: Some(point(px(9.0), px(9.0))),
+                }),
+                ..Default::default()
+            };
+
+            let window = cx
+                .open_window(options, |window, cx| {
+                    let view = cx.new(|cx| SettingsWindow::new(window, cx));
+                    cx.new(|cx| Root::new(view, window, cx))
+                })
+                .expect("failed to open settings window");
+
+            *SETTINGS_WINDOW.lock().unwrap() = Some(window.window_id());
+
+            window
+                .update(cx, |_, window, _| {
+                    window.activate_window();
+                    window.set_window_title("Settings");
+                    window.set_background_appearance(gpui::WindowBackgroundAppearance::Opaque);
+                })
+                .expect("failed to update settings window");
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
+    fn open_settings_json() {
+        if let Some(home) = dirs::home_dir() {
+            let config_path = if cfg!(target_os = "linux") || cfg!(target_os = "macos") {
+                home.join(".config").join("remindr")
             } else {
                 dirs::config_dir()
                     .unwrap_or(home.join(".config"))
@@ -507,6 +1327,15 @@ impl SettingsWindow {
             ),
             (SettingsSection::Editor, "Editor", "icons/file-text.svg"),
             (SettingsSection::Blocks, "Blocks", "icons/layout-grid.svg"),
+            (SettingsSection::Snippets, "Snippets", "icons/braces.svg"),
+            (
+                SettingsSection::Assistant,
+                "Assistant",
+                "icons/sparkles.svg",
+            ),
+            (SettingsSection::Security, "Security", "icons/lock.svg"),
+            (SettingsSection::Storage, "Storage", "icons/folder.svg"),
+            (SettingsSection::Data, "Data", "icons/database.svg"),
         ];
 
         let active = self.active_section;
@@ -564,6 +1393,11 @@ impl SettingsWindow {
             SettingsSection::Appearance => "Appearance",
             SettingsSection::Editor => "Editor",
             SettingsSection::Blocks => "Blocks",
+            SettingsSection::Snippets => "Snippets",
+            SettingsSection::Assistant => "Assistant",
+            SettingsSection::Security => "Security",
+            SettingsSection::Storage => "Storage",
+            SettingsSection::Data => "Data",
         };
 
         h_flex()
@@ -578,13 +1412,44 @@ impl SettingsWindow {
                     .text_color(cx.theme().foreground),
             )
             .child(
-                Button::new("edit-settings-json")
-                    .small()
-                    .ghost()
-                    .label("Edit in settings.json")
-                    .on_click(|_, _, _| {
-                        Self::open_settings_json();
-                    }),
+                h_flex()
+                    .gap_1()
+                    .child(
+                        Button::new("import-settings")
+                            .small()
+                            .ghost()
+                            .label("Import")
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.import_settings(window, cx);
+                            })),
+                    )
+                    .child(
+                        Button::new("export-settings")
+                            .small()
+                            .ghost()
+                            .label("Export")
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.export_settings(window, cx);
+                            })),
+                    )
+                    .child(
+                        Button::new("reset-settings")
+                            .small()
+                            .ghost()
+                            .label("Reset all to defaults")
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.reset_settings(window, cx);
+                            })),
+                    )
+                    .child(
+                        Button::new("edit-settings-json")
+                            .small()
+                            .ghost()
+                            .label("Edit in settings.json")
+                            .on_click(|_, _, _| {
+                                Self::open_settings_json();
+                            }),
+                    ),
             )
     }
 
@@ -779,53 +1644,173 @@ impl SettingsWindow {
                     .child(ui_font_control),
             );
 
-        v_flex().gap_3().child(theme_card).child(font_card)
+        v_flex()
+            .gap_3()
+            .child(theme_card)
+            .child(font_card)
+            .child(self.render_dates_card(&settings.dates, cx))
     }
 
-    fn render_theme_mode_toggle(
-        &self,
-        current: ThemeMode,
-        cx: &mut Context<Self>,
-    ) -> impl IntoElement {
-        let (icon_path, tooltip) = match current {
-            ThemeMode::Light => ("icons/sun.svg", "Light — click to switch to Dark"),
-            ThemeMode::Dark => ("icons/moon.svg", "Dark — click to switch to System"),
-            ThemeMode::System => ("icons/sun-moon.svg", "System — click to switch to Light"),
-        };
+    fn render_dates_card(&self, dates: &DateSettings, cx: &mut Context<Self>) -> impl IntoElement {
+        let fg = cx.theme().foreground;
+        let muted_fg = cx.theme().muted_foreground;
+        let border = cx.theme().border;
 
-        let next_mode = match current {
-            ThemeMode::Light => ThemeMode::Dark,
-            ThemeMode::Dark => ThemeMode::System,
-            ThemeMode::System => ThemeMode::Light,
+        let row = |label: &'static str, buttons: Vec<Button>| {
+            h_flex()
+                .w_full()
+                .justify_between()
+                .items_center()
+                .py_2()
+                .px_2()
+                .child(Label::new(label).text_xs().text_color(fg))
+                .child(h_flex().gap_1().children(buttons))
         };
 
-        Button::new("theme-mode-toggle")
-            .small()
-            .ghost()
-            .icon(gpui_component::Icon::default().path(icon_path))
-            .tooltip(tooltip)
-            .on_click(cx.listener(move |_, _, window, cx| {
-                cx.update_global::<Settings, _>(|settings, _| {
-                    settings.theme.mode = next_mode;
-                    settings.save();
-                });
-                apply_theme(window, cx);
-                cx.notify();
-            }))
-    }
-
-    fn render_theme_dropdown(
-        &self,
-        id: &str,
-        current: &str,
-        is_dark: bool,
-        search_input: &Entity<InputState>,
-        cx: &mut Context<Self>,
-    ) -> impl IntoElement {
-        let current = current.to_string();
-        let theme_registry = ThemeRegistry::global(cx);
-        let all_themes = theme_registry.sorted_themes();
-
+        let order_buttons = DateOrder::ALL
+            .into_iter()
+            .map(|order| {
+                Button::new(SharedString::from(format!("date-order-{:?}", order)))
+                    .xsmall()
+                    .outline()
+                    .label(order.label())
+                    .selected(order == dates.order)
+                    .on_click(cx.listener(move |_, _, _, cx| {
+                        cx.update_global::<Settings, _>(|settings, _| {
+                            settings.dates.order = order;
+                            settings.save();
+                        });
+                    }))
+            })
+            .collect();
+        let clock_buttons = ClockFormat::ALL
+            .into_iter()
+            .map(|clock| {
+                Button::new(SharedString::from(format!("clock-format-{:?}", clock)))
+                    .xsmall()
+                    .outline()
+                    .label(clock.label())
+                    .selected(clock == dates.clock)
+                    .on_click(cx.listener(move |_, _, _, cx| {
+                        cx.update_global::<Settings, _>(|settings, _| {
+                            settings.dates.clock = clock;
+                            settings.save();
+                        });
+                    }))
+            })
+            .collect();
+        let weekday_buttons = FirstWeekday::ALL
+            .into_iter()
+            .map(|weekday| {
+                Button::new(SharedString::from(format!("first-weekday-{:?}", weekday)))
+                    .xsmall()
+                    .outline()
+                    .label(weekday.label())
+                    .selected(weekday == dates.first_weekday)
+                    .on_click(cx.listener(move |_, _, _, cx| {
+                        cx.update_global::<Settings, _>(|settings, _| {
+                            settings.dates.first_weekday = weekday;
+                            settings.save();
+                        });
+                    }))
+            })
+            .collect();
+
+        v_flex()
+            .w_full()
+            .p_3()
+            .rounded_lg()
+            .border_1()
+            .border_color(border)
+            .gap_3()
+            .child(
+                h_flex()
+                    .gap_2()
+                    .items_center()
+                    .child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .justify_center()
+                            .size_8()
+                            .rounded_md()
+                            .bg(border)
+                            .child(
+                                gpui_component::Icon::default()
+                                    .path("icons/calendar.svg")
+                                    .size_4()
+                                    .text_color(fg),
+                            ),
+                    )
+                    .child(
+                        v_flex()
+                            .gap_0p5()
+                            .child(
+                                Label::new("Date & Time")
+                                    .text_sm()
+                                    .font_semibold()
+                                    .text_color(fg),
+                            )
+                            .child(
+                                Label::new("\"System\" follows the language of the system.")
+                                    .text_xs()
+                                    .text_color(muted_fg),
+                            ),
+                    ),
+            )
+            .child(
+                v_flex()
+                    .gap_0()
+                    .child(row("Date Format", order_buttons))
+                    .child(row("Time Format", clock_buttons))
+                    .child(row("First Day of the Week", weekday_buttons)),
+            )
+    }
+
+    fn render_theme_mode_toggle(
+        &self,
+        current: ThemeMode,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let (icon_path, tooltip) = match current {
+            ThemeMode::Light => ("icons/sun.svg", "Light — click to switch to Dark"),
+            ThemeMode::Dark => ("icons/moon.svg", "Dark — click to switch to System"),
+            ThemeMode::System => ("icons/sun-moon.svg", "System — click to switch to Light"),
+        };
+
+        let next_mode = match current {
+            ThemeMode::Light => ThemeMode::Dark,
+            ThemeMode::Dark => ThemeMode::System,
+            ThemeMode::System => ThemeMode::Light,
+        };
+
+        Button::new("theme-mode-toggle")
+            .small()
+            .ghost()
+            .icon(gpui_component::Icon::default().path(icon_path))
+            .tooltip(tooltip)
+            .on_click(cx.listener(move |_, _, window, cx| {
+                cx.update_global::<Settings, _>(|settings, _| {
+                    settings.theme.mode = next_mode;
+                    settings.save();
+                });
+                apply_theme(window, cx);
+                cx.notify();
+            }))
+    }
+
+    fn render_theme_dropdown(
+        &self,
+        id: &str,
+        current: &str,
+        is_dark: bool,
+        search_input: &Entity<InputState>,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let current = current.to_string();
+        let theme_registry = ThemeRegistry::global(cx);
+        let all_themes = theme_registry.sorted_themes();
+
         let themes: Vec<String> = all_themes
             .iter()
             .filter(|t| t.mode.is_dark() == is_dark)
@@ -983,9 +1968,11 @@ impl SettingsWindow {
                             .gap_0p5()
                             .child(Label::new("Font").text_sm().font_semibold().text_color(fg))
                             .child(
-                                Label::new("Base font size and zoom for the editor.")
-                                    .text_xs()
-                                    .text_color(muted_fg),
+                                Label::new(
+                                    "Base font size, zoom and spacing of the text for the editor.",
+                                )
+                                .text_xs()
+                                .text_color(muted_fg),
                             ),
                     ),
             )
@@ -1003,6 +1990,18 @@ impl SettingsWindow {
                         &self.zoom_input.clone(),
                         1.0,
                         cx,
+                    ))
+                    .child(self.render_editor_setting_row(
+                        "Line Height",
+                        &self.line_height_input.clone(),
+                        1.5,
+                        cx,
+                    ))
+                    .child(self.render_editor_setting_row(
+                        "Block Spacing (px)",
+                        &self.block_spacing_input.clone(),
+                        8.0,
+                        cx,
                     )),
             );
 
@@ -1123,7 +2122,235 @@ impl SettingsWindow {
             )
             .child(v_flex().w_full().px_2().child(block_list));
 
-        v_flex().gap_3().child(font_card).child(block_font_card)
+        // -- Writing card --
+        let autocomplete = cx.global::<Settings>().editor.autocomplete;
+        let hover_toolbar = cx.global::<Settings>().editor.hover_toolbar;
+        let typewriter_scrolling = cx.global::<Settings>().editor.typewriter_scrolling;
+        let hidden_toolbar_actions = cx
+            .global::<Settings>()
+            .editor
+            .hidden_toolbar_actions
+            .clone();
+        let new_block = cx.global::<Settings>().editor.new_block;
+        let first_block = cx.global::<Settings>().editor.first_block();
+        let editor_settings = cx.global::<Settings>().editor.clone();
+        let writing_card = v_flex()
+            .w_full()
+            .p_3()
+            .rounded_lg()
+            .border_1()
+            .border_color(border)
+            .gap_3()
+            .child(
+                h_flex()
+                    .w_full()
+                    .justify_between()
+                    .items_center()
+                    .child(
+                        v_flex()
+                            .gap_0p5()
+                            .child(
+                                Label::new("Autocomplete")
+                                    .text_sm()
+                                    .font_semibold()
+                                    .text_color(fg),
+                            )
+                            .child(
+                                Label::new(
+                                    "Suggest words and phrases from recent documents. Press Tab to accept.",
+                                )
+                                .text_xs()
+                                .text_color(muted_fg),
+                            ),
+                    )
+                    .child(
+                        Switch::new("editor-autocomplete")
+                            .checked(autocomplete)
+                            .small()
+                            .on_click(cx.listener(|_, checked, _, cx| {
+                                let checked = *checked;
+                                cx.update_global::<Settings, _>(|settings, _| {
+                                    settings.editor.autocomplete = checked;
+                                    settings.save();
+                                });
+                                cx.notify();
+                            })),
+                    ),
+            )
+            .child(
+                h_flex()
+                    .w_full()
+                    .justify_between()
+                    .items_center()
+                    .child(
+                        v_flex()
+                            .gap_0p5()
+                            .child(
+                                Label::new("Typewriter scrolling")
+                                    .text_sm()
+                                    .font_semibold()
+                                    .text_color(fg),
+                            )
+                            .child(
+                                Label::new(
+                                    "Keep the line being typed in the middle of the document.",
+                                )
+                                .text_xs()
+                                .text_color(muted_fg),
+                            ),
+                    )
+                    .child(
+                        Switch::new("editor-typewriter-scrolling")
+                            .checked(typewriter_scrolling)
+                            .small()
+                            .on_click(cx.listener(|_, checked, _, cx| {
+                                let checked = *checked;
+                                cx.update_global::<Settings, _>(|settings, _| {
+                                    settings.editor.typewriter_scrolling = checked;
+                                    settings.save();
+                                });
+                                cx.notify();
+                            })),
+                    ),
+            )
+            .child(
+                h_flex()
+                    .w_full()
+                    .justify_between()
+                    .items_center()
+                    .child(
+                        v_flex()
+                            .gap_0p5()
+                            .child(
+                                Label::new("Block toolbar")
+                                    .text_sm()
+                                    .font_semibold()
+                                    .text_color(fg),
+                            )
+                            .child(
+                                Label::new(
+                                    "Show quick actions above a block when hovering it or selecting text.",
+                                )
+                                .text_xs()
+                                .text_color(muted_fg),
+                            ),
+                    )
+                    .child(
+                        Switch::new("editor-hover-toolbar")
+                            .checked(hover_toolbar)
+                            .small()
+                            .on_click(cx.listener(|_, checked, _, cx| {
+                                let checked = *checked;
+                                cx.update_global::<Settings, _>(|settings, _| {
+                                    settings.editor.hover_toolbar = checked;
+                                    settings.save();
+                                });
+                                cx.notify();
+                            })),
+                    ),
+            )
+            .when(hover_toolbar, |this| {
+                this.child(h_flex().flex_wrap().gap_1().children(
+                    ToolbarAction::ALL.into_iter().map(|action| {
+                        let action_id = action.id().to_string();
+                        let shown = !hidden_toolbar_actions.contains(&action_id);
+
+                        Button::new(SharedString::from(format!("toolbar-action-{}", action_id)))
+                            .xsmall()
+                            .outline()
+                            .label(action.label())
+                            .selected(shown)
+                            .on_click(cx.listener(move |_, _, _, cx| {
+                                let action_id = action_id.clone();
+                                cx.update_global::<Settings, _>(|settings, _| {
+                                    let hidden = &mut settings.editor.hidden_toolbar_actions;
+                                    if shown {
+                                        hidden.push(action_id);
+                                    } else {
+                                        hidden.retain(|id| id != &action_id);
+                                    }
+                                    settings.save();
+                                });
+                                cx.notify();
+                            }))
+                    }),
+                ))
+            })
+            .child(
+                v_flex()
+                    .gap_1()
+                    .child(
+                        Label::new("New block on Enter")
+                            .text_sm()
+                            .font_semibold()
+                            .text_color(fg),
+                    )
+                    .child(
+                        Label::new("Block added when pressing Enter in a list item or a to-do.")
+                            .text_xs()
+                            .text_color(muted_fg),
+                    )
+                    .child(h_flex().flex_wrap().gap_1().children(
+                        NewBlockType::ALL.into_iter().map(|block| {
+                            Button::new(SharedString::from(format!("new-block-{:?}", block)))
+                                .xsmall()
+                                .outline()
+                                .label(block.label())
+                                .selected(block == new_block)
+                                .on_click(cx.listener(move |_, _, _, cx| {
+                                    cx.update_global::<Settings, _>(|settings, _| {
+                                        settings.editor.new_block = block;
+                                        settings.save();
+                                    });
+                                    cx.notify();
+                                }))
+                        }),
+                    )),
+            )
+            .child(
+                v_flex()
+                    .gap_1()
+                    .child(
+                        Label::new("Empty documents start with")
+                            .text_sm()
+                            .font_semibold()
+                            .text_color(fg),
+                    )
+                    .child(
+                        Label::new("Disabled block types can't be picked.")
+                            .text_xs()
+                            .text_color(muted_fg),
+                    )
+                    .child(h_flex().flex_wrap().gap_1().children(
+                        FirstBlockType::ALL.into_iter().map(|block| {
+                            Button::new(SharedString::from(format!("first-block-{:?}", block)))
+                                .xsmall()
+                                .outline()
+                                .label(block.label())
+                                .selected(block == first_block)
+                                .disabled(!editor_settings.blocks_enabled(block.block_ids()))
+                                .on_click(cx.listener(move |_, _, _, cx| {
+                                    cx.update_global::<Settings, _>(|settings, _| {
+                                        settings.editor.first_block = block;
+                                        settings.save();
+                                    });
+                                    cx.notify();
+                                }))
+                        }),
+                    )),
+            )
+            .child(self.render_editor_setting_row(
+                "Autosave Delay (s)",
+                &self.autosave_delay_input.clone(),
+                1.0,
+                cx,
+            ));
+
+        v_flex()
+            .gap_3()
+            .child(font_card)
+            .child(block_font_card)
+            .child(writing_card)
     }
 
     fn render_editor_setting_row(
@@ -1340,6 +2567,798 @@ impl SettingsWindow {
         section
     }
 
+    fn render_snippets_section(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let snippets = cx.global::<Settings>().snippets.clone();
+        let fg = cx.theme().foreground;
+        let muted_fg = cx.theme().muted_foreground;
+        let border = cx.theme().border;
+
+        v_flex().gap_3().child(
+            v_flex()
+                .w_full()
+                .p_3()
+                .rounded_lg()
+                .border_1()
+                .border_color(border)
+                .gap_3()
+                .child(
+                    v_flex()
+                        .gap_0p5()
+                        .child(
+                            Label::new("Text snippets")
+                                .text_sm()
+                                .font_semibold()
+                                .text_color(fg),
+                        )
+                        .child(
+                            Label::new(
+                                "Typing a trigger then Tab in a block replaces it with its expansion. {{date}} and {{time}} are replaced by the current date and time, and the cursor is placed at {{cursor}}.",
+                            )
+                            .text_xs()
+                            .text_color(muted_fg),
+                        ),
+                )
+                .child(
+                    h_flex()
+                        .w_full()
+                        .gap_2()
+                        .child(
+                            div()
+                                .w(px(140.))
+                                .child(Input::new(&self.snippet_trigger_input).small()),
+                        )
+                        .child(
+                            div()
+                                .flex_1()
+                                .child(Input::new(&self.snippet_expansion_input).small()),
+                        )
+                        .child(
+                            Button::new("add-snippet")
+                                .small()
+                                .outline()
+                                .label("Add")
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.add_snippet(window, cx);
+                                })),
+                        ),
+                )
+                .child(
+                    v_flex().children(snippets.into_iter().enumerate().map(|(ix, snippet)| {
+                        h_flex()
+                            .w_full()
+                            .gap_4()
+                            .py_2()
+                            .px_2()
+                            .border_t_1()
+                            .border_color(border)
+                            .child(
+                                div()
+                                    .w(px(124.))
+                                    .flex_shrink_0()
+                                    .child(Label::new(snippet.trigger).text_xs().text_color(fg)),
+                            )
+                            .child(
+                                div().flex_1().min_w_0().child(
+                                    Label::new(snippet.expansion)
+                                        .text_xs()
+                                        .text_color(muted_fg),
+                                ),
+                            )
+                            .child(
+                                Button::new(("remove-snippet", ix))
+                                    .xsmall()
+                                    .ghost()
+                                    .label("Remove")
+                                    .on_click(cx.listener(move |_, _, _, cx| {
+                                        cx.update_global::<Settings, _>(|settings, _| {
+                                            if ix < settings.snippets.len() {
+                                                settings.snippets.remove(ix);
+                                                settings.save();
+                                            }
+                                        });
+                                        cx.notify();
+                                    })),
+                            )
+                    })),
+                ),
+        )
+    }
+
+    fn render_assistant_section(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let enabled = cx.global::<Settings>().assistant.enabled;
+        let fg = cx.theme().foreground;
+        let muted_fg = cx.theme().muted_foreground;
+        let border = cx.theme().border;
+
+        let field = |label: &'static str, input: &Entity<InputState>| {
+            h_flex()
+                .w_full()
+                .justify_between()
+                .items_center()
+                .gap_4()
+                .py_2()
+                .px_2()
+                .child(Label::new(label).text_xs().text_color(fg))
+                .child(div().w(px(320.)).child(Input::new(input).small()))
+        };
+
+        v_flex().gap_3().child(
+            v_flex()
+                .w_full()
+                .p_3()
+                .rounded_lg()
+                .border_1()
+                .border_color(border)
+                .gap_3()
+                .child(
+                    h_flex()
+                        .w_full()
+                        .justify_between()
+                        .items_center()
+                        .child(
+                            v_flex()
+                                .gap_0p5()
+                                .child(
+                                    Label::new("Writing assistant")
+                                        .text_sm()
+                                        .font_semibold()
+                                        .text_color(fg),
+                                )
+                                .child(
+                                    Label::new(
+                                        "Adds \"Continue writing\" and \"Summarize\" to the slash menu. Works with any OpenAI-compatible API.",
+                                    )
+                                    .text_xs()
+                                    .text_color(muted_fg),
+                                ),
+                        )
+                        .child(
+                            Switch::new("assistant-enabled")
+                                .checked(enabled)
+                                .small()
+                                .on_click(cx.listener(|_, checked, _, cx| {
+                                    let checked = *checked;
+                                    cx.update_global::<Settings, _>(|settings, _| {
+                                        settings.assistant.enabled = checked;
+                                        settings.save();
+                                    });
+                                    cx.notify();
+                                })),
+                        ),
+                )
+                .child(
+                    v_flex()
+                        .gap_0()
+                        .child(field("Endpoint", &self.assistant_endpoint_input))
+                        .child(field("API key", &self.assistant_api_key_input))
+                        .child(field("Model", &self.assistant_model_input)),
+                ),
+        )
+    }
+
+    fn render_security_section(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let encrypted = cx.global::<Settings>().security.encrypted;
+        let busy = self.encryption_in_progress;
+        let fg = cx.theme().foreground;
+        let muted_fg = cx.theme().muted_foreground;
+        let border = cx.theme().border;
+
+        let description = if !database::ENCRYPTION_AVAILABLE {
+            "This build of Remindr was compiled without SQLCipher support. Rebuild with the `sqlcipher` feature to encrypt your workspace."
+        } else if encrypted {
+            "Your workspace is encrypted. The passphrase is asked for every time Remindr starts and cannot be recovered if lost."
+        } else {
+            "Encrypt your workspace with a passphrase. It will be asked for every time Remindr starts and cannot be recovered if lost."
+        };
+
+        let field = |label: &'static str, input: &Entity<InputState>| {
+            h_flex()
+                .w_full()
+                .justify_between()
+                .items_center()
+                .gap_4()
+                .py_2()
+                .px_2()
+                .child(Label::new(label).text_xs().text_color(fg))
+                .child(div().w(px(320.)).child(Input::new(input).small()))
+        };
+
+        let card = v_flex()
+            .w_full()
+            .p_3()
+            .rounded_lg()
+            .border_1()
+            .border_color(border)
+            .gap_3()
+            .child(
+                v_flex()
+                    .gap_0p5()
+                    .child(
+                        Label::new("Database encryption")
+                            .text_sm()
+                            .font_semibold()
+                            .text_color(fg),
+                    )
+                    .child(Label::new(description).text_xs().text_color(muted_fg)),
+            );
+
+        if !database::ENCRYPTION_AVAILABLE {
+            return v_flex().gap_3().child(card);
+        }
+
+        let (primary_action, primary_label) = if encrypted {
+            (EncryptionAction::ChangePassphrase, "Change passphrase")
+        } else {
+            (EncryptionAction::Encrypt, "Encrypt database")
+        };
+
+        let card = card
+            .child(
+                v_flex()
+                    .gap_0()
+                    .child(field("New passphrase", &self.passphrase_input))
+                    .child(field("Confirm", &self.passphrase_confirm_input)),
+            )
+            .child(
+                h_flex()
+                    .w_full()
+                    .justify_end()
+                    .gap_2()
+                    .when(encrypted, |this| {
+                        this.child(
+                            Button::new("remove-encryption")
+                                .small()
+                                .ghost()
+                                .label("Remove encryption")
+                                .disabled(busy)
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.apply_encryption(EncryptionAction::Decrypt, window, cx);
+                                })),
+                        )
+                    })
+                    .child(
+                        Button::new("apply-encryption")
+                            .small()
+                            .primary()
+                            .label(primary_label)
+                            .loading(busy)
+                            .disabled(busy)
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                this.apply_encryption(primary_action, window, cx);
+                            })),
+                    ),
+            );
+
+        v_flex().gap_3().child(card)
+    }
+
+    fn render_storage_section(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let vault = cx.global::<Settings>().vault.clone();
+        let active = cx
+            .try_global::<RepositoryState>()
+            .is_some_and(|state| state.vault.is_some());
+        let fg = cx.theme().foreground;
+        let muted_fg = cx.theme().muted_foreground;
+        let border = cx.theme().border;
+
+        let status = match (active, vault.is_configured()) {
+            (true, true) => "Documents are currently stored in the vault folder.",
+            (false, false) => "Documents are currently stored in the local database.",
+            _ => "Restart Remindr to switch storage.",
+        };
+
+        let path_label = if vault.path.is_empty() {
+            "No folder selected".to_string()
+        } else {
+            vault.path.clone()
+        };
+
+        v_flex().gap_3().child(
+            v_flex()
+                .w_full()
+                .p_3()
+                .rounded_lg()
+                .border_1()
+                .border_color(border)
+                .gap_3()
+                .child(
+                    h_flex()
+                        .w_full()
+                        .justify_between()
+                        .items_center()
+                        .child(
+                            v_flex()
+                                .gap_0p5()
+                                .child(
+                                    Label::new("File vault")
+                                        .text_sm()
+                                        .font_semibold()
+                                        .text_color(fg),
+                                )
+                                .child(
+                                    Label::new(
+                                        "Store each document as a JSON file in a folder of your choice, so it can be versioned with Git or synced with Dropbox. Edits made outside Remindr are picked up automatically.",
+                                    )
+                                    .text_xs()
+                                    .text_color(muted_fg),
+                                ),
+                        )
+                        .child(
+                            Switch::new("vault-enabled")
+                                .checked(vault.enabled)
+                                .small()
+                                .on_click(cx.listener(|_, checked, _, cx| {
+                                    let checked = *checked;
+                                    cx.update_global::<Settings, _>(|settings, _| {
+                                        settings.vault.enabled = checked;
+                                        settings.save();
+                                    });
+                                    cx.notify();
+                                })),
+                        ),
+                )
+                .child(
+                    h_flex()
+                        .w_full()
+                        .justify_between()
+                        .items_center()
+                        .gap_4()
+                        .py_2()
+                        .px_2()
+                        .child(
+                            Label::new(path_label)
+                                .text_xs()
+                                .text_color(if vault.path.is_empty() { muted_fg } else { fg }),
+                        )
+                        .child(
+                            Button::new("choose-vault-folder")
+                                .small()
+                                .label("Choose folder...")
+                                .on_click(cx.listener(|this, _, _, cx| {
+                                    this.choose_vault_folder(cx);
+                                })),
+                        ),
+                )
+                .child(Label::new(status).text_xs().text_color(muted_fg)),
+        )
+        .child(self.render_local_api_card(cx))
+        .child(self.render_email_card(cx))
+    }
+
+    fn render_email_card(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let email = cx.global::<Settings>().email.clone();
+        let fg = cx.theme().foreground;
+        let muted_fg = cx.theme().muted_foreground;
+        let border = cx.theme().border;
+
+        let field = |label: &'static str, input: &Entity<InputState>| {
+            h_flex()
+                .w_full()
+                .justify_between()
+                .items_center()
+                .gap_4()
+                .py_2()
+                .px_2()
+                .child(Label::new(label).text_xs().text_color(fg))
+                .child(div().w(px(320.)).child(Input::new(input).small()))
+        };
+
+        v_flex()
+            .w_full()
+            .p_3()
+            .rounded_lg()
+            .border_1()
+            .border_color(border)
+            .gap_3()
+            .child(
+                h_flex()
+                    .w_full()
+                    .justify_between()
+                    .items_center()
+                    .child(
+                        v_flex()
+                            .gap_0p5()
+                            .child(
+                                Label::new("Email import")
+                                    .text_sm()
+                                    .font_semibold()
+                                    .text_color(fg),
+                            )
+                            .child(
+                                Label::new(format!(
+                                    "Turn the unread emails of a mailbox into documents of the \"Email\" folder, checked every {} minutes over IMAP with TLS. Imported emails are marked as read.",
+                                    email.poll_minutes
+                                ))
+                                .text_xs()
+                                .text_color(muted_fg),
+                            ),
+                    )
+                    .child(
+                        Switch::new("email-enabled")
+                            .checked(email.enabled)
+                            .small()
+                            .on_click(cx.listener(|_, checked, _, cx| {
+                                let checked = *checked;
+                                cx.update_global::<Settings, _>(|settings, _| {
+                                    settings.email.enabled = checked;
+                                    settings.save();
+                                });
+                                cx.notify();
+                            })),
+                    ),
+            )
+            .child(
+                v_flex()
+                    .gap_0()
+                    .child(field("Server", &self.email_server_input))
+                    .child(field("Username", &self.email_username_input))
+                    .child(field("Password", &self.email_password_input))
+                    .child(field("Mailbox", &self.email_folder_input)),
+            )
+    }
+
+    fn render_local_api_card(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let api = cx.global::<Settings>().api.clone();
+        let fg = cx.theme().foreground;
+        let muted_fg = cx.theme().muted_foreground;
+        let border = cx.theme().border;
+
+        let status = if cfg!(feature = "http-api") {
+            "Changes apply the next time Remindr starts."
+        } else {
+            "This build of Remindr was compiled without the local API. Rebuild with the `http-api` feature to use it."
+        };
+
+        let row = |label: &'static str, value: String| {
+            h_flex()
+                .w_full()
+                .justify_between()
+                .items_center()
+                .gap_4()
+                .py_2()
+                .px_2()
+                .child(Label::new(label).text_xs().text_color(fg))
+                .child(Label::new(value).text_xs().text_color(muted_fg))
+        };
+
+        v_flex()
+            .w_full()
+            .p_3()
+            .rounded_lg()
+            .border_1()
+            .border_color(border)
+            .gap_3()
+            .child(
+                h_flex()
+                    .w_full()
+                    .justify_between()
+                    .items_center()
+                    .child(
+                        v_flex()
+                            .gap_0p5()
+                            .child(
+                                Label::new("Local API")
+                                    .text_sm()
+                                    .font_semibold()
+                                    .text_color(fg),
+                            )
+                            .child(
+                                Label::new(
+                                    "Let browser extensions and capture tools on this computer read and add documents. Requests must carry the token as `Authorization: Bearer <token>`.",
+                                )
+                                .text_xs()
+                                .text_color(muted_fg),
+                            ),
+                    )
+                    .child(
+                        Switch::new("api-enabled")
+                            .checked(api.enabled)
+                            .small()
+                            .on_click(cx.listener(|_, checked, _, cx| {
+                                let checked = *checked;
+                                cx.update_global::<Settings, _>(|settings, _| {
+                                    settings.api.enabled = checked;
+                                    if checked && settings.api.token.trim().is_empty() {
+                                        settings.api.token = ApiSettings::generate_token();
+                                    }
+                                    settings.save();
+                                });
+                                cx.notify();
+                            })),
+                    ),
+            )
+            .when(api.enabled, |this| {
+                this.child(
+                    v_flex()
+                        .gap_0()
+                        .child(row("Address", format!("http://127.0.0.1:{}", api.port)))
+                        .child(row("Token", api.token.clone()))
+                        .child(
+                            h_flex()
+                                .w_full()
+                                .justify_end()
+                                .gap_2()
+                                .py_2()
+                                .child(
+                                    Button::new("regenerate-api-token")
+                                        .small()
+                                        .ghost()
+                                        .label("New token")
+                                        .on_click(cx.listener(|_, _, _, cx| {
+                                            cx.update_global::<Settings, _>(|settings, _| {
+                                                settings.api.token = ApiSettings::generate_token();
+                                                settings.save();
+                                            });
+                                            cx.notify();
+                                        })),
+                                )
+                                .child(
+                                    Button::new("copy-api-token")
+                                        .small()
+                                        .label("Copy token")
+                                        .on_click(move |_, _, cx| {
+                                            cx.write_to_clipboard(ClipboardItem::new_string(
+                                                api.token.clone(),
+                                            ));
+                                        }),
+                                ),
+                        ),
+                )
+            })
+            .child(Label::new(status).text_xs().text_color(muted_fg))
+    }
+
+    fn render_data_section(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .gap_3()
+            .child(self.render_backup_card(cx))
+            .child(self.render_integrity_card(cx))
+            .child(self.render_unused_attachments_card(cx))
+    }
+
+    fn render_integrity_card(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let fg = cx.theme().foreground;
+        let muted_fg = cx.theme().muted_foreground;
+        let border = cx.theme().border;
+
+        h_flex()
+            .w_full()
+            .p_3()
+            .rounded_lg()
+            .border_1()
+            .border_color(border)
+            .justify_between()
+            .items_center()
+            .gap_4()
+            .child(
+                v_flex()
+                    .gap_0p5()
+                    .child(
+                        Label::new("Integrity check")
+                            .text_sm()
+                            .font_semibold()
+                            .text_color(fg),
+                    )
+                    .child(
+                        Label::new(
+                            "Check the database file and that every document can be opened. Broken documents can be restored from the newest backup holding a readable copy of them.",
+                        )
+                        .text_xs()
+                        .text_color(muted_fg),
+                    ),
+            )
+            .child(
+                Button::new("check-integrity")
+                    .small()
+                    .label("Check now")
+                    .on_click(|_, window, cx| integrity_dialog::check_integrity(window, cx)),
+            )
+    }
+
+    fn render_unused_attachments_card(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let fg = cx.theme().foreground;
+        let muted_fg = cx.theme().muted_foreground;
+        let border = cx.theme().border;
+
+        h_flex()
+            .w_full()
+            .p_3()
+            .rounded_lg()
+            .border_1()
+            .border_color(border)
+            .justify_between()
+            .items_center()
+            .gap_4()
+            .child(
+                v_flex()
+                    .gap_0p5()
+                    .child(
+                        Label::new("Unused attachments")
+                            .text_sm()
+                            .font_semibold()
+                            .text_color(fg),
+                    )
+                    .child(
+                        Label::new(
+                            "List the attached files no block refers to anymore, then choose whether to delete them.",
+                        )
+                        .text_xs()
+                        .text_color(muted_fg),
+                    ),
+            )
+            .child(
+                Button::new("find-unused-attachments")
+                    .small()
+                    .label("Scan")
+                    .on_click(|_, window, cx| {
+                        attachment_cleanup_dialog::find_unused_attachments(window, cx)
+                    }),
+            )
+    }
+
+    fn render_backup_card(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let settings = cx.global::<Settings>().backup.clone();
+        let (last, running, error) = cx
+            .try_global::<BackupState>()
+            .map(|state| (state.last.clone(), state.running, state.error.clone()))
+            .unwrap_or_default();
+        let uses_vault = cx
+            .try_global::<RepositoryState>()
+            .is_some_and(|state| state.vault.is_some());
+        let fg = cx.theme().foreground;
+        let muted_fg = cx.theme().muted_foreground;
+        let border = cx.theme().border;
+        let danger = cx.theme().danger;
+
+        let description = if uses_vault {
+            "Copy the vault folder to a zip file of a backup folder, without its Git history. The oldest backups are deleted once there are more than the ones kept."
+        } else {
+            "Copy the database to a backup folder, encrypted like the database itself. The oldest backups are deleted once there are more than the ones kept."
+        };
+        let status = match (&last, running) {
+            (_, true) => "Backing up…".to_string(),
+            (Some(last), false) => format!(
+                "Last backup {}",
+                DateFormat::new(cx).relative(last.created_at)
+            ),
+            (None, false) => "No backup yet".to_string(),
+        };
+        let directory_label = if settings.directory.is_empty() {
+            "No folder selected".to_string()
+        } else {
+            settings.directory.clone()
+        };
+
+        let row = |label: &'static str| {
+            h_flex()
+                .w_full()
+                .justify_between()
+                .items_center()
+                .gap_4()
+                .py_2()
+                .px_2()
+                .child(Label::new(label).text_xs().text_color(fg))
+        };
+        let frequency_buttons = BackupFrequency::ALL
+            .into_iter()
+            .map(|frequency| {
+                Button::new(SharedString::from(format!(
+                    "backup-frequency-{:?}",
+                    frequency
+                )))
+                .xsmall()
+                .outline()
+                .label(frequency.label())
+                .selected(frequency == settings.frequency)
+                .on_click(cx.listener(move |_, _, _, cx| {
+                    cx.update_global::<Settings, _>(|settings, _| {
+                        settings.backup.frequency = frequency;
+                        settings.save();
+                    });
+                }))
+            })
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .w_full()
+            .p_3()
+            .rounded_lg()
+            .border_1()
+            .border_color(border)
+            .gap_3()
+            .child(
+                h_flex()
+                    .w_full()
+                    .justify_between()
+                    .items_center()
+                    .child(
+                        v_flex()
+                            .gap_0p5()
+                            .child(
+                                Label::new("Automatic backups")
+                                    .text_sm()
+                                    .font_semibold()
+                                    .text_color(fg),
+                            )
+                            .child(Label::new(description).text_xs().text_color(muted_fg)),
+                    )
+                    .child(
+                        Switch::new("backup-enabled")
+                            .checked(settings.enabled)
+                            .small()
+                            .on_click(cx.listener(|_, checked, _, cx| {
+                                let checked = *checked;
+                                cx.update_global::<Settings, _>(|settings, _| {
+                                    settings.backup.enabled = checked;
+                                    settings.save();
+                                });
+                                cx.notify();
+                            })),
+                    ),
+            )
+            .child(
+                v_flex()
+                    .gap_0()
+                    .child(
+                        row("Backup folder").child(
+                            h_flex()
+                                .gap_2()
+                                .items_center()
+                                .child(Label::new(directory_label).text_xs().text_color(
+                                    if settings.directory.is_empty() {
+                                        muted_fg
+                                    } else {
+                                        fg
+                                    },
+                                ))
+                                .child(
+                                    Button::new("choose-backup-folder")
+                                        .small()
+                                        .label("Choose folder...")
+                                        .on_click(cx.listener(|this, _, _, cx| {
+                                            this.choose_backup_folder(cx);
+                                        })),
+                                ),
+                        ),
+                    )
+                    .child(row("Frequency").child(h_flex().gap_1().children(frequency_buttons)))
+                    .child(
+                        row("Backups kept").child(
+                            div()
+                                .w(px(120.))
+                                .child(NumberInput::new(&self.backups_kept_input).small()),
+                        ),
+                    ),
+            )
+            .child(
+                h_flex()
+                    .w_full()
+                    .justify_between()
+                    .items_center()
+                    .gap_4()
+                    .child(
+                        v_flex()
+                            .gap_0p5()
+                            .child(Label::new(status).text_xs().text_color(muted_fg))
+                            .when_some(error, |this, error| {
+                                this.child(
+                                    Label::new(format!("The last backup failed: {}", error))
+                                        .text_xs()
+                                        .text_color(danger),
+                                )
+                            }),
+                    )
+                    .child(
+                        Button::new("back-up-now")
+                            .small()
+                            .label("Back up now")
+                            .loading(running)
+                            .disabled(running || settings.directory.is_empty())
+                            .on_click(|_, window, cx| backup::back_up_now(window, cx)),
+                    ),
+            )
+    }
+
     fn render_content(&mut self, cx: &mut Context<Self>) -> impl IntoElement {
         let content = div()
             .flex_1()
@@ -1352,13 +3371,20 @@ impl SettingsWindow {
             SettingsSection::Appearance => content.child(self.render_appearance_section(cx)),
             SettingsSection::Editor => content.child(self.render_editor_section(cx)),
             SettingsSection::Blocks => content.child(self.render_blocks_section(cx)),
+            SettingsSection::Snippets => content.child(self.render_snippets_section(cx)),
+            SettingsSection::Assistant => content.child(self.render_assistant_section(cx)),
+            SettingsSection::Security => content.child(self.render_security_section(cx)),
+            SettingsSection::Storage => content.child(self.render_storage_section(cx)),
+            SettingsSection::Data => content.child(self.render_data_section(cx)),
         }
     }
 }
 
 impl Render for SettingsWindow {
-    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let bg = cx.theme().background;
+        let notification_layer = Root::render_notification_layer(window, cx);
+        let dialog_layer = Root::render_dialog_layer(window, cx);
 
         h_flex()
             .size_full()
@@ -1374,6 +3400,8 @@ impl Render for SettingsWindow {
                     .child(self.render_header(cx))
                     .child(self.render_content(cx)),
             )
+            .children(dialog_layer)
+            .children(notification_layer)
     }
 }
 
@@ -1,13 +1,14 @@
 use gpui::prelude::FluentBuilder;
 use gpui::{
-    App, AppContext, BorrowAppContext, Context, Div, DragMoveEvent, Entity, Hsla,
-    InteractiveElement, IntoElement, MouseButton, ParentElement, Render, Stateful,
-    StatefulInteractiveElement, Styled, Window, div, px,
+    App, AppContext, AsyncApp, BorrowAppContext, Context, Div, DragMoveEvent, Entity, Hsla,
+    InteractiveElement, IntoElement, MouseButton, ParentElement, PathPromptOptions, Render,
+    SharedString, Stateful, StatefulInteractiveElement, Styled, Window, div, px,
 };
 use gpui_component::{
     ActiveTheme, Icon, IconName, Sizable, WindowExt,
     avatar::Avatar,
     button::{Button, ButtonVariants},
+    checkbox::Checkbox,
     h_flex,
     input::{Input, InputEvent, InputState},
     menu::{ContextMenu, ContextMenuExt as _, DropdownMenu as _, PopupMenuItem},
@@ -15,19 +16,51 @@ use gpui_component::{
     sidebar::SidebarHeader,
     v_flex,
 };
-use std::collections::HashSet;
-use std::time::Duration;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use crate::{
-    LoadingState,
+    LoadingState, Utils,
     app::{
-        components::{confirm_dialog::ConfirmDialog, settings_dialog::SettingsDialog},
-        screens::document_screen::DocumentScreen,
+        components::{
+            confirm_dialog::ConfirmDialog,
+            document_preview::DocumentPreview,
+            focus_ring::FocusRingExt,
+            merge_dialog::MergeDialog,
+            nodes::heading::data::{HeadingMetadata, HeadingNodeData},
+            settings_dialog::SettingsDialog,
+            smart_folder_dialog::SmartFolderDialog,
+            tag_dialog::TagDialog,
+            template_picker, vault_git,
+        },
+        main_window,
+        remindr::Remindr,
         states::{
-            app_state::AppState, document_state::DocumentState, repository_state::RepositoryState,
+            app_state::AppState,
+            deletion_state::DeletionState,
+            document_list_state::DocumentListState,
+            document_state::{DocumentState, PersistenceState},
+            error_state::{AppError, ErrorKind},
+            progress_state::ProgressTask,
+            reminder_state::ReminderState,
+            repository_state::RepositoryState,
+            settings_state::Settings,
+            toast_state::Toast,
+        },
+    },
+    domain::database::{
+        document::DocumentModel, folder::FolderModel, smart_folder::SmartFolderModel,
+    },
+    infrastructure::{
+        export::{
+            docx, markdown, opml,
+            site::{Site, SiteTheme},
+            template::{ExportTemplate, TemplateFormat},
         },
+        repositories::tag_repository::TagRepository,
+        vault::file_vault,
     },
-    domain::database::{document::DocumentModel, folder::FolderModel},
 };
 
 /// Drag data for a document being dragged in the sidebar
@@ -86,10 +119,55 @@ enum SidebarItem {
     Document(DocumentModel),
 }
 
+/// Everything the sidebar shows, fetched from the repositories in one go.
+struct SidebarData {
+    documents: Vec<DocumentModel>,
+    unfiled_documents: Vec<DocumentModel>,
+    folders: Vec<FolderModel>,
+    smart_folders: Vec<SmartFolderModel>,
+    document_tags: HashMap<i32, Vec<String>>,
+    dirty_documents: HashSet<i32>,
+}
+
+/// How long the badges are kept before their queries run again.
+const BADGES_CACHE_DURATION: Duration = Duration::from_secs(30);
+
+/// Open tasks and overdue reminders of each document, by document id.
+#[derive(Default)]
+struct DocumentBadges {
+    open_tasks: HashMap<i32, usize>,
+    overdue_reminders: HashMap<i32, usize>,
+}
+
+/// Documents of a folder and of its subfolders, with the badges they add up to.
+#[derive(Default, Clone, Copy)]
+struct FolderCounts {
+    documents: usize,
+    open_tasks: usize,
+    overdue_reminders: usize,
+}
+
 pub struct AppSidebar {
     document_state: LoadingState<Vec<DocumentModel>>,
     folder_state: LoadingState<Vec<FolderModel>>,
     expanded_folders: HashSet<i32>,
+    dirty_documents: HashSet<i32>,
+    /// Documents outside any folder, listed in the "Unfiled" group.
+    unfiled_documents: Vec<DocumentModel>,
+    unfiled_expanded: bool,
+    smart_folders: Vec<SmartFolderModel>,
+    expanded_smart_folders: HashSet<i32>,
+    /// Unfiled documents selected for a bulk move.
+    selected_unfiled: HashSet<i32>,
+    document_tags: HashMap<i32, Vec<String>>,
+    badges: DocumentBadges,
+    /// When the badges were last read, none until they are.
+    badges_fetched_at: Option<Instant>,
+    folder_counts: HashMap<i32, FolderCounts>,
+    /// Documents of the tree selected with secondary or shift click, for bulk actions.
+    selected_documents: HashSet<i32>,
+    /// Document the next shift click extends the selection from.
+    selection_anchor: Option<i32>,
     drop_target_folder: Option<i32>,
     editing_item: Option<EditingItem>,
     rename_input: Option<Entity<InputState>>,
@@ -99,46 +177,53 @@ pub struct AppSidebar {
 impl AppSidebar {
     pub fn new(app_state: Entity<AppState>, cx: &mut App) -> Entity<Self> {
         cx.new(|cx| {
-            let repository_state = cx.global::<RepositoryState>();
-            let document_repository = repository_state.documents.clone();
-            let folder_repository = repository_state.folders.clone();
+            cx.observe_global::<DeletionState>(|_, cx| {
+                AppSidebar::refresh_data(&cx.entity(), cx);
+            })
+            .detach();
+            cx.observe_global::<DocumentListState>(|_, cx| {
+                AppSidebar::refresh_data(&cx.entity(), cx);
+            })
+            .detach();
 
             // Initial fetch
-            cx.spawn({
-                let doc_repo = document_repository.clone();
-                let folder_repo = folder_repository.clone();
-                async move |this, cx| {
-                    let documents = doc_repo.get_documents().await;
-                    let folders = folder_repo.get_folders().await;
-                    if let (Ok(documents), Ok(folders)) = (documents, folders) {
-                        let _ = this.update(cx, |state: &mut Self, _| {
-                            state.document_state = LoadingState::Loaded(documents);
-                            state.folder_state = LoadingState::Loaded(folders);
-                        });
-                    }
+            cx.spawn(async move |this, cx| {
+                if let Ok(data) = Self::fetch_data(cx).await {
+                    let _ = this.update(cx, |state: &mut Self, _| state.set_data(data));
+                }
+                if let Ok(badges) = Self::fetch_badges(cx).await {
+                    let _ = this.update(cx, |state: &mut Self, cx| {
+                        state.set_badges(badges);
+                        cx.notify();
+                    });
                 }
             })
             .detach();
 
-            // Poll every 5 seconds
-            cx.spawn({
-                let doc_repo = document_repository.clone();
-                let folder_repo = folder_repository.clone();
-                async move |this, cx| {
-                    loop {
-                        smol::Timer::after(Duration::from_secs(5)).await;
-                        let documents = doc_repo.get_documents().await;
-                        let folders = folder_repo.get_folders().await;
-                        if let (Ok(documents), Ok(folders)) = (documents, folders) {
-                            let result = this.update(cx, |state: &mut Self, _| {
-                                state.document_state = LoadingState::Loaded(documents);
-                                state.folder_state = LoadingState::Loaded(folders);
-                            });
-                            if result.is_err() {
-                                break;
-                            }
+            // Poll every 5 seconds, the badges once their cache expired
+            cx.spawn(async move |this, cx| {
+                loop {
+                    smol::Timer::after(Duration::from_secs(5)).await;
+                    if let Ok(data) = Self::fetch_data(cx).await {
+                        let result = this.update(cx, |state: &mut Self, _| state.set_data(data));
+                        if result.is_err() {
+                            break;
                         }
                     }
+
+                    let Ok(stale) = this.read_with(cx, |state: &Self, _| {
+                        state
+                            .badges_fetched_at
+                            .is_none_or(|fetched_at| fetched_at.elapsed() >= BADGES_CACHE_DURATION)
+                    }) else {
+                        break;
+                    };
+                    if stale && let Ok(badges) = Self::fetch_badges(cx).await {
+                        let _ = this.update(cx, |state: &mut Self, cx| {
+                            state.set_badges(badges);
+                            cx.notify();
+                        });
+                    }
                 }
             })
             .detach();
@@ -147,6 +232,18 @@ impl AppSidebar {
                 document_state: LoadingState::Loading,
                 folder_state: LoadingState::Loading,
                 expanded_folders: HashSet::new(),
+                dirty_documents: HashSet::new(),
+                unfiled_documents: Vec::new(),
+                unfiled_expanded: true,
+                smart_folders: Vec::new(),
+                expanded_smart_folders: HashSet::new(),
+                selected_unfiled: HashSet::new(),
+                document_tags: HashMap::new(),
+                badges: DocumentBadges::default(),
+                badges_fetched_at: None,
+                folder_counts: HashMap::new(),
+                selected_documents: HashSet::new(),
+                selection_anchor: None,
                 drop_target_folder: None,
                 editing_item: None,
                 rename_input: None,
@@ -164,6 +261,7 @@ impl AppSidebar {
     fn render_user_dropdown(&self, cx: &Context<Self>) -> impl IntoElement {
         let username = Self::get_username();
         let sidebar_fg = cx.theme().sidebar_foreground;
+        let app_state = self.app_state.clone();
 
         SidebarHeader::new()
             .p_1()
@@ -191,14 +289,51 @@ impl AppSidebar {
                             .text_color(sidebar_fg.opacity(0.6)),
                     ),
             )
-            .dropdown_menu(|menu, _, _| {
-                menu.min_w(px(220.)).item(
-                    PopupMenuItem::new("Settings")
-                        .icon(Icon::new(IconName::Settings))
-                        .on_click(|_, window, cx| {
-                            SettingsDialog::open(window, cx);
-                        }),
-                )
+            .dropdown_menu(move |menu, _, _| {
+                let board_app_state = app_state.clone();
+                let calendar_app_state = app_state.clone();
+                let reminders_app_state = app_state.clone();
+                let tasks_app_state = app_state.clone();
+                menu.min_w(px(220.))
+                    .item(
+                        PopupMenuItem::new("Board")
+                            .icon(Icon::default().path("icons/kanban.svg"))
+                            .on_click(move |_, _, cx| {
+                                board_app_state
+                                    .update(cx, |app_state, cx| app_state.open_board(cx));
+                            }),
+                    )
+                    .item(
+                        PopupMenuItem::new("Calendar")
+                            .icon(Icon::default().path("icons/calendar.svg"))
+                            .on_click(move |_, _, cx| {
+                                calendar_app_state
+                                    .update(cx, |app_state, cx| app_state.open_calendar(cx));
+                            }),
+                    )
+                    .item(
+                        PopupMenuItem::new("Reminders")
+                            .icon(Icon::default().path("icons/bell.svg"))
+                            .on_click(move |_, _, cx| {
+                                reminders_app_state
+                                    .update(cx, |app_state, cx| app_state.open_reminders(cx));
+                            }),
+                    )
+                    .item(
+                        PopupMenuItem::new("My tasks")
+                            .icon(Icon::default().path("icons/square-check.svg"))
+                            .on_click(move |_, _, cx| {
+                                tasks_app_state
+                                    .update(cx, |app_state, cx| app_state.open_tasks(cx));
+                            }),
+                    )
+                    .item(
+                        PopupMenuItem::new("Settings")
+                            .icon(Icon::new(IconName::Settings))
+                            .on_click(|_, window, cx| {
+                                SettingsDialog::open(window, cx);
+                            }),
+                    )
             })
     }
 
@@ -294,52 +429,1143 @@ impl AppSidebar {
         self.rename_input = None;
     }
 
-    /// Build a tree structure from flat lists of folders and documents
-    fn build_tree(folders: &[FolderModel], documents: &[DocumentModel]) -> Vec<SidebarItem> {
-        fn build_children(
-            parent_id: Option<i32>,
-            folders: &[FolderModel],
-            documents: &[DocumentModel],
-        ) -> Vec<SidebarItem> {
-            let mut items = Vec::new();
+    /// Build a tree structure from flat lists of folders and documents
+    fn build_tree(folders: &[FolderModel], documents: &[DocumentModel]) -> Vec<SidebarItem> {
+        fn build_children(
+            parent_id: Option<i32>,
+            folders: &[FolderModel],
+            documents: &[DocumentModel],
+        ) -> Vec<SidebarItem> {
+            let mut items = Vec::new();
+
+            // Add child folders first
+            for folder in folders.iter().filter(|f| f.parent_id == parent_id) {
+                let children = build_children(Some(folder.id), folders, documents);
+                items.push(SidebarItem::Folder {
+                    model: folder.clone(),
+                    children,
+                });
+            }
+
+            // Then add documents
+            for doc in documents.iter().filter(|d| d.folder_id == parent_id) {
+                items.push(SidebarItem::Document(doc.clone()));
+            }
+
+            items
+        }
+
+        build_children(None, folders, documents)
+    }
+
+    fn refresh_data(this: &Entity<Self>, cx: &mut App) {
+        let this = this.clone();
+
+        cx.spawn(async move |cx| {
+            let data = Self::fetch_data(cx).await?;
+            this.update(cx, |state, _| state.set_data(data));
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
+    async fn fetch_data(cx: &mut AsyncApp) -> Result<SidebarData, anyhow::Error> {
+        let (doc_repo, folder_repo, tag_repo, smart_folder_repo) = cx.update(|cx| {
+            let repositories = cx.global::<RepositoryState>();
+            (
+                repositories.documents.clone(),
+                repositories.folders.clone(),
+                repositories.tags.clone(),
+                repositories.smart_folders.clone(),
+            )
+        });
+
+        let mut documents = doc_repo.get_documents().await?;
+        let mut unfiled_documents = doc_repo.get_unfiled_documents().await?;
+        // Deleted documents are only removed once the deletion can't be undone anymore
+        cx.update(|cx| {
+            let deletions = cx.global::<DeletionState>();
+            documents.retain(|document| !deletions.is_pending(document.id));
+            unfiled_documents.retain(|document| !deletions.is_pending(document.id));
+        });
+        let folders = folder_repo.get_folders().await?;
+        let smart_folders = smart_folder_repo.get_smart_folders().await?;
+
+        let mut document_tags: HashMap<i32, Vec<String>> = HashMap::new();
+        for tag in tag_repo.get_document_tags().await? {
+            document_tags
+                .entry(tag.document_id)
+                .or_default()
+                .push(tag.tag);
+        }
+
+        Ok(SidebarData {
+            documents,
+            unfiled_documents,
+            folders,
+            smart_folders,
+            document_tags,
+            dirty_documents: Self::fetch_dirty_documents(cx).await,
+        })
+    }
+
+    /// Counts the open tasks and overdue reminders of the documents, from the tasks
+    /// indexed when the documents were saved.
+    async fn fetch_badges(cx: &mut AsyncApp) -> Result<DocumentBadges, anyhow::Error> {
+        let (task_repo, reminder_repo) = cx.update(|cx| {
+            let repositories = cx.global::<RepositoryState>();
+            (repositories.tasks.clone(), repositories.reminders.clone())
+        });
+
+        let mut badges = DocumentBadges::default();
+        for task in task_repo.get_open_tasks().await? {
+            *badges.open_tasks.entry(task.document_id).or_default() += 1;
+        }
+        let now = ReminderState::now();
+        for reminder in reminder_repo.get_pending_reminders().await? {
+            if reminder.is_overdue(now) {
+                *badges
+                    .overdue_reminders
+                    .entry(reminder.document_id)
+                    .or_default() += 1;
+            }
+        }
+        Ok(badges)
+    }
+
+    fn set_badges(&mut self, badges: DocumentBadges) {
+        self.badges = badges;
+        self.badges_fetched_at = Some(Instant::now());
+        self.update_folder_counts();
+    }
+
+    /// Adds the documents and badges of each folder up to its parents.
+    fn update_folder_counts(&mut self) {
+        let (LoadingState::Loaded(folders), LoadingState::Loaded(documents)) =
+            (&self.folder_state, &self.document_state)
+        else {
+            return;
+        };
+
+        let mut counts = HashMap::<i32, FolderCounts>::new();
+        for document in documents {
+            let open_tasks = self.badges.open_tasks.get(&document.id).copied();
+            let overdue_reminders = self.badges.overdue_reminders.get(&document.id).copied();
+            let mut folder_id = document.folder_id;
+            // Bounded in case the parents loop
+            for _ in 0..folders.len() {
+                let Some(folder) = folder_id.and_then(|id| folders.iter().find(|f| f.id == id))
+                else {
+                    break;
+                };
+                let count = counts.entry(folder.id).or_default();
+                count.documents += 1;
+                count.open_tasks += open_tasks.unwrap_or_default();
+                count.overdue_reminders += overdue_reminders.unwrap_or_default();
+                folder_id = folder.parent_id;
+            }
+        }
+        self.folder_counts = counts;
+    }
+
+    fn set_data(&mut self, data: SidebarData) {
+        let exists = |id: &i32| data.documents.iter().any(|document| document.id == *id);
+        self.selected_documents.retain(exists);
+        self.selected_unfiled.retain(|id| {
+            data.unfiled_documents
+                .iter()
+                .any(|document| document.id == *id)
+        });
+
+        self.document_state = LoadingState::Loaded(data.documents);
+        self.unfiled_documents = data.unfiled_documents;
+        self.folder_state = LoadingState::Loaded(data.folders);
+        self.smart_folders = data.smart_folders;
+        self.document_tags = data.document_tags;
+        self.dirty_documents = data.dirty_documents;
+        self.update_folder_counts();
+    }
+
+    /// Moves the selected unfiled documents into a folder.
+    fn move_selected_unfiled(this: &Entity<Self>, folder_id: i32, cx: &mut App) {
+        let ids = this.update(cx, |state, cx| {
+            cx.notify();
+            std::mem::take(&mut state.selected_unfiled)
+        });
+        let doc_repo = cx.global::<RepositoryState>().documents.clone();
+        let this = this.clone();
+
+        cx.spawn(async move |cx| {
+            for id in ids {
+                if let Err(e) = doc_repo.move_document(id, Some(folder_id)).await {
+                    let doc_repo = doc_repo.clone();
+                    AppError::new(ErrorKind::Storage, "Failed to move a document", &e)
+                        .with_retry_operation(move || {
+                            let doc_repo = doc_repo.clone();
+                            async move { doc_repo.move_document(id, Some(folder_id)).await }
+                        })
+                        .report();
+                }
+            }
+            cx.update(|cx| {
+                AppSidebar::refresh_data(&this, cx);
+            });
+        })
+        .detach();
+    }
+
+    /// Updates the selection for a click on a document of the tree and returns whether the
+    /// document should be opened.
+    ///
+    /// Secondary click toggles the document, shift click selects the visible documents
+    /// between the last clicked one and this one, and a plain click clears the selection.
+    fn select_on_click(
+        &mut self,
+        document_id: i32,
+        window: &Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let modifiers = window.modifiers();
+        if !modifiers.shift && !modifiers.secondary() {
+            self.selected_documents.clear();
+            self.selection_anchor = Some(document_id);
+            cx.notify();
+            return true;
+        }
+
+        // The document opened last is part of a selection started from it
+        if self.selected_documents.is_empty()
+            && let Some(anchor) = self.selection_anchor
+        {
+            self.selected_documents.insert(anchor);
+        }
+
+        if modifiers.shift {
+            let order = self.visible_document_ids();
+            let anchor = self
+                .selection_anchor
+                .and_then(|anchor| order.iter().position(|id| *id == anchor));
+            let target = order.iter().position(|id| *id == document_id);
+
+            match (anchor, target) {
+                (Some(anchor), Some(target)) => {
+                    let range = anchor.min(target)..=anchor.max(target);
+                    self.selected_documents.extend(&order[range]);
+                }
+                _ => {
+                    self.selected_documents.insert(document_id);
+                }
+            }
+        } else {
+            if !self.selected_documents.remove(&document_id) {
+                self.selected_documents.insert(document_id);
+            }
+            self.selection_anchor = Some(document_id);
+        }
+
+        cx.notify();
+        false
+    }
+
+    /// Documents of the tree in display order, skipping the ones in collapsed folders.
+    fn visible_document_ids(&self) -> Vec<i32> {
+        fn collect(items: &[SidebarItem], expanded_folders: &HashSet<i32>, ids: &mut Vec<i32>) {
+            for item in items {
+                match item {
+                    SidebarItem::Folder { model, children } => {
+                        if expanded_folders.contains(&model.id) {
+                            collect(children, expanded_folders, ids);
+                        }
+                    }
+                    SidebarItem::Document(document) => ids.push(document.id),
+                }
+            }
+        }
+
+        let (LoadingState::Loaded(folders), LoadingState::Loaded(documents)) =
+            (&self.folder_state, &self.document_state)
+        else {
+            return Vec::new();
+        };
+
+        let mut ids = Vec::new();
+        collect(
+            &Self::build_tree(folders, documents),
+            &self.expanded_folders,
+            &mut ids,
+        );
+        ids
+    }
+
+    /// Clears the selection and returns the documents it contained.
+    fn take_selection(this: &Entity<Self>, cx: &mut App) -> Vec<i32> {
+        this.update(cx, |state, cx| {
+            cx.notify();
+            std::mem::take(&mut state.selected_documents)
+                .into_iter()
+                .collect()
+        })
+    }
+
+    fn bulk_move(this: &Entity<Self>, folder_id: Option<i32>, cx: &mut App) {
+        let ids = Self::take_selection(this, cx);
+        let doc_repo = cx.global::<RepositoryState>().documents.clone();
+        let this = this.clone();
+
+        cx.spawn(async move |cx| {
+            for id in ids {
+                if let Err(e) = doc_repo.move_document(id, folder_id).await {
+                    let doc_repo = doc_repo.clone();
+                    AppError::new(ErrorKind::Storage, "Failed to move a document", &e)
+                        .with_retry_operation(move || {
+                            let doc_repo = doc_repo.clone();
+                            async move { doc_repo.move_document(id, folder_id).await }
+                        })
+                        .report();
+                }
+            }
+            cx.update(|cx| {
+                AppSidebar::refresh_data(&this, cx);
+            });
+        })
+        .detach();
+    }
+
+    /// Deletes a folder with its documents and sub-folders once the user typed DELETE, as
+    /// it can't be undone.
+    fn delete_folder_with_contents(
+        this: &Entity<Self>,
+        folder_id: i32,
+        name: String,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let this = this.clone();
+
+        ConfirmDialog::new("Delete Folder")
+            .message(format!(
+                "Are you sure you want to delete \"{}\" and all its contents? This action cannot be undone.",
+                name
+            ))
+            .confirm_text("Delete")
+            .cancel_text("Cancel")
+            .destructive_requires_typing("DELETE")
+            .on_confirm_async(move |window, cx| {
+                let folder_repo = cx.global::<RepositoryState>().folders.clone();
+                let this = this.clone();
+                let name = name.clone();
+                let window_handle = window.window_handle();
+
+                cx.spawn(async move |cx| {
+                    folder_repo.delete_folder(folder_id).await?;
+
+                    cx.update_window(window_handle, |_, window, cx| {
+                        window.push_notification(format!("\"{}\" has been deleted", name), cx);
+                    })?;
+                    cx.update(|cx| AppSidebar::refresh_data(&this, cx));
+
+                    Ok(())
+                })
+            })
+            .open(window, cx);
+    }
+
+    fn bulk_delete(this: &Entity<Self>, window: &mut Window, cx: &mut App) {
+        let count = this.read(cx).selected_documents.len();
+        let this = this.clone();
+
+        ConfirmDialog::new("Delete Pages")
+            .message(format!(
+                "Are you sure you want to delete {} documents?",
+                count
+            ))
+            .confirm_text("Delete")
+            .cancel_text("Cancel")
+            .danger()
+            .on_confirm(move |window, cx| {
+                let ids = Self::take_selection(&this, cx);
+                let message = format!("{} documents have been deleted", ids.len());
+                DeletionState::delete_documents(ids, message, window, cx);
+
+                true
+            })
+            .open(window, cx);
+    }
+
+    fn bulk_tag(this: &Entity<Self>, window: &mut Window, cx: &mut App) {
+        let count = this.read(cx).selected_documents.len();
+        let this = this.clone();
+
+        TagDialog::open(
+            format!("The tag is added to the {} selected documents.", count),
+            move |tag, window, cx| {
+                let ids = Self::take_selection(&this, cx);
+                let tag_repo = cx.global::<RepositoryState>().tags.clone();
+                let window_handle = window.window_handle();
+                let this = this.clone();
+
+                cx.spawn(async move |cx| {
+                    let result = tag_repo.add_tag(ids, tag.clone()).await;
+                    cx.update(|cx| AppSidebar::refresh_data(&this, cx));
+
+                    let message = match result {
+                        Ok(()) => format!("Tagged documents with #{}", tag),
+                        Err(e) => {
+                            tracing::error!("failed to tag documents: {:?}", e);
+                            "Failed to tag documents".to_string()
+                        }
+                    };
+                    cx.update_window(window_handle, |_, window, cx| {
+                        window.push_notification(message, cx);
+                    })?;
+
+                    Ok::<_, anyhow::Error>(())
+                })
+                .detach();
+            },
+            window,
+            cx,
+        );
+    }
+
+    /// Appends the blocks of a document to another one picked by the user, under a heading
+    /// with its title, then deletes it.
+    fn merge_document(
+        this: &Entity<Self>,
+        source_id: i32,
+        title: String,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let this = this.clone();
+
+        MergeDialog::open(
+            source_id,
+            title,
+            move |target, window, cx| {
+                let state = cx.global::<DocumentState>();
+                if state.persistence(source_id) == PersistenceState::Pending
+                    || state.persistence(target.id) == PersistenceState::Pending
+                {
+                    window.push_notification("Wait for the changes to be saved before merging", cx);
+                    return;
+                }
+
+                let repository = cx.global::<RepositoryState>().documents.clone();
+                let window_handle = window.window_handle();
+                let this = this.clone();
+
+                cx.spawn(async move |cx| {
+                    let result = async {
+                        let source = repository.get_document_by_id(source_id).await?;
+                        let mut merged = repository.get_document_by_id(target.id).await?;
+                        merged.content = merged_content(&merged.content, &source);
+                        repository.update_document(merged).await?;
+                        repository.delete_document(source_id).await
+                    }
+                    .await;
+
+                    // Not retried, the blocks would be appended twice if only the deletion failed
+                    let message = match result {
+                        Ok(()) => format!("Merged into \"{}\"", target.title),
+                        Err(e) => {
+                            AppError::new(ErrorKind::Storage, "Failed to merge documents", &e)
+                                .report();
+                            return Ok(());
+                        }
+                    };
+
+                    cx.update(|cx| {
+                        cx.update_global::<DocumentState, _>(|state, _| {
+                            state.remove_document(source_id);
+                            state.reload_documents(&[target.id]);
+                        });
+                        AppSidebar::refresh_data(&this, cx);
+                    });
+                    cx.update_window(window_handle, |_, window, cx| {
+                        window.push_notification(message, cx);
+                    })?;
+
+                    Ok::<_, anyhow::Error>(())
+                })
+                .detach();
+            },
+            window,
+            cx,
+        );
+    }
+
+    /// Writes the selected documents as markdown files in a folder picked by the user.
+    fn bulk_export(this: &Entity<Self>, window: &mut Window, cx: &mut App) {
+        let selected = this.read(cx).selected_documents.clone();
+        let documents = match &this.read(cx).document_state {
+            LoadingState::Loaded(documents) => documents
+                .iter()
+                .filter(|document| selected.contains(&document.id))
+                .cloned()
+                .collect::<Vec<_>>(),
+            _ => return,
+        };
+
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: false,
+            directories: true,
+            multiple: false,
+            prompt: Some("Export here".into()),
+        });
+        let numbered_headings = cx.global::<Settings>().editor.numbered_headings.clone();
+        let tags = cx.global::<RepositoryState>().tags.clone();
+        let window_handle = window.window_handle();
+        let this = this.clone();
+
+        cx.spawn(async move |cx| {
+            let Ok(Ok(Some(paths))) = paths.await else {
+                return Ok(());
+            };
+            let Some(directory) = paths.into_iter().next() else {
+                return Ok(());
+            };
+
+            let count = documents.len();
+            let task = cx.update(|cx| {
+                Self::take_selection(&this, cx);
+                ProgressTask::start(format!("Exporting {} documents", count), true, cx)
+            });
+
+            // Files are reserved first so that the documents can link to each other
+            let prepared = async {
+                let template = export_template(&tags, TemplateFormat::Markdown).await?;
+                let documents = documents.clone();
+                let directory = directory.clone();
+                let numbered_headings = numbered_headings.clone();
+                let links = cx
+                    .background_executor()
+                    .spawn(async move {
+                        markdown::ExportLinks::reserve(&documents, &directory, |id| {
+                            numbered_headings.contains(&id)
+                        })
+                    })
+                    .await?;
+                Ok::<_, anyhow::Error>((Arc::new(links), Arc::new(template)))
+            }
+            .await;
+
+            let mut exported = 0;
+            let (links, template, mut result) = match prepared {
+                Ok((links, template)) => (links, template, Ok(())),
+                Err(e) => (Arc::default(), Arc::default(), Err(e)),
+            };
+            for document in documents {
+                if result.is_err() || task.is_cancelled() {
+                    break;
+                }
+                let links = links.clone();
+                let template = template.clone();
+                let numbered = numbered_headings.contains(&document.id);
+                result = cx
+                    .background_executor()
+                    .spawn(async move {
+                        let (template, tags) = match template.as_ref() {
+                            Some((template, tags)) => (Some(template), tags.get(&document.id)),
+                            None => (None, None),
+                        };
+                        markdown::export_linked_document(
+                            &document,
+                            &links,
+                            numbered,
+                            template,
+                            tags.map(Vec::as_slice).unwrap_or_default(),
+                        )
+                    })
+                    .await
+                    .map(|_| ());
+                if result.is_err() {
+                    break;
+                }
+                exported += 1;
+                cx.update(|cx| task.set_percent(exported as f32 * 100.0 / count as f32, cx));
+            }
+
+            // Finished even if the window was closed meanwhile
+            cx.update(|cx| task.finish(cx));
+            cx.update_window(window_handle, |_, window, cx| match result {
+                Ok(()) => {
+                    Toast::success(format!("Exported {} documents", exported))
+                        .action("Show", move |_, cx| cx.reveal_path(&directory))
+                        .show(window, cx);
+                }
+                Err(e) => {
+                    tracing::error!("failed to export documents: {:?}", e);
+                    Toast::error(format!("Failed to export documents: {}", e)).show(window, cx);
+                }
+            })?;
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
+    /// Actions applying to every document of the current selection.
+    fn render_selection_actions(
+        &self,
+        folders: &[FolderModel],
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let this = cx.entity().clone();
+        let folders = folders.to_vec();
+
+        h_flex()
+            .flex_shrink_0()
+            .mx_1()
+            .mb_1()
+            .px_2()
+            .h_8()
+            .gap_0p5()
+            .items_center()
+            .rounded(cx.theme().radius)
+            .bg(cx.theme().sidebar_accent)
+            .text_xs()
+            .text_color(cx.theme().sidebar_foreground.opacity(0.9))
+            .child(
+                div()
+                    .flex_1()
+                    .child(format!("{} selected", self.selected_documents.len())),
+            )
+            .child({
+                let this = this.clone();
+                Button::new("bulk-move")
+                    .icon(Icon::new(IconName::Folder))
+                    .ghost()
+                    .xsmall()
+                    .cursor_pointer()
+                    .tooltip("Move to folder")
+                    .dropdown_menu(move |menu, _, _| {
+                        let this_root = this.clone();
+                        let mut menu =
+                            menu.min_w(px(180.))
+                                .item(PopupMenuItem::new("Root").on_click(move |_, _, cx| {
+                                    AppSidebar::bulk_move(&this_root, None, cx);
+                                }));
+                        if !folders.is_empty() {
+                            menu = menu.separator();
+                        }
+                        for folder in &folders {
+                            let folder_id = folder.id;
+                            let this = this.clone();
+                            menu = menu.item(
+                                PopupMenuItem::new(folder.name.clone())
+                                    .icon(Icon::new(IconName::Folder))
+                                    .on_click(move |_, _, cx| {
+                                        AppSidebar::bulk_move(&this, Some(folder_id), cx);
+                                    }),
+                            );
+                        }
+                        menu
+                    })
+            })
+            .child({
+                let this = this.clone();
+                Button::new("bulk-tag")
+                    .icon(Icon::default().path("icons/tag.svg"))
+                    .ghost()
+                    .xsmall()
+                    .cursor_pointer()
+                    .tooltip("Add tag")
+                    .on_click(move |_, window, cx| {
+                        AppSidebar::bulk_tag(&this, window, cx);
+                    })
+            })
+            .child({
+                let this = this.clone();
+                Button::new("bulk-export")
+                    .icon(Icon::default().path("icons/download.svg"))
+                    .ghost()
+                    .xsmall()
+                    .cursor_pointer()
+                    .tooltip("Export as markdown")
+                    .on_click(move |_, window, cx| {
+                        AppSidebar::bulk_export(&this, window, cx);
+                    })
+            })
+            .child({
+                let this = this.clone();
+                Button::new("bulk-delete")
+                    .icon(Icon::default().path("icons/trash-2.svg"))
+                    .danger()
+                    .xsmall()
+                    .cursor_pointer()
+                    .tooltip("Delete")
+                    .on_click(move |_, window, cx| {
+                        AppSidebar::bulk_delete(&this, window, cx);
+                    })
+            })
+            .child(
+                Button::new("clear-selection")
+                    .icon(Icon::new(IconName::Close))
+                    .ghost()
+                    .xsmall()
+                    .cursor_pointer()
+                    .tooltip("Clear selection")
+                    .on_click(move |_, _, cx| {
+                        AppSidebar::take_selection(&this, cx);
+                    }),
+            )
+    }
+
+    /// Smart group listing the documents that are not in any folder, with bulk filing.
+    fn render_unfiled_group(
+        &self,
+        folders: &[FolderModel],
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let header_text_color = cx.theme().sidebar_foreground.opacity(0.5);
+        let item_text_color = cx.theme().sidebar_foreground.opacity(0.9);
+        let icon_color = cx.theme().sidebar_foreground.opacity(0.6);
+        let accent_bg = cx.theme().sidebar_accent;
+        let this = cx.entity().clone();
+
+        let expanded = self.unfiled_expanded;
+        let selected_count = self.selected_unfiled.len();
+        let all_selected = selected_count == self.unfiled_documents.len();
+
+        let header = h_flex()
+            .id("unfiled-header")
+            .flex_shrink_0()
+            .px_2()
+            .h_8()
+            .gap_1()
+            .items_center()
+            .text_xs()
+            .text_color(header_text_color)
+            .cursor_pointer()
+            .on_click(cx.listener(|this, _, _, cx| {
+                this.unfiled_expanded = !this.unfiled_expanded;
+                cx.notify();
+            }))
+            .child(
+                Icon::new(if expanded {
+                    IconName::ChevronDown
+                } else {
+                    IconName::ChevronRight
+                })
+                .size_3()
+                .text_color(icon_color),
+            )
+            .child(div().flex_1().child("Unfiled"))
+            .child(self.unfiled_documents.len().to_string())
+            .when(expanded, |el| {
+                el.child(
+                    div()
+                        .on_mouse_down(MouseButton::Left, |_, _, cx| {
+                            cx.stop_propagation();
+                        })
+                        .child(
+                            Checkbox::new("unfiled-select-all")
+                                .checked(all_selected)
+                                .xsmall()
+                                .on_click(cx.listener(|this, checked: &bool, _, cx| {
+                                    this.selected_unfiled = if *checked {
+                                        this.unfiled_documents.iter().map(|doc| doc.id).collect()
+                                    } else {
+                                        HashSet::new()
+                                    };
+                                    cx.notify();
+                                })),
+                        ),
+                )
+            });
+
+        let move_button = {
+            let folders = folders.to_vec();
+            Button::new("move-unfiled")
+                .icon(Icon::new(IconName::Folder))
+                .label(format!("Move {} to...", selected_count))
+                .ghost()
+                .xsmall()
+                .cursor_pointer()
+                .dropdown_menu(move |menu, _, _| {
+                    let mut menu = menu.min_w(px(180.));
+                    for folder in &folders {
+                        let folder_id = folder.id;
+                        let this = this.clone();
+                        menu = menu.item(
+                            PopupMenuItem::new(folder.name.clone())
+                                .icon(Icon::new(IconName::Folder))
+                                .on_click(move |_, _, cx| {
+                                    AppSidebar::move_selected_unfiled(&this, folder_id, cx);
+                                }),
+                        );
+                    }
+                    menu
+                })
+        };
+
+        let items = self.unfiled_documents.iter().map(|document| {
+            let document_id = document.id;
+            let title = document.title.clone();
+            let folder_id = document.folder_id;
+            let is_selected = self.selected_unfiled.contains(&document_id);
+            let app_state = self.app_state.clone();
+
+            h_flex()
+                .id(("unfiled-document", document_id as usize))
+                .w_full()
+                .px_2()
+                .py_1()
+                .gap_2()
+                .items_center()
+                .rounded_md()
+                .cursor_pointer()
+                .when(is_selected, |el| el.bg(accent_bg.opacity(0.5)))
+                .hover(|el| el.bg(accent_bg))
+                .tooltip(DocumentPreview::tooltip(document_id))
+                .on_click(move |_, _, cx| {
+                    app_state.update(cx, |app_state, cx| {
+                        app_state.open_document(document_id, title.clone(), folder_id, cx);
+                    });
+                })
+                .child(
+                    div()
+                        .on_mouse_down(MouseButton::Left, |_, _, cx| {
+                            cx.stop_propagation();
+                        })
+                        .child(
+                            Checkbox::new(("unfiled-select", document_id as usize))
+                                .checked(is_selected)
+                                .xsmall()
+                                .on_click(cx.listener(move |this, checked: &bool, _, cx| {
+                                    if *checked {
+                                        this.selected_unfiled.insert(document_id);
+                                    } else {
+                                        this.selected_unfiled.remove(&document_id);
+                                    }
+                                    cx.notify();
+                                })),
+                        ),
+                )
+                .child(
+                    Icon::default()
+                        .path("icons/file-text.svg")
+                        .size_4()
+                        .text_color(icon_color),
+                )
+                .child(
+                    div()
+                        .flex_1()
+                        .text_sm()
+                        .overflow_hidden()
+                        .text_ellipsis()
+                        .text_color(item_text_color)
+                        .child(document.title.clone()),
+                )
+        });
+
+        v_flex()
+            .w_full()
+            .px_1()
+            .child(header)
+            .when(
+                expanded && selected_count > 0 && !folders.is_empty(),
+                |el| el.child(h_flex().px_2().pb_1().child(move_button)),
+            )
+            .when(expanded, |el| el.children(items.collect::<Vec<_>>()))
+    }
+
+    /// Asks for a new smart folder, or for changes to an existing one, and saves it.
+    fn edit_smart_folder(
+        this: &Entity<Self>,
+        smart_folder: Option<SmartFolderModel>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let this = this.clone();
+
+        SmartFolderDialog::open(
+            smart_folder.as_ref(),
+            move |name, query, window, cx| {
+                let repository = cx.global::<RepositoryState>().smart_folders.clone();
+                let window_handle = window.window_handle();
+                let existing_id = smart_folder.as_ref().map(|smart_folder| smart_folder.id);
+                let this = this.clone();
+
+                cx.spawn(async move |cx| {
+                    let result =
+                        match existing_id {
+                            Some(id) => {
+                                repository
+                                    .update_smart_folder(SmartFolderModel { id, name, query })
+                                    .await
+                            }
+                            None => repository.insert_smart_folder(name, query).await.map(
+                                |smart_folder| {
+                                    cx.update(|cx| {
+                                        this.update(cx, |state, _| {
+                                            state.expanded_smart_folders.insert(smart_folder.id);
+                                        })
+                                    });
+                                },
+                            ),
+                        };
+                    cx.update(|cx| AppSidebar::refresh_data(&this, cx));
+
+                    if let Err(e) = result {
+                        tracing::error!("failed to save smart folder: {:?}", e);
+                        cx.update_window(window_handle, |_, window, cx| {
+                            window.push_notification("Failed to save the smart folder", cx);
+                        })?;
+                    }
+
+                    Ok::<_, anyhow::Error>(())
+                })
+                .detach();
+            },
+            window,
+            cx,
+        );
+    }
+
+    fn delete_smart_folder(this: &Entity<Self>, id: i32, cx: &mut App) {
+        let repository = cx.global::<RepositoryState>().smart_folders.clone();
+        let this = this.clone();
+
+        cx.spawn(async move |cx| {
+            if let Err(e) = repository.delete_smart_folder(id).await {
+                let repository = repository.clone();
+                AppError::new(ErrorKind::Storage, "Failed to delete the smart folder", &e)
+                    .with_retry_operation(move || {
+                        let repository = repository.clone();
+                        async move { repository.delete_smart_folder(id).await }
+                    })
+                    .report();
+            }
+            cx.update(|cx| AppSidebar::refresh_data(&this, cx));
+        })
+        .detach();
+    }
+
+    /// Saved searches, whose matching documents are listed when expanded. They are computed
+    /// from the data fetched for the tree, so they follow the changes to the documents.
+    fn render_smart_folders(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let header_text_color = cx.theme().sidebar_foreground.opacity(0.5);
+        let item_text_color = cx.theme().sidebar_foreground.opacity(0.9);
+        let icon_color = cx.theme().sidebar_foreground.opacity(0.6);
+        let accent_bg = cx.theme().sidebar_accent;
+        let this = cx.entity().clone();
+
+        let documents = match &self.document_state {
+            LoadingState::Loaded(documents) => documents.as_slice(),
+            _ => &[],
+        };
+
+        let header = h_flex()
+            .flex_shrink_0()
+            .px_2()
+            .h_8()
+            .justify_between()
+            .items_center()
+            .text_xs()
+            .text_color(header_text_color)
+            .child("Smart folders")
+            .child(
+                Button::new("create-smart-folder")
+                    .icon(Icon::new(IconName::Plus))
+                    .ghost()
+                    .xsmall()
+                    .cursor_pointer()
+                    .tooltip("New smart folder")
+                    .on_click({
+                        let this = this.clone();
+                        move |_, window, cx| {
+                            AppSidebar::edit_smart_folder(&this, None, window, cx);
+                        }
+                    }),
+            );
+
+        let smart_folders = self.smart_folders.iter().map(|smart_folder| {
+            let smart_folder_id = smart_folder.id;
+            let expanded = self.expanded_smart_folders.contains(&smart_folder_id);
 
-            // Add child folders first
-            for folder in folders.iter().filter(|f| f.parent_id == parent_id) {
-                let children = build_children(Some(folder.id), folders, documents);
-                items.push(SidebarItem::Folder {
-                    model: folder.clone(),
-                    children,
+            let row = h_flex()
+                .id(("smart-folder", smart_folder_id as usize))
+                .w_full()
+                .px_2()
+                .py_1()
+                .gap_1()
+                .items_center()
+                .rounded_md()
+                .cursor_pointer()
+                .focus_ring(cx.theme().ring)
+                .hover(|el| el.bg(accent_bg))
+                .on_click(cx.listener(move |this, _, _, cx| {
+                    if !this.expanded_smart_folders.remove(&smart_folder_id) {
+                        this.expanded_smart_folders.insert(smart_folder_id);
+                    }
+                    cx.notify();
+                }))
+                .child(
+                    Icon::new(if expanded {
+                        IconName::ChevronDown
+                    } else {
+                        IconName::ChevronRight
+                    })
+                    .size_3()
+                    .text_color(icon_color),
+                )
+                .child(
+                    Icon::default()
+                        .path("icons/search.svg")
+                        .size_4()
+                        .text_color(icon_color),
+                )
+                .child(
+                    div()
+                        .flex_1()
+                        .text_sm()
+                        .overflow_hidden()
+                        .text_ellipsis()
+                        .text_color(item_text_color)
+                        .child(smart_folder.name.clone()),
+                )
+                .context_menu({
+                    let this = this.clone();
+                    let smart_folder = smart_folder.clone();
+                    move |menu, _window, _cx| {
+                        menu.item(
+                            PopupMenuItem::new("Edit")
+                                .icon(Icon::default().path("icons/pencil-line.svg"))
+                                .on_click({
+                                    let this = this.clone();
+                                    let smart_folder = smart_folder.clone();
+                                    move |_, window, cx| {
+                                        AppSidebar::edit_smart_folder(
+                                            &this,
+                                            Some(smart_folder.clone()),
+                                            window,
+                                            cx,
+                                        );
+                                    }
+                                }),
+                        )
+                        .separator()
+                        .item(
+                            PopupMenuItem::new("Delete")
+                                .icon(Icon::default().path("icons/trash-2.svg"))
+                                .on_click({
+                                    let this = this.clone();
+                                    move |_, _, cx| {
+                                        AppSidebar::delete_smart_folder(&this, smart_folder_id, cx);
+                                    }
+                                }),
+                        )
+                    }
                 });
-            }
 
-            // Then add documents
-            for doc in documents.iter().filter(|d| d.folder_id == parent_id) {
-                items.push(SidebarItem::Document(doc.clone()));
-            }
+            let matches = if expanded {
+                documents
+                    .iter()
+                    .filter(|document| {
+                        let tags = self
+                            .document_tags
+                            .get(&document.id)
+                            .map(Vec::as_slice)
+                            .unwrap_or_default();
+                        smart_folder.matches(document, tags)
+                    })
+                    .map(|document| {
+                        let document_id = document.id;
+                        let title = document.title.clone();
+                        let folder_id = document.folder_id;
+                        let app_state = self.app_state.clone();
+
+                        h_flex()
+                            .id(SharedString::from(format!(
+                                "smart-folder-{}-{}",
+                                smart_folder_id, document_id
+                            )))
+                            .w_full()
+                            .pl_6()
+                            .pr_2()
+                            .py_1()
+                            .gap_2()
+                            .items_center()
+                            .rounded_md()
+                            .cursor_pointer()
+                            .hover(|el| el.bg(accent_bg))
+                            .tooltip(DocumentPreview::tooltip(document_id))
+                            .on_click(move |_, _, cx| {
+                                app_state.update(cx, |app_state, cx| {
+                                    app_state.open_document(
+                                        document_id,
+                                        title.clone(),
+                                        folder_id,
+                                        cx,
+                                    );
+                                });
+                            })
+                            .child(
+                                Icon::default()
+                                    .path("icons/file-text.svg")
+                                    .size_4()
+                                    .text_color(icon_color),
+                            )
+                            .child(
+                                div()
+                                    .flex_1()
+                                    .text_sm()
+                                    .overflow_hidden()
+                                    .text_ellipsis()
+                                    .text_color(item_text_color)
+                                    .child(document.title.clone()),
+                            )
+                            .into_any_element()
+                    })
+                    .collect::<Vec<_>>()
+            } else {
+                Vec::new()
+            };
+            let no_match = expanded && matches.is_empty();
 
-            items
-        }
+            v_flex()
+                .w_full()
+                .child(row)
+                .children(matches)
+                .when(no_match, |el| {
+                    el.child(
+                        div()
+                            .pl_6()
+                            .py_1()
+                            .text_xs()
+                            .text_color(header_text_color)
+                            .child("No matching document"),
+                    )
+                })
+        });
 
-        build_children(None, folders, documents)
+        v_flex()
+            .w_full()
+            .px_1()
+            .child(header)
+            .children(smart_folders.collect::<Vec<_>>())
     }
 
-    fn refresh_data(this: &Entity<Self>, cx: &mut App) {
-        let doc_repo = cx.global::<RepositoryState>().documents.clone();
-        let folder_repo = cx.global::<RepositoryState>().folders.clone();
-        let this = this.clone();
-
-        cx.spawn(async move |cx| {
-            let documents = doc_repo.get_documents().await?;
-            let folders = folder_repo.get_folders().await?;
-
-            this.update(cx, |state, _| {
-                state.document_state = LoadingState::Loaded(documents);
-                state.folder_state = LoadingState::Loaded(folders);
-            });
+    /// Documents with uncommitted changes when the vault is a git repository.
+    async fn fetch_dirty_documents(cx: &mut AsyncApp) -> HashSet<i32> {
+        let vault = cx.update(|cx| {
+            cx.try_global::<RepositoryState>()
+                .and_then(|state| state.vault.clone())
+        });
+        let Some(vault) = vault else {
+            return HashSet::new();
+        };
 
-            Ok::<_, anyhow::Error>(())
-        })
-        .detach();
+        cx.background_executor()
+            .spawn(async move { vault.dirty_documents() })
+            .await
+            .unwrap_or_else(|e| {
+                tracing::error!("failed to read git status: {:?}", e);
+                HashSet::new()
+            })
     }
 }
 
@@ -368,6 +1594,18 @@ impl Render for AppSidebar {
 
         let tree = Self::build_tree(&folders, &documents);
         let expanded_folders = self.expanded_folders.clone();
+        let dirty_documents = self.dirty_documents.clone();
+        let dirty_color = cx.theme().warning;
+        let overdue_color = cx.theme().danger;
+        let unsaved_documents = {
+            let state = cx.global::<DocumentState>();
+            documents
+                .iter()
+                .map(|document| document.id)
+                .filter(|id| state.persistence(*id) == PersistenceState::Pending)
+                .collect::<HashSet<_>>()
+        };
+        let ring_color = cx.theme().ring;
         let drop_target_folder = self.drop_target_folder;
         let editing_item = self.editing_item;
         let rename_input = self.rename_input.clone();
@@ -459,14 +1697,13 @@ impl Render for AppSidebar {
                                         cx.update(|cx: &mut App| {
                                             Self::refresh_data(&this_clone, cx);
 
-                                            cx.update_global::<DocumentState, _>(|state, _| {
-                                                state.open_document(new_id, "Untitled".to_string());
-                                            });
-
                                             app_state.update(cx, |app_state, cx| {
-                                                let document_screen =
-                                                    DocumentScreen::new(cx.weak_entity());
-                                                app_state.navigator.push(document_screen, cx);
+                                                app_state.open_document(
+                                                    new_id,
+                                                    "Untitled".to_string(),
+                                                    None,
+                                                    cx,
+                                                );
                                             });
                                         });
 
@@ -483,6 +1720,12 @@ impl Render for AppSidebar {
             tree,
             0,
             &expanded_folders,
+            &dirty_documents,
+            &unsaved_documents,
+            &self.selected_documents,
+            &self.document_tags,
+            &self.badges,
+            &self.folder_counts,
             drop_target_folder,
             editing_item,
             &rename_input,
@@ -492,6 +1735,9 @@ impl Render for AppSidebar {
             item_text_color,
             icon_color,
             accent_bg,
+            dirty_color,
+            overdue_color,
+            ring_color,
         );
 
         // Root drop zone: drop a document here to move it to root
@@ -555,17 +1801,14 @@ impl Render for AppSidebar {
                                                 repository.insert_document(new_document).await?;
                                             cx.update(|cx: &mut App| {
                                                 AppSidebar::refresh_data(&this_clone, cx);
-                                                cx.update_global::<DocumentState, _>(|state, _| {
-                                                    state.open_document(
+                                                app_state.update(cx, |app_state, cx| {
+                                                    app_state.open_document(
                                                         new_id,
                                                         "Untitled".to_string(),
+                                                        None,
+                                                        cx,
                                                     );
                                                 });
-                                                app_state.update(cx, |app_state, cx| {
-                                                    let document_screen =
-                                                        DocumentScreen::new(cx.weak_entity());
-                                                    app_state.navigator.push(document_screen, cx);
-                                                });
                                             });
                                             Ok::<_, anyhow::Error>(())
                                         })
@@ -607,7 +1850,14 @@ impl Render for AppSidebar {
             .border_r_1()
             .border_color(border_color)
             .child(div().px_2().py_2().child(self.render_user_dropdown(cx)))
+            .when(!self.unfiled_documents.is_empty(), |el| {
+                el.child(self.render_unfiled_group(&folders, cx))
+            })
+            .child(self.render_smart_folders(cx))
             .child(header)
+            .when(!self.selected_documents.is_empty(), |el| {
+                el.child(self.render_selection_actions(&folders, cx))
+            })
             .child(
                 div()
                     .flex()
@@ -623,10 +1873,47 @@ impl Render for AppSidebar {
 }
 
 #[allow(clippy::too_many_arguments)]
+/// Open tasks and overdue reminders of a sidebar item, nothing when there are none.
+fn render_badges(
+    open_tasks: usize,
+    overdue_reminders: usize,
+    task_color: Hsla,
+    overdue_color: Hsla,
+) -> impl IntoElement {
+    h_flex()
+        .flex_shrink_0()
+        .gap_1()
+        .text_xs()
+        .when(open_tasks > 0, |el| {
+            el.child(
+                h_flex()
+                    .gap_0p5()
+                    .text_color(task_color)
+                    .child(Icon::default().path("icons/square-check.svg").size_3())
+                    .child(open_tasks.to_string()),
+            )
+        })
+        .when(overdue_reminders > 0, |el| {
+            el.child(
+                h_flex()
+                    .gap_0p5()
+                    .text_color(overdue_color)
+                    .child(Icon::default().path("icons/bell.svg").size_3())
+                    .child(overdue_reminders.to_string()),
+            )
+        })
+}
+
 fn render_tree_items(
     items: Vec<SidebarItem>,
     depth: u32,
     expanded_folders: &HashSet<i32>,
+    dirty_documents: &HashSet<i32>,
+    unsaved_documents: &HashSet<i32>,
+    selected_documents: &HashSet<i32>,
+    document_tags: &HashMap<i32, Vec<String>>,
+    badges: &DocumentBadges,
+    folder_counts: &HashMap<i32, FolderCounts>,
     drop_target_folder: Option<i32>,
     editing_item: Option<EditingItem>,
     rename_input: &Option<Entity<InputState>>,
@@ -636,6 +1923,9 @@ fn render_tree_items(
     item_text_color: Hsla,
     icon_color: Hsla,
     accent_bg: Hsla,
+    dirty_color: Hsla,
+    overdue_color: Hsla,
+    ring_color: Hsla,
 ) -> Vec<ContextMenu<Stateful<Div>>> {
     let mut elements = Vec::new();
 
@@ -665,6 +1955,7 @@ fn render_tree_items(
                     .items_center()
                     .rounded_md()
                     .cursor_pointer()
+                    .focus_ring(ring_color)
                     .hover(|el| el.bg(accent_bg))
                     .when(is_drop_target, |el| {
                         el.bg(accent_bg).border_1().border_color(accent_bg)
@@ -768,6 +2059,27 @@ fn render_tree_items(
                                 })
                         }
                     })
+                    .map(|el| {
+                        let counts = folder_counts.get(&folder_id).copied().unwrap_or_default();
+                        // Collapsed folders show the badges of the documents they hide
+                        el.when(!is_expanded, |el| {
+                            el.child(render_badges(
+                                counts.open_tasks,
+                                counts.overdue_reminders,
+                                icon_color,
+                                overdue_color,
+                            ))
+                        })
+                        .when(counts.documents > 0, |el| {
+                            el.child(
+                                div()
+                                    .flex_shrink_0()
+                                    .text_xs()
+                                    .text_color(icon_color)
+                                    .child(counts.documents.to_string()),
+                            )
+                        })
+                    })
                     .child(
                         div()
                             .opacity(0.0)
@@ -786,6 +2098,7 @@ fn render_tree_items(
                                             .ghost()
                                             .xsmall()
                                             .cursor_pointer()
+                                            .tooltip("New document in folder")
                                             .on_click(move |_, _, cx| {
                                                 let repository =
                                                     cx.global::<RepositoryState>().documents.clone();
@@ -793,10 +2106,15 @@ fn render_tree_items(
                                                 let app_state = app_state.clone();
 
                                                 cx.spawn(async move |cx| {
+                                                    let content =
+                                                        template_picker::new_document_content(
+                                                            folder_id, "Untitled", cx,
+                                                        )
+                                                        .await?;
                                                     let new_document = DocumentModel {
                                                         id: 0,
                                                         title: "Untitled".to_string(),
-                                                        content: serde_json::json!([]),
+                                                        content,
                                                         folder_id: Some(folder_id),
                                                     };
 
@@ -807,24 +2125,13 @@ fn render_tree_items(
                                                     cx.update(|cx: &mut App| {
                                                         AppSidebar::refresh_data(&this_clone, cx);
 
-                                                        cx.update_global::<DocumentState, _>(
-                                                            |state, _| {
-                                                                state.open_document_in_folder(
-                                                                    new_id,
-                                                                    "Untitled".to_string(),
-                                                                    Some(folder_id),
-                                                                );
-                                                            },
-                                                        );
-
                                                         app_state.update(cx, |app_state, cx| {
-                                                            let document_screen =
-                                                                DocumentScreen::new(
-                                                                    cx.weak_entity(),
-                                                                );
-                                                            app_state
-                                                                .navigator
-                                                                .push(document_screen, cx);
+                                                            app_state.open_document(
+                                                                new_id,
+                                                                "Untitled".to_string(),
+                                                                Some(folder_id),
+                                                                cx,
+                                                            );
                                                         });
                                                     });
 
@@ -840,41 +2147,15 @@ fn render_tree_items(
                                             .danger()
                                             .xsmall()
                                             .cursor_pointer()
+                                            .tooltip("Delete folder")
                                             .on_click(move |_, window, cx| {
-                                                let this_clone = this.clone();
-                                                let delete_name = folder_name.clone();
-
-                                                ConfirmDialog::new("Delete Folder")
-                                                    .message(format!(
-                                                        "Are you sure you want to delete \"{}\" and all its contents? This action cannot be undone.",
-                                                        delete_name
-                                                    ))
-                                                    .confirm_text("Delete")
-                                                    .cancel_text("Cancel")
-                                                    .danger()
-                                                    .on_confirm(move |window, cx| {
-                                                        let folder_repo = cx.global::<RepositoryState>().folders.clone();
-                                                        let this_for_spawn = this_clone.clone();
-
-                                                        window.push_notification(
-                                                            format!("\"{}\" has been deleted", delete_name),
-                                                            cx,
-                                                        );
-
-                                                        cx.spawn(async move |cx| {
-                                                            let _ = folder_repo.delete_folder(folder_id).await;
-
-                                                            cx.update(|cx| {
-                                                                AppSidebar::refresh_data(&this_for_spawn, cx);
-                                                            });
-
-                                                            Ok::<_, anyhow::Error>(())
-                                                        })
-                                                        .detach();
-
-                                                        true
-                                                    })
-                                                    .open(window, cx);
+                                                AppSidebar::delete_folder_with_contents(
+                                                    &this,
+                                                    folder_id,
+                                                    folder_name.clone(),
+                                                    window,
+                                                    cx,
+                                                );
                                             })
                                     }),
                             ),
@@ -897,21 +2178,18 @@ fn render_tree_items(
                                             let app_state = app_state.clone();
 
                                             cx.spawn(async move |cx| {
+                                                let content = template_picker::new_document_content(folder_id, "Untitled", cx).await?;
                                                 let new_document = DocumentModel {
                                                     id: 0,
                                                     title: "Untitled".to_string(),
-                                                    content: serde_json::json!([]),
+                                                    content,
                                                     folder_id: Some(folder_id),
                                                 };
                                                 let new_id = repository.insert_document(new_document).await?;
                                                 cx.update(|cx: &mut App| {
                                                     AppSidebar::refresh_data(&this_clone, cx);
-                                                    cx.update_global::<DocumentState, _>(|state, _| {
-                                                        state.open_document_in_folder(new_id, "Untitled".to_string(), Some(folder_id));
-                                                    });
                                                     app_state.update(cx, |app_state, cx| {
-                                                        let document_screen = DocumentScreen::new(cx.weak_entity());
-                                                        app_state.navigator.push(document_screen, cx);
+                                                        app_state.open_document(new_id, "Untitled".to_string(), Some(folder_id), cx);
                                                     });
                                                 });
                                                 Ok::<_, anyhow::Error>(())
@@ -940,6 +2218,32 @@ fn render_tree_items(
                                         }
                                     }),
                             )
+                            .item(
+                                PopupMenuItem::new("Default template…")
+                                    .icon(Icon::default().path("icons/file-text.svg"))
+                                    .on_click(move |_, window, cx| {
+                                        template_picker::open_folder_default(folder_id, window, cx);
+                                    }),
+                            )
+                            .item(
+                                PopupMenuItem::new("Open as collection")
+                                    .icon(Icon::default().path("icons/layout-grid.svg"))
+                                    .on_click({
+                                        let app_state = app_state.clone();
+                                        move |_, _, cx| {
+                                            app_state.update(cx, |app_state, cx| {
+                                                app_state.open_collection(folder_id, cx);
+                                            });
+                                        }
+                                    }),
+                            )
+                            .item(
+                                PopupMenuItem::new("Publish as site…")
+                                    .icon(Icon::default().path("icons/app-window.svg"))
+                                    .on_click(move |_, window, cx| {
+                                        publish_folder_as_site(folder_id, window, cx);
+                                    }),
+                            )
                             .separator()
                             .item(
                                 PopupMenuItem::new("Rename")
@@ -962,30 +2266,13 @@ fn render_tree_items(
                                         let this = this.clone();
                                         let folder_name = folder_name.clone();
                                         move |_, window, cx| {
-                                            let this_clone = this.clone();
-                                            let name = folder_name.clone();
-
-                                            ConfirmDialog::new("Delete Folder")
-                                                .message(format!(
-                                                    "Are you sure you want to delete \"{}\" and all its contents? This action cannot be undone.",
-                                                    name
-                                                ))
-                                                .confirm_text("Delete")
-                                                .cancel_text("Cancel")
-                                                .danger()
-                                                .on_confirm(move |window, cx| {
-                                                    let folder_repo = cx.global::<RepositoryState>().folders.clone();
-                                                    let this_spawn = this_clone.clone();
-                                                    let name = name.clone();
-                                                    window.push_notification(format!("\"{}\" has been deleted", name), cx);
-                                                    cx.spawn(async move |cx| {
-                                                        let _ = folder_repo.delete_folder(folder_id).await;
-                                                        cx.update(|cx| { AppSidebar::refresh_data(&this_spawn, cx); });
-                                                        Ok::<_, anyhow::Error>(())
-                                                    }).detach();
-                                                    true
-                                                })
-                                                .open(window, cx);
+                                            AppSidebar::delete_folder_with_contents(
+                                                &this,
+                                                folder_id,
+                                                folder_name.clone(),
+                                                window,
+                                                cx,
+                                            );
                                         }
                                     }),
                             )
@@ -1013,7 +2300,15 @@ fn render_tree_items(
                                                     let name = name.clone();
                                                     window.push_notification(format!("\"{}\" has been deleted", name), cx);
                                                     cx.spawn(async move |cx| {
-                                                        let _ = folder_repo.delete_folder_keep_children(folder_id).await;
+                                                        if let Err(e) = folder_repo.delete_folder_keep_children(folder_id).await {
+                                                            let folder_repo = folder_repo.clone();
+                                                            AppError::new(ErrorKind::Storage, "Failed to delete a folder", &e)
+                                                                .with_retry_operation(move || {
+                                                                    let folder_repo = folder_repo.clone();
+                                                                    async move { folder_repo.delete_folder_keep_children(folder_id).await }
+                                                                })
+                                                                .report();
+                                                        }
                                                         cx.update(|cx| { AppSidebar::refresh_data(&this_spawn, cx); });
                                                         Ok::<_, anyhow::Error>(())
                                                     }).detach();
@@ -1034,6 +2329,12 @@ fn render_tree_items(
                         children,
                         depth + 1,
                         expanded_folders,
+                        dirty_documents,
+                        unsaved_documents,
+                        selected_documents,
+                        document_tags,
+                        badges,
+                        folder_counts,
                         drop_target_folder,
                         editing_item,
                         rename_input,
@@ -1043,6 +2344,9 @@ fn render_tree_items(
                         item_text_color,
                         icon_color,
                         accent_bg,
+                        dirty_color,
+                        overdue_color,
+                        ring_color,
                     );
                     elements.extend(child_elements);
                 }
@@ -1052,7 +2356,10 @@ fn render_tree_items(
                 let document_title = document.title.clone();
                 let delete_title = document.title.clone();
                 let document_folder_id = document.folder_id;
-                let this_clone = this.clone();
+                let is_dirty = dirty_documents.contains(&document_id);
+                let is_unsaved = unsaved_documents.contains(&document_id);
+                let is_selected = selected_documents.contains(&document_id);
+                let tags = document_tags.get(&document_id).cloned().unwrap_or_default();
                 let app_state_clone = app_state.clone();
 
                 let drag_title = document_title.clone();
@@ -1067,7 +2374,10 @@ fn render_tree_items(
                     .items_center()
                     .rounded_md()
                     .cursor_pointer()
+                    .focus_ring(ring_color)
+                    .when(is_selected, |el| el.bg(accent_bg))
                     .hover(|el| el.bg(accent_bg))
+                    .tooltip(DocumentPreview::tooltip(document_id))
                     .on_drag(
                         DraggableDocument { id: document_id },
                         move |_, _, _, cx| {
@@ -1077,21 +2387,25 @@ fn render_tree_items(
                         },
                     )
                     .on_click({
+                        let this = this.clone();
                         let document_title = document_title.clone();
                         let app_state = app_state_clone.clone();
-                        move |_, _, cx| {
-                            cx.update_global::<DocumentState, _>(|state, _| {
-                                state.open_document_in_folder(
+                        move |_, window, cx| {
+                            let open = this.update(cx, |state, cx| {
+                                state.select_on_click(document_id, window, cx)
+                            });
+                            if !open {
+                                return;
+                            }
+
+                            app_state.update(cx, |app_state, cx| {
+                                app_state.open_document(
                                     document_id,
                                     document_title.clone(),
                                     document_folder_id,
+                                    cx,
                                 );
                             });
-
-                            app_state.update(cx, |app_state, cx| {
-                                let document_screen = DocumentScreen::new(cx.weak_entity());
-                                app_state.navigator.push(document_screen, cx);
-                            });
                         }
                     })
                     .child(
@@ -1144,6 +2458,50 @@ fn render_tree_items(
                                 })
                         }
                     })
+                    .when_some(tags.first(), |el, tag| {
+                        let label = match tags.len() {
+                            1 => format!("#{}", tag),
+                            count => format!("#{} +{}", tag, count - 1),
+                        };
+                        el.child(
+                            div()
+                                .flex_shrink_0()
+                                .max_w(px(80.))
+                                .overflow_hidden()
+                                .text_ellipsis()
+                                .text_xs()
+                                .text_color(icon_color)
+                                .child(label),
+                        )
+                    })
+                    .child(render_badges(
+                        badges.open_tasks.get(&document_id).copied().unwrap_or_default(),
+                        badges
+                            .overdue_reminders
+                            .get(&document_id)
+                            .copied()
+                            .unwrap_or_default(),
+                        icon_color,
+                        overdue_color,
+                    ))
+                    .when(is_unsaved, |el| {
+                        el.child(
+                            div()
+                                .flex_shrink_0()
+                                .size_1p5()
+                                .rounded_full()
+                                .bg(icon_color),
+                        )
+                    })
+                    .when(is_dirty, |el| {
+                        el.child(
+                            div()
+                                .flex_shrink_0()
+                                .size_1p5()
+                                .rounded_full()
+                                .bg(dirty_color),
+                        )
+                    })
                     .child(
                         div()
                             .opacity(0.0)
@@ -1163,6 +2521,7 @@ fn render_tree_items(
                                             .ghost()
                                             .xsmall()
                                             .cursor_pointer()
+                                            .tooltip("Move to folder")
                                             .dropdown_menu(move |menu, _, _| {
                                                 let mut menu = menu.min_w(px(180.));
 
@@ -1247,48 +2606,26 @@ fn render_tree_items(
                                             .danger()
                                             .xsmall()
                                             .cursor_pointer()
+                                            .tooltip("Delete document")
                                             .on_click({
-                                                let this_clone = this_clone.clone();
                                                 move |_, window, cx| {
-                                                    let this_clone = this_clone.clone();
                                                     let delete_title = delete_title.clone();
 
                                                     ConfirmDialog::new("Delete Page")
                                                         .message(format!(
-                                                            "Are you sure you want to delete \"{}\"? This action cannot be undone.",
+                                                            "Are you sure you want to delete \"{}\"?",
                                                             delete_title
                                                         ))
                                                         .confirm_text("Delete")
                                                         .cancel_text("Cancel")
                                                         .danger()
                                                         .on_confirm(move |window, cx| {
-                                                            let repository = cx.global::<RepositoryState>().documents.clone();
-                                                            let this_for_spawn = this_clone.clone();
-                                                            let deleted_title = delete_title.clone();
-
-                                                            cx.update_global::<DocumentState, _>(|state, _| {
-                                                                state.remove_document(document_id);
-                                                                if state.current_opened_document == Some(document_id) {
-                                                                    state.current_opened_document = None;
-                                                                }
-                                                            });
-
-                                                            window.push_notification(
-                                                                format!("\"{}\" has been deleted", deleted_title),
+                                                            DeletionState::delete_documents(
+                                                                vec![document_id],
+                                                                format!("\"{}\" has been deleted", delete_title),
+                                                                window,
                                                                 cx,
                                                             );
-
-                                                            cx.spawn(async move |cx| {
-                                                                let _ = repository.delete_document(document_id).await;
-
-                                                                cx.update(|cx| {
-                                                                    AppSidebar::refresh_data(&this_for_spawn, cx);
-                                                                });
-
-                                                                Ok::<_, anyhow::Error>(())
-                                                            })
-                                                            .detach();
-
                                                             true
                                                         })
                                                         .open(window, cx);
@@ -1301,7 +2638,6 @@ fn render_tree_items(
                         let this = this.clone();
                         let doc_title = document_title.clone();
                         let delete_title2 = document.title.clone();
-                        let this_clone2 = this_clone.clone();
                         move |menu, _window, _cx| {
                             menu.item(
                                 PopupMenuItem::new("Rename")
@@ -1316,50 +2652,77 @@ fn render_tree_items(
                                         }
                                     }),
                             )
+                            .item(
+                                PopupMenuItem::new("Open in new window")
+                                    .icon(Icon::default().path("icons/app-window.svg"))
+                                    .on_click({
+                                        let title = doc_title.clone();
+                                        move |_, window, cx| {
+                                            main_window::show(
+                                                Some((document_id, title.clone(), document_folder_id)),
+                                                Some(window.bounds()),
+                                                cx,
+                                            );
+                                        }
+                                    }),
+                            )
+                            .item(
+                                PopupMenuItem::new("Merge into…")
+                                    .icon(Icon::default().path("icons/file-text.svg"))
+                                    .on_click({
+                                        let this = this.clone();
+                                        let title = doc_title.clone();
+                                        move |_, window, cx| {
+                                            AppSidebar::merge_document(&this, document_id, title.clone(), window, cx);
+                                        }
+                                    }),
+                            )
+                            .item(
+                                PopupMenuItem::new("Export as Word (.docx)")
+                                    .icon(Icon::default().path("icons/download.svg"))
+                                    .on_click(move |_, window, cx| {
+                                        export_document_as_docx(document_id, window, cx);
+                                    }),
+                            )
+                            .when(is_dirty, |menu| {
+                                let diff_title = doc_title.clone();
+                                menu.item(
+                                    PopupMenuItem::new("Show changes")
+                                        .icon(Icon::default().path("icons/file-diff.svg"))
+                                        .on_click(move |_, window, cx| {
+                                            vault_git::show_document_diff(
+                                                document_id,
+                                                diff_title.clone(),
+                                                window,
+                                                cx,
+                                            );
+                                        }),
+                                )
+                            })
                             .separator()
                             .item(
                                 PopupMenuItem::new("Delete")
                                     .icon(Icon::default().path("icons/trash-2.svg"))
                                     .on_click({
-                                        let this_clone = this_clone2.clone();
                                         let delete_title = delete_title2.clone();
                                         move |_, window, cx| {
-                                            let this_clone = this_clone.clone();
                                             let delete_title = delete_title.clone();
 
                                             ConfirmDialog::new("Delete Page")
                                                 .message(format!(
-                                                    "Are you sure you want to delete \"{}\"? This action cannot be undone.",
+                                                    "Are you sure you want to delete \"{}\"?",
                                                     delete_title
                                                 ))
                                                 .confirm_text("Delete")
                                                 .cancel_text("Cancel")
                                                 .danger()
                                                 .on_confirm(move |window, cx| {
-                                                    let repository = cx.global::<RepositoryState>().documents.clone();
-                                                    let this_for_spawn = this_clone.clone();
-                                                    let deleted_title = delete_title.clone();
-
-                                                    cx.update_global::<DocumentState, _>(|state, _| {
-                                                        state.remove_document(document_id);
-                                                        if state.current_opened_document == Some(document_id) {
-                                                            state.current_opened_document = None;
-                                                        }
-                                                    });
-
-                                                    window.push_notification(
-                                                        format!("\"{}\" has been deleted", deleted_title),
+                                                    DeletionState::delete_documents(
+                                                        vec![document_id],
+                                                        format!("\"{}\" has been deleted", delete_title),
+                                                        window,
                                                         cx,
                                                     );
-
-                                                    cx.spawn(async move |cx| {
-                                                        let _ = repository.delete_document(document_id).await;
-                                                        cx.update(|cx| {
-                                                            AppSidebar::refresh_data(&this_for_spawn, cx);
-                                                        });
-                                                        Ok::<_, anyhow::Error>(())
-                                                    }).detach();
-
                                                     true
                                                 })
                                                 .open(window, cx);
@@ -1376,3 +2739,229 @@ fn render_tree_items(
 
     elements
 }
+
+/// Blocks of a document followed by a heading with the title of another one and its blocks.
+fn merged_content(content: &serde_json::Value, merged: &DocumentModel) -> serde_json::Value {
+    let heading = HeadingNodeData::new(
+        Utils::generate_uuid(),
+        "heading".to_string(),
+        HeadingMetadata {
+            content: merged.title.clone().into(),
+            level: 2,
+        },
+    );
+
+    content
+        .as_array()
+        .into_iter()
+        .flatten()
+        .cloned()
+        .chain(serde_json::to_value(heading).ok())
+        .chain(merged.content.as_array().into_iter().flatten().cloned())
+        .collect()
+}
+
+/// Asks for a directory and writes the folders and documents there as an OPML outline.
+pub fn export_folder_tree(window: &mut Window, cx: &mut App) {
+    let Some(repositories) = cx.try_global::<RepositoryState>() else {
+        return;
+    };
+    let documents = repositories.documents.clone();
+    let folders = repositories.folders.clone();
+
+    let paths = cx.prompt_for_paths(PathPromptOptions {
+        files: false,
+        directories: true,
+        multiple: false,
+        prompt: Some("Export here".into()),
+    });
+    let window_handle = window.window_handle();
+
+    cx.spawn(async move |cx| {
+        let Ok(Ok(Some(paths))) = paths.await else {
+            return Ok(());
+        };
+        let Some(directory) = paths.into_iter().next() else {
+            return Ok(());
+        };
+
+        let result = async {
+            let folders = folders.get_folders().await?;
+            let documents = documents.get_documents().await?;
+            let path = file_vault::unique_path(&directory, "remindr", Some("opml"));
+            std::fs::write(&path, opml::folder_tree(&folders, &documents))?;
+            Ok::<_, anyhow::Error>(path)
+        }
+        .await;
+
+        cx.update_window(window_handle, |_, window, cx| match result {
+            Ok(path) => {
+                Toast::success("Folder tree exported")
+                    .action("Show", move |_, cx| cx.reveal_path(&path))
+                    .show(window, cx);
+            }
+            Err(e) => {
+                tracing::error!("failed to export the folder tree: {:?}", e);
+                Toast::error("Failed to export the folder tree").show(window, cx);
+            }
+        })?;
+
+        Ok::<_, anyhow::Error>(())
+    })
+    .detach();
+}
+
+/// Exports the folder, its subfolders and their documents as a static HTML site, styled
+/// with the colors of the current theme, in a directory picked by the user.
+pub fn publish_folder_as_site(folder_id: i32, window: &mut Window, cx: &mut App) {
+    let Some(repositories) = cx.try_global::<RepositoryState>() else {
+        return;
+    };
+    let documents = repositories.documents.clone();
+    let folders = repositories.folders.clone();
+    let attachments = repositories.attachments.clone();
+    let tags = repositories.tags.clone();
+
+    let css = |color: Hsla| {
+        format!(
+            "hsla({:.0}, {:.1}%, {:.1}%, {:.2})",
+            color.h * 360.,
+            color.s * 100.,
+            color.l * 100.,
+            color.a
+        )
+    };
+    let theme = SiteTheme {
+        background: css(cx.theme().background),
+        foreground: css(cx.theme().foreground),
+        muted_foreground: css(cx.theme().muted_foreground),
+        accent: css(cx.theme().primary),
+        border: css(cx.theme().border),
+        font_family: format!("\"{}\"", cx.theme().font_family),
+    };
+
+    let paths = cx.prompt_for_paths(PathPromptOptions {
+        files: false,
+        directories: true,
+        multiple: false,
+        prompt: Some("Publish here".into()),
+    });
+    let window_handle = window.window_handle();
+
+    cx.spawn(async move |cx| {
+        let Ok(Ok(Some(paths))) = paths.await else {
+            return Ok(());
+        };
+        let Some(directory) = paths.into_iter().next() else {
+            return Ok(());
+        };
+
+        let result = async {
+            let folders = folders.get_folders().await?;
+            let documents = documents.get_documents().await?;
+            let mut site = Site::new(folder_id, folders, documents)
+                .ok_or_else(|| anyhow::anyhow!("The folder no longer exists"))?;
+            if let Some((template, tags)) = export_template(&tags, TemplateFormat::Html).await? {
+                site = site.with_template(template, tags);
+            }
+
+            // Files that are missing from the vault are only named in the pages
+            let mut files = HashMap::new();
+            for id in site.attachment_ids() {
+                if let Ok(path) = attachments.attachment_path(id).await {
+                    files.insert(id, path);
+                }
+            }
+
+            cx.background_executor()
+                .spawn(async move { site.write(&directory, &theme, &files) })
+                .await
+        }
+        .await;
+
+        cx.update_window(window_handle, |_, window, cx| match result {
+            Ok(path) => {
+                Toast::success("Site published")
+                    .action("Show", move |_, cx| cx.reveal_path(&path))
+                    .show(window, cx);
+            }
+            Err(e) => {
+                tracing::error!("failed to publish the folder as a site: {:?}", e);
+                Toast::error(format!("Failed to publish the site: {}", e)).show(window, cx);
+            }
+        })?;
+
+        Ok::<_, anyhow::Error>(())
+    })
+    .detach();
+}
+
+/// Export template the user wrote for the format in the config directory, with the tags
+/// of every document, none when there is no template.
+async fn export_template(
+    tags: &TagRepository,
+    format: TemplateFormat,
+) -> Result<Option<(ExportTemplate, HashMap<i32, Vec<String>>)>, anyhow::Error> {
+    let directory = Remindr::new().templates_dir()?;
+    let Some(template) = ExportTemplate::load(&directory, format)? else {
+        return Ok(None);
+    };
+
+    let mut document_tags = HashMap::<i32, Vec<String>>::new();
+    for tag in tags.get_document_tags().await? {
+        document_tags
+            .entry(tag.document_id)
+            .or_default()
+            .push(tag.tag);
+    }
+    Ok(Some((template, document_tags)))
+}
+
+/// Exports the document as a Word file in a directory picked by the user.
+pub fn export_document_as_docx(document_id: i32, window: &mut Window, cx: &mut App) {
+    let Some(repositories) = cx.try_global::<RepositoryState>() else {
+        return;
+    };
+    let documents = repositories.documents.clone();
+
+    let paths = cx.prompt_for_paths(PathPromptOptions {
+        files: false,
+        directories: true,
+        multiple: false,
+        prompt: Some("Export here".into()),
+    });
+    let window_handle = window.window_handle();
+
+    cx.spawn(async move |cx| {
+        let Ok(Ok(Some(paths))) = paths.await else {
+            return Ok(());
+        };
+        let Some(directory) = paths.into_iter().next() else {
+            return Ok(());
+        };
+
+        let result = match documents.get_document_by_id(document_id).await {
+            Ok(document) => {
+                cx.background_executor()
+                    .spawn(async move { docx::export_document(&document, &directory) })
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+
+        cx.update_window(window_handle, |_, window, cx| match result {
+            Ok(path) => {
+                Toast::success("Document exported")
+                    .action("Show", move |_, cx| cx.reveal_path(&path))
+                    .show(window, cx);
+            }
+            Err(e) => {
+                tracing::error!("failed to export the document as docx: {:?}", e);
+                Toast::error("Failed to export the document").show(window, cx);
+            }
+        })?;
+
+        Ok::<_, anyhow::Error>(())
+    })
+    .detach();
+}
@@ -5,20 +5,31 @@ use gpui::{
     SharedString, StatefulInteractiveElement, Styled, Window, div, px,
 };
 use gpui_component::{
-    ActiveTheme, Icon, Selectable, Sizable,
+    ActiveTheme, Icon, Selectable, Sizable, WindowExt,
     input::{Backspace, Escape, Input, InputEvent, InputState, MoveDown, MoveUp, Position},
     label::Label,
     popover::Popover,
 };
 use uuid::Uuid;
 
-use crate::app::{
-    components::nodes::{
-        element::{NodePayload, RemindrElement},
-        heading::data::HeadingMetadata,
-        text::data::TextMetadata,
+use crate::{
+    app::{
+        components::nodes::{
+            attachment::data::AttachmentMetadata,
+            drawing::data::DrawingMetadata,
+            element::{NodePayload, RemindrElement},
+            equation::data::EquationMetadata,
+            heading::data::HeadingMetadata,
+            list::data::ListMetadata,
+            quote::data::QuoteMetadata,
+            synced_block::data::SyncedBlockMetadata,
+            text::data::TextMetadata,
+            todo::data::TodoMetadata,
+        },
+        states::{node_state::NodeState, settings_state::Settings},
     },
-    states::{node_state::NodeState, settings_state::Settings},
+    domain::ports::AssistantProvider,
+    infrastructure::assistant::openai_provider::OpenAiProvider,
 };
 
 pub struct SlashMenuDismissEvent {
@@ -37,9 +48,20 @@ struct MenuItem {
 #[derive(Clone, Copy)]
 enum MenuAction {
     InsertText,
+    InsertHeading1,
     InsertHeading2,
     InsertHeading3,
+    InsertBulletedList,
+    InsertNumberedList,
+    InsertQuote,
+    InsertTodo,
+    InsertAttachment,
+    InsertDrawing,
+    InsertEquation,
+    InsertSyncedBlock,
     InsertDivider,
+    AssistantContinue,
+    AssistantSummarize,
 }
 
 #[derive(Clone, Copy, PartialEq)]
@@ -59,6 +81,8 @@ pub struct SlashMenu {
     search_input: Entity<InputState>,
     items: Vec<MenuItem>,
     mode: SlashMenuMode,
+    /// Text selected in the block when the menu was opened, what "Summarize" summarizes.
+    selection: Option<SharedString>,
 }
 
 impl SlashMenu {
@@ -76,6 +100,13 @@ impl SlashMenu {
                 shortcut: None,
                 action: MenuAction::InsertText,
             },
+            MenuItem {
+                id: "heading_1",
+                label: "Heading 1",
+                icon_path: "icons/heading-1.svg",
+                shortcut: Some("#"),
+                action: MenuAction::InsertHeading1,
+            },
             MenuItem {
                 id: "heading_2",
                 label: "Heading 2",
@@ -90,6 +121,62 @@ impl SlashMenu {
                 shortcut: Some("###"),
                 action: MenuAction::InsertHeading3,
             },
+            MenuItem {
+                id: "bulleted_list",
+                label: "Bulleted list",
+                icon_path: "icons/list.svg",
+                shortcut: Some("-"),
+                action: MenuAction::InsertBulletedList,
+            },
+            MenuItem {
+                id: "numbered_list",
+                label: "Numbered list",
+                icon_path: "icons/list-ordered.svg",
+                shortcut: Some("1."),
+                action: MenuAction::InsertNumberedList,
+            },
+            MenuItem {
+                id: "quote",
+                label: "Quote",
+                icon_path: "icons/text-quote.svg",
+                shortcut: Some(">"),
+                action: MenuAction::InsertQuote,
+            },
+            MenuItem {
+                id: "todo",
+                label: "To-do",
+                icon_path: "icons/square-check.svg",
+                shortcut: Some("[]"),
+                action: MenuAction::InsertTodo,
+            },
+            MenuItem {
+                id: "attachment",
+                label: "File",
+                icon_path: "icons/paperclip.svg",
+                shortcut: None,
+                action: MenuAction::InsertAttachment,
+            },
+            MenuItem {
+                id: "drawing",
+                label: "Drawing",
+                icon_path: "icons/brush.svg",
+                shortcut: None,
+                action: MenuAction::InsertDrawing,
+            },
+            MenuItem {
+                id: "equation",
+                label: "Equation",
+                icon_path: "icons/sigma.svg",
+                shortcut: None,
+                action: MenuAction::InsertEquation,
+            },
+            MenuItem {
+                id: "synced_block",
+                label: "Synced block",
+                icon_path: "icons/refresh-cw.svg",
+                shortcut: None,
+                action: MenuAction::InsertSyncedBlock,
+            },
             MenuItem {
                 id: "divider",
                 label: "Divider",
@@ -97,6 +184,20 @@ impl SlashMenu {
                 shortcut: Some("---"),
                 action: MenuAction::InsertDivider,
             },
+            MenuItem {
+                id: "assistant_continue",
+                label: "Continue writing",
+                icon_path: "icons/sparkles.svg",
+                shortcut: None,
+                action: MenuAction::AssistantContinue,
+            },
+            MenuItem {
+                id: "assistant_summarize",
+                label: "Summarize",
+                icon_path: "icons/text.svg",
+                shortcut: None,
+                action: MenuAction::AssistantSummarize,
+            },
         ];
 
         let search_input = cx.new(|cx| InputState::new(window, cx).placeholder("Search blocks..."));
@@ -122,6 +223,7 @@ impl SlashMenu {
             search_input,
             items,
             mode: SlashMenuMode::InsertAfter,
+            selection: None,
         }
     }
 
@@ -138,6 +240,10 @@ impl SlashMenu {
         self.related_id
     }
 
+    pub fn set_selection(&mut self, selection: Option<SharedString>) {
+        self.selection = selection;
+    }
+
     pub fn set_mode(&mut self, mode: SlashMenuMode) {
         self.mode = mode;
     }
@@ -162,6 +268,9 @@ impl SlashMenu {
             .try_global::<Settings>()
             .map(|s| s.editor.disabled_blocks.clone())
             .unwrap_or_default();
+        let assistant_enabled = cx
+            .try_global::<Settings>()
+            .is_some_and(|s| s.assistant.is_configured());
 
         self.items
             .iter()
@@ -170,6 +279,13 @@ impl SlashMenu {
                 if disabled_blocks.contains(&item.id.to_string()) {
                     return false;
                 }
+                if matches!(
+                    item.action,
+                    MenuAction::AssistantContinue | MenuAction::AssistantSummarize
+                ) && !assistant_enabled
+                {
+                    return false;
+                }
                 if search.is_empty() {
                     return true;
                 }
@@ -210,16 +326,68 @@ impl SlashMenu {
     pub fn confirm_selection(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         let filtered = self.filtered_items(cx);
         if let Some((_, item)) = filtered.get(self.selected_index) {
-            match item.action {
-                MenuAction::InsertText => self.insert_text(window, cx),
-                MenuAction::InsertHeading2 => self.insert_heading(2, window, cx),
-                MenuAction::InsertHeading3 => self.insert_heading(3, window, cx),
-                MenuAction::InsertDivider => self.insert_divider(window, cx),
-            }
+            self.run_action(item.action, window, cx);
         }
         self.selected_index = 0;
     }
 
+    fn run_action(&mut self, action: MenuAction, window: &mut Window, cx: &mut Context<Self>) {
+        match action {
+            MenuAction::InsertText => self.insert_text(window, cx),
+            MenuAction::InsertHeading1 => self.insert_heading(1, window, cx),
+            MenuAction::InsertHeading2 => self.insert_heading(2, window, cx),
+            MenuAction::InsertHeading3 => self.insert_heading(3, window, cx),
+            MenuAction::InsertBulletedList => self.insert_block(
+                NodePayload::List((ListMetadata::default(), true)),
+                window,
+                cx,
+            ),
+            MenuAction::InsertNumberedList => self.insert_block(
+                NodePayload::List((
+                    ListMetadata {
+                        ordered: true,
+                        ..Default::default()
+                    },
+                    true,
+                )),
+                window,
+                cx,
+            ),
+            MenuAction::InsertQuote => self.insert_block(
+                NodePayload::Quote((QuoteMetadata::default(), true)),
+                window,
+                cx,
+            ),
+            MenuAction::InsertTodo => self.insert_block(
+                NodePayload::Todo((TodoMetadata::default(), true)),
+                window,
+                cx,
+            ),
+            MenuAction::InsertAttachment => self.insert_block(
+                NodePayload::Attachment((AttachmentMetadata::default(), true)),
+                window,
+                cx,
+            ),
+            MenuAction::InsertDrawing => {
+                self.insert_block(NodePayload::Drawing(DrawingMetadata::default()), window, cx)
+            }
+            MenuAction::InsertEquation => self.insert_block(
+                NodePayload::Equation((EquationMetadata::default(), true)),
+                window,
+                cx,
+            ),
+            MenuAction::InsertSyncedBlock => self.insert_block(
+                NodePayload::SyncedBlock(SyncedBlockMetadata::default()),
+                window,
+                cx,
+            ),
+            MenuAction::InsertDivider => self.insert_divider(window, cx),
+            MenuAction::AssistantContinue | MenuAction::AssistantSummarize => {
+                self.run_assistant(action, window, cx)
+            }
+        }
+    }
+
     fn render_section_label(
         &self,
         label: &'static str,
@@ -275,12 +443,7 @@ impl SlashMenu {
             .on_mouse_down(MouseButton::Left, move |_, _, cx| {
                 cx.stop_propagation();
             })
-            .on_click(cx.listener(move |this, _, window, cx| match action {
-                MenuAction::InsertText => this.insert_text(window, cx),
-                MenuAction::InsertHeading2 => this.insert_heading(2, window, cx),
-                MenuAction::InsertHeading3 => this.insert_heading(3, window, cx),
-                MenuAction::InsertDivider => this.insert_divider(window, cx),
-            }))
+            .on_click(cx.listener(move |this, _, window, cx| this.run_action(action, window, cx)))
             .child(
                 div()
                     .flex()
@@ -398,21 +561,19 @@ impl SlashMenu {
 
     fn remove_slash(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         let current_node = self.state.read(cx).get_current_nodes(self.related_id);
-        if let Some(node) = current_node {
-            match node.element.clone() {
-                RemindrElement::Text(element) => element.update(cx, |element, cx| {
-                    element.rich_text_state.update(cx, |state, cx| {
-                        let value = self.remove_slash_command(state.value());
-                        state.set_content(value.to_string(), cx);
-                    })
-                }),
-                RemindrElement::Heading(element) => element.update(cx, |element, cx| {
+        if let Some(element) = current_node.map(|node| node.element.clone()) {
+            if let Some(rich_text) = element.rich_text_state(cx) {
+                rich_text.update(cx, |state, cx| {
+                    let value = self.remove_slash_command(state.value());
+                    state.set_content(value.to_string(), cx);
+                });
+            } else if let RemindrElement::Heading(element) = element {
+                element.update(cx, |element, cx| {
                     element.input_state.update(cx, |element, cx| {
                         let value = self.remove_slash_command(element.value());
                         element.set_value(value, window, cx);
                     })
-                }),
-                _ => {}
+                });
             }
         }
     }
@@ -423,15 +584,30 @@ impl SlashMenu {
         }
 
         self.state.update(cx, |state, cx| {
-            state.insert_node_after(
-                self.related_id,
-                &RemindrElement::create_node(
-                    NodePayload::Text((TextMetadata::default(), true)),
-                    &self.state,
-                    window,
-                    cx,
-                ),
+            let node = RemindrElement::create_node(
+                NodePayload::Text((TextMetadata::default(), true)),
+                &self.state,
+                window,
+                cx,
             );
+            state.insert_node_after(self.related_id, &node, cx);
+        });
+
+        self.open = false;
+        cx.emit(SlashMenuDismissEvent {
+            restore_focus: false,
+        });
+        cx.notify();
+    }
+
+    fn insert_block(&mut self, payload: NodePayload, window: &mut Window, cx: &mut Context<Self>) {
+        if self.mode == SlashMenuMode::Replace {
+            self.remove_slash(window, cx);
+        }
+
+        self.state.update(cx, |state, cx| {
+            let node = RemindrElement::create_node(payload, &self.state, window, cx);
+            state.insert_node_after(self.related_id, &node, cx);
         });
 
         self.open = false;
@@ -459,7 +635,7 @@ impl SlashMenu {
                     window,
                     cx,
                 );
-                state.insert_node_after(current_id, &node);
+                state.insert_node_after(current_id, &node, cx);
             });
         } else {
             // Replace mode: get current content and cursor position before replacing
@@ -496,7 +672,7 @@ impl SlashMenu {
                     });
                 }
 
-                state.replace_node(current_id, &node);
+                state.replace_node(current_id, &node, cx);
             });
         }
 
@@ -539,6 +715,134 @@ impl SlashMenu {
         }
     }
 
+    fn assistant_provider(cx: &App) -> Option<OpenAiProvider> {
+        let settings = &cx.try_global::<Settings>()?.assistant;
+        settings.is_configured().then(|| {
+            OpenAiProvider::new(
+                settings.endpoint.clone(),
+                settings.api_key.clone(),
+                settings.model.clone(),
+            )
+        })
+    }
+
+    /// Plain text of the document blocks, stopping after `until` when given.
+    fn document_text(&self, until: Option<Uuid>, cx: &mut App) -> String {
+        let nodes = self.state.read(cx).get_nodes().clone();
+        let mut paragraphs = Vec::new();
+
+        for node in nodes {
+            let data = node.element.get_data(cx);
+            if let Some(content) = data["metadata"]["content"].as_str()
+                && !content.trim().is_empty()
+            {
+                paragraphs.push(content.to_string());
+            }
+            if Some(node.id) == until {
+                break;
+            }
+        }
+
+        paragraphs.join("\n\n")
+    }
+
+    /// Streams an assistant answer into new text blocks inserted after the related node.
+    /// Each blank line in the answer starts a new block.
+    fn run_assistant(&mut self, action: MenuAction, window: &mut Window, cx: &mut Context<Self>) {
+        self.open = false;
+        cx.emit(SlashMenuDismissEvent {
+            restore_focus: false,
+        });
+        cx.notify();
+
+        let Some(provider) = Self::assistant_provider(cx) else {
+            return;
+        };
+
+        self.remove_slash(window, cx);
+
+        let related_id = self.related_id;
+        let stream = if let MenuAction::AssistantContinue = action {
+            provider.complete(self.document_text(Some(related_id), cx))
+        } else {
+            // The selection, else the block the menu was opened from, else the whole document
+            let block = self.get_current_content(cx).trim().to_string();
+            let text = match self.selection.take() {
+                Some(selection) if !selection.trim().is_empty() => selection.trim().to_string(),
+                _ if !block.is_empty() => block,
+                _ => self.document_text(None, cx),
+            };
+            provider.summarize(text)
+        };
+
+        let state = self.state.clone();
+        let node = RemindrElement::create_node(
+            NodePayload::Text((TextMetadata::default(), false)),
+            &state,
+            window,
+            cx,
+        );
+        state.update(cx, |state, cx| {
+            state.insert_node_after(related_id, &node, cx)
+        });
+
+        let RemindrElement::Text(mut target) = node.element else {
+            return;
+        };
+        let window_handle = window.window_handle();
+
+        cx.spawn(async move |_, cx| {
+            let mut text = String::new();
+
+            while let Ok(chunk) = stream.recv().await {
+                let chunk = match chunk {
+                    Ok(chunk) => chunk,
+                    Err(e) => {
+                        tracing::error!("assistant request failed: {:?}", e);
+                        let _ = cx.update_window(window_handle, |_, window, cx| {
+                            window.push_notification(format!("Assistant error: {}", e), cx);
+                        });
+                        break;
+                    }
+                };
+                text.push_str(&chunk);
+
+                let _ = cx.update_window(window_handle, |_, window, cx| {
+                    while let Some(index) = text.find("\n\n") {
+                        let paragraph = text[..index].trim().to_string();
+                        text.drain(..index + 2);
+
+                        target.update(cx, |node, cx| node.set_text(paragraph.into(), cx));
+
+                        let next = RemindrElement::create_node(
+                            NodePayload::Text((TextMetadata::default(), false)),
+                            &state,
+                            window,
+                            cx,
+                        );
+                        let target_id = target.read(cx).data.id;
+                        state.update(cx, |state, cx| {
+                            state.insert_node_after(target_id, &next, cx)
+                        });
+
+                        if let RemindrElement::Text(next) = next.element {
+                            target = next;
+                        }
+                    }
+
+                    let pending = text.trim_start().to_string();
+                    target.update(cx, |node, cx| node.set_text(pending.into(), cx));
+
+                    let target_id = target.read(cx).data.id;
+                    state.update(cx, |state, cx| state.node_changed(target_id, cx));
+                });
+            }
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
     fn insert_divider(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         if self.mode == SlashMenuMode::Replace {
             self.remove_slash(window, cx);
@@ -549,21 +853,19 @@ impl SlashMenu {
         self.state.update(cx, |state, cx| {
             let node = RemindrElement::create_node(NodePayload::Divider, &self.state, window, cx);
 
-            state.insert_node_after(self.related_id, &node);
+            state.insert_node_after(self.related_id, &node, cx);
             self.related_id = node.id;
         });
 
         // Insert a text node after the divider
         self.state.update(cx, |state, cx| {
-            state.insert_node_after(
-                self.related_id,
-                &RemindrElement::create_node(
-                    NodePayload::Text((TextMetadata::default(), true)),
-                    &self.state,
-                    window,
-                    cx,
-                ),
+            let node = RemindrElement::create_node(
+                NodePayload::Text((TextMetadata::default(), true)),
+                &self.state,
+                window,
+                cx,
             );
+            state.insert_node_after(self.related_id, &node, cx);
         });
 
         self.related_id = current_slash_menu_id;
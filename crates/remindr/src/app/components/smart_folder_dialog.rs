@@ -0,0 +1,133 @@
+use std::rc::Rc;
+
+use gpui::{
+    App, AppContext, ClickEvent, Context, Entity, IntoElement, ParentElement, Render, Styled,
+    Window, px,
+};
+use gpui_component::{
+    ActiveTheme, Sizable, StyledExt, WindowExt,
+    button::{Button, ButtonVariants},
+    input::{Input, InputEvent, InputState},
+    label::Label,
+    v_flex,
+};
+
+use crate::domain::database::smart_folder::SmartFolderModel;
+
+type SubmitCallback = Rc<dyn Fn(String, String, &mut Window, &mut App) + 'static>;
+
+/// Asks for the name and the query of a smart folder.
+pub struct SmartFolderDialog {
+    name_input: Entity<InputState>,
+    query_input: Entity<InputState>,
+    on_submit: SubmitCallback,
+}
+
+impl SmartFolderDialog {
+    /// Opens the dialog, filled with an existing smart folder when editing it.
+    pub fn open(
+        smart_folder: Option<&SmartFolderModel>,
+        on_submit: impl Fn(String, String, &mut Window, &mut App) + 'static,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let title = if smart_folder.is_some() {
+            "Edit smart folder"
+        } else {
+            "New smart folder"
+        };
+        let (name, query) = smart_folder
+            .map(|smart_folder| (smart_folder.name.clone(), smart_folder.query.clone()))
+            .unwrap_or_default();
+        let on_submit: SubmitCallback = Rc::new(on_submit);
+        let view = cx.new(|cx| SmartFolderDialog::new(name, query, on_submit, window, cx));
+
+        window.open_dialog(cx, move |dialog, _, _| {
+            let view = view.clone();
+
+            dialog
+                .w(px(400.))
+                .title(v_flex().text_sm().font_semibold().child(title))
+                .footer(move |_, _, _, _| {
+                    let view = view.clone();
+                    vec![
+                        Button::new("save-smart-folder")
+                            .small()
+                            .primary()
+                            .label("Save")
+                            .on_click(move |_: &ClickEvent, window, cx| {
+                                view.update(cx, |this, cx| this.submit(window, cx));
+                            })
+                            .into_any_element(),
+                    ]
+                })
+                .child(view.clone())
+        });
+    }
+
+    fn new(
+        name: String,
+        query: String,
+        on_submit: SubmitCallback,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let name_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("Name")
+                .default_value(name)
+        });
+        let query_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("Words, #tags, has:todos or has:date")
+                .default_value(query)
+        });
+
+        for input in [&name_input, &query_input] {
+            cx.subscribe_in(input, window, |this, _, event: &InputEvent, window, cx| {
+                if let InputEvent::PressEnter { .. } = event {
+                    this.submit(window, cx);
+                }
+            })
+            .detach();
+        }
+
+        name_input.update(cx, |input, cx| {
+            input.focus(window, cx);
+        });
+
+        Self {
+            name_input,
+            query_input,
+            on_submit,
+        }
+    }
+
+    fn submit(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let name = self.name_input.read(cx).value().trim().to_string();
+        let query = self.query_input.read(cx).value().trim().to_string();
+        if name.is_empty() || query.is_empty() {
+            return;
+        }
+
+        window.close_dialog(cx);
+        (self.on_submit)(name, query, window, cx);
+    }
+}
+
+impl Render for SmartFolderDialog {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .gap_2()
+            .child(Input::new(&self.name_input).small())
+            .child(Input::new(&self.query_input).small())
+            .child(
+                Label::new(
+                    "Documents matching every word of the query are listed, and the list \
+                     follows their changes.",
+                )
+                .text_xs()
+                .text_color(cx.theme().muted_foreground),
+            )
+    }
+}
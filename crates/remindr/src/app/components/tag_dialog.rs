@@ -0,0 +1,115 @@
+use std::rc::Rc;
+
+use gpui::{
+    App, AppContext, ClickEvent, Context, Entity, IntoElement, ParentElement, Render, Styled,
+    Window, px,
+};
+use gpui_component::{
+    ActiveTheme, Sizable, StyledExt, WindowExt,
+    button::{Button, ButtonVariants},
+    input::{Input, InputEvent, InputState},
+    label::Label,
+    v_flex,
+};
+
+type SubmitCallback = Rc<dyn Fn(String, &mut Window, &mut App) + 'static>;
+
+/// Asks for the name of a tag to add to one or more documents.
+pub struct TagDialog {
+    input: Entity<InputState>,
+    description: String,
+    on_submit: SubmitCallback,
+}
+
+impl TagDialog {
+    pub fn open(
+        description: impl Into<String>,
+        on_submit: impl Fn(String, &mut Window, &mut App) + 'static,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let description = description.into();
+        let on_submit: SubmitCallback = Rc::new(on_submit);
+        let view = cx.new(|cx| TagDialog::new(description, on_submit, window, cx));
+
+        window.open_dialog(cx, move |dialog, _, _| {
+            let view = view.clone();
+
+            dialog
+                .w(px(360.))
+                .title(v_flex().text_sm().font_semibold().child("Add tag"))
+                .footer(move |_, _, _, _| {
+                    let view = view.clone();
+                    vec![
+                        Button::new("add-tag")
+                            .small()
+                            .primary()
+                            .label("Add tag")
+                            .on_click(move |_: &ClickEvent, window, cx| {
+                                view.update(cx, |this, cx| this.submit(window, cx));
+                            })
+                            .into_any_element(),
+                    ]
+                })
+                .child(view.clone())
+        });
+    }
+
+    fn new(
+        description: String,
+        on_submit: SubmitCallback,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let input = cx.new(|cx| InputState::new(window, cx).placeholder("Tag name"));
+
+        cx.subscribe_in(&input, window, |this, _, event: &InputEvent, window, cx| {
+            if let InputEvent::PressEnter { .. } = event {
+                this.submit(window, cx);
+            }
+        })
+        .detach();
+
+        input.update(cx, |input, cx| {
+            input.focus(window, cx);
+        });
+
+        Self {
+            input,
+            description,
+            on_submit,
+        }
+    }
+
+    fn submit(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let tag = normalize_tag(&self.input.read(cx).value());
+        if tag.is_empty() {
+            return;
+        }
+
+        window.close_dialog(cx);
+        (self.on_submit)(tag, window, cx);
+    }
+}
+
+impl Render for TagDialog {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .gap_2()
+            .child(
+                Label::new(self.description.clone())
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground),
+            )
+            .child(Input::new(&self.input).small())
+    }
+}
+
+/// Tags are stored without the leading `#` and with inner whitespace replaced by dashes.
+pub fn normalize_tag(tag: &str) -> String {
+    tag.trim()
+        .trim_start_matches('#')
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join("-")
+}
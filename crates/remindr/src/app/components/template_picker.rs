@@ -0,0 +1,438 @@
+use std::rc::Rc;
+
+use anyhow::Error;
+use chrono::Local;
+use gpui::prelude::FluentBuilder;
+use gpui::{App, AsyncApp, IntoElement, ParentElement, Styled, Window, div, px};
+use gpui_component::{
+    ActiveTheme, Disableable, Sizable, StyledExt, WindowExt,
+    button::{Button, ButtonVariants},
+    h_flex,
+    label::Label,
+    scroll::ScrollableElement,
+    v_flex,
+};
+use serde_json::{Value, json};
+
+use crate::{
+    LoadingState, Utils,
+    app::{
+        date_format::DateFormat,
+        states::{
+            document_state::DocumentState, repository_state::RepositoryState, toast_state::Toast,
+        },
+    },
+    domain::{
+        database::document::{DocumentModel, expand_template},
+        ports::{DocumentRepositoryPort, FolderRepositoryPort},
+    },
+};
+
+/// Folder at the root of the sidebar whose documents are the templates.
+pub const TEMPLATES_FOLDER: &str = "Templates";
+
+/// Lists the documents of the templates folder, any of which can be inserted below the
+/// block being edited in the current document.
+pub fn open(window: &mut Window, cx: &mut App) {
+    let Some(repositories) = cx.try_global::<RepositoryState>() else {
+        return;
+    };
+    let folders = repositories.folders.clone();
+    let documents = repositories.documents.clone();
+    let window_handle = window.window_handle();
+
+    cx.spawn(async move |cx| {
+        let templates = get_templates(&folders, &documents).await?;
+
+        cx.update_window(window_handle, |_, window, cx| {
+            show_templates(templates, window, cx)
+        })?;
+        Ok::<_, anyhow::Error>(())
+    })
+    .detach();
+}
+
+/// Lists the documents of the templates folder, one of which new documents of the folder
+/// start from.
+pub fn open_folder_default(folder_id: i32, window: &mut Window, cx: &mut App) {
+    let Some(repositories) = cx.try_global::<RepositoryState>() else {
+        return;
+    };
+    let folders = repositories.folders.clone();
+    let documents = repositories.documents.clone();
+    let window_handle = window.window_handle();
+
+    cx.spawn(async move |cx| {
+        let templates = get_templates(&folders, &documents).await?;
+        let current = folders.get_template(folder_id).await?;
+
+        cx.update_window(window_handle, |_, window, cx| {
+            show_folder_templates(folder_id, templates, current, window, cx)
+        })?;
+        Ok::<_, anyhow::Error>(())
+    })
+    .detach();
+}
+
+/// Content a new document of the folder starts with: the blocks of the default template of
+/// the folder with their placeholders expanded, none when the folder has no template.
+pub async fn new_document_content(
+    folder_id: i32,
+    title: &str,
+    cx: &mut AsyncApp,
+) -> Result<Value, Error> {
+    let (folders, documents, format) = cx.update(|cx| {
+        let repositories = cx.global::<RepositoryState>();
+        (
+            repositories.folders.clone(),
+            repositories.documents.clone(),
+            DateFormat::new(cx),
+        )
+    });
+
+    let Some(template_id) = folders.get_template(folder_id).await? else {
+        return Ok(json!([]));
+    };
+    // The template may have been deleted since it was chosen
+    let Some(template) = documents.find_document(template_id).await? else {
+        return Ok(json!([]));
+    };
+    let blocks = template.content.as_array().cloned().unwrap_or_default();
+    let (blocks, _) = expand(&blocks, title.to_string(), &format);
+    Ok(Value::Array(blocks))
+}
+
+/// Documents of the templates folder at the root of the sidebar.
+async fn get_templates(
+    folders: &dyn FolderRepositoryPort,
+    documents: &dyn DocumentRepositoryPort,
+) -> Result<Vec<DocumentModel>, Error> {
+    let folder = folders.list().await?.into_iter().find(|folder| {
+        folder.parent_id.is_none() && folder.name.eq_ignore_ascii_case(TEMPLATES_FOLDER)
+    });
+    Ok(match folder {
+        Some(folder) => documents
+            .list()
+            .await?
+            .into_iter()
+            .filter(|document| document.folder_id == Some(folder.id))
+            .collect(),
+        None => Vec::new(),
+    })
+}
+
+/// Blocks of a template with their placeholders expanded and new ids, along with the index
+/// of the block holding the cursor.
+fn expand(blocks: &[Value], title: String, format: &DateFormat) -> (Vec<Value>, Option<usize>) {
+    let now = Local::now();
+    let variables = [
+        ("date", format.date(now.date_naive())),
+        ("time", format.time(now.time())),
+        ("title", title),
+    ];
+    let (mut blocks, cursor) = expand_template(blocks, &variables);
+    // The template can be inserted several times in the same document
+    for block in &mut blocks {
+        if let Some(object) = block.as_object_mut() {
+            object.insert(
+                "id".to_string(),
+                Value::String(Utils::generate_uuid().to_string()),
+            );
+        }
+    }
+    (blocks, cursor)
+}
+
+fn show_templates(templates: Vec<DocumentModel>, window: &mut Window, cx: &mut App) {
+    let templates = Rc::new(templates);
+
+    window.open_dialog(cx, move |dialog, _, cx| {
+        let muted_fg = cx.theme().muted_foreground;
+        let border = cx.theme().border;
+
+        let rows = templates
+            .iter()
+            .enumerate()
+            .map(|(ix, template)| {
+                let template = template.clone();
+
+                h_flex()
+                    .gap_2()
+                    .py_1()
+                    .border_t_1()
+                    .border_color(border)
+                    .child(
+                        div()
+                            .flex_1()
+                            .min_w_0()
+                            .child(Label::new(template.title.clone()).text_sm()),
+                    )
+                    .child(
+                        Button::new(("insert-template", ix))
+                            .xsmall()
+                            .ghost()
+                            .label("Insert")
+                            .on_click(move |_, window, cx| insert(&template, window, cx)),
+                    )
+            })
+            .collect::<Vec<_>>();
+
+        dialog
+            .w(px(420.))
+            .title(v_flex().text_sm().font_semibold().child("Insert template"))
+            .overlay_closable(true)
+            .child(
+                v_flex()
+                    .gap_2()
+                    .when(rows.is_empty(), |el| {
+                        el.child(
+                            Label::new(format!(
+                                "Documents of a \"{}\" folder at the root of the sidebar can be \
+                                 inserted as templates.",
+                                TEMPLATES_FOLDER
+                            ))
+                            .text_sm()
+                            .text_color(muted_fg),
+                        )
+                    })
+                    .when(!rows.is_empty(), |el| {
+                        el.child(
+                            v_flex()
+                                .max_h(px(360.))
+                                .overflow_y_scrollbar()
+                                .children(rows),
+                        )
+                        .child(
+                            Label::new(
+                                "{{date}}, {{time}} and {{title}} are replaced when inserting, \
+                                 and the cursor is placed at {{cursor}}.",
+                            )
+                            .text_xs()
+                            .text_color(muted_fg),
+                        )
+                    }),
+            )
+    });
+}
+
+/// Inserts the blocks of the template below the block that last had the focus in the
+/// current document of the window, with its placeholders expanded.
+fn insert(template: &DocumentModel, window: &mut Window, cx: &mut App) {
+    let window_id = window.window_handle().window_id();
+    let current = cx
+        .global::<DocumentState>()
+        .workspace(window_id)
+        .and_then(|workspace| workspace.get_current_document())
+        .and_then(|document| match &document.state {
+            LoadingState::Loaded(content) => Some((
+                content.renderer.read(cx).state.clone(),
+                document.title.clone(),
+            )),
+            _ => None,
+        });
+    let Some((state, title)) = current else {
+        Toast::error("Open a document to insert the template in").show(window, cx);
+        return;
+    };
+    let Some(blocks) = template
+        .content
+        .as_array()
+        .filter(|blocks| !blocks.is_empty())
+    else {
+        Toast::error("The template is empty").show(window, cx);
+        return;
+    };
+
+    let (blocks, cursor) = expand(blocks, title, &DateFormat::new(cx));
+
+    window.close_dialog(cx);
+    state.update(cx, |node_state, cx| {
+        let nodes = blocks
+            .iter()
+            .map(|block| node_state.parse_node(block, &state, window, cx))
+            .collect::<Vec<_>>();
+        node_state.insert_nodes_at_cursor(&nodes, cx);
+
+        let focused = cursor.and_then(|index| nodes.get(index)).or(nodes.last());
+        if let Some(node) = focused {
+            node.element.focus_end(window, cx);
+        }
+    });
+}
+
+fn show_folder_templates(
+    folder_id: i32,
+    templates: Vec<DocumentModel>,
+    current: Option<i32>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let templates = Rc::new(templates);
+
+    window.open_dialog(cx, move |dialog, _, cx| {
+        let muted_fg = cx.theme().muted_foreground;
+        let border = cx.theme().border;
+
+        let rows = templates
+            .iter()
+            .enumerate()
+            .map(|(ix, template)| {
+                let template_id = template.id;
+                let is_current = current == Some(template_id);
+
+                h_flex()
+                    .gap_2()
+                    .py_1()
+                    .border_t_1()
+                    .border_color(border)
+                    .child(
+                        div()
+                            .flex_1()
+                            .min_w_0()
+                            .child(Label::new(template.title.clone()).text_sm()),
+                    )
+                    .child(
+                        Button::new(("use-template", ix))
+                            .xsmall()
+                            .ghost()
+                            .label(if is_current { "Default" } else { "Use" })
+                            .disabled(is_current)
+                            .on_click(move |_, window, cx| {
+                                set_folder_default(folder_id, Some(template_id), window, cx)
+                            }),
+                    )
+            })
+            .collect::<Vec<_>>();
+
+        dialog
+            .w(px(420.))
+            .title(v_flex().text_sm().font_semibold().child("Default template"))
+            .overlay_closable(true)
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(
+                        Label::new("New documents of the folder start from this template.")
+                            .text_sm()
+                            .text_color(muted_fg),
+                    )
+                    .when(rows.is_empty(), |el| {
+                        el.child(
+                            Label::new(format!(
+                                "Documents of a \"{}\" folder at the root of the sidebar can be \
+                                 used as templates.",
+                                TEMPLATES_FOLDER
+                            ))
+                            .text_sm()
+                            .text_color(muted_fg),
+                        )
+                    })
+                    .when(!rows.is_empty(), |el| {
+                        el.child(
+                            v_flex()
+                                .max_h(px(360.))
+                                .overflow_y_scrollbar()
+                                .children(rows),
+                        )
+                    })
+                    .when(current.is_some(), |el| {
+                        el.child(
+                            h_flex().justify_end().child(
+                                Button::new("clear-folder-template")
+                                    .xsmall()
+                                    .ghost()
+                                    .label("Start empty")
+                                    .on_click(move |_, window, cx| {
+                                        set_folder_default(folder_id, None, window, cx)
+                                    }),
+                            ),
+                        )
+                    }),
+            )
+    });
+}
+
+fn set_folder_default(folder_id: i32, template_id: Option<i32>, window: &mut Window, cx: &mut App) {
+    window.close_dialog(cx);
+    let folders = cx.global::<RepositoryState>().folders.clone();
+    let window_handle = window.window_handle();
+
+    cx.spawn(async move |cx| {
+        let result = folders.set_template(folder_id, template_id).await;
+
+        cx.update_window(window_handle, |_, window, cx| {
+            if let Err(e) = result {
+                tracing::error!("failed to set the default template of a folder: {:?}", e);
+                Toast::error(format!("Failed to set the default template: {}", e)).show(window, cx);
+            }
+        })?;
+        Ok::<_, anyhow::Error>(())
+    })
+    .detach();
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        domain::database::folder::FolderModel,
+        infrastructure::repositories::memory::{
+            InMemoryDocumentRepository, InMemoryFolderRepository,
+        },
+    };
+
+    use super::*;
+
+    fn folder(id: i32, name: &str, parent_id: Option<i32>) -> FolderModel {
+        FolderModel {
+            id,
+            name: name.to_string(),
+            parent_id,
+        }
+    }
+
+    fn document(id: i32, title: &str, folder_id: Option<i32>) -> DocumentModel {
+        DocumentModel {
+            id,
+            title: title.to_string(),
+            content: json!([]),
+            folder_id,
+        }
+    }
+
+    fn titles(documents: Vec<DocumentModel>) -> Vec<String> {
+        documents
+            .into_iter()
+            .map(|document| document.title)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn templates_are_the_documents_of_the_root_templates_folder() {
+        let folders = InMemoryFolderRepository::with_folders(vec![
+            folder(1, "Work", None),
+            folder(2, "Templates", Some(1)),
+            folder(3, "templates", None),
+        ]);
+        let documents = InMemoryDocumentRepository::with_documents(vec![
+            document(1, "Meeting", Some(3)),
+            document(2, "Not a template", Some(2)),
+            document(3, "Weekly review", Some(3)),
+            document(4, "Inbox", None),
+        ]);
+
+        let templates = get_templates(&folders, &documents).await.unwrap();
+
+        assert_eq!(titles(templates), vec!["Meeting", "Weekly review"]);
+    }
+
+    #[tokio::test]
+    async fn no_templates_without_a_templates_folder() {
+        let folders = InMemoryFolderRepository::with_folders(vec![folder(1, "Work", None)]);
+        let documents =
+            InMemoryDocumentRepository::with_documents(vec![document(1, "Meeting", Some(1))]);
+
+        let templates = get_templates(&folders, &documents).await.unwrap();
+
+        assert!(templates.is_empty());
+    }
+}
@@ -1,6 +1,6 @@
 use gpui::{
     BorrowAppContext, Context, InteractiveElement, IntoElement, ParentElement, Render, Styled,
-    Window, div, px, rems,
+    Window, div, prelude::FluentBuilder, px, rems,
 };
 use gpui_component::{
     ActiveTheme, Icon, IconName, Sizable,
@@ -8,12 +8,21 @@ use gpui_component::{
 };
 use std::ops::DerefMut;
 
-use crate::app::states::settings_state::{Settings, ThemeMode};
+use crate::app::{
+    components::notification_center::NotificationCenter,
+    states::{
+        error_state::ErrorState,
+        settings_state::{Settings, ThemeMode},
+    },
+};
 
 pub struct TitleBar;
 
 impl TitleBar {
-    pub fn new(_cx: &mut Context<Self>) -> Self {
+    pub fn new(cx: &mut Context<Self>) -> Self {
+        cx.observe_global::<ErrorState>(|_, cx| cx.notify())
+            .detach();
+
         Self
     }
 
@@ -43,6 +52,11 @@ impl Render for TitleBar {
             ),
         };
 
+        let error_count = cx
+            .try_global::<ErrorState>()
+            .map(|state| state.errors().count())
+            .unwrap_or(0);
+
         div()
             .id("title-bar")
             .w_full()
@@ -63,14 +77,34 @@ impl Render for TitleBar {
                     .text_sm(),
             )
             .child(
-                Button::new("theme-toggle")
-                    .icon(icon)
-                    .ghost()
-                    .small()
-                    .tooltip(tooltip_text)
-                    .on_click(cx.listener(|this, _, window, cx| {
-                        this.cycle_theme_mode(window, cx);
-                    })),
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_1()
+                    .when(error_count > 0, |this| {
+                        this.child(
+                            Button::new("notification-center")
+                                .icon(
+                                    Icon::new(IconName::TriangleAlert)
+                                        .text_color(cx.theme().danger),
+                                )
+                                .label(error_count.to_string())
+                                .ghost()
+                                .small()
+                                .tooltip("Notifications")
+                                .on_click(|_, window, cx| NotificationCenter::open(window, cx)),
+                        )
+                    })
+                    .child(
+                        Button::new("theme-toggle")
+                            .icon(icon)
+                            .ghost()
+                            .small()
+                            .tooltip(tooltip_text)
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.cycle_theme_mode(window, cx);
+                            })),
+                    ),
             )
     }
 }
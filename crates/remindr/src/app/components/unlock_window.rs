@@ -0,0 +1,187 @@
+use std::{path::PathBuf, rc::Rc};
+
+use gpui::{
+    App, AppContext, Bounds, Context, Entity, IntoElement, ParentElement, Render, SharedString,
+    Styled, TitlebarOptions, Window, WindowBounds, WindowKind, WindowOptions, point, px, size,
+};
+use gpui_component::{
+    ActiveTheme, Disableable, Root, Sizable, StyledExt,
+    button::{Button, ButtonVariants},
+    input::{Input, InputEvent, InputState},
+    label::Label,
+    v_flex,
+};
+
+use crate::{
+    app::states::{repository_state::RepositoryState, settings_state::Settings},
+    infrastructure::database,
+};
+
+/// Startup window asking for the passphrase of an encrypted database.
+///
+/// Once the database is unlocked, `RepositoryState` is set and `on_unlock` opens the rest of
+/// the application.
+pub struct UnlockWindow {
+    database_path: PathBuf,
+    passphrase_input: Entity<InputState>,
+    error: Option<SharedString>,
+    unlocking: bool,
+    on_unlock: Rc<dyn Fn(&mut App)>,
+}
+
+impl UnlockWindow {
+    pub fn new(
+        database_path: PathBuf,
+        on_unlock: Rc<dyn Fn(&mut App)>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let passphrase_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("Passphrase")
+                .masked(true)
+        });
+
+        cx.subscribe_in(
+            &passphrase_input,
+            window,
+            |this, _, event: &InputEvent, window, cx| {
+                if let InputEvent::PressEnter { .. } = event {
+                    this.unlock(window, cx);
+                }
+            },
+        )
+        .detach();
+
+        passphrase_input.update(cx, |input, cx| {
+            input.focus(window, cx);
+        });
+
+        Self {
+            database_path,
+            passphrase_input,
+            error: None,
+            unlocking: false,
+            on_unlock,
+        }
+    }
+
+    pub fn open(database_path: PathBuf, on_unlock: impl Fn(&mut App) + 'static, cx: &mut App) {
+        let on_unlock: Rc<dyn Fn(&mut App)> = Rc::new(on_unlock);
+        let window_bounds = Bounds::centered(None, size(px(420.), px(260.)), cx);
+
+        let options = WindowOptions {
+            window_bounds: Some(WindowBounds::Windowed(window_bounds)),
+            is_resizable: false,
+            kind: WindowKind::Normal,
+            titlebar: Some(TitlebarOptions {
+                appears_transparent: true,
+                title: Some("Unlock Remindr".into()),
+                traffic_light_position: Some(point(px(9.0), px(9.0))),
+            }),
+            ..Default::default()
+        };
+
+        let window = cx
+            .open_window(options, |window, cx| {
+                let view = cx.new(|cx| UnlockWindow::new(database_path, on_unlock, window, cx));
+                cx.new(|cx| Root::new(view, window, cx))
+            })
+            .expect("failed to open unlock window");
+
+        window
+            .update(cx, |_, window, _| {
+                window.activate_window();
+                window.set_window_title("Unlock Remindr");
+            })
+            .expect("failed to update unlock window");
+    }
+
+    fn unlock(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let passphrase = self.passphrase_input.read(cx).value().to_string();
+        if self.unlocking || passphrase.is_empty() {
+            return;
+        }
+
+        self.unlocking = true;
+        self.error = None;
+        cx.notify();
+
+        let database_path = self.database_path.clone();
+        let pool_config = cx
+            .try_global::<Settings>()
+            .map(|settings| settings.database.pool_config())
+            .unwrap_or_default();
+        let window_handle = window.window_handle();
+
+        cx.spawn(async move |this, cx| {
+            let result = database::open(&database_path, Some(&passphrase), &pool_config).await;
+
+            cx.update_window(window_handle, |_, window, cx| match result {
+                Ok(pool) => {
+                    let repositories =
+                        RepositoryState::for_settings(pool, cx.try_global::<Settings>());
+                    cx.set_global(repositories);
+                    if let Some(this) = this.upgrade() {
+                        let on_unlock = this.read(cx).on_unlock.clone();
+                        on_unlock(cx);
+                    }
+                    window.remove_window();
+                }
+                Err(e) => {
+                    tracing::error!("failed to unlock database: {:?}", e);
+                    let _ = this.update(cx, |this, cx| {
+                        this.unlocking = false;
+                        this.error = Some(SharedString::from(e.to_string()));
+                        cx.notify();
+                    });
+                }
+            })?;
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+}
+
+impl Render for UnlockWindow {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .size_full()
+            .justify_center()
+            .gap_3()
+            .px_8()
+            .bg(cx.theme().background)
+            .child(
+                Label::new("This workspace is encrypted")
+                    .text_sm()
+                    .font_semibold()
+                    .text_color(cx.theme().foreground),
+            )
+            .child(
+                Label::new("Enter your passphrase to open it.")
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground),
+            )
+            .child(Input::new(&self.passphrase_input).small())
+            .children(
+                self.error
+                    .clone()
+                    .map(|error| Label::new(error).text_xs().text_color(cx.theme().danger)),
+            )
+            .child(
+                Button::new("unlock")
+                    .primary()
+                    .small()
+                    .label(if self.unlocking {
+                        "Unlocking..."
+                    } else {
+                        "Unlock"
+                    })
+                    .disabled(self.unlocking)
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.unlock(window, cx);
+                    })),
+            )
+    }
+}
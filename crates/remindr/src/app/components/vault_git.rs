@@ -0,0 +1,138 @@
+use gpui::{App, IntoElement, ParentElement, SharedString, Styled, Window, div, px};
+use gpui_component::{
+    ActiveTheme, StyledExt, WindowExt, label::Label, scroll::ScrollableElement, v_flex,
+};
+
+use crate::app::states::{
+    error_state::{AppError, ErrorKind},
+    progress_state::ProgressTask,
+    repository_state::RepositoryState,
+    toast_state::Toast,
+};
+
+/// Commits every change of the file vault with a generated message.
+pub fn commit_changes(window: &mut Window, cx: &mut App) {
+    let Some(vault) = cx
+        .try_global::<RepositoryState>()
+        .and_then(|state| state.vault.clone())
+    else {
+        return;
+    };
+
+    let window_handle = window.window_handle();
+    let task = ProgressTask::start("Committing the vault changes", false, cx);
+
+    cx.spawn(async move |cx| {
+        let result = cx
+            .background_executor()
+            .spawn(async move { vault.commit_changes() })
+            .await;
+        cx.update(|cx| task.finish(cx));
+
+        let toast = match result {
+            Ok(Some(message)) => {
+                Toast::success(message.lines().next().unwrap_or_default().to_string())
+                    .title("Committed")
+            }
+            Ok(None) => Toast::new("Nothing to commit"),
+            Err(e) => {
+                AppError::new(ErrorKind::Sync, "Failed to commit the vault changes", &e)
+                    .with_retry(|cx| {
+                        if let Some(window) = cx.active_window() {
+                            let _ = window.update(cx, |_, window, cx| commit_changes(window, cx));
+                        }
+                    })
+                    .report();
+                return Ok(());
+            }
+        };
+
+        cx.update_window(window_handle, |_, window, cx| {
+            toast.show(window, cx);
+        })?;
+
+        Ok::<_, anyhow::Error>(())
+    })
+    .detach();
+}
+
+/// Shows the changes made to a document since the last commit.
+pub fn show_document_diff(uid: i32, title: String, window: &mut Window, cx: &mut App) {
+    let Some(vault) = cx
+        .try_global::<RepositoryState>()
+        .and_then(|state| state.vault.clone())
+    else {
+        return;
+    };
+
+    let window_handle = window.window_handle();
+
+    cx.spawn(async move |cx| {
+        let result = cx
+            .background_executor()
+            .spawn(async move { vault.document_diff(uid) })
+            .await;
+
+        cx.update_window(window_handle, |_, window, cx| match result {
+            Ok(diff) if diff.trim().is_empty() => {
+                window.push_notification(format!("\"{}\" has no uncommitted changes", title), cx);
+            }
+            Ok(diff) => open_diff_dialog(title, diff, window, cx),
+            Err(e) => {
+                tracing::error!("diff failed: {:?}", e);
+                window.push_notification(format!("Failed to compute changes: {}", e), cx);
+            }
+        })?;
+
+        Ok::<_, anyhow::Error>(())
+    })
+    .detach();
+}
+
+fn open_diff_dialog(title: String, diff: String, window: &mut Window, cx: &mut App) {
+    let lines = diff
+        .lines()
+        .map(|line| SharedString::from(line.to_string()))
+        .collect::<Vec<_>>();
+
+    window.open_dialog(cx, move |dialog, _, cx| {
+        let added = cx.theme().success;
+        let removed = cx.theme().danger;
+        let hunk = cx.theme().muted_foreground;
+        let fg = cx.theme().foreground;
+
+        dialog
+            .w(px(720.))
+            .title(
+                v_flex()
+                    .text_sm()
+                    .font_semibold()
+                    .child(format!("Changes in \"{}\"", title)),
+            )
+            .overlay_closable(true)
+            .child(
+                v_flex()
+                    .max_h(px(480.))
+                    .overflow_y_scrollbar()
+                    .font_family(cx.theme().mono_font_family.clone())
+                    .text_xs()
+                    .children(lines.iter().map(|line| {
+                        let color = if line.starts_with("+++") || line.starts_with("---") {
+                            hunk
+                        } else if line.starts_with('+') {
+                            added
+                        } else if line.starts_with('-') {
+                            removed
+                        } else if line.starts_with("@@") {
+                            hunk
+                        } else {
+                            fg
+                        };
+
+                        div()
+                            .whitespace_nowrap()
+                            .child(Label::new(line.clone()).text_color(color))
+                    })),
+            )
+    });
+}
@@ -0,0 +1,182 @@
+use chrono::{Datelike, Days, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc, Weekday};
+use gpui::App;
+
+use crate::{
+    Utils,
+    app::states::settings_state::{ClockFormat, DateOrder, DateSettings, FirstWeekday, Settings},
+};
+
+/// Regions writing the month before the day.
+const MONTH_FIRST_REGIONS: &[&str] = &["US", "PH", "PR", "FM", "MH"];
+
+/// Regions writing the year first.
+const YEAR_FIRST_REGIONS: &[&str] = &["CN", "JP", "KR", "TW", "HU", "LT", "MN", "SE"];
+
+const TWELVE_HOURS_REGIONS: &[&str] = &[
+    "US", "CA", "AU", "NZ", "PH", "IN", "PK", "BD", "EG", "SA", "MY",
+];
+
+const SUNDAY_FIRST_REGIONS: &[&str] = &[
+    "US", "CA", "JP", "BR", "IL", "PH", "MX", "KR", "TW", "IN", "ZA", "HK", "SA", "PE",
+];
+
+const SATURDAY_FIRST_REGIONS: &[&str] = &[
+    "EG", "AE", "AF", "BH", "DZ", "IQ", "IR", "JO", "KW", "LY", "OM", "QA", "SD", "SY",
+];
+
+/// How old a time can be to be described relatively, e.g. "3 days ago".
+const RELATIVE_LIMIT: i64 = 7 * 86_400;
+
+/// Formats dates following the settings, or the conventions of the system locale for the
+/// ones left to "System". Without a known locale, dates are written day first with a 24
+/// hours clock and weeks starting on Monday.
+#[derive(Clone, Copy, Debug)]
+pub struct DateFormat {
+    order: DateOrder,
+    twelve_hours: bool,
+    first_weekday: Weekday,
+}
+
+impl DateFormat {
+    pub fn new(cx: &App) -> Self {
+        let settings = cx
+            .try_global::<Settings>()
+            .map(|settings| settings.dates.clone())
+            .unwrap_or_default();
+
+        Self::from_settings(&settings)
+    }
+
+    pub fn from_settings(settings: &DateSettings) -> Self {
+        let region = system_region();
+        let in_region = |regions: &[&str]| {
+            region
+                .as_deref()
+                .is_some_and(|region| regions.contains(&region))
+        };
+
+        let order = match settings.order {
+            DateOrder::System if in_region(MONTH_FIRST_REGIONS) => DateOrder::MonthDayYear,
+            DateOrder::System if in_region(YEAR_FIRST_REGIONS) => DateOrder::YearMonthDay,
+            DateOrder::System => DateOrder::DayMonthYear,
+            order => order,
+        };
+        let twelve_hours = match settings.clock {
+            ClockFormat::System => in_region(TWELVE_HOURS_REGIONS),
+            ClockFormat::TwentyFourHours => false,
+            ClockFormat::TwelveHours => true,
+        };
+        let first_weekday = match settings.first_weekday {
+            FirstWeekday::System if in_region(SUNDAY_FIRST_REGIONS) => Weekday::Sun,
+            FirstWeekday::System if in_region(SATURDAY_FIRST_REGIONS) => Weekday::Sat,
+            FirstWeekday::System | FirstWeekday::Monday => Weekday::Mon,
+            FirstWeekday::Sunday => Weekday::Sun,
+            FirstWeekday::Saturday => Weekday::Sat,
+        };
+
+        Self {
+            order,
+            twelve_hours,
+            first_weekday,
+        }
+    }
+
+    /// First day of the week containing `date`.
+    pub fn week_start(&self, date: NaiveDate) -> NaiveDate {
+        date - Days::new(date.weekday().days_since(self.first_weekday) as u64)
+    }
+
+    /// Date without the weekday, e.g. "3 February 2025".
+    pub fn day(&self, date: NaiveDate) -> String {
+        let pattern = match self.order {
+            DateOrder::MonthDayYear => "%B %-d, %Y",
+            DateOrder::YearMonthDay => "%Y-%m-%d",
+            _ => "%-d %B %Y",
+        };
+        date.format(pattern).to_string()
+    }
+
+    /// e.g. "Mon 3 Feb 2025".
+    pub fn date(&self, date: NaiveDate) -> String {
+        let pattern = match self.order {
+            DateOrder::MonthDayYear => "%a %b %-d, %Y",
+            DateOrder::YearMonthDay => "%a %Y-%m-%d",
+            _ => "%a %-d %b %Y",
+        };
+        date.format(pattern).to_string()
+    }
+
+    /// Date without the year, e.g. "Mon 3 Feb".
+    pub fn short_date(&self, date: NaiveDate) -> String {
+        let pattern = match self.order {
+            DateOrder::MonthDayYear => "%a %b %-d",
+            DateOrder::YearMonthDay => "%a %m-%d",
+            _ => "%a %-d %b",
+        };
+        date.format(pattern).to_string()
+    }
+
+    /// e.g. "Monday 3 February 2025".
+    pub fn long_date(&self, date: NaiveDate) -> String {
+        let pattern = match self.order {
+            DateOrder::MonthDayYear => "%A, %B %-d, %Y",
+            DateOrder::YearMonthDay => "%A %Y-%m-%d",
+            _ => "%A %-d %B %Y",
+        };
+        date.format(pattern).to_string()
+    }
+
+    pub fn time(&self, time: NaiveTime) -> String {
+        let pattern = if self.twelve_hours {
+            "%-I:%M %p"
+        } else {
+            "%H:%M"
+        };
+        time.format(pattern).to_string()
+    }
+
+    /// Time with the seconds, for logs.
+    pub fn precise_time(&self, time: NaiveTime) -> String {
+        let pattern = if self.twelve_hours {
+            "%-I:%M:%S %p"
+        } else {
+            "%H:%M:%S"
+        };
+        time.format(pattern).to_string()
+    }
+
+    /// e.g. "Mon 3 Feb 2025, 14:30".
+    pub fn date_time(&self, date_time: NaiveDateTime) -> String {
+        format!(
+            "{}, {}",
+            self.date(date_time.date()),
+            self.time(date_time.time())
+        )
+    }
+
+    /// Describes a timestamp, in seconds since the epoch, relatively when it is recent,
+    /// e.g. "2 hours ago", and with its date otherwise, e.g. "on 3 Feb 2025".
+    pub fn relative(&self, timestamp: i64) -> String {
+        if Utc::now().timestamp() - timestamp < RELATIVE_LIMIT {
+            return Utils::relative_time(timestamp);
+        }
+
+        match Local.timestamp_opt(timestamp, 0).single() {
+            Some(time) => format!("on {}", self.day(time.date_naive())),
+            None => Utils::relative_time(timestamp),
+        }
+    }
+}
+
+/// Region of the locale used for times, e.g. "US" for "en_US.UTF-8".
+fn system_region() -> Option<String> {
+    ["LC_ALL", "LC_TIME", "LANG"]
+        .into_iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty())
+        .and_then(|locale| {
+            let locale = locale.split(['.', '@']).next()?.to_string();
+            let (_, region) = locale.split_once(['_', '-'])?;
+            Some(region.to_uppercase())
+        })
+}
@@ -0,0 +1,103 @@
+use std::time::Duration;
+
+use gpui::{App, AsyncApp};
+use smol::Timer;
+
+use crate::{
+    app::states::{
+        error_state::{AppError, ErrorKind},
+        repository_state::RepositoryState,
+        settings_state::Settings,
+        toast_state::Toast,
+    },
+    infrastructure::email::{Mailbox, fetch_unread, import_emails, mark_seen},
+};
+
+/// Time waited before looking at the settings again while the import is off.
+const IDLE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Imports the unread emails of the mailbox of the settings every few minutes, while the
+/// import is enabled. A failure is reported once, until an import succeeds again.
+pub fn watch(cx: &mut App) {
+    cx.spawn(async move |cx| {
+        let mut failing = false;
+
+        loop {
+            let settings = cx.update(|cx| {
+                cx.try_global::<Settings>()
+                    .map(|settings| settings.email.clone())
+                    .filter(|email| email.is_configured())
+            });
+            let Some(settings) = settings else {
+                Timer::after(IDLE_INTERVAL).await;
+                continue;
+            };
+
+            match import(settings.mailbox(), cx).await {
+                Ok(count) => {
+                    failing = false;
+                    if count > 0 {
+                        cx.update(|cx| announce(count, cx));
+                    }
+                }
+                Err(e) => {
+                    tracing::error!("failed to import emails: {:?}", e);
+                    if !failing {
+                        AppError::new(ErrorKind::Email, "Failed to import emails", &e).report();
+                    }
+                    failing = true;
+                }
+            }
+
+            Timer::after(settings.poll_interval()).await;
+        }
+    })
+    .detach();
+}
+
+/// Imports the unread emails and returns how many there were.
+async fn import(mailbox: Mailbox, cx: &mut AsyncApp) -> anyhow::Result<usize> {
+    let Some((documents, folders, attachments)) = cx.update(|cx| {
+        cx.try_global::<RepositoryState>().map(|repositories| {
+            (
+                repositories.documents.clone(),
+                repositories.folders.clone(),
+                repositories.attachments.clone(),
+            )
+        })
+    }) else {
+        return Ok(0);
+    };
+
+    let emails = {
+        let mailbox = mailbox.clone();
+        smol::unblock(move || fetch_unread(&mailbox)).await?
+    };
+    let imported = import_emails(emails, &documents, &folders, &attachments).await;
+
+    // Only the emails that were stored are marked as read, the others are fetched again
+    // by the next import
+    if !imported.uids.is_empty() {
+        let uids = imported.uids.clone();
+        smol::unblock(move || mark_seen(&mailbox, &uids)).await?;
+    }
+    match imported.error {
+        Some(e) => Err(e),
+        None => Ok(imported.document_ids.len()),
+    }
+}
+
+fn announce(count: usize, cx: &mut App) {
+    let Some(window) = cx.active_window().or_else(|| cx.windows().first().copied()) else {
+        return;
+    };
+
+    let message = if count == 1 {
+        "1 email was imported to the Email folder".to_string()
+    } else {
+        format!("{} emails were imported to the Email folder", count)
+    };
+    let _ = window.update(cx, |_, window, cx| {
+        Toast::success(message).show(window, cx);
+    });
+}
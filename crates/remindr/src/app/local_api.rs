@@ -0,0 +1,131 @@
+use gpui::App;
+#[cfg(feature = "http-api")]
+use gpui::BorrowAppContext;
+
+use crate::app::states::settings_state::Settings;
+#[cfg(feature = "http-api")]
+use crate::{
+    app::{
+        components::quick_capture::capture_blocks,
+        states::{
+            deletion_state::DeletionState,
+            document_list_state::DocumentListState,
+            document_state::{DocumentState, PersistenceState},
+            error_state::{AppError, ErrorKind},
+            repository_state::RepositoryState,
+            toast_state::Toast,
+        },
+    },
+    infrastructure::http_api::{self, ApiEvent, ApiRepositories},
+};
+
+/// Starts the local HTTP API when it is enabled in the settings, once the repositories
+/// are available. Documents changed through it are reloaded where they are opened, and
+/// clipped pages are appended to the inbox like quick captures, then announced.
+#[cfg(feature = "http-api")]
+pub fn start(cx: &mut App) {
+    let Some(settings) = cx
+        .try_global::<Settings>()
+        .map(|settings| settings.api.clone())
+        .filter(|settings| settings.is_configured())
+    else {
+        return;
+    };
+    let Some(repositories) =
+        cx.try_global::<RepositoryState>()
+            .map(|repositories| ApiRepositories {
+                documents: repositories.documents.clone(),
+                reminders: repositories.reminders.clone(),
+                tasks: repositories.tasks.clone(),
+            })
+    else {
+        return;
+    };
+
+    let (sender, events) = smol::channel::unbounded();
+    tokio::spawn(async move {
+        if let Err(e) = http_api::serve(settings.port, settings.token, repositories, sender).await {
+            tracing::error!("local API stopped: {:?}", e);
+            AppError::new(ErrorKind::Api, "Failed to start the local API", &e).report();
+        }
+    });
+
+    cx.spawn(async move |cx| {
+        while let Ok(event) = events.recv().await {
+            cx.update(|cx| handle_event(event, cx));
+        }
+    })
+    .detach();
+}
+
+#[cfg(feature = "http-api")]
+fn handle_event(event: ApiEvent, cx: &mut App) {
+    match event {
+        ApiEvent::Created(_) => DocumentListState::notify_changed(cx),
+        ApiEvent::Changed(id) => {
+            cx.update_global::<DocumentState, _>(|state, _| state.reload_documents(&[id]));
+            // The title or the folder may have changed
+            DocumentListState::notify_changed(cx);
+            cx.refresh_windows();
+        }
+        ApiEvent::Deleted { id, title, reply } => {
+            let window = cx.active_window().or_else(|| cx.windows().first().copied());
+            let deleted = window.is_some_and(|window| {
+                window
+                    .update(cx, |_, window, cx| {
+                        DeletionState::delete_documents(
+                            vec![id],
+                            format!("\"{}\" was deleted through the local API", title),
+                            window,
+                            cx,
+                        );
+                    })
+                    .is_ok()
+            });
+            if deleted {
+                let _ = reply.send(());
+            }
+        }
+        ApiEvent::Clip {
+            title,
+            blocks,
+            reply,
+        } => {
+            let capture = capture_blocks(blocks, cx);
+            cx.spawn(async move |cx| {
+                let result = capture.await;
+                if result.is_ok() {
+                    cx.update(|cx| announce_clip(&title, cx));
+                }
+                let _ = reply.send(result);
+            })
+            .detach();
+        }
+        ApiEvent::HasUnsavedEdits { id, reply } => {
+            let pending = cx.global::<DocumentState>().persistence(id) == PersistenceState::Pending;
+            let _ = reply.send(pending);
+        }
+        ApiEvent::PendingDeletions { reply } => {
+            let _ = reply.send(cx.global::<DeletionState>().pending().clone());
+        }
+    }
+}
+
+#[cfg(feature = "http-api")]
+fn announce_clip(title: &str, cx: &mut App) {
+    if let Some(window) = cx.active_window().or_else(|| cx.windows().first().copied()) {
+        let _ = window.update(cx, |_, window, cx| {
+            Toast::success(format!("\"{}\" was clipped to the Inbox", title)).show(window, cx);
+        });
+    }
+}
+
+#[cfg(not(feature = "http-api"))]
+pub fn start(cx: &mut App) {
+    if cx
+        .try_global::<Settings>()
+        .is_some_and(|settings| settings.api.enabled)
+    {
+        tracing::warn!("the local API is enabled but this build doesn't include it");
+    }
+}
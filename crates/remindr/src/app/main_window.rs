@@ -0,0 +1,92 @@
+use gpui::{
+    App, AppContext, BorrowAppContext, Bounds, Pixels, Size, TitlebarOptions, WindowBounds,
+    WindowHandle, WindowKind, WindowOptions, point, px, size,
+};
+use gpui_component::Root;
+
+use crate::app::{apply_theme, screens::AppRouter, states::window_state::WindowState};
+
+const MIN_WINDOW_SIZE: Size<Pixels> = Size {
+    width: px(640.),
+    height: px(480.),
+};
+
+/// Offset between a new window and the window it was opened from.
+const CASCADE_OFFSET: Pixels = px(28.);
+
+/// Document shown in a window when it opens: id, title and folder.
+pub type InitialDocument = (i32, String, Option<i32>);
+
+fn create_window_options(bounds: Bounds<Pixels>) -> WindowOptions {
+    WindowOptions {
+        window_bounds: Some(WindowBounds::Windowed(bounds)),
+        window_min_size: Some(MIN_WINDOW_SIZE),
+        kind: WindowKind::Normal,
+        titlebar: Some(TitlebarOptions {
+            appears_transparent: true,
+            title: Some("Remindr".into()),
+            traffic_light_position: Some(point(px(9.0), px(9.0))),
+        }),
+        ..Default::default()
+    }
+}
+
+fn compute_window_bounds(opened_from: Option<Bounds<Pixels>>, cx: &App) -> Bounds<Pixels> {
+    // New windows cascade from the one they are opened from
+    if let Some(bounds) = opened_from {
+        return Bounds::new(
+            point(
+                bounds.origin.x + CASCADE_OFFSET,
+                bounds.origin.y + CASCADE_OFFSET,
+            ),
+            bounds.size,
+        );
+    }
+
+    let mut window_size = size(MIN_WINDOW_SIZE.width, MIN_WINDOW_SIZE.height);
+    if let Some(display) = cx.primary_display() {
+        let display_size = display.bounds().size;
+        window_size.width = display_size.width * 0.85;
+        window_size.height = display_size.height * 0.85;
+    }
+    Bounds::centered(None, window_size, cx)
+}
+
+/// Opens a main window, where it was last placed when its position is remembered.
+/// Otherwise it cascades from `opened_from`, the bounds of the window it was opened from.
+pub fn open(
+    document: Option<InitialDocument>,
+    opened_from: Option<Bounds<Pixels>>,
+    cx: &mut App,
+) -> anyhow::Result<WindowHandle<Root>> {
+    let (slot, saved_bounds) = cx.update_global::<WindowState, _>(|state, _| state.claim_slot());
+    let bounds = saved_bounds.unwrap_or_else(|| compute_window_bounds(opened_from, cx));
+
+    let window = cx.open_window(create_window_options(bounds), |window, cx| {
+        let view = cx.new(|cx| AppRouter::new(slot, window, cx));
+        if let Some((id, title, folder_id)) = document {
+            view.update(cx, |router, cx| {
+                router.open_document(id, title, folder_id, cx)
+            });
+        }
+        cx.new(|cx| Root::new(view, window, cx))
+    });
+
+    if window.is_err() {
+        cx.update_global::<WindowState, _>(|state, _| state.release_slot(slot));
+    }
+    window
+}
+
+/// Opens a main window and brings it to the front.
+pub fn show(document: Option<InitialDocument>, opened_from: Option<Bounds<Pixels>>, cx: &mut App) {
+    let window = open(document, opened_from, cx).expect("failed to open window");
+
+    window
+        .update(cx, |_, window, cx| {
+            window.activate_window();
+            window.set_window_title("Remindr");
+            apply_theme(window, cx);
+        })
+        .expect("failed to update window");
+}
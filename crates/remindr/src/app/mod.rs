@@ -1,7 +1,13 @@
+pub mod backup;
 pub mod components;
+pub mod date_format;
+pub mod email_import;
+pub mod local_api;
+pub mod main_window;
 pub mod remindr;
 pub mod screens;
 pub mod states;
+pub mod tray;
 
 use gpui::{App, SharedString, Window, WindowAppearance};
 use gpui_component::theme::{Theme, ThemeRegistry};
@@ -54,9 +54,17 @@ impl Remindr {
         Ok(())
     }
 
+    pub fn database_path(&self) -> Result<PathBuf, Error> {
+        Ok(self.get_config_dir("remindr")?.join("database.sqlite"))
+    }
+
+    /// Directory of the export templates written by the user.
+    pub fn templates_dir(&self) -> Result<PathBuf, Error> {
+        Ok(self.get_config_dir("remindr")?.join("templates"))
+    }
+
     pub async fn init_default_database(&self) -> Result<PathBuf, Error> {
-        let config_path = self.get_config_dir("remindr")?;
-        let database_path = config_path.join("database.sqlite");
+        let database_path = self.database_path()?;
 
         if !database_path.exists() {
             write(&database_path, "")
@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+
+use chrono::{Days, Local, NaiveDate};
+use gpui::prelude::FluentBuilder;
+use gpui::{
+    AnyElement, App, Context, FontWeight, InteractiveElement, IntoElement, ParentElement, Render,
+    SharedString, StatefulInteractiveElement, Styled, WeakEntity, Window, actions, div, px,
+};
+use gpui_component::{ActiveTheme, h_flex, label::Label, scroll::ScrollableElement, v_flex};
+use gpui_nav::{Screen, ScreenContext};
+
+use crate::{
+    app::{
+        date_format::DateFormat,
+        states::{app_state::AppState, repository_state::RepositoryState},
+    },
+    domain::database::activity::ActivityModel,
+};
+
+actions!(activity, [OpenActivity]);
+
+/// Weeks shown on the heatmap, the current one included.
+const WEEKS: u64 = 53;
+
+/// Size of a day of the heatmap.
+const CELL_SIZE: f32 = 12.;
+
+/// Local statistics of the documents edited and blocks created each day, with a heatmap of
+/// the last year.
+pub struct ActivityScreen {
+    _ctx: ScreenContext<AppState>,
+    initialized: bool,
+    activity: HashMap<NaiveDate, ActivityModel>,
+    /// Day clicked on the heatmap, detailed below it.
+    selected_day: Option<NaiveDate>,
+}
+
+impl Screen for ActivityScreen {
+    fn id(&self) -> &'static str {
+        "Activity"
+    }
+}
+
+impl ActivityScreen {
+    pub fn new(app_state: WeakEntity<AppState>) -> Self {
+        Self {
+            _ctx: ScreenContext::new(app_state),
+            initialized: false,
+            activity: HashMap::new(),
+            selected_day: None,
+        }
+    }
+
+    fn ensure_initialized(&mut self, cx: &mut Context<Self>) {
+        if self.initialized {
+            return;
+        }
+        self.initialized = true;
+
+        let repository = cx.global::<RepositoryState>().activity.clone();
+        let since = Self::first_day(&DateFormat::new(cx));
+
+        cx.spawn(async move |this, cx| {
+            let activity = repository.get_activity(since).await?;
+
+            this.update(cx, |this, cx| {
+                this.activity = activity.into_iter().map(|day| (day.day, day)).collect();
+                cx.notify();
+            })?;
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
+    /// First day of the first week shown on the heatmap.
+    fn first_day(format: &DateFormat) -> NaiveDate {
+        let week_start = format.week_start(Local::now().date_naive());
+        week_start - Days::new((WEEKS - 1) * 7)
+    }
+
+    /// Consecutive days with some activity, ending today or yesterday.
+    fn streak(&self) -> usize {
+        let today = Local::now().date_naive();
+        let start = if self.activity.contains_key(&today) {
+            today
+        } else {
+            today - Days::new(1)
+        };
+
+        std::iter::successors(Some(start), |day| day.pred_opt())
+            .take_while(|day| self.activity.contains_key(day))
+            .count()
+    }
+
+    fn render_stat(&self, label: &'static str, value: String, cx: &Context<Self>) -> AnyElement {
+        v_flex()
+            .flex_1()
+            .gap_1()
+            .px_4()
+            .py_3()
+            .rounded(cx.theme().radius)
+            .border_1()
+            .border_color(cx.theme().border)
+            .child(
+                div()
+                    .text_xl()
+                    .font_weight(FontWeight::SEMIBOLD)
+                    .text_color(cx.theme().foreground)
+                    .child(value),
+            )
+            .child(
+                Label::new(label)
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground),
+            )
+            .into_any_element()
+    }
+
+    fn render_day(&self, day: NaiveDate, today: NaiveDate, cx: &mut Context<Self>) -> AnyElement {
+        let cell = div().size(px(CELL_SIZE)).rounded(px(2.));
+        if day > today {
+            return cell.into_any_element();
+        }
+
+        // Levels of a GitHub like heatmap, from no activity to a busy day
+        let score = self
+            .activity
+            .get(&day)
+            .map(|activity| activity.documents_edited + activity.blocks_created)
+            .unwrap_or(0);
+        let color = match score {
+            0 => cx.theme().muted,
+            1..=2 => cx.theme().primary.opacity(0.3),
+            3..=9 => cx.theme().primary.opacity(0.55),
+            10..=24 => cx.theme().primary.opacity(0.8),
+            _ => cx.theme().primary,
+        };
+
+        cell.id(SharedString::from(format!("activity-{}", day)))
+            .bg(color)
+            .cursor_pointer()
+            .when(self.selected_day == Some(day), |this| {
+                this.border_1().border_color(cx.theme().foreground)
+            })
+            .on_click(cx.listener(move |this, _, _, cx| {
+                this.selected_day = Some(day);
+                cx.notify();
+            }))
+            .into_any_element()
+    }
+
+    fn render_heatmap(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let today = Local::now().date_naive();
+        let first_day = Self::first_day(&DateFormat::new(cx));
+
+        h_flex().gap(px(3.)).children((0..WEEKS).map(|week| {
+            let week_start = first_day + Days::new(week * 7);
+            v_flex().gap(px(3.)).children(
+                week_start
+                    .iter_days()
+                    .take(7)
+                    .map(|day| self.render_day(day, today, cx))
+                    .collect::<Vec<_>>(),
+            )
+        }))
+    }
+
+    fn selected_day_details(&self, cx: &App) -> String {
+        let Some(day) = self.selected_day else {
+            return "Click a day to see its activity.".to_string();
+        };
+
+        let date = DateFormat::new(cx).date(day);
+        match self.activity.get(&day) {
+            Some(activity) => format!(
+                "{}: {} document{} edited, {} block{} created",
+                date,
+                activity.documents_edited,
+                if activity.documents_edited == 1 {
+                    ""
+                } else {
+                    "s"
+                },
+                activity.blocks_created,
+                if activity.blocks_created == 1 {
+                    ""
+                } else {
+                    "s"
+                },
+            ),
+            None => format!("{}: no activity", date),
+        }
+    }
+}
+
+impl Render for ActivityScreen {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        self.ensure_initialized(cx);
+
+        let documents_edited: i32 = self.activity.values().map(|day| day.documents_edited).sum();
+        let blocks_created: i32 = self.activity.values().map(|day| day.blocks_created).sum();
+
+        v_flex()
+            .size_full()
+            .overflow_y_scrollbar()
+            .p_6()
+            .gap_4()
+            .child(
+                div()
+                    .text_xl()
+                    .font_weight(FontWeight::SEMIBOLD)
+                    .text_color(cx.theme().foreground)
+                    .child("Activity"),
+            )
+            .child(
+                Label::new(
+                    "Recorded on this device only when documents are saved, and never sent anywhere.",
+                )
+                .text_sm()
+                .text_color(cx.theme().muted_foreground),
+            )
+            .child(
+                h_flex()
+                    .gap_3()
+                    .child(self.render_stat(
+                        "Active days",
+                        self.activity.len().to_string(),
+                        cx,
+                    ))
+                    .child(self.render_stat("Current streak", format!("{} d", self.streak()), cx))
+                    .child(self.render_stat(
+                        "Documents edited",
+                        documents_edited.to_string(),
+                        cx,
+                    ))
+                    .child(self.render_stat("Blocks created", blocks_created.to_string(), cx)),
+            )
+            .child(self.render_heatmap(cx))
+            .child(
+                Label::new(self.selected_day_details(cx))
+                    .text_sm()
+                    .text_color(cx.theme().muted_foreground),
+            )
+    }
+}
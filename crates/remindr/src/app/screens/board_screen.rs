@@ -0,0 +1,458 @@
+use std::collections::{HashMap, HashSet};
+
+use gpui::prelude::FluentBuilder;
+use gpui::{
+    AnyElement, App, AppContext, ClickEvent, Context, FontWeight, InteractiveElement, IntoElement,
+    ParentElement, Render, SharedString, StatefulInteractiveElement, Styled, WeakEntity, Window,
+    actions, div, px,
+};
+use gpui_component::{
+    ActiveTheme, Icon, Sizable,
+    button::{Button, ButtonVariants},
+    h_flex,
+    label::Label,
+    menu::{DropdownMenu as _, PopupMenuItem},
+    scroll::ScrollableElement,
+    v_flex,
+};
+use gpui_nav::{Screen, ScreenContext};
+use uuid::Uuid;
+
+use crate::{
+    app::{
+        components::nodes::todo::{
+            data::{TodoStatus, set_todo_status, todo_blocks},
+            todo_node::TodoNode,
+        },
+        states::{
+            app_state::AppState,
+            deletion_state::DeletionState,
+            error_state::{AppError, ErrorKind},
+            repository_state::RepositoryState,
+        },
+    },
+    domain::database::{document::DocumentModel, folder::FolderModel, tag::DocumentTagModel},
+};
+
+actions!(board, [OpenBoard]);
+
+/// Documents whose to-do blocks are shown on the board.
+#[derive(Clone, PartialEq)]
+enum BoardScope {
+    All,
+    /// A folder and its subfolders.
+    Folder(i32),
+    Tag(String),
+}
+
+/// To-do block displayed as a card.
+struct BoardCard {
+    document_id: i32,
+    document_title: String,
+    block_id: Uuid,
+    content: SharedString,
+    status: TodoStatus,
+}
+
+/// Drag data for a card being moved between columns.
+#[derive(Clone)]
+struct DraggableCard {
+    document_id: i32,
+    block_id: Uuid,
+}
+
+/// Ghost view displayed while dragging a card.
+struct CardGhost {
+    content: SharedString,
+}
+
+impl Render for CardGhost {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .w(px(240.))
+            .px_3()
+            .py_2()
+            .rounded(cx.theme().radius)
+            .border_1()
+            .border_color(cx.theme().primary)
+            .bg(cx.theme().popover)
+            .text_sm()
+            .text_color(cx.theme().foreground)
+            .child(self.content.clone())
+    }
+}
+
+/// Kanban board gathering the to-do blocks of a folder or a tag, one column per status.
+pub struct BoardScreen {
+    ctx: ScreenContext<AppState>,
+    initialized: bool,
+    scope: BoardScope,
+    documents: Vec<DocumentModel>,
+    folders: Vec<FolderModel>,
+    tags: Vec<DocumentTagModel>,
+}
+
+impl Screen for BoardScreen {
+    fn id(&self) -> &'static str {
+        "Board"
+    }
+}
+
+impl BoardScreen {
+    pub fn new(app_state: WeakEntity<AppState>) -> Self {
+        Self {
+            ctx: ScreenContext::new(app_state),
+            initialized: false,
+            scope: BoardScope::All,
+            documents: Vec::new(),
+            folders: Vec::new(),
+            tags: Vec::new(),
+        }
+    }
+
+    fn ensure_initialized(&mut self, cx: &mut Context<Self>) {
+        if self.initialized {
+            return;
+        }
+        self.initialized = true;
+
+        let repositories = cx.global::<RepositoryState>();
+        let documents = repositories.documents.clone();
+        let folders = repositories.folders.clone();
+        let tags = repositories.tags.clone();
+
+        cx.spawn(async move |this, cx| {
+            let mut documents = documents.get_documents().await?;
+            let folders = folders.get_folders().await?;
+            let tags = tags.get_document_tags().await?;
+
+            this.update(cx, |this, cx| {
+                let deletions = cx.global::<DeletionState>();
+                documents.retain(|document| !deletions.is_pending(document.id));
+                this.documents = documents;
+                this.folders = folders;
+                this.tags = tags;
+                cx.notify();
+            })?;
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
+    /// Documents matching the current scope.
+    fn scoped_document_ids(&self) -> Option<HashSet<i32>> {
+        match &self.scope {
+            BoardScope::All => None,
+            BoardScope::Folder(folder_id) => {
+                let mut folder_ids = HashSet::from([*folder_id]);
+                // Parents may be listed after their children, so loop until stable
+                loop {
+                    let before = folder_ids.len();
+                    for folder in &self.folders {
+                        if folder
+                            .parent_id
+                            .is_some_and(|parent_id| folder_ids.contains(&parent_id))
+                        {
+                            folder_ids.insert(folder.id);
+                        }
+                    }
+                    if folder_ids.len() == before {
+                        break;
+                    }
+                }
+
+                Some(
+                    self.documents
+                        .iter()
+                        .filter(|document| {
+                            document
+                                .folder_id
+                                .is_some_and(|folder_id| folder_ids.contains(&folder_id))
+                        })
+                        .map(|document| document.id)
+                        .collect(),
+                )
+            }
+            BoardScope::Tag(tag) => Some(
+                self.tags
+                    .iter()
+                    .filter(|document_tag| &document_tag.tag == tag)
+                    .map(|document_tag| document_tag.document_id)
+                    .collect(),
+            ),
+        }
+    }
+
+    fn cards(&self) -> Vec<BoardCard> {
+        let scoped = self.scoped_document_ids();
+
+        self.documents
+            .iter()
+            .filter(|document| {
+                scoped
+                    .as_ref()
+                    .is_none_or(|scoped| scoped.contains(&document.id))
+            })
+            .flat_map(|document| {
+                todo_blocks(&document.content).map(|todo| BoardCard {
+                    document_id: document.id,
+                    document_title: document.title.clone(),
+                    block_id: todo.id,
+                    content: todo.metadata.content,
+                    status: todo.metadata.status,
+                })
+            })
+            .collect()
+    }
+
+    fn scope_label(&self) -> String {
+        match &self.scope {
+            BoardScope::All => "All documents".to_string(),
+            BoardScope::Folder(folder_id) => self
+                .folders
+                .iter()
+                .find(|folder| folder.id == *folder_id)
+                .map(|folder| folder.name.clone())
+                .unwrap_or_else(|| "Folder".to_string()),
+            BoardScope::Tag(tag) => format!("#{}", tag),
+        }
+    }
+
+    fn open_document(&self, document_id: i32, cx: &mut Context<Self>) {
+        let Some(document) = self
+            .documents
+            .iter()
+            .find(|document| document.id == document_id)
+        else {
+            return;
+        };
+        let (title, folder_id) = (document.title.clone(), document.folder_id);
+
+        self.ctx.update(cx, |app_state, cx| {
+            app_state.open_document(document_id, title, folder_id, cx);
+        });
+    }
+
+    /// Writes the new status into the block metadata of its document.
+    fn move_card(
+        &mut self,
+        document_id: i32,
+        block_id: Uuid,
+        status: TodoStatus,
+        cx: &mut Context<Self>,
+    ) {
+        // Update the local copy first so the card lands in its column right away
+        if let Some(document) = self
+            .documents
+            .iter_mut()
+            .find(|document| document.id == document_id)
+        {
+            set_todo_status(&mut document.content, block_id, status);
+        }
+        cx.notify();
+
+        let update = TodoNode::set_document_status(document_id, block_id, status, cx);
+        cx.spawn(async move |this, cx| {
+            if let Err(e) = update.await {
+                AppError::new(ErrorKind::Storage, "Failed to move the card", &e).report();
+                // Puts the card back in the column of its stored status
+                let _ = this.update(cx, |this, cx| {
+                    this.initialized = false;
+                    cx.notify();
+                });
+            }
+        })
+        .detach();
+    }
+
+    fn render_scope_picker(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let this = cx.entity().downgrade();
+        let folders = self
+            .folders
+            .iter()
+            .map(|folder| (folder.id, folder.name.clone()))
+            .collect::<Vec<_>>();
+        let mut tags = self
+            .tags
+            .iter()
+            .map(|document_tag| document_tag.tag.clone())
+            .collect::<Vec<_>>();
+        tags.sort();
+        tags.dedup();
+
+        Button::new("board-scope")
+            .outline()
+            .small()
+            .label(self.scope_label())
+            .dropdown_menu(move |menu, _, _| {
+                let set_scope = |scope: BoardScope| {
+                    let this = this.clone();
+                    move |_: &ClickEvent, _: &mut Window, cx: &mut App| {
+                        let scope = scope.clone();
+                        let _ = this.update(cx, |this, cx| {
+                            this.scope = scope;
+                            cx.notify();
+                        });
+                    }
+                };
+
+                let mut menu = menu
+                    .min_w(px(180.))
+                    .item(PopupMenuItem::new("All documents").on_click(set_scope(BoardScope::All)));
+
+                if !folders.is_empty() {
+                    menu = menu.separator();
+                    for (id, name) in &folders {
+                        menu = menu.item(
+                            PopupMenuItem::new(name.clone())
+                                .icon(Icon::default().path("icons/folder.svg"))
+                                .on_click(set_scope(BoardScope::Folder(*id))),
+                        );
+                    }
+                }
+                if !tags.is_empty() {
+                    menu = menu.separator();
+                    for tag in &tags {
+                        menu = menu.item(
+                            PopupMenuItem::new(tag.clone())
+                                .icon(Icon::default().path("icons/tag.svg"))
+                                .on_click(set_scope(BoardScope::Tag(tag.clone()))),
+                        );
+                    }
+                }
+
+                menu
+            })
+    }
+
+    fn render_column(
+        &self,
+        status: TodoStatus,
+        cards: Vec<&BoardCard>,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        v_flex()
+            .id(SharedString::from(format!(
+                "board-column-{}",
+                status.label()
+            )))
+            .flex_1()
+            .min_w(px(220.))
+            .h_full()
+            .gap_2()
+            .p_2()
+            .rounded(cx.theme().radius)
+            .overflow_y_scrollbar()
+            .bg(cx.theme().muted.opacity(0.4))
+            .drag_over::<DraggableCard>(|this, _, _, cx| this.bg(cx.theme().accent.opacity(0.5)))
+            .on_drop(cx.listener(move |this, card: &DraggableCard, _, cx| {
+                this.move_card(card.document_id, card.block_id, status, cx);
+            }))
+            .child(
+                h_flex()
+                    .gap_2()
+                    .px_1()
+                    .child(
+                        Icon::default()
+                            .path(status.icon_path())
+                            .small()
+                            .text_color(cx.theme().muted_foreground),
+                    )
+                    .child(
+                        Label::new(format!("{} ({})", status.label(), cards.len()))
+                            .text_xs()
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .text_color(cx.theme().muted_foreground),
+                    ),
+            )
+            .children(cards.into_iter().map(|card| self.render_card(card, cx)))
+    }
+
+    fn render_card(&self, card: &BoardCard, cx: &mut Context<Self>) -> AnyElement {
+        let document_id = card.document_id;
+        let drag = DraggableCard {
+            document_id,
+            block_id: card.block_id,
+        };
+        let ghost_content = card.content.clone();
+
+        v_flex()
+            .id(SharedString::from(format!("board-card-{}", card.block_id)))
+            .w_full()
+            .gap_1()
+            .px_3()
+            .py_2()
+            .rounded(cx.theme().radius)
+            .border_1()
+            .border_color(cx.theme().border)
+            .bg(cx.theme().background)
+            .cursor_pointer()
+            .hover(|this| this.border_color(cx.theme().primary.opacity(0.5)))
+            .on_drag(drag, move |_, _, _, cx| {
+                cx.new(|_| CardGhost {
+                    content: ghost_content.clone(),
+                })
+            })
+            .on_click(cx.listener(move |this, _, _, cx| {
+                this.open_document(document_id, cx);
+            }))
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(cx.theme().foreground)
+                    .when(card.status == TodoStatus::Done, |this| this.line_through())
+                    .child(card.content.clone()),
+            )
+            .child(
+                Label::new(card.document_title.clone())
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground),
+            )
+            .into_any_element()
+    }
+}
+
+impl Render for BoardScreen {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        self.ensure_initialized(cx);
+
+        let cards = self.cards();
+        let mut columns: HashMap<TodoStatus, Vec<&BoardCard>> = HashMap::new();
+        for card in &cards {
+            columns.entry(card.status).or_default().push(card);
+        }
+
+        v_flex()
+            .size_full()
+            .p_6()
+            .gap_4()
+            .child(
+                h_flex()
+                    .w_full()
+                    .gap_2()
+                    .child(
+                        div()
+                            .text_xl()
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .text_color(cx.theme().foreground)
+                            .child("Board"),
+                    )
+                    .child(div().flex_1())
+                    .child(self.render_scope_picker(cx)),
+            )
+            .when(cards.is_empty(), |this| {
+                this.child(
+                    Label::new("No to-do blocks here yet. Type [] in a document to add one.")
+                        .text_sm()
+                        .text_color(cx.theme().muted_foreground),
+                )
+            })
+            .child(h_flex().flex_1().min_h_0().items_start().gap_3().children(
+                TodoStatus::ALL.into_iter().map(|status| {
+                    let cards = columns.remove(&status).unwrap_or_default();
+                    self.render_column(status, cards, cx)
+                }),
+            ))
+    }
+}
@@ -0,0 +1,595 @@
+use std::collections::HashMap;
+
+use chrono::{Datelike, Days, Local, Months, NaiveDate, TimeZone};
+use gpui::prelude::FluentBuilder;
+use gpui::{
+    AnyElement, App, Context, FontWeight, InteractiveElement, IntoElement, ParentElement,
+    PathPromptOptions, Render, SharedString, StatefulInteractiveElement, Styled, WeakEntity,
+    Window, actions, div, px,
+};
+use gpui_component::{
+    ActiveTheme, Icon, IconName, Selectable, Sizable,
+    button::{Button, ButtonVariants},
+    h_flex,
+    label::Label,
+    scroll::ScrollableElement,
+    v_flex,
+};
+use gpui_nav::{Screen, ScreenContext};
+use serde_json::{Value, to_value};
+
+use crate::{
+    Utils,
+    app::{
+        components::nodes::text::data::{TextMetadata, TextNodeData},
+        date_format::DateFormat,
+        states::{
+            app_state::AppState, reminder_state::ReminderState, repository_state::RepositoryState,
+            toast_state::Toast,
+        },
+    },
+    domain::database::{
+        document::DocumentModel,
+        task::{DATE_MENTION_FORMAT, DATE_MENTION_PREFIX, date_mentions},
+    },
+    infrastructure::{export::ical, vault::file_vault},
+};
+
+actions!(calendar, [OpenCalendar]);
+
+/// Number of items listed inside a day cell of the month view.
+const MONTH_CELL_ITEMS: usize = 3;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CalendarMode {
+    Month,
+    Week,
+}
+
+/// Something happening on a given day.
+#[derive(Clone)]
+enum CalendarItem {
+    /// Block mentioning the day.
+    Mention {
+        document_id: i32,
+        document_title: String,
+        text: String,
+    },
+    Reminder {
+        document_id: i32,
+        title: String,
+    },
+}
+
+impl CalendarItem {
+    fn document_id(&self) -> i32 {
+        match self {
+            CalendarItem::Mention { document_id, .. }
+            | CalendarItem::Reminder { document_id, .. } => *document_id,
+        }
+    }
+
+    fn label(&self) -> String {
+        match self {
+            CalendarItem::Mention { document_title, .. } => document_title.clone(),
+            CalendarItem::Reminder { title, .. } => title.clone(),
+        }
+    }
+
+    fn icon_path(&self) -> &'static str {
+        match self {
+            CalendarItem::Mention { .. } => "icons/file-text.svg",
+            CalendarItem::Reminder { .. } => "icons/bell.svg",
+        }
+    }
+}
+
+/// Month or week calendar gathering the dates mentioned in blocks and the reminders of
+/// every document.
+pub struct CalendarScreen {
+    ctx: ScreenContext<AppState>,
+    initialized: bool,
+    mode: CalendarMode,
+    /// Day the displayed month or week is built around.
+    anchor: NaiveDate,
+    selected: NaiveDate,
+    /// Title and folder of every document, used to open them.
+    documents: HashMap<i32, (String, Option<i32>)>,
+    mentions: HashMap<NaiveDate, Vec<CalendarItem>>,
+}
+
+impl Screen for CalendarScreen {
+    fn id(&self) -> &'static str {
+        "Calendar"
+    }
+}
+
+impl CalendarScreen {
+    pub fn new(app_state: WeakEntity<AppState>) -> Self {
+        let today = Local::now().date_naive();
+
+        Self {
+            ctx: ScreenContext::new(app_state),
+            initialized: false,
+            mode: CalendarMode::Month,
+            anchor: today,
+            selected: today,
+            documents: HashMap::new(),
+            mentions: HashMap::new(),
+        }
+    }
+
+    fn ensure_initialized(&mut self, cx: &mut Context<Self>) {
+        if self.initialized {
+            return;
+        }
+        self.initialized = true;
+
+        cx.observe_global::<ReminderState>(|_, cx| cx.notify())
+            .detach();
+        ReminderState::refresh(cx);
+        self.load_documents(cx);
+    }
+
+    fn load_documents(&mut self, cx: &mut Context<Self>) {
+        let repository = cx.global::<RepositoryState>().documents.clone();
+        cx.spawn(async move |this, cx| {
+            let documents = repository.get_documents().await?;
+
+            this.update(cx, |this, cx| {
+                this.mentions = collect_mentions(&documents);
+                this.documents = documents
+                    .into_iter()
+                    .map(|document| (document.id, (document.title, document.folder_id)))
+                    .collect();
+                cx.notify();
+            })?;
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
+    /// Mentions and reminders of a day, reminders first.
+    fn items_on(&self, date: NaiveDate, cx: &Context<Self>) -> Vec<CalendarItem> {
+        let reminders = cx
+            .global::<ReminderState>()
+            .reminders()
+            .iter()
+            .filter(|reminder| local_date(reminder.trigger_at()) == Some(date))
+            .map(|reminder| CalendarItem::Reminder {
+                document_id: reminder.document_id,
+                title: reminder.title.clone(),
+            });
+
+        reminders
+            .chain(self.mentions.get(&date).into_iter().flatten().cloned())
+            .collect()
+    }
+
+    /// Days displayed by the current mode, grouped by week.
+    fn weeks(&self, format: &DateFormat) -> Vec<Vec<NaiveDate>> {
+        let (first, last) = match self.mode {
+            CalendarMode::Month => {
+                let first = self.anchor.with_day(1).unwrap_or(self.anchor);
+                let last = first
+                    .checked_add_months(Months::new(1))
+                    .and_then(|next| next.pred_opt())
+                    .unwrap_or(first);
+                (first, last)
+            }
+            CalendarMode::Week => (self.anchor, self.anchor),
+        };
+
+        let mut day = format.week_start(first);
+        let mut weeks = Vec::new();
+        while day <= last {
+            weeks.push(day.iter_days().take(7).collect::<Vec<_>>());
+            day = day + Days::new(7);
+        }
+
+        weeks
+    }
+
+    fn shift(&mut self, forward: bool, cx: &mut Context<Self>) {
+        self.anchor = match (self.mode, forward) {
+            (CalendarMode::Month, true) => self.anchor.checked_add_months(Months::new(1)),
+            (CalendarMode::Month, false) => self.anchor.checked_sub_months(Months::new(1)),
+            (CalendarMode::Week, true) => self.anchor.checked_add_days(Days::new(7)),
+            (CalendarMode::Week, false) => self.anchor.checked_sub_days(Days::new(7)),
+        }
+        .unwrap_or(self.anchor);
+        cx.notify();
+    }
+
+    fn open_document(&self, document_id: i32, cx: &mut Context<Self>) {
+        let Some((title, folder_id)) = self.documents.get(&document_id).cloned() else {
+            return;
+        };
+
+        self.ctx.update(cx, |app_state, cx| {
+            app_state.open_document(document_id, title, folder_id, cx);
+        });
+    }
+
+    /// Creates a document mentioning the selected day and opens it.
+    fn create_dated_note(&mut self, cx: &mut Context<Self>) {
+        let date = self.selected;
+        let title = DateFormat::new(cx).long_date(date);
+        let block = TextNodeData::new(
+            Utils::generate_uuid(),
+            "text".to_string(),
+            TextMetadata {
+                content: format!(
+                    "{}{} ",
+                    DATE_MENTION_PREFIX,
+                    date.format(DATE_MENTION_FORMAT)
+                )
+                .into(),
+            },
+        );
+
+        let repository = cx.global::<RepositoryState>().documents.clone();
+        cx.spawn(async move |this, cx| {
+            let id = repository
+                .insert_document(DocumentModel {
+                    id: 0,
+                    title: title.clone(),
+                    content: Value::Array(vec![to_value(block)?]),
+                    folder_id: None,
+                })
+                .await?;
+
+            this.update(cx, |this, cx| {
+                this.documents.insert(id, (title, None));
+                this.open_document(id, cx);
+                this.load_documents(cx);
+            })?;
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
+    fn render_header(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let period = match self.mode {
+            CalendarMode::Month => self.anchor.format("%B %Y").to_string(),
+            CalendarMode::Week => {
+                let format = DateFormat::new(cx);
+                format!("Week of {}", format.day(format.week_start(self.anchor)))
+            }
+        };
+
+        h_flex()
+            .w_full()
+            .gap_2()
+            .child(
+                div()
+                    .text_xl()
+                    .font_weight(FontWeight::SEMIBOLD)
+                    .text_color(cx.theme().foreground)
+                    .child(period),
+            )
+            .child(div().flex_1())
+            .child(
+                Button::new("calendar-previous")
+                    .ghost()
+                    .small()
+                    .icon(IconName::ChevronLeft)
+                    .on_click(cx.listener(|this, _, _, cx| this.shift(false, cx))),
+            )
+            .child(
+                Button::new("calendar-today")
+                    .outline()
+                    .small()
+                    .label("Today")
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        let today = Local::now().date_naive();
+                        this.anchor = today;
+                        this.selected = today;
+                        cx.notify();
+                    })),
+            )
+            .child(
+                Button::new("calendar-next")
+                    .ghost()
+                    .small()
+                    .icon(IconName::ChevronRight)
+                    .on_click(cx.listener(|this, _, _, cx| this.shift(true, cx))),
+            )
+            .children(
+                [("Month", CalendarMode::Month), ("Week", CalendarMode::Week)].map(
+                    |(label, mode)| {
+                        Button::new(SharedString::from(format!("calendar-mode-{}", label)))
+                            .outline()
+                            .small()
+                            .label(label)
+                            .selected(self.mode == mode)
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.mode = mode;
+                                this.anchor = this.selected;
+                                cx.notify();
+                            }))
+                    },
+                ),
+            )
+            .child(
+                Button::new("calendar-export")
+                    .ghost()
+                    .small()
+                    .label("Export .ics")
+                    .on_click(|_, window, cx| export_calendar(window, cx)),
+            )
+    }
+
+    fn render_day(&self, date: NaiveDate, cx: &mut Context<Self>) -> AnyElement {
+        let items = self.items_on(date, cx);
+        let in_period = self.mode == CalendarMode::Week || date.month() == self.anchor.month();
+        let is_today = date == Local::now().date_naive();
+        let is_selected = date == self.selected;
+        let shown = match self.mode {
+            CalendarMode::Month => MONTH_CELL_ITEMS,
+            CalendarMode::Week => usize::MAX,
+        };
+
+        v_flex()
+            .id(SharedString::from(format!("calendar-day-{}", date)))
+            .flex_1()
+            .min_w_0()
+            .h(match self.mode {
+                CalendarMode::Month => px(96.),
+                CalendarMode::Week => px(280.),
+            })
+            .gap_0p5()
+            .p_1()
+            .border_1()
+            .border_color(if is_selected {
+                cx.theme().primary
+            } else {
+                cx.theme().border
+            })
+            .rounded(cx.theme().radius)
+            .overflow_hidden()
+            .cursor_pointer()
+            .when(!in_period, |this| this.opacity(0.5))
+            .hover(|this| this.bg(cx.theme().accent.opacity(0.3)))
+            .on_click(cx.listener(move |this, _, _, cx| {
+                this.selected = date;
+                cx.notify();
+            }))
+            .child(
+                Label::new(date.day().to_string())
+                    .text_xs()
+                    .when(is_today, |this| this.font_weight(FontWeight::BOLD))
+                    .text_color(if is_today {
+                        cx.theme().primary
+                    } else {
+                        cx.theme().muted_foreground
+                    }),
+            )
+            .children(items.iter().take(shown).map(|item| {
+                h_flex()
+                    .gap_1()
+                    .child(
+                        Icon::default()
+                            .path(item.icon_path())
+                            .xsmall()
+                            .text_color(cx.theme().muted_foreground),
+                    )
+                    .child(
+                        div()
+                            .text_xs()
+                            .truncate()
+                            .text_color(cx.theme().foreground)
+                            .child(item.label()),
+                    )
+            }))
+            .when(items.len() > shown, |this| {
+                this.child(
+                    Label::new(format!("+{} more", items.len() - shown))
+                        .text_xs()
+                        .text_color(cx.theme().muted_foreground),
+                )
+            })
+            .into_any_element()
+    }
+
+    fn render_selected_day(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let items = self.items_on(self.selected, cx);
+
+        v_flex()
+            .gap_2()
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        Label::new(DateFormat::new(cx).long_date(self.selected))
+                            .text_sm()
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .text_color(cx.theme().foreground),
+                    )
+                    .child(div().flex_1())
+                    .child(
+                        Button::new("calendar-new-note")
+                            .small()
+                            .primary()
+                            .icon(IconName::Plus)
+                            .label("New note for this day")
+                            .on_click(cx.listener(|this, _, _, cx| this.create_dated_note(cx))),
+                    ),
+            )
+            .when(items.is_empty(), |this| {
+                this.child(
+                    Label::new("Nothing planned")
+                        .text_xs()
+                        .text_color(cx.theme().muted_foreground),
+                )
+            })
+            .children(items.into_iter().enumerate().map(|(index, item)| {
+                let document_id = item.document_id();
+                let details = match &item {
+                    CalendarItem::Mention { text, .. } => text.clone(),
+                    CalendarItem::Reminder { .. } => "Reminder".to_string(),
+                };
+
+                h_flex()
+                    .id(("calendar-item", index))
+                    .gap_2()
+                    .px_2()
+                    .py_1()
+                    .rounded(cx.theme().radius)
+                    .cursor_pointer()
+                    .hover(|this| this.bg(cx.theme().accent.opacity(0.3)))
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        this.open_document(document_id, cx);
+                    }))
+                    .child(
+                        Icon::default()
+                            .path(item.icon_path())
+                            .small()
+                            .text_color(cx.theme().muted_foreground),
+                    )
+                    .child(
+                        v_flex()
+                            .min_w_0()
+                            .child(
+                                Label::new(item.label())
+                                    .text_sm()
+                                    .text_color(cx.theme().foreground),
+                            )
+                            .child(
+                                div()
+                                    .text_xs()
+                                    .truncate()
+                                    .text_color(cx.theme().muted_foreground)
+                                    .child(details),
+                            ),
+                    )
+            }))
+    }
+}
+
+impl Render for CalendarScreen {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        self.ensure_initialized(cx);
+
+        let weeks = self.weeks(&DateFormat::new(cx));
+        let weekdays = weeks.first().cloned().unwrap_or_default();
+
+        v_flex()
+            .size_full()
+            .overflow_y_scrollbar()
+            .p_6()
+            .gap_3()
+            .child(self.render_header(cx))
+            .child(
+                h_flex()
+                    .w_full()
+                    .gap_1()
+                    .children(weekdays.iter().map(|day| {
+                        div()
+                            .flex_1()
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(day.format("%a").to_string())
+                    })),
+            )
+            .children(weeks.into_iter().map(|week| {
+                h_flex()
+                    .w_full()
+                    .gap_1()
+                    .children(week.into_iter().map(|date| self.render_day(date, cx)))
+            }))
+            .child(self.render_selected_day(cx))
+    }
+}
+
+fn local_date(timestamp: i64) -> Option<NaiveDate> {
+    Local
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .map(|time| time.date_naive())
+}
+
+/// Dates mentioned with `@YYYY-MM-DD` in the blocks of the documents.
+fn collect_mentions(documents: &[DocumentModel]) -> HashMap<NaiveDate, Vec<CalendarItem>> {
+    let mut mentions: HashMap<NaiveDate, Vec<CalendarItem>> = HashMap::new();
+
+    for document in documents {
+        let texts = document
+            .content
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|block| block.get("metadata")?.get("content")?.as_str());
+
+        for text in texts {
+            for date in date_mentions(text) {
+                mentions
+                    .entry(date)
+                    .or_default()
+                    .push(CalendarItem::Mention {
+                        document_id: document.id,
+                        document_title: document.title.clone(),
+                        text: text.trim().to_string(),
+                    });
+            }
+        }
+    }
+
+    mentions
+}
+
+/// Writes the pending reminders and the dated tasks as an iCalendar file in a folder picked
+/// by the user, to import them in another calendar app.
+pub fn export_calendar(window: &mut Window, cx: &mut App) {
+    let Some(repositories) = cx.try_global::<RepositoryState>() else {
+        return;
+    };
+    let reminders = repositories.reminders.clone();
+    let tasks = repositories.tasks.clone();
+
+    let paths = cx.prompt_for_paths(PathPromptOptions {
+        files: false,
+        directories: true,
+        multiple: false,
+        prompt: Some("Export here".into()),
+    });
+    let window_handle = window.window_handle();
+
+    cx.spawn(async move |cx| {
+        let Ok(Ok(Some(paths))) = paths.await else {
+            return Ok(());
+        };
+        let Some(directory) = paths.into_iter().next() else {
+            return Ok(());
+        };
+
+        let result = async {
+            let reminders = reminders.get_pending_reminders().await?;
+            let tasks = tasks.get_open_tasks().await?;
+            let path = file_vault::unique_path(&directory, "remindr", Some("ics"));
+            std::fs::write(
+                &path,
+                ical::calendar(&reminders, &tasks, ReminderState::now()),
+            )?;
+            Ok::<_, anyhow::Error>(path)
+        }
+        .await;
+
+        cx.update_window(window_handle, |_, window, cx| match result {
+            Ok(path) => {
+                Toast::success("Calendar exported")
+                    .action("Show", move |_, cx| cx.reveal_path(&path))
+                    .show(window, cx);
+            }
+            Err(e) => {
+                tracing::error!("failed to export the calendar: {:?}", e);
+                Toast::error("Failed to export the calendar").show(window, cx);
+            }
+        })?;
+
+        Ok::<_, anyhow::Error>(())
+    })
+    .detach();
+}
@@ -0,0 +1,438 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use chrono::{Local, NaiveDate};
+use gpui::prelude::FluentBuilder;
+use gpui::{
+    AnyElement, App, AppContext, Context, Entity, FontWeight, InteractiveElement, IntoElement,
+    ParentElement, Render, SharedString, StatefulInteractiveElement, Styled, WeakEntity, Window,
+    div, px,
+};
+use gpui_component::{
+    ActiveTheme, Sizable,
+    button::{Button, ButtonVariants},
+    h_flex,
+    input::{Input, InputEvent, InputState},
+    label::Label,
+    menu::{DropdownMenu as _, PopupMenuItem},
+    scroll::ScrollableElement,
+    v_flex,
+};
+use gpui_nav::{Screen, ScreenContext};
+
+use crate::{
+    app::{
+        date_format::DateFormat,
+        states::{app_state::AppState, repository_state::RepositoryState},
+    },
+    domain::database::{
+        collection::{CollectionColumn, CollectionView},
+        document::DocumentModel,
+        task::{TaskModel, date_mentions},
+    },
+};
+
+/// Width of the columns other than the title.
+const COLUMN_WIDTH: f32 = 120.0;
+
+/// Document of a collection with the properties shown in its columns.
+struct CollectionRow {
+    id: i32,
+    title: String,
+    folder_id: Option<i32>,
+    tags: Vec<String>,
+    words: usize,
+    open_todos: usize,
+    /// Earliest date mentioned in the document.
+    due_date: Option<NaiveDate>,
+}
+
+impl CollectionRow {
+    fn new(document: DocumentModel, tags: Vec<String>) -> Self {
+        let texts = document
+            .content
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|block| block.get("metadata")?.get("content")?.as_str())
+            .collect::<Vec<_>>();
+
+        Self {
+            id: document.id,
+            open_todos: TaskModel::from_document(document.id, &document.content).len(),
+            title: document.title,
+            folder_id: document.folder_id,
+            tags,
+            words: texts
+                .iter()
+                .map(|text| text.split_whitespace().count())
+                .sum(),
+            due_date: texts.iter().flat_map(|text| date_mentions(text)).min(),
+        }
+    }
+
+    fn matches(&self, filter: &str) -> bool {
+        let filter = filter.trim().to_lowercase();
+        filter.is_empty()
+            || self.title.to_lowercase().contains(&filter)
+            || self
+                .tags
+                .iter()
+                .any(|tag| tag.to_lowercase().contains(&filter))
+    }
+
+    fn compare(&self, other: &Self, column: CollectionColumn) -> Ordering {
+        match column {
+            CollectionColumn::Title => self.title.to_lowercase().cmp(&other.title.to_lowercase()),
+            CollectionColumn::Tags => self.tags.cmp(&other.tags),
+            CollectionColumn::Words => self.words.cmp(&other.words),
+            CollectionColumn::Todos => self.open_todos.cmp(&other.open_todos),
+            // Documents without a date come last
+            CollectionColumn::DueDate => match (self.due_date, other.due_date) {
+                (Some(date), Some(other)) => date.cmp(&other),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            },
+        }
+    }
+}
+
+/// Documents of a folder displayed as a table of their properties, which can be sorted and
+/// filtered. The view is saved for each folder.
+pub struct CollectionScreen {
+    ctx: ScreenContext<AppState>,
+    initialized: bool,
+    folder_id: i32,
+    folder_name: String,
+    view: CollectionView,
+    rows: Vec<CollectionRow>,
+    filter_input: Option<Entity<InputState>>,
+}
+
+impl Screen for CollectionScreen {
+    fn id(&self) -> &'static str {
+        "Collection"
+    }
+}
+
+impl CollectionScreen {
+    pub fn new(app_state: WeakEntity<AppState>, folder_id: i32) -> Self {
+        Self {
+            ctx: ScreenContext::new(app_state),
+            initialized: false,
+            folder_id,
+            folder_name: String::new(),
+            view: CollectionView::default(),
+            rows: Vec::new(),
+            filter_input: None,
+        }
+    }
+
+    fn ensure_initialized(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.initialized {
+            return;
+        }
+        self.initialized = true;
+
+        let filter_input =
+            cx.new(|cx| InputState::new(window, cx).placeholder("Filter by title or tag"));
+        cx.subscribe_in(
+            &filter_input,
+            window,
+            |this, input, event: &InputEvent, _, cx| {
+                if let InputEvent::Change = event {
+                    let filter = input.read(cx).value().to_string();
+                    if filter != this.view.filter {
+                        this.view.filter = filter;
+                        this.save_view(cx);
+                    }
+                }
+            },
+        )
+        .detach();
+        self.filter_input = Some(filter_input.clone());
+
+        let repositories = cx.global::<RepositoryState>();
+        let documents = repositories.documents.clone();
+        let folders = repositories.folders.clone();
+        let tags = repositories.tags.clone();
+        let collections = repositories.collections.clone();
+        let folder_id = self.folder_id;
+        let window_handle = window.window_handle();
+
+        cx.spawn(async move |this, cx| {
+            let folder = folders.get_folder_by_id(folder_id).await?;
+            let view = collections.get_view(folder_id).await?;
+            let documents = documents.get_documents().await?;
+
+            let mut document_tags = HashMap::<i32, Vec<String>>::new();
+            for tag in tags.get_document_tags().await? {
+                document_tags
+                    .entry(tag.document_id)
+                    .or_default()
+                    .push(tag.tag);
+            }
+            let rows = documents
+                .into_iter()
+                .filter(|document| document.folder_id == Some(folder_id))
+                .map(|document| {
+                    let tags = document_tags.remove(&document.id).unwrap_or_default();
+                    CollectionRow::new(document, tags)
+                })
+                .collect::<Vec<_>>();
+
+            cx.update_window(window_handle, |_, window, cx| {
+                // The view is set first so the filter change below isn't saved back
+                let filter = view.filter.clone();
+                this.update(cx, |this, cx| {
+                    this.folder_name = folder.name;
+                    this.view = view;
+                    this.rows = rows;
+                    cx.notify();
+                })?;
+                filter_input.update(cx, |input, cx| {
+                    input.set_value(filter, window, cx);
+                });
+
+                Ok::<_, anyhow::Error>(())
+            })??;
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
+    fn save_view(&mut self, cx: &mut Context<Self>) {
+        cx.notify();
+
+        let repository = cx.global::<RepositoryState>().collections.clone();
+        let folder_id = self.folder_id;
+        let view = self.view.clone();
+        cx.spawn(async move |_, _| {
+            if let Err(e) = repository.save_view(folder_id, view).await {
+                tracing::error!("failed to save view: {:?}", e);
+            }
+        })
+        .detach();
+    }
+
+    /// Sorts by a column, or reverses the order when already sorted by it.
+    fn sort_by(&mut self, column: CollectionColumn, cx: &mut Context<Self>) {
+        if self.view.sort == column {
+            self.view.descending = !self.view.descending;
+        } else {
+            self.view.sort = column;
+            self.view.descending = false;
+        }
+        self.save_view(cx);
+    }
+
+    fn toggle_column(&mut self, column: CollectionColumn, cx: &mut Context<Self>) {
+        if self.view.columns.contains(&column) {
+            self.view.columns.retain(|other| *other != column);
+        } else {
+            // Keeps the columns in their usual order
+            self.view.columns = CollectionColumn::ALL
+                .into_iter()
+                .filter(|other| *other == column || self.view.columns.contains(other))
+                .collect();
+        }
+        self.save_view(cx);
+    }
+
+    fn open_document(&self, row: &CollectionRow, cx: &mut Context<Self>) {
+        let (id, title, folder_id) = (row.id, row.title.clone(), row.folder_id);
+        self.ctx.update(cx, |app_state, cx| {
+            app_state.open_document(id, title, folder_id, cx);
+        });
+    }
+
+    fn render_cell(&self, row: &CollectionRow, column: CollectionColumn, cx: &App) -> AnyElement {
+        let muted = cx.theme().muted_foreground;
+        let cell = div().text_sm().truncate();
+        let cell = match column {
+            CollectionColumn::Title => cell.flex_1().min_w_0(),
+            _ => cell.w(px(COLUMN_WIDTH)).flex_shrink_0(),
+        };
+
+        match column {
+            CollectionColumn::Title => cell
+                .text_color(cx.theme().foreground)
+                .child(row.title.clone())
+                .into_any_element(),
+            CollectionColumn::Tags => cell
+                .flex()
+                .gap_1()
+                .children(row.tags.iter().map(|tag| {
+                    div()
+                        .px_1()
+                        .rounded(cx.theme().radius)
+                        .bg(cx.theme().secondary)
+                        .text_xs()
+                        .text_color(cx.theme().secondary_foreground)
+                        .child(tag.clone())
+                }))
+                .into_any_element(),
+            CollectionColumn::Words => cell
+                .text_color(muted)
+                .child(row.words.to_string())
+                .into_any_element(),
+            CollectionColumn::Todos => cell
+                .text_color(muted)
+                .child(row.open_todos.to_string())
+                .into_any_element(),
+            CollectionColumn::DueDate => {
+                let overdue = row
+                    .due_date
+                    .is_some_and(|date| date < Local::now().date_naive());
+                cell.text_color(if overdue { cx.theme().danger } else { muted })
+                    .child(
+                        row.due_date
+                            .map(|date| DateFormat::new(cx).date(date))
+                            .unwrap_or_else(|| "—".to_string()),
+                    )
+                    .into_any_element()
+            }
+        }
+    }
+
+    fn render_header(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        h_flex()
+            .gap_3()
+            .px_3()
+            .py_1()
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .children(self.view.columns.iter().map(|column| {
+                let column = *column;
+                let label = if self.view.sort == column {
+                    let arrow = if self.view.descending { "↓" } else { "↑" };
+                    format!("{} {}", column.label(), arrow)
+                } else {
+                    column.label().to_string()
+                };
+                let cell = div()
+                    .id(SharedString::from(format!("collection-sort-{:?}", column)))
+                    .text_xs()
+                    .font_weight(FontWeight::SEMIBOLD)
+                    .text_color(cx.theme().muted_foreground)
+                    .cursor_pointer()
+                    .on_click(cx.listener(move |this, _, _, cx| this.sort_by(column, cx)))
+                    .child(label);
+
+                match column {
+                    CollectionColumn::Title => cell.flex_1().min_w_0(),
+                    _ => cell.w(px(COLUMN_WIDTH)).flex_shrink_0(),
+                }
+            }))
+    }
+}
+
+impl Render for CollectionScreen {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        self.ensure_initialized(window, cx);
+
+        let mut rows = self
+            .rows
+            .iter()
+            .filter(|row| row.matches(&self.view.filter))
+            .collect::<Vec<_>>();
+        rows.sort_by(|a, b| {
+            let ordering = a.compare(b, self.view.sort);
+            if self.view.descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+
+        let screen = cx.entity().downgrade();
+        let columns = self.view.columns.clone();
+
+        v_flex()
+            .size_full()
+            .overflow_y_scrollbar()
+            .p_6()
+            .gap_4()
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        div()
+                            .flex_1()
+                            .text_xl()
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .text_color(cx.theme().foreground)
+                            .child(self.folder_name.clone()),
+                    )
+                    .when_some(self.filter_input.as_ref(), |this, input| {
+                        this.child(div().w(px(240.)).child(Input::new(input).small()))
+                    })
+                    .child(
+                        Button::new("collection-columns")
+                            .outline()
+                            .small()
+                            .label("Columns")
+                            .dropdown_menu(move |menu, _, _| {
+                                let mut menu = menu.min_w(px(160.));
+                                for column in CollectionColumn::ALL {
+                                    let screen = screen.clone();
+                                    menu = menu.item(
+                                        PopupMenuItem::new(column.label())
+                                            .checked(columns.contains(&column))
+                                            .on_click(move |_, _, cx: &mut App| {
+                                                let _ = screen.update(cx, |this, cx| {
+                                                    this.toggle_column(column, cx);
+                                                });
+                                            }),
+                                    );
+                                }
+                                menu
+                            }),
+                    ),
+            )
+            .child(
+                v_flex()
+                    .rounded(cx.theme().radius)
+                    .border_1()
+                    .border_color(cx.theme().border)
+                    .child(self.render_header(cx))
+                    .when(rows.is_empty(), |this| {
+                        this.child(
+                            Label::new(if self.rows.is_empty() {
+                                "This folder has no documents yet."
+                            } else {
+                                "No document matches the filter."
+                            })
+                            .px_3()
+                            .py_2()
+                            .text_sm()
+                            .text_color(cx.theme().muted_foreground),
+                        )
+                    })
+                    .children(rows.into_iter().map(|row| {
+                        let open_row = row.id;
+
+                        h_flex()
+                            .id(SharedString::from(format!("collection-row-{}", row.id)))
+                            .gap_3()
+                            .px_3()
+                            .py_2()
+                            .cursor_pointer()
+                            .hover(|this| this.bg(cx.theme().accent.opacity(0.3)))
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                if let Some(row) = this.rows.iter().find(|row| row.id == open_row) {
+                                    this.open_document(row, cx);
+                                }
+                            }))
+                            .children(
+                                self.view
+                                    .columns
+                                    .iter()
+                                    .map(|column| self.render_cell(row, *column, cx)),
+                            )
+                    })),
+            )
+    }
+}
@@ -1,13 +1,15 @@
 use gpui::prelude::FluentBuilder;
 use gpui::{
-    Animation, AnimationExt, App, AppContext, BorrowAppContext, Context, IntoElement,
-    ParentElement, Render, RenderOnce, Styled, Transformation, WeakEntity, Window, div, percentage,
-    px,
+    Animation, AnimationExt, App, AppContext, BorrowAppContext, Context, Entity,
+    InteractiveElement, IntoElement, KeyBinding, KeyDownEvent, MouseButton, MouseDownEvent,
+    ParentElement, Render, RenderOnce, StatefulInteractiveElement, Styled, Transformation,
+    WeakEntity, Window, WindowId, actions, div, percentage, px,
 };
 use gpui_component::{
-    ActiveTheme, Colorize, Disableable, Icon, Sizable,
+    ActiveTheme, Colorize, Disableable, Icon, Selectable, Sizable, StyledExt,
     button::{Button, ButtonVariants},
-    input::Input,
+    h_flex,
+    input::{Input, InputEvent, InputState},
     scroll::ScrollableElement,
     tab::{Tab, TabBar},
 };
@@ -21,15 +23,34 @@ use crate::{
         states::{
             app_state::AppState,
             document_state::{DocumentContent, DocumentState, OpenedDocument, PersistenceState},
+            error_state::{AppError, ErrorKind},
             repository_state::RepositoryState,
             settings_state::Settings,
         },
     },
+    domain::database::{document::DocumentModel, folder::FolderModel},
 };
 
+actions!(document_screen, [NextTab, PreviousTab, CloseTab]);
+
+const CONTEXT: &str = "DocumentScreen";
+
+pub fn init(cx: &mut App) {
+    cx.bind_keys([
+        KeyBinding::new("ctrl-tab", NextTab, Some(CONTEXT)),
+        KeyBinding::new("ctrl-shift-tab", PreviousTab, Some(CONTEXT)),
+        KeyBinding::new("secondary-w", CloseTab, Some(CONTEXT)),
+    ]);
+}
+
 pub struct DocumentScreen {
     _ctx: ScreenContext<AppState>,
     initialized: bool,
+    /// Document whose tab is being renamed, with the title input shown in the tab.
+    renaming: Option<(i32, Entity<InputState>)>,
+    /// Folders for the breadcrumbs, read again when another document is shown.
+    folders: Vec<FolderModel>,
+    folders_document: Option<i32>,
 }
 
 impl Screen for DocumentScreen {
@@ -43,9 +64,45 @@ impl DocumentScreen {
         Self {
             _ctx: ScreenContext::new(app_state),
             initialized: false,
+            renaming: None,
+            folders: Vec::new(),
+            folders_document: None,
         }
     }
 
+    /// Shows the tab after the current one, or the one before it.
+    fn select_adjacent_tab(window_id: WindowId, forward: bool, cx: &mut App) {
+        cx.update_global::<DocumentState, _>(|state, _| {
+            let workspace = state.workspace_mut(window_id);
+            let Some(index) = workspace.get_current_document_index() else {
+                return;
+            };
+            let index = if forward {
+                index + 1
+            } else {
+                match index.checked_sub(1) {
+                    Some(index) => index,
+                    None => return,
+                }
+            };
+            if let Some(doc) = workspace.documents.get(index) {
+                workspace.current_opened_document = Some(doc.uid);
+            }
+        });
+    }
+
+    /// Closes the tab of `uid`, showing the one before it.
+    fn close_tab(window_id: WindowId, uid: i32, cx: &mut App) {
+        cx.update_global::<DocumentState, _>(|state, _| {
+            let workspace = state.workspace_mut(window_id);
+            let previous_document = workspace.get_previous_document(uid);
+
+            workspace.current_opened_document = previous_document.map(|doc| doc.uid);
+
+            workspace.remove_document(uid);
+        })
+    }
+
     fn ensure_initialized(&mut self, cx: &mut Context<Self>) {
         if !self.initialized {
             self.initialized = true;
@@ -57,17 +114,44 @@ impl DocumentScreen {
         }
     }
 
+    /// Reads the folders again once another document is shown, so that its breadcrumbs
+    /// follow the folders renamed or moved since.
+    fn load_folders_if_needed(&mut self, document_id: Option<i32>, cx: &mut Context<Self>) {
+        if document_id.is_none() || self.folders_document == document_id {
+            return;
+        }
+        self.folders_document = document_id;
+
+        let folders = cx.global::<RepositoryState>().folders.clone();
+        cx.spawn(async move |this, cx| {
+            let folders = folders.get_folders().await?;
+            this.update(cx, |this, cx| {
+                this.folders = folders;
+                cx.notify();
+            })?;
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
     fn load_document_if_needed(&self, window: &mut Window, cx: &mut Context<Self>) {
+        let window_id = window.window_handle().window_id();
         let (needs_loading, document_id) = cx.read_global::<DocumentState, _>(|state, _| {
-            let id = state.current_opened_document;
-            let needs = id.map(|id| state.needs_loading(id)).unwrap_or(false);
+            let workspace = state.workspace(window_id);
+            let id = workspace.and_then(|workspace| workspace.current_opened_document);
+            let needs = workspace
+                .zip(id)
+                .map(|(workspace, id)| workspace.needs_loading(id))
+                .unwrap_or(false);
             (needs, id)
         });
 
         if needs_loading && let Some(doc_id) = document_id {
             // Mark as loading in progress to prevent duplicate loads
             cx.update_global::<DocumentState, _>(|state, _| {
-                state.set_loading_in_progress(doc_id, true);
+                state
+                    .workspace_mut(window_id)
+                    .set_loading_in_progress(doc_id, true);
             });
 
             let repository = cx.global::<RepositoryState>().documents.clone();
@@ -86,18 +170,20 @@ impl DocumentScreen {
 
                             // Then update the global state
                             cx.update_global::<DocumentState, _>(|state, _| {
-                                state.apply_document_content(doc_id, content);
-                                state.set_loading_in_progress(doc_id, false);
+                                let workspace = state.workspace_mut(window_id);
+                                workspace.apply_document_content(doc_id, content);
+                                workspace.set_loading_in_progress(doc_id, false);
                             });
                         });
 
                         // If window update failed, set error state
                         if let Err(e) = update_result {
-                            eprintln!("[document_screen] update_window failed: {:?}", e);
+                            tracing::error!("update_window failed: {:?}", e);
                             cx.update(|cx| {
                                 cx.update_global::<DocumentState, _>(|state, _| {
-                                    state.set_loading_in_progress(doc_id, false);
-                                    state.set_document_error(
+                                    let workspace = state.workspace_mut(window_id);
+                                    workspace.set_loading_in_progress(doc_id, false);
+                                    workspace.set_document_error(
                                         doc_id,
                                         "Failed to load document".to_string(),
                                     );
@@ -106,11 +192,12 @@ impl DocumentScreen {
                         }
                     }
                     Err(e) => {
-                        eprintln!("[document_screen] get_document_by_id failed: {:?}", e);
+                        tracing::error!("get_document_by_id failed: {:?}", e);
                         cx.update(|cx| {
                             cx.update_global::<DocumentState, _>(|state, _| {
-                                state.set_loading_in_progress(doc_id, false);
-                                state.set_document_error(doc_id, e.to_string());
+                                let workspace = state.workspace_mut(window_id);
+                                workspace.set_loading_in_progress(doc_id, false);
+                                workspace.set_document_error(doc_id, e.to_string());
                             });
                         });
                     }
@@ -121,19 +208,150 @@ impl DocumentScreen {
             .detach();
         }
     }
+
+    fn start_rename(&mut self, uid: i32, title: &str, window: &mut Window, cx: &mut Context<Self>) {
+        let input = cx.new(|cx| {
+            let mut state = InputState::new(window, cx).placeholder("Untitled");
+            state.set_value(title.to_string(), window, cx);
+            state
+        });
+
+        cx.subscribe_in(
+            &input,
+            window,
+            |this, _, event: &InputEvent, window, cx| match event {
+                InputEvent::PressEnter { .. } => this.commit_rename(window, cx),
+                InputEvent::Blur => this.cancel_rename(cx),
+                _ => {}
+            },
+        )
+        .detach();
+
+        input.update(cx, |state, cx| {
+            state.focus(window, cx);
+        });
+
+        self.renaming = Some((uid, input));
+        cx.notify();
+    }
+
+    /// Renames the document everywhere it is shown and saves the new title.
+    fn commit_rename(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some((uid, input)) = self.renaming.take() else {
+            return;
+        };
+        cx.notify();
+
+        let title = input.read(cx).value().trim().to_string();
+        if title.is_empty() {
+            return;
+        }
+
+        let window_id = window.window_handle().window_id();
+        let (content, is_current) = cx.update_global::<DocumentState, _>(|state, _| {
+            state.rename_document(uid, &title);
+
+            let workspace = state.workspace(window_id);
+            let content = workspace
+                .and_then(|workspace| workspace.documents.iter().find(|doc| doc.uid == uid))
+                .and_then(|doc| match &doc.state {
+                    LoadingState::Loaded(content) => Some(content.clone()),
+                    _ => None,
+                });
+            let is_current =
+                workspace.and_then(|workspace| workspace.current_opened_document) == Some(uid);
+            (content, is_current)
+        });
+
+        if let Some(content) = content {
+            content.title_input.update(cx, |input, cx| {
+                input.set_value(title.clone(), window, cx);
+            });
+        }
+
+        // The save of the current document carries its title, and supersedes a pending one
+        if is_current {
+            cx.update_global::<DocumentState, _>(|state, cx| {
+                state.mark_changed(window, cx);
+            });
+            return;
+        }
+
+        let repository = cx.global::<RepositoryState>().documents.clone();
+        cx.spawn(async move |_, cx| {
+            let result = async {
+                let document = repository.get_document_by_id(uid).await?;
+                repository
+                    .update_document(DocumentModel { title, ..document })
+                    .await
+            }
+            .await;
+
+            match result {
+                // Other windows showing the document pick up the new title
+                Ok(()) => cx.update(|cx| {
+                    cx.update_global::<DocumentState, _>(|state, _| {
+                        state.reload_documents(&[uid]);
+                    });
+                }),
+                Err(e) => {
+                    AppError::new(ErrorKind::Storage, "Couldn't rename the document", &e).report()
+                }
+            }
+        })
+        .detach();
+    }
+
+    fn cancel_rename(&mut self, cx: &mut Context<Self>) {
+        if self.renaming.take().is_some() {
+            cx.notify();
+        }
+    }
+
+    /// Focuses the block requested when the document was opened, once it is loaded.
+    fn focus_pending_block(&self, window: &mut Window, cx: &mut Context<Self>) {
+        let window_id = window.window_handle().window_id();
+        let Some((content, block_id)) = cx.update_global::<DocumentState, _>(|state, _| {
+            state.workspace_mut(window_id).take_focus_block()
+        }) else {
+            return;
+        };
+
+        let node = content
+            .renderer
+            .read(cx)
+            .state
+            .read(cx)
+            .get_current_nodes(block_id)
+            .cloned();
+        if let Some(node) = node {
+            cx.defer_in(window, move |_, window, cx| {
+                node.element.focus_end(window, cx);
+            });
+        }
+    }
 }
 
 impl Render for DocumentScreen {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         self.ensure_initialized(cx);
         self.load_document_if_needed(window, cx);
+        self.focus_pending_block(window, cx);
 
-        let (documents, current_document, current_index, is_saving, can_go_previous, can_go_next) =
+        let window_id = window.window_handle().window_id();
+        let (documents, current_document, current_index, save_status, can_go_previous, can_go_next) =
             cx.read_global::<DocumentState, _>(|state, _| {
-                let documents: Vec<OpenedDocument> = state.documents.clone();
-                let current_document = state.get_current_document().cloned();
-                let current_index = state.get_current_document_index();
-                let is_saving = state.persistence == PersistenceState::Pending;
+                let workspace = state.workspace(window_id).cloned().unwrap_or_default();
+                let documents: Vec<OpenedDocument> = workspace.documents.clone();
+                let current_document = workspace.get_current_document().cloned();
+                let current_index = workspace.get_current_document_index();
+                let save_status = match &current_document {
+                    Some(doc) if state.is_saving(doc.uid) => SaveStatus::Saving,
+                    Some(doc) if state.persistence(doc.uid) == PersistenceState::Pending => {
+                        SaveStatus::Unsaved
+                    }
+                    _ => SaveStatus::Saved,
+                };
                 let can_go_previous = current_index.map(|i| i > 0).unwrap_or(false);
                 let can_go_next = current_index
                     .map(|i| i < documents.len().saturating_sub(1))
@@ -143,32 +361,35 @@ impl Render for DocumentScreen {
                     documents,
                     current_document,
                     current_index,
-                    is_saving,
+                    save_status,
                     can_go_previous,
                     can_go_next,
                 )
             });
 
+        let current_uid = current_document.as_ref().map(|doc| doc.uid);
+        self.load_folders_if_needed(current_uid, cx);
+        let breadcrumbs = current_document
+            .as_ref()
+            .map(|doc| folder_path(&self.folders, doc.folder_id))
+            .unwrap_or_default();
+
         div()
             .w_full()
             .h_full()
             .relative()
-            .when(is_saving, |this| {
-                this.child(
-                    div().absolute().bottom_4().right_4().child(
-                        Icon::default()
-                            .path("icons/loader-circle.svg")
-                            .size_4()
-                            .with_animation(
-                                "rotate-loader",
-                                Animation::new(Duration::from_secs(1)).repeat(),
-                                |icon, delta| {
-                                    icon.transform(Transformation::rotate(percentage(delta)))
-                                },
-                            ),
-                    ),
-                )
-            })
+            .key_context(CONTEXT)
+            .on_action(cx.listener(move |_, _: &NextTab, _, cx| {
+                Self::select_adjacent_tab(window_id, true, cx);
+            }))
+            .on_action(cx.listener(move |_, _: &PreviousTab, _, cx| {
+                Self::select_adjacent_tab(window_id, false, cx);
+            }))
+            .on_action(cx.listener(move |_, _: &CloseTab, _, cx| {
+                if let Some(uid) = current_uid {
+                    Self::close_tab(window_id, uid, cx);
+                }
+            }))
             .when(!documents.is_empty(), |this| {
                 this.child(
                     TabBar::new("tabs")
@@ -185,17 +406,8 @@ impl Render for DocumentScreen {
                                         .icon(Icon::default().path("icons/chevron-left.svg"))
                                         .disabled(!can_go_previous)
                                         .tooltip("Previous tab")
-                                        .on_click(cx.listener(|_, _, _, cx| {
-                                            cx.update_global::<DocumentState, _>(|state, _| {
-                                                if let Some(index) =
-                                                    state.get_current_document_index()
-                                                    && index > 0
-                                                    && let Some(doc) =
-                                                        state.documents.get(index - 1)
-                                                {
-                                                    state.current_opened_document = Some(doc.uid);
-                                                }
-                                            });
+                                        .on_click(cx.listener(move |_, _, _, cx| {
+                                            Self::select_adjacent_tab(window_id, false, cx);
                                         })),
                                 )
                                 .child(
@@ -206,69 +418,147 @@ impl Render for DocumentScreen {
                                         .icon(Icon::default().path("icons/chevron-right.svg"))
                                         .disabled(!can_go_next)
                                         .tooltip("Next tab")
-                                        .on_click(cx.listener(|_, _, _, cx| {
-                                            cx.update_global::<DocumentState, _>(|state, _| {
-                                                let current_index =
-                                                    state.get_current_document_index();
-
-                                                if let Some(index) = current_index
-                                                    && index < state.documents.len() - 1
-                                                {
-                                                    let document = state.documents.get(index + 1);
-                                                    if let Some(doc) = document {
-                                                        state.current_opened_document =
-                                                            Some(doc.uid);
-                                                    }
-                                                }
-                                            });
+                                        .on_click(cx.listener(move |_, _, _, cx| {
+                                            Self::select_adjacent_tab(window_id, true, cx);
                                         })),
                                 ),
                         )
                         .suffix(
-                            div().px_4().flex().items_center().child(
-                                Button::new("toggle-code-btn")
-                                    .xsmall()
-                                    .ghost()
-                                    .cursor_pointer()
-                                    .icon(Icon::default().path("icons/braces.svg"))
-                                    .tooltip("Open code view")
-                                    .on_click({
-                                        let current_doc = current_document.clone();
-                                        cx.listener(move |_, _, _, cx| {
-                                            if let Some(doc) = &current_doc
-                                                && let LoadingState::Loaded(content) = &doc.state
-                                            {
-                                                let nodes = content
-                                                    .renderer
-                                                    .read(cx)
-                                                    .state
-                                                    .read(cx)
-                                                    .get_nodes()
-                                                    .clone();
-                                                CodeWindow::open(
-                                                    doc.title.clone(),
-                                                    doc.uid,
-                                                    nodes,
-                                                    cx,
-                                                );
-                                            }
-                                        })
-                                    }),
-                            ),
+                            div()
+                                .px_4()
+                                .flex()
+                                .items_center()
+                                .when_some(current_document.as_ref(), |this, doc| {
+                                    let uid = doc.uid;
+                                    let numbered =
+                                        cx.global::<Settings>().editor.numbers_headings(uid);
+
+                                    this.child(
+                                        Button::new("toggle-heading-numbers-btn")
+                                            .xsmall()
+                                            .ghost()
+                                            .cursor_pointer()
+                                            .icon(Icon::default().path("icons/list-ordered.svg"))
+                                            .selected(numbered)
+                                            .tooltip(if numbered {
+                                                "Stop numbering headings"
+                                            } else {
+                                                "Number headings"
+                                            })
+                                            .on_click(cx.listener(move |_, _, _, cx| {
+                                                cx.update_global::<Settings, _>(|settings, _| {
+                                                    settings.editor.toggle_numbered_headings(uid);
+                                                    settings.save();
+                                                });
+                                                cx.notify();
+                                            })),
+                                    )
+                                })
+                                .child(
+                                    Button::new("toggle-code-btn")
+                                        .xsmall()
+                                        .ghost()
+                                        .cursor_pointer()
+                                        .icon(Icon::default().path("icons/braces.svg"))
+                                        .tooltip("Open code view")
+                                        .on_click({
+                                            let current_doc = current_document.clone();
+                                            cx.listener(move |_, _, _, cx| {
+                                                if let Some(doc) = &current_doc
+                                                    && let LoadingState::Loaded(content) =
+                                                        &doc.state
+                                                {
+                                                    let state =
+                                                        content.renderer.read(cx).state.clone();
+                                                    CodeWindow::open(
+                                                        doc.title.clone(),
+                                                        doc.uid,
+                                                        state,
+                                                        cx,
+                                                    );
+                                                }
+                                            })
+                                        }),
+                                ),
                         )
                         .selected_index(current_index.unwrap_or(0))
-                        .on_click(cx.listener(|_, index: &usize, _, cx| {
+                        .on_click(cx.listener(move |_, index: &usize, _, cx| {
                             cx.update_global::<DocumentState, _>(|state, _| {
-                                if let Some(doc) = state.documents.get(*index) {
-                                    state.current_opened_document = Some(doc.uid);
+                                let workspace = state.workspace_mut(window_id);
+                                let uid = workspace.documents.get(*index).map(|doc| doc.uid);
+                                if let Some(uid) = uid {
+                                    workspace.current_opened_document = Some(uid);
+                                    state.mark_recent(uid);
                                 }
                             });
                         }))
                         .children(documents.iter().map(|element| {
+                            let uid = element.uid;
+                            let unsaved = cx.global::<DocumentState>().persistence(uid)
+                                == PersistenceState::Pending;
+                            let rename_input = self
+                                .renaming
+                                .as_ref()
+                                .filter(|(renaming_uid, _)| *renaming_uid == uid)
+                                .map(|(_, input)| input.clone());
+
                             Tab::new()
                                 .bg(cx.theme().background.lighten(0.2))
                                 .cursor_pointer()
-                                .label(element.title.clone())
+                                .map(|this| match rename_input {
+                                    Some(input) => this.child(
+                                        div()
+                                            .w(px(160.))
+                                            .on_key_down(cx.listener(
+                                                |this, event: &KeyDownEvent, _, cx| {
+                                                    if event.keystroke.key.as_str() == "escape" {
+                                                        cx.stop_propagation();
+                                                        this.cancel_rename(cx);
+                                                    }
+                                                },
+                                            ))
+                                            .child(
+                                                Input::new(&input)
+                                                    .xsmall()
+                                                    .appearance(false)
+                                                    .text_sm(),
+                                            ),
+                                    ),
+                                    None => {
+                                        let title = element.title.clone();
+                                        this.child(
+                                            div()
+                                                .on_mouse_down(
+                                                    MouseButton::Left,
+                                                    cx.listener(
+                                                        move |this,
+                                                              event: &MouseDownEvent,
+                                                              window,
+                                                              cx| {
+                                                            if event.click_count == 2 {
+                                                                this.start_rename(
+                                                                    uid, &title, window, cx,
+                                                                );
+                                                            }
+                                                        },
+                                                    ),
+                                                )
+                                                .flex()
+                                                .items_center()
+                                                .gap_1p5()
+                                                .child(element.title.clone())
+                                                .when(unsaved, |el| {
+                                                    el.child(
+                                                        div()
+                                                            .flex_shrink_0()
+                                                            .size_1p5()
+                                                            .rounded_full()
+                                                            .bg(cx.theme().muted_foreground),
+                                                    )
+                                                }),
+                                        )
+                                    }
+                                })
                                 .suffix(
                                     Button::new("btn")
                                         .xsmall()
@@ -280,21 +570,17 @@ impl Render for DocumentScreen {
                                         .on_click({
                                             let element_id = element.uid;
                                             cx.listener(move |_, _, _, cx| {
-                                                cx.update_global::<DocumentState, _>(|state, _| {
-                                                    let previous_document =
-                                                        state.get_previous_document(element_id);
-
-                                                    state.current_opened_document =
-                                                        previous_document.map(|doc| doc.uid);
-
-                                                    state.remove_document(element_id);
-                                                })
+                                                Self::close_tab(window_id, element_id, cx);
                                             })
                                         }),
                                 )
                         })),
                 )
-                .child(self.render_document_content(current_document))
+                .child(self.render_document_content(
+                    current_document,
+                    breadcrumbs,
+                    save_status,
+                ))
             })
             .when(documents.is_empty(), |this| this.child(DocumentStateEmpty))
     }
@@ -304,12 +590,17 @@ impl DocumentScreen {
     fn render_document_content(
         &self,
         current_document: Option<OpenedDocument>,
+        breadcrumbs: Vec<String>,
+        save_status: SaveStatus,
     ) -> impl IntoElement {
         match current_document {
             Some(doc) => match &doc.state {
                 LoadingState::Loading => DocumentLoading.into_any_element(),
                 LoadingState::Loaded(content) => DocumentStateLoaded {
                     content: content.clone(),
+                    title: doc.title.clone(),
+                    breadcrumbs,
+                    save_status,
                 }
                 .into_any_element(),
                 LoadingState::Error(error) => DocumentLoadingError {
@@ -355,9 +646,97 @@ impl RenderOnce for DocumentLoadingError {
     }
 }
 
+/// Names of the folder of a document and of its parents, from the root.
+fn folder_path(folders: &[FolderModel], folder_id: Option<i32>) -> Vec<String> {
+    let mut path = Vec::new();
+    let mut current = folder_id;
+    // Bounded in case the parents loop
+    while let Some(id) = current
+        && path.len() < folders.len()
+    {
+        let Some(folder) = folders.iter().find(|folder| folder.id == id) else {
+            break;
+        };
+        path.push(folder.name.clone());
+        current = folder.parent_id;
+    }
+    path.reverse();
+    path
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum SaveStatus {
+    Saved,
+    Unsaved,
+    Saving,
+}
+
+/// Scroll after which the title is out of sight and the header shows it instead.
+const COMPACT_HEADER_OFFSET: f32 = 56.;
+
 #[derive(IntoElement)]
 struct DocumentStateLoaded {
     content: DocumentContent,
+    title: String,
+    breadcrumbs: Vec<String>,
+    save_status: SaveStatus,
+}
+
+impl DocumentStateLoaded {
+    /// Bar above the scrolled blocks with the breadcrumbs and the save indicator, which
+    /// also shows the title once the document is scrolled past it.
+    fn render_header(&self, cx: &App) -> impl IntoElement {
+        let compact = self.content.scroll_handle.offset().y < px(-COMPACT_HEADER_OFFSET);
+        let muted_fg = cx.theme().muted_foreground;
+
+        h_flex()
+            .flex_shrink_0()
+            .w_full()
+            .h_8()
+            .px_4()
+            .gap_1()
+            .items_center()
+            .text_xs()
+            .text_color(muted_fg)
+            .when(compact, |this| {
+                this.border_b_1()
+                    .border_color(cx.theme().border)
+                    .bg(cx.theme().background)
+            })
+            .children(self.breadcrumbs.iter().enumerate().map(|(ix, name)| {
+                h_flex()
+                    .gap_1()
+                    .when(ix > 0, |this| this.child("›"))
+                    .child(name.clone())
+            }))
+            .when(compact, |this| {
+                this.when(!self.breadcrumbs.is_empty(), |this| this.child("›"))
+                    .child(
+                        div()
+                            .min_w_0()
+                            .truncate()
+                            .text_sm()
+                            .font_semibold()
+                            .text_color(cx.theme().foreground)
+                            .child(self.title.clone()),
+                    )
+            })
+            .child(div().flex_1())
+            .map(|this| match self.save_status {
+                SaveStatus::Saving => this.child(
+                    Icon::default()
+                        .path("icons/loader-circle.svg")
+                        .size_3p5()
+                        .with_animation(
+                            "rotate-loader",
+                            Animation::new(Duration::from_secs(1)).repeat(),
+                            |icon, delta| icon.transform(Transformation::rotate(percentage(delta))),
+                        ),
+                ),
+                SaveStatus::Unsaved => this.child("Unsaved"),
+                SaveStatus::Saved => this.child("Saved"),
+            })
+    }
 }
 
 impl RenderOnce for DocumentStateLoaded {
@@ -377,13 +756,17 @@ impl RenderOnce for DocumentStateLoaded {
             .h_full()
             .w_full()
             .overflow_hidden()
+            .child(self.render_header(cx))
             .child(
                 div()
                     .flex()
                     .gap_10()
+                    .id("document-scroll")
                     .flex_1()
                     .min_h_0()
-                    .overflow_y_scrollbar()
+                    .overflow_y_scroll()
+                    .track_scroll(&self.content.scroll_handle)
+                    .vertical_scrollbar(&self.content.scroll_handle)
                     .child(
                         div()
                             .max_w(px(820.0))
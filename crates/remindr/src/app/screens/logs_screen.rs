@@ -0,0 +1,206 @@
+use std::time::Duration;
+
+use gpui::prelude::FluentBuilder;
+use gpui::{
+    Context, FontWeight, IntoElement, ParentElement, Render, SharedString, Styled, WeakEntity,
+    Window, actions, div,
+};
+use gpui_component::{
+    ActiveTheme, Sizable,
+    button::{Button, ButtonVariants},
+    h_flex,
+    label::Label,
+    scroll::ScrollableElement,
+    v_flex,
+};
+use gpui_nav::{Screen, ScreenContext};
+use tracing::Level;
+
+use crate::{
+    app::states::{app_state::AppState, repository_state::RepositoryState},
+    infrastructure::{
+        database::{self, PoolHealth},
+        logging::{self, LogLine},
+    },
+};
+
+actions!(logs, [OpenLogs]);
+
+/// Lines of the log file kept on screen.
+const TAIL_LINES: usize = 500;
+
+/// Delay between two reads of the log file.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+const LEVELS: [Level; 5] = [
+    Level::ERROR,
+    Level::WARN,
+    Level::INFO,
+    Level::DEBUG,
+    Level::TRACE,
+];
+
+/// Last lines of the log file and the state of the database connections, refreshed while
+/// the screen is shown, to diagnose failed saves and syncs.
+pub struct LogsScreen {
+    _ctx: ScreenContext<AppState>,
+    initialized: bool,
+    lines: Vec<LogLine>,
+    pool_health: Option<PoolHealth>,
+    /// Most verbose level shown.
+    level: Level,
+}
+
+impl Screen for LogsScreen {
+    fn id(&self) -> &'static str {
+        "Logs"
+    }
+}
+
+impl LogsScreen {
+    pub fn new(app_state: WeakEntity<AppState>) -> Self {
+        Self {
+            _ctx: ScreenContext::new(app_state),
+            initialized: false,
+            lines: Vec::new(),
+            pool_health: None,
+            level: Level::INFO,
+        }
+    }
+
+    fn ensure_initialized(&mut self, cx: &mut Context<Self>) {
+        if self.initialized {
+            return;
+        }
+        self.initialized = true;
+
+        cx.spawn(async move |this, cx| {
+            loop {
+                let lines = cx
+                    .background_executor()
+                    .spawn(async { logging::tail(TAIL_LINES) })
+                    .await;
+                let pool = cx.update(|cx| {
+                    cx.try_global::<RepositoryState>()
+                        .map(|state| state.pool.clone())
+                });
+                let pool_health = match pool {
+                    Some(pool) => database::health(&pool).await.ok(),
+                    None => None,
+                };
+
+                let result = this.update(cx, |this, cx| {
+                    this.lines = lines;
+                    this.pool_health = pool_health;
+                    cx.notify();
+                });
+                if result.is_err() {
+                    break;
+                }
+
+                smol::Timer::after(REFRESH_INTERVAL).await;
+            }
+        })
+        .detach();
+    }
+
+    fn render_pool_health(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let text = match &self.pool_health {
+            Some(health) => format!(
+                "Database: {} of {} connections open, {} idle · journal mode {} · {} busy \
+                 writes retried",
+                health.connections,
+                health.max_connections,
+                health.idle,
+                health.journal_mode,
+                health.busy_retries
+            ),
+            None => "Database: not connected".to_string(),
+        };
+
+        Label::new(text)
+            .text_xs()
+            .text_color(cx.theme().muted_foreground)
+    }
+
+    fn render_toolbar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        h_flex()
+            .gap_1()
+            .children(LEVELS.into_iter().map(|level| {
+                Button::new(SharedString::from(format!("log-level-{}", level)))
+                    .xsmall()
+                    .outline()
+                    .label(level.as_str())
+                    .selected(self.level == level)
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        this.level = level;
+                        cx.notify();
+                    }))
+            }))
+            .child(div().flex_1())
+            .child(
+                Button::new("open-log-folder")
+                    .xsmall()
+                    .ghost()
+                    .label("Open log folder")
+                    .on_click(|_, _, cx| {
+                        if let Some(dir) = logging::log_dir() {
+                            cx.open_with_system(&dir);
+                        }
+                    }),
+            )
+    }
+}
+
+impl Render for LogsScreen {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        self.ensure_initialized(cx);
+
+        let lines = self
+            .lines
+            .iter()
+            .filter(|line| line.level <= self.level)
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .size_full()
+            .p_6()
+            .gap_4()
+            .child(
+                div()
+                    .text_xl()
+                    .font_weight(FontWeight::SEMIBOLD)
+                    .text_color(cx.theme().foreground)
+                    .child("Logs"),
+            )
+            .child(self.render_pool_health(cx))
+            .child(self.render_toolbar(cx))
+            .child(
+                v_flex()
+                    .flex_1()
+                    .min_h_0()
+                    .p_2()
+                    .rounded(cx.theme().radius)
+                    .border_1()
+                    .border_color(cx.theme().border)
+                    .overflow_y_scrollbar()
+                    .font_family(cx.theme().mono_font_family.clone())
+                    .text_xs()
+                    .when(lines.is_empty(), |this| {
+                        this.child(
+                            Label::new("Nothing logged at this level yet.")
+                                .text_color(cx.theme().muted_foreground),
+                        )
+                    })
+                    .children(lines.into_iter().map(|line| {
+                        let color = match line.level {
+                            Level::ERROR => cx.theme().danger,
+                            Level::WARN => cx.theme().warning,
+                            Level::INFO => cx.theme().foreground,
+                            _ => cx.theme().muted_foreground,
+                        };
+                        div().text_color(color).child(line.text.clone())
+                    })),
+            )
+    }
+}
@@ -1,29 +1,62 @@
+use std::time::Duration;
+
+use gpui::prelude::FluentBuilder;
 use gpui::{
-    AnyView, AppContext, Context, EmptyView, Entity, IntoElement, ParentElement, Render, Styled,
-    Window, div, px,
+    AnyView, AppContext, BorrowAppContext, Context, EmptyView, Entity, InteractiveElement,
+    IntoElement, ParentElement, Render, Styled, Task, Window, div, px,
 };
 use gpui_component::{ActiveTheme, Root};
 
 use crate::app::{
-    components::{sidebar::AppSidebar, title_bar::TitleBar},
-    screens::home_screen::HomeScreen,
-    states::{app_state::AppState, settings_state::Settings},
+    components::{
+        command_palette::{CommandPalette, ToggleCommandPalette},
+        demo_document::{self, CreateDemoDocument},
+        nav_inspector::{NavInspector, ToggleNavInspector},
+        progress_panel::ProgressPanel,
+        quick_switcher::{QuickSwitcher, ToggleQuickSwitcher},
+        sidebar::AppSidebar,
+        title_bar::TitleBar,
+    },
+    screens::{
+        activity_screen::OpenActivity, board_screen::OpenBoard, calendar_screen::OpenCalendar,
+        home_screen::HomeScreen, logs_screen::OpenLogs, reminders_screen::OpenReminders,
+        tasks_screen::OpenTasks,
+    },
+    states::{
+        app_state::AppState, document_state::DocumentState, settings_state::Settings,
+        window_state::WindowState,
+    },
 };
 
+pub mod activity_screen;
+pub mod board_screen;
+pub mod calendar_screen;
+pub mod collection_screen;
 pub mod document_screen;
 pub mod home_screen;
 pub mod login_screen;
+pub mod logs_screen;
+pub mod reminders_screen;
+pub mod tasks_screen;
 
+/// Root view of a main window.
 pub struct AppRouter {
     app_state: Entity<AppState>,
     sidebar: Entity<AppSidebar>,
     title_bar: Entity<TitleBar>,
+    progress_panel: Entity<ProgressPanel>,
+    /// Whether the navigation stack of the window is shown over it.
+    show_nav_inspector: bool,
+    /// Slot of the window in `WindowState`, used to remember its position.
+    window_slot: usize,
+    _save_bounds_task: Option<Task<()>>,
 }
 
 impl AppRouter {
-    pub fn new(cx: &mut Context<Self>) -> Self {
+    pub fn new(window_slot: usize, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let window_id = window.window_handle().window_id();
         let app_state = cx.new(|cx| {
-            let mut state = AppState::new();
+            let mut state = AppState::new(window_id);
             let home = HomeScreen::new(cx.weak_entity());
             state.navigator.push(home, cx);
             state
@@ -34,12 +67,56 @@ impl AppRouter {
         })
         .detach();
 
+        cx.observe_window_bounds(window, |this, window, cx| {
+            this.schedule_bounds_save(window, cx);
+        })
+        .detach();
+
+        cx.on_release(move |this: &mut Self, cx| {
+            cx.update_global::<DocumentState, _>(|state, _| state.close_workspace(window_id));
+            cx.update_global::<WindowState, _>(|state, _| state.release_slot(this.window_slot));
+        })
+        .detach();
+
+        // Saves the changes still waiting for the autosave delay before the window closes
+        window.on_window_should_close(cx, DocumentState::close_window);
+
         Self {
             app_state: app_state.clone(),
             sidebar: AppSidebar::new(app_state, cx),
             title_bar: cx.new(TitleBar::new),
+            progress_panel: cx.new(ProgressPanel::new),
+            show_nav_inspector: false,
+            window_slot,
+            _save_bounds_task: None,
         }
     }
+
+    /// Opens a document tab in this window.
+    pub fn open_document(
+        &mut self,
+        id: i32,
+        title: String,
+        folder_id: Option<i32>,
+        cx: &mut Context<Self>,
+    ) {
+        self.app_state.update(cx, |app_state, cx| {
+            app_state.open_document(id, title, folder_id, cx);
+        });
+    }
+
+    /// Remembers the window position once it stops moving.
+    fn schedule_bounds_save(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let bounds = window.bounds();
+        let slot = self.window_slot;
+
+        self._save_bounds_task = Some(cx.spawn(async move |_, cx| {
+            smol::Timer::after(Duration::from_millis(500)).await;
+            cx.update(|cx| {
+                cx.update_global::<WindowState, _>(|state, _| state.update_bounds(slot, bounds));
+            });
+        }));
+    }
 }
 
 impl Render for AppRouter {
@@ -58,6 +135,43 @@ impl Render for AppRouter {
             .flex()
             .flex_col()
             .text_size(px(ui_font_size))
+            .on_action(cx.listener(|_, _: &ToggleCommandPalette, window, cx| {
+                CommandPalette::open(window, cx);
+            }))
+            .on_action(cx.listener(|this, _: &ToggleQuickSwitcher, window, cx| {
+                QuickSwitcher::open(this.app_state.clone(), window, cx);
+            }))
+            .on_action(cx.listener(|this, _: &CreateDemoDocument, _, cx| {
+                demo_document::create(this.app_state.clone(), cx);
+            }))
+            .on_action(cx.listener(|this, _: &ToggleNavInspector, _, cx| {
+                this.show_nav_inspector = !this.show_nav_inspector;
+                cx.notify();
+            }))
+            .on_action(cx.listener(|this, _: &OpenActivity, _, cx| {
+                this.app_state
+                    .update(cx, |app_state, cx| app_state.open_activity(cx));
+            }))
+            .on_action(cx.listener(|this, _: &OpenBoard, _, cx| {
+                this.app_state
+                    .update(cx, |app_state, cx| app_state.open_board(cx));
+            }))
+            .on_action(cx.listener(|this, _: &OpenCalendar, _, cx| {
+                this.app_state
+                    .update(cx, |app_state, cx| app_state.open_calendar(cx));
+            }))
+            .on_action(cx.listener(|this, _: &OpenLogs, _, cx| {
+                this.app_state
+                    .update(cx, |app_state, cx| app_state.open_logs(cx));
+            }))
+            .on_action(cx.listener(|this, _: &OpenReminders, _, cx| {
+                this.app_state
+                    .update(cx, |app_state, cx| app_state.open_reminders(cx));
+            }))
+            .on_action(cx.listener(|this, _: &OpenTasks, _, cx| {
+                this.app_state
+                    .update(cx, |app_state, cx| app_state.open_tasks(cx));
+            }))
             .child(self.title_bar.clone())
             .child(
                 div()
@@ -66,14 +180,28 @@ impl Render for AppRouter {
                     .min_h_0()
                     .overflow_hidden()
                     .child(div().bg(cx.theme().accent).child(self.sidebar.clone()))
-                    .child(div().flex_1().min_w_0().overflow_hidden().child(
-                        if let Some(current_view) = self.app_state.read(cx).navigator.current() {
-                            current_view.clone()
-                        } else {
-                            AnyView::from(cx.new(|_| EmptyView))
-                        },
-                    )),
+                    .child(
+                        div()
+                            .flex_1()
+                            .min_w_0()
+                            .overflow_hidden()
+                            .relative()
+                            .child(
+                                if let Some(current_view) =
+                                    self.app_state.read(cx).navigator.current()
+                                {
+                                    current_view.clone()
+                                } else {
+                                    AnyView::from(cx.new(|_| EmptyView))
+                                },
+                            )
+                            .children(self.app_state.read(cx).navigator.modal_layer()),
+                    ),
             )
+            .child(self.progress_panel.clone())
+            .when(self.show_nav_inspector, |this| {
+                this.child(NavInspector::new(self.app_state.clone()))
+            })
             .children(dialog_layer)
             .children(notification_layer)
     }
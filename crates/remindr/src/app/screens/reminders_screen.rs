@@ -0,0 +1,269 @@
+use std::collections::HashMap;
+
+use gpui::prelude::FluentBuilder;
+use gpui::{
+    AnyElement, App, Context, FontWeight, IntoElement, ParentElement, Render, SharedString, Styled,
+    WeakEntity, Window, actions, div, px,
+};
+use gpui_component::{
+    ActiveTheme, Icon, Sizable,
+    button::{Button, ButtonVariants},
+    h_flex,
+    label::Label,
+    menu::{DropdownMenu as _, PopupMenuItem},
+    scroll::ScrollableElement,
+    v_flex,
+};
+use gpui_nav::{Screen, ScreenContext};
+
+use crate::{
+    app::states::{
+        app_state::AppState, reminder_state::ReminderState, repository_state::RepositoryState,
+    },
+    domain::database::reminder::ReminderModel,
+};
+
+actions!(reminders, [OpenReminders]);
+
+/// Snooze delays offered for each reminder of the list.
+const SNOOZE_OPTIONS: [(&str, i64); 3] = [
+    ("10 minutes", 10 * 60),
+    ("1 hour", 60 * 60),
+    ("1 day", 24 * 60 * 60),
+];
+
+/// Upcoming and overdue reminders across all documents.
+pub struct RemindersScreen {
+    ctx: ScreenContext<AppState>,
+    initialized: bool,
+    /// Title and folder of the documents reminders point to.
+    documents: HashMap<i32, (String, Option<i32>)>,
+}
+
+impl Screen for RemindersScreen {
+    fn id(&self) -> &'static str {
+        "Reminders"
+    }
+}
+
+impl RemindersScreen {
+    pub fn new(app_state: WeakEntity<AppState>) -> Self {
+        Self {
+            ctx: ScreenContext::new(app_state),
+            initialized: false,
+            documents: HashMap::new(),
+        }
+    }
+
+    fn ensure_initialized(&mut self, cx: &mut Context<Self>) {
+        if self.initialized {
+            return;
+        }
+        self.initialized = true;
+
+        cx.observe_global::<ReminderState>(|_, cx| cx.notify())
+            .detach();
+        ReminderState::refresh(cx);
+
+        let repository = cx.global::<RepositoryState>().documents.clone();
+        cx.spawn(async move |this, cx| {
+            let documents = repository.get_documents().await?;
+
+            this.update(cx, |this, cx| {
+                this.documents = documents
+                    .into_iter()
+                    .map(|document| (document.id, (document.title, document.folder_id)))
+                    .collect();
+                cx.notify();
+            })?;
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
+    fn open_document(&self, document_id: i32, cx: &mut Context<Self>) {
+        let Some((title, folder_id)) = self.documents.get(&document_id).cloned() else {
+            return;
+        };
+
+        self.ctx.update(cx, |app_state, cx| {
+            app_state.open_document(document_id, title, folder_id, cx);
+        });
+    }
+
+    fn render_section(
+        &self,
+        title: &'static str,
+        reminders: Vec<&ReminderModel>,
+        overdue: bool,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let title_color = if overdue {
+            cx.theme().danger
+        } else {
+            cx.theme().muted_foreground
+        };
+
+        v_flex()
+            .gap_1()
+            .child(
+                Label::new(format!("{} ({})", title, reminders.len()))
+                    .text_xs()
+                    .font_weight(FontWeight::SEMIBOLD)
+                    .text_color(title_color),
+            )
+            .children(
+                reminders
+                    .into_iter()
+                    .map(|reminder| self.render_reminder(reminder, overdue, cx)),
+            )
+    }
+
+    fn render_reminder(
+        &self,
+        reminder: &ReminderModel,
+        overdue: bool,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let id = reminder.id;
+        let document_id = reminder.document_id;
+        let document_title = self
+            .documents
+            .get(&document_id)
+            .map(|(title, _)| title.clone())
+            .unwrap_or_else(|| "Untitled".to_string());
+
+        let mut details = vec![
+            document_title,
+            ReminderState::format_time(reminder.trigger_at(), cx),
+        ];
+        if reminder.snoozed_until.is_some() {
+            details.push("snoozed".to_string());
+        }
+        if let Some(recurrence) = reminder.recurrence() {
+            details.push(recurrence.label());
+        }
+
+        h_flex()
+            .w_full()
+            .gap_2()
+            .px_3()
+            .py_2()
+            .rounded(cx.theme().radius)
+            .border_1()
+            .border_color(cx.theme().border)
+            .child(
+                Icon::default()
+                    .path("icons/bell.svg")
+                    .small()
+                    .text_color(if overdue {
+                        cx.theme().danger
+                    } else {
+                        cx.theme().muted_foreground
+                    }),
+            )
+            .child(
+                v_flex()
+                    .flex_1()
+                    .min_w_0()
+                    .child(
+                        Label::new(reminder.title.clone())
+                            .text_sm()
+                            .text_color(cx.theme().foreground),
+                    )
+                    .child(
+                        Label::new(details.join(" · "))
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground),
+                    ),
+            )
+            .child(
+                Button::new(("open-reminder-document", id as usize))
+                    .ghost()
+                    .small()
+                    .label("Open")
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        this.open_document(document_id, cx);
+                    })),
+            )
+            .child(
+                Button::new(("snooze-reminder", id as usize))
+                    .ghost()
+                    .small()
+                    .label("Snooze")
+                    .dropdown_menu(move |menu, _, _| {
+                        let mut menu = menu.min_w(px(140.));
+                        for (label, seconds) in SNOOZE_OPTIONS {
+                            menu = menu.item(PopupMenuItem::new(label).on_click(
+                                move |_, _, cx: &mut App| {
+                                    ReminderState::snooze(
+                                        id,
+                                        chrono::Duration::seconds(seconds),
+                                        cx,
+                                    );
+                                },
+                            ));
+                        }
+                        menu
+                    }),
+            )
+            .child(
+                Button::new(("complete-reminder", id as usize))
+                    .ghost()
+                    .small()
+                    .icon(Icon::default().path("icons/check.svg"))
+                    .tooltip("Done")
+                    .on_click(move |_, _, cx| ReminderState::complete(id, cx)),
+            )
+            .child(
+                Button::new(("delete-reminder", id as usize))
+                    .ghost()
+                    .small()
+                    .icon(Icon::default().path("icons/trash-2.svg"))
+                    .tooltip("Delete")
+                    .on_click(move |_, _, cx| ReminderState::delete(id, cx)),
+            )
+            .into_any_element()
+    }
+}
+
+impl Render for RemindersScreen {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        self.ensure_initialized(cx);
+
+        let now = ReminderState::now();
+        let reminders = cx.global::<ReminderState>().reminders().to_vec();
+        let (overdue, upcoming): (Vec<_>, Vec<_>) = reminders
+            .iter()
+            .partition(|reminder| reminder.is_overdue(now));
+
+        v_flex()
+            .size_full()
+            .overflow_y_scrollbar()
+            .p_6()
+            .gap_4()
+            .child(
+                div()
+                    .text_xl()
+                    .font_weight(FontWeight::SEMIBOLD)
+                    .text_color(cx.theme().foreground)
+                    .child("Reminders"),
+            )
+            .when(reminders.is_empty(), |this| {
+                this.child(
+                    Label::new(SharedString::from(
+                        "No reminders yet. Add one to the current document from the command palette.",
+                    ))
+                    .text_sm()
+                    .text_color(cx.theme().muted_foreground),
+                )
+            })
+            .when(!overdue.is_empty(), |this| {
+                this.child(self.render_section("Overdue", overdue, true, cx))
+            })
+            .when(!upcoming.is_empty(), |this| {
+                this.child(self.render_section("Upcoming", upcoming, false, cx))
+            })
+    }
+}
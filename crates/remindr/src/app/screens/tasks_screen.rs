@@ -0,0 +1,276 @@
+use std::collections::HashMap;
+
+use chrono::Local;
+use gpui::prelude::FluentBuilder;
+use gpui::{
+    AnyElement, Context, FontWeight, InteractiveElement, IntoElement, ParentElement, Render,
+    SharedString, StatefulInteractiveElement, Styled, WeakEntity, Window, actions, div,
+};
+use gpui_component::{
+    ActiveTheme, Icon, Sizable,
+    button::{Button, ButtonVariants},
+    h_flex,
+    label::Label,
+    scroll::ScrollableElement,
+    v_flex,
+};
+use gpui_nav::{Screen, ScreenContext};
+use uuid::Uuid;
+
+use crate::{
+    app::{
+        components::nodes::todo::{data::TodoStatus, todo_node::TodoNode},
+        date_format::DateFormat,
+        states::{
+            app_state::AppState,
+            deletion_state::DeletionState,
+            error_state::{AppError, ErrorKind},
+            repository_state::RepositoryState,
+        },
+    },
+    domain::database::task::TaskModel,
+};
+
+actions!(tasks, [OpenTasks]);
+
+/// Open to-do blocks of every document, by due date.
+pub struct TasksScreen {
+    ctx: ScreenContext<AppState>,
+    initialized: bool,
+    tasks: Vec<TaskModel>,
+    /// Title and folder of the documents tasks belong to.
+    documents: HashMap<i32, (String, Option<i32>)>,
+}
+
+impl Screen for TasksScreen {
+    fn id(&self) -> &'static str {
+        "Tasks"
+    }
+}
+
+impl TasksScreen {
+    pub fn new(app_state: WeakEntity<AppState>) -> Self {
+        Self {
+            ctx: ScreenContext::new(app_state),
+            initialized: false,
+            tasks: Vec::new(),
+            documents: HashMap::new(),
+        }
+    }
+
+    fn ensure_initialized(&mut self, cx: &mut Context<Self>) {
+        if self.initialized {
+            return;
+        }
+        self.initialized = true;
+
+        self.load(cx);
+    }
+
+    fn load(&mut self, cx: &mut Context<Self>) {
+        let repositories = cx.global::<RepositoryState>();
+        let documents = repositories.documents.clone();
+        let tasks = repositories.tasks.clone();
+
+        cx.spawn(async move |this, cx| {
+            let mut tasks = tasks.get_open_tasks().await?;
+            let documents = documents.get_documents().await?;
+
+            this.update(cx, |this, cx| {
+                let deletions = cx.global::<DeletionState>();
+                tasks.retain(|task| !deletions.is_pending(task.document_id));
+                this.tasks = tasks;
+                this.documents = documents
+                    .into_iter()
+                    .filter(|document| !deletions.is_pending(document.id))
+                    .map(|document| (document.id, (document.title, document.folder_id)))
+                    .collect();
+                cx.notify();
+            })?;
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
+    fn open_task(&self, task: &TaskModel, cx: &mut Context<Self>) {
+        let Some((title, folder_id)) = self.documents.get(&task.document_id).cloned() else {
+            return;
+        };
+        let document_id = task.document_id;
+        let block_id = Uuid::parse_str(&task.block_id).ok();
+
+        self.ctx.update(cx, |app_state, cx| match block_id {
+            Some(block_id) => {
+                app_state.open_document_at_block(document_id, title, folder_id, block_id, cx)
+            }
+            None => app_state.open_document(document_id, title, folder_id, cx),
+        });
+    }
+
+    /// Marks the block of a task as done in its document.
+    fn complete_task(&mut self, task: &TaskModel, cx: &mut Context<Self>) {
+        let Ok(block_id) = Uuid::parse_str(&task.block_id) else {
+            return;
+        };
+        let document_id = task.document_id;
+
+        self.tasks
+            .retain(|other| other.document_id != document_id || other.block_id != task.block_id);
+        cx.notify();
+
+        let update = TodoNode::set_document_status(document_id, block_id, TodoStatus::Done, cx);
+        cx.spawn(async move |this, cx| {
+            if let Err(e) = update.await {
+                AppError::new(ErrorKind::Storage, "Failed to complete the task", &e).report();
+                // Lists the task again
+                let _ = this.update(cx, |this, cx| this.load(cx));
+            }
+        })
+        .detach();
+    }
+
+    fn render_section(
+        &self,
+        title: &'static str,
+        tasks: Vec<&TaskModel>,
+        overdue: bool,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        v_flex()
+            .gap_1()
+            .child(
+                Label::new(format!("{} ({})", title, tasks.len()))
+                    .text_xs()
+                    .font_weight(FontWeight::SEMIBOLD)
+                    .text_color(if overdue {
+                        cx.theme().danger
+                    } else {
+                        cx.theme().muted_foreground
+                    }),
+            )
+            .children(
+                tasks
+                    .into_iter()
+                    .map(|task| self.render_task(task, overdue, cx)),
+            )
+    }
+
+    fn render_task(&self, task: &TaskModel, overdue: bool, cx: &mut Context<Self>) -> AnyElement {
+        let document_title = self
+            .documents
+            .get(&task.document_id)
+            .map(|(title, _)| title.clone())
+            .unwrap_or_else(|| "Untitled".to_string());
+        let details = match task.due_date {
+            Some(due_date) => format!(
+                "{} · due {}",
+                document_title,
+                DateFormat::new(cx).short_date(due_date)
+            ),
+            None => document_title,
+        };
+        let id = SharedString::from(format!("task-{}-{}", task.document_id, task.block_id));
+        let open_task = task.clone();
+        let complete_task = task.clone();
+
+        h_flex()
+            .id(id.clone())
+            .w_full()
+            .gap_2()
+            .px_3()
+            .py_2()
+            .rounded(cx.theme().radius)
+            .border_1()
+            .border_color(cx.theme().border)
+            .cursor_pointer()
+            .hover(|this| this.bg(cx.theme().accent.opacity(0.3)))
+            .on_click(cx.listener(move |this, _, _, cx| this.open_task(&open_task, cx)))
+            .child(
+                Button::new(SharedString::from(format!("complete-{}", id)))
+                    .ghost()
+                    .xsmall()
+                    .icon(
+                        Icon::default()
+                            .path(TodoStatus::Todo.icon_path())
+                            .text_color(if overdue {
+                                cx.theme().danger
+                            } else {
+                                cx.theme().muted_foreground
+                            }),
+                    )
+                    .tooltip("Mark as done")
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        cx.stop_propagation();
+                        this.complete_task(&complete_task, cx);
+                    })),
+            )
+            .child(
+                v_flex()
+                    .flex_1()
+                    .min_w_0()
+                    .child(
+                        div()
+                            .text_sm()
+                            .truncate()
+                            .text_color(cx.theme().foreground)
+                            .child(task.content.clone()),
+                    )
+                    .child(
+                        Label::new(details)
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground),
+                    ),
+            )
+            .into_any_element()
+    }
+}
+
+impl Render for TasksScreen {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        self.ensure_initialized(cx);
+
+        let today = Local::now().date_naive();
+        let mut overdue = Vec::new();
+        let mut upcoming = Vec::new();
+        let mut undated = Vec::new();
+        for task in &self.tasks {
+            match task.due_date {
+                Some(due_date) if due_date < today => overdue.push(task),
+                Some(_) => upcoming.push(task),
+                None => undated.push(task),
+            }
+        }
+
+        v_flex()
+            .size_full()
+            .overflow_y_scrollbar()
+            .p_6()
+            .gap_4()
+            .child(
+                div()
+                    .text_xl()
+                    .font_weight(FontWeight::SEMIBOLD)
+                    .text_color(cx.theme().foreground)
+                    .child("My tasks"),
+            )
+            .when(self.tasks.is_empty(), |this| {
+                this.child(
+                    Label::new(
+                        "No open tasks. Type [] in a document to add one, and @YYYY-MM-DD to give it a due date.",
+                    )
+                    .text_sm()
+                    .text_color(cx.theme().muted_foreground),
+                )
+            })
+            .when(!overdue.is_empty(), |this| {
+                this.child(self.render_section("Overdue", overdue, true, cx))
+            })
+            .when(!upcoming.is_empty(), |this| {
+                this.child(self.render_section("Upcoming", upcoming, false, cx))
+            })
+            .when(!undated.is_empty(), |this| {
+                this.child(self.render_section("No due date", undated, false, cx))
+            })
+    }
+}
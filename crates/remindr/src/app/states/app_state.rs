@@ -1,19 +1,112 @@
-use gpui_nav::Navigator;
+use gpui::{BorrowAppContext, Context, WindowId};
+use gpui_nav::{Navigator, Screen};
+use uuid::Uuid;
 
+use crate::app::{
+    screens::{
+        activity_screen::ActivityScreen, board_screen::BoardScreen,
+        calendar_screen::CalendarScreen, collection_screen::CollectionScreen,
+        document_screen::DocumentScreen, logs_screen::LogsScreen,
+        reminders_screen::RemindersScreen, tasks_screen::TasksScreen,
+    },
+    states::document_state::DocumentState,
+};
+
+/// Navigation state of one main window.
 pub struct AppState {
     pub navigator: Navigator,
-}
-
-impl Default for AppState {
-    fn default() -> Self {
-        Self::new()
-    }
+    pub window_id: WindowId,
 }
 
 impl AppState {
-    pub fn new() -> Self {
+    pub fn new(window_id: WindowId) -> Self {
         Self {
             navigator: Navigator::new(),
+            window_id,
         }
     }
+
+    /// Opens a document tab in this window and shows the document screen.
+    pub fn open_document(
+        &mut self,
+        id: i32,
+        title: String,
+        folder_id: Option<i32>,
+        cx: &mut Context<Self>,
+    ) {
+        let window_id = self.window_id;
+        cx.update_global::<DocumentState, _>(|state, _| {
+            state.mark_recent(id);
+            state
+                .workspace_mut(window_id)
+                .open_document_in_folder(id, title, folder_id);
+        });
+
+        let document_screen = DocumentScreen::new(cx.weak_entity());
+        self.push(document_screen, cx);
+    }
+
+    /// Opens a document and focuses one of its blocks once it is loaded.
+    pub fn open_document_at_block(
+        &mut self,
+        id: i32,
+        title: String,
+        folder_id: Option<i32>,
+        block_id: Uuid,
+        cx: &mut Context<Self>,
+    ) {
+        self.open_document(id, title, folder_id, cx);
+
+        let window_id = self.window_id;
+        cx.update_global::<DocumentState, _>(|state, _| {
+            state.workspace_mut(window_id).focus_block(id, block_id);
+        });
+    }
+
+    /// Shows the statistics of the documents edited and blocks created each day.
+    pub fn open_activity(&mut self, cx: &mut Context<Self>) {
+        let activity_screen = ActivityScreen::new(cx.weak_entity());
+        self.push(activity_screen, cx);
+    }
+
+    /// Shows the to-do blocks of a folder or tag as a kanban board.
+    pub fn open_board(&mut self, cx: &mut Context<Self>) {
+        let board_screen = BoardScreen::new(cx.weak_entity());
+        self.push(board_screen, cx);
+    }
+
+    /// Shows the dated blocks and reminders of every document on a calendar.
+    pub fn open_calendar(&mut self, cx: &mut Context<Self>) {
+        let calendar_screen = CalendarScreen::new(cx.weak_entity());
+        self.push(calendar_screen, cx);
+    }
+
+    /// Shows the documents of a folder as a table of their properties.
+    pub fn open_collection(&mut self, folder_id: i32, cx: &mut Context<Self>) {
+        let collection_screen = CollectionScreen::new(cx.weak_entity(), folder_id);
+        self.push(collection_screen, cx);
+    }
+
+    /// Shows the last lines of the log file.
+    pub fn open_logs(&mut self, cx: &mut Context<Self>) {
+        let logs_screen = LogsScreen::new(cx.weak_entity());
+        self.push(logs_screen, cx);
+    }
+
+    /// Shows the upcoming and overdue reminders of every document.
+    pub fn open_reminders(&mut self, cx: &mut Context<Self>) {
+        let reminders_screen = RemindersScreen::new(cx.weak_entity());
+        self.push(reminders_screen, cx);
+    }
+
+    /// Lists the open to-do blocks of every document.
+    pub fn open_tasks(&mut self, cx: &mut Context<Self>) {
+        let tasks_screen = TasksScreen::new(cx.weak_entity());
+        self.push(tasks_screen, cx);
+    }
+
+    fn push<S: Screen>(&mut self, screen: S, cx: &mut Context<Self>) {
+        tracing::debug!("navigating to {}", screen.id());
+        self.navigator.push(screen, cx);
+    }
 }
@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+
+use gpui::{App, Entity, Global};
+use serde_json::Value;
+
+use crate::app::{components::rich_text::RichTextState, states::settings_state::Settings};
+
+/// Number of recently opened documents whose words feed the suggestions.
+const MAX_INDEXED_DOCUMENTS: usize = 10;
+
+/// Minimum number of typed characters before a word completion is offered.
+const MIN_PREFIX_LEN: usize = 3;
+
+/// Minimum number of occurrences before a phrase continuation is offered.
+const MIN_PHRASE_OCCURRENCES: usize = 2;
+
+#[derive(Default)]
+struct DocumentIndex {
+    words: HashMap<String, usize>,
+    next_words: HashMap<String, HashMap<String, usize>>,
+}
+
+impl DocumentIndex {
+    fn from_nodes(nodes: &[Value]) -> Self {
+        let mut index = Self::default();
+
+        for node in nodes {
+            let Some(content) = node
+                .get("metadata")
+                .and_then(|metadata| metadata.get("content"))
+                .and_then(Value::as_str)
+            else {
+                continue;
+            };
+
+            let words = split_words(content);
+            for (i, word) in words.iter().enumerate() {
+                *index.words.entry(word.to_string()).or_default() += 1;
+
+                if let Some(next) = words.get(i + 1) {
+                    *index
+                        .next_words
+                        .entry(word.to_lowercase())
+                        .or_default()
+                        .entry(next.to_string())
+                        .or_default() += 1;
+                }
+            }
+        }
+
+        index
+    }
+}
+
+/// Word and phrase index over the recently opened documents, used for inline autocompletion.
+#[derive(Default)]
+pub struct CompletionState {
+    /// Most recently indexed document first.
+    documents: Vec<(i32, DocumentIndex)>,
+}
+
+impl CompletionState {
+    /// (Re)indexes the nodes of a document and moves it to the front of the recent list.
+    pub fn index_document(&mut self, uid: i32, nodes: &[Value]) {
+        self.documents.retain(|(id, _)| *id != uid);
+        self.documents
+            .insert(0, (uid, DocumentIndex::from_nodes(nodes)));
+        self.documents.truncate(MAX_INDEXED_DOCUMENTS);
+    }
+
+    /// Returns the text to append after `text` as a ghost suggestion.
+    ///
+    /// A partially typed word is completed with the most frequent known word sharing
+    /// its prefix. After a space, the word that most often followed the previous one
+    /// is suggested instead.
+    pub fn suggest(&self, text: &str) -> Option<String> {
+        if text.ends_with(' ') {
+            let previous = split_words(text).last()?.to_lowercase();
+            return self.suggest_next_word(&previous);
+        }
+
+        let prefix = trailing_word(text);
+        if prefix.chars().count() < MIN_PREFIX_LEN {
+            return None;
+        }
+
+        let lowercase_prefix = prefix.to_lowercase();
+        let mut candidates: HashMap<&str, usize> = HashMap::new();
+        for (_, index) in &self.documents {
+            for (word, count) in &index.words {
+                if word.len() > prefix.len() && word.to_lowercase().starts_with(&lowercase_prefix) {
+                    *candidates.entry(word.as_str()).or_default() += count;
+                }
+            }
+        }
+
+        candidates
+            .into_iter()
+            .max_by(|(a, a_count), (b, b_count)| a_count.cmp(b_count).then(b.len().cmp(&a.len())))
+            .and_then(|(word, _)| word.get(prefix.len()..))
+            .map(str::to_string)
+    }
+
+    fn suggest_next_word(&self, previous: &str) -> Option<String> {
+        let mut candidates: HashMap<&str, usize> = HashMap::new();
+        for (_, index) in &self.documents {
+            if let Some(next_words) = index.next_words.get(previous) {
+                for (word, count) in next_words {
+                    *candidates.entry(word.as_str()).or_default() += count;
+                }
+            }
+        }
+
+        candidates
+            .into_iter()
+            .filter(|(_, count)| *count >= MIN_PHRASE_OCCURRENCES)
+            .max_by(|(a, a_count), (b, b_count)| a_count.cmp(b_count).then(b.cmp(a)))
+            .map(|(word, _)| word.to_string())
+    }
+
+    /// Recomputes the ghost suggestion shown in a rich text field after its content changed.
+    pub fn refresh_suggestion(rich_text: &Entity<RichTextState>, cx: &mut App) {
+        let enabled = cx
+            .try_global::<Settings>()
+            .map(|settings| settings.editor.autocomplete)
+            .unwrap_or(true);
+
+        let suggestion = {
+            let state = rich_text.read(cx);
+            let selection = state.selection();
+            let at_end = selection.is_empty() && selection.head() == state.content().len();
+
+            if enabled && at_end {
+                cx.try_global::<CompletionState>()
+                    .and_then(|completion| completion.suggest(state.content()))
+            } else {
+                None
+            }
+        };
+
+        rich_text.update(cx, |state, cx| {
+            state.set_suggestion(suggestion, cx);
+        });
+    }
+}
+
+fn split_words(text: &str) -> Vec<&str> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+fn trailing_word(text: &str) -> &str {
+    let start = text
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| c.is_alphanumeric() || *c == '\'')
+        .last()
+        .map(|(i, _)| i)
+        .unwrap_or(text.len());
+
+    &text[start..]
+}
+
+impl Global for CompletionState {}
@@ -0,0 +1,61 @@
+use std::collections::VecDeque;
+
+use chrono::{DateTime, Local};
+use gpui::Global;
+use serde_json::Value;
+
+/// Number of deleted blocks kept, the oldest being dropped first.
+const MAX_DELETED_BLOCKS: usize = 30;
+
+pub struct DeletedBlock {
+    pub data: Value,
+    /// Document the block was deleted from, none for blocks edited outside of a document.
+    pub document_id: Option<i32>,
+    pub deleted_at: DateTime<Local>,
+}
+
+/// Last blocks deleted from the editor during this session, newest first, to put one back
+/// without undoing the changes made since.
+#[derive(Default)]
+pub struct DeletedBlocksState {
+    blocks: VecDeque<DeletedBlock>,
+}
+
+impl DeletedBlocksState {
+    pub fn blocks(&self) -> &VecDeque<DeletedBlock> {
+        &self.blocks
+    }
+
+    /// Keeps deleted blocks, given in document order. Blocks without content, like the
+    /// empty line removed with backspace, aren't worth getting back.
+    pub fn keep(&mut self, document_id: Option<i32>, blocks: Vec<Value>) {
+        let deleted_at = Local::now();
+        for data in blocks.into_iter().filter(|block| !is_blank(block)) {
+            self.blocks.push_front(DeletedBlock {
+                data,
+                document_id,
+                deleted_at,
+            });
+        }
+        self.blocks.truncate(MAX_DELETED_BLOCKS);
+    }
+
+    pub fn take(&mut self, index: usize) -> Option<DeletedBlock> {
+        self.blocks.remove(index)
+    }
+
+    pub fn clear(&mut self) {
+        self.blocks.clear();
+    }
+}
+
+impl Global for DeletedBlocksState {}
+
+/// Textual block whose text is empty.
+fn is_blank(block: &Value) -> bool {
+    block
+        .get("metadata")
+        .and_then(|metadata| metadata.get("content"))
+        .and_then(Value::as_str)
+        .is_some_and(|content| content.trim().is_empty())
+}
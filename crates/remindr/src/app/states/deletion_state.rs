@@ -0,0 +1,123 @@
+use std::{collections::HashSet, time::Duration};
+
+use anyhow::{Error, anyhow};
+use gpui::{App, BorrowAppContext, Global, Task, Window};
+use smol::Timer;
+
+use crate::app::states::{
+    document_state::DocumentState,
+    error_state::{AppError, ErrorKind},
+    repository_state::RepositoryState,
+    toast_state::Toast,
+};
+
+/// Time during which a deletion can be undone.
+const UNDO_DELAY: Duration = Duration::from_secs(10);
+
+/// Documents deleted during this session but only removed from the storage once their
+/// deletion can't be undone anymore, or when quitting.
+#[derive(Default)]
+pub struct DeletionState {
+    pending: HashSet<i32>,
+}
+
+impl DeletionState {
+    /// Whether the document is deleted and waiting for the undo delay to expire.
+    pub fn is_pending(&self, id: i32) -> bool {
+        self.pending.contains(&id)
+    }
+
+    pub fn pending(&self) -> &HashSet<i32> {
+        &self.pending
+    }
+
+    /// Deletes right away the documents waiting for the undo delay to expire, when quitting.
+    pub fn delete_pending(cx: &mut App) -> Task<Result<(), Error>> {
+        let ids = cx.global::<DeletionState>().pending.clone();
+        if ids.is_empty() {
+            return Task::ready(Ok(()));
+        }
+        let repository = cx.global::<RepositoryState>().documents.clone();
+
+        cx.spawn(async move |cx| {
+            let mut failed = 0;
+            for id in ids.iter().copied() {
+                if let Err(e) = repository.delete_document(id).await {
+                    tracing::error!("failed to delete document {}: {:?}", id, e);
+                    failed += 1;
+                }
+            }
+            cx.update_global::<DeletionState, _>(|state, _| {
+                state.pending.retain(|id| !ids.contains(id));
+            });
+
+            if failed == 0 {
+                Ok(())
+            } else {
+                Err(anyhow!("{} deleted documents couldn't be removed", failed))
+            }
+        })
+    }
+
+    /// Hides the documents and deletes them once the undo toast, showing
+    /// `message`, expires.
+    pub fn delete_documents(ids: Vec<i32>, message: String, window: &mut Window, cx: &mut App) {
+        cx.update_global::<DeletionState, _>(|state, _| state.pending.extend(&ids));
+        cx.update_global::<DocumentState, _>(|state, _| {
+            for id in &ids {
+                state.remove_document(*id);
+            }
+        });
+
+        let toast = Toast::new(message).duration(UNDO_DELAY).action("Undo", {
+            let ids = ids.clone();
+            move |_, cx| {
+                cx.update_global::<DeletionState, _>(|state, _| {
+                    for id in &ids {
+                        state.pending.remove(id);
+                    }
+                });
+            }
+        });
+        let toast = toast.show(window, cx);
+
+        let repository = cx.global::<RepositoryState>().documents.clone();
+
+        cx.spawn(async move |cx| {
+            Timer::after(UNDO_DELAY).await;
+
+            let ids = cx.update(|cx| {
+                let state = cx.global::<DeletionState>();
+                ids.into_iter()
+                    .filter(|id| state.is_pending(*id))
+                    .collect::<Vec<_>>()
+            });
+
+            cx.update(|cx| toast.dismiss(cx));
+
+            for id in ids.iter().copied() {
+                if let Err(e) = repository.delete_document(id).await {
+                    let repository = repository.clone();
+                    AppError::new(ErrorKind::Storage, "Failed to delete a document", &e)
+                        .with_retry_operation(move || {
+                            let repository = repository.clone();
+                            async move { repository.delete_document(id).await }
+                        })
+                        .report();
+                }
+            }
+
+            // Only now, so the documents don't show up again until deleted
+            cx.update(|cx| {
+                cx.update_global::<DeletionState, _>(|state, _| {
+                    for id in &ids {
+                        state.pending.remove(id);
+                    }
+                });
+            });
+        })
+        .detach();
+    }
+}
+
+impl Global for DeletionState {}
@@ -0,0 +1,14 @@
+use gpui::{App, Global};
+
+/// Observed by the lists of documents, like the sidebar, to read the documents again when
+/// they are created or changed outside of them, e.g. through the local API.
+#[derive(Default)]
+pub struct DocumentListState;
+
+impl DocumentListState {
+    pub fn notify_changed(cx: &mut App) {
+        cx.update_global::<Self, _>(|_, _| {});
+    }
+}
+
+impl Global for DocumentListState {}
@@ -1,24 +1,55 @@
-use gpui::{App, AppContext, BorrowAppContext, Context, Entity, Global, Window};
+use anyhow::{Error, anyhow};
+use gpui::{
+    App, AppContext, BorrowAppContext, Context, Entity, Global, ScrollHandle, Task, Window,
+    WindowId,
+};
 use gpui_component::input::{InputEvent, InputState};
 use serde_json::Value;
+use std::collections::{HashMap, HashSet, hash_map::Entry};
 use std::time::{Duration, Instant};
 use tokio::time::sleep;
+use uuid::Uuid;
 
 use crate::{
     LoadingState,
     app::{
         components::{
+            confirm_dialog::ConfirmDialog,
+            conflict_dialog::ConflictDialog,
             node_renderer::NodeRenderer,
             nodes::{
                 element::{NodePayload, RemindrElement},
+                migration::stamp_version,
                 text::data::TextMetadata,
             },
         },
-        states::repository_state::RepositoryState,
+        states::{
+            completion_state::CompletionState,
+            deletion_state::DeletionState,
+            error_state::{AppError, ErrorKind},
+            node_state::NodeState,
+            preview_state::PreviewState,
+            recovery_journal::{JournalEntry, RecoveryJournal},
+            repository_state::RepositoryState,
+            settings_state::Settings,
+        },
     },
-    domain::database::document::DocumentModel,
+    domain::{database::document::DocumentModel, ports::DocumentRepositoryPort},
+    infrastructure::vault::file_vault::ExternalChangeConflict,
 };
 
+/// Attempts to save a document after the first failed one.
+const SAVE_RETRIES: u32 = 4;
+
+/// Wait before the first retry, doubled for each of the next ones.
+const SAVE_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Longest wait for the unsaved changes to be saved when quitting or closing a window.
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Pause in the changes after which the document is written to the recovery journal.
+const JOURNAL_DELAY: Duration = Duration::from_millis(300);
+
 /// Helper entity to handle title input events with proper subscription context
 pub struct TitleInputHandler {
     pub input_state: Entity<InputState>,
@@ -48,21 +79,15 @@ impl TitleInputHandler {
                             window,
                             cx,
                         );
-                        state.update(cx, |node_state, _| {
-                            node_state.insert_node_at(0, &node);
+                        state.update(cx, |node_state, cx| {
+                            node_state.insert_node_at(0, &node, cx);
                         });
                     });
                 }
                 InputEvent::Change => {
                     let new_title = input_state_for_closure.read(cx).value().to_string();
                     cx.update_global::<DocumentState, _>(|doc_state, cx| {
-                        if let Some(doc) = doc_state
-                            .documents
-                            .iter_mut()
-                            .find(|d| d.uid == document_id)
-                        {
-                            doc.title = new_title;
-                        }
+                        doc_state.rename_document(document_id, &new_title);
                         doc_state.mark_changed(window, cx);
                     });
                 }
@@ -87,6 +112,8 @@ pub struct OpenedDocument {
     pub state: LoadingState<DocumentContent>,
     /// Indicates if a loading task is currently in progress
     pub loading_in_progress: bool,
+    /// Block to focus once the document is displayed, e.g. when jumping to a task.
+    pub focus_block: Option<Uuid>,
 }
 
 #[derive(Clone)]
@@ -94,24 +121,40 @@ pub struct DocumentContent {
     pub nodes: Vec<Value>,
     pub renderer: Entity<NodeRenderer>,
     pub title_input: Entity<InputState>,
+    /// Scroll of the blocks, followed by the caret in typewriter scrolling.
+    pub scroll_handle: ScrollHandle,
     _title_handler: Entity<TitleInputHandler>,
 }
 
-#[derive(Clone, PartialEq)]
+/// Whether the changes to a document are saved.
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum PersistenceState {
+    /// Edited since its last successful save, the save may be under way.
     Pending,
     Idle,
 }
 
-pub struct DocumentState {
+/// Autosave of a document edited since its last successful save.
+struct DocumentPersistence {
+    last_change: Instant,
+    /// Set while the document is written.
+    saving: bool,
+    /// Waits for the autosave delay. Replaced on each change, which cancels the previous
+    /// one, so that only the last change of a burst saves the document.
+    _autosave: Task<()>,
+    /// Waits for a pause in the changes to write the recovery journal, replaced like
+    /// `_autosave`.
+    _journal: Task<()>,
+}
+
+/// Tabs and focused document of one main window.
+#[derive(Clone, Default)]
+pub struct DocumentWorkspace {
     pub documents: Vec<OpenedDocument>,
     pub current_opened_document: Option<i32>,
-
-    pub persistence: PersistenceState,
-    pub last_change: Option<Instant>,
 }
 
-impl DocumentState {
+impl DocumentWorkspace {
     pub fn get_current_document(&self) -> Option<&OpenedDocument> {
         self.current_opened_document
             .and_then(|id| self.documents.iter().find(|doc| doc.uid == id))
@@ -150,11 +193,196 @@ impl DocumentState {
                 folder_id,
                 state: LoadingState::Loading,
                 loading_in_progress: false,
+                focus_block: None,
             });
         }
         self.current_opened_document = Some(id);
     }
 
+    /// Requests the focus of a block of an opened document, applied once it is loaded.
+    pub fn focus_block(&mut self, uid: i32, block_id: Uuid) {
+        if let Some(doc) = self.documents.iter_mut().find(|d| d.uid == uid) {
+            doc.focus_block = Some(block_id);
+        }
+    }
+
+    /// Takes the pending block focus of the current document if its content is loaded.
+    pub fn take_focus_block(&mut self) -> Option<(DocumentContent, Uuid)> {
+        let uid = self.current_opened_document?;
+        let doc = self.documents.iter_mut().find(|d| d.uid == uid)?;
+        let LoadingState::Loaded(content) = &doc.state else {
+            return None;
+        };
+        let block_id = doc.focus_block.take()?;
+
+        Some((content.clone(), block_id))
+    }
+
+    /// Apply pre-created document content to a document
+    pub fn apply_document_content(&mut self, uid: i32, content: DocumentContent) {
+        if let Some(doc) = self.documents.iter_mut().find(|d| d.uid == uid) {
+            doc.state = LoadingState::Loaded(content);
+        }
+    }
+
+    /// Set error state for a document
+    pub fn set_document_error(&mut self, uid: i32, error: String) {
+        if let Some(doc) = self.documents.iter_mut().find(|d| d.uid == uid) {
+            doc.state = LoadingState::Error(error);
+        }
+    }
+
+    /// Check if a document needs loading (is in Loading state and no loading task is in progress)
+    pub fn needs_loading(&self, uid: i32) -> bool {
+        self.documents
+            .iter()
+            .find(|d| d.uid == uid)
+            .map(|d| matches!(d.state, LoadingState::Loading) && !d.loading_in_progress)
+            .unwrap_or(false)
+    }
+
+    /// Mark a document as having a loading task in progress
+    pub fn set_loading_in_progress(&mut self, uid: i32, in_progress: bool) {
+        if let Some(doc) = self.documents.iter_mut().find(|d| d.uid == uid) {
+            doc.loading_in_progress = in_progress;
+        }
+    }
+
+    pub fn remove_document(&mut self, uid: i32) {
+        self.documents.retain(|element| element.uid != uid);
+    }
+}
+
+pub struct DocumentState {
+    /// Opened documents of each main window.
+    workspaces: HashMap<WindowId, DocumentWorkspace>,
+
+    /// Documents edited since their last successful save.
+    persistence: HashMap<i32, DocumentPersistence>,
+    /// Documents waiting for the user to merge them with the version on disk.
+    conflicting_documents: HashSet<i32>,
+    /// Opened documents, most recently opened first.
+    recent_documents: Vec<i32>,
+    /// Block ids of the documents as of their last save in this session, to count the
+    /// blocks created by the next one.
+    saved_blocks: HashMap<i32, HashSet<String>>,
+}
+
+impl DocumentState {
+    pub fn workspace(&self, window_id: WindowId) -> Option<&DocumentWorkspace> {
+        self.workspaces.get(&window_id)
+    }
+
+    pub fn workspace_mut(&mut self, window_id: WindowId) -> &mut DocumentWorkspace {
+        self.workspaces.entry(window_id).or_default()
+    }
+
+    /// Drops the tabs of a closed window.
+    pub fn close_workspace(&mut self, window_id: WindowId) {
+        self.workspaces.remove(&window_id);
+    }
+
+    /// Finds a document opened in any window.
+    pub fn find_document(&self, uid: i32) -> Option<&OpenedDocument> {
+        self.workspaces
+            .values()
+            .flat_map(|workspace| workspace.documents.iter())
+            .find(|doc| doc.uid == uid)
+    }
+
+    /// Moves a document to the front of the recently opened documents.
+    pub fn mark_recent(&mut self, uid: i32) {
+        self.recent_documents.retain(|id| *id != uid);
+        self.recent_documents.insert(0, uid);
+    }
+
+    pub fn recent_documents(&self) -> &[i32] {
+        &self.recent_documents
+    }
+
+    /// Blocks of the document in the first window where it is loaded, with that window.
+    pub fn loaded_nodes(&self, uid: i32, cx: &App) -> Option<(WindowId, Entity<NodeState>)> {
+        self.workspaces.iter().find_map(|(window_id, workspace)| {
+            workspace
+                .documents
+                .iter()
+                .filter(|doc| doc.uid == uid)
+                .find_map(|doc| match &doc.state {
+                    LoadingState::Loaded(content) => {
+                        Some((*window_id, content.renderer.read(cx).state.clone()))
+                    }
+                    _ => None,
+                })
+        })
+    }
+
+    /// Appends blocks at the end of the document where it is loaded, so that they are saved
+    /// along with its unsaved edits rather than overwritten by them. Returns whether the
+    /// document was loaded.
+    pub fn append_to_loaded(uid: i32, blocks: &[Value], cx: &mut App) -> bool {
+        let Some((window_id, state)) = cx.global::<DocumentState>().loaded_nodes(uid, cx) else {
+            return false;
+        };
+        let Some(window) = cx
+            .windows()
+            .into_iter()
+            .find(|window| window.window_id() == window_id)
+        else {
+            return false;
+        };
+
+        window
+            .update(cx, |_, window, cx| {
+                state.update(cx, |node_state, cx| {
+                    for block in blocks {
+                        let node = node_state.parse_node(block, &state, window, cx);
+                        let index = node_state.get_nodes().len();
+                        node_state.insert_node_at(index, &node, cx);
+                    }
+                });
+            })
+            .is_ok()
+    }
+
+    /// Whether a document has changes waiting to be saved.
+    pub fn persistence(&self, uid: i32) -> PersistenceState {
+        if self.persistence.contains_key(&uid) {
+            PersistenceState::Pending
+        } else {
+            PersistenceState::Idle
+        }
+    }
+
+    /// Whether a document is being written.
+    pub fn is_saving(&self, uid: i32) -> bool {
+        self.persistence
+            .get(&uid)
+            .is_some_and(|persistence| persistence.saving)
+    }
+
+    /// Closes the tabs of a deleted document in every window.
+    pub fn remove_document(&mut self, uid: i32) {
+        self.recent_documents.retain(|id| *id != uid);
+        for workspace in self.workspaces.values_mut() {
+            workspace.remove_document(uid);
+            if workspace.current_opened_document == Some(uid) {
+                workspace.current_opened_document = None;
+            }
+        }
+    }
+
+    /// Updates the tab title of a document in every window.
+    pub fn rename_document(&mut self, uid: i32, title: &str) {
+        for doc in self
+            .workspaces
+            .values_mut()
+            .flat_map(|workspace| workspace.documents.iter_mut())
+            .filter(|doc| doc.uid == uid)
+        {
+            doc.title = title.to_string();
+        }
+    }
+
     /// Create document content (entities) - call this outside of update_global
     pub fn create_document_content(
         uid: i32,
@@ -163,9 +391,13 @@ impl DocumentState {
         cx: &mut App,
     ) -> DocumentContent {
         let nodes = document.content.as_array().cloned().unwrap_or_default();
+        cx.update_global::<CompletionState, _>(|completion, _| {
+            completion.index_document(uid, &nodes);
+        });
 
-        let renderer = NodeRenderer::new(nodes.clone(), window, cx);
-        let renderer = cx.new(|_| renderer);
+        let renderer = cx.new(|cx| NodeRenderer::new(nodes.clone(), window, cx));
+        let state = renderer.read(cx).state.clone();
+        state.update(cx, |state, _| state.document_id = Some(uid));
 
         // Create title input state
         let title = document.title.clone();
@@ -185,67 +417,236 @@ impl DocumentState {
             nodes,
             renderer,
             title_input,
+            scroll_handle: ScrollHandle::new(),
             _title_handler: title_handler,
         }
     }
 
-    /// Apply pre-created document content to a document
-    pub fn apply_document_content(&mut self, uid: i32, content: DocumentContent) {
-        if let Some(doc) = self.documents.iter_mut().find(|d| d.uid == uid) {
-            doc.state = LoadingState::Loaded(content);
-        }
+    /// Drops the unsaved changes of a document whose stored content was replaced, so that
+    /// its next autosave doesn't overwrite it.
+    pub fn discard_changes(&mut self, uid: i32) {
+        self.persistence.remove(&uid);
+        self.conflicting_documents.remove(&uid);
+        RecoveryJournal::remove(uid);
     }
 
-    /// Set the loaded content for a document (legacy - combines create and apply)
-    pub fn set_document_content(
-        &mut self,
-        uid: i32,
-        document: DocumentModel,
-        window: &mut Window,
-        cx: &mut App,
-    ) {
-        let content = Self::create_document_content(uid, &document, window, cx);
-        self.apply_document_content(uid, content);
+    /// Puts the given opened documents back in the loading state so their content is read
+    /// again. Documents with unsaved changes are left alone, they go through the conflict
+    /// dialog instead.
+    pub fn reload_documents(&mut self, uids: &[i32]) {
+        for doc in self
+            .workspaces
+            .values_mut()
+            .flat_map(|workspace| workspace.documents.iter_mut())
+            .filter(|doc| uids.contains(&doc.uid))
+        {
+            if self.persistence.contains_key(&doc.uid)
+                || self.conflicting_documents.contains(&doc.uid)
+            {
+                continue;
+            }
+            if matches!(doc.state, LoadingState::Loaded(_)) && !doc.loading_in_progress {
+                doc.state = LoadingState::Loading;
+            }
+        }
     }
 
-    /// Set error state for a document
-    pub fn set_document_error(&mut self, uid: i32, error: String) {
-        if let Some(doc) = self.documents.iter_mut().find(|d| d.uid == uid) {
-            doc.state = LoadingState::Error(error);
+    /// Reloads the copies of a document opened in other windows than the one it was
+    /// saved from.
+    fn reload_in_other_workspaces(&mut self, uid: i32, saved_from: WindowId) {
+        for doc in self
+            .workspaces
+            .iter_mut()
+            .filter(|(window_id, _)| **window_id != saved_from)
+            .flat_map(|(_, workspace)| workspace.documents.iter_mut())
+            .filter(|doc| doc.uid == uid)
+        {
+            if matches!(doc.state, LoadingState::Loaded(_)) && !doc.loading_in_progress {
+                doc.state = LoadingState::Loading;
+            }
         }
     }
 
-    /// Check if a document needs loading (is in Loading state and no loading task is in progress)
-    pub fn needs_loading(&self, uid: i32) -> bool {
-        self.documents
+    /// Polls the file vault, if any, and reloads opened documents edited outside Remindr.
+    pub fn watch_vault(cx: &mut App) {
+        cx.spawn(async move |cx| {
+            loop {
+                sleep(Duration::from_secs(2)).await;
+
+                let vault = cx.update(|cx| {
+                    cx.try_global::<RepositoryState>()
+                        .and_then(|repositories| repositories.vault.clone())
+                });
+                let Some(vault) = vault else {
+                    continue;
+                };
+
+                let changes = cx
+                    .background_executor()
+                    .spawn(async move { vault.poll_external_changes() })
+                    .await;
+
+                match changes {
+                    Ok(uids) if !uids.is_empty() => {
+                        cx.update(|cx| {
+                            let conflicts = cx.update_global::<DocumentState, _>(|state, cx| {
+                                let conflicts = state.unsaved_versions(&uids, cx);
+                                state.reload_documents(&uids);
+                                conflicts
+                            });
+                            for local in conflicts {
+                                Self::show_conflict(local, cx);
+                            }
+                            cx.refresh_windows();
+                        });
+                    }
+                    Ok(_) => {}
+                    Err(e) => tracing::error!("failed to poll vault: {:?}", e),
+                }
+            }
+        })
+        .detach();
+    }
+
+    /// Blocks of a document about to be saved that were not in its previous version.
+    fn blocks_created(&self, uid: i32, current: &HashSet<String>) -> i32 {
+        let created = match self.saved_blocks.get(&uid) {
+            Some(saved) => current.difference(saved).count(),
+            None => {
+                let loaded = self
+                    .find_document(uid)
+                    .and_then(|doc| match &doc.state {
+                        LoadingState::Loaded(content) => Some(block_ids(&content.nodes)),
+                        _ => None,
+                    })
+                    .unwrap_or_default();
+                current.difference(&loaded).count()
+            }
+        };
+        created as i32
+    }
+
+    /// Blocks shown by every loaded tab, including the changes not saved yet.
+    pub fn opened_blocks(&self, cx: &mut App) -> Vec<Value> {
+        self.workspaces
+            .values()
+            .flat_map(|workspace| workspace.documents.iter())
+            .filter_map(|doc| match &doc.state {
+                LoadingState::Loaded(content) => Some(content.renderer.clone()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
             .iter()
-            .find(|d| d.uid == uid)
-            .map(|d| matches!(d.state, LoadingState::Loading) && !d.loading_in_progress)
-            .unwrap_or(false)
+            .flat_map(|renderer| document_nodes(renderer, cx))
+            .collect()
     }
 
-    /// Mark a document as having a loading task in progress
-    pub fn set_loading_in_progress(&mut self, uid: i32, in_progress: bool) {
-        if let Some(doc) = self.documents.iter_mut().find(|d| d.uid == uid) {
-            doc.loading_in_progress = in_progress;
+    /// Local versions of the given documents when they have unsaved changes and no conflict
+    /// is already being resolved for them.
+    fn unsaved_versions(&self, uids: &[i32], cx: &mut App) -> Vec<DocumentModel> {
+        uids.iter()
+            .filter(|uid| {
+                self.persistence.contains_key(uid) && !self.conflicting_documents.contains(uid)
+            })
+            .filter_map(|uid| self.find_document(*uid))
+            .filter_map(|doc| match &doc.state {
+                LoadingState::Loaded(content) => Some(DocumentModel {
+                    id: doc.uid,
+                    title: doc.title.clone(),
+                    content: Value::from_iter(document_nodes(&content.renderer, cx)),
+                    folder_id: doc.folder_id,
+                }),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Pauses saving for the document and asks the user to merge the local version with
+    /// the one on disk.
+    fn show_conflict(local: DocumentModel, cx: &mut App) {
+        let uid = local.id;
+        let already_shown = cx
+            .update_global::<DocumentState, _>(|state, _| !state.conflicting_documents.insert(uid));
+        if already_shown {
+            return;
         }
+
+        let documents = cx.global::<RepositoryState>().documents.clone();
+
+        cx.spawn(async move |cx| {
+            let external = match documents.get_document_by_id(uid).await {
+                Ok(external) => external,
+                Err(e) => {
+                    tracing::error!("failed to read conflicting document: {:?}", e);
+                    cx.update_global::<DocumentState, _>(|state, _| {
+                        state.conflicting_documents.remove(&uid);
+                    });
+                    return;
+                }
+            };
+
+            cx.update(|cx| {
+                let window = cx.active_window().or_else(|| cx.windows().first().copied());
+                if let Some(window) = window {
+                    let _ = window.update(cx, |_, window, cx| {
+                        ConflictDialog::open(local, external, window, cx);
+                    });
+                }
+            });
+        })
+        .detach();
     }
 
-    pub fn remove_document(&mut self, uid: i32) {
-        self.documents.retain(|element| element.uid != uid);
+    /// Saves the version picked in the conflict dialog and reloads the document from it.
+    pub fn resolve_conflict(document: DocumentModel, cx: &mut App) {
+        let documents = cx.global::<RepositoryState>().documents.clone();
+        let uid = document.id;
+
+        cx.spawn(async move |cx| {
+            let result = documents.update_document(document).await;
+            if result.is_ok() {
+                RecoveryJournal::remove(uid);
+            }
+
+            cx.update_global::<DocumentState, _>(|state, cx| {
+                state.conflicting_documents.remove(&uid);
+                state.persistence.remove(&uid);
+                state.reload_documents(&[uid]);
+                cx.refresh_windows();
+            });
+
+            result
+        })
+        .detach();
     }
 
-    pub fn mark_changed(&mut self, _: &mut Window, cx: &mut App) {
-        let trigger_time = Instant::now();
+    /// Saves the current document of the window once the autosave delay passes without
+    /// another change, e.g. after its title was edited.
+    pub fn mark_changed(&mut self, window: &mut Window, cx: &mut App) {
+        let window_id = window.window_handle().window_id();
+        let uid = self
+            .workspace(window_id)
+            .and_then(|workspace| workspace.get_current_document())
+            .map(|document| document.uid);
 
-        self.last_change = Some(trigger_time);
+        if let Some(uid) = uid {
+            self.mark_document_changed(uid, window_id, cx);
+        }
+    }
 
-        let documents = cx.global::<RepositoryState>().documents.clone();
+    /// Saves a document opened in the window once the autosave delay passes without
+    /// another change. Its blocks report their changes through their `DocumentEvent`s.
+    /// Only needs the id of the window, so that changes made outside of it are saved too.
+    pub fn mark_document_changed(&mut self, uid: i32, window_id: WindowId, cx: &mut App) {
+        let trigger_time = Instant::now();
+
+        let repositories = cx.global::<RepositoryState>();
+        let documents = repositories.documents.clone();
+        let activity = repositories.activity.clone();
 
         let document = self
-            .documents
-            .iter()
-            .find(|doc| Some(doc.uid) == self.current_opened_document)
+            .workspace(window_id)
+            .and_then(|workspace| workspace.documents.iter().find(|doc| doc.uid == uid))
             .cloned();
 
         if let Some(document) = document
@@ -255,68 +656,538 @@ impl DocumentState {
             let doc_uid = document.uid;
             let doc_title = document.title.clone();
             let doc_folder_id = document.folder_id;
+            // settings.json can be edited by hand, so the delay isn't trusted to be valid
+            let delay = Duration::try_from_secs_f32(cx.global::<Settings>().editor.autosave_delay)
+                .unwrap_or(Duration::from_secs(1));
 
-            cx.spawn(async move |cx| {
-                sleep(Duration::from_secs(1)).await;
+            // Kept until saved, so the change survives a crash during the delay. Written
+            // once typing pauses rather than on each keystroke, and off the main thread.
+            let journal = if RecoveryJournal::is_enabled(cx) {
+                let renderer = renderer.clone();
+                let title = doc_title.clone();
+                cx.spawn(async move |cx| {
+                    sleep(JOURNAL_DELAY).await;
+
+                    let document = cx.update(|cx| {
+                        if !cx
+                            .global::<DocumentState>()
+                            .persistence
+                            .contains_key(&doc_uid)
+                        {
+                            // Saved in the meantime
+                            return None;
+                        }
+                        let state = renderer.read(cx).state.clone();
+                        let nodes = state.read(cx).get_nodes().clone();
+                        let blocks = nodes
+                            .iter()
+                            .map(|node| node.element.get_data(cx))
+                            .collect::<Vec<_>>();
+                        let document = DocumentModel {
+                            id: doc_uid,
+                            title,
+                            content: Value::from_iter(blocks),
+                            folder_id: doc_folder_id,
+                        };
+                        Some((document, Instant::now()))
+                    });
+                    // Not written if the save removes the journal before the writer gets to it
+                    if let Some((document, taken_at)) = document {
+                        smol::unblock(move || RecoveryJournal::record(&document, taken_at)).await;
+                    }
+                })
+            } else {
+                Task::ready(())
+            };
+
+            let autosave = cx.spawn(async move |cx| {
+                sleep(delay).await;
 
                 cx.update_global::<DocumentState, _>(move |state, cx| {
-                    if let Some(last) = state.last_change
-                        && last <= trigger_time
+                    if !state.conflicting_documents.contains(&doc_uid)
+                        && let Some(persistence) = state.persistence.get_mut(&doc_uid)
                     {
                         // Debounce expired, start saving
-                        state.persistence = PersistenceState::Pending;
+                        persistence.saving = true;
                         cx.refresh_windows();
 
-                        let nodes = {
-                            let nodes = renderer.read(cx).state.clone();
-                            let nodes = nodes.read(cx).get_nodes().clone();
-                            nodes
-                                .iter()
-                                .map(|node| node.element.get_data(cx))
-                                .collect::<Vec<_>>()
-                        };
+                        let nodes = document_nodes(&renderer, cx);
+                        let block_ids = block_ids(&nodes);
+                        let blocks_created = state.blocks_created(doc_uid, &block_ids);
+
+                        cx.update_global::<CompletionState, _>(|completion, _| {
+                            completion.index_document(doc_uid, &nodes);
+                        });
 
                         let document_model = DocumentModel {
                             id: doc_uid,
-                            title: doc_title,
+                            title: doc_title.clone(),
                             content: Value::from_iter(nodes),
                             folder_id: doc_folder_id,
                         };
 
                         cx.spawn(async move |cx| {
-                            let result = documents.update_document(document_model).await;
+                            let result =
+                                save_with_retries(&*documents, &document_model, SAVE_RETRY_DELAY)
+                                    .await;
+
+                            if result.is_ok()
+                                && let Err(e) =
+                                    activity.record_activity(doc_uid, blocks_created).await
+                            {
+                                tracing::error!(
+                                    "failed to record activity of document {}: {:?}",
+                                    doc_uid,
+                                    e
+                                );
+                            }
 
                             // Minimum display time for the loader
                             sleep(Duration::from_secs(1)).await;
 
                             // Mark as idle when save completes
                             cx.update_global::<DocumentState, _>(|state, cx| {
-                                state.persistence = PersistenceState::Idle;
+                                if let Some(persistence) = state.persistence.get_mut(&doc_uid) {
+                                    persistence.saving = false;
+                                }
+                                if result.is_ok() {
+                                    cx.update_global::<PreviewState, _>(|previews, _| {
+                                        previews.invalidate(doc_uid);
+                                    });
+                                    state.saved_blocks.insert(doc_uid, block_ids);
+                                    // Unless the document changed again while it was saved
+                                    if state.persistence.get(&doc_uid).is_none_or(|persistence| {
+                                        persistence.last_change <= trigger_time
+                                    }) {
+                                        state.persistence.remove(&doc_uid);
+                                        RecoveryJournal::remove(doc_uid);
+                                    }
+                                    state.reload_in_other_workspaces(doc_uid, window_id);
+                                }
                                 cx.refresh_windows();
                             });
 
-                            result
+                            match result {
+                                // The file changed on disk since it was loaded
+                                Err(e) if e.is::<ExternalChangeConflict>() => {
+                                    cx.update(|cx| Self::show_conflict(document_model, cx));
+                                }
+                                Err(e) => Self::report_save_failure(doc_uid, &doc_title, &e),
+                                Ok(()) => {}
+                            }
                         })
                         .detach();
                     }
                 });
+            });
 
-                Ok::<_, anyhow::Error>(())
+            match self.persistence.entry(doc_uid) {
+                Entry::Occupied(mut entry) => {
+                    let persistence = entry.get_mut();
+                    persistence.last_change = trigger_time;
+                    persistence._autosave = autosave;
+                    persistence._journal = journal;
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(DocumentPersistence {
+                        last_change: trigger_time,
+                        saving: false,
+                        _autosave: autosave,
+                        _journal: journal,
+                    });
+                    // Shows the unsaved indicator of the tabs and the sidebar
+                    cx.refresh_windows();
+                }
+            }
+        }
+    }
+
+    fn report_save_failure(uid: i32, title: &str, error: &anyhow::Error) {
+        AppError::new(
+            ErrorKind::Storage,
+            format!("Failed to save “{}”", title),
+            error,
+        )
+        .with_retry(move |cx| Self::retry_save(uid, cx))
+        .report();
+    }
+
+    /// Whether documents open in the window, or in any window, have changes not saved yet.
+    pub fn has_unsaved_changes(&self, window_id: Option<WindowId>) -> bool {
+        self.unsaved_uids(window_id).next().is_some()
+    }
+
+    fn unsaved_uids(&self, window_id: Option<WindowId>) -> impl Iterator<Item = i32> + '_ {
+        self.persistence.keys().copied().filter(move |uid| {
+            window_id.is_none_or(|window_id| {
+                self.workspace(window_id)
+                    .is_some_and(|workspace| workspace.documents.iter().any(|doc| doc.uid == *uid))
+            })
+        })
+    }
+
+    /// Saves right away the unsaved changes of the documents open in the window, or in any
+    /// window, instead of waiting for the autosave delay. Fails when a save fails or takes
+    /// longer than `FLUSH_TIMEOUT`. Documents waiting for a conflict to be resolved are
+    /// left to the recovery journal.
+    pub fn flush_unsaved(window_id: Option<WindowId>, cx: &mut App) -> Task<Result<(), Error>> {
+        let documents = cx.update_global::<DocumentState, _>(|state, cx| {
+            let uids = state.unsaved_uids(window_id).collect::<Vec<_>>();
+            state.unsaved_versions(&uids, cx)
+        });
+        let repository = cx.global::<RepositoryState>().documents.clone();
+
+        cx.spawn(async move |cx| {
+            let save = async {
+                let mut failed = Vec::new();
+                for document in documents {
+                    let uid = document.id;
+                    let title = document.title.clone();
+                    match repository.update_document(document).await {
+                        Ok(()) => {
+                            RecoveryJournal::remove(uid);
+                            cx.update_global::<DocumentState, _>(|state, cx| {
+                                state.persistence.remove(&uid);
+                                cx.refresh_windows();
+                            });
+                        }
+                        Err(e) => {
+                            tracing::error!("failed to save document {}: {:?}", uid, e);
+                            failed.push(format!("“{}”", title));
+                        }
+                    }
+                }
+
+                if failed.is_empty() {
+                    Ok(())
+                } else {
+                    Err(anyhow!("{} couldn't be saved", failed.join(", ")))
+                }
+            };
+            let timeout = async {
+                sleep(FLUSH_TIMEOUT).await;
+                Err(anyhow!("Saving took longer than {:?}", FLUSH_TIMEOUT))
+            };
+
+            smol::future::or(save, timeout).await
+        })
+    }
+
+    /// Quits once the unsaved changes are saved and the documents waiting for their undo
+    /// delay deleted. When the changes can't be saved, asks whether to quit anyway, which
+    /// keeps them in the recovery journal for the next launch.
+    pub fn quit(cx: &mut App) {
+        let deletions = DeletionState::delete_pending(cx);
+        if !cx.global::<DocumentState>().has_unsaved_changes(None) {
+            cx.spawn(async move |cx| {
+                if let Err(e) = deletions.await {
+                    tracing::error!("failed to delete documents when quitting: {:?}", e);
+                }
+                cx.update(|cx| cx.quit());
             })
             .detach();
+            return;
         }
+
+        let flush = Self::flush_unsaved(None, cx);
+        cx.spawn(async move |cx| {
+            if let Err(e) = deletions.await {
+                tracing::error!("failed to delete documents when quitting: {:?}", e);
+            }
+            let result = flush.await;
+            cx.update(|cx| match result {
+                Ok(()) => cx.quit(),
+                Err(e) => {
+                    let window = cx.active_window().or_else(|| cx.windows().first().copied());
+                    let Some(window) = window else {
+                        cx.quit();
+                        return;
+                    };
+                    let _ = window.update(cx, |_, window, cx| {
+                        Self::confirm_unsaved_close("Quit anyway", e, window, cx, |_, cx| {
+                            cx.quit()
+                        });
+                    });
+                }
+            });
+        })
+        .detach();
+    }
+
+    /// Lets a main window close once the unsaved changes of its documents are saved.
+    /// Returns whether it can close right away.
+    pub fn close_window(window: &mut Window, cx: &mut App) -> bool {
+        let window_id = window.window_handle().window_id();
+        if !cx
+            .global::<DocumentState>()
+            .has_unsaved_changes(Some(window_id))
+        {
+            return true;
+        }
+
+        let window_handle = window.window_handle();
+        let flush = Self::flush_unsaved(Some(window_id), cx);
+        cx.spawn(async move |cx| {
+            let result = flush.await;
+            cx.update_window(window_handle, |_, window, cx| match result {
+                Ok(()) => window.remove_window(),
+                Err(e) => {
+                    Self::confirm_unsaved_close("Close anyway", e, window, cx, |window, _| {
+                        window.remove_window()
+                    })
+                }
+            })?;
+
+            Ok::<_, Error>(())
+        })
+        .detach();
+
+        false
+    }
+
+    fn confirm_unsaved_close(
+        confirm_text: &str,
+        error: Error,
+        window: &mut Window,
+        cx: &mut App,
+        close: impl Fn(&mut Window, &mut App) + 'static,
+    ) {
+        ConfirmDialog::new("Unsaved changes")
+            .message(format!(
+                "{}. The changes are kept and offered again on the next launch.",
+                error
+            ))
+            .confirm_text(confirm_text)
+            .cancel_text("Stay")
+            .danger()
+            .on_confirm(move |window, cx| {
+                close(window, cx);
+                true
+            })
+            .open(window, cx);
+    }
+
+    /// Saves again the current version of a document whose save failed, unless a later
+    /// save succeeded in the meantime.
+    fn retry_save(uid: i32, cx: &mut App) {
+        let document = cx
+            .update_global::<DocumentState, _>(|state, cx| state.unsaved_versions(&[uid], cx))
+            .pop();
+        let Some(document) = document else {
+            return;
+        };
+        let title = document.title.clone();
+        let documents = cx.global::<RepositoryState>().documents.clone();
+
+        cx.spawn(async move |cx| {
+            let result = documents.update_document(document).await;
+            cx.update_global::<DocumentState, _>(|state, cx| {
+                if result.is_ok() {
+                    state.persistence.remove(&uid);
+                    RecoveryJournal::remove(uid);
+                }
+                cx.refresh_windows();
+            });
+
+            if let Err(e) = result {
+                Self::report_save_failure(uid, &title, &e);
+            }
+        })
+        .detach();
+    }
+
+    /// Offers to restore the changes a crash left in the recovery journal, or to discard
+    /// them.
+    pub fn prompt_recovery(cx: &mut App) {
+        let entries = RecoveryJournal::entries();
+        if entries.is_empty() {
+            return;
+        }
+
+        let titles = entries
+            .iter()
+            .map(|entry| format!("“{}”", entry.document.title))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let message = format!(
+            "Remindr closed before saving the last changes to {}. Restore them?",
+            titles
+        );
+        let ids = entries
+            .iter()
+            .map(|entry| entry.document.id)
+            .collect::<Vec<_>>();
+
+        let window = cx.active_window().or_else(|| cx.windows().first().copied());
+        let Some(window) = window else {
+            return;
+        };
+        let _ = window.update(cx, |_, window, cx| {
+            ConfirmDialog::new("Recover unsaved changes")
+                .message(message)
+                .confirm_text("Restore")
+                .cancel_text("Discard")
+                .on_confirm(move |_, cx| {
+                    Self::restore(entries.clone(), cx);
+                    true
+                })
+                .on_cancel(move |_, _| {
+                    for id in &ids {
+                        RecoveryJournal::remove(*id);
+                    }
+                })
+                .open(window, cx);
+        });
+    }
+
+    fn restore(entries: Vec<JournalEntry>, cx: &mut App) {
+        let documents = cx.global::<RepositoryState>().documents.clone();
+
+        cx.spawn(async move |cx| {
+            let mut restored = Vec::new();
+            for entry in entries {
+                let id = entry.document.id;
+                // Saved since, e.g. from the command line, so the journaled version is older
+                if let Ok(Some(updated_at)) = documents.updated_at(id).await
+                    && updated_at > entry.recorded_at
+                {
+                    tracing::info!("skipping the unsaved changes of document {}", id);
+                    RecoveryJournal::remove(id);
+                    continue;
+                }
+
+                match documents.update_document(entry.document).await {
+                    Ok(()) => {
+                        RecoveryJournal::remove(id);
+                        restored.push(id);
+                    }
+                    Err(e) => tracing::error!(
+                        "failed to restore unsaved changes of document {}: {:?}",
+                        id,
+                        e
+                    ),
+                }
+            }
+
+            cx.update(|cx| {
+                cx.update_global::<DocumentState, _>(|state, _| {
+                    state.reload_documents(&restored);
+                });
+                cx.refresh_windows();
+            });
+        })
+        .detach();
     }
 }
 
 impl Default for DocumentState {
     fn default() -> Self {
         Self {
-            documents: Vec::new(),
-            current_opened_document: None,
-            persistence: PersistenceState::Idle,
-            last_change: None,
+            workspaces: HashMap::new(),
+            persistence: HashMap::new(),
+            conflicting_documents: HashSet::new(),
+            recent_documents: Vec::new(),
+            saved_blocks: HashMap::new(),
         }
     }
 }
 
 impl Global for DocumentState {}
+
+fn block_ids(nodes: &[Value]) -> HashSet<String> {
+    nodes
+        .iter()
+        .filter_map(|node| node.get("id").and_then(Value::as_str))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Serializes the nodes currently displayed by a renderer, with their creation and edit
+/// times and the version of their shape.
+fn document_nodes(renderer: &Entity<NodeRenderer>, cx: &mut App) -> Vec<Value> {
+    let state = renderer.read(cx).state.clone();
+    let nodes = state.read(cx).get_nodes().clone();
+    let mut blocks = nodes
+        .iter()
+        .map(|node| node.element.get_data(cx))
+        .collect::<Vec<_>>();
+    state.update(cx, |state, _| state.stamp_times(&mut blocks));
+    for (block, node) in blocks.iter_mut().zip(&nodes) {
+        match &node.element {
+            // Saved exactly as they were loaded
+            RemindrElement::Unsupported(unsupported) => *block = unsupported.read(cx).data.clone(),
+            _ => stamp_version(block),
+        }
+    }
+    blocks
+}
+
+/// Saves the document, retrying with an exponential backoff from `retry_delay` when the
+/// save fails, except on conflicts.
+async fn save_with_retries(
+    documents: &dyn DocumentRepositoryPort,
+    document: &DocumentModel,
+    mut retry_delay: Duration,
+) -> Result<(), Error> {
+    let mut result = documents.update_document(document.clone()).await;
+    for _ in 0..SAVE_RETRIES {
+        match &result {
+            Err(e) if !e.is::<ExternalChangeConflict>() => {
+                tracing::warn!(
+                    "failed to save document {}, retrying in {:?}: {:?}",
+                    document.id,
+                    retry_delay,
+                    e
+                );
+            }
+            _ => break,
+        }
+        sleep(retry_delay).await;
+        retry_delay *= 2;
+        result = documents.update_document(document.clone()).await;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::infrastructure::repositories::memory::InMemoryDocumentRepository;
+
+    use super::*;
+
+    fn document(title: &str) -> DocumentModel {
+        DocumentModel {
+            id: 1,
+            title: title.to_string(),
+            content: json!([]),
+            folder_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn failed_saves_are_retried() {
+        let documents = InMemoryDocumentRepository::with_documents(vec![document("Draft")]);
+        documents.fail_updates(SAVE_RETRIES);
+
+        save_with_retries(&documents, &document("Final"), Duration::ZERO)
+            .await
+            .unwrap();
+
+        let saved = documents.get_document_by_id(1).await.unwrap();
+        assert_eq!(saved.title, "Final");
+    }
+
+    #[tokio::test]
+    async fn saves_give_up_after_the_last_retry() {
+        let documents = InMemoryDocumentRepository::with_documents(vec![document("Draft")]);
+        documents.fail_updates(SAVE_RETRIES + 1);
+
+        let result = save_with_retries(&documents, &document("Final"), Duration::ZERO).await;
+
+        assert!(result.is_err());
+        let saved = documents.get_document_by_id(1).await.unwrap();
+        assert_eq!(saved.title, "Draft");
+    }
+}
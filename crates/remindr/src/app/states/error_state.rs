@@ -0,0 +1,217 @@
+use std::{
+    future::Future,
+    sync::{Arc, LazyLock},
+};
+
+use anyhow::Error;
+use chrono::{DateTime, Local};
+use gpui::{App, BorrowAppContext, Global};
+use smol::channel::{Receiver, Sender, unbounded};
+
+use crate::app::{components::notification_center::NotificationCenter, states::toast_state::Toast};
+
+/// Errors sent from anywhere, including code without access to the app, and collected by
+/// `ErrorState::watch`.
+static ERROR_BUS: LazyLock<(Sender<AppError>, Receiver<AppError>)> = LazyLock::new(unbounded);
+
+/// Errors kept in the notification center, the oldest being dropped first.
+const MAX_ERRORS: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// Reading or writing documents, folders and the other stored data.
+    Storage,
+    /// Reading or writing the settings file.
+    Settings,
+    /// Syncing the vault with the files on disk or its git repository.
+    Sync,
+    /// Serving the local HTTP API.
+    Api,
+    /// Importing the emails of the mailbox.
+    Email,
+    /// Backing up the database or the vault.
+    Backup,
+}
+
+impl ErrorKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ErrorKind::Storage => "Storage",
+            ErrorKind::Settings => "Settings",
+            ErrorKind::Sync => "Sync",
+            ErrorKind::Api => "Local API",
+            ErrorKind::Email => "Email import",
+            ErrorKind::Backup => "Backup",
+        }
+    }
+}
+
+pub type RetryHandler = Arc<dyn Fn(&mut App) + Send + Sync + 'static>;
+
+/// Failure shown in the notification center.
+#[derive(Clone)]
+pub struct AppError {
+    pub id: usize,
+    pub kind: ErrorKind,
+    /// What failed, in the words of the user.
+    pub message: String,
+    /// The error and its causes, as copied for a bug report.
+    pub details: String,
+    pub time: DateTime<Local>,
+    pub retry: Option<RetryHandler>,
+}
+
+impl AppError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>, error: &Error) -> Self {
+        Self {
+            id: 0,
+            kind,
+            message: message.into(),
+            details: format!("{:?}", error),
+            time: Local::now(),
+            retry: None,
+        }
+    }
+
+    pub fn with_retry(mut self, retry: impl Fn(&mut App) + Send + Sync + 'static) -> Self {
+        self.retry = Some(Arc::new(retry));
+        self
+    }
+
+    /// Retries by running the failed operation again in the background.
+    pub fn with_retry_operation<F, Fut>(self, operation: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), Error>> + 'static,
+    {
+        self.retrying(Arc::new(operation))
+    }
+
+    fn retrying<F, Fut>(self, operation: Arc<F>) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), Error>> + 'static,
+    {
+        let kind = self.kind;
+        let message = self.message.clone();
+        self.with_retry(move |cx| {
+            ErrorState::attempt_shared(kind, message.clone(), operation.clone(), cx)
+        })
+    }
+
+    /// Logs the error and sends it to the notification center.
+    pub fn report(self) {
+        tracing::error!("{}: {}", self.message, self.details);
+        let _ = ERROR_BUS.0.try_send(self);
+    }
+}
+
+/// Errors reported since the start, shown in the notification center.
+#[derive(Default)]
+pub struct ErrorState {
+    errors: Vec<AppError>,
+    next_id: usize,
+}
+
+impl ErrorState {
+    /// Most recent errors first.
+    pub fn errors(&self) -> impl Iterator<Item = &AppError> {
+        self.errors.iter().rev()
+    }
+
+    pub fn dismiss(&mut self, id: usize) {
+        self.errors.retain(|error| error.id != id);
+    }
+
+    pub fn clear(&mut self) {
+        self.errors.clear();
+    }
+
+    /// Dismisses an error and runs its operation again, which reports a new error if it
+    /// fails again.
+    pub fn retry(id: usize, cx: &mut App) {
+        let retry = cx.update_global::<ErrorState, _>(|state, _| {
+            let retry = state
+                .errors
+                .iter()
+                .find(|error| error.id == id)
+                .and_then(|error| error.retry.clone());
+            state.dismiss(id);
+            retry
+        });
+        if let Some(retry) = retry {
+            retry(cx);
+        }
+        cx.refresh_windows();
+    }
+
+    /// Runs an operation in the background, reporting its failure with a retry running it
+    /// again.
+    pub fn attempt<F, Fut>(kind: ErrorKind, message: impl Into<String>, operation: F, cx: &mut App)
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), Error>> + 'static,
+    {
+        Self::attempt_shared(kind, message.into(), Arc::new(operation), cx);
+    }
+
+    fn attempt_shared<F, Fut>(kind: ErrorKind, message: String, operation: Arc<F>, cx: &mut App)
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), Error>> + 'static,
+    {
+        cx.spawn(async move |_| {
+            if let Err(e) = operation().await {
+                AppError::new(kind, message, &e)
+                    .retrying(operation)
+                    .report();
+            }
+        })
+        .detach();
+    }
+
+    /// Collects the reported errors and shows each one in a notification.
+    pub fn watch(cx: &mut App) {
+        let receiver = ERROR_BUS.1.clone();
+
+        cx.spawn(async move |cx| {
+            while let Ok(error) = receiver.recv().await {
+                cx.update(|cx| Self::push(error, cx));
+            }
+        })
+        .detach();
+    }
+
+    fn push(mut error: AppError, cx: &mut App) {
+        cx.update_global::<ErrorState, _>(|state, _| {
+            state.next_id += 1;
+            error.id = state.next_id;
+            state.errors.push(error.clone());
+            if state.errors.len() > MAX_ERRORS {
+                state.errors.remove(0);
+            }
+        });
+        cx.refresh_windows();
+
+        let Some(window) = cx.active_window().or_else(|| cx.windows().first().copied()) else {
+            return;
+        };
+        let _ = window.update(cx, |_, window, cx| {
+            Self::toast(&error).show(window, cx);
+        });
+    }
+
+    fn toast(error: &AppError) -> Toast {
+        let id = error.id;
+        let toast =
+            Toast::error(format!("{} error", error.kind.label())).title(error.message.clone());
+
+        if error.retry.is_some() {
+            toast.action("Retry", move |_, cx| ErrorState::retry(id, cx))
+        } else {
+            toast.action("Details", |window, cx| NotificationCenter::open(window, cx))
+        }
+    }
+}
+
+impl Global for ErrorState {}
@@ -1,5 +1,17 @@
 pub mod app_state;
+pub mod completion_state;
+pub mod deleted_blocks_state;
+pub mod deletion_state;
+pub mod document_list_state;
 pub mod document_state;
+pub mod error_state;
 pub mod node_state;
+pub mod preview_state;
+pub mod progress_state;
+pub mod recovery_journal;
+pub mod reminder_state;
 pub mod repository_state;
 pub mod settings_state;
+pub mod synced_block_state;
+pub mod toast_state;
+pub mod window_state;
@@ -1,29 +1,82 @@
-use gpui::{App, AppContext, DragMoveEvent, Entity, Window};
+use std::collections::{HashMap, HashSet};
+
+use chrono::Utc;
+use gpui::{
+    App, AppContext, BorrowAppContext, Context, DragMoveEvent, Entity, EventEmitter, Window,
+};
 use serde_json::{Value, from_value};
 use uuid::Uuid;
 
-use crate::app::components::nodes::{
-    divider::divider_node::DividerNode,
-    element::RemindrElement,
-    heading::heading_node::HeadingNode,
-    node::{PartialRemindrNode, RemindrNode, RemindrNodeType},
-    text::text_node::TextNode,
+use crate::{
+    Utils,
+    app::{
+        components::nodes::{
+            attachment::attachment_node::AttachmentNode,
+            divider::divider_node::DividerNode,
+            drawing::drawing_node::DrawingNode,
+            element::RemindrElement,
+            equation::equation_node::EquationNode,
+            heading::heading_node::HeadingNode,
+            link::link_node::LinkNode,
+            list::list_node::ListNode,
+            migration::migrate_block,
+            node::{PartialRemindrNode, RemindrNode, RemindrNodeType, block_problem},
+            quote::quote_node::QuoteNode,
+            synced_block::synced_block_node::SyncedBlockNode,
+            text::text_node::TextNode,
+            todo::todo_node::TodoNode,
+            unsupported::unsupported_node::UnsupportedNode,
+        },
+        states::deleted_blocks_state::DeletedBlocksState,
+    },
+    domain::database::document::outline_numbers,
 };
 
+/// Change to the blocks of a `NodeState`, for the features following a document's content,
+/// e.g. saving it, instead of each block notifying them itself.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DocumentEvent {
+    NodeInserted(Uuid),
+    NodeRemoved(Uuid),
+    /// The content of a block was edited in place.
+    NodeChanged(Uuid),
+    /// Blocks moved, without being added or removed.
+    Reordered,
+}
+
 #[derive(Clone, PartialEq)]
 pub enum MovingElement {
     Before,
     After,
 }
 
+/// Creation and last edit times of a block, in seconds since the epoch. Unknown for blocks
+/// saved before they were recorded.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BlockTimes {
+    pub created_at: Option<i64>,
+    pub updated_at: Option<i64>,
+}
+
 #[derive(Clone, Default)]
 pub struct NodeState {
+    /// Document the nodes belong to, none for nodes edited outside of a document.
+    pub document_id: Option<i32>,
     elements: Vec<RemindrNode>,
     pub hovered_drop_zone: Option<(Uuid, MovingElement)>,
     pub dragging_id: Option<Uuid>,
     pub is_dragging: bool,
+    /// Whole blocks selected with the select all shortcut.
+    selected_blocks: HashSet<Uuid>,
+    /// Times of each block with its data when last saved, the nodes themselves don't
+    /// carry them.
+    block_times: HashMap<Uuid, (BlockTimes, Value)>,
+    /// Block that last had the focus, where blocks picked outside of the editor go.
+    last_focused: Option<Uuid>,
 }
 
+impl EventEmitter<DocumentEvent> for NodeState {}
+
 impl NodeState {
     pub fn get_nodes(&self) -> &Vec<RemindrNode> {
         &self.elements
@@ -33,6 +86,28 @@ impl NodeState {
         self.elements.iter().find(|element| element.id == id)
     }
 
+    /// Outline number of the heading `id`, whose `level` is given as the heading may be
+    /// the one being rendered.
+    pub fn heading_number(&self, id: Uuid, level: u32, cx: &App) -> Option<String> {
+        let headings = self
+            .elements
+            .iter()
+            .filter_map(|node| match &node.element {
+                RemindrElement::Heading(_) if node.id == id => Some((node.id, level)),
+                RemindrElement::Heading(heading) => {
+                    Some((node.id, heading.read(cx).data.metadata.level()))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let levels = headings.iter().map(|(_, level)| *level).collect::<Vec<_>>();
+        let index = headings
+            .iter()
+            .position(|(heading_id, _)| *heading_id == id)?;
+        outline_numbers(&levels).into_iter().nth(index)
+    }
+
     pub fn start_drag(&mut self, id: Uuid) {
         self.dragging_id = Some(id);
         self.is_dragging = true;
@@ -78,28 +153,100 @@ impl NodeState {
         from_index: usize,
         target_index: usize,
         position: MovingElement,
+        cx: &mut Context<Self>,
     ) {
-        let element = self.elements.remove(from_index);
+        if move_item(&mut self.elements, from_index, target_index, position) {
+            cx.emit(DocumentEvent::Reordered);
+        }
+        self.stop_drag();
+    }
 
-        let mut to_index = target_index;
+    /// Moves a block one position up or down, as if it was dropped on its neighbour.
+    /// Returns false when it is already at the edge of the document.
+    pub fn move_node(&mut self, id: Uuid, up: bool, cx: &mut Context<Self>) -> bool {
+        let Some(index) = self.elements.iter().position(|node| node.id == id) else {
+            return false;
+        };
 
-        match position {
-            MovingElement::After => {
-                if from_index < target_index {
-                    to_index = target_index.saturating_sub(1);
-                }
-            }
-            MovingElement::Before => {
-                if from_index >= target_index {
-                    to_index = target_index + 1;
-                }
-            }
+        if up && index > 0 {
+            self.drop_element_by_index(index, index - 1, MovingElement::After, cx);
+        } else if !up && index + 1 < self.elements.len() {
+            self.drop_element_by_index(index, index + 1, MovingElement::Before, cx);
+        } else {
+            return false;
         }
 
-        let final_index = to_index.clamp(0, self.elements.len());
-        self.elements.insert(final_index, element);
+        true
+    }
 
-        self.stop_drag();
+    /// Inserts a copy of a block, with a new id, right above or below it.
+    pub fn duplicate_node(
+        &mut self,
+        id: Uuid,
+        below: bool,
+        state: &Entity<NodeState>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(index) = self.elements.iter().position(|node| node.id == id) else {
+            return;
+        };
+
+        let mut data = self.elements[index].element.get_data(cx);
+        // Unsupported blocks may not even be objects
+        let Some(object) = data.as_object_mut() else {
+            return;
+        };
+        object.insert(
+            "id".to_string(),
+            Value::String(Utils::generate_uuid().to_string()),
+        );
+        let node = self.parse_node(&data, state, window, cx);
+        self.insert_node_at(if below { index + 1 } else { index }, &node, cx);
+    }
+
+    pub fn is_selected(&self, id: Uuid) -> bool {
+        self.selected_blocks.contains(&id)
+    }
+
+    pub fn has_selection(&self) -> bool {
+        !self.selected_blocks.is_empty()
+    }
+
+    /// Selects a whole block, or every block of the document when it already is.
+    pub fn escalate_selection(&mut self, id: Uuid) {
+        if self.selected_blocks.contains(&id) {
+            self.selected_blocks = self.elements.iter().map(|node| node.id).collect();
+        } else {
+            self.selected_blocks = HashSet::from([id]);
+        }
+    }
+
+    pub fn clear_selection(&mut self) {
+        self.selected_blocks.clear();
+    }
+
+    pub fn remove_selected(&mut self, cx: &mut Context<Self>) {
+        let selected = std::mem::take(&mut self.selected_blocks);
+        self.keep_deleted(&selected, cx);
+        self.elements.retain(|node| !selected.contains(&node.id));
+        for id in selected {
+            cx.emit(DocumentEvent::NodeRemoved(id));
+        }
+    }
+
+    /// Blocks moved along with a block: the selected ones, in document order, when it is
+    /// selected, or only itself otherwise.
+    pub fn blocks_with(&self, id: Uuid) -> Vec<RemindrNode> {
+        if self.selected_blocks.contains(&id) {
+            self.elements
+                .iter()
+                .filter(|node| self.selected_blocks.contains(&node.id))
+                .cloned()
+                .collect()
+        } else {
+            self.get_current_nodes(id).cloned().into_iter().collect()
+        }
     }
 
     pub fn on_outside<T>(&mut self, event: &DragMoveEvent<T>) -> bool {
@@ -125,6 +272,25 @@ impl NodeState {
         window: &mut Window,
         app: &mut App,
     ) -> RemindrNode {
+        let mut migrated = value.clone();
+        migrate_block(&mut migrated);
+
+        // Blocks this version can't show are kept as they were loaded
+        if let Some(problem) = block_problem(&migrated) {
+            tracing::warn!("unsupported block: {}", problem);
+            let id = value
+                .get("id")
+                .and_then(Value::as_str)
+                .and_then(|id| Uuid::parse_str(id).ok())
+                .unwrap_or_else(Utils::generate_uuid);
+            let element = app.new(|_| UnsupportedNode::new(value.clone(), problem));
+            return RemindrNode {
+                id,
+                element: RemindrElement::Unsupported(element),
+            };
+        }
+
+        let value = &migrated;
         let partial_node = from_value::<PartialRemindrNode>(value.clone()).unwrap();
         let element = match partial_node.node_type {
             RemindrNodeType::Text => {
@@ -139,6 +305,40 @@ impl NodeState {
                 let element = app.new(|cx| DividerNode::parse(value, window, cx).unwrap());
                 RemindrElement::Divider(element)
             }
+            RemindrNodeType::List => {
+                let element = app.new(|cx| ListNode::parse(value, state, window, cx).unwrap());
+                RemindrElement::List(element)
+            }
+            RemindrNodeType::Quote => {
+                let element = app.new(|cx| QuoteNode::parse(value, state, window, cx).unwrap());
+                RemindrElement::Quote(element)
+            }
+            RemindrNodeType::Todo => {
+                let element = app.new(|cx| TodoNode::parse(value, state, window, cx).unwrap());
+                RemindrElement::Todo(element)
+            }
+            RemindrNodeType::Attachment => {
+                let element =
+                    app.new(|cx| AttachmentNode::parse(value, state, window, cx).unwrap());
+                RemindrElement::Attachment(element)
+            }
+            RemindrNodeType::Drawing => {
+                let element = app.new(|cx| DrawingNode::parse(value, state, window, cx).unwrap());
+                RemindrElement::Drawing(element)
+            }
+            RemindrNodeType::Equation => {
+                let element = app.new(|cx| EquationNode::parse(value, state, window, cx).unwrap());
+                RemindrElement::Equation(element)
+            }
+            RemindrNodeType::SyncedBlock => {
+                let element =
+                    app.new(|cx| SyncedBlockNode::parse(value, state, window, cx).unwrap());
+                RemindrElement::SyncedBlock(element)
+            }
+            RemindrNodeType::Link => {
+                let element = app.new(|cx| LinkNode::parse(value, window, cx).unwrap());
+                RemindrElement::Link(element)
+            }
         };
 
         RemindrNode {
@@ -147,29 +347,159 @@ impl NodeState {
         }
     }
 
+    pub fn block_times(&self, id: Uuid) -> Option<BlockTimes> {
+        self.block_times.get(&id).map(|(times, _)| *times)
+    }
+
+    /// Keeps the times stored in the metadata of a loaded block. `data` is the block as
+    /// serialized by its node, to detect later edits.
+    pub fn record_times(&mut self, id: Uuid, value: &Value, data: Value) {
+        let metadata = value.get("metadata");
+        let time = |key: &str| {
+            metadata
+                .and_then(|metadata| metadata.get(key))
+                .and_then(Value::as_i64)
+        };
+        let times = BlockTimes {
+            created_at: time("created_at"),
+            updated_at: time("updated_at"),
+        };
+
+        self.block_times.insert(id, (times, data));
+    }
+
+    /// Writes the creation and edit times into the metadata of the blocks about to be
+    /// saved. Blocks not seen before are new, and blocks differing from their last saved
+    /// version were edited.
+    pub fn stamp_times(&mut self, blocks: &mut [Value]) {
+        let now = Utc::now().timestamp();
+
+        for block in blocks {
+            let Some(id) = block
+                .get("id")
+                .and_then(Value::as_str)
+                .and_then(|id| Uuid::parse_str(id).ok())
+            else {
+                continue;
+            };
+
+            let (times, saved) = self.block_times.entry(id).or_insert_with(|| {
+                let times = BlockTimes {
+                    created_at: Some(now),
+                    updated_at: Some(now),
+                };
+                (times, block.clone())
+            });
+            if saved != block {
+                times.updated_at = Some(now);
+                *saved = block.clone();
+            }
+
+            if let Some(metadata) = block.get_mut("metadata").and_then(Value::as_object_mut) {
+                if let Some(created_at) = times.created_at {
+                    metadata.insert("created_at".to_string(), created_at.into());
+                }
+                if let Some(updated_at) = times.updated_at {
+                    metadata.insert("updated_at".to_string(), updated_at.into());
+                }
+            }
+        }
+    }
+
+    /// Appends a node without a change event: a loaded block, or a new empty one that is
+    /// only saved once edited.
     pub fn push_node(&mut self, node: &RemindrNode) {
         self.elements.push(node.clone());
     }
 
-    pub fn remove_node(&mut self, id: Uuid) {
+    pub fn remove_node(&mut self, id: Uuid, cx: &mut Context<Self>) {
+        self.keep_deleted(&HashSet::from([id]), cx);
         self.elements.retain(|node| node.id != id);
+        cx.emit(DocumentEvent::NodeRemoved(id));
+    }
+
+    /// Inserts a node below the block that last had the focus, or at the end of the
+    /// document.
+    pub fn insert_node_at_cursor(&mut self, node: &RemindrNode, cx: &mut Context<Self>) {
+        self.insert_nodes_at_cursor(std::slice::from_ref(node), cx);
+    }
+
+    /// Inserts nodes in order below the block that last had the focus, or at the end of
+    /// the document.
+    pub fn insert_nodes_at_cursor(&mut self, nodes: &[RemindrNode], cx: &mut Context<Self>) {
+        let index = self
+            .last_focused
+            .and_then(|id| self.elements.iter().position(|node| node.id == id))
+            .map_or(self.elements.len(), |index| index + 1);
+        for (offset, node) in nodes.iter().enumerate() {
+            self.insert_node_at(index + offset, node, cx);
+        }
     }
 
-    pub fn insert_node_after(&mut self, id: Uuid, node: &RemindrNode) {
+    pub fn insert_node_after(&mut self, id: Uuid, node: &RemindrNode, cx: &mut Context<Self>) {
         let index = self.elements.iter().position(|node| node.id == id).unwrap();
         self.elements.insert(index + 1, node.clone());
+        cx.emit(DocumentEvent::NodeInserted(node.id));
     }
 
-    pub fn insert_node_at(&mut self, index: usize, node: &RemindrNode) {
+    pub fn insert_node_at(&mut self, index: usize, node: &RemindrNode, cx: &mut Context<Self>) {
         self.elements.insert(index, node.clone());
+        cx.emit(DocumentEvent::NodeInserted(node.id));
     }
 
-    pub fn replace_node(&mut self, id: Uuid, node: &RemindrNode) {
+    pub fn replace_node(&mut self, id: Uuid, node: &RemindrNode, cx: &mut Context<Self>) {
         if let Some(index) = self.elements.iter().position(|n| n.id == id) {
             self.elements[index] = node.clone();
+            cx.emit(DocumentEvent::NodeRemoved(id));
+            cx.emit(DocumentEvent::NodeInserted(node.id));
         }
     }
 
+    /// Replaces blocks with a single one, placed where the first of them was.
+    pub fn replace_nodes(
+        &mut self,
+        ids: &HashSet<Uuid>,
+        node: &RemindrNode,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(index) = self.elements.iter().position(|n| ids.contains(&n.id)) else {
+            return;
+        };
+
+        self.elements.retain(|n| !ids.contains(&n.id));
+        self.elements.insert(index, node.clone());
+        self.selected_blocks.retain(|id| !ids.contains(id));
+        for id in ids {
+            cx.emit(DocumentEvent::NodeRemoved(*id));
+        }
+        cx.emit(DocumentEvent::NodeInserted(node.id));
+    }
+
+    /// Tells the followers of the document that the content of a block was edited.
+    pub fn node_changed(&mut self, id: Uuid, cx: &mut Context<Self>) {
+        cx.emit(DocumentEvent::NodeChanged(id));
+    }
+
+    pub fn set_last_focused(&mut self, id: Uuid) {
+        self.last_focused = Some(id);
+    }
+
+    /// Keeps the blocks about to be removed in the recently deleted blocks, in document
+    /// order.
+    fn keep_deleted(&self, ids: &HashSet<Uuid>, cx: &mut Context<Self>) {
+        if !cx.has_global::<DeletedBlocksState>() {
+            return;
+        }
+        let blocks = self
+            .elements
+            .iter()
+            .filter(|node| ids.contains(&node.id))
+            .map(|node| node.element.get_data(cx))
+            .collect::<Vec<_>>();
+        let document_id = self.document_id;
+        cx.update_global::<DeletedBlocksState, _>(|deleted, _| deleted.keep(document_id, blocks));
+    }
+
     pub fn get_previous_node(&self, id: Uuid) -> Option<RemindrNode> {
         let index = self.elements.iter().position(|node| node.id == id)?;
         if index == 0 {
@@ -178,3 +508,125 @@ impl NodeState {
         self.elements.get(index - 1).cloned()
     }
 }
+
+/// Moves the item at `from_index` next to the one at `target_index`: above it when dropped
+/// on its upper half (`After`), below it otherwise. Dropping an item on itself, or indices
+/// out of range, leave the items as they are. Returns whether the item moved.
+fn move_item<T>(
+    items: &mut Vec<T>,
+    from_index: usize,
+    target_index: usize,
+    position: MovingElement,
+) -> bool {
+    if from_index == target_index || from_index >= items.len() || target_index >= items.len() {
+        return false;
+    }
+
+    let item = items.remove(from_index);
+    // Where the target is once the item is removed
+    let target_index = if from_index < target_index {
+        target_index - 1
+    } else {
+        target_index
+    };
+    let to_index = match position {
+        MovingElement::After => target_index,
+        MovingElement::Before => target_index + 1,
+    };
+    items.insert(to_index, item);
+    to_index != from_index
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn moved(len: usize, from: usize, target: usize, position: MovingElement) -> Vec<usize> {
+        let mut items = (0..len).collect::<Vec<_>>();
+        move_item(&mut items, from, target, position);
+        items
+    }
+
+    fn position() -> impl Strategy<Value = MovingElement> {
+        prop_oneof![Just(MovingElement::Before), Just(MovingElement::After)]
+    }
+
+    /// Length of a list with a moved item and a drop target within it.
+    fn drop_indices() -> impl Strategy<Value = (usize, usize, usize)> {
+        (1..20usize).prop_flat_map(|len| (Just(len), 0..len, 0..len))
+    }
+
+    proptest! {
+        #[test]
+        fn no_item_is_lost_or_duplicated((len, from, target) in drop_indices(), position in position()) {
+            let mut items = moved(len, from, target, position);
+            items.sort();
+            prop_assert_eq!(items, (0..len).collect::<Vec<_>>());
+        }
+
+        #[test]
+        fn other_items_keep_their_order((len, from, target) in drop_indices(), position in position()) {
+            let others = moved(len, from, target, position)
+                .into_iter()
+                .filter(|item| *item != from)
+                .collect::<Vec<_>>();
+            prop_assert_eq!(others, (0..len).filter(|item| *item != from).collect::<Vec<_>>());
+        }
+
+        #[test]
+        fn item_lands_next_to_target((len, from, target) in drop_indices(), position in position()) {
+            prop_assume!(from != target);
+            let items = moved(len, from, target, position.clone());
+            let index = |item| items.iter().position(|other| *other == item).unwrap();
+
+            match position {
+                MovingElement::After => prop_assert_eq!(index(from) + 1, index(target)),
+                MovingElement::Before => prop_assert_eq!(index(from), index(target) + 1),
+            }
+        }
+
+        #[test]
+        fn reports_whether_the_order_changed((len, from, target) in drop_indices(), position in position()) {
+            let mut items = (0..len).collect::<Vec<_>>();
+            let changed = move_item(&mut items, from, target, position);
+            prop_assert_eq!(changed, items != (0..len).collect::<Vec<_>>());
+        }
+
+        #[test]
+        fn out_of_range_is_ignored(len in 0..10usize, from in 0..20usize, target in 0..20usize) {
+            prop_assume!(from >= len || target >= len);
+            prop_assert_eq!(
+                moved(len, from, target, MovingElement::Before),
+                (0..len).collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn dropping_on_itself_keeps_the_order() {
+        assert_eq!(moved(3, 1, 1, MovingElement::After), vec![0, 1, 2]);
+        // Used to move the item one position down
+        assert_eq!(moved(3, 1, 1, MovingElement::Before), vec![0, 1, 2]);
+        assert_eq!(moved(3, 2, 2, MovingElement::Before), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn moves_to_first_and_last_positions() {
+        assert_eq!(moved(4, 2, 0, MovingElement::After), vec![2, 0, 1, 3]);
+        assert_eq!(moved(4, 3, 0, MovingElement::After), vec![3, 0, 1, 2]);
+        assert_eq!(moved(4, 1, 3, MovingElement::Before), vec![0, 2, 3, 1]);
+        assert_eq!(moved(4, 0, 3, MovingElement::Before), vec![1, 2, 3, 0]);
+    }
+
+    #[test]
+    fn moves_between_neighbours() {
+        // As `move_node` does
+        assert_eq!(moved(3, 1, 0, MovingElement::After), vec![1, 0, 2]);
+        assert_eq!(moved(3, 1, 2, MovingElement::Before), vec![0, 2, 1]);
+        // Already in place
+        assert_eq!(moved(3, 0, 1, MovingElement::After), vec![0, 1, 2]);
+        assert_eq!(moved(3, 2, 1, MovingElement::Before), vec![0, 1, 2]);
+    }
+}
@@ -0,0 +1,59 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use gpui::Global;
+use serde_json::Value;
+
+/// How long a preview is reused before the document is read again, so that changes made
+/// outside the editor show up.
+const PREVIEW_TTL: Duration = Duration::from_secs(30);
+
+/// Maximum number of blocks kept for a preview.
+pub const PREVIEW_BLOCKS: usize = 5;
+
+#[derive(Clone)]
+pub struct CachedPreview {
+    pub title: String,
+    pub blocks: Vec<Value>,
+    fetched_at: Instant,
+}
+
+/// First blocks of the documents previewed on hover, kept to avoid reading a document
+/// each time the pointer passes over it.
+#[derive(Default)]
+pub struct PreviewState {
+    previews: HashMap<i32, CachedPreview>,
+}
+
+impl PreviewState {
+    pub fn get(&self, document_id: i32) -> Option<&CachedPreview> {
+        self.previews
+            .get(&document_id)
+            .filter(|preview| preview.fetched_at.elapsed() < PREVIEW_TTL)
+    }
+
+    pub fn insert(&mut self, document_id: i32, title: String, content: &Value) {
+        let blocks = content
+            .as_array()
+            .map(|blocks| blocks.iter().take(PREVIEW_BLOCKS).cloned().collect())
+            .unwrap_or_default();
+
+        self.previews.insert(
+            document_id,
+            CachedPreview {
+                title,
+                blocks,
+                fetched_at: Instant::now(),
+            },
+        );
+    }
+
+    /// Drops the preview of a document that was just saved.
+    pub fn invalidate(&mut self, document_id: i32) {
+        self.previews.remove(&document_id);
+    }
+}
+
+impl Global for PreviewState {}
@@ -0,0 +1,102 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+};
+
+use gpui::{App, BorrowAppContext, Global, SharedString};
+
+/// Long operation shown in the progress panel, with how far it got.
+#[derive(Clone)]
+pub struct ProgressEntry {
+    pub id: usize,
+    pub title: SharedString,
+    /// From 0 to 100, `None` while unknown.
+    pub percent: Option<f32>,
+    /// Set when the operation can be cancelled.
+    pub cancelled: Option<Arc<AtomicBool>>,
+}
+
+impl ProgressEntry {
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled
+            .as_ref()
+            .is_some_and(|cancelled| cancelled.load(Ordering::Relaxed))
+    }
+}
+
+/// Handle of a running operation, used to report its progress and to end it.
+#[derive(Clone)]
+pub struct ProgressTask {
+    id: usize,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl ProgressTask {
+    /// Shows the operation in the progress panel until [`ProgressTask::finish`] is called.
+    /// A cancel button is shown when `cancellable` is set, the operation checking
+    /// [`ProgressTask::is_cancelled`] to stop.
+    pub fn start(title: impl Into<SharedString>, cancellable: bool, cx: &mut App) -> Self {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let id = cx.update_global::<ProgressState, _>(|state, _| {
+            state.next_id += 1;
+            state.tasks.push(ProgressEntry {
+                id: state.next_id,
+                title: title.into(),
+                percent: None,
+                cancelled: cancellable.then(|| cancelled.clone()),
+            });
+            state.next_id
+        });
+
+        Self { id, cancelled }
+    }
+
+    pub fn set_percent(&self, percent: f32, cx: &mut App) {
+        cx.update_global::<ProgressState, _>(|state, _| {
+            if let Some(entry) = state.tasks.iter_mut().find(|entry| entry.id == self.id) {
+                entry.percent = Some(percent.clamp(0.0, 100.0));
+            }
+        });
+    }
+
+    /// Whether the user cancelled the operation. Can be checked from any thread.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Removes the operation from the progress panel.
+    pub fn finish(self, cx: &mut App) {
+        cx.update_global::<ProgressState, _>(|state, _| {
+            state.tasks.retain(|entry| entry.id != self.id);
+        });
+    }
+}
+
+/// Long operations running in the app, shown in the progress panel of every main window.
+#[derive(Default)]
+pub struct ProgressState {
+    next_id: usize,
+    tasks: Vec<ProgressEntry>,
+}
+
+impl ProgressState {
+    pub fn tasks(&self) -> &[ProgressEntry] {
+        &self.tasks
+    }
+
+    /// Asks the operation to stop, it stays shown until it does.
+    pub fn cancel(id: usize, cx: &mut App) {
+        cx.update_global::<ProgressState, _>(|state, _| {
+            if let Some(cancelled) = state
+                .tasks
+                .iter()
+                .find(|entry| entry.id == id)
+                .and_then(|entry| entry.cancelled.as_ref())
+            {
+                cancelled.store(true, Ordering::Relaxed);
+            }
+        });
+    }
+}
+
+impl Global for ProgressState {}
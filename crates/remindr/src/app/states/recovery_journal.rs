@@ -0,0 +1,148 @@
+use std::{
+    collections::HashMap,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::PathBuf,
+    sync::{LazyLock, Mutex},
+    time::{Instant, SystemTime},
+};
+
+use gpui::App;
+
+use crate::{
+    app::{remindr::Remindr, states::settings_state::Settings},
+    domain::database::document::DocumentModel,
+};
+
+/// Size over which a recovery file is rewritten with only its latest version.
+const COMPACT_SIZE: u64 = 1024 * 1024;
+
+/// When the recovery file of each document was last removed in this session. Locked while
+/// the files are written, so that a version taken before a save isn't written after the
+/// save removed the file.
+static REMOVED: LazyLock<Mutex<HashMap<i32, Instant>>> = LazyLock::new(Mutex::default);
+
+/// Unsaved version of a document found at startup.
+#[derive(Clone)]
+pub struct JournalEntry {
+    pub document: DocumentModel,
+    /// When the version was written, to skip it if the document was saved since.
+    pub recorded_at: SystemTime,
+}
+
+/// Unsaved versions of the documents, in one `recovery/<id>.jsonl` file per document next
+/// to the settings. Each change appends a line, and the file is deleted once the document
+/// is saved, so the files found at startup hold edits a crash prevented from saving.
+pub struct RecoveryJournal;
+
+impl RecoveryJournal {
+    /// The files are plain JSON, so nothing is journaled while the database is encrypted,
+    /// rather than leaving readable copies of the documents next to it.
+    pub fn is_enabled(cx: &App) -> bool {
+        !cx.try_global::<Settings>()
+            .is_some_and(|settings| settings.security.encrypted)
+    }
+
+    /// Appends a version of the document taken at `taken_at`, unless the document was saved
+    /// since.
+    pub fn record(document: &DocumentModel, taken_at: Instant) {
+        let Some(path) = Self::file_path(document.id) else {
+            return;
+        };
+
+        let removed = REMOVED.lock().unwrap();
+        if removed
+            .get(&document.id)
+            .is_some_and(|removed_at| *removed_at >= taken_at)
+        {
+            return;
+        }
+
+        let result = serde_json::to_string(document)
+            .map_err(std::io::Error::from)
+            .and_then(|line| {
+                if let Some(dir) = path.parent() {
+                    fs::create_dir_all(dir)?;
+                }
+
+                let compact = fs::metadata(&path).is_ok_and(|meta| meta.len() > COMPACT_SIZE);
+                let mut file = OpenOptions::new()
+                    .create(true)
+                    .append(!compact)
+                    .write(true)
+                    .truncate(compact)
+                    .open(&path)?;
+                writeln!(file, "{}", line)
+            });
+        if let Err(e) = result {
+            tracing::error!("failed to write {:?}: {:?}", path, e);
+        }
+    }
+
+    /// Drops the recovery file of a document saved with its latest changes.
+    pub fn remove(id: i32) {
+        let Some(path) = Self::file_path(id) else {
+            return;
+        };
+
+        let mut removed = REMOVED.lock().unwrap();
+        removed.insert(id, Instant::now());
+        if let Err(e) = fs::remove_file(&path)
+            && e.kind() != std::io::ErrorKind::NotFound
+        {
+            tracing::error!("failed to remove {:?}: {:?}", path, e);
+        }
+    }
+
+    /// Drops every recovery file, e.g. once the database is encrypted.
+    pub fn clear() {
+        let Some(dir) = Self::dir() else {
+            return;
+        };
+
+        let _removed = REMOVED.lock().unwrap();
+        if let Err(e) = fs::remove_dir_all(&dir)
+            && e.kind() != std::io::ErrorKind::NotFound
+        {
+            tracing::error!("failed to remove {:?}: {:?}", dir, e);
+        }
+    }
+
+    /// Latest version of each document left unsaved. A line cut short by the crash is
+    /// skipped in favor of the previous one.
+    pub fn entries() -> Vec<JournalEntry> {
+        let Some(dir) = Self::dir() else {
+            return Vec::new();
+        };
+        let Ok(files) = fs::read_dir(dir) else {
+            return Vec::new();
+        };
+
+        files
+            .flatten()
+            .filter_map(|file| {
+                let recorded_at = file.metadata().and_then(|meta| meta.modified()).ok()?;
+                let content = fs::read_to_string(file.path()).ok()?;
+                let document = content
+                    .lines()
+                    .rev()
+                    .find_map(|line| serde_json::from_str::<DocumentModel>(line).ok())?;
+                Some(JournalEntry {
+                    document,
+                    recorded_at,
+                })
+            })
+            .collect()
+    }
+
+    fn dir() -> Option<PathBuf> {
+        Remindr::new()
+            .get_config_dir("remindr")
+            .ok()
+            .map(|config_dir| config_dir.join("recovery"))
+    }
+
+    fn file_path(id: i32) -> Option<PathBuf> {
+        Self::dir().map(|dir| dir.join(format!("{}.jsonl", id)))
+    }
+}
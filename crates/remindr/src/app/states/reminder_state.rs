@@ -0,0 +1,207 @@
+use std::{collections::HashSet, time::Duration};
+
+use chrono::{Local, TimeZone, Utc};
+use gpui::{App, AsyncApp, BorrowAppContext, Global};
+use gpui_component::{
+    Sizable, WindowExt,
+    button::{Button, ButtonVariants},
+    notification::Notification,
+};
+use smol::Timer;
+
+use crate::{
+    app::{date_format::DateFormat, states::repository_state::RepositoryState},
+    domain::database::reminder::ReminderModel,
+};
+
+/// Interval at which reminders are checked.
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Delay applied by the snooze action of a notification.
+pub const NOTIFICATION_SNOOZE: chrono::Duration = chrono::Duration::minutes(10);
+
+/// Pending reminders of every document, refreshed from the repository.
+#[derive(Default)]
+pub struct ReminderState {
+    reminders: Vec<ReminderModel>,
+    /// Reminders already notified, with the time they fired at, so each occurrence is only
+    /// shown once.
+    notified: HashSet<(i32, i64)>,
+}
+
+impl ReminderState {
+    /// Pending reminders, the earliest first.
+    pub fn reminders(&self) -> &[ReminderModel] {
+        &self.reminders
+    }
+
+    pub fn now() -> i64 {
+        Utc::now().timestamp()
+    }
+
+    /// Formats a reminder time for display, in local time.
+    pub fn format_time(timestamp: i64, cx: &App) -> String {
+        let format = DateFormat::new(cx);
+        Local
+            .timestamp_opt(timestamp, 0)
+            .single()
+            .map(|time| format.date_time(time.naive_local()))
+            .unwrap_or_default()
+    }
+
+    /// Checks reminders periodically and shows a notification for each one coming due.
+    pub fn watch(cx: &mut App) {
+        cx.spawn(async move |cx| {
+            loop {
+                if let Err(e) = Self::refresh_async(cx).await {
+                    tracing::error!("failed to load reminders: {:?}", e);
+                }
+                cx.update(Self::notify_due);
+
+                Timer::after(CHECK_INTERVAL).await;
+            }
+        })
+        .detach();
+    }
+
+    /// Reloads the reminders from the repository.
+    pub fn refresh(cx: &mut App) {
+        cx.spawn(async move |cx| {
+            if let Err(e) = Self::refresh_async(cx).await {
+                tracing::error!("failed to load reminders: {:?}", e);
+            }
+        })
+        .detach();
+    }
+
+    async fn refresh_async(cx: &mut AsyncApp) -> anyhow::Result<()> {
+        let Some(repository) = cx.update(|cx| {
+            cx.try_global::<RepositoryState>()
+                .map(|repositories| repositories.reminders.clone())
+        }) else {
+            return Ok(());
+        };
+
+        let reminders = repository.get_pending_reminders().await?;
+        cx.update(|cx| {
+            cx.update_global::<ReminderState, _>(|state, _| state.reminders = reminders);
+            cx.refresh_windows();
+        });
+
+        Ok(())
+    }
+
+    pub fn create(reminder: ReminderModel, cx: &mut App) {
+        let repository = cx.global::<RepositoryState>().reminders.clone();
+        cx.spawn(async move |cx| {
+            repository.insert_reminder(reminder).await?;
+            Self::refresh_async(cx).await?;
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
+    pub fn snooze(id: i32, delay: chrono::Duration, cx: &mut App) {
+        let until = Self::now() + delay.num_seconds();
+        Self::edit(id, cx, move |reminder| reminder.snooze(until));
+    }
+
+    /// Completes a reminder, or moves it to its next occurrence when it repeats.
+    pub fn complete(id: i32, cx: &mut App) {
+        let now = Self::now();
+        Self::edit(id, cx, move |reminder| reminder.complete(now));
+    }
+
+    pub fn delete(id: i32, cx: &mut App) {
+        let repository = cx.global::<RepositoryState>().reminders.clone();
+        cx.spawn(async move |cx| {
+            repository.delete_reminder(id).await?;
+            Self::refresh_async(cx).await?;
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
+    fn edit(id: i32, cx: &mut App, edit: impl FnOnce(&mut ReminderModel) + 'static) {
+        let Some(mut reminder) = cx
+            .global::<ReminderState>()
+            .reminders
+            .iter()
+            .find(|reminder| reminder.id == id)
+            .cloned()
+        else {
+            return;
+        };
+        edit(&mut reminder);
+
+        let repository = cx.global::<RepositoryState>().reminders.clone();
+        cx.spawn(async move |cx| {
+            repository.update_reminder(reminder).await?;
+            Self::refresh_async(cx).await?;
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .detach();
+    }
+
+    fn notify_due(cx: &mut App) {
+        let now = Self::now();
+        let due = cx.update_global::<ReminderState, _>(|state, _| {
+            let due = state
+                .reminders
+                .iter()
+                .filter(|reminder| reminder.is_overdue(now))
+                .filter(|reminder| {
+                    !state
+                        .notified
+                        .contains(&(reminder.id, reminder.trigger_at()))
+                })
+                .cloned()
+                .collect::<Vec<_>>();
+            state.notified.extend(
+                due.iter()
+                    .map(|reminder| (reminder.id, reminder.trigger_at())),
+            );
+            due
+        });
+        if due.is_empty() {
+            return;
+        }
+
+        let Some(window) = cx.active_window().or_else(|| cx.windows().first().copied()) else {
+            return;
+        };
+
+        let _ = window.update(cx, |_, window, cx| {
+            for reminder in due {
+                let notification = Self::notification(&reminder, cx);
+                window.push_notification(notification, cx);
+            }
+        });
+    }
+
+    fn notification(reminder: &ReminderModel, cx: &App) -> Notification {
+        let id = reminder.id;
+
+        Notification::new()
+            .title(reminder.title.clone())
+            .message(format!("Due {}", Self::format_time(reminder.due_at, cx)))
+            .autohide(false)
+            .action(move |_, _, cx| {
+                let notification = cx.entity();
+                Button::new(("snooze-reminder", id as usize))
+                    .small()
+                    .ghost()
+                    .label("Snooze 10 min")
+                    .on_click(move |_, window, cx| {
+                        ReminderState::snooze(id, NOTIFICATION_SNOOZE, cx);
+                        notification
+                            .update(cx, |notification, cx| notification.dismiss(window, cx));
+                    })
+            })
+    }
+}
+
+impl Global for ReminderState {}
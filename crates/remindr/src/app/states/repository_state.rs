@@ -1,10 +1,95 @@
+use crate::app::states::error_state::{AppError, ErrorKind};
+use crate::app::states::settings_state::Settings;
+use crate::domain::ports::{DocumentRepositoryPort, FolderRepositoryPort};
+use crate::infrastructure::indexer::Indexer;
+use crate::infrastructure::repositories::activity_repository::ActivityRepository;
+use crate::infrastructure::repositories::attachment_repository::AttachmentRepository;
+use crate::infrastructure::repositories::collection_repository::CollectionRepository;
 use crate::infrastructure::repositories::document_repository::DocumentRepository;
 use crate::infrastructure::repositories::folder_repository::FolderRepository;
+use crate::infrastructure::repositories::reminder_repository::ReminderRepository;
+use crate::infrastructure::repositories::smart_folder_repository::SmartFolderRepository;
+use crate::infrastructure::repositories::synced_block_repository::SyncedBlockRepository;
+use crate::infrastructure::repositories::tag_repository::TagRepository;
+use crate::infrastructure::repositories::task_repository::TaskRepository;
+use crate::infrastructure::vault::file_vault::FileVault;
 use gpui::Global;
+use sqlx::SqlitePool;
+use std::sync::Arc;
 
 pub struct RepositoryState {
-    pub documents: DocumentRepository,
-    pub folders: FolderRepository,
+    pub pool: SqlitePool,
+    pub documents: Arc<dyn DocumentRepositoryPort>,
+    pub folders: Arc<dyn FolderRepositoryPort>,
+    pub tags: TagRepository,
+    pub reminders: ReminderRepository,
+    pub tasks: TaskRepository,
+    pub attachments: AttachmentRepository,
+    pub synced_blocks: SyncedBlockRepository,
+    pub collections: CollectionRepository,
+    pub smart_folders: SmartFolderRepository,
+    pub activity: ActivityRepository,
+    /// Set when documents are stored in a file vault instead of the database.
+    pub vault: Option<FileVault>,
+}
+
+impl RepositoryState {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            documents: Arc::new(DocumentRepository::new(
+                pool.clone(),
+                Indexer::spawn(pool.clone()),
+            )),
+            folders: Arc::new(FolderRepository::new(pool.clone())),
+            tags: TagRepository::new(pool.clone()),
+            reminders: ReminderRepository::new(pool.clone()),
+            tasks: TaskRepository::new(pool.clone()),
+            attachments: AttachmentRepository::new(pool.clone()),
+            synced_blocks: SyncedBlockRepository::new(pool.clone()),
+            collections: CollectionRepository::new(pool.clone()),
+            smart_folders: SmartFolderRepository::new(pool.clone()),
+            activity: ActivityRepository::new(pool.clone()),
+            vault: None,
+            pool,
+        }
+    }
+
+    /// Builds the repositories for the storage selected in the settings, falling back to
+    /// the database when the vault folder cannot be opened.
+    pub fn for_settings(pool: SqlitePool, settings: Option<&Settings>) -> Self {
+        let Some(vault_settings) = settings
+            .map(|settings| &settings.vault)
+            .filter(|vault| vault.is_configured())
+        else {
+            return Self::new(pool);
+        };
+
+        match FileVault::open(vault_settings.path.trim()) {
+            Ok(vault) => Self {
+                documents: Arc::new(vault.clone()),
+                folders: Arc::new(vault.clone()),
+                tags: TagRepository::with_vault(vault.clone()),
+                reminders: ReminderRepository::with_vault(vault.clone()),
+                tasks: TaskRepository::with_vault(vault.clone()),
+                attachments: AttachmentRepository::with_vault(vault.clone()),
+                synced_blocks: SyncedBlockRepository::with_vault(vault.clone()),
+                collections: CollectionRepository::with_vault(vault.clone()),
+                smart_folders: SmartFolderRepository::with_vault(vault.clone()),
+                activity: ActivityRepository::new(pool.clone()),
+                vault: Some(vault),
+                pool,
+            },
+            Err(e) => {
+                AppError::new(
+                    ErrorKind::Sync,
+                    "Failed to open the vault, documents are read from the database",
+                    &e,
+                )
+                .report();
+                Self::new(pool)
+            }
+        }
+    }
 }
 
 impl Global for RepositoryState {}
@@ -1,7 +1,14 @@
-use gpui::Global;
+use std::time::Duration;
+
+use anyhow::{Context, Error, bail};
+use gpui::{App, Global, Pixels, px};
 use serde::{Deserialize, Serialize};
 
-use crate::domain::entities::settings::DbContext;
+use crate::{
+    app::states::error_state::{AppError, ErrorKind},
+    domain::entities::settings::DbContext,
+    infrastructure::{database::PoolConfig, email::Mailbox},
+};
 
 #[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
@@ -30,6 +37,185 @@ impl ThemeMode {
     }
 }
 
+/// Block added by Enter at the end of a list item or a to-do.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NewBlockType {
+    Text,
+    /// Another list item or to-do, Enter on an empty one leaving the list.
+    #[default]
+    Continue,
+}
+
+impl NewBlockType {
+    pub const ALL: [NewBlockType; 2] = [NewBlockType::Text, NewBlockType::Continue];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            NewBlockType::Text => "Text",
+            NewBlockType::Continue => "Same type",
+        }
+    }
+}
+
+/// Block a new, empty document starts with.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FirstBlockType {
+    #[default]
+    None,
+    Text,
+    Heading,
+    Todo,
+    List,
+}
+
+impl FirstBlockType {
+    pub const ALL: [FirstBlockType; 5] = [
+        FirstBlockType::None,
+        FirstBlockType::Text,
+        FirstBlockType::Heading,
+        FirstBlockType::Todo,
+        FirstBlockType::List,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            FirstBlockType::None => "Nothing",
+            FirstBlockType::Text => "Text",
+            FirstBlockType::Heading => "Heading",
+            FirstBlockType::Todo => "To-do",
+            FirstBlockType::List => "List",
+        }
+    }
+
+    /// Identifiers that disable this block type in the editor settings.
+    pub fn block_ids(&self) -> &'static [&'static str] {
+        match self {
+            FirstBlockType::None => &[],
+            FirstBlockType::Text => &["text"],
+            FirstBlockType::Heading => &["heading", "heading_2"],
+            FirstBlockType::Todo => &["todo"],
+            FirstBlockType::List => &["list", "bulleted_list"],
+        }
+    }
+}
+
+/// Order of the day, month and year in dates.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DateOrder {
+    /// Following the system locale.
+    #[default]
+    System,
+    DayMonthYear,
+    MonthDayYear,
+    YearMonthDay,
+}
+
+impl DateOrder {
+    pub const ALL: [DateOrder; 4] = [
+        DateOrder::System,
+        DateOrder::DayMonthYear,
+        DateOrder::MonthDayYear,
+        DateOrder::YearMonthDay,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DateOrder::System => "System",
+            DateOrder::DayMonthYear => "3 Feb 2025",
+            DateOrder::MonthDayYear => "Feb 3, 2025",
+            DateOrder::YearMonthDay => "2025-02-03",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ClockFormat {
+    /// Following the system locale.
+    #[default]
+    System,
+    #[serde(rename = "24h")]
+    TwentyFourHours,
+    #[serde(rename = "12h")]
+    TwelveHours,
+}
+
+impl ClockFormat {
+    pub const ALL: [ClockFormat; 3] = [
+        ClockFormat::System,
+        ClockFormat::TwentyFourHours,
+        ClockFormat::TwelveHours,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ClockFormat::System => "System",
+            ClockFormat::TwentyFourHours => "14:30",
+            ClockFormat::TwelveHours => "2:30 PM",
+        }
+    }
+}
+
+/// Day the weeks start on in the calendar and the activity.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FirstWeekday {
+    /// Following the system locale.
+    #[default]
+    System,
+    Monday,
+    Sunday,
+    Saturday,
+}
+
+impl FirstWeekday {
+    pub const ALL: [FirstWeekday; 4] = [
+        FirstWeekday::System,
+        FirstWeekday::Monday,
+        FirstWeekday::Sunday,
+        FirstWeekday::Saturday,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            FirstWeekday::System => "System",
+            FirstWeekday::Monday => "Monday",
+            FirstWeekday::Sunday => "Sunday",
+            FirstWeekday::Saturday => "Saturday",
+        }
+    }
+}
+
+/// Time between two automatic backups.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupFrequency {
+    #[default]
+    Daily,
+    Weekly,
+}
+
+impl BackupFrequency {
+    pub const ALL: [BackupFrequency; 2] = [BackupFrequency::Daily, BackupFrequency::Weekly];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            BackupFrequency::Daily => "Daily",
+            BackupFrequency::Weekly => "Weekly",
+        }
+    }
+
+    pub fn interval(&self) -> Duration {
+        match self {
+            BackupFrequency::Daily => Duration::from_secs(24 * 60 * 60),
+            BackupFrequency::Weekly => Duration::from_secs(7 * 24 * 60 * 60),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Default)]
 pub struct Settings {
     contexts: Vec<DbContext>,
@@ -38,7 +224,25 @@ pub struct Settings {
     #[serde(default)]
     pub appearance: AppearanceSettings,
     #[serde(default)]
+    pub dates: DateSettings,
+    #[serde(default)]
     pub editor: EditorSettings,
+    #[serde(default)]
+    pub assistant: AssistantSettings,
+    #[serde(default)]
+    pub security: SecuritySettings,
+    #[serde(default)]
+    pub vault: VaultSettings,
+    #[serde(default)]
+    pub database: DatabaseSettings,
+    #[serde(default)]
+    pub api: ApiSettings,
+    #[serde(default)]
+    pub email: EmailSettings,
+    #[serde(default)]
+    pub backup: BackupSettings,
+    #[serde(default)]
+    pub snippets: Vec<Snippet>,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -57,16 +261,167 @@ pub struct AppearanceSettings {
     pub ui_font_size: f32,
 }
 
+/// Overrides of the system locale for dates and times.
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct DateSettings {
+    #[serde(default)]
+    pub order: DateOrder,
+    #[serde(default)]
+    pub clock: ClockFormat,
+    #[serde(default)]
+    pub first_weekday: FirstWeekday,
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct EditorSettings {
     #[serde(default = "default_editor_font_size")]
     pub font_size: f32,
     #[serde(default = "default_zoom")]
     pub zoom: f32,
+    /// Height of the lines of text, relative to the font size.
+    #[serde(default = "default_line_height")]
+    pub line_height: f32,
+    /// Pixels above and below each paragraph, halved for list items and to-dos.
+    #[serde(default = "default_block_spacing")]
+    pub block_spacing: f32,
     #[serde(default)]
     pub disabled_blocks: Vec<String>,
     #[serde(default)]
     pub block_font_sizes: BlockFontSizes,
+    #[serde(default = "default_autocomplete")]
+    pub autocomplete: bool,
+    #[serde(default = "default_hover_toolbar")]
+    pub hover_toolbar: bool,
+    /// Keeps the line being typed in the middle of the document by scrolling it.
+    #[serde(default)]
+    pub typewriter_scrolling: bool,
+    /// Block toolbar actions the user chose to hide, by id.
+    #[serde(default)]
+    pub hidden_toolbar_actions: Vec<String>,
+    /// Seconds without changes before a document is saved.
+    #[serde(default = "default_autosave_delay")]
+    pub autosave_delay: f32,
+    #[serde(default)]
+    pub new_block: NewBlockType,
+    #[serde(default)]
+    pub first_block: FirstBlockType,
+    /// Documents whose headings are numbered, by id.
+    #[serde(default)]
+    pub numbered_headings: Vec<i32>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AssistantSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Base URL of an OpenAI-compatible API (e.g. `http://localhost:11434/v1` for Ollama).
+    #[serde(default = "default_assistant_endpoint")]
+    pub endpoint: String,
+    #[serde(default)]
+    pub api_key: String,
+    #[serde(default = "default_assistant_model")]
+    pub model: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct SecuritySettings {
+    /// The database is encrypted with SQLCipher and must be unlocked at startup.
+    #[serde(default)]
+    pub encrypted: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct VaultSettings {
+    /// Store documents as files in `path` instead of the SQLite database.
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub path: String,
+}
+
+/// Connections to the database, applied at the next start.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct DatabaseSettings {
+    /// Write-ahead logging, so that reads don't wait for writes.
+    #[serde(default = "default_wal")]
+    pub wal: bool,
+    /// Time a write waits for the lock held by another connection before failing.
+    #[serde(default = "default_busy_timeout_ms")]
+    pub busy_timeout_ms: u64,
+    /// Connections opened at most, an encrypted database always using a single one.
+    #[serde(default = "default_max_connections")]
+    pub max_connections: u32,
+}
+
+/// Localhost HTTP API for browser extensions and capture tools, applied at the next start.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ApiSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_api_port")]
+    pub port: u16,
+    /// Bearer token every request must carry.
+    #[serde(default)]
+    pub token: String,
+}
+
+/// Mailbox polled for emails to turn into documents of the "Email" folder.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct EmailSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// IMAP server, reached over TLS.
+    #[serde(default)]
+    pub server: String,
+    #[serde(default = "default_imap_port")]
+    pub port: u16,
+    #[serde(default)]
+    pub username: String,
+    #[serde(default)]
+    pub password: String,
+    /// Mailbox whose unread emails are imported.
+    #[serde(default = "default_email_folder")]
+    pub folder: String,
+    #[serde(default = "default_poll_minutes")]
+    pub poll_minutes: u32,
+}
+
+/// Copies of the database, or of the vault folder, made in the background.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct BackupSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub frequency: BackupFrequency,
+    /// Backups kept in the directory, the oldest ones being deleted.
+    #[serde(default = "default_backups_kept")]
+    pub keep: u32,
+    #[serde(default)]
+    pub directory: String,
+}
+
+/// Text replacing its trigger when Tab is pressed right after it in a block.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Snippet {
+    pub trigger: String,
+    /// `{{date}}` and `{{time}}` are replaced by the current date and time, and the cursor
+    /// is placed at `{{cursor}}`.
+    pub expansion: String,
+}
+
+impl Snippet {
+    /// Snippet whose trigger ends `text` as a word of its own, the longest one if several do.
+    pub fn ending<'a>(snippets: &'a [Snippet], text: &str) -> Option<&'a Snippet> {
+        snippets
+            .iter()
+            .filter(|snippet| !snippet.trigger.is_empty())
+            .filter(|snippet| {
+                text.strip_suffix(&snippet.trigger).is_some_and(|before| {
+                    before.chars().next_back().is_none_or(char::is_whitespace)
+                })
+            })
+            .max_by_key(|snippet| snippet.trigger.len())
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -101,6 +456,66 @@ fn default_zoom() -> f32 {
     1.0
 }
 
+fn default_line_height() -> f32 {
+    1.5
+}
+
+fn default_block_spacing() -> f32 {
+    8.0
+}
+
+fn default_autocomplete() -> bool {
+    true
+}
+
+fn default_hover_toolbar() -> bool {
+    true
+}
+
+fn default_autosave_delay() -> f32 {
+    1.0
+}
+
+fn default_assistant_endpoint() -> String {
+    "https://api.openai.com/v1".to_string()
+}
+
+fn default_assistant_model() -> String {
+    "gpt-4o-mini".to_string()
+}
+
+fn default_wal() -> bool {
+    true
+}
+
+fn default_busy_timeout_ms() -> u64 {
+    5_000
+}
+
+fn default_max_connections() -> u32 {
+    4
+}
+
+fn default_api_port() -> u16 {
+    4517
+}
+
+fn default_imap_port() -> u16 {
+    993
+}
+
+fn default_email_folder() -> String {
+    "INBOX".to_string()
+}
+
+fn default_poll_minutes() -> u32 {
+    5
+}
+
+fn default_backups_kept() -> u32 {
+    7
+}
+
 fn default_h1_font_size() -> f32 {
     30.0
 }
@@ -140,8 +555,202 @@ impl Default for EditorSettings {
         Self {
             font_size: default_editor_font_size(),
             zoom: default_zoom(),
+            line_height: default_line_height(),
+            block_spacing: default_block_spacing(),
             disabled_blocks: Vec::new(),
             block_font_sizes: BlockFontSizes::default(),
+            autocomplete: default_autocomplete(),
+            hover_toolbar: default_hover_toolbar(),
+            typewriter_scrolling: false,
+            hidden_toolbar_actions: Vec::new(),
+            autosave_delay: default_autosave_delay(),
+            new_block: NewBlockType::default(),
+            first_block: FirstBlockType::default(),
+            numbered_headings: Vec::new(),
+        }
+    }
+}
+
+impl EditorSettings {
+    /// Line height of the text blocks, relative to their font size.
+    pub fn line_height(cx: &App) -> f32 {
+        cx.try_global::<Settings>()
+            .map_or_else(default_line_height, |settings| settings.editor.line_height)
+    }
+
+    /// Vertical margin of a block, halved for the `compact` ones like list items.
+    pub fn block_margin(cx: &App, compact: bool) -> Pixels {
+        let spacing = cx
+            .try_global::<Settings>()
+            .map_or_else(default_block_spacing, |settings| {
+                settings.editor.block_spacing
+            });
+        px(if compact { spacing / 2.0 } else { spacing })
+    }
+
+    /// Whether none of the given block identifiers is disabled.
+    pub fn blocks_enabled(&self, ids: &[&str]) -> bool {
+        !ids.iter()
+            .any(|id| self.disabled_blocks.iter().any(|disabled| disabled == id))
+    }
+
+    /// Whether Enter at the end of a block, identified by `ids`, adds a block of the same
+    /// type rather than a text block.
+    pub fn continues_block(&self, ids: &[&str]) -> bool {
+        self.new_block == NewBlockType::Continue && self.blocks_enabled(ids)
+    }
+
+    /// Whether the headings of the document are numbered.
+    pub fn numbers_headings(&self, document_id: i32) -> bool {
+        self.numbered_headings.contains(&document_id)
+    }
+
+    /// Numbers the headings of a document, or stops numbering them.
+    pub fn toggle_numbered_headings(&mut self, document_id: i32) {
+        if self.numbers_headings(document_id) {
+            self.numbered_headings.retain(|id| *id != document_id);
+        } else {
+            self.numbered_headings.push(document_id);
+        }
+    }
+
+    /// Block a new document starts with, nothing when its type is disabled.
+    pub fn first_block(&self) -> FirstBlockType {
+        if self.blocks_enabled(self.first_block.block_ids()) {
+            self.first_block
+        } else {
+            FirstBlockType::None
+        }
+    }
+}
+
+impl Default for AssistantSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: default_assistant_endpoint(),
+            api_key: String::new(),
+            model: default_assistant_model(),
+        }
+    }
+}
+
+impl AssistantSettings {
+    pub fn is_configured(&self) -> bool {
+        self.enabled && !self.endpoint.trim().is_empty() && !self.model.trim().is_empty()
+    }
+}
+
+impl VaultSettings {
+    pub fn is_configured(&self) -> bool {
+        self.enabled && !self.path.trim().is_empty()
+    }
+}
+
+impl DatabaseSettings {
+    pub fn pool_config(&self) -> PoolConfig {
+        PoolConfig {
+            wal: self.wal,
+            busy_timeout: Duration::from_millis(self.busy_timeout_ms),
+            max_connections: self.max_connections,
+        }
+    }
+}
+
+impl Default for DatabaseSettings {
+    fn default() -> Self {
+        Self {
+            wal: default_wal(),
+            busy_timeout_ms: default_busy_timeout_ms(),
+            max_connections: default_max_connections(),
+        }
+    }
+}
+
+impl ApiSettings {
+    pub fn is_configured(&self) -> bool {
+        self.enabled && !self.token.trim().is_empty()
+    }
+
+    /// Random token, hard enough to guess for a server only reachable from this machine.
+    pub fn generate_token() -> String {
+        uuid::Uuid::new_v4().simple().to_string()
+    }
+}
+
+impl Default for ApiSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: default_api_port(),
+            token: String::new(),
+        }
+    }
+}
+
+impl EmailSettings {
+    pub fn is_configured(&self) -> bool {
+        self.enabled
+            && !self.server.trim().is_empty()
+            && !self.username.trim().is_empty()
+            && !self.folder.trim().is_empty()
+    }
+
+    pub fn mailbox(&self) -> Mailbox {
+        Mailbox {
+            server: self.server.clone(),
+            port: self.port,
+            username: self.username.clone(),
+            password: self.password.clone(),
+            folder: self.folder.clone(),
+        }
+    }
+
+    /// Time between two imports, at least a minute even when the settings file was edited
+    /// by hand to import more often.
+    pub fn poll_interval(&self) -> Duration {
+        Duration::from_secs(self.poll_minutes.max(1) as u64 * 60)
+    }
+}
+
+impl Default for EmailSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            server: String::new(),
+            port: default_imap_port(),
+            username: String::new(),
+            password: String::new(),
+            folder: default_email_folder(),
+            poll_minutes: default_poll_minutes(),
+        }
+    }
+}
+
+impl BackupSettings {
+    pub fn is_configured(&self) -> bool {
+        self.enabled && !self.directory.trim().is_empty()
+    }
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            frequency: BackupFrequency::default(),
+            keep: default_backups_kept(),
+            directory: String::new(),
+        }
+    }
+}
+
+impl BlockFontSizes {
+    /// Font size of a heading, levels past the third sharing its size.
+    pub fn heading(&self, level: u32) -> f32 {
+        match level {
+            1 => self.heading_1,
+            2 => self.heading_2,
+            _ => self.heading_3,
         }
     }
 }
@@ -158,21 +767,121 @@ impl Default for BlockFontSizes {
 }
 
 impl Settings {
-    pub fn save(&self) {
-        if let Some(home) = dirs::home_dir() {
-            let config_path = if cfg!(target_os = "linux") || cfg!(target_os = "macos") {
-                home.join(".config").join("remindr")
-            } else {
-                dirs::config_dir()
-                    .unwrap_or(home.join(".config"))
-                    .join("remindr")
-            };
-
-            let settings_file = config_path.join("settings.json");
-            if let Ok(json) = serde_json::to_string_pretty(self) {
-                let _ = std::fs::write(settings_file, json);
+    /// Settings written by "Export settings", without the assistant API key, the token of
+    /// the local API and the email password.
+    pub fn to_export_json(&self) -> Result<String, Error> {
+        let mut settings = self.clone();
+        settings.assistant.api_key.clear();
+        settings.api.token.clear();
+        settings.email.password.clear();
+        Ok(serde_json::to_string_pretty(&settings)?)
+    }
+
+    /// Settings of an exported file, keeping what belongs to this install: the database
+    /// contexts, encryption and connections, the vault, the local API, the mailbox, the
+    /// backups and the assistant API key if the file has none.
+    pub fn imported(&self, json: &str) -> Result<Settings, Error> {
+        let mut settings =
+            serde_json::from_str::<Settings>(json).context("The file isn't a settings file")?;
+        settings.validate()?;
+
+        if settings.assistant.api_key.is_empty() {
+            settings.assistant.api_key = self.assistant.api_key.clone();
+        }
+        Ok(self.keep_install(settings))
+    }
+
+    /// Default settings, keeping what belongs to this install.
+    pub fn reset(&self) -> Settings {
+        self.keep_install(Settings::default())
+    }
+
+    fn keep_install(&self, settings: Settings) -> Settings {
+        Settings {
+            contexts: self.contexts.clone(),
+            security: self.security.clone(),
+            vault: self.vault.clone(),
+            database: self.database.clone(),
+            api: self.api.clone(),
+            email: self.email.clone(),
+            backup: self.backup.clone(),
+            ..settings
+        }
+    }
+
+    /// Checks the values are within the bounds of the settings window.
+    fn validate(&self) -> Result<(), Error> {
+        let sizes = &self.editor.block_font_sizes;
+        let bounds = [
+            ("UI font size", self.appearance.ui_font_size, 10.0, 24.0),
+            ("Editor font size", self.editor.font_size, 10.0, 32.0),
+            ("Zoom", self.editor.zoom, 0.5, 2.0),
+            ("Line height", self.editor.line_height, 1.0, 2.5),
+            ("Block spacing", self.editor.block_spacing, 0.0, 32.0),
+            ("Autosave delay", self.editor.autosave_delay, 0.5, 10.0),
+            ("Heading 1 font size", sizes.heading_1, 8.0, 72.0),
+            ("Heading 2 font size", sizes.heading_2, 8.0, 72.0),
+            ("Heading 3 font size", sizes.heading_3, 8.0, 72.0),
+            ("Text font size", sizes.text, 8.0, 72.0),
+            (
+                "Busy timeout",
+                self.database.busy_timeout_ms as f32,
+                0.0,
+                60_000.0,
+            ),
+            (
+                "Maximum connections",
+                self.database.max_connections as f32,
+                1.0,
+                16.0,
+            ),
+            (
+                "Email check interval",
+                self.email.poll_minutes as f32,
+                1.0,
+                1440.0,
+            ),
+            ("Backups kept", self.backup.keep as f32, 1.0, 100.0),
+        ];
+
+        for (name, value, min, max) in bounds {
+            if !(min..=max).contains(&value) {
+                bail!(
+                    "{} must be between {} and {}, not {}",
+                    name,
+                    min,
+                    max,
+                    value
+                );
             }
         }
+        Ok(())
+    }
+
+    /// Writes the settings file, reporting a failure to the notification center.
+    pub fn save(&self) {
+        if let Err(e) = self.write() {
+            let settings = self.clone();
+            AppError::new(ErrorKind::Settings, "Failed to save the settings", &e)
+                .with_retry(move |_| settings.save())
+                .report();
+        }
+    }
+
+    fn write(&self) -> Result<(), Error> {
+        let home = dirs::home_dir().context("Failed to find the home directory")?;
+        let config_path = if cfg!(target_os = "linux") || cfg!(target_os = "macos") {
+            home.join(".config").join("remindr")
+        } else {
+            dirs::config_dir()
+                .unwrap_or(home.join(".config"))
+                .join("remindr")
+        };
+
+        let settings_file = config_path.join("settings.json");
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(&settings_file, json)
+            .with_context(|| format!("Failed to write {:?}", settings_file))
     }
 }
 
@@ -0,0 +1,101 @@
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+
+use gpui::{App, BorrowAppContext, Global};
+use smol::Timer;
+
+use crate::{
+    app::states::{
+        error_state::{AppError, ErrorKind},
+        repository_state::RepositoryState,
+    },
+    domain::database::synced_block::SyncedBlockModel,
+};
+
+/// Delay between the last edit of a synced block and its save.
+const SAVE_DELAY: Duration = Duration::from_millis(500);
+
+/// Content of the synced blocks shown in open documents. Every instance of a block reads
+/// it from here, so an edit made in one document shows up in all the others.
+#[derive(Default)]
+pub struct SyncedBlockState {
+    blocks: HashMap<i32, String>,
+    /// Blocks edited since their last save.
+    pending: HashSet<i32>,
+}
+
+impl SyncedBlockState {
+    pub fn get(&self, id: i32) -> Option<&str> {
+        self.blocks.get(&id).map(String::as_str)
+    }
+
+    /// Reads a synced block from the repository unless it is already loaded.
+    pub fn load(id: i32, cx: &mut App) {
+        if cx.global::<SyncedBlockState>().blocks.contains_key(&id) {
+            return;
+        }
+
+        let repository = cx.global::<RepositoryState>().synced_blocks.clone();
+        cx.spawn(
+            async move |cx| match repository.get_synced_block(id).await {
+                Ok(block) => cx.update(|cx| {
+                    cx.update_global::<SyncedBlockState, _>(|state, _| {
+                        state.blocks.entry(id).or_insert(block.content);
+                    });
+                    cx.refresh_windows();
+                }),
+                Err(e) => tracing::error!("failed to load block {}: {:?}", id, e),
+            },
+        )
+        .detach();
+    }
+
+    /// Sets the content of a synced block in every document showing it, and saves it
+    /// shortly after.
+    pub fn update(id: i32, content: String, cx: &mut App) {
+        let schedule_save = cx.update_global::<SyncedBlockState, _>(|state, _| {
+            state.blocks.insert(id, content);
+            state.pending.insert(id)
+        });
+        if !schedule_save {
+            return;
+        }
+
+        cx.spawn(async move |cx| {
+            Timer::after(SAVE_DELAY).await;
+
+            let save = cx.update(|cx| {
+                let content = cx.update_global::<SyncedBlockState, _>(|state, _| {
+                    state.pending.remove(&id);
+                    state.blocks.get(&id).cloned()
+                })?;
+                let repository = cx.global::<RepositoryState>().synced_blocks.clone();
+                Some((repository, content))
+            });
+            let Some((repository, content)) = save else {
+                return;
+            };
+
+            let block = SyncedBlockModel { id, content };
+            if let Err(e) = repository.update_synced_block(block.clone()).await {
+                AppError::new(ErrorKind::Storage, "Failed to save a synced block", &e)
+                    .with_retry_operation(move || {
+                        let repository = repository.clone();
+                        let block = block.clone();
+                        async move { repository.update_synced_block(block).await }
+                    })
+                    .report();
+            }
+        })
+        .detach();
+    }
+
+    /// Adds a block just created or picked to the loaded ones.
+    pub fn insert(&mut self, block: SyncedBlockModel) {
+        self.blocks.insert(block.id, block.content);
+    }
+}
+
+impl Global for SyncedBlockState {}
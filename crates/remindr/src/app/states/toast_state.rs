@@ -0,0 +1,269 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    rc::Rc,
+    time::Duration,
+};
+
+use gpui::{
+    AnyWindowHandle, App, AppContext, BorrowAppContext, Global, IntoElement, ParentElement,
+    SharedString, Styled, WeakEntity, Window, div, prelude::FluentBuilder, relative,
+};
+use gpui_component::{
+    ActiveTheme, Sizable, WindowExt,
+    button::{Button, ButtonVariants},
+    notification::{Notification, NotificationType},
+    v_flex,
+};
+use smol::Timer;
+
+/// Toasts shown at once, the next ones waiting for one of them to be dismissed.
+const MAX_VISIBLE: usize = 3;
+
+/// Time a toast stays for when not given another duration.
+const DEFAULT_DURATION: Duration = Duration::from_secs(5);
+
+type ToastCallback = Rc<dyn Fn(&mut Window, &mut App) + 'static>;
+
+/// Slot filled with the notification of a toast once it is rendered.
+type NotificationSlot = Rc<RefCell<Option<WeakEntity<Notification>>>>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ToastKind {
+    Info,
+    Success,
+    Error,
+}
+
+/// Short message shown over the notification layer, with an optional action or progress.
+#[derive(Clone)]
+pub struct Toast {
+    kind: ToastKind,
+    title: Option<SharedString>,
+    message: SharedString,
+    /// Kept until dismissed through its handle when `None`.
+    duration: Option<Duration>,
+    action: Option<(SharedString, ToastCallback)>,
+    progress: Option<ToastProgress>,
+}
+
+/// Completion of the operation shown in a progress toast, from 0 to 1.
+#[derive(Clone, Default)]
+pub struct ToastProgress(Rc<Cell<f32>>);
+
+impl ToastProgress {
+    pub fn set(&self, value: f32, cx: &mut App) {
+        self.0.set(value.clamp(0.0, 1.0));
+        cx.refresh_windows();
+    }
+}
+
+/// Shown or queued toast.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ToastHandle(usize);
+
+impl ToastHandle {
+    /// Hides the toast, or drops it if it is still waiting to be shown.
+    pub fn dismiss(self, cx: &mut App) {
+        ToastState::dismiss(self.0, cx);
+    }
+}
+
+impl Toast {
+    pub fn new(message: impl Into<SharedString>) -> Self {
+        Self {
+            kind: ToastKind::Info,
+            title: None,
+            message: message.into(),
+            duration: Some(DEFAULT_DURATION),
+            action: None,
+            progress: None,
+        }
+    }
+
+    pub fn success(message: impl Into<SharedString>) -> Self {
+        Self {
+            kind: ToastKind::Success,
+            ..Self::new(message)
+        }
+    }
+
+    pub fn error(message: impl Into<SharedString>) -> Self {
+        Self {
+            kind: ToastKind::Error,
+            ..Self::new(message)
+        }
+    }
+
+    pub fn title(mut self, title: impl Into<SharedString>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+
+    /// Adds a button running `callback`, the toast being dismissed afterwards.
+    pub fn action(
+        mut self,
+        label: impl Into<SharedString>,
+        callback: impl Fn(&mut Window, &mut App) + 'static,
+    ) -> Self {
+        self.action = Some((label.into(), Rc::new(callback)));
+        self
+    }
+
+    /// Shows a progress bar following `progress`. The toast stays until dismissed.
+    pub fn progress(mut self, progress: ToastProgress) -> Self {
+        self.progress = Some(progress);
+        self.duration = None;
+        self
+    }
+
+    /// Shows the toast in the window, once fewer than `MAX_VISIBLE` toasts are shown.
+    pub fn show(self, window: &mut Window, cx: &mut App) -> ToastHandle {
+        ToastState::enqueue(self, window.window_handle(), cx)
+    }
+
+    fn notification(self, id: usize, slot: NotificationSlot) -> Notification {
+        let kind = match self.kind {
+            ToastKind::Info => NotificationType::Info,
+            ToastKind::Success => NotificationType::Success,
+            ToastKind::Error => NotificationType::Error,
+        };
+        let message = self.message;
+        let progress = self.progress;
+
+        let notification = Notification::new()
+            .with_type(kind)
+            .autohide(false)
+            .content(move |_, _, cx| {
+                *slot.borrow_mut() = Some(cx.entity().downgrade());
+
+                v_flex()
+                    .gap_2()
+                    .text_sm()
+                    .child(message.clone())
+                    .when_some(progress.clone(), |this, progress| {
+                        this.child(
+                            div()
+                                .h_1()
+                                .w_full()
+                                .rounded_full()
+                                .bg(cx.theme().muted)
+                                .child(
+                                    div()
+                                        .h_full()
+                                        .rounded_full()
+                                        .bg(cx.theme().primary)
+                                        .w(relative(progress.0.get())),
+                                ),
+                        )
+                    })
+                    .into_any_element()
+            })
+            .when_some(self.title, |this, title| this.title(title));
+
+        match self.action {
+            Some((label, callback)) => notification.action(move |_, _, _| {
+                let callback = callback.clone();
+                Button::new(("toast-action", id))
+                    .small()
+                    .ghost()
+                    .label(label.clone())
+                    .on_click(move |_, window, cx| {
+                        callback(window, cx);
+                        ToastState::dismiss(id, cx);
+                    })
+            }),
+            None => notification,
+        }
+    }
+}
+
+/// Toasts waiting to be shown and the ones shown, so that only a few are shown at once.
+#[derive(Default)]
+pub struct ToastState {
+    next_id: usize,
+    queue: VecDeque<(usize, AnyWindowHandle, Toast)>,
+    shown: Vec<(usize, AnyWindowHandle, NotificationSlot)>,
+}
+
+impl ToastState {
+    fn enqueue(toast: Toast, window: AnyWindowHandle, cx: &mut App) -> ToastHandle {
+        let id = cx.update_global::<ToastState, _>(|state, _| {
+            state.next_id += 1;
+            state.queue.push_back((state.next_id, window, toast));
+            state.next_id
+        });
+        Self::show_queued(cx);
+
+        ToastHandle(id)
+    }
+
+    fn show_queued(cx: &mut App) {
+        loop {
+            let next = cx.update_global::<ToastState, _>(|state, _| {
+                // Toasts closed with their close button are gone from the window
+                state.shown.retain(|(_, _, slot)| {
+                    slot.borrow()
+                        .as_ref()
+                        .is_none_or(|notification| notification.upgrade().is_some())
+                });
+
+                if state.shown.len() < MAX_VISIBLE {
+                    state.queue.pop_front()
+                } else {
+                    None
+                }
+            });
+            let Some((id, window, toast)) = next else {
+                return;
+            };
+
+            let slot = NotificationSlot::default();
+            let duration = toast.duration;
+            let notification = toast.notification(id, slot.clone());
+            let shown = window
+                .update(cx, |_, window, cx| {
+                    window.push_notification(notification, cx)
+                })
+                .is_ok();
+            if !shown {
+                continue;
+            }
+
+            cx.update_global::<ToastState, _>(|state, _| state.shown.push((id, window, slot)));
+            if let Some(duration) = duration {
+                cx.spawn(async move |cx| {
+                    Timer::after(duration).await;
+                    cx.update(|cx| Self::dismiss(id, cx));
+                })
+                .detach();
+            }
+        }
+    }
+
+    fn dismiss(id: usize, cx: &mut App) {
+        let shown = cx.update_global::<ToastState, _>(|state, _| {
+            state.queue.retain(|(queued, _, _)| *queued != id);
+            let index = state.shown.iter().position(|(shown, _, _)| *shown == id)?;
+            Some(state.shown.remove(index))
+        });
+
+        if let Some((_, window, slot)) = shown {
+            let notification = slot.borrow_mut().take().and_then(|weak| weak.upgrade());
+            if let Some(notification) = notification {
+                let _ = window.update(cx, |_, window, cx| {
+                    notification.update(cx, |notification, cx| notification.dismiss(window, cx));
+                });
+            }
+        }
+
+        Self::show_queued(cx);
+    }
+}
+
+impl Global for ToastState {}
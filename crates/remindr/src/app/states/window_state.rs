@@ -0,0 +1,111 @@
+use std::{fs, path::PathBuf};
+
+use gpui::{Bounds, Global, Pixels, point, px, size};
+use serde::{Deserialize, Serialize};
+
+use crate::app::remindr::Remindr;
+
+/// Position and size of a main window, in logical pixels.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct SavedBounds {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl SavedBounds {
+    fn from_bounds(bounds: Bounds<Pixels>) -> Self {
+        Self {
+            x: f32::from(bounds.origin.x),
+            y: f32::from(bounds.origin.y),
+            width: f32::from(bounds.size.width),
+            height: f32::from(bounds.size.height),
+        }
+    }
+
+    fn to_bounds(self) -> Bounds<Pixels> {
+        Bounds::new(
+            point(px(self.x), px(self.y)),
+            size(px(self.width), px(self.height)),
+        )
+    }
+}
+
+/// Remembers where the main windows were placed, in `windows.json` next to the settings.
+///
+/// Each open main window holds a slot, so the n-th window opens where the n-th window was
+/// last moved to.
+#[derive(Default)]
+pub struct WindowState {
+    saved: Vec<Option<SavedBounds>>,
+    occupied: Vec<bool>,
+}
+
+impl WindowState {
+    pub fn load() -> Self {
+        let saved = Self::file_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+
+        Self {
+            saved,
+            occupied: Vec::new(),
+        }
+    }
+
+    /// Reserves the first free slot and returns it with the bounds remembered for it.
+    pub fn claim_slot(&mut self) -> (usize, Option<Bounds<Pixels>>) {
+        let slot = match self.occupied.iter().position(|occupied| !occupied) {
+            Some(slot) => slot,
+            None => {
+                self.occupied.push(false);
+                self.occupied.len() - 1
+            }
+        };
+        self.occupied[slot] = true;
+
+        let bounds = self
+            .saved
+            .get(slot)
+            .copied()
+            .flatten()
+            .map(SavedBounds::to_bounds);
+        (slot, bounds)
+    }
+
+    pub fn release_slot(&mut self, slot: usize) {
+        if let Some(occupied) = self.occupied.get_mut(slot) {
+            *occupied = false;
+        }
+    }
+
+    pub fn update_bounds(&mut self, slot: usize, bounds: Bounds<Pixels>) {
+        if self.saved.len() <= slot {
+            self.saved.resize(slot + 1, None);
+        }
+        self.saved[slot] = Some(SavedBounds::from_bounds(bounds));
+        self.save();
+    }
+
+    fn save(&self) {
+        let Some(path) = Self::file_path() else {
+            return;
+        };
+        if let Ok(json) = serde_json::to_string_pretty(&self.saved)
+            && let Err(e) = fs::write(&path, json)
+        {
+            tracing::error!("failed to write {:?}: {:?}", path, e);
+        }
+    }
+
+    fn file_path() -> Option<PathBuf> {
+        Remindr::new()
+            .get_config_dir("remindr")
+            .ok()
+            .map(|config_dir| config_dir.join("windows.json"))
+    }
+}
+
+impl Global for WindowState {}
@@ -0,0 +1,162 @@
+use gpui::App;
+
+#[cfg(feature = "tray")]
+use crate::app::{
+    components::quick_capture::QuickCaptureWindow, states::document_state::DocumentState,
+};
+
+#[cfg(feature = "tray")]
+use global_hotkey::{
+    GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState,
+    hotkey::{CMD_OR_CTRL, Code, HotKey, Modifiers},
+};
+#[cfg(feature = "tray")]
+use gpui::Global;
+#[cfg(feature = "tray")]
+use tray_icon::{
+    Icon, TrayIcon, TrayIconBuilder,
+    menu::{Menu, MenuEvent, MenuItem},
+};
+
+#[cfg(feature = "tray")]
+const QUICK_CAPTURE_ID: &str = "quick-capture";
+#[cfg(feature = "tray")]
+const QUIT_ID: &str = "quit";
+
+/// What the tray icon and the system-wide shortcut ask the app to do.
+#[cfg(feature = "tray")]
+enum TrayEvent {
+    QuickCapture,
+    Quit,
+}
+
+/// Keeps the shortcut registered, and the tray icon shown when it lives on the main thread.
+#[cfg(feature = "tray")]
+struct TrayState {
+    _hotkeys: Option<GlobalHotKeyManager>,
+    _tray: Option<TrayIcon>,
+}
+
+#[cfg(feature = "tray")]
+impl Global for TrayState {}
+
+/// Shows the tray icon and registers the quick capture shortcut system-wide, so a note can
+/// be captured while another app is focused.
+#[cfg(feature = "tray")]
+pub fn init(cx: &mut App) {
+    let (sender, events) = smol::channel::unbounded();
+
+    let hotkeys = match register_hotkey(sender.clone()) {
+        Ok(hotkeys) => Some(hotkeys),
+        Err(e) => {
+            tracing::error!("failed to register the quick capture shortcut: {:?}", e);
+            None
+        }
+    };
+
+    let menu_sender = sender.clone();
+    MenuEvent::set_event_handler(Some(move |event: MenuEvent| {
+        let event = match event.id.0.as_str() {
+            QUICK_CAPTURE_ID => TrayEvent::QuickCapture,
+            QUIT_ID => TrayEvent::Quit,
+            _ => return,
+        };
+        let _ = menu_sender.try_send(event);
+    }));
+
+    // The tray lives on the thread running the gtk loop on Linux, and on the main thread
+    // elsewhere
+    #[cfg(target_os = "linux")]
+    let tray = {
+        std::thread::spawn(|| {
+            if let Err(e) = gtk::init() {
+                tracing::error!("failed to start gtk for the tray icon: {:?}", e);
+                return;
+            }
+            match build_tray() {
+                Ok(_tray) => gtk::main(),
+                Err(e) => tracing::error!("failed to show the tray icon: {:?}", e),
+            }
+        });
+        None
+    };
+    #[cfg(not(target_os = "linux"))]
+    let tray = match build_tray() {
+        Ok(tray) => Some(tray),
+        Err(e) => {
+            tracing::error!("failed to show the tray icon: {:?}", e);
+            None
+        }
+    };
+
+    cx.set_global(TrayState {
+        _hotkeys: hotkeys,
+        _tray: tray,
+    });
+
+    cx.spawn(async move |cx| {
+        while let Ok(event) = events.recv().await {
+            cx.update(|cx| match event {
+                TrayEvent::QuickCapture => QuickCaptureWindow::open(cx),
+                TrayEvent::Quit => DocumentState::quit(cx),
+            });
+        }
+    })
+    .detach();
+}
+
+/// Without the tray, the quick capture shortcut only works while Remindr is focused.
+#[cfg(not(feature = "tray"))]
+pub fn init(_cx: &mut App) {}
+
+/// Registers the shortcut bound to quick capture in the app, Cmd/Ctrl+Shift+Space, with
+/// the system.
+#[cfg(feature = "tray")]
+fn register_hotkey(
+    sender: smol::channel::Sender<TrayEvent>,
+) -> Result<GlobalHotKeyManager, global_hotkey::Error> {
+    let manager = GlobalHotKeyManager::new()?;
+    let hotkey = HotKey::new(Some(CMD_OR_CTRL | Modifiers::SHIFT), Code::Space);
+    manager.register(hotkey)?;
+
+    let id = hotkey.id();
+    GlobalHotKeyEvent::set_event_handler(Some(move |event: GlobalHotKeyEvent| {
+        if event.id == id && event.state == HotKeyState::Pressed {
+            let _ = sender.try_send(TrayEvent::QuickCapture);
+        }
+    }));
+    Ok(manager)
+}
+
+#[cfg(feature = "tray")]
+fn build_tray() -> anyhow::Result<TrayIcon> {
+    let menu = Menu::new();
+    menu.append_items(&[
+        &MenuItem::with_id(QUICK_CAPTURE_ID, "Quick capture", true, None),
+        &MenuItem::with_id(QUIT_ID, "Quit Remindr", true, None),
+    ])?;
+
+    Ok(TrayIconBuilder::new()
+        .with_menu(Box::new(menu))
+        .with_tooltip("Remindr")
+        .with_icon(icon()?)
+        .build()?)
+}
+
+/// A filled circle, drawn rather than decoded to keep image crates out of the build.
+#[cfg(feature = "tray")]
+fn icon() -> Result<Icon, tray_icon::BadIcon> {
+    const SIZE: u32 = 32;
+    let center = (SIZE as f32 - 1.) / 2.;
+    let radius = SIZE as f32 / 2. - 2.;
+
+    let mut rgba = Vec::with_capacity((SIZE * SIZE * 4) as usize);
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            let distance = ((x as f32 - center).powi(2) + (y as f32 - center).powi(2)).sqrt();
+            let alpha = (radius + 0.5 - distance).clamp(0., 1.);
+            rgba.extend_from_slice(&[0x4f, 0x6b, 0xed, (alpha * 255.) as u8]);
+        }
+    }
+    Icon::from_rgba(rgba, SIZE, SIZE)
+}
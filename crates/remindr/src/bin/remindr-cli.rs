@@ -0,0 +1,178 @@
+use std::{collections::HashMap, env, path::PathBuf, process::ExitCode};
+
+use anyhow::{Context, Error, bail};
+use remindr::{
+    app::{
+        remindr::Remindr,
+        states::{repository_state::RepositoryState, settings_state::Settings},
+    },
+    infrastructure::{
+        database,
+        export::{
+            ical,
+            markdown::{
+                ExportLinks, document_to_markdown, export_linked_document, templated_markdown,
+            },
+            template::{ExportTemplate, TemplateFormat},
+        },
+        inbox::append_to_inbox,
+    },
+};
+
+/// Environment variable holding the passphrase of an encrypted database.
+const PASSPHRASE_VAR: &str = "REMINDR_PASSPHRASE";
+
+const USAGE: &str = "\
+Usage: remindr-cli <command>
+
+Commands:
+  list                  List the documents with their id
+  show <id>             Print a document as markdown
+  note <text>           Append a note to the Inbox document
+  export <directory>    Write every document as a markdown file in the directory
+  backup <file>         Copy the database to a new file
+  calendar <file>       Write the reminders and dated tasks as an iCalendar file
+
+The database of the app is used, unlocked with the REMINDR_PASSPHRASE environment
+variable when it is encrypted.";
+
+/// Command line companion of Remindr, working on the same database as the app.
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+
+    match run(&args).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {:#}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(args: &[String]) -> Result<(), Error> {
+    let Some((command, rest)) = args.split_first() else {
+        println!("{}", USAGE);
+        return Ok(());
+    };
+    if matches!(command.as_str(), "help" | "-h" | "--help") {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+
+    let remindr = Remindr::new();
+    let settings = remindr.load_settings().await?;
+    let database_path = remindr.init_default_database().await?;
+
+    let passphrase = if settings.security.encrypted {
+        Some(env::var(PASSPHRASE_VAR).with_context(|| {
+            format!(
+                "The database is encrypted, set {} to unlock it",
+                PASSPHRASE_VAR
+            )
+        })?)
+    } else {
+        None
+    };
+    let pool = database::open(
+        &database_path,
+        passphrase.as_deref(),
+        &settings.database.pool_config(),
+    )
+    .await?;
+
+    let repositories = RepositoryState::for_settings(pool.clone(), Some(&settings));
+    let documents = &repositories.documents;
+
+    match (command.as_str(), rest) {
+        ("list", []) => {
+            for document in documents.get_documents().await? {
+                println!("{}\t{}", document.id, document.title);
+            }
+        }
+        ("show", [id]) => {
+            let id = id.parse::<i32>().context("The id must be a number")?;
+            let document = documents
+                .find_document(id)
+                .await?
+                .with_context(|| format!("No document with the id {}", id))?;
+            let links = ExportLinks::default();
+            let markdown = match markdown_template(&remindr, &repositories).await? {
+                Some((template, tags)) => templated_markdown(
+                    &document,
+                    numbered(&settings, id),
+                    &links,
+                    &template,
+                    tags.get(&id).map(Vec::as_slice).unwrap_or_default(),
+                )?,
+                None => document_to_markdown(&document, numbered(&settings, id), &links),
+            };
+            print!("{}", markdown);
+        }
+        ("note", words) if !words.is_empty() => {
+            let id = append_to_inbox(documents, words.join(" ")).await?;
+            println!("Added to the Inbox document ({})", id);
+        }
+        ("export", [directory]) => {
+            let directory = PathBuf::from(directory);
+            let exported = documents.get_documents().await?;
+            let template = markdown_template(&remindr, &repositories).await?;
+            let links = ExportLinks::reserve(&exported, &directory, |id| numbered(&settings, id))?;
+            for document in &exported {
+                let (template, tags) = match &template {
+                    Some((template, tags)) => (Some(template), tags.get(&document.id)),
+                    None => (None, None),
+                };
+                export_linked_document(
+                    document,
+                    &links,
+                    numbered(&settings, document.id),
+                    template,
+                    tags.map(Vec::as_slice).unwrap_or_default(),
+                )?;
+            }
+            println!("Exported {} documents to {:?}", exported.len(), directory);
+        }
+        ("backup", [file]) => {
+            let target = PathBuf::from(file);
+            database::backup(&pool, &target).await?;
+            println!("Backed up the database to {:?}", target);
+        }
+        ("calendar", [file]) => {
+            let reminders = repositories.reminders.get_pending_reminders().await?;
+            let tasks = repositories.tasks.get_open_tasks().await?;
+            let calendar = ical::calendar(&reminders, &tasks, chrono::Utc::now().timestamp());
+            std::fs::write(file, calendar)?;
+            println!("Wrote the calendar to {:?}", file);
+        }
+        _ => bail!("Unknown command\n\n{}", USAGE),
+    }
+
+    documents.flush_index().await;
+    pool.close().await;
+    Ok(())
+}
+
+fn numbered(settings: &Settings, document_id: i32) -> bool {
+    settings.editor.numbered_headings.contains(&document_id)
+}
+
+/// Markdown export template written by the user, with the tags of every document.
+async fn markdown_template(
+    remindr: &Remindr,
+    repositories: &RepositoryState,
+) -> Result<Option<(ExportTemplate, HashMap<i32, Vec<String>>)>, Error> {
+    let Some(template) = ExportTemplate::load(&remindr.templates_dir()?, TemplateFormat::Markdown)?
+    else {
+        return Ok(None);
+    };
+
+    let mut document_tags = HashMap::<i32, Vec<String>>::new();
+    for tag in repositories.tags.get_document_tags().await? {
+        document_tags
+            .entry(tag.document_id)
+            .or_default()
+            .push(tag.tag);
+    }
+    Ok(Some((template, document_tags)))
+}
@@ -0,0 +1,11 @@
+use chrono::NaiveDate;
+use sqlx::FromRow;
+
+/// Editing done on one day, recorded on this device each time a document is saved.
+#[derive(Debug, FromRow, Clone)]
+pub struct ActivityModel {
+    pub day: NaiveDate,
+    /// Documents saved at least once that day.
+    pub documents_edited: i32,
+    pub blocks_created: i32,
+}
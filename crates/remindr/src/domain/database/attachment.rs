@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// File attached to a document through an attachment block. The content itself is read
+/// through the repository when the file is opened.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct AttachmentModel {
+    pub id: i32,
+    pub file_name: String,
+    /// Size in bytes.
+    pub size: i64,
+}
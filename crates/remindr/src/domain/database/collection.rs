@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+
+/// Property of the documents shown as a column of a collection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CollectionColumn {
+    Title,
+    Tags,
+    Words,
+    Todos,
+    DueDate,
+}
+
+impl CollectionColumn {
+    pub const ALL: [CollectionColumn; 5] = [
+        CollectionColumn::Title,
+        CollectionColumn::Tags,
+        CollectionColumn::Words,
+        CollectionColumn::Todos,
+        CollectionColumn::DueDate,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CollectionColumn::Title => "Title",
+            CollectionColumn::Tags => "Tags",
+            CollectionColumn::Words => "Words",
+            CollectionColumn::Todos => "Open to-dos",
+            CollectionColumn::DueDate => "Due date",
+        }
+    }
+}
+
+/// How a folder is displayed as a collection: the visible columns, the sort and the filter.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CollectionView {
+    #[serde(default = "CollectionView::default_columns")]
+    pub columns: Vec<CollectionColumn>,
+    #[serde(default = "CollectionView::default_sort")]
+    pub sort: CollectionColumn,
+    #[serde(default)]
+    pub descending: bool,
+    /// Text the title or the tags of the documents must contain.
+    #[serde(default)]
+    pub filter: String,
+}
+
+impl CollectionView {
+    fn default_columns() -> Vec<CollectionColumn> {
+        CollectionColumn::ALL.to_vec()
+    }
+
+    fn default_sort() -> CollectionColumn {
+        CollectionColumn::Title
+    }
+}
+
+impl Default for CollectionView {
+    fn default() -> Self {
+        Self {
+            columns: Self::default_columns(),
+            sort: Self::default_sort(),
+            descending: false,
+            filter: String::new(),
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use std::{collections::HashMap, path::PathBuf};
+
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use sqlx::FromRow;
@@ -9,3 +11,479 @@ pub struct DocumentModel {
     pub content: Value,
     pub folder_id: Option<i32>,
 }
+
+/// Where a document is stored, to read it as written when its content can't be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DocumentLocation {
+    /// Row of the documents table, by id.
+    Row(i32),
+    /// File of the vault, relative to its root.
+    File(PathBuf),
+}
+
+/// Document as stored, its content being the JSON text of its blocks.
+#[derive(Debug, Clone)]
+pub struct StoredDocument {
+    pub location: DocumentLocation,
+    pub title: String,
+    pub content: String,
+}
+
+/// Text of the blocks of a document, one block per line, as indexed for search.
+pub fn plain_text(content: &Value) -> String {
+    content
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|block| block.get("metadata")?.get("content")?.as_str())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Documents opened by the link blocks of a document, without duplicates.
+pub fn linked_documents(content: &Value) -> Vec<i32> {
+    let mut ids = content
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|block| block.get("type").and_then(Value::as_str) == Some("link"))
+        .filter_map(|block| block.get("metadata")?.get("document_id")?.as_i64())
+        .map(|id| id as i32)
+        .collect::<Vec<_>>();
+    ids.sort_unstable();
+    ids.dedup();
+    ids
+}
+
+/// Outline numbers (1, 1.1, 1.2.1…) of headings of the given levels, in document order.
+/// The shallowest level present is numbered first, skipped levels count as 0.
+pub fn outline_numbers(levels: &[u32]) -> Vec<String> {
+    let top = levels.iter().copied().min().unwrap_or(1);
+    let mut counters: Vec<u32> = Vec::new();
+
+    levels
+        .iter()
+        .map(|level| {
+            let depth = (level - top) as usize + 1;
+            counters.resize(depth, 0);
+            counters[depth - 1] += 1;
+            counters
+                .iter()
+                .map(u32::to_string)
+                .collect::<Vec<_>>()
+                .join(".")
+        })
+        .collect()
+}
+
+/// Anchors of headings given in document order, named like GitHub does: the text in
+/// lowercase, without punctuation and with dashes for spaces, numbered from 1 when repeated.
+/// They only change when the headings are edited, so links to them stay valid.
+pub fn heading_slugs<'a>(headings: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut used = HashMap::<String, usize>::new();
+    headings
+        .into_iter()
+        .map(|heading| {
+            let mut slug = heading
+                .trim()
+                .to_lowercase()
+                .chars()
+                .filter(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_'))
+                .map(|c| if c == ' ' { '-' } else { c })
+                .collect::<String>();
+            if slug.is_empty() {
+                slug = "section".to_string();
+            }
+            let count = used.entry(slug.clone()).or_default();
+            let unique = match *count {
+                0 => slug,
+                count => format!("{}-{}", slug, count),
+            };
+            *count += 1;
+            unique
+        })
+        .collect()
+}
+
+/// Occurrence of a searched text in a textual block, by byte offset in the block content.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TextMatch {
+    pub block_id: String,
+    pub start: usize,
+    /// Text found, which differs from the searched text by its case when it was ignored.
+    pub text: String,
+    /// Text around the occurrence, to preview it.
+    pub before: String,
+    pub after: String,
+}
+
+/// Characters of context kept on each side of a match.
+const MATCH_CONTEXT: usize = 32;
+
+/// Occurrences of `needle` in the text of the blocks of a document, in document order. The
+/// case of the text is ignored when `any_case` is set.
+pub fn find_matches(content: &Value, needle: &str, any_case: bool) -> Vec<TextMatch> {
+    if needle.is_empty() {
+        return Vec::new();
+    }
+
+    content
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|block| {
+            let id = block.get("id")?.as_str()?;
+            let text = block.get("metadata")?.get("content")?.as_str()?;
+            Some((id, text))
+        })
+        .flat_map(|(id, text)| {
+            occurrences(text, needle, any_case)
+                .into_iter()
+                .map(move |(start, end)| {
+                    let before = text[..start]
+                        .chars()
+                        .rev()
+                        .take(MATCH_CONTEXT)
+                        .collect::<Vec<_>>();
+                    TextMatch {
+                        block_id: id.to_string(),
+                        start,
+                        text: text[start..end].to_string(),
+                        before: before.into_iter().rev().collect(),
+                        after: text[end..].chars().take(MATCH_CONTEXT).collect(),
+                    }
+                })
+        })
+        .collect()
+}
+
+/// Byte ranges of the occurrences of `needle` in `text`, which don't overlap.
+fn occurrences(text: &str, needle: &str, any_case: bool) -> Vec<(usize, usize)> {
+    if !any_case {
+        return text
+            .match_indices(needle)
+            .map(|(start, found)| (start, start + found.len()))
+            .collect();
+    }
+
+    let mut ranges = Vec::new();
+    let mut from = 0;
+    for (start, _) in text.char_indices() {
+        if start < from {
+            continue;
+        }
+        if let Some(len) = starts_with_any_case(&text[start..], needle) {
+            ranges.push((start, start + len));
+            from = start + len;
+        }
+    }
+    ranges
+}
+
+/// Length in bytes of the start of `text` equal to `needle` regardless of case.
+fn starts_with_any_case(text: &str, needle: &str) -> Option<usize> {
+    let mut chars = text.chars();
+    let mut len = 0;
+    for expected in needle.chars() {
+        let found = chars.next()?;
+        if !found.to_lowercase().eq(expected.to_lowercase()) {
+            return None;
+        }
+        len += found.len_utf8();
+    }
+    Some(len)
+}
+
+/// Cases the replacement like the text it replaces: lowercase, Capitalized or UPPERCASE. The
+/// replacement is kept as typed when the text is cased otherwise.
+pub fn match_case_pattern(found: &str, replacement: &str) -> String {
+    let letters = found
+        .chars()
+        .filter(|c| c.is_alphabetic())
+        .collect::<Vec<_>>();
+    let Some((first, rest)) = letters.split_first() else {
+        return replacement.to_string();
+    };
+
+    if letters.iter().all(|c| c.is_lowercase()) {
+        replacement.to_lowercase()
+    } else if !rest.is_empty() && letters.iter().all(|c| c.is_uppercase()) {
+        replacement.to_uppercase()
+    } else if first.is_uppercase() && rest.iter().all(|c| c.is_lowercase()) {
+        let mut chars = replacement.chars();
+        chars
+            .next()
+            .map(|first| {
+                first
+                    .to_uppercase()
+                    .chain(chars.flat_map(char::to_lowercase))
+                    .collect()
+            })
+            .unwrap_or_default()
+    } else {
+        replacement.to_string()
+    }
+}
+
+/// Replaces the given occurrences by `replacement`, cased like each of them when
+/// `case_pattern` is set, and returns the number of replacements. Occurrences no longer found
+/// at their offset are left as they are.
+pub fn replace_matches(
+    content: &mut Value,
+    replacement: &str,
+    matches: &[TextMatch],
+    case_pattern: bool,
+) -> usize {
+    let mut replaced = 0;
+    for block in content.as_array_mut().into_iter().flatten() {
+        let Some(id) = block.get("id").and_then(Value::as_str).map(str::to_string) else {
+            continue;
+        };
+        let Some(text) = block
+            .get_mut("metadata")
+            .and_then(|metadata| metadata.get_mut("content"))
+        else {
+            continue;
+        };
+        let Some(current) = text.as_str() else {
+            continue;
+        };
+
+        let mut found = matches
+            .iter()
+            .filter(|text_match| text_match.block_id == id)
+            .filter(|text_match| {
+                current
+                    .get(text_match.start..)
+                    .is_some_and(|rest| rest.starts_with(&text_match.text))
+            })
+            .collect::<Vec<_>>();
+        if found.is_empty() {
+            continue;
+        }
+        found.sort_unstable_by_key(|text_match| text_match.start);
+        found.dedup_by_key(|text_match| text_match.start);
+
+        // Replaced from the end so the offsets before stay valid
+        let mut updated = current.to_string();
+        let mut previous = usize::MAX;
+        for text_match in found.into_iter().rev() {
+            let end = text_match.start + text_match.text.len();
+            // Overlapping occurrences can't both be replaced
+            if end > previous {
+                continue;
+            }
+            let replacement = if case_pattern {
+                match_case_pattern(&text_match.text, replacement)
+            } else {
+                replacement.to_string()
+            };
+            updated.replace_range(text_match.start..end, &replacement);
+            previous = text_match.start;
+            replaced += 1;
+        }
+        *text = Value::String(updated);
+    }
+    replaced
+}
+
+/// Versions of the block `block_id` found in snapshots of its document, given newest first
+/// with their time. Each version is listed once, at its newest snapshot, and the `current`
+/// one is left out. The creation and edit times of the block don't make versions differ.
+pub fn block_versions(
+    block_id: &str,
+    current: &Value,
+    snapshots: &[(i64, Value)],
+) -> Vec<(i64, Value)> {
+    let mut seen = vec![without_times(current)];
+    let mut versions = Vec::new();
+    for (time, content) in snapshots {
+        let Some(block) = content
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|block| block.get("id").and_then(Value::as_str) == Some(block_id))
+        else {
+            continue;
+        };
+        let version = without_times(block);
+        if !seen.contains(&version) {
+            seen.push(version);
+            versions.push((*time, block.clone()));
+        }
+    }
+    versions
+}
+
+fn without_times(block: &Value) -> Value {
+    let mut block = block.clone();
+    if let Some(metadata) = block.get_mut("metadata").and_then(Value::as_object_mut) {
+        metadata.remove("created_at");
+        metadata.remove("updated_at");
+    }
+    block
+}
+
+/// Blocks of a template with the `{{name}}` placeholders of their text replaced by the
+/// values of `variables`, unknown ones being kept as written. `{{cursor}}` is removed and
+/// the index of the first block that held it is returned with the blocks.
+pub fn expand_template(
+    blocks: &[Value],
+    variables: &[(&str, String)],
+) -> (Vec<Value>, Option<usize>) {
+    let mut cursor = None;
+    let blocks = blocks
+        .iter()
+        .enumerate()
+        .map(|(index, block)| {
+            let mut block = block.clone();
+            if let Some(content) = block.pointer_mut("/metadata/content")
+                && let Some(text) = content.as_str()
+            {
+                let (text, offset) = expand_placeholders(text, variables);
+                if offset.is_some() {
+                    cursor.get_or_insert(index);
+                }
+                *content = Value::String(text);
+            }
+            block
+        })
+        .collect();
+    (blocks, cursor)
+}
+
+/// Text with its `{{name}}` placeholders replaced by the values of `variables`, unknown
+/// ones being kept as written, and the offset `{{cursor}}` was at, removed.
+pub fn expand_placeholders(text: &str, variables: &[(&str, String)]) -> (String, Option<usize>) {
+    let mut expanded = String::with_capacity(text.len());
+    let mut cursor = None;
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let Some(length) = rest[start..].find("}}") else {
+            break;
+        };
+        let placeholder = &rest[start..start + length + 2];
+        expanded.push_str(&rest[..start]);
+        match placeholder[2..placeholder.len() - 2].trim() {
+            "cursor" => {
+                cursor.get_or_insert(expanded.len());
+            }
+            name => match variables.iter().find(|(variable, _)| *variable == name) {
+                Some((_, value)) => expanded.push_str(value),
+                None => expanded.push_str(placeholder),
+            },
+        }
+        rest = &rest[start + length + 2..];
+    }
+    expanded.push_str(rest);
+    (expanded, cursor)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn heading_slugs_are_unique() {
+        assert_eq!(
+            heading_slugs([
+                "Getting started",
+                "What's new?",
+                "Getting started",
+                "",
+                "1.2 Setup"
+            ]),
+            vec![
+                "getting-started",
+                "whats-new",
+                "getting-started-1",
+                "section",
+                "12-setup",
+            ]
+        );
+    }
+
+    #[test]
+    fn case_pattern_follows_the_replaced_text() {
+        assert_eq!(match_case_pattern("color", "Colour"), "colour");
+        assert_eq!(match_case_pattern("Color", "colour"), "Colour");
+        assert_eq!(match_case_pattern("COLOR", "colour"), "COLOUR");
+        assert_eq!(match_case_pattern("A", "the"), "The");
+        assert_eq!(match_case_pattern("iPhone", "pixel"), "pixel");
+        assert_eq!(match_case_pattern("42", "Pixel"), "Pixel");
+    }
+
+    #[test]
+    fn replaces_every_case_variant() {
+        let mut content = json!([
+            { "id": "a", "type": "text", "metadata": { "content": "Todo: the todo list, TODO" } },
+            { "id": "b", "type": "divider", "metadata": {} },
+        ]);
+
+        let matches = find_matches(&content, "todo", true);
+        assert_eq!(matches.len(), 3);
+        assert_eq!(replace_matches(&mut content, "task", &matches, true), 3);
+        assert_eq!(
+            content[0]["metadata"]["content"],
+            "Task: the task list, TASK"
+        );
+    }
+
+    #[test]
+    fn skips_matches_changed_since_found() {
+        let mut content = json!([
+            { "id": "a", "type": "text", "metadata": { "content": "one two one" } },
+        ]);
+        let matches = find_matches(&content, "one", false);
+        content[0]["metadata"]["content"] = json!("one two 1");
+
+        assert_eq!(replace_matches(&mut content, "three", &matches, false), 1);
+        assert_eq!(content[0]["metadata"]["content"], "three two 1");
+    }
+
+    #[test]
+    fn block_versions_skip_unchanged_snapshots() {
+        let text = |content: &str, updated_at: i64| {
+            json!({
+                "id": "a",
+                "type": "text",
+                "metadata": { "content": content, "updated_at": updated_at },
+            })
+        };
+        let snapshots = vec![
+            (30, json!([text("third", 3)])),
+            (20, json!([text("second", 2)])),
+            (15, json!([])),
+            (10, json!([text("second", 2)])),
+            (5, json!([text("first", 1)])),
+        ];
+
+        let versions = block_versions("a", &text("third", 4), &snapshots);
+        let times = versions.iter().map(|(time, _)| *time).collect::<Vec<_>>();
+        assert_eq!(times, vec![20, 5]);
+        assert_eq!(versions[1].1["metadata"]["content"], "first");
+    }
+
+    #[test]
+    fn templates_expand_their_placeholders() {
+        let blocks = json!([
+            { "id": "a", "type": "heading", "metadata": { "content": "{{title}} – {{ date }}" } },
+            { "id": "b", "type": "text", "metadata": { "content": "Notes: {{cursor}}{{unknown}}" } },
+        ]);
+        let variables = [
+            ("title", "Standup".to_string()),
+            ("date", "16/10/2026".to_string()),
+        ];
+
+        let (expanded, cursor) = expand_template(blocks.as_array().unwrap(), &variables);
+        assert_eq!(expanded[0]["metadata"]["content"], "Standup – 16/10/2026");
+        assert_eq!(expanded[1]["metadata"]["content"], "Notes: {{unknown}}");
+        assert_eq!(cursor, Some(1));
+        assert_eq!(
+            expand_placeholders("Hi {{ cursor }}!", &[]),
+            ("Hi !".to_string(), Some(3))
+        );
+    }
+}
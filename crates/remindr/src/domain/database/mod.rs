@@ -1,2 +1,10 @@
+pub mod activity;
+pub mod attachment;
+pub mod collection;
 pub mod document;
 pub mod folder;
+pub mod reminder;
+pub mod smart_folder;
+pub mod synced_block;
+pub mod tag;
+pub mod task;
@@ -0,0 +1,226 @@
+use anyhow::{Error, anyhow, bail};
+use chrono::{DateTime, Datelike, Duration, Local, Months, NaiveDateTime, TimeZone, Timelike};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Reminder attached to a document. Times are Unix timestamps in seconds.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct ReminderModel {
+    pub id: i32,
+    pub document_id: i32,
+    pub title: String,
+    pub due_at: i64,
+    /// Recurrence rule as written by [`Recurrence::to_rule`], if the reminder repeats.
+    pub recurrence: Option<String>,
+    pub snoozed_until: Option<i64>,
+    pub completed: bool,
+}
+
+impl ReminderModel {
+    /// Time the reminder should fire, accounting for snoozing.
+    pub fn trigger_at(&self) -> i64 {
+        self.snoozed_until.unwrap_or(self.due_at)
+    }
+
+    pub fn is_overdue(&self, now: i64) -> bool {
+        !self.completed && self.trigger_at() <= now
+    }
+
+    pub fn recurrence(&self) -> Option<Recurrence> {
+        self.recurrence
+            .as_deref()
+            .and_then(|rule| Recurrence::parse(rule).ok())
+    }
+
+    pub fn snooze(&mut self, until: i64) {
+        self.snoozed_until = Some(until);
+    }
+
+    /// Marks the reminder as done. Recurring reminders move to their next occurrence after
+    /// `now` instead of being completed.
+    pub fn complete(&mut self, now: i64) {
+        self.snoozed_until = None;
+
+        match self.recurrence().and_then(|recurrence| {
+            let mut due_at = self.due_at;
+            while due_at <= now {
+                due_at = recurrence.next_after(due_at)?;
+            }
+            Some(due_at)
+        }) {
+            Some(due_at) => self.due_at = due_at,
+            None => self.completed = true,
+        }
+    }
+}
+
+/// How a reminder repeats once done.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Recurrence {
+    Daily,
+    Weekly,
+    Monthly,
+    /// Five-field cron expression: minute, hour, day of month, month and day of week.
+    Cron(CronSchedule),
+}
+
+impl Recurrence {
+    /// Parses `daily`, `weekly`, `monthly` or a cron expression such as `0 9 * * 1-5`.
+    pub fn parse(rule: &str) -> Result<Self, Error> {
+        match rule.trim().to_lowercase().as_str() {
+            "daily" => Ok(Recurrence::Daily),
+            "weekly" => Ok(Recurrence::Weekly),
+            "monthly" => Ok(Recurrence::Monthly),
+            expression => CronSchedule::parse(expression).map(Recurrence::Cron),
+        }
+    }
+
+    pub fn to_rule(&self) -> String {
+        match self {
+            Recurrence::Daily => "daily".to_string(),
+            Recurrence::Weekly => "weekly".to_string(),
+            Recurrence::Monthly => "monthly".to_string(),
+            Recurrence::Cron(schedule) => schedule.expression.clone(),
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Recurrence::Daily => "Every day".to_string(),
+            Recurrence::Weekly => "Every week".to_string(),
+            Recurrence::Monthly => "Every month".to_string(),
+            Recurrence::Cron(schedule) => format!("Cron: {}", schedule.expression),
+        }
+    }
+
+    /// Next occurrence strictly after `timestamp`, in local time.
+    pub fn next_after(&self, timestamp: i64) -> Option<i64> {
+        let time = Local.timestamp_opt(timestamp, 0).single()?;
+
+        let next = match self {
+            Recurrence::Daily => time + Duration::days(1),
+            Recurrence::Weekly => time + Duration::weeks(1),
+            Recurrence::Monthly => time.checked_add_months(Months::new(1))?,
+            Recurrence::Cron(schedule) => return schedule.next_after(time),
+        };
+
+        Some(next.timestamp())
+    }
+}
+
+/// Parsed cron expression. Each field accepts `*`, numbers, ranges (`1-5`), lists (`1,15`)
+/// and steps (`*/15`, `0-30/10`). Days of week go from 0 (Sunday) to 6, 7 also being Sunday.
+/// Unlike classic cron, a time must match both the day of month and the day of week.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    expression: String,
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+}
+
+impl CronSchedule {
+    pub fn parse(expression: &str) -> Result<Self, Error> {
+        let fields = expression.split_whitespace().collect::<Vec<_>>();
+        let [minutes, hours, days, months, weekdays] = fields.as_slice() else {
+            bail!(
+                "Expected 5 fields (minute hour day month weekday), got {}",
+                fields.len()
+            );
+        };
+
+        let mut weekdays = parse_field(weekdays, 0, 7)?;
+        // 7 is an alias of Sunday
+        if weekdays & (1 << 7) != 0 {
+            weekdays |= 1;
+        }
+
+        Ok(Self {
+            expression: fields.join(" "),
+            minutes: parse_field(minutes, 0, 59)?,
+            hours: parse_field(hours, 0, 23)?,
+            days: parse_field(days, 1, 31)?,
+            months: parse_field(months, 1, 12)?,
+            weekdays,
+        })
+    }
+
+    fn next_after(&self, time: DateTime<Local>) -> Option<i64> {
+        let mut candidate =
+            time.naive_local().with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        // Expressions such as `0 0 31 2 *` never match, so the search is bounded
+        let limit = candidate + Duration::days(366 * 5);
+
+        while candidate < limit {
+            if !matches(self.months, candidate.month()) {
+                candidate = first_of_next_month(candidate)?;
+            } else if !matches(self.days, candidate.day())
+                || !matches(self.weekdays, candidate.weekday().num_days_from_sunday())
+            {
+                candidate = candidate.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+            } else if !matches(self.hours, candidate.hour()) {
+                candidate = candidate.with_minute(0)? + Duration::hours(1);
+            } else if !matches(self.minutes, candidate.minute()) {
+                candidate += Duration::minutes(1);
+            } else {
+                // Times skipped by a DST change are not matched
+                if let Some(time) = Local.from_local_datetime(&candidate).earliest() {
+                    return Some(time.timestamp());
+                }
+                candidate += Duration::minutes(1);
+            }
+        }
+
+        None
+    }
+}
+
+fn matches(field: u64, value: u32) -> bool {
+    field & (1 << value) != 0
+}
+
+fn first_of_next_month(time: NaiveDateTime) -> Option<NaiveDateTime> {
+    time.date()
+        .with_day(1)?
+        .checked_add_months(Months::new(1))?
+        .and_hms_opt(0, 0, 0)
+}
+
+/// Parses one cron field into a bit set of the allowed values.
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64, Error> {
+    let mut set = 0u64;
+
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>()?),
+            None => (part, 1),
+        };
+        if step == 0 {
+            bail!("Invalid step in \"{}\"", part);
+        }
+
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((start, end)) => (start.parse::<u32>()?, end.parse::<u32>()?),
+                None => {
+                    let value = range.parse::<u32>()?;
+                    // `5/10` means from 5 to the end of the range
+                    (value, if part.contains('/') { max } else { value })
+                }
+            },
+        };
+
+        if start < min || end > max || start > end {
+            return Err(anyhow!("\"{}\" is out of range {}-{}", part, min, max));
+        }
+
+        for value in (start..=end).step_by(step as usize) {
+            set |= 1 << value;
+        }
+    }
+
+    Ok(set)
+}
@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use crate::domain::database::{
+    document::DocumentModel,
+    task::{TaskModel, date_mentions},
+};
+
+/// Saved search listed in the sidebar, whose documents are found again each time it is
+/// expanded.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct SmartFolderModel {
+    pub id: i32,
+    pub name: String,
+    /// Words separated by spaces, all of which must match. `#tag` requires a tag,
+    /// `has:todos` an open to-do and `has:date` a date mention; any other word is searched
+    /// in the title and the text of the blocks.
+    pub query: String,
+}
+
+impl SmartFolderModel {
+    pub fn matches(&self, document: &DocumentModel, tags: &[String]) -> bool {
+        let texts = document
+            .content
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|block| block.get("metadata")?.get("content")?.as_str())
+            .map(str::to_lowercase)
+            .collect::<Vec<_>>();
+        let title = document.title.to_lowercase();
+
+        self.query
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .all(|term| match term.as_str() {
+                "has:todos" => !TaskModel::from_document(document.id, &document.content).is_empty(),
+                "has:date" => texts.iter().any(|text| !date_mentions(text).is_empty()),
+                term => match term.strip_prefix('#') {
+                    Some(tag) => tags.iter().any(|other| other.to_lowercase() == tag),
+                    None => title.contains(term) || texts.iter().any(|text| text.contains(term)),
+                },
+            })
+    }
+}
@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Content shared by every synced block referencing it, whatever the document.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct SyncedBlockModel {
+    pub id: i32,
+    pub content: String,
+}
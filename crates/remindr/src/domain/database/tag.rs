@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct DocumentTagModel {
+    pub document_id: i32,
+    pub tag: String,
+}
@@ -0,0 +1,64 @@
+use chrono::NaiveDate;
+use serde_json::Value;
+use sqlx::FromRow;
+
+/// Prefix of the date mentions written in blocks, e.g. `@2026-10-16`.
+pub const DATE_MENTION_PREFIX: char = '@';
+
+/// Format of the date following the mention prefix.
+pub const DATE_MENTION_FORMAT: &str = "%Y-%m-%d";
+
+/// Open to-do block of a document, as indexed when the document is saved.
+#[derive(Debug, FromRow, Clone)]
+pub struct TaskModel {
+    pub document_id: i32,
+    pub block_id: String,
+    pub content: String,
+    /// First date mentioned in the task, if any.
+    pub due_date: Option<NaiveDate>,
+}
+
+impl TaskModel {
+    /// Extracts the to-do blocks of a document that are not done yet.
+    pub fn from_document(document_id: i32, content: &Value) -> Vec<TaskModel> {
+        content
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|block| block.get("type").and_then(Value::as_str) == Some("todo"))
+            .filter_map(|block| {
+                let metadata = block.get("metadata");
+                let status = metadata
+                    .and_then(|metadata| metadata.get("status"))
+                    .and_then(Value::as_str);
+                if status == Some("done") {
+                    return None;
+                }
+
+                let text = metadata
+                    .and_then(|metadata| metadata.get("content"))
+                    .and_then(Value::as_str)
+                    .unwrap_or_default();
+
+                Some(TaskModel {
+                    document_id,
+                    block_id: block.get("id")?.as_str()?.to_string(),
+                    content: text.to_string(),
+                    due_date: date_mentions(text).first().copied(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Dates mentioned in a text with the `@YYYY-MM-DD` syntax, in order of appearance.
+pub fn date_mentions(text: &str) -> Vec<NaiveDate> {
+    let mut dates = text
+        .split(DATE_MENTION_PREFIX)
+        .skip(1)
+        .filter_map(|rest| rest.get(..10))
+        .filter_map(|date| NaiveDate::parse_from_str(date, DATE_MENTION_FORMAT).ok())
+        .collect::<Vec<_>>();
+    dates.dedup();
+    dates
+}
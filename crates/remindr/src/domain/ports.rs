@@ -1,42 +1,200 @@
-use anyhow::Result;
-use std::future::Future;
+use anyhow::{Context, Result};
+use std::{future::Future, pin::Pin, time::SystemTime};
 
-use crate::domain::database::document::DocumentModel;
+use crate::domain::database::document::{
+    DocumentLocation, DocumentModel, StoredDocument, plain_text,
+};
 use crate::domain::database::folder::FolderModel;
 
+/// Future returned by the ports, boxed so that they can be used as trait objects.
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Where the documents are stored, the database, a vault folder or memory in the tests.
 pub trait DocumentRepositoryPort: Send + Sync {
-    type ListFuture<'a>: Future<Output = Result<Vec<DocumentModel>>> + Send + 'a
-    where
-        Self: 'a;
-
-    /// Future renvoyé par `get`.
-    type GetFuture<'a>: Future<Output = Result<Option<DocumentModel>>> + Send + 'a
-    where
-        Self: 'a;
-
-    type SaveFuture<'a>: Future<Output = Result<()>> + Send + 'a
-    where
-        Self: 'a;
-
-    fn list<'a>(&'a self) -> Self::ListFuture<'a>;
-    fn get<'a>(&'a self, id: i32) -> Self::GetFuture<'a>;
-    fn save<'a>(&'a self, document: DocumentModel) -> Self::SaveFuture<'a>;
+    /// Every document, by id.
+    fn get_documents(&self) -> BoxFuture<'_, Result<Vec<DocumentModel>>>;
+
+    /// Document with the given id, `None` when there is none.
+    fn find_document(&self, id: i32) -> BoxFuture<'_, Result<Option<DocumentModel>>>;
+
+    /// Stores a new document and returns the id it was given.
+    fn insert_document(&self, document: DocumentModel) -> BoxFuture<'_, Result<i32>>;
+
+    fn update_document(&self, document: DocumentModel) -> BoxFuture<'_, Result<()>>;
+
+    fn move_document(&self, id: i32, folder_id: Option<i32>) -> BoxFuture<'_, Result<()>>;
+
+    fn delete_document(&self, id: i32) -> BoxFuture<'_, Result<()>>;
+
+    fn get_document_by_id(&self, id: i32) -> BoxFuture<'_, Result<DocumentModel>> {
+        Box::pin(async move {
+            self.find_document(id)
+                .await?
+                .with_context(|| format!("Document {} not found", id))
+        })
+    }
+
+    /// Documents that are not filed in any folder.
+    fn get_unfiled_documents(&self) -> BoxFuture<'_, Result<Vec<DocumentModel>>> {
+        Box::pin(async move {
+            let documents = self.get_documents().await?;
+            Ok(documents
+                .into_iter()
+                .filter(|document| document.folder_id.is_none())
+                .collect())
+        })
+    }
+
+    /// Documents whose title or text contain every word of `text`.
+    fn search_documents<'a>(&'a self, text: &'a str) -> BoxFuture<'a, Result<Vec<DocumentModel>>> {
+        Box::pin(async move {
+            let words = text
+                .split_whitespace()
+                .map(|word| word.to_lowercase())
+                .collect::<Vec<_>>();
+            if words.is_empty() {
+                return Ok(Vec::new());
+            }
+
+            let documents = self.get_documents().await?;
+            Ok(documents
+                .into_iter()
+                .filter(|document| {
+                    let text = format!("{}\n{}", document.title, plain_text(&document.content))
+                        .to_lowercase();
+                    words.iter().all(|word| text.contains(word.as_str()))
+                })
+                .collect())
+        })
+    }
+
+    /// Every document as stored, its content unparsed, to find the ones that can't be opened.
+    fn get_stored_documents(&self) -> BoxFuture<'_, Result<Vec<StoredDocument>>> {
+        Box::pin(async move {
+            let documents = self.get_documents().await?;
+            Ok(documents
+                .into_iter()
+                .map(|document| StoredDocument {
+                    location: DocumentLocation::Row(document.id),
+                    title: document.title,
+                    content: document.content.to_string(),
+                })
+                .collect())
+        })
+    }
+
+    /// Where a document is stored, to find its copies in the backups.
+    fn document_location(&self, id: i32) -> BoxFuture<'_, Result<DocumentLocation>> {
+        Box::pin(async move { Ok(DocumentLocation::Row(id)) })
+    }
+
+    /// When the document was last saved, none if unknown.
+    fn updated_at(&self, _id: i32) -> BoxFuture<'_, Result<Option<SystemTime>>> {
+        Box::pin(async { Ok(None) })
+    }
+
+    /// Indexes a document again after its content was replaced outside the repository,
+    /// e.g. restored from a backup. Nothing to do for the storages without an index.
+    fn reindex_document(&self, _id: i32) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    /// Waits for the documents saved so far to be indexed.
+    fn flush_index(&self) -> BoxFuture<'_, ()> {
+        Box::pin(async {})
+    }
+
+    fn list(&self) -> BoxFuture<'_, Result<Vec<DocumentModel>>> {
+        self.get_documents()
+    }
+
+    fn get(&self, id: i32) -> BoxFuture<'_, Result<Option<DocumentModel>>> {
+        self.find_document(id)
+    }
+
+    /// Inserts the document when its id is 0, updates it otherwise.
+    fn save(&self, document: DocumentModel) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async move {
+            if document.id == 0 {
+                self.insert_document(document).await.map(|_| ())
+            } else {
+                self.update_document(document).await
+            }
+        })
+    }
 }
 
+/// Where the folders are stored, next to the documents.
 pub trait FolderRepositoryPort: Send + Sync {
-    type ListFuture<'a>: Future<Output = Result<Vec<FolderModel>>> + Send + 'a
-    where
-        Self: 'a;
+    /// Every folder, by name.
+    fn get_folders(&self) -> BoxFuture<'_, Result<Vec<FolderModel>>>;
+
+    /// Folder with the given id, `None` when there is none.
+    fn find_folder(&self, id: i32) -> BoxFuture<'_, Result<Option<FolderModel>>>;
+
+    /// Creates a folder and returns its id.
+    fn insert_folder(&self, name: String, parent_id: Option<i32>) -> BoxFuture<'_, Result<i32>>;
+
+    /// Renames the folder.
+    fn update_folder(&self, folder: FolderModel) -> BoxFuture<'_, Result<()>>;
+
+    fn delete_folder(&self, id: i32) -> BoxFuture<'_, Result<()>>;
+
+    fn move_folder(&self, id: i32, new_parent_id: Option<i32>) -> BoxFuture<'_, Result<()>>;
+
+    /// Id of the template new documents of the folder start from, `None` when they start
+    /// empty.
+    fn get_template(&self, folder_id: i32) -> BoxFuture<'_, Result<Option<i32>>>;
+
+    fn set_template(&self, folder_id: i32, template_id: Option<i32>) -> BoxFuture<'_, Result<()>>;
+
+    /// Delete a folder but keep its children by moving them to the folder's parent.
+    fn delete_folder_keep_children(&self, id: i32) -> BoxFuture<'_, Result<()>>;
+
+    fn get_folder_by_id(&self, id: i32) -> BoxFuture<'_, Result<FolderModel>> {
+        Box::pin(async move {
+            self.find_folder(id)
+                .await?
+                .with_context(|| format!("Folder {} not found", id))
+        })
+    }
+
+    fn list(&self) -> BoxFuture<'_, Result<Vec<FolderModel>>> {
+        self.get_folders()
+    }
+
+    fn get(&self, id: i32) -> BoxFuture<'_, Result<Option<FolderModel>>> {
+        self.find_folder(id)
+    }
+
+    /// Inserts the folder when its id is 0, renames and moves it otherwise.
+    fn save(&self, folder: FolderModel) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async move {
+            if folder.id == 0 {
+                return self
+                    .insert_folder(folder.name, folder.parent_id)
+                    .await
+                    .map(|_| ());
+            }
+
+            let (id, parent_id) = (folder.id, folder.parent_id);
+            self.update_folder(folder).await?;
+            self.move_folder(id, parent_id).await
+        })
+    }
+}
 
-    type GetFuture<'a>: Future<Output = Result<Option<FolderModel>>> + Send + 'a
-    where
-        Self: 'a;
+/// Stream of text chunks produced by an assistant request.
+pub type AssistantStream = smol::channel::Receiver<Result<String>>;
 
-    type SaveFuture<'a>: Future<Output = Result<()>> + Send + 'a
-    where
-        Self: 'a;
+/// Language model backend used by the editor assistant.
+///
+/// Each call starts a request in the background and returns the chunks as they arrive;
+/// the stream closes once the answer is complete.
+pub trait AssistantProvider: Send + Sync {
+    /// Continues writing after the given document context.
+    fn complete(&self, context: String) -> AssistantStream;
 
-    fn list<'a>(&'a self) -> Self::ListFuture<'a>;
-    fn get<'a>(&'a self, id: i32) -> Self::GetFuture<'a>;
-    fn save<'a>(&'a self, folder: FolderModel) -> Self::SaveFuture<'a>;
+    /// Summarizes the given text.
+    fn summarize(&self, text: String) -> AssistantStream;
 }
@@ -0,0 +1,116 @@
+use anyhow::{Result, anyhow};
+use serde_json::{Value, json};
+
+use crate::domain::ports::{AssistantProvider, AssistantStream};
+
+const CONTINUE_PROMPT: &str = "You are a writing assistant embedded in a note-taking app. \
+Continue the user's text in the same language, tone and style. \
+Reply with the continuation only, without repeating the existing text.";
+
+const SUMMARIZE_PROMPT: &str = "You are a writing assistant embedded in a note-taking app. \
+Summarize the user's text concisely in the same language. Reply with the summary only.";
+
+/// Assistant backed by any server implementing the OpenAI chat completions API.
+#[derive(Clone)]
+pub struct OpenAiProvider {
+    client: reqwest::Client,
+    endpoint: String,
+    api_key: String,
+    model: String,
+}
+
+impl OpenAiProvider {
+    pub fn new(endpoint: String, api_key: String, model: String) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            endpoint,
+            api_key,
+            model,
+        }
+    }
+
+    fn stream_chat(&self, system: &'static str, user: String) -> AssistantStream {
+        let (sender, receiver) = smol::channel::unbounded();
+        let provider = self.clone();
+
+        tokio::spawn(async move {
+            if let Err(e) = provider.run_chat(system, user, &sender).await {
+                let _ = sender.send(Err(e)).await;
+            }
+        });
+
+        receiver
+    }
+
+    async fn run_chat(
+        &self,
+        system: &str,
+        user: String,
+        sender: &smol::channel::Sender<Result<String>>,
+    ) -> Result<()> {
+        let url = format!("{}/chat/completions", self.endpoint.trim_end_matches('/'));
+        let body = json!({
+            "model": self.model,
+            "stream": true,
+            "messages": [
+                { "role": "system", "content": system },
+                { "role": "user", "content": user },
+            ],
+        });
+
+        let mut request = self.client.post(url).json(&body);
+        if !self.api_key.is_empty() {
+            request = request.bearer_auth(&self.api_key);
+        }
+
+        let mut response = request.send().await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(anyhow!("assistant request failed ({}): {}", status, text));
+        }
+
+        // Server-sent events: one `data: {json}` line per chunk, terminated by `data: [DONE]`
+        // Lines are only decoded once complete, a chunk may end in the middle of a character
+        let mut buffer = Vec::new();
+        while let Some(bytes) = response.chunk().await? {
+            buffer.extend_from_slice(&bytes);
+
+            while let Some(line_end) = buffer.iter().position(|byte| *byte == b'\n') {
+                let line = String::from_utf8_lossy(&buffer[..line_end])
+                    .trim()
+                    .to_string();
+                buffer.drain(..=line_end);
+
+                let Some(data) = line.strip_prefix("data:") else {
+                    continue;
+                };
+                let data = data.trim();
+                if data == "[DONE]" {
+                    return Ok(());
+                }
+
+                let event: Value = serde_json::from_str(data)?;
+                if let Some(content) = event["choices"][0]["delta"]["content"].as_str()
+                    && !content.is_empty()
+                    && sender.send(Ok(content.to_string())).await.is_err()
+                {
+                    // Receiver dropped, nobody is listening anymore
+                    return Ok(());
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl AssistantProvider for OpenAiProvider {
+    fn complete(&self, context: String) -> AssistantStream {
+        self.stream_chat(CONTINUE_PROMPT, context)
+    }
+
+    fn summarize(&self, text: String) -> AssistantStream {
+        self.stream_chat(SUMMARIZE_PROMPT, text)
+    }
+}
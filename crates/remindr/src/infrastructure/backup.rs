@@ -0,0 +1,252 @@
+use std::{
+    fs::{self, File},
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Error, bail};
+use chrono::{Local, NaiveDateTime};
+use sqlx::SqlitePool;
+use zip::{CompressionMethod, ZipArchive, ZipWriter, write::SimpleFileOptions};
+
+use crate::{
+    domain::database::document::DocumentLocation,
+    infrastructure::{
+        database,
+        vault::file_vault::{self, FileVault},
+    },
+};
+
+/// Start of the name of the backups, followed by their time, e.g.
+/// "remindr-2026-10-16-143000.sqlite".
+const PREFIX: &str = "remindr-";
+
+const TIME_FORMAT: &str = "%Y-%m-%d-%H%M%S";
+
+/// Copy of the database (`.sqlite`) or of the vault folder (`.zip`).
+#[derive(Clone, Debug)]
+pub struct Backup {
+    pub path: PathBuf,
+    /// Timestamp in seconds, read from the name of the file.
+    pub created_at: i64,
+}
+
+/// Backups of `directory`, the newest first. Other files are ignored.
+pub fn list_backups(directory: &Path) -> Result<Vec<Backup>, Error> {
+    if !directory.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut backups = Vec::new();
+    for entry in
+        fs::read_dir(directory).with_context(|| format!("Failed to read {:?}", directory))?
+    {
+        let path = entry?.path();
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let time = name
+            .strip_prefix(PREFIX)
+            .and_then(|rest| {
+                rest.strip_suffix(".sqlite")
+                    .or_else(|| rest.strip_suffix(".zip"))
+            })
+            .and_then(|time| NaiveDateTime::parse_from_str(time, TIME_FORMAT).ok())
+            .and_then(|time| time.and_local_timezone(Local).earliest());
+        if let Some(time) = time {
+            backups.push(Backup {
+                path,
+                created_at: time.timestamp(),
+            });
+        }
+    }
+
+    backups.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    Ok(backups)
+}
+
+/// Writes a copy of the database in `directory`, encrypted like the database itself.
+pub async fn back_up_database(pool: &SqlitePool, directory: &Path) -> Result<Backup, Error> {
+    fs::create_dir_all(directory).with_context(|| format!("Failed to create {:?}", directory))?;
+
+    let now = Local::now();
+    let path = directory.join(format!("{}{}.sqlite", PREFIX, now.format(TIME_FORMAT)));
+    database::backup(pool, &path).await?;
+    Ok(Backup {
+        path,
+        created_at: now.timestamp(),
+    })
+}
+
+/// Writes the files of the vault at `root` in a zip file of `directory`, without its git
+/// history.
+pub fn back_up_vault(root: &Path, directory: &Path) -> Result<Backup, Error> {
+    fs::create_dir_all(directory).with_context(|| format!("Failed to create {:?}", directory))?;
+    if directory.canonicalize()?.starts_with(root) {
+        bail!("The backup folder can't be inside the vault");
+    }
+
+    let now = Local::now();
+    let path = directory.join(format!("{}{}.zip", PREFIX, now.format(TIME_FORMAT)));
+    if path.exists() {
+        bail!("{:?} already exists", path);
+    }
+
+    // Written under another name first, so that an interrupted backup isn't listed
+    let partial = path.with_extension("zip.part");
+    let mut zip = ZipWriter::new(
+        File::create(&partial).with_context(|| format!("Failed to create {:?}", partial))?,
+    );
+    let result = add_folder(&mut zip, root, "").and_then(|()| {
+        zip.finish()?;
+        Ok(())
+    });
+    if let Err(e) = result {
+        let _ = fs::remove_file(&partial);
+        return Err(e.context("Failed to back up the vault"));
+    }
+    fs::rename(&partial, &path).with_context(|| format!("Failed to write {:?}", path))?;
+
+    Ok(Backup {
+        path,
+        created_at: now.timestamp(),
+    })
+}
+
+fn add_folder(zip: &mut ZipWriter<File>, folder: &Path, prefix: &str) -> Result<(), Error> {
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    for entry in fs::read_dir(folder)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name == ".git" {
+            continue;
+        }
+
+        let entry_name = format!("{}{}", prefix, name);
+        if entry.file_type()?.is_dir() {
+            zip.add_directory(format!("{}/", entry_name), options)?;
+            add_folder(zip, &entry.path(), &format!("{}/", entry_name))?;
+        } else {
+            zip.start_file(entry_name, options)?;
+            io::copy(&mut File::open(entry.path())?, zip)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Deletes the oldest backups of `directory` past the `keep` newest ones and returns how
+/// many were deleted.
+pub fn prune(directory: &Path, keep: usize) -> Result<usize, Error> {
+    let backups = list_backups(directory)?;
+    let mut deleted = 0;
+    for backup in backups.into_iter().skip(keep.max(1)) {
+        fs::remove_file(&backup.path)
+            .with_context(|| format!("Failed to delete {:?}", backup.path))?;
+        deleted += 1;
+    }
+    Ok(deleted)
+}
+
+/// Content of the document at `location` in the backup, as stored, none if the backup
+/// doesn't have it. Database backups are opened through `pool`, the database they were
+/// made from.
+pub async fn backed_up_content(
+    backup: &Backup,
+    location: &DocumentLocation,
+    pool: &SqlitePool,
+) -> Result<Option<String>, Error> {
+    match location {
+        DocumentLocation::Row(id) if is_database(backup) => {
+            database::backed_up_content(pool, &backup.path, *id).await
+        }
+        DocumentLocation::File(file) if !is_database(backup) => {
+            let (archive, name) = (backup.path.clone(), entry_name(file));
+            let data = smol::unblock(move || read_entry(&archive, &name)).await?;
+            Ok(data.map(|data| file_vault::stored_content(&String::from_utf8_lossy(&data))))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Contents of every document in the backup, as stored, along with the synced blocks of
+/// database backups. Database backups are opened through `pool`, the database they were
+/// made from.
+pub async fn backed_up_contents(backup: &Backup, pool: &SqlitePool) -> Result<Vec<String>, Error> {
+    if is_database(backup) {
+        return database::backed_up_contents(pool, &backup.path).await;
+    }
+
+    let archive = backup.path.clone();
+    smol::unblock(move || {
+        let file = File::open(&archive).with_context(|| format!("Failed to open {:?}", archive))?;
+        let mut archive = ZipArchive::new(file)?;
+        let mut contents = Vec::new();
+        for index in 0..archive.len() {
+            let mut entry = archive.by_index(index)?;
+            if !entry.is_file() || !entry.name().ends_with(".json") {
+                continue;
+            }
+            let mut text = String::new();
+            if entry.read_to_string(&mut text).is_ok() {
+                contents.push(file_vault::stored_content(&text));
+            }
+        }
+        Ok(contents)
+    })
+    .await
+}
+
+/// Replaces the document at `location` with its copy in the backup, its row in the
+/// database or its file in the vault, and returns the id of the document.
+pub async fn restore_document(
+    backup: &Backup,
+    location: &DocumentLocation,
+    pool: &SqlitePool,
+    vault: Option<&FileVault>,
+) -> Result<i32, Error> {
+    match (location, vault) {
+        (DocumentLocation::Row(id), _) => {
+            database::restore_content(pool, &backup.path, *id).await?;
+            Ok(*id)
+        }
+        (DocumentLocation::File(file), Some(vault)) => {
+            let (archive, name) = (backup.path.clone(), entry_name(file));
+            let data = smol::unblock(move || read_entry(&archive, &name))
+                .await?
+                .context("The backup has no copy of the document")?;
+            vault.restore_file(file.clone(), data).await
+        }
+        (DocumentLocation::File(_), None) => bail!("The vault isn't open"),
+    }
+}
+
+fn is_database(backup: &Backup) -> bool {
+    backup
+        .path
+        .extension()
+        .is_some_and(|extension| extension == "sqlite")
+}
+
+/// Name of a file of the vault in its zip backups, e.g. "Work/Notes.json".
+fn entry_name(file: &Path) -> String {
+    file.components()
+        .map(|component| component.as_os_str().to_string_lossy().to_string())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn read_entry(archive: &Path, name: &str) -> Result<Option<Vec<u8>>, Error> {
+    let file = File::open(archive).with_context(|| format!("Failed to open {:?}", archive))?;
+    let mut archive = ZipArchive::new(file)?;
+    let mut entry = match archive.by_name(name) {
+        Ok(entry) => entry,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let mut data = Vec::new();
+    entry.read_to_end(&mut data)?;
+    Ok(Some(data))
+}
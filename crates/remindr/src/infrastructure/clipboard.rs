@@ -0,0 +1,16 @@
+use arboard::Clipboard;
+
+/// HTML on the clipboard, e.g. a selection copied from a browser.
+///
+/// gpui only reads the plain text of the clipboard, so the HTML is read from the system
+/// clipboard directly. None when the clipboard holds no HTML or can't be read.
+pub fn read_html() -> Option<String> {
+    let mut clipboard = Clipboard::new()
+        .inspect_err(|e| tracing::warn!("failed to open the clipboard: {}", e))
+        .ok()?;
+    clipboard
+        .get()
+        .html()
+        .ok()
+        .filter(|html| !html.trim().is_empty())
+}
@@ -0,0 +1,292 @@
+use std::{path::Path, time::Duration};
+
+use anyhow::{Context, Error, bail};
+use sqlx::{
+    SqliteConnection, SqlitePool, migrate, query, query_scalar,
+    sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions},
+};
+
+use crate::infrastructure::repositories;
+
+/// Whether this build links SQLCipher (`sqlcipher` cargo feature).
+pub const ENCRYPTION_AVAILABLE: bool = cfg!(feature = "sqlcipher");
+
+/// PBKDF2-HMAC-SHA512 iterations SQLCipher uses to derive the page key from the passphrase.
+const KDF_ITERATIONS: u32 = 256_000;
+
+/// Minimum passphrase length accepted when encrypting the database.
+pub const MIN_PASSPHRASE_LEN: usize = 8;
+
+/// How the connections to the database are opened and shared.
+#[derive(Clone, Debug)]
+pub struct PoolConfig {
+    /// Write-ahead logging, so that reads don't wait for writes.
+    pub wal: bool,
+    /// Time a connection waits for a lock held by another one before failing with
+    /// `SQLITE_BUSY`.
+    pub busy_timeout: Duration,
+    /// Ignored for an encrypted database, which uses a single connection.
+    pub max_connections: u32,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            wal: true,
+            busy_timeout: Duration::from_secs(5),
+            max_connections: 4,
+        }
+    }
+}
+
+/// State of the connections of a pool, shown on the logs screen.
+#[derive(Clone, Debug)]
+pub struct PoolHealth {
+    pub connections: u32,
+    pub idle: usize,
+    pub max_connections: u32,
+    pub journal_mode: String,
+    /// Writes retried since the start because the database was locked.
+    pub busy_retries: usize,
+}
+
+/// Opens the database at `path` with `config`, unlocking it with `passphrase` when given,
+/// and runs the pending migrations.
+///
+/// The key is derived by SQLCipher itself: the passphrase goes through PBKDF2 with the
+/// per-database salt stored in the file header, so no key material is kept on disk.
+pub async fn open(
+    path: &Path,
+    passphrase: Option<&str>,
+    config: &PoolConfig,
+) -> Result<SqlitePool, Error> {
+    let journal_mode = if config.wal {
+        SqliteJournalMode::Wal
+    } else {
+        SqliteJournalMode::Delete
+    };
+    let options = SqliteConnectOptions::new()
+        .filename(path)
+        .create_if_missing(true)
+        .journal_mode(journal_mode)
+        .busy_timeout(config.busy_timeout);
+
+    let pool = match passphrase {
+        Some(passphrase) => {
+            ensure_available()?;
+
+            let options = options
+                .pragma("key", quote(passphrase))
+                .pragma("kdf_iter", KDF_ITERATIONS.to_string());
+
+            // A single long-lived connection, so that a rekey applies to every later query
+            SqlitePoolOptions::new()
+                .max_connections(1)
+                .idle_timeout(None)
+                .max_lifetime(None)
+                .connect_with(options)
+                .await?
+        }
+        None => {
+            SqlitePoolOptions::new()
+                .max_connections(config.max_connections.max(1))
+                .connect_with(options)
+                .await?
+        }
+    };
+
+    // SQLCipher only checks the key when the first page is read
+    query("SELECT count(*) FROM sqlite_master")
+        .execute(&pool)
+        .await
+        .context("Incorrect passphrase")?;
+
+    migrate!("./migrations")
+        .run(&pool)
+        .await
+        .map_err(|err| Error::msg(err.to_string()))?;
+
+    tracing::info!("opened database {:?}", path);
+    Ok(pool)
+}
+
+/// Reads the state of the connections of `pool`.
+pub async fn health(pool: &SqlitePool) -> Result<PoolHealth, Error> {
+    let journal_mode = query_scalar::<_, String>("PRAGMA journal_mode")
+        .fetch_one(pool)
+        .await?;
+
+    Ok(PoolHealth {
+        connections: pool.size(),
+        idle: pool.num_idle(),
+        max_connections: pool.options().get_max_connections(),
+        journal_mode,
+        busy_retries: repositories::busy_retries(),
+    })
+}
+
+/// Writes a copy of the database to `target`, encrypted like the database itself.
+pub async fn backup(pool: &SqlitePool, target: &Path) -> Result<(), Error> {
+    if target.exists() {
+        bail!("{:?} already exists", target);
+    }
+
+    query("VACUUM INTO ?")
+        .bind(target.to_string_lossy().to_string())
+        .execute(pool)
+        .await
+        .with_context(|| format!("Failed to back up the database to {:?}", target))?;
+
+    Ok(())
+}
+
+/// Problems `PRAGMA integrity_check` finds in the database, none when it is sound.
+pub async fn integrity_check(pool: &SqlitePool) -> Result<Vec<String>, Error> {
+    let rows = query_scalar::<_, String>("PRAGMA integrity_check")
+        .fetch_all(pool)
+        .await
+        .context("Failed to check the database")?;
+
+    Ok(rows.into_iter().filter(|row| row != "ok").collect())
+}
+
+/// Content of a document in the backup at `path`, as stored, none if the backup doesn't
+/// have it. The backup is opened with the key of `pool`, the database it was made from.
+pub async fn backed_up_content(
+    pool: &SqlitePool,
+    path: &Path,
+    document_id: i32,
+) -> Result<Option<String>, Error> {
+    let mut connection = pool.acquire().await?;
+    attach_backup(&mut connection, path).await?;
+    let content = query_scalar::<_, String>(
+        "SELECT CAST(content AS TEXT) FROM backup.documents WHERE id = ?",
+    )
+    .bind(document_id)
+    .fetch_optional(&mut *connection)
+    .await;
+    query("DETACH DATABASE backup")
+        .execute(&mut *connection)
+        .await?;
+
+    Ok(content?)
+}
+
+/// Contents of every document and synced block in the backup at `path`, as stored. The
+/// backup is opened with the key of `pool`, the database it was made from.
+pub async fn backed_up_contents(pool: &SqlitePool, path: &Path) -> Result<Vec<String>, Error> {
+    let mut connection = pool.acquire().await?;
+    attach_backup(&mut connection, path).await?;
+    let contents = query_scalar::<_, String>(
+        "SELECT CAST(content AS TEXT) FROM backup.documents \
+         UNION ALL SELECT CAST(content AS TEXT) FROM backup.synced_blocks",
+    )
+    .fetch_all(&mut *connection)
+    .await;
+    query("DETACH DATABASE backup")
+        .execute(&mut *connection)
+        .await?;
+
+    Ok(contents?)
+}
+
+/// Replaces the content of a document with its content in the backup at `path`.
+pub async fn restore_content(
+    pool: &SqlitePool,
+    path: &Path,
+    document_id: i32,
+) -> Result<(), Error> {
+    let mut connection = pool.acquire().await?;
+    attach_backup(&mut connection, path).await?;
+    let result = query(
+        "UPDATE documents SET content = (SELECT content FROM backup.documents WHERE id = ?1), \
+         updated_at = CAST(strftime('%s', 'now') AS INTEGER) \
+         WHERE id = ?1 AND EXISTS (SELECT 1 FROM backup.documents WHERE id = ?1)",
+    )
+    .bind(document_id)
+    .execute(&mut *connection)
+    .await;
+    query("DETACH DATABASE backup")
+        .execute(&mut *connection)
+        .await?;
+
+    if result?.rows_affected() == 0 {
+        bail!("The backup has no copy of the document");
+    }
+    Ok(())
+}
+
+/// Attaches a backup as the `backup` schema. SQLCipher opens it with the key of the
+/// connection, the one the backup was encrypted with.
+async fn attach_backup(connection: &mut SqliteConnection, path: &Path) -> Result<(), Error> {
+    query("ATTACH DATABASE ? AS backup")
+        .bind(path.to_string_lossy().to_string())
+        .execute(&mut *connection)
+        .await
+        .with_context(|| format!("Failed to open the backup {:?}", path))?;
+    Ok(())
+}
+
+/// Re-encrypts an unlocked database with a new passphrase.
+pub async fn change_passphrase(pool: &SqlitePool, passphrase: &str) -> Result<(), Error> {
+    ensure_available()?;
+
+    query(&format!("PRAGMA rekey = {}", quote(passphrase)))
+        .execute(pool)
+        .await
+        .context("Failed to change the passphrase")?;
+
+    Ok(())
+}
+
+/// Copies the database behind `pool` into a new file encrypted with `passphrase` (or in plain
+/// text when `None`), replaces the file at `path` with it and reopens it.
+///
+/// `pool` is closed once the copy is done and must not be used afterwards.
+pub async fn export(
+    pool: &SqlitePool,
+    path: &Path,
+    passphrase: Option<&str>,
+    config: &PoolConfig,
+) -> Result<SqlitePool, Error> {
+    ensure_available()?;
+
+    let target = path.with_extension("sqlite.export");
+    if target.exists() {
+        tokio::fs::remove_file(&target).await?;
+    }
+
+    let mut connection = pool.acquire().await?;
+    query("ATTACH DATABASE ? AS export KEY ?")
+        .bind(target.to_string_lossy().to_string())
+        .bind(passphrase.unwrap_or_default())
+        .execute(&mut *connection)
+        .await?;
+    query("SELECT sqlcipher_export('export')")
+        .execute(&mut *connection)
+        .await?;
+    query("DETACH DATABASE export")
+        .execute(&mut *connection)
+        .await?;
+    drop(connection);
+
+    pool.close().await;
+    tokio::fs::rename(&target, path)
+        .await
+        .with_context(|| format!("Failed to replace {:?}", path))?;
+
+    open(path, passphrase, config).await
+}
+
+fn ensure_available() -> Result<(), Error> {
+    if !ENCRYPTION_AVAILABLE {
+        bail!("This build of Remindr was compiled without SQLCipher support");
+    }
+
+    Ok(())
+}
+
+/// Quotes a passphrase as an SQL string literal, since pragmas cannot take bound parameters.
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
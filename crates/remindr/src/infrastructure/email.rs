@@ -0,0 +1,262 @@
+use anyhow::{Context, Error};
+use chrono::DateTime;
+use mail_parser::{Message, MessageParser, MimeHeaders};
+use native_tls::TlsConnector;
+use serde_json::{Value, json};
+
+use crate::{
+    Utils,
+    domain::{
+        database::document::DocumentModel,
+        ports::{DocumentRepositoryPort, FolderRepositoryPort},
+    },
+    infrastructure::{
+        import::html::html_to_blocks, repositories::attachment_repository::AttachmentRepository,
+    },
+};
+
+/// Root folder the imported emails are filed in, created when missing.
+const EMAIL_FOLDER: &str = "Email";
+
+/// IMAP mailbox to import the unread emails of.
+#[derive(Clone, Debug)]
+pub struct Mailbox {
+    pub server: String,
+    pub port: u16,
+    pub username: String,
+    pub password: String,
+    pub folder: String,
+}
+
+/// An email of the mailbox, as the server sent it.
+pub struct UnreadEmail {
+    pub uid: u32,
+    pub raw: Vec<u8>,
+}
+
+/// Emails stored by [`import_emails`], and the error that stopped it, if any.
+pub struct ImportedEmails {
+    pub document_ids: Vec<i32>,
+    /// UIDs of the emails that were stored, or skipped because they couldn't be parsed.
+    pub uids: Vec<u32>,
+    pub error: Option<Error>,
+}
+
+type Session = imap::Session<native_tls::TlsStream<std::net::TcpStream>>;
+
+fn open(mailbox: &Mailbox) -> Result<Session, Error> {
+    let server = mailbox.server.trim();
+    let tls = TlsConnector::builder().build()?;
+    let client = imap::connect((server, mailbox.port), server, &tls)
+        .with_context(|| format!("Failed to connect to {}", server))?;
+    let mut session = client
+        .login(mailbox.username.trim(), &mailbox.password)
+        .map_err(|(e, _)| e)
+        .context("The email server refused the credentials")?;
+
+    session
+        .select(mailbox.folder.trim())
+        .with_context(|| format!("Failed to open the {} mailbox", mailbox.folder))?;
+    Ok(session)
+}
+
+/// Downloads the unread emails of the mailbox, leaving them unread until [`mark_seen`].
+///
+/// Blocks while talking to the server.
+pub fn fetch_unread(mailbox: &Mailbox) -> Result<Vec<UnreadEmail>, Error> {
+    let mut session = open(mailbox)?;
+
+    let mut uids = session
+        .uid_search("UNSEEN")?
+        .into_iter()
+        .collect::<Vec<_>>();
+    uids.sort_unstable();
+
+    let mut emails = Vec::new();
+    if !uids.is_empty() {
+        let set = uids
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(",");
+        // PEEK keeps the emails unread, in case storing them fails
+        for message in session.uid_fetch(set, "(UID BODY.PEEK[])")?.iter() {
+            if let (Some(uid), Some(body)) = (message.uid, message.body()) {
+                emails.push(UnreadEmail {
+                    uid,
+                    raw: body.to_vec(),
+                });
+            }
+        }
+    }
+
+    session.logout()?;
+    Ok(emails)
+}
+
+/// Marks the emails as read, once they're stored.
+///
+/// Blocks while talking to the server.
+pub fn mark_seen(mailbox: &Mailbox, uids: &[u32]) -> Result<(), Error> {
+    let mut session = open(mailbox)?;
+    let set = uids
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(",");
+    session
+        .uid_store(set, "+FLAGS.SILENT (\\Seen)")
+        .context("Failed to mark the imported emails as read")?;
+    session.logout()?;
+    Ok(())
+}
+
+/// Stores each email as a document of the "Email" folder, its attachments as attachment
+/// blocks, until one fails.
+pub async fn import_emails(
+    emails: Vec<UnreadEmail>,
+    documents: &dyn DocumentRepositoryPort,
+    folders: &dyn FolderRepositoryPort,
+    attachments: &AttachmentRepository,
+) -> ImportedEmails {
+    let mut imported = ImportedEmails {
+        document_ids: Vec::new(),
+        uids: Vec::new(),
+        error: None,
+    };
+    if emails.is_empty() {
+        return imported;
+    }
+
+    let folder_id = match email_folder(folders).await {
+        Ok(folder_id) => folder_id,
+        Err(e) => {
+            imported.error = Some(e);
+            return imported;
+        }
+    };
+
+    for email in emails {
+        match import_email(&email.raw, folder_id, documents, attachments).await {
+            Ok(Some(id)) => imported.document_ids.push(id),
+            Ok(None) => tracing::warn!("skipped an email that couldn't be parsed"),
+            Err(e) => {
+                imported.error = Some(e);
+                break;
+            }
+        }
+        imported.uids.push(email.uid);
+    }
+
+    imported
+}
+
+/// Stores the email as a document of the folder, `None` if it couldn't be parsed.
+async fn import_email(
+    raw: &[u8],
+    folder_id: i32,
+    documents: &dyn DocumentRepositoryPort,
+    attachments: &AttachmentRepository,
+) -> Result<Option<i32>, Error> {
+    let Some(message) = MessageParser::default().parse(raw) else {
+        return Ok(None);
+    };
+
+    let mut content = vec![text_block(sender_line(&message))];
+    content.extend(body_blocks(&message));
+
+    for part in message.attachments() {
+        let file_name = part.attachment_name().unwrap_or("attachment").to_string();
+        let attachment = attachments
+            .insert_attachment(file_name, part.contents().to_vec())
+            .await?;
+        content.push(json!({
+            "id": Utils::generate_uuid(),
+            "type": "attachment",
+            "metadata": {
+                "attachment_id": attachment.id,
+                "file_name": attachment.file_name,
+                "size": attachment.size,
+            },
+        }));
+    }
+
+    let title = message
+        .subject()
+        .map(str::trim)
+        .filter(|subject| !subject.is_empty())
+        .unwrap_or("(No subject)")
+        .to_string();
+
+    let id = documents
+        .insert_document(DocumentModel {
+            id: 0,
+            title,
+            content: Value::Array(content),
+            folder_id: Some(folder_id),
+        })
+        .await?;
+    Ok(Some(id))
+}
+
+async fn email_folder(folders: &dyn FolderRepositoryPort) -> Result<i32, Error> {
+    let existing = folders
+        .get_folders()
+        .await?
+        .into_iter()
+        .find(|folder| folder.parent_id.is_none() && folder.name == EMAIL_FOLDER);
+
+    match existing {
+        Some(folder) => Ok(folder.id),
+        None => folders.insert_folder(EMAIL_FOLDER.to_string(), None).await,
+    }
+}
+
+/// e.g. "From Ada Lovelace <ada@example.com>, 2025-02-03 14:30".
+fn sender_line(message: &Message) -> String {
+    let sender = message
+        .from()
+        .and_then(|from| from.first())
+        .map(|from| match (from.name(), from.address()) {
+            (Some(name), Some(address)) => format!("{} <{}>", name, address),
+            (Some(name), None) => name.to_string(),
+            (None, Some(address)) => address.to_string(),
+            (None, None) => "an unknown sender".to_string(),
+        })
+        .unwrap_or_else(|| "an unknown sender".to_string());
+
+    match message
+        .date()
+        .and_then(|date| DateTime::from_timestamp(date.to_timestamp(), 0))
+    {
+        Some(date) => format!("From {}, {}", sender, date.format("%Y-%m-%d %H:%M")),
+        None => format!("From {}", sender),
+    }
+}
+
+/// Paragraphs of the text body, or the blocks of the HTML body for HTML-only emails.
+fn body_blocks(message: &Message) -> Vec<Value> {
+    if message.text_body_count() == 0
+        && let Some(html) = message.body_html(0)
+    {
+        return html_to_blocks(&html);
+    }
+
+    message
+        .body_text(0)
+        .unwrap_or_default()
+        .replace("\r\n", "\n")
+        .split("\n\n")
+        .map(str::trim)
+        .filter(|paragraph| !paragraph.is_empty())
+        .map(|paragraph| text_block(paragraph.to_string()))
+        .collect()
+}
+
+fn text_block(content: String) -> Value {
+    json!({
+        "id": Utils::generate_uuid(),
+        "type": "text",
+        "metadata": { "content": content },
+    })
+}
@@ -1,8 +1,17 @@
+use chrono::NaiveDate;
 use serde_json::Value;
 use sqlx::prelude::FromRow;
 
+use crate::domain::database::activity::ActivityModel;
+use crate::domain::database::attachment::AttachmentModel;
+use crate::domain::database::collection::CollectionView;
 use crate::domain::database::document::DocumentModel;
 use crate::domain::database::folder::FolderModel;
+use crate::domain::database::reminder::ReminderModel;
+use crate::domain::database::smart_folder::SmartFolderModel;
+use crate::domain::database::synced_block::SyncedBlockModel;
+use crate::domain::database::tag::DocumentTagModel;
+use crate::domain::database::task::TaskModel;
 
 #[derive(Debug, FromRow)]
 pub struct DocumentEntity {
@@ -39,3 +48,140 @@ impl From<FolderEntity> for FolderModel {
         }
     }
 }
+
+#[derive(Debug, FromRow)]
+pub struct DocumentTagEntity {
+    pub document_id: i32,
+    pub tag: String,
+}
+
+impl From<DocumentTagEntity> for DocumentTagModel {
+    fn from(entity: DocumentTagEntity) -> Self {
+        DocumentTagModel {
+            document_id: entity.document_id,
+            tag: entity.tag,
+        }
+    }
+}
+
+#[derive(Debug, FromRow)]
+pub struct ReminderEntity {
+    pub id: i32,
+    pub document_id: i32,
+    pub title: String,
+    pub due_at: i64,
+    pub recurrence: Option<String>,
+    pub snoozed_until: Option<i64>,
+    pub completed: bool,
+}
+
+impl From<ReminderEntity> for ReminderModel {
+    fn from(entity: ReminderEntity) -> Self {
+        ReminderModel {
+            id: entity.id,
+            document_id: entity.document_id,
+            title: entity.title,
+            due_at: entity.due_at,
+            recurrence: entity.recurrence,
+            snoozed_until: entity.snoozed_until,
+            completed: entity.completed,
+        }
+    }
+}
+
+#[derive(Debug, FromRow)]
+pub struct ActivityEntity {
+    pub day: NaiveDate,
+    pub documents_edited: i64,
+    pub blocks_created: i64,
+}
+
+impl From<ActivityEntity> for ActivityModel {
+    fn from(entity: ActivityEntity) -> Self {
+        ActivityModel {
+            day: entity.day,
+            documents_edited: entity.documents_edited as i32,
+            blocks_created: entity.blocks_created as i32,
+        }
+    }
+}
+
+#[derive(Debug, FromRow)]
+pub struct TaskEntity {
+    pub document_id: i32,
+    pub block_id: String,
+    pub content: String,
+    pub due_date: Option<NaiveDate>,
+}
+
+impl From<TaskEntity> for TaskModel {
+    fn from(entity: TaskEntity) -> Self {
+        TaskModel {
+            document_id: entity.document_id,
+            block_id: entity.block_id,
+            content: entity.content,
+            due_date: entity.due_date,
+        }
+    }
+}
+
+#[derive(Debug, FromRow)]
+pub struct AttachmentEntity {
+    pub id: i32,
+    pub file_name: String,
+    pub size: i64,
+}
+
+impl From<AttachmentEntity> for AttachmentModel {
+    fn from(entity: AttachmentEntity) -> Self {
+        AttachmentModel {
+            id: entity.id,
+            file_name: entity.file_name,
+            size: entity.size,
+        }
+    }
+}
+
+#[derive(Debug, FromRow)]
+pub struct SyncedBlockEntity {
+    pub id: i32,
+    pub content: String,
+}
+
+impl From<SyncedBlockEntity> for SyncedBlockModel {
+    fn from(entity: SyncedBlockEntity) -> Self {
+        SyncedBlockModel {
+            id: entity.id,
+            content: entity.content,
+        }
+    }
+}
+
+#[derive(Debug, FromRow)]
+pub struct SmartFolderEntity {
+    pub id: i32,
+    pub name: String,
+    pub query: String,
+}
+
+impl From<SmartFolderEntity> for SmartFolderModel {
+    fn from(entity: SmartFolderEntity) -> Self {
+        SmartFolderModel {
+            id: entity.id,
+            name: entity.name,
+            query: entity.query,
+        }
+    }
+}
+
+#[derive(Debug, FromRow)]
+pub struct CollectionViewEntity {
+    pub folder_id: i32,
+    pub view: Value,
+}
+
+impl From<CollectionViewEntity> for CollectionView {
+    fn from(entity: CollectionViewEntity) -> Self {
+        serde_json::from_value(entity.view).unwrap_or_default()
+    }
+}
@@ -0,0 +1,251 @@
+use std::{
+    fs::{self, File},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Error};
+use docx_rs::{
+    AbstractNumbering, AlignmentType, BreakType, Docx, IndentLevel, Level, LevelJc, LevelOverride,
+    LevelText, NumberFormat, Numbering, NumberingId, Paragraph, Run, RunFonts, SpecialIndentType,
+    Start, Style, StyleType, Table, TableCell, TableRow,
+};
+
+use crate::{domain::database::document::DocumentModel, infrastructure::vault::file_vault};
+
+/// Numbering of the bulleted lists, shared by all of them.
+const BULLETS: usize = 1;
+/// Abstract numbering the numbered lists restart from 1 with.
+const DECIMAL: usize = 2;
+
+/// Writes the document as a `.docx` file in `directory`, for Word users, and returns the
+/// created file.
+pub fn export_document(document: &DocumentModel, directory: &Path) -> Result<PathBuf, Error> {
+    fs::create_dir_all(directory).with_context(|| format!("Failed to create {:?}", directory))?;
+
+    let path = file_vault::unique_path(directory, &document.title, Some("docx"));
+    let file = File::create(&path).with_context(|| format!("Failed to create {:?}", path))?;
+    document_to_docx(document)
+        .build()
+        .pack(file)
+        .with_context(|| format!("Failed to write {:?}", path))?;
+    Ok(path)
+}
+
+/// Renders a document as a Word document: headings keep their level, lists become Word
+/// lists and text blocks written as markdown tables become tables.
+pub fn document_to_docx(document: &DocumentModel) -> Docx {
+    let mut docx = with_styles(Docx::new())
+        .add_abstract_numbering(list_numbering(BULLETS, "bullet", "•"))
+        .add_abstract_numbering(list_numbering(DECIMAL, "decimal", "%1."))
+        .add_numbering(Numbering::new(BULLETS, BULLETS))
+        .add_paragraph(
+            Paragraph::new()
+                .style("Title")
+                .add_run(Run::new().add_text(&document.title)),
+        );
+
+    // Each numbered list restarts from 1 with its own numbering
+    let mut next_numbering = DECIMAL + 1;
+    let mut numbered_list: Option<usize> = None;
+
+    for block in document.content.as_array().into_iter().flatten() {
+        let metadata = &block["metadata"];
+        let content = metadata["content"].as_str().unwrap_or_default();
+        let kind = block["type"].as_str().unwrap_or_default();
+
+        let ordered = kind == "list" && metadata["ordered"].as_bool() == Some(true);
+        if !ordered {
+            numbered_list = None;
+        }
+
+        match kind {
+            "heading" => {
+                let level = metadata["level"].as_u64().unwrap_or(1).clamp(1, 5);
+                // The document title is the only Title paragraph
+                docx = docx.add_paragraph(
+                    Paragraph::new()
+                        .style(&format!("Heading{}", level))
+                        .add_run(text_run(content)),
+                );
+            }
+            "list" => {
+                let id = match numbered_list {
+                    Some(id) => id,
+                    None if ordered => {
+                        let id = next_numbering;
+                        next_numbering += 1;
+                        numbered_list = Some(id);
+                        docx = docx.add_numbering(
+                            Numbering::new(id, DECIMAL)
+                                .add_override(LevelOverride::new(0).start(1)),
+                        );
+                        id
+                    }
+                    None => BULLETS,
+                };
+                docx = docx.add_paragraph(
+                    Paragraph::new()
+                        .numbering(NumberingId::new(id), IndentLevel::new(0))
+                        .add_run(text_run(content)),
+                );
+            }
+            "todo" => {
+                let done = metadata["status"] == "done";
+                let mut run = text_run(&format!("{} {}", if done { "☑" } else { "☐" }, content));
+                if done {
+                    run = run.strike();
+                }
+                docx = docx.add_paragraph(Paragraph::new().add_run(run));
+            }
+            "quote" => {
+                docx = docx.add_paragraph(
+                    Paragraph::new()
+                        .indent(Some(720), None, None, None)
+                        .add_run(text_run(content).italic()),
+                );
+            }
+            "divider" => {
+                docx = docx.add_paragraph(
+                    Paragraph::new()
+                        .align(AlignmentType::Center)
+                        .add_run(Run::new().add_text("* * *")),
+                );
+            }
+            "equation" if !content.is_empty() => {
+                docx = docx.add_paragraph(Paragraph::new().add_run(
+                    text_run(content).fonts(RunFonts::new().ascii("Consolas").hi_ansi("Consolas")),
+                ));
+            }
+            "link" => {
+                let title = metadata["title"].as_str().unwrap_or_default();
+                docx = docx.add_paragraph(
+                    Paragraph::new().add_run(Run::new().add_text(title).underline("single")),
+                );
+            }
+            "attachment" => {
+                let file_name = metadata["file_name"].as_str().unwrap_or_default();
+                if !file_name.is_empty() {
+                    docx = docx.add_paragraph(
+                        Paragraph::new().add_run(Run::new().add_text(format!("📎 {}", file_name))),
+                    );
+                }
+            }
+            // Drawings have no Word equivalent
+            "drawing" => {}
+            _ => match table_rows(content) {
+                Some(rows) => docx = docx.add_table(table(rows)),
+                None if !content.is_empty() => {
+                    docx = docx.add_paragraph(Paragraph::new().add_run(text_run(content)))
+                }
+                None => {}
+            },
+        }
+    }
+
+    docx
+}
+
+/// Styles of the title and headings, which a new document doesn't define.
+fn with_styles(docx: Docx) -> Docx {
+    let docx = docx.add_style(
+        Style::new("Title", StyleType::Paragraph)
+            .name("Title")
+            .size(56),
+    );
+    [36, 30, 26, 24, 22]
+        .into_iter()
+        .enumerate()
+        .fold(docx, |docx, (index, size)| {
+            docx.add_style(
+                Style::new(&format!("Heading{}", index + 1), StyleType::Paragraph)
+                    .name(&format!("Heading {}", index + 1))
+                    .size(size)
+                    .bold(),
+            )
+        })
+}
+
+fn list_numbering(id: usize, format: &str, text: &str) -> AbstractNumbering {
+    AbstractNumbering::new(id).add_level(
+        Level::new(
+            0,
+            Start::new(1),
+            NumberFormat::new(format),
+            LevelText::new(text),
+            LevelJc::new("left"),
+        )
+        .indent(Some(720), Some(SpecialIndentType::Hanging(360)), None, None),
+    )
+}
+
+/// Run of text keeping its line breaks.
+fn text_run(content: &str) -> Run {
+    content
+        .split('\n')
+        .enumerate()
+        .fold(Run::new(), |run, (index, line)| {
+            let run = if index > 0 {
+                run.add_break(BreakType::TextWrapping)
+            } else {
+                run
+            };
+            run.add_text(line)
+        })
+}
+
+/// Cells of a markdown table, e.g. "| Name | Age |", when every line of the block is a row.
+fn table_rows(content: &str) -> Option<Vec<Vec<String>>> {
+    let lines = content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>();
+    if lines.len() < 2
+        || !lines
+            .iter()
+            .all(|line| line.starts_with('|') && line.ends_with('|'))
+    {
+        return None;
+    }
+
+    let rows = lines
+        .into_iter()
+        // The line under the header
+        .filter(|line| {
+            !line
+                .chars()
+                .all(|character| matches!(character, '|' | '-' | ':' | ' '))
+        })
+        .map(|line| {
+            line.trim_matches('|')
+                .split('|')
+                .map(|cell| cell.trim().to_string())
+                .collect()
+        })
+        .collect();
+    Some(rows)
+}
+
+fn table(rows: Vec<Vec<String>>) -> Table {
+    let columns = rows.iter().map(Vec::len).max().unwrap_or_default();
+    Table::new(
+        rows.into_iter()
+            .enumerate()
+            .map(|(index, mut row)| {
+                row.resize(columns, String::new());
+                TableRow::new(
+                    row.into_iter()
+                        .map(|cell| {
+                            let mut run = Run::new().add_text(cell);
+                            // The first row is the header of the table
+                            if index == 0 {
+                                run = run.bold();
+                            }
+                            TableCell::new().add_paragraph(Paragraph::new().add_run(run))
+                        })
+                        .collect(),
+                )
+            })
+            .collect(),
+    )
+}
@@ -0,0 +1,120 @@
+use chrono::{DateTime, Days, Duration, NaiveDate};
+
+use crate::domain::database::{
+    reminder::{Recurrence, ReminderModel},
+    task::TaskModel,
+};
+
+/// Length given to reminder events, which are instants in Remindr.
+const REMINDER_DURATION: Duration = Duration::minutes(15);
+
+/// Longest line of an iCalendar file in bytes, longer ones being folded.
+const MAX_LINE_LENGTH: usize = 75;
+
+/// Renders the pending reminders and the open tasks with a due date as an iCalendar file,
+/// for calendar apps to subscribe to. `now` is the timestamp the file is generated at.
+///
+/// Reminders repeating daily, weekly or monthly get the matching rule, the ones following
+/// a cron expression only their next occurrence. Tasks are all-day events.
+pub fn calendar(reminders: &[ReminderModel], tasks: &[TaskModel], now: i64) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Remindr//Remindr//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "X-WR-CALNAME:Remindr".to_string(),
+    ];
+    let stamp = format!("DTSTAMP:{}", utc_time(now));
+
+    for reminder in reminders.iter().filter(|reminder| !reminder.completed) {
+        let start = reminder.trigger_at();
+        lines.extend([
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:reminder-{}@remindr", reminder.id),
+            stamp.clone(),
+            format!("DTSTART:{}", utc_time(start)),
+            format!(
+                "DTEND:{}",
+                utc_time(start + REMINDER_DURATION.num_seconds())
+            ),
+            format!("SUMMARY:{}", escape(&reminder.title)),
+        ]);
+        if let Some(rule) = reminder
+            .recurrence()
+            .and_then(|recurrence| recurrence_rule(&recurrence))
+        {
+            lines.push(format!("RRULE:{}", rule));
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+
+    for task in tasks {
+        let Some(due_date) = task.due_date else {
+            continue;
+        };
+        lines.extend([
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:task-{}@remindr", task.block_id),
+            stamp.clone(),
+            format!("DTSTART;VALUE=DATE:{}", date(due_date)),
+            format!("DTEND;VALUE=DATE:{}", date(due_date + Days::new(1))),
+            format!("SUMMARY:{}", escape(&task.content)),
+            "END:VEVENT".to_string(),
+        ]);
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+
+    lines
+        .iter()
+        .map(|line| fold(line))
+        .collect::<Vec<_>>()
+        .join("\r\n")
+        + "\r\n"
+}
+
+fn recurrence_rule(recurrence: &Recurrence) -> Option<&'static str> {
+    match recurrence {
+        Recurrence::Daily => Some("FREQ=DAILY"),
+        Recurrence::Weekly => Some("FREQ=WEEKLY"),
+        Recurrence::Monthly => Some("FREQ=MONTHLY"),
+        Recurrence::Cron(_) => None,
+    }
+}
+
+/// e.g. `20261016T143000Z`.
+fn utc_time(timestamp: i64) -> String {
+    DateTime::from_timestamp(timestamp, 0)
+        .unwrap_or_default()
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string()
+}
+
+fn date(date: NaiveDate) -> String {
+    date.format("%Y%m%d").to_string()
+}
+
+/// Escapes a text value, e.g. a summary.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Splits a line longer than allowed, the following parts starting with a space.
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut length = 0;
+
+    for character in line.chars() {
+        if length + character.len_utf8() > MAX_LINE_LENGTH {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(character);
+        length += character.len_utf8();
+    }
+    folded
+}
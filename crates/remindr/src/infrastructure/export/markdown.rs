@@ -0,0 +1,291 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Error};
+use serde_json::Value;
+
+use crate::{
+    domain::database::document::{DocumentModel, heading_slugs, outline_numbers},
+    infrastructure::{export::template::ExportTemplate, vault::file_vault},
+};
+
+/// Files and heading anchors of documents exported together, so that the links between
+/// them keep working once exported.
+#[derive(Debug, Default)]
+pub struct ExportLinks {
+    /// File of each exported document.
+    files: HashMap<i32, PathBuf>,
+    /// Anchors of the headings of the exported documents, by document and block id.
+    anchors: HashMap<i32, HashMap<String, String>>,
+}
+
+impl ExportLinks {
+    /// Reserves a new `.md` file in `directory` for each document, so that documents can link
+    /// to the ones written after them.
+    pub fn reserve(
+        documents: &[DocumentModel],
+        directory: &Path,
+        numbered_headings: impl Fn(i32) -> bool,
+    ) -> Result<Self, Error> {
+        fs::create_dir_all(directory)
+            .with_context(|| format!("Failed to create {:?}", directory))?;
+
+        let mut links = Self::default();
+        for document in documents {
+            let path = file_vault::unique_path(directory, &document.title, Some("md"));
+            fs::File::create(&path).with_context(|| format!("Failed to create {:?}", path))?;
+            links.files.insert(document.id, path);
+            links.anchors.insert(
+                document.id,
+                heading_anchors(document, numbered_headings(document.id)),
+            );
+        }
+        Ok(links)
+    }
+
+    /// Address of the file of the document `document_id`, at its heading `heading` if any.
+    /// None when the document wasn't exported.
+    fn address(&self, document_id: i32, heading: Option<&str>) -> Option<String> {
+        let file = self.files.get(&document_id)?.file_name()?.to_string_lossy();
+        let anchor = heading
+            .and_then(|heading| self.anchors.get(&document_id)?.get(heading))
+            .map(|anchor| format!("#{}", anchor))
+            .unwrap_or_default();
+        // Angle brackets let the file name keep its spaces
+        Some(format!("<{}{}>", file, anchor))
+    }
+}
+
+/// Renders a document as markdown, one paragraph per block, with outline numbers before
+/// the headings when `numbered_headings` is set. Links to other documents point to their
+/// files when they are part of `links`, and are kept as wiki links otherwise.
+pub fn document_to_markdown(
+    document: &DocumentModel,
+    numbered_headings: bool,
+    links: &ExportLinks,
+) -> String {
+    format!(
+        "# {}\n{}",
+        document.title,
+        blocks_to_markdown(document, numbered_headings, links)
+    )
+}
+
+/// Renders a document through the export template of the user, its blocks being the
+/// `content` variable.
+pub fn templated_markdown(
+    document: &DocumentModel,
+    numbered_headings: bool,
+    links: &ExportLinks,
+    template: &ExportTemplate,
+    tags: &[String],
+) -> Result<String, Error> {
+    let content = blocks_to_markdown(document, numbered_headings, links);
+    template.render(document, tags, content.trim_start(), &[])
+}
+
+/// Blocks of a document as markdown paragraphs, each preceded by an empty line.
+fn blocks_to_markdown(
+    document: &DocumentModel,
+    numbered_headings: bool,
+    links: &ExportLinks,
+) -> String {
+    let mut markdown = String::new();
+    let blocks = document.content.as_array().cloned().unwrap_or_default();
+    let mut numbers = heading_numbers(&blocks, numbered_headings).into_iter();
+    // Headings of this document can be linked to even when it is exported alone
+    let anchors = heading_anchors(document, numbered_headings);
+
+    for block in &blocks {
+        let is_heading = block.get("type").and_then(Value::as_str) == Some("heading");
+        let number = if is_heading { numbers.next() } else { None };
+
+        let line = if block.get("type").and_then(Value::as_str) == Some("link") {
+            link_to_markdown(block, document.id, &anchors, links)
+        } else {
+            block_to_markdown(block, number)
+        };
+        if let Some(line) = line {
+            markdown.push('\n');
+            markdown.push_str(&line);
+            markdown.push('\n');
+        }
+    }
+
+    markdown
+}
+
+/// Writes the document as a `.md` file in `directory` and returns the created file.
+pub fn export_document(
+    document: &DocumentModel,
+    directory: &Path,
+    numbered_headings: bool,
+) -> Result<PathBuf, Error> {
+    fs::create_dir_all(directory).with_context(|| format!("Failed to create {:?}", directory))?;
+
+    let path = file_vault::unique_path(directory, &document.title, Some("md"));
+    fs::write(
+        &path,
+        document_to_markdown(document, numbered_headings, &ExportLinks::default()),
+    )
+    .with_context(|| format!("Failed to write {:?}", path))?;
+    Ok(path)
+}
+
+/// Writes a document in the file reserved for it by `links`, its links pointing to the
+/// other documents of `links`, through `template` when the user wrote one.
+pub fn export_linked_document(
+    document: &DocumentModel,
+    links: &ExportLinks,
+    numbered_headings: bool,
+    template: Option<&ExportTemplate>,
+    tags: &[String],
+) -> Result<PathBuf, Error> {
+    let path = links
+        .files
+        .get(&document.id)
+        .context("No file was reserved for the document")?;
+    let markdown = match template {
+        Some(template) => templated_markdown(document, numbered_headings, links, template, tags)?,
+        None => document_to_markdown(document, numbered_headings, links),
+    };
+    fs::write(path, markdown).with_context(|| format!("Failed to write {:?}", path))?;
+    Ok(path.clone())
+}
+
+/// Outline numbers of the headings of the blocks, none when they aren't numbered.
+fn heading_numbers(blocks: &[Value], numbered_headings: bool) -> Vec<String> {
+    if !numbered_headings {
+        return Vec::new();
+    }
+    let levels = blocks
+        .iter()
+        .filter(|block| block.get("type").and_then(Value::as_str) == Some("heading"))
+        .map(heading_level)
+        .collect::<Vec<_>>();
+    outline_numbers(&levels)
+}
+
+/// Anchor of each heading of the document, by block id, as markdown renderers name them
+/// from the heading text, numbers included.
+fn heading_anchors(document: &DocumentModel, numbered_headings: bool) -> HashMap<String, String> {
+    let blocks = document.content.as_array().cloned().unwrap_or_default();
+    let mut numbers = heading_numbers(&blocks, numbered_headings).into_iter();
+    let headings = blocks
+        .iter()
+        .filter(|block| block.get("type").and_then(Value::as_str) == Some("heading"))
+        .map(|block| {
+            let id = block.get("id").and_then(Value::as_str).unwrap_or_default();
+            (id.to_string(), heading_text(block, numbers.next()))
+        })
+        .collect::<Vec<_>>();
+
+    let slugs = heading_slugs(headings.iter().map(|(_, text)| text.as_str()));
+    headings.into_iter().map(|(id, _)| id).zip(slugs).collect()
+}
+
+fn heading_text(block: &Value, number: Option<String>) -> String {
+    let content = block
+        .get("metadata")
+        .and_then(|metadata| metadata.get("content"))
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    match number {
+        Some(number) => format!("{} {}", number, content),
+        None => content.to_string(),
+    }
+}
+
+/// Link block as a markdown link to the document or to one of its headings, `anchors`
+/// being those of the document `from` it is in, or as a wiki link when the document isn't
+/// exported.
+fn link_to_markdown(
+    block: &Value,
+    from: i32,
+    anchors: &HashMap<String, String>,
+    links: &ExportLinks,
+) -> Option<String> {
+    let metadata = block.get("metadata")?;
+    let title = metadata.get("title").and_then(Value::as_str)?;
+    let document_id = metadata
+        .get("document_id")
+        .and_then(Value::as_i64)
+        .unwrap_or_default() as i32;
+    let heading = metadata.get("heading").and_then(Value::as_str);
+
+    let address = if document_id == from {
+        heading
+            .and_then(|heading| anchors.get(heading))
+            .map(|anchor| format!("#{}", anchor))
+    } else {
+        links.address(document_id, heading)
+    };
+    Some(match address {
+        Some(address) => format!("[{}]({})", title, address),
+        None => format!("[[{}]]", title),
+    })
+}
+
+fn heading_level(block: &Value) -> u32 {
+    block
+        .get("metadata")
+        .and_then(|metadata| metadata.get("level"))
+        .and_then(Value::as_u64)
+        .unwrap_or(1)
+        .clamp(1, 6) as u32
+}
+
+fn block_to_markdown(block: &Value, number: Option<String>) -> Option<String> {
+    let metadata = block.get("metadata");
+    let content = metadata
+        .and_then(|metadata| metadata.get("content"))
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+
+    match block.get("type").and_then(Value::as_str)? {
+        "heading" => {
+            let level = heading_level(block) as usize;
+            // The document title is the first level heading
+            Some(format!(
+                "{} {}",
+                "#".repeat((level + 1).min(6)),
+                heading_text(block, number)
+            ))
+        }
+        "list" => {
+            let ordered = metadata
+                .and_then(|metadata| metadata.get("ordered"))
+                .and_then(Value::as_bool)
+                .unwrap_or(false);
+            Some(format!("{} {}", if ordered { "1." } else { "-" }, content))
+        }
+        "quote" => Some(
+            content
+                .lines()
+                .map(|line| format!("> {}", line))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        ),
+        "todo" => {
+            let done = metadata
+                .and_then(|metadata| metadata.get("status"))
+                .and_then(Value::as_str)
+                == Some("done");
+            Some(format!("- [{}] {}", if done { "x" } else { " " }, content))
+        }
+        "attachment" => metadata
+            .and_then(|metadata| metadata.get("file_name"))
+            .and_then(Value::as_str)
+            .filter(|file_name| !file_name.is_empty())
+            .map(|file_name| format!("📎 {}", file_name)),
+        // Drawings have no markdown equivalent
+        "drawing" => None,
+        "equation" if !content.is_empty() => Some(format!("$$\n{}\n$$", content)),
+        "divider" => Some("---".to_string()),
+        _ if content.is_empty() => None,
+        _ => Some(content.to_string()),
+    }
+}
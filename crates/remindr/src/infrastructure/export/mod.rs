@@ -0,0 +1,6 @@
+pub mod docx;
+pub mod ical;
+pub mod markdown;
+pub mod opml;
+pub mod site;
+pub mod template;
@@ -0,0 +1,83 @@
+use crate::domain::database::{document::DocumentModel, folder::FolderModel};
+
+/// Renders the folders and their documents as an OPML outline, for outliners to open.
+///
+/// Folders and documents are outlines typed `folder` and `document`, the blocks of a
+/// document being the outlines under it, one per block with text. Unfiled documents are at
+/// the top level, after the folders.
+pub fn folder_tree(folders: &[FolderModel], documents: &[DocumentModel]) -> String {
+    let mut opml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <opml version=\"2.0\">\n  <head>\n    <title>Remindr</title>\n  </head>\n  <body>\n",
+    );
+    write_children(None, folders, documents, 2, &mut opml);
+    opml.push_str("  </body>\n</opml>\n");
+    opml
+}
+
+fn write_children(
+    parent_id: Option<i32>,
+    folders: &[FolderModel],
+    documents: &[DocumentModel],
+    depth: usize,
+    opml: &mut String,
+) {
+    let indent = "  ".repeat(depth);
+
+    for folder in folders
+        .iter()
+        .filter(|folder| folder.parent_id == parent_id)
+    {
+        opml.push_str(&format!(
+            "{}<outline text=\"{}\" type=\"folder\">\n",
+            indent,
+            escape(&folder.name)
+        ));
+        write_children(Some(folder.id), folders, documents, depth + 1, opml);
+        opml.push_str(&format!("{}</outline>\n", indent));
+    }
+
+    for document in documents
+        .iter()
+        .filter(|document| document.folder_id == parent_id)
+    {
+        let lines = document
+            .content
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|block| block["metadata"]["content"].as_str())
+            .filter(|content| !content.trim().is_empty())
+            .collect::<Vec<_>>();
+        let title = escape(&document.title);
+
+        if lines.is_empty() {
+            opml.push_str(&format!(
+                "{}<outline text=\"{}\" type=\"document\"/>\n",
+                indent, title
+            ));
+            continue;
+        }
+        opml.push_str(&format!(
+            "{}<outline text=\"{}\" type=\"document\">\n",
+            indent, title
+        ));
+        for line in lines {
+            opml.push_str(&format!(
+                "{}  <outline text=\"{}\"/>\n",
+                indent,
+                escape(line)
+            ));
+        }
+        opml.push_str(&format!("{}</outline>\n", indent));
+    }
+}
+
+/// Escapes an attribute value, keeping its line breaks.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\n', "&#10;")
+}
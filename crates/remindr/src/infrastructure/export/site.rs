@@ -0,0 +1,475 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Error};
+use serde_json::Value;
+
+use crate::{
+    domain::database::{
+        document::{DocumentModel, heading_slugs},
+        folder::FolderModel,
+    },
+    infrastructure::{export::template::ExportTemplate, vault::file_vault},
+};
+
+/// Directory of the site the attached files are copied to.
+const FILES_DIR: &str = "files";
+
+/// Extensions of the attached files shown as images.
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp", "svg"];
+
+/// Colors and font of the site, as CSS values taken from the theme of the app.
+#[derive(Debug, Clone)]
+pub struct SiteTheme {
+    pub background: String,
+    pub foreground: String,
+    pub muted_foreground: String,
+    pub accent: String,
+    pub border: String,
+    pub font_family: String,
+}
+
+/// Folder published as a static site: a page per document of the folder and its
+/// subfolders, with the folder tree as navigation.
+pub struct Site {
+    root: FolderModel,
+    /// Subfolders of the root, at any depth.
+    folders: Vec<FolderModel>,
+    documents: Vec<DocumentModel>,
+    /// File name of the page of each document.
+    pages: HashMap<i32, String>,
+    /// Anchors of the headings of each document, by block id.
+    anchors: HashMap<i32, HashMap<String, String>>,
+    /// Template of the user for the pages of the documents, with their tags.
+    template: Option<(ExportTemplate, HashMap<i32, Vec<String>>)>,
+}
+
+impl Site {
+    /// Site of the folder `folder_id`, none if it doesn't exist.
+    pub fn new(
+        folder_id: i32,
+        folders: Vec<FolderModel>,
+        documents: Vec<DocumentModel>,
+    ) -> Option<Self> {
+        let root = folders
+            .iter()
+            .find(|folder| folder.id == folder_id)?
+            .clone();
+
+        let mut ids = HashSet::from([folder_id]);
+        let mut subfolders = Vec::new();
+        // Parents are added before their children, whatever the order of the folders
+        while let Some(folder) = folders.iter().find(|folder| {
+            !ids.contains(&folder.id) && folder.parent_id.is_some_and(|id| ids.contains(&id))
+        }) {
+            ids.insert(folder.id);
+            subfolders.push(folder.clone());
+        }
+
+        let documents = documents
+            .into_iter()
+            .filter(|document| document.folder_id.is_some_and(|id| ids.contains(&id)))
+            .collect::<Vec<_>>();
+
+        let mut names = HashSet::from(["index".to_string()]);
+        let pages = documents
+            .iter()
+            .map(|document| {
+                let base = slug(&document.title);
+                let mut name = base.clone();
+                let mut suffix = 2;
+                while !names.insert(name.clone()) {
+                    name = format!("{}-{}", base, suffix);
+                    suffix += 1;
+                }
+                (document.id, format!("{}.html", name))
+            })
+            .collect();
+        let anchors = documents
+            .iter()
+            .map(|document| (document.id, heading_anchors(document)))
+            .collect();
+
+        Some(Self {
+            root,
+            folders: subfolders,
+            documents,
+            pages,
+            anchors,
+            template: None,
+        })
+    }
+
+    /// Writes the pages of the documents through `template`, given the tags of each
+    /// document along with its other properties.
+    pub fn with_template(
+        mut self,
+        template: ExportTemplate,
+        tags: HashMap<i32, Vec<String>>,
+    ) -> Self {
+        self.template = Some((template, tags));
+        self
+    }
+
+    /// Ids of the files attached to the documents, to copy along with the pages.
+    pub fn attachment_ids(&self) -> Vec<i32> {
+        self.documents
+            .iter()
+            .flat_map(|document| blocks(document))
+            .filter(|block| block["type"] == "attachment")
+            .filter_map(|block| block["metadata"]["attachment_id"].as_i64())
+            .map(|id| id as i32)
+            .collect()
+    }
+
+    /// Writes the site in a new directory of `directory`, named after the folder, and
+    /// returns it. `files` are the paths of the attached files, by attachment id.
+    pub fn write(
+        &self,
+        directory: &Path,
+        theme: &SiteTheme,
+        files: &HashMap<i32, PathBuf>,
+    ) -> Result<PathBuf, Error> {
+        let site_dir = file_vault::unique_path(directory, &self.root.name, None);
+        fs::create_dir_all(&site_dir)
+            .with_context(|| format!("Failed to create {:?}", site_dir))?;
+
+        // Attached files keep their name, made unique in the files directory
+        let mut copied = HashMap::new();
+        for (id, path) in files {
+            let files_dir = site_dir.join(FILES_DIR);
+            fs::create_dir_all(&files_dir)?;
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| id.to_string());
+            let target = file_vault::unique_path(&files_dir, &name, None);
+            fs::copy(path, &target).with_context(|| format!("Failed to copy {:?}", path))?;
+            let name = target
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            copied.insert(*id, format!("{}/{}", FILES_DIR, name));
+        }
+
+        fs::write(site_dir.join("style.css"), stylesheet(theme))?;
+        fs::write(
+            site_dir.join("index.html"),
+            self.page(&self.root.name, None, &self.index_body()),
+        )?;
+        for document in &self.documents {
+            let body = self.document_body(document, &copied);
+            let page = match &self.template {
+                Some((template, tags)) => self.templated_page(document, template, tags, &body)?,
+                None => self.page(&document.title, Some(document.id), &body),
+            };
+            fs::write(site_dir.join(&self.pages[&document.id]), page)?;
+        }
+
+        Ok(site_dir)
+    }
+
+    fn page(&self, title: &str, current: Option<i32>, body: &str) -> String {
+        format!(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+             <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+             <title>{title} · {site}</title>\n<link rel=\"stylesheet\" href=\"style.css\">\n\
+             </head>\n<body>\n<nav>\n<a class=\"site\" href=\"index.html\">{site}</a>\n{nav}</nav>\n\
+             <main>\n<h1>{title}</h1>\n{body}</main>\n</body>\n</html>\n",
+            title = escape(title),
+            site = escape(&self.root.name),
+            nav = self.navigation(self.root.id, current),
+            body = body,
+        )
+    }
+
+    /// Page of a document written with the template of the user, which also gets the
+    /// `site` name, its `navigation` and the `stylesheet` of the site.
+    fn templated_page(
+        &self,
+        document: &DocumentModel,
+        template: &ExportTemplate,
+        tags: &HashMap<i32, Vec<String>>,
+        body: &str,
+    ) -> Result<String, Error> {
+        let navigation = self.navigation(self.root.id, Some(document.id));
+        template.render(
+            document,
+            tags.get(&document.id)
+                .map(Vec::as_slice)
+                .unwrap_or_default(),
+            body,
+            &[
+                ("site", self.root.name.as_str().into()),
+                ("navigation", minijinja::Value::from_safe_string(navigation)),
+                ("stylesheet", "style.css".into()),
+            ],
+        )
+    }
+
+    /// Documents of the folder followed by its subfolders, as nested lists.
+    fn navigation(&self, folder_id: i32, current: Option<i32>) -> String {
+        let mut html = String::from("<ul>\n");
+        for document in self
+            .documents
+            .iter()
+            .filter(|document| document.folder_id == Some(folder_id))
+        {
+            let class = if current == Some(document.id) {
+                " class=\"current\""
+            } else {
+                ""
+            };
+            html.push_str(&format!(
+                "<li><a href=\"{}\"{}>{}</a></li>\n",
+                self.pages[&document.id],
+                class,
+                escape(&document.title)
+            ));
+        }
+        for folder in self
+            .folders
+            .iter()
+            .filter(|folder| folder.parent_id == Some(folder_id))
+        {
+            html.push_str(&format!(
+                "<li class=\"folder\"><span>{}</span>\n{}</li>\n",
+                escape(&folder.name),
+                self.navigation(folder.id, current)
+            ));
+        }
+        html.push_str("</ul>\n");
+        html
+    }
+
+    fn index_body(&self) -> String {
+        match self.documents.len() {
+            0 => "<p class=\"muted\">This folder has no documents.</p>\n".to_string(),
+            1 => "<p class=\"muted\">1 document</p>\n".to_string(),
+            count => format!("<p class=\"muted\">{} documents</p>\n", count),
+        }
+    }
+
+    fn document_body(&self, document: &DocumentModel, files: &HashMap<i32, String>) -> String {
+        let mut html = String::new();
+        // List the items being written belong to, closed by the next block of another kind
+        let mut open_list: Option<&str> = None;
+        let anchors = self.anchors.get(&document.id);
+
+        for block in blocks(document) {
+            let kind = block["type"].as_str().unwrap_or_default();
+            let metadata = &block["metadata"];
+            let content = metadata["content"].as_str().unwrap_or_default();
+
+            let list = match kind {
+                "list" if metadata["ordered"].as_bool() == Some(true) => Some("ol"),
+                "list" => Some("ul"),
+                "todo" => Some("ul class=\"todos\""),
+                _ => None,
+            };
+            if open_list != list {
+                if let Some(open) = open_list {
+                    html.push_str(&format!("</{}>\n", open.split(' ').next().unwrap_or(open)));
+                }
+                if let Some(list) = list {
+                    html.push_str(&format!("<{}>\n", list));
+                }
+                open_list = list;
+            }
+
+            match kind {
+                "heading" => {
+                    // The title of the page is the only h1
+                    let level = metadata["level"].as_u64().unwrap_or(1).clamp(1, 5) + 1;
+                    let anchor = block["id"]
+                        .as_str()
+                        .and_then(|id| anchors?.get(id))
+                        .map(|anchor| format!(" id=\"{}\"", escape(anchor)))
+                        .unwrap_or_default();
+                    html.push_str(&format!(
+                        "<h{0}{1}>{2}</h{0}>\n",
+                        level,
+                        anchor,
+                        text(content)
+                    ));
+                }
+                "list" => html.push_str(&format!("<li>{}</li>\n", text(content))),
+                "todo" => {
+                    let done = metadata["status"] == "done";
+                    html.push_str(&format!(
+                        "<li{}>{} {}</li>\n",
+                        if done { " class=\"done\"" } else { "" },
+                        if done { "☑" } else { "☐" },
+                        text(content)
+                    ));
+                }
+                "quote" => html.push_str(&format!("<blockquote>{}</blockquote>\n", text(content))),
+                "divider" => html.push_str("<hr>\n"),
+                "equation" => html.push_str(&format!(
+                    "<pre class=\"equation\">{}</pre>\n",
+                    escape(content)
+                )),
+                "link" => {
+                    let title = escape(metadata["title"].as_str().unwrap_or_default());
+                    let target = metadata["document_id"].as_i64().map(|id| id as i32);
+                    let page = target.and_then(|id| self.pages.get(&id));
+                    match page {
+                        Some(page) => {
+                            // Links to a heading of the page it is on stay on the page
+                            let page = if target == Some(document.id) {
+                                ""
+                            } else {
+                                page
+                            };
+                            let anchor = metadata["heading"]
+                                .as_str()
+                                .and_then(|heading| self.anchors.get(&target?)?.get(heading))
+                                .map(|anchor| format!("#{}", anchor))
+                                .unwrap_or_default();
+                            let href = match format!("{}{}", page, anchor) {
+                                href if href.is_empty() => self.pages[&document.id].clone(),
+                                href => href,
+                            };
+                            html.push_str(&format!(
+                                "<p class=\"link\"><a href=\"{}\">{}</a></p>\n",
+                                escape(&href),
+                                title
+                            ))
+                        }
+                        // Documents outside of the site aren't published
+                        None => html.push_str(&format!("<p class=\"link\">{}</p>\n", title)),
+                    }
+                }
+                "attachment" => {
+                    let file_name = metadata["file_name"].as_str().unwrap_or_default();
+                    let file = metadata["attachment_id"]
+                        .as_i64()
+                        .and_then(|id| files.get(&(id as i32)));
+                    html.push_str(&attachment(file_name, file));
+                }
+                _ if !content.trim().is_empty() => {
+                    html.push_str(&format!("<p>{}</p>\n", text(content)))
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(open) = open_list {
+            html.push_str(&format!("</{}>\n", open.split(' ').next().unwrap_or(open)));
+        }
+        html
+    }
+}
+
+/// Anchor of each heading of the document, by block id, named from the heading text.
+fn heading_anchors(document: &DocumentModel) -> HashMap<String, String> {
+    let headings = blocks(document)
+        .filter(|block| block["type"] == "heading")
+        .map(|block| {
+            (
+                block["id"].as_str().unwrap_or_default(),
+                block["metadata"]["content"].as_str().unwrap_or_default(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let slugs = heading_slugs(headings.iter().map(|(_, content)| *content));
+    headings
+        .into_iter()
+        .map(|(id, _)| id.to_string())
+        .zip(slugs)
+        .collect()
+}
+
+fn blocks(document: &DocumentModel) -> impl Iterator<Item = &Value> {
+    document.content.as_array().into_iter().flatten()
+}
+
+/// Image or link to an attached file, or only its name when it couldn't be copied.
+fn attachment(file_name: &str, file: Option<&String>) -> String {
+    let name = escape(file_name);
+    let Some(file) = file else {
+        return format!("<p class=\"attachment\">{}</p>\n", name);
+    };
+
+    let is_image = Path::new(file)
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .is_some_and(|extension| IMAGE_EXTENSIONS.contains(&extension.as_str()));
+    if is_image {
+        format!(
+            "<figure><img src=\"{}\" alt=\"{}\"></figure>\n",
+            escape(file),
+            name
+        )
+    } else {
+        format!(
+            "<p class=\"attachment\"><a href=\"{}\">{}</a></p>\n",
+            escape(file),
+            name
+        )
+    }
+}
+
+fn stylesheet(theme: &SiteTheme) -> String {
+    format!(
+        ":root {{\n  --background: {};\n  --foreground: {};\n  --muted: {};\n  --accent: {};\n  \
+         --border: {};\n}}\n\
+         body {{ margin: 0; display: flex; min-height: 100vh; background: var(--background); \
+         color: var(--foreground); font-family: {}, system-ui, sans-serif; line-height: 1.6; }}\n\
+         nav {{ width: 240px; flex-shrink: 0; padding: 24px 16px; border-right: 1px solid var(--border); \
+         font-size: 14px; }}\n\
+         nav ul {{ list-style: none; margin: 0; padding-left: 12px; }}\n\
+         nav > ul {{ padding-left: 0; }}\n\
+         nav a {{ color: inherit; text-decoration: none; }}\n\
+         nav a:hover, nav a.current {{ color: var(--accent); }}\n\
+         nav .site {{ display: block; margin-bottom: 16px; font-weight: 600; }}\n\
+         nav .folder > span {{ color: var(--muted); }}\n\
+         main {{ flex: 1; max-width: 760px; padding: 32px 48px; }}\n\
+         a {{ color: var(--accent); }}\n\
+         blockquote {{ margin: 0; padding-left: 16px; border-left: 3px solid var(--border); \
+         color: var(--muted); }}\n\
+         hr {{ border: none; border-top: 1px solid var(--border); }}\n\
+         .todos {{ list-style: none; padding-left: 0; }}\n\
+         .done {{ color: var(--muted); text-decoration: line-through; }}\n\
+         .muted, .attachment {{ color: var(--muted); }}\n\
+         pre {{ white-space: pre-wrap; }}\n\
+         img {{ max-width: 100%; border-radius: 6px; }}\n",
+        theme.background,
+        theme.foreground,
+        theme.muted_foreground,
+        theme.accent,
+        theme.border,
+        theme.font_family,
+    )
+}
+
+/// Escaped text keeping its line breaks.
+fn text(content: &str) -> String {
+    escape(content).replace('\n', "<br>")
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Name of a page, e.g. "meeting-notes" for "Meeting notes!".
+fn slug(title: &str) -> String {
+    let slug = title
+        .to_lowercase()
+        .split(|character: char| !character.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if slug.is_empty() {
+        "page".to_string()
+    } else {
+        slug
+    }
+}
@@ -0,0 +1,144 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use anyhow::{Context, Error};
+use minijinja::{Environment, Value};
+
+use crate::domain::database::{document::DocumentModel, task::TaskModel};
+
+/// Format of the files an export template writes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemplateFormat {
+    Markdown,
+    Html,
+}
+
+impl TemplateFormat {
+    /// Name of the template file in the templates directory. Its extension also turns
+    /// the escaping of the variables on for HTML.
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            TemplateFormat::Markdown => "document.md",
+            TemplateFormat::Html => "document.html",
+        }
+    }
+}
+
+/// Template written by the user around each exported document, with the `title`,
+/// `properties` and rendered `content` of the document as variables.
+#[derive(Debug, Clone)]
+pub struct ExportTemplate {
+    format: TemplateFormat,
+    source: String,
+}
+
+impl ExportTemplate {
+    pub fn new(format: TemplateFormat, source: impl Into<String>) -> Result<Self, Error> {
+        let template = Self {
+            format,
+            source: source.into(),
+        };
+        // Syntax errors are reported before anything is written
+        Environment::new()
+            .template_from_named_str(format.file_name(), &template.source)
+            .context("Invalid export template")?;
+        Ok(template)
+    }
+
+    /// Template of the format in `directory`, none when the user didn't write one.
+    pub fn load(directory: &Path, format: TemplateFormat) -> Result<Option<Self>, Error> {
+        let path = directory.join(format.file_name());
+        if !path.exists() {
+            return Ok(None);
+        }
+        let source =
+            fs::read_to_string(&path).with_context(|| format!("Failed to read {:?}", path))?;
+        Self::new(format, source)
+            .with_context(|| format!("Invalid template {:?}", path))
+            .map(Some)
+    }
+
+    /// Renders the template for a document whose blocks were rendered as `content`.
+    /// `extra` variables are added for the formats that have more, like the navigation
+    /// of a site.
+    pub fn render(
+        &self,
+        document: &DocumentModel,
+        tags: &[String],
+        content: &str,
+        extra: &[(&str, Value)],
+    ) -> Result<String, Error> {
+        let mut variables = BTreeMap::from([
+            ("title", Value::from(document.title.as_str())),
+            (
+                "properties",
+                Value::from_serialize(document_properties(document, tags)),
+            ),
+            // Already rendered in the format, so it is never escaped
+            ("content", Value::from_safe_string(content.to_string())),
+        ]);
+        variables.extend(extra.iter().cloned());
+
+        Environment::new()
+            .template_from_named_str(self.format.file_name(), &self.source)?
+            .render(variables)
+            .with_context(|| format!("Failed to render the template of {:?}", document.title))
+    }
+}
+
+/// Properties of a document given to the templates, as shown in the collections.
+pub fn document_properties(
+    document: &DocumentModel,
+    tags: &[String],
+) -> BTreeMap<&'static str, serde_json::Value> {
+    let words = document
+        .content
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|block| block.get("metadata")?.get("content")?.as_str())
+        .map(|text| text.split_whitespace().count())
+        .sum::<usize>();
+
+    BTreeMap::from([
+        ("id", document.id.into()),
+        ("folder_id", document.folder_id.into()),
+        ("tags", tags.into()),
+        ("words", words.into()),
+        (
+            "open_todos",
+            TaskModel::from_document(document.id, &document.content)
+                .len()
+                .into(),
+        ),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn html_templates_escape_the_title_but_not_the_content() {
+        let document = DocumentModel {
+            id: 3,
+            title: "Fish & chips".to_string(),
+            content: json!([{ "id": "a", "type": "text", "metadata": { "content": "Two words" } }]),
+            folder_id: None,
+        };
+        let template = ExportTemplate::new(
+            TemplateFormat::Html,
+            "<h1>{{ title }}</h1>{{ content }}<p>{{ properties.words }} {{ properties.tags | join(\", \") }}</p>",
+        )
+        .unwrap();
+
+        let html = template
+            .render(&document, &["food".to_string()], "<p>Two words</p>", &[])
+            .unwrap();
+        assert_eq!(
+            html,
+            "<h1>Fish &amp; chips</h1><p>Two words</p><p>2 food</p>"
+        );
+    }
+}
@@ -0,0 +1,500 @@
+//! Localhost HTTP API, for browser extensions and capture tools to read and write documents.
+//!
+//! Every request must carry the token of the settings as `Authorization: Bearer <token>`.
+//!
+//! - `GET /documents`: id, title and folder of every document
+//! - `POST /documents`: creates a document from `{ "title", "content"?, "folder_id"? }`
+//! - `GET /documents/{id}`: the document with its blocks
+//! - `PUT /documents/{id}`: replaces the fields given among `title`, `content` and `folder_id`,
+//!   `null` moving the document to the root. Answers `409 Conflict` while the document has
+//!   edits the app hasn't saved yet.
+//! - `DELETE /documents/{id}`: deletes the document like the app does, with a delay to undo
+//!   it. Answers `409 Conflict` while the document has unsaved edits, like `PUT`.
+//! - `GET /search?q=<text>`: documents containing every word of the text
+//! - `POST /clip`: appends `{ "url", "title"?, "html"? }` to the Inbox document, the HTML
+//!   of the selection converted to blocks
+//! - `GET /calendar.ics?token=<token>`: reminders and dated tasks as an iCalendar feed. The
+//!   token is given in the URL since calendar apps can't send headers.
+
+use std::{collections::HashSet, net::Ipv4Addr, sync::Arc};
+
+use chrono::Utc;
+
+use anyhow::{Context, Error};
+use axum::{
+    Json, Router,
+    extract::{Path, Query, Request, State},
+    http::{
+        StatusCode,
+        header::{AUTHORIZATION, CONTENT_TYPE},
+    },
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{Value, json};
+use smol::channel::Sender;
+use tokio::{net::TcpListener, sync::oneshot};
+
+use crate::{
+    Utils,
+    domain::{database::document::DocumentModel, ports::DocumentRepositoryPort},
+    infrastructure::{
+        export::ical,
+        import::html::html_to_blocks,
+        repositories::{reminder_repository::ReminderRepository, task_repository::TaskRepository},
+    },
+};
+
+/// Repositories the API reads and writes.
+#[derive(Clone)]
+pub struct ApiRepositories {
+    pub documents: Arc<dyn DocumentRepositoryPort>,
+    pub reminders: ReminderRepository,
+    pub tasks: TaskRepository,
+}
+
+/// Changes made through the API, for the app to show them, and questions to the app.
+pub enum ApiEvent {
+    /// The document was created.
+    Created(i32),
+    /// The document was updated.
+    Changed(i32),
+    /// Asks to delete the document as from the sidebar, closing its tabs and offering to
+    /// undo it, and to reply once it's hidden. The reply is dropped when no window can
+    /// offer the undo, for the API to delete it right away.
+    Deleted {
+        id: i32,
+        title: String,
+        reply: oneshot::Sender<()>,
+    },
+    /// Asks to append the blocks of a web page to the inbox document, through its editor
+    /// when it's loaded, and to reply with the inbox id.
+    Clip {
+        title: String,
+        blocks: Vec<Value>,
+        reply: oneshot::Sender<Result<i32, Error>>,
+    },
+    /// Asks whether the document has edits waiting to be saved, which would overwrite a
+    /// change made through the API.
+    HasUnsavedEdits {
+        id: i32,
+        reply: oneshot::Sender<bool>,
+    },
+    /// Asks for the documents deleted in the app whose deletion can still be undone, which
+    /// the API doesn't show anymore.
+    PendingDeletions {
+        reply: oneshot::Sender<HashSet<i32>>,
+    },
+}
+
+/// Shared by the handlers of every request.
+#[derive(Clone)]
+struct ApiContext {
+    repositories: ApiRepositories,
+    token: String,
+    events: Sender<ApiEvent>,
+}
+
+impl ApiContext {
+    fn send(&self, event: ApiEvent) {
+        let _ = self.events.try_send(event);
+    }
+
+    /// Whether the app holds unsaved edits of the document, false once it's closed.
+    async fn has_unsaved_edits(&self, id: i32) -> bool {
+        let (reply, answer) = oneshot::channel();
+        self.send(ApiEvent::HasUnsavedEdits { id, reply });
+        answer.await.unwrap_or(false)
+    }
+
+    /// Documents deleted in the app but not yet from the storage, none once it's closed.
+    async fn pending_deletions(&self) -> HashSet<i32> {
+        let (reply, answer) = oneshot::channel();
+        self.send(ApiEvent::PendingDeletions { reply });
+        answer.await.unwrap_or_default()
+    }
+
+    /// The document, unless missing or deleted in the app.
+    async fn find_document(&self, id: i32) -> Result<Option<DocumentModel>, Error> {
+        if self.pending_deletions().await.contains(&id) {
+            return Ok(None);
+        }
+        self.repositories.documents.find_document(id).await
+    }
+}
+
+/// Failure answered as `{ "error": message }` with its status.
+struct ApiError(StatusCode, String);
+
+impl From<Error> for ApiError {
+    fn from(error: Error) -> Self {
+        tracing::error!("local API request failed: {:?}", error);
+        ApiError(StatusCode::INTERNAL_SERVER_ERROR, error.to_string())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(json!({ "error": self.1 }))).into_response()
+    }
+}
+
+type ApiResult<T> = Result<Json<T>, ApiError>;
+
+#[derive(Serialize)]
+struct DocumentSummary {
+    id: i32,
+    title: String,
+    folder_id: Option<i32>,
+}
+
+impl From<DocumentModel> for DocumentSummary {
+    fn from(document: DocumentModel) -> Self {
+        Self {
+            id: document.id,
+            title: document.title,
+            folder_id: document.folder_id,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct NewDocument {
+    title: String,
+    #[serde(default)]
+    content: Option<Value>,
+    #[serde(default)]
+    folder_id: Option<i32>,
+}
+
+#[derive(Deserialize)]
+struct DocumentChanges {
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    content: Option<Value>,
+    /// `Some(None)` when given as `null`, to move the document to the root.
+    #[serde(default, deserialize_with = "double_option")]
+    folder_id: Option<Option<i32>>,
+}
+
+/// Tells a field given as `null`, `Some(None)`, from a missing one, `None`.
+fn double_option<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+#[derive(Deserialize)]
+struct Clip {
+    url: String,
+    #[serde(default)]
+    title: Option<String>,
+    /// Selected part of the page.
+    #[serde(default)]
+    html: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SearchQuery {
+    q: String,
+}
+
+#[derive(Deserialize)]
+struct TokenQuery {
+    #[serde(default)]
+    token: String,
+}
+
+/// Serves the API on `127.0.0.1:port` until the process exits, sending the changes made
+/// through it to `events`.
+pub async fn serve(
+    port: u16,
+    token: String,
+    repositories: ApiRepositories,
+    events: Sender<ApiEvent>,
+) -> Result<(), Error> {
+    let context = ApiContext {
+        repositories,
+        token,
+        events,
+    };
+
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+        .await
+        .with_context(|| format!("Failed to listen on port {}", port))?;
+    tracing::info!("local API listening on {:?}", listener.local_addr()?);
+
+    axum::serve(listener, router(context)).await?;
+    Ok(())
+}
+
+fn router(context: ApiContext) -> Router {
+    Router::new()
+        .route("/documents", get(list_documents).post(create_document))
+        .route(
+            "/documents/{id}",
+            get(get_document)
+                .put(update_document)
+                .delete(delete_document),
+        )
+        .route("/search", get(search_documents))
+        .route("/clip", post(clip))
+        .route_layer(middleware::from_fn_with_state(context.clone(), authorize))
+        // Added after the layer, checking the token of the URL itself
+        .route("/calendar.ics", get(calendar))
+        .with_state(context)
+}
+
+async fn authorize(State(context): State<ApiContext>, request: Request, next: Next) -> Response {
+    let token = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    if !token.is_some_and(|token| same_token(token, &context.token)) {
+        return ApiError(StatusCode::UNAUTHORIZED, "Missing or invalid token".into())
+            .into_response();
+    }
+    next.run(request).await
+}
+
+/// Compares the tokens in a time that doesn't depend on where they differ.
+fn same_token(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0, |difference, (a, b)| difference | (a ^ b))
+            == 0
+}
+
+/// Blocks of a document, which must be a JSON array.
+fn validate_content(content: Value) -> Result<Value, ApiError> {
+    if content.is_array() {
+        Ok(content)
+    } else {
+        Err(ApiError(
+            StatusCode::BAD_REQUEST,
+            "The content must be an array of blocks".into(),
+        ))
+    }
+}
+
+async fn list_documents(State(context): State<ApiContext>) -> ApiResult<Vec<DocumentSummary>> {
+    let documents = context.repositories.documents.get_documents().await?;
+    let deleted = context.pending_deletions().await;
+    Ok(Json(
+        documents
+            .into_iter()
+            .filter(|document| !deleted.contains(&document.id))
+            .map(Into::into)
+            .collect(),
+    ))
+}
+
+async fn get_document(
+    State(context): State<ApiContext>,
+    Path(id): Path<i32>,
+) -> ApiResult<DocumentModel> {
+    match context.find_document(id).await? {
+        Some(document) => Ok(Json(document)),
+        None => Err(not_found(id)),
+    }
+}
+
+async fn create_document(
+    State(context): State<ApiContext>,
+    Json(document): Json<NewDocument>,
+) -> Result<(StatusCode, Json<Value>), ApiError> {
+    let content = validate_content(document.content.unwrap_or_else(|| json!([])))?;
+    let id = context
+        .repositories
+        .documents
+        .insert_document(DocumentModel {
+            id: 0,
+            title: document.title,
+            content,
+            folder_id: document.folder_id,
+        })
+        .await?;
+    context.send(ApiEvent::Created(id));
+
+    Ok((StatusCode::CREATED, Json(json!({ "id": id }))))
+}
+
+async fn update_document(
+    State(context): State<ApiContext>,
+    Path(id): Path<i32>,
+    Json(changes): Json<DocumentChanges>,
+) -> ApiResult<DocumentModel> {
+    let Some(mut document) = context.find_document(id).await? else {
+        return Err(not_found(id));
+    };
+    if context.has_unsaved_edits(id).await {
+        return Err(unsaved_edits(id));
+    }
+
+    if let Some(title) = changes.title {
+        document.title = title;
+    }
+    if let Some(content) = changes.content {
+        document.content = validate_content(content)?;
+    }
+    if let Some(folder_id) = changes.folder_id {
+        document.folder_id = folder_id;
+    }
+
+    context
+        .repositories
+        .documents
+        .update_document(document.clone())
+        .await?;
+    context.send(ApiEvent::Changed(id));
+    Ok(Json(document))
+}
+
+async fn delete_document(
+    State(context): State<ApiContext>,
+    Path(id): Path<i32>,
+) -> Result<StatusCode, ApiError> {
+    let Some(document) = context.find_document(id).await? else {
+        return Err(not_found(id));
+    };
+    if context.has_unsaved_edits(id).await {
+        return Err(unsaved_edits(id));
+    }
+
+    let (reply, answer) = oneshot::channel();
+    context.send(ApiEvent::Deleted {
+        id,
+        title: document.title,
+        reply,
+    });
+    if answer.await.is_err() {
+        context.repositories.documents.delete_document(id).await?;
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn search_documents(
+    State(context): State<ApiContext>,
+    Query(query): Query<SearchQuery>,
+) -> ApiResult<Vec<DocumentSummary>> {
+    let documents = context
+        .repositories
+        .documents
+        .search_documents(&query.q)
+        .await?;
+    let deleted = context.pending_deletions().await;
+    Ok(Json(
+        documents
+            .into_iter()
+            .filter(|document| !deleted.contains(&document.id))
+            .map(Into::into)
+            .collect(),
+    ))
+}
+
+async fn clip(
+    State(context): State<ApiContext>,
+    Json(clip): Json<Clip>,
+) -> Result<(StatusCode, Json<Value>), ApiError> {
+    let url = clip.url.trim().to_string();
+    if url.is_empty() {
+        return Err(ApiError(
+            StatusCode::BAD_REQUEST,
+            "The url is required".into(),
+        ));
+    }
+    let title = clip
+        .title
+        .map(|title| title.trim().to_string())
+        .filter(|title| !title.is_empty())
+        .unwrap_or_else(|| url.clone());
+
+    let mut blocks = vec![
+        json!({
+            "id": Utils::generate_uuid(),
+            "type": "heading",
+            "metadata": { "content": title, "level": 2 },
+        }),
+        json!({
+            "id": Utils::generate_uuid(),
+            "type": "text",
+            "metadata": { "content": url },
+        }),
+    ];
+    blocks.extend(html_to_blocks(clip.html.as_deref().unwrap_or_default()));
+
+    let (reply, answer) = oneshot::channel();
+    context.send(ApiEvent::Clip {
+        title,
+        blocks,
+        reply,
+    });
+    let inbox_id = answer.await.context("The app didn't store the clip")??;
+    Ok((StatusCode::CREATED, Json(json!({ "id": inbox_id }))))
+}
+
+async fn calendar(
+    State(context): State<ApiContext>,
+    Query(query): Query<TokenQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    if !same_token(&query.token, &context.token) {
+        return Err(ApiError(
+            StatusCode::UNAUTHORIZED,
+            "Missing or invalid token".into(),
+        ));
+    }
+
+    let deleted = context.pending_deletions().await;
+    let mut reminders = context
+        .repositories
+        .reminders
+        .get_pending_reminders()
+        .await?;
+    reminders.retain(|reminder| !deleted.contains(&reminder.document_id));
+    let mut tasks = context.repositories.tasks.get_open_tasks().await?;
+    tasks.retain(|task| !deleted.contains(&task.document_id));
+    let calendar = ical::calendar(&reminders, &tasks, Utc::now().timestamp());
+
+    Ok(([(CONTENT_TYPE, "text/calendar; charset=utf-8")], calendar))
+}
+
+fn not_found(id: i32) -> ApiError {
+    ApiError(
+        StatusCode::NOT_FOUND,
+        format!("No document with the id {}", id),
+    )
+}
+
+fn unsaved_edits(id: i32) -> ApiError {
+    ApiError(
+        StatusCode::CONFLICT,
+        format!("The document {} has unsaved edits in the app", id),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn folder_id(body: &str) -> Option<Option<i32>> {
+        serde_json::from_str::<DocumentChanges>(body)
+            .unwrap()
+            .folder_id
+    }
+
+    #[test]
+    fn folder_id_tells_null_from_missing() {
+        assert_eq!(folder_id(r#"{"title": "Notes"}"#), None);
+        assert_eq!(folder_id(r#"{"folder_id": null}"#), Some(None));
+        assert_eq!(folder_id(r#"{"folder_id": 3}"#), Some(Some(3)));
+    }
+}
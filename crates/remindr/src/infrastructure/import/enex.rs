@@ -0,0 +1,229 @@
+use std::{collections::HashSet, fs::File, io::BufReader, path::Path};
+
+use anyhow::{Context, Error};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use chrono::NaiveDateTime;
+use md5::{Digest, Md5};
+use quick_xml::{Reader, events::Event};
+
+use crate::infrastructure::import::{
+    Import, ImportedDocument, ImportedFile, attachment_block, html::html_to_blocks_with_media,
+};
+
+/// Elements written `<en-todo/>` in notes, which HTML doesn't allow to be empty.
+const EMPTY_ELEMENTS: &[&str] = &["en-todo", "en-media"];
+
+/// Reads an ENEX file, the notebook export of Evernote and Apple Notes.
+///
+/// Each note becomes a document of a folder named after the file, keeping its text,
+/// headings, lists and checklists. Images and other files become attachment blocks. The
+/// creation and edit dates of the notes are kept as the times of their blocks.
+pub fn read_enex(path: &Path) -> Result<Import, Error> {
+    let file = File::open(path).with_context(|| format!("Failed to open {:?}", path))?;
+    let mut import = Import {
+        root_folder: path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| "Evernote".to_string()),
+        ..Import::default()
+    };
+
+    let notes = read_notes(BufReader::new(file)).context("The file isn't an ENEX export")?;
+    if notes.is_empty() {
+        anyhow::bail!("The file has no notes");
+    }
+    for note in notes {
+        import
+            .documents
+            .push(note.into_document(&mut import.warnings));
+    }
+    Ok(import)
+}
+
+/// Note as written in the export, its content being XHTML.
+#[derive(Default)]
+struct Note {
+    title: String,
+    content: String,
+    created: Option<i64>,
+    updated: Option<i64>,
+    resources: Vec<Resource>,
+}
+
+#[derive(Default)]
+struct Resource {
+    /// Base64 encoded, possibly split in lines.
+    data: String,
+    mime: String,
+    file_name: Option<String>,
+}
+
+fn read_notes(reader: impl std::io::BufRead) -> Result<Vec<Note>, Error> {
+    let mut reader = Reader::from_reader(reader);
+    let mut buffer = Vec::new();
+    let mut notes = Vec::new();
+    let mut note: Option<Note> = None;
+    let mut resource: Option<Resource> = None;
+    // Names of the elements the text being read is in
+    let mut path = Vec::<String>::new();
+
+    loop {
+        let text = match reader.read_event_into(&mut buffer)? {
+            Event::Start(element) => {
+                let name = String::from_utf8_lossy(element.name().as_ref()).to_string();
+                match name.as_str() {
+                    "note" => note = Some(Note::default()),
+                    "resource" => resource = Some(Resource::default()),
+                    _ => {}
+                }
+                path.push(name);
+                None
+            }
+            Event::End(_) => {
+                match path.pop().as_deref() {
+                    Some("note") => notes.extend(note.take()),
+                    Some("resource") => {
+                        if let (Some(note), Some(resource)) = (&mut note, resource.take()) {
+                            note.resources.push(resource);
+                        }
+                    }
+                    _ => {}
+                }
+                None
+            }
+            Event::Text(text) => Some(text.unescape()?.to_string()),
+            Event::CData(data) => Some(String::from_utf8_lossy(&data.into_inner()).to_string()),
+            Event::Eof => break,
+            _ => None,
+        };
+        buffer.clear();
+
+        let (Some(text), Some(note)) = (text, &mut note) else {
+            continue;
+        };
+        match (path.last().map(String::as_str), &mut resource) {
+            (Some("data"), Some(resource)) => resource.data.push_str(&text),
+            (Some("mime"), Some(resource)) => resource.mime.push_str(&text),
+            (Some("file-name"), Some(resource)) => {
+                resource.file_name = Some(text.trim().to_string())
+            }
+            (Some("title"), None) => note.title.push_str(&text),
+            (Some("content"), None) => note.content.push_str(&text),
+            (Some("created"), None) => note.created = timestamp(&text),
+            (Some("updated"), None) => note.updated = timestamp(&text),
+            _ => {}
+        }
+    }
+
+    Ok(notes)
+}
+
+impl Note {
+    fn into_document(self, warnings: &mut Vec<String>) -> ImportedDocument {
+        let title = match self.title.trim() {
+            "" => "Untitled".to_string(),
+            title => title.to_string(),
+        };
+
+        // Notes point to their files with the MD5 hash of their data
+        let mut resources = Vec::new();
+        for (index, resource) in self.resources.into_iter().enumerate() {
+            let data = resource
+                .data
+                .chars()
+                .filter(|character| !character.is_whitespace())
+                .collect::<String>();
+            match STANDARD.decode(data) {
+                Ok(data) => {
+                    let hash = format!("{:x}", Md5::digest(&data));
+                    let file_name = resource
+                        .file_name
+                        .unwrap_or_else(|| default_file_name(index, &resource.mime));
+                    resources.push((hash, ImportedFile { file_name, data }));
+                }
+                Err(_) => warnings.push(format!("{}: a file that couldn't be read", title)),
+            }
+        }
+
+        let mut files = Vec::new();
+        let mut shown = HashSet::new();
+        let mut content =
+            html_to_blocks_with_media(&close_empty_elements(&self.content), |element| {
+                let hash = element.attr("hash")?;
+                let (_, file) = resources.iter().find(|(other, _)| other == hash)?;
+                shown.insert(hash.to_string());
+                files.push(file.clone());
+                Some(attachment_block(&file.file_name))
+            });
+
+        // Files attached to the note without being shown in it
+        for (hash, file) in resources {
+            if !shown.contains(&hash) {
+                content.push(attachment_block(&file.file_name));
+                files.push(file);
+            }
+        }
+
+        ImportedDocument {
+            title,
+            content,
+            files,
+            created_at: self.created,
+            updated_at: self.updated,
+            ..ImportedDocument::default()
+        }
+    }
+}
+
+/// e.g. "20261016T143000Z".
+fn timestamp(text: &str) -> Option<i64> {
+    NaiveDateTime::parse_from_str(text.trim(), "%Y%m%dT%H%M%SZ")
+        .ok()
+        .map(|time| time.and_utc().timestamp())
+}
+
+/// Name of a file the export doesn't name, e.g. "image-2.png".
+fn default_file_name(index: usize, mime: &str) -> String {
+    let (kind, subtype) = mime.split_once('/').unwrap_or(("file", ""));
+    let extension = match subtype {
+        "jpeg" => "jpg",
+        "svg+xml" => "svg",
+        subtype => subtype,
+    };
+    if extension.is_empty() {
+        format!("{}-{}", kind, index + 1)
+    } else {
+        format!("{}-{}.{}", kind, index + 1, extension)
+    }
+}
+
+/// Writes the empty elements of a note like `<en-todo/>` as `<en-todo></en-todo>`, so that
+/// they don't hold the text following them once parsed as HTML.
+fn close_empty_elements(content: &str) -> String {
+    let mut result = String::with_capacity(content.len());
+    let mut rest = content;
+
+    while let Some(start) = rest.find('<') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let name = rest[1..]
+            .split(|character: char| character.is_whitespace() || matches!(character, '/' | '>'))
+            .next()
+            .unwrap_or_default();
+        let end = rest.find('>').map_or(rest.len(), |end| end + 1);
+        let tag = &rest[..end];
+
+        match tag.strip_suffix("/>") {
+            Some(opening) if EMPTY_ELEMENTS.contains(&name) => {
+                result.push_str(opening.trim_end());
+                result.push_str(&format!("></{}>", name));
+            }
+            _ => result.push_str(tag),
+        }
+        rest = &rest[end..];
+    }
+
+    result.push_str(rest);
+    result
+}
@@ -0,0 +1,510 @@
+use ego_tree::NodeRef;
+use scraper::{Html, Node, node::Element};
+use serde_json::{Value, json};
+
+use crate::{Utils, infrastructure::import::block};
+
+/// Elements whose content is never shown.
+const HIDDEN_ELEMENTS: &[&str] = &[
+    "head", "script", "style", "noscript", "template", "svg", "iframe", "button", "form",
+];
+
+/// Elements starting a new paragraph in the text around them.
+const BLOCK_ELEMENTS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "body",
+    "dd",
+    "details",
+    "div",
+    "dl",
+    "dt",
+    "figcaption",
+    "figure",
+    "footer",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "html",
+    "li",
+    "main",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "summary",
+    "table",
+    "tr",
+    "ul",
+];
+
+/// Elements embedding a file, handed to the media callback of [`html_to_blocks_with_media`].
+const MEDIA_ELEMENTS: &[&str] = &["img", "en-media"];
+
+/// Converts an HTML fragment, e.g. the selection of a web page, to document blocks.
+///
+/// Headings, paragraphs, lists, checklists, quotes, preformatted text and rules become
+/// the matching blocks, code being kept as text. Inline formatting is dropped, keeping the
+/// text only, links being followed by the address of web pages. Images are dropped.
+pub fn html_to_blocks(html: &str) -> Vec<Value> {
+    html_to_blocks_with_media(html, |_| None)
+}
+
+/// Converts an HTML fragment like [`html_to_blocks`], `media` giving the block of each
+/// image or embedded file, if it can be kept.
+pub fn html_to_blocks_with_media(
+    html: &str,
+    media: impl FnMut(&Element) -> Option<Value>,
+) -> Vec<Value> {
+    let fragment = Html::parse_fragment(html);
+    let mut converter = Converter {
+        blocks: Vec::new(),
+        paragraph: String::new(),
+        media,
+    };
+    converter.visit_children(*fragment.root_element());
+    converter.flush();
+    converter.blocks
+}
+
+struct Converter<F> {
+    blocks: Vec<Value>,
+    /// Text of the paragraph being read, ended by the next block element.
+    paragraph: String,
+    media: F,
+}
+
+impl<F: FnMut(&Element) -> Option<Value>> Converter<F> {
+    fn visit_children(&mut self, node: NodeRef<Node>) {
+        for child in node.children() {
+            self.visit(child);
+        }
+    }
+
+    fn visit(&mut self, node: NodeRef<Node>) {
+        let element = match node.value() {
+            Node::Text(text) => {
+                push_text(&mut self.paragraph, text);
+                return;
+            }
+            Node::Element(element) => element,
+            _ => return,
+        };
+
+        match element.name() {
+            name if HIDDEN_ELEMENTS.contains(&name) => {}
+            "br" => self.paragraph.push('\n'),
+            "a" => {
+                let start = self.paragraph.len();
+                self.visit_children(node);
+                let label = self.paragraph[start..].to_string();
+                push_address(&mut self.paragraph, &label, element);
+            }
+            name if MEDIA_ELEMENTS.contains(&name) => {
+                if let Some(block) = (self.media)(element) {
+                    self.flush();
+                    self.blocks.push(block);
+                }
+                self.visit_children(node);
+            }
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                self.flush();
+                let level = element.name()[1..].parse::<u32>().unwrap_or(1);
+                self.push_block(
+                    "heading",
+                    json!({ "content": text_of(node), "level": level }),
+                );
+            }
+            "ul" | "ol" => {
+                self.flush();
+                self.visit_list(node);
+            }
+            "blockquote" => {
+                self.flush();
+                self.push_block("quote", json!({ "content": text_of(node) }));
+            }
+            "pre" => {
+                self.flush();
+                let text = raw_text(node);
+                self.push_block("text", json!({ "content": text.trim_end_matches('\n') }));
+            }
+            "hr" => {
+                self.flush();
+                self.blocks.push(json!({
+                    "id": Utils::generate_uuid(),
+                    "type": "divider",
+                }));
+            }
+            "td" | "th" => {
+                self.visit_children(node);
+                push_text(&mut self.paragraph, " ");
+            }
+            "div" | "p" if leading_checkbox(node).is_some() => {
+                // Checklist item outside of a list, as Evernote writes them
+                let checked = leading_checkbox(node) == Some(true);
+                self.flush();
+                self.push_block(
+                    "todo",
+                    json!({
+                        "content": text_of(node),
+                        "status": if checked { "done" } else { "todo" },
+                    }),
+                );
+            }
+            name if BLOCK_ELEMENTS.contains(&name) => {
+                self.flush();
+                self.visit_children(node);
+                self.flush();
+            }
+            _ => self.visit_children(node),
+        }
+    }
+
+    /// Adds an item block per `li`, the items of nested lists following their parent.
+    fn visit_list(&mut self, list: NodeRef<Node>) {
+        let ordered = matches!(list.value(), Node::Element(element) if element.name() == "ol");
+        for child in list.children() {
+            let Node::Element(element) = child.value() else {
+                continue;
+            };
+            match element.name() {
+                "li" => {
+                    let content = text_of(child);
+                    match checkbox(child) {
+                        Some(checked) => self.push_block(
+                            "todo",
+                            json!({
+                                "content": content,
+                                "status": if checked { "done" } else { "todo" },
+                            }),
+                        ),
+                        None => self
+                            .push_block("list", json!({ "content": content, "ordered": ordered })),
+                    }
+
+                    for nested in child.descendants().skip(1) {
+                        if is_list(nested) && is_outermost_list(nested, child) {
+                            self.visit_list(nested);
+                        }
+                    }
+                }
+                "ul" | "ol" => self.visit_list(child),
+                _ => {}
+            }
+        }
+    }
+
+    /// Ends the paragraph being read as a text block.
+    fn flush(&mut self) {
+        let content = normalize(&self.paragraph);
+        self.paragraph.clear();
+        if !content.is_empty() {
+            self.push_block("text", json!({ "content": content }));
+        }
+    }
+
+    fn push_block(&mut self, kind: &str, metadata: Value) {
+        if metadata["content"].as_str().is_some_and(str::is_empty) {
+            return;
+        }
+        self.blocks.push(block(kind, metadata));
+    }
+}
+
+/// Whether `list` isn't inside another list of `item`.
+fn is_outermost_list(list: NodeRef<Node>, item: NodeRef<Node>) -> bool {
+    list.ancestors()
+        .take_while(|ancestor| ancestor.id() != item.id())
+        .all(|ancestor| !is_list(ancestor))
+}
+
+fn is_list(node: NodeRef<Node>) -> bool {
+    matches!(node.value(), Node::Element(element) if matches!(element.name(), "ul" | "ol"))
+}
+
+/// State of the checkbox starting a list item, for task lists.
+fn checkbox(item: NodeRef<Node>) -> Option<bool> {
+    item.descendants()
+        .filter_map(|node| match node.value() {
+            Node::Element(element) => Some(element),
+            _ => None,
+        })
+        .take_while(|element| !matches!(element.name(), "ul" | "ol"))
+        .find_map(checkbox_state)
+}
+
+/// State of the checkbox starting a paragraph, before any text or nested block.
+fn leading_checkbox(paragraph: NodeRef<Node>) -> Option<bool> {
+    for node in paragraph.descendants().skip(1) {
+        match node.value() {
+            Node::Text(text) if text.trim().is_empty() => {}
+            Node::Element(element) => {
+                if let Some(checked) = checkbox_state(element) {
+                    return Some(checked);
+                }
+                if BLOCK_ELEMENTS.contains(&element.name()) {
+                    return None;
+                }
+            }
+            Node::Text(_) => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Whether the element is a checked checkbox, if it is a checkbox.
+fn checkbox_state(element: &Element) -> Option<bool> {
+    match element.name() {
+        "input" if element.attr("type") == Some("checkbox") => {
+            Some(element.attr("checked").is_some())
+        }
+        "en-todo" => Some(element.attr("checked") == Some("true")),
+        _ => None,
+    }
+}
+
+/// Text of an element, with a line per paragraph and without the nested lists.
+fn text_of(node: NodeRef<Node>) -> String {
+    fn collect(node: NodeRef<Node>, text: &mut String) {
+        for child in node.children() {
+            match child.value() {
+                Node::Text(content) => push_text(text, content),
+                Node::Element(element) => match element.name() {
+                    name if HIDDEN_ELEMENTS.contains(&name) => {}
+                    "ul" | "ol" => {}
+                    "br" => text.push('\n'),
+                    "a" => {
+                        let start = text.len();
+                        collect(child, text);
+                        let label = text[start..].to_string();
+                        push_address(text, &label, element);
+                    }
+                    name if BLOCK_ELEMENTS.contains(&name) => {
+                        text.push('\n');
+                        collect(child, text);
+                        text.push('\n');
+                    }
+                    _ => collect(child, text),
+                },
+                _ => {}
+            }
+        }
+    }
+
+    let mut text = String::new();
+    collect(node, &mut text);
+    normalize(&text)
+}
+
+/// Text of an element as written, for preformatted text.
+fn raw_text(node: NodeRef<Node>) -> String {
+    node.descendants()
+        .filter_map(|node| match node.value() {
+            Node::Text(text) => Some(&**text),
+            Node::Element(element) if element.name() == "br" => Some("\n"),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Appends the address of a link to a web page after its label, unless the label is the
+/// address, e.g. "Remindr (https://remindr.app)".
+fn push_address(text: &mut String, label: &str, link: &Element) {
+    let Some(address) = link
+        .attr("href")
+        .map(str::trim)
+        .filter(|address| address.starts_with("http://") || address.starts_with("https://"))
+    else {
+        return;
+    };
+    let label = label.trim();
+    if label.is_empty() || without_scheme(label) == without_scheme(address) {
+        return;
+    }
+
+    if !text.ends_with([' ', '\n']) {
+        text.push(' ');
+    }
+    text.push_str(&format!("({})", address));
+}
+
+/// Address without its `http(s)://` scheme and trailing `/`, to compare it with a label
+/// written like "example.com/".
+fn without_scheme(address: &str) -> &str {
+    address
+        .strip_prefix("https://")
+        .or_else(|| address.strip_prefix("http://"))
+        .unwrap_or(address)
+        .trim_end_matches('/')
+}
+
+/// Appends text with its whitespace collapsed, like a browser lays it out.
+fn push_text(text: &mut String, content: &str) {
+    for character in content.chars() {
+        if character.is_whitespace() {
+            if !text.is_empty() && !text.ends_with([' ', '\n']) {
+                text.push(' ');
+            }
+        } else {
+            text.push(character);
+        }
+    }
+}
+
+/// Trims the lines of the text and drops the empty ones.
+fn normalize(text: &str) -> String {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Type and text of each block.
+    fn blocks(html: &str) -> Vec<(String, String)> {
+        html_to_blocks(html)
+            .iter()
+            .map(|block| {
+                (
+                    block["type"].as_str().unwrap_or_default().to_string(),
+                    block["metadata"]["content"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                )
+            })
+            .collect()
+    }
+
+    fn block(kind: &str, content: &str) -> (String, String) {
+        (kind.to_string(), content.to_string())
+    }
+
+    #[test]
+    fn google_docs_selection() {
+        let html = r#"<meta charset="utf-8"><b style="font-weight:normal;" id="docs-internal-guid-1"><h1 dir="ltr"><span>Trip plan</span></h1><p dir="ltr"><span>Book the </span><span style="font-weight:700">train</span><span>.</span></p><ul><li dir="ltr"><p dir="ltr"><span>Passport</span></p></li><li><p><span>Charger</span></p></li></ul></b>"#;
+
+        assert_eq!(
+            blocks(html),
+            vec![
+                block("heading", "Trip plan"),
+                block("text", "Book the train."),
+                block("list", "Passport"),
+                block("list", "Charger"),
+            ]
+        );
+    }
+
+    #[test]
+    fn windows_fragment_with_links() {
+        let html = "<html><body><!--StartFragment--><p>Read&nbsp;the <a href=\"https://example.com/guide\">guide</a>, then <a href=\"https://example.com\">https://example.com</a>.</p><!--EndFragment--></body></html>";
+
+        assert_eq!(
+            blocks(html),
+            vec![block(
+                "text",
+                "Read the guide (https://example.com/guide), then https://example.com."
+            )]
+        );
+    }
+
+    #[test]
+    fn labels_written_as_the_address() {
+        let html = r#"<p><a href="https://example.com/">example.com</a> and <a href="http://a.io/x">a.io/x/</a></p>"#;
+
+        assert_eq!(blocks(html), vec![block("text", "example.com and a.io/x/")]);
+    }
+
+    #[test]
+    fn links_in_list_items() {
+        let html = r#"<ol><li><a href="https://a.io/x">Docs</a></li><li><a href="/relative">Local</a></li></ol>"#;
+
+        assert_eq!(
+            blocks(html),
+            vec![
+                block("list", "Docs (https://a.io/x)"),
+                block("list", "Local"),
+            ]
+        );
+    }
+
+    #[test]
+    fn code_keeps_its_lines() {
+        let html = "<p>Run this:</p><pre><code>cargo build\n  --release\n</code></pre>";
+
+        assert_eq!(
+            blocks(html),
+            vec![
+                block("text", "Run this:"),
+                block("text", "cargo build\n  --release"),
+            ]
+        );
+    }
+
+    #[test]
+    fn github_task_list() {
+        let html = r#"<ul class="contains-task-list"><li class="task-list-item"><input type="checkbox" disabled checked> Ship it</li><li class="task-list-item"><input type="checkbox" disabled> Test it<ul><li>on Linux</li></ul></li></ul>"#;
+
+        let converted = html_to_blocks(html);
+        assert_eq!(
+            blocks(html),
+            vec![
+                block("todo", "Ship it"),
+                block("todo", "Test it"),
+                block("list", "on Linux"),
+            ]
+        );
+        assert_eq!(converted[0]["metadata"]["status"], "done");
+        assert_eq!(converted[1]["metadata"]["status"], "todo");
+    }
+
+    #[test]
+    fn hidden_elements_and_nested_divs() {
+        let html = "<div><style>p { color: red }</style><div>First</div><div><span>Second</span><br>line</div><script>alert(1)</script></div>";
+
+        assert_eq!(
+            blocks(html),
+            vec![block("text", "First"), block("text", "Second\nline")]
+        );
+    }
+
+    #[test]
+    fn quotes_and_rules() {
+        let html = "<blockquote><p>Be kind.</p><p>Always.</p></blockquote><hr><p>After</p>";
+
+        assert_eq!(
+            blocks(html),
+            vec![
+                block("quote", "Be kind.\nAlways."),
+                block("divider", ""),
+                block("text", "After"),
+            ]
+        );
+    }
+
+    #[test]
+    fn evernote_checklist() {
+        let html = r#"<div><en-todo checked="true"></en-todo>Milk</div><div><en-todo checked="false"></en-todo>Eggs</div>"#;
+
+        let converted = html_to_blocks(html);
+        assert_eq!(
+            blocks(html),
+            vec![block("todo", "Milk"), block("todo", "Eggs")]
+        );
+        assert_eq!(converted[0]["metadata"]["status"], "done");
+        assert_eq!(converted[1]["metadata"]["status"], "todo");
+    }
+}
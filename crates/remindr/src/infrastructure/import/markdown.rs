@@ -0,0 +1,323 @@
+use serde_json::{Value, json};
+
+use crate::{
+    Utils,
+    infrastructure::import::{block, link_block, text_block},
+};
+
+/// Blocks of a markdown text, with what couldn't be converted.
+#[derive(Debug, Default)]
+pub struct MarkdownConversion {
+    pub blocks: Vec<Value>,
+    /// e.g. "an image (map.png)" or "a table, kept as text".
+    pub unsupported: Vec<String>,
+}
+
+/// Converts markdown to document blocks.
+///
+/// Headings, paragraphs, lists, checklists, quotes, code and rules become the matching
+/// blocks, code being kept as text. Inline formatting is dropped, links keeping their text
+/// followed by the address of web pages. Wiki links become link blocks, following the block
+/// they are in unless they are alone on their line. Images are left out and tables kept as
+/// text, both being reported as unsupported.
+pub fn markdown_to_blocks(markdown: &str) -> MarkdownConversion {
+    let mut converter = Converter::default();
+    let mut lines = markdown.lines();
+
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim();
+
+        if let Some(fence) = code_fence(trimmed) {
+            converter.flush();
+            let code = lines
+                .by_ref()
+                .take_while(|line| !line.trim_start().starts_with(fence))
+                .collect::<Vec<_>>()
+                .join("\n");
+            if !code.trim().is_empty() {
+                converter.conversion.blocks.push(text_block(code));
+            }
+        } else if trimmed.is_empty() {
+            converter.flush();
+        } else if let Some((level, title)) = heading(trimmed) {
+            converter.flush();
+            let content = converter.inline(title);
+            converter.push("heading", json!({ "content": content, "level": level }));
+        } else if is_rule(trimmed) {
+            converter.flush();
+            converter.conversion.blocks.push(json!({
+                "id": Utils::generate_uuid(),
+                "type": "divider",
+            }));
+        } else if let Some(quoted) = trimmed.strip_prefix('>') {
+            if !matches!(converter.pending, Pending::Quote(_)) {
+                converter.flush();
+                converter.pending = Pending::Quote(Vec::new());
+            }
+            let quoted = converter.inline(quoted.trim());
+            if let Pending::Quote(quote) = &mut converter.pending {
+                quote.push(quoted);
+            }
+        } else if let Some((kind, text)) = list_item(trimmed) {
+            converter.flush();
+            let content = converter.inline(text);
+            let metadata = match kind {
+                ListItem::Bullet => json!({ "content": content, "ordered": false }),
+                ListItem::Numbered => json!({ "content": content, "ordered": true }),
+                ListItem::Task(done) => json!({
+                    "content": content,
+                    "status": if done { "done" } else { "todo" },
+                }),
+            };
+            let kind = if matches!(kind, ListItem::Task(_)) {
+                "todo"
+            } else {
+                "list"
+            };
+            converter.push(kind, metadata);
+        } else if trimmed.starts_with('|') {
+            if !matches!(converter.pending, Pending::Table(_)) {
+                converter.flush();
+                converter.pending = Pending::Table(Vec::new());
+            }
+            if !is_table_separator(trimmed) {
+                let row = table_row(trimmed)
+                    .into_iter()
+                    .map(|cell| converter.inline(cell))
+                    .collect::<Vec<_>>()
+                    .join(" · ");
+                if let Pending::Table(rows) = &mut converter.pending {
+                    rows.push(row);
+                }
+            }
+        } else if let Some(((target, _), _)) =
+            wiki_link(trimmed).filter(|(_, length)| *length == trimmed.len())
+        {
+            converter.flush();
+            converter.conversion.blocks.push(link_block(target));
+        } else {
+            if !matches!(converter.pending, Pending::Paragraph(_)) {
+                converter.flush();
+                converter.pending = Pending::Paragraph(Vec::new());
+            }
+            let text = converter.inline(trimmed);
+            if let Pending::Paragraph(paragraph) = &mut converter.pending
+                && !text.is_empty()
+            {
+                paragraph.push(text);
+            }
+        }
+    }
+
+    converter.flush();
+    converter.conversion
+}
+
+/// Lines read until the block they form ends.
+#[derive(Default)]
+enum Pending {
+    #[default]
+    None,
+    Paragraph(Vec<String>),
+    Quote(Vec<String>),
+    Table(Vec<String>),
+}
+
+#[derive(Default)]
+struct Converter {
+    conversion: MarkdownConversion,
+    pending: Pending,
+    /// Targets of the wiki links of the pending block, linked after it.
+    links: Vec<String>,
+}
+
+impl Converter {
+    fn flush(&mut self) {
+        match std::mem::take(&mut self.pending) {
+            Pending::None => {}
+            Pending::Paragraph(lines) => {
+                self.push("text", json!({ "content": lines.join("\n") }));
+            }
+            Pending::Quote(lines) => {
+                self.push("quote", json!({ "content": lines.join("\n") }));
+            }
+            Pending::Table(rows) => {
+                self.push("text", json!({ "content": rows.join("\n") }));
+                self.conversion
+                    .unsupported
+                    .push("a table, kept as text".to_string());
+            }
+        }
+    }
+
+    fn push(&mut self, kind: &str, metadata: Value) {
+        if !metadata["content"]
+            .as_str()
+            .is_some_and(|content| content.trim().is_empty())
+        {
+            self.conversion.blocks.push(block(kind, metadata));
+        }
+
+        for target in std::mem::take(&mut self.links) {
+            self.conversion.blocks.push(link_block(&target));
+        }
+    }
+
+    /// Text of a line without its inline formatting. Images are dropped and reported.
+    fn inline(&mut self, text: &str) -> String {
+        let mut result = String::with_capacity(text.len());
+        let mut rest = text;
+
+        while let Some(start) = rest.find(['[', '!', '*', '_', '~', '`']) {
+            result.push_str(&rest[..start]);
+            rest = &rest[start..];
+
+            if let Some(((target, alias), length)) = wiki_link(rest) {
+                // Shown by its alias when it has one
+                result.push_str(alias);
+                if !self.links.iter().any(|link| link == target) {
+                    self.links.push(target.to_string());
+                }
+                rest = &rest[length..];
+            } else if let Some(((target, _), length)) = rest.strip_prefix('!').and_then(wiki_link) {
+                self.conversion
+                    .unsupported
+                    .push(format!("an embedded file ({})", target));
+                rest = &rest[length + 1..];
+            } else if let Some((alt, source, length)) = rest.strip_prefix('!').and_then(link) {
+                let name = if alt.is_empty() { source } else { alt };
+                self.conversion
+                    .unsupported
+                    .push(format!("an image ({})", name));
+                rest = &rest[length + 1..];
+            } else if let Some((label, address, length)) = link(rest) {
+                result.push_str(label);
+                if address.starts_with("http") && label != address {
+                    result.push_str(&format!(" ({})", address));
+                }
+                rest = &rest[length..];
+            } else if let Some(marker) = ["**", "__", "~~", "`"]
+                .into_iter()
+                .find(|marker| rest.starts_with(marker))
+            {
+                rest = &rest[marker.len()..];
+            } else {
+                let character = rest.chars().next().unwrap_or_default();
+                result.push(character);
+                rest = &rest[character.len_utf8()..];
+            }
+        }
+
+        result.push_str(rest);
+        result.trim().to_string()
+    }
+}
+
+/// Target and alias of a `[[target|alias]]` wiki link starting the text, with its length.
+/// The target is the name of the linked note, without its folders or heading.
+fn wiki_link(text: &str) -> Option<((&str, &str), usize)> {
+    let inner = text.strip_prefix("[[")?;
+    let end = inner.find("]]")?;
+    let (target, alias) = inner[..end].split_once('|').unwrap_or((&inner[..end], ""));
+
+    let target = target.split(['#', '^']).next().unwrap_or_default();
+    let target = target.rsplit('/').next().unwrap_or_default().trim();
+    let target = target.strip_suffix(".md").unwrap_or(target);
+    if target.is_empty() {
+        return None;
+    }
+    let alias = if alias.trim().is_empty() {
+        target
+    } else {
+        alias.trim()
+    };
+    Some(((target, alias), end + 4))
+}
+
+/// Label and address of a `[label](address)` link starting the text, with its length.
+fn link(text: &str) -> Option<(&str, &str, usize)> {
+    let label_end = text.strip_prefix('[')?.find("](")? + 1;
+    let address_start = label_end + 2;
+    let address_end = address_start + text[address_start..].find(')')?;
+
+    let address = text[address_start..address_end]
+        .split_whitespace()
+        .next()
+        .unwrap_or_default();
+    Some((&text[1..label_end], address, address_end + 1))
+}
+
+/// Marker closing the code block opened by the line, if it opens one.
+fn code_fence(line: &str) -> Option<&'static str> {
+    ["```", "~~~"]
+        .into_iter()
+        .find(|fence| line.starts_with(fence))
+}
+
+fn heading(line: &str) -> Option<(u32, &str)> {
+    let level = line
+        .chars()
+        .take_while(|character| *character == '#')
+        .count();
+    let title = line[level..].strip_prefix(' ')?;
+    (1..=6)
+        .contains(&level)
+        .then(|| (level as u32, title.trim_end_matches('#').trim()))
+}
+
+fn is_rule(line: &str) -> bool {
+    let line = line.replace(' ', "");
+    line.len() >= 3
+        && ['-', '*', '_']
+            .into_iter()
+            .any(|marker| line.chars().all(|character| character == marker))
+}
+
+#[derive(Clone, Copy)]
+enum ListItem {
+    Bullet,
+    Numbered,
+    /// Checklist item, done when true.
+    Task(bool),
+}
+
+/// Kind and text of a list item.
+fn list_item(line: &str) -> Option<(ListItem, &str)> {
+    if let Some(item) = ["- ", "* ", "+ "]
+        .into_iter()
+        .find_map(|marker| line.strip_prefix(marker))
+    {
+        let task = [("[ ] ", false), ("[x] ", true), ("[X] ", true)]
+            .into_iter()
+            .find_map(|(checkbox, done)| item.strip_prefix(checkbox).map(|text| (done, text)));
+        return Some(match task {
+            Some((done, text)) => (ListItem::Task(done), text),
+            None => (ListItem::Bullet, item),
+        });
+    }
+
+    let digits = line
+        .chars()
+        .take_while(|character| character.is_ascii_digit())
+        .count();
+    if digits == 0 {
+        return None;
+    }
+    let item = line[digits..]
+        .strip_prefix(". ")
+        .or_else(|| line[digits..].strip_prefix(") "))?;
+    Some((ListItem::Numbered, item))
+}
+
+fn is_table_separator(line: &str) -> bool {
+    line.chars()
+        .all(|character| matches!(character, '|' | '-' | ':' | ' '))
+}
+
+fn table_row(line: &str) -> Vec<&str> {
+    line.trim_matches('|')
+        .split('|')
+        .map(str::trim)
+        .filter(|cell| !cell.is_empty())
+        .collect()
+}
@@ -0,0 +1,231 @@
+pub mod enex;
+pub mod html;
+pub mod markdown;
+pub mod notion;
+pub mod obsidian;
+pub mod opml;
+
+use std::collections::HashMap;
+
+use anyhow::Error;
+use serde_json::{Value, json};
+
+use crate::{
+    Utils,
+    domain::{
+        database::document::DocumentModel,
+        ports::{DocumentRepositoryPort, FolderRepositoryPort},
+    },
+    infrastructure::repositories::attachment_repository::AttachmentRepository,
+};
+
+/// Document read from another app, before it is stored.
+#[derive(Debug, Clone, Default)]
+pub struct ImportedDocument {
+    pub title: String,
+    pub content: Vec<Value>,
+    /// Names of the folders the document is in, from the root folder of the import.
+    pub folder_path: Vec<String>,
+    /// Files of the attachment blocks made with [`attachment_block`], in the same order.
+    pub files: Vec<ImportedFile>,
+    /// Creation and last edit times in the other app, in seconds since the epoch.
+    pub created_at: Option<i64>,
+    pub updated_at: Option<i64>,
+}
+
+/// File attached to an imported document.
+#[derive(Debug, Clone)]
+pub struct ImportedFile {
+    pub file_name: String,
+    pub data: Vec<u8>,
+}
+
+/// Documents read from another app, with what couldn't be converted.
+#[derive(Debug, Default)]
+pub struct Import {
+    /// Folder created to hold the imported documents.
+    pub root_folder: String,
+    pub documents: Vec<ImportedDocument>,
+    /// Paths of the folders to create even if no document is in them.
+    pub folders: Vec<Vec<String>>,
+    /// Content left out or only kept as text, e.g. "Trips.md: an image (map.png)".
+    pub warnings: Vec<String>,
+}
+
+/// Outcome of a stored import, shown to the user.
+#[derive(Debug, Default, Clone)]
+pub struct ImportReport {
+    pub documents: usize,
+    pub folders: usize,
+    pub warnings: Vec<String>,
+}
+
+/// Stores the documents of an import under its root folder, creating the folders they are
+/// in.
+///
+/// Link blocks point to the imported document with their title, matched regardless of case.
+/// The ones to documents that weren't imported are kept as text. The times of the documents
+/// become the times of their blocks, as if they had been written then.
+pub async fn save_import(
+    import: Import,
+    documents: &dyn DocumentRepositoryPort,
+    folders: &dyn FolderRepositoryPort,
+    attachments: &AttachmentRepository,
+) -> Result<ImportReport, Error> {
+    let mut report = ImportReport {
+        warnings: import.warnings,
+        ..ImportReport::default()
+    };
+
+    let root_id = folders.insert_folder(import.root_folder, None).await?;
+    report.folders += 1;
+    let mut folder_ids = HashMap::<Vec<String>, i32>::new();
+    let mut stored = Vec::with_capacity(import.documents.len());
+
+    for path in &import.folders {
+        create_folders(path, root_id, &mut folder_ids, folders, &mut report).await?;
+    }
+
+    for document in import.documents {
+        let parent_id = create_folders(
+            &document.folder_path,
+            root_id,
+            &mut folder_ids,
+            folders,
+            &mut report,
+        )
+        .await?;
+
+        let mut content = document.content;
+        let mut files = document.files.into_iter();
+        for block in content.iter_mut() {
+            if block["type"] == "attachment"
+                && block["metadata"]["attachment_id"] == 0
+                && let Some(file) = files.next()
+            {
+                let attachment = attachments
+                    .insert_attachment(file.file_name, file.data)
+                    .await?;
+                block["metadata"]["attachment_id"] = json!(attachment.id);
+                block["metadata"]["size"] = json!(attachment.size);
+            }
+            if let Some(metadata) = block.get_mut("metadata").and_then(Value::as_object_mut) {
+                if let Some(created_at) = document.created_at {
+                    metadata.insert("created_at".to_string(), created_at.into());
+                }
+                if let Some(updated_at) = document.updated_at.or(document.created_at) {
+                    metadata.insert("updated_at".to_string(), updated_at.into());
+                }
+            }
+        }
+
+        let mut model = DocumentModel {
+            id: 0,
+            title: document.title,
+            content: Value::Array(content),
+            folder_id: Some(parent_id),
+        };
+        model.id = documents.insert_document(model.clone()).await?;
+        report.documents += 1;
+        stored.push(model);
+    }
+
+    // Links can only point to the documents once they all have an id
+    let ids = stored
+        .iter()
+        .map(|document| (document.title.to_lowercase(), document.id))
+        .collect::<HashMap<_, _>>();
+    for mut document in stored {
+        if resolve_links(&mut document, &ids, &mut report.warnings) {
+            documents.update_document(document).await?;
+        }
+    }
+
+    Ok(report)
+}
+
+/// Creates the folders of the path missing from `folder_ids` and returns the id of the last
+/// one.
+async fn create_folders(
+    folder_path: &[String],
+    root_id: i32,
+    folder_ids: &mut HashMap<Vec<String>, i32>,
+    folders: &dyn FolderRepositoryPort,
+    report: &mut ImportReport,
+) -> Result<i32, Error> {
+    let mut parent_id = root_id;
+    for depth in 1..=folder_path.len() {
+        let path = folder_path[..depth].to_vec();
+        parent_id = match folder_ids.get(&path) {
+            Some(id) => *id,
+            None => {
+                let id = folders
+                    .insert_folder(path[depth - 1].clone(), Some(parent_id))
+                    .await?;
+                report.folders += 1;
+                folder_ids.insert(path, id);
+                id
+            }
+        };
+    }
+    Ok(parent_id)
+}
+
+/// Points the link blocks of an imported document to the documents they name. Returns
+/// whether the document has links.
+fn resolve_links(
+    document: &mut DocumentModel,
+    ids: &HashMap<String, i32>,
+    warnings: &mut Vec<String>,
+) -> bool {
+    let Some(blocks) = document.content.as_array_mut() else {
+        return false;
+    };
+
+    let mut linked = false;
+    for block in blocks.iter_mut().filter(|block| block["type"] == "link") {
+        linked = true;
+        let title = block["metadata"]["title"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+        match ids.get(&title.to_lowercase()) {
+            Some(id) => block["metadata"]["document_id"] = json!(id),
+            None => {
+                warnings.push(format!(
+                    "{}: a link to \"{}\", which wasn't imported",
+                    document.title, title
+                ));
+                *block = text_block(title);
+            }
+        }
+    }
+    linked
+}
+
+/// Block of the given type, with a new id.
+pub fn block(kind: &str, metadata: Value) -> Value {
+    json!({
+        "id": Utils::generate_uuid(),
+        "type": kind,
+        "metadata": metadata,
+    })
+}
+
+/// Link to the document titled `title`, resolved once the import is stored.
+pub fn link_block(title: &str) -> Value {
+    block("link", json!({ "document_id": 0, "title": title }))
+}
+
+/// Attachment block of the next file of the imported document, stored with the import.
+pub fn attachment_block(file_name: &str) -> Value {
+    block(
+        "attachment",
+        json!({ "attachment_id": 0, "file_name": file_name, "size": 0 }),
+    )
+}
+
+/// Text block, the most common one.
+pub fn text_block(content: impl Into<String>) -> Value {
+    block("text", json!({ "content": content.into() }))
+}
@@ -0,0 +1,178 @@
+use std::{
+    fs::File,
+    io::{Cursor, Read, Seek},
+    path::Path,
+};
+
+use anyhow::{Context, Error};
+use serde_json::json;
+use zip::ZipArchive;
+
+use crate::infrastructure::import::{
+    Import, ImportedDocument, block, markdown::markdown_to_blocks,
+};
+
+/// Folder the pages of a Notion export are imported in.
+const ROOT_FOLDER: &str = "Notion";
+
+/// Length of the id Notion appends to the names of pages, in hexadecimal digits.
+const PAGE_ID_LENGTH: usize = 32;
+
+/// Reads the "Markdown & CSV" export of a Notion workspace, a zip file possibly holding
+/// other zip files.
+///
+/// Pages become documents and the folders of subpages become folders, Notion keeping the
+/// subpages of a page in a folder named after it. Each row of a database becomes a list
+/// item of a document named after the database.
+pub fn read_notion_export(path: &Path) -> Result<Import, Error> {
+    let file = File::open(path).with_context(|| format!("Failed to open {:?}", path))?;
+    let mut import = Import {
+        root_folder: ROOT_FOLDER.to_string(),
+        ..Import::default()
+    };
+
+    read_archive(file, &mut import).context("The file isn't a Notion export")?;
+    import
+        .documents
+        .sort_by(|a, b| (&a.folder_path, &a.title).cmp(&(&b.folder_path, &b.title)));
+    Ok(import)
+}
+
+fn read_archive(reader: impl Read + Seek, import: &mut Import) -> Result<(), Error> {
+    let mut archive = ZipArchive::new(reader)?;
+
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index)?;
+        if entry.is_dir() {
+            continue;
+        }
+        let name = entry.name().to_string();
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data)?;
+
+        let (directories, file_name) = match name.rsplit_once('/') {
+            Some((directories, file_name)) => (directories, file_name),
+            None => ("", name.as_str()),
+        };
+        let folder_path = directories
+            .split('/')
+            .map(page_name)
+            .filter(|name| !name.is_empty())
+            .collect::<Vec<_>>();
+        let (stem, extension) = file_name.rsplit_once('.').unwrap_or((file_name, ""));
+
+        match extension.to_lowercase().as_str() {
+            "zip" => read_archive(Cursor::new(data), import)?,
+            "md" => {
+                let text = String::from_utf8_lossy(&data);
+                import.documents.push(read_page(
+                    &text,
+                    page_name(stem),
+                    folder_path,
+                    &mut import.warnings,
+                ));
+            }
+            // Notion writes every database twice, the `_all` file also having the rows
+            // hidden by the filters of the view
+            "csv" if !stem.ends_with("_all") => {
+                match read_database(&data, page_name(stem), folder_path) {
+                    Ok(document) => import.documents.push(document),
+                    Err(e) => import
+                        .warnings
+                        .push(format!("{}: the database couldn't be read ({})", name, e)),
+                }
+            }
+            "csv" => {}
+            _ => import
+                .warnings
+                .push(format!("{}: the file wasn't imported", name)),
+        }
+    }
+
+    Ok(())
+}
+
+/// Document of a markdown page, titled by its first heading like Notion writes it.
+fn read_page(
+    text: &str,
+    file_title: String,
+    folder_path: Vec<String>,
+    warnings: &mut Vec<String>,
+) -> ImportedDocument {
+    let (title, body) = match text.trim_start().split_once('\n') {
+        Some((first, body)) if first.starts_with("# ") => (first[2..].trim().to_string(), body),
+        None if text.trim_start().starts_with("# ") => {
+            (text.trim_start()[2..].trim().to_string(), "")
+        }
+        _ => (file_title, text),
+    };
+
+    let conversion = markdown_to_blocks(body);
+    warnings.extend(
+        conversion
+            .unsupported
+            .into_iter()
+            .map(|item| format!("{}: {}", title, item)),
+    );
+
+    ImportedDocument {
+        title,
+        content: conversion.blocks,
+        folder_path,
+        ..ImportedDocument::default()
+    }
+}
+
+/// Document listing the rows of a database, e.g. "Buy milk · Status: Done · Due: May 2".
+fn read_database(
+    data: &[u8],
+    title: String,
+    folder_path: Vec<String>,
+) -> Result<ImportedDocument, Error> {
+    let mut reader = csv::Reader::from_reader(data);
+    let headers = reader.headers()?.clone();
+
+    let mut content = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        let text = record
+            .iter()
+            .zip(headers.iter())
+            .enumerate()
+            .filter(|(_, (value, _))| !value.trim().is_empty())
+            .map(|(column, (value, header))| {
+                if column == 0 {
+                    value.trim().to_string()
+                } else {
+                    format!("{}: {}", header.trim(), value.trim())
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" · ");
+        if !text.is_empty() {
+            content.push(block("list", json!({ "content": text, "ordered": false })));
+        }
+    }
+
+    Ok(ImportedDocument {
+        title,
+        content,
+        folder_path,
+        ..ImportedDocument::default()
+    })
+}
+
+/// Name of a page without the id Notion appends, e.g. "Trips" for
+/// "Trips 0123456789abcdef0123456789abcdef".
+fn page_name(name: &str) -> String {
+    let name = name.trim();
+    match name.rsplit_once(' ') {
+        Some((page, id))
+            if id.len() == PAGE_ID_LENGTH
+                && id.chars().all(|character| character.is_ascii_hexdigit()) =>
+        {
+            page.trim().to_string()
+        }
+        _ => name.to_string(),
+    }
+}
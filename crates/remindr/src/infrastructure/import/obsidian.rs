@@ -0,0 +1,93 @@
+use std::{fs, path::Path};
+
+use anyhow::{Context, Error};
+
+use crate::infrastructure::import::{Import, ImportedDocument, markdown::markdown_to_blocks};
+
+/// Reads a folder of markdown files, like an Obsidian vault.
+///
+/// Each note becomes a document titled by its file name and each subfolder a folder, the
+/// imported folder giving its name to the root one. Hidden folders, like `.obsidian`, are
+/// skipped. Wiki links become links to the notes they name.
+pub fn read_markdown_folder(path: &Path) -> Result<Import, Error> {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "Markdown".to_string());
+    let mut import = Import {
+        root_folder: name,
+        ..Import::default()
+    };
+
+    read_folder(path, &[], &mut import).with_context(|| format!("Failed to read {:?}", path))?;
+    if import.documents.is_empty() {
+        anyhow::bail!("The folder has no markdown files");
+    }
+    import
+        .documents
+        .sort_by(|a, b| (&a.folder_path, &a.title).cmp(&(&b.folder_path, &b.title)));
+    Ok(import)
+}
+
+fn read_folder(directory: &Path, folder_path: &[String], import: &mut Import) -> Result<(), Error> {
+    for entry in fs::read_dir(directory)? {
+        let entry = entry?;
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') {
+            continue;
+        }
+
+        if entry.file_type()?.is_dir() {
+            let mut subfolder = folder_path.to_vec();
+            subfolder.push(name);
+            read_folder(&path, &subfolder, import)?;
+            continue;
+        }
+
+        let relative = folder_path
+            .iter()
+            .chain([&name])
+            .cloned()
+            .collect::<Vec<_>>()
+            .join("/");
+        match name.rsplit_once('.') {
+            Some((title, extension)) if extension.eq_ignore_ascii_case("md") => {
+                let text = fs::read_to_string(&path)
+                    .with_context(|| format!("Failed to read {}", relative))?;
+                let conversion = markdown_to_blocks(without_front_matter(&text));
+                import.warnings.extend(
+                    conversion
+                        .unsupported
+                        .into_iter()
+                        .map(|item| format!("{}: {}", relative, item)),
+                );
+                import.documents.push(ImportedDocument {
+                    title: title.to_string(),
+                    content: conversion.blocks,
+                    folder_path: folder_path.to_vec(),
+                    ..ImportedDocument::default()
+                });
+            }
+            _ => import
+                .warnings
+                .push(format!("{}: the file wasn't imported", relative)),
+        }
+    }
+
+    Ok(())
+}
+
+/// Text of a note without the YAML properties starting it.
+fn without_front_matter(text: &str) -> &str {
+    let Some(rest) = text
+        .strip_prefix("---\n")
+        .or_else(|| text.strip_prefix("---\r\n"))
+    else {
+        return text;
+    };
+    match rest.find("\n---") {
+        Some(end) => rest[end + 4..].trim_start_matches(['\r', '\n']),
+        None => text,
+    }
+}
@@ -0,0 +1,157 @@
+use std::{fs::File, io::BufReader, path::Path};
+
+use anyhow::{Context, Error};
+use quick_xml::{
+    Reader,
+    events::{BytesStart, Event},
+};
+use serde_json::{Value, json};
+
+use crate::infrastructure::import::{Import, ImportedDocument, block, text_block};
+
+/// Outline of an OPML file, with the outlines under it.
+#[derive(Default)]
+struct Outline {
+    text: String,
+    /// `folder` or `document` in the outlines exported by Remindr.
+    kind: Option<String>,
+    children: Vec<Outline>,
+}
+
+/// Reads an OPML outline, exported by Remindr or by an outliner.
+///
+/// Outlines typed `folder` or `document` become what they were exported from. In other
+/// outlines, the ones with nested outlines under them become folders and the others
+/// documents. The outlines under a document become its text, the nested ones list items.
+pub fn read_opml(path: &Path) -> Result<Import, Error> {
+    let file = File::open(path).with_context(|| format!("Failed to open {:?}", path))?;
+    let (title, outlines) =
+        read_outlines(BufReader::new(file)).context("The file isn't an OPML outline")?;
+    if outlines.is_empty() {
+        anyhow::bail!("The outline is empty");
+    }
+
+    let mut import = Import {
+        root_folder: title
+            .filter(|title| !title.trim().is_empty())
+            .or_else(|| {
+                path.file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+            })
+            .unwrap_or_else(|| "Outline".to_string()),
+        ..Import::default()
+    };
+    for outline in outlines {
+        add_outline(outline, &[], &mut import);
+    }
+    Ok(import)
+}
+
+fn add_outline(outline: Outline, folder_path: &[String], import: &mut Import) {
+    let is_folder = match outline.kind.as_deref() {
+        Some("folder") => true,
+        Some("document") => false,
+        _ => outline
+            .children
+            .iter()
+            .any(|child| !child.children.is_empty()),
+    };
+    let name = match outline.text.trim() {
+        "" => "Untitled".to_string(),
+        text => text.to_string(),
+    };
+
+    if is_folder {
+        let mut path = folder_path.to_vec();
+        path.push(name);
+        import.folders.push(path.clone());
+        for child in outline.children {
+            add_outline(child, &path, import);
+        }
+        return;
+    }
+
+    let mut content = Vec::new();
+    for child in outline.children {
+        if !child.text.trim().is_empty() {
+            content.push(text_block(child.text.trim()));
+        }
+        add_items(child.children, &mut content);
+    }
+    import.documents.push(ImportedDocument {
+        title: name,
+        content,
+        folder_path: folder_path.to_vec(),
+        ..ImportedDocument::default()
+    });
+}
+
+/// Adds the outlines as list items, each followed by the items nested under it.
+fn add_items(outlines: Vec<Outline>, content: &mut Vec<Value>) {
+    for outline in outlines {
+        if outline.text.trim().is_empty() {
+            add_items(outline.children, content);
+            continue;
+        }
+        content.push(block(
+            "list",
+            json!({ "content": outline.text.trim(), "ordered": false }),
+        ));
+        add_items(outline.children, content);
+    }
+}
+
+/// Title of the outline and its top-level outlines.
+fn read_outlines(reader: impl std::io::BufRead) -> Result<(Option<String>, Vec<Outline>), Error> {
+    let mut reader = Reader::from_reader(reader);
+    let mut buffer = Vec::new();
+    let mut title = None;
+    let mut in_title = false;
+    // Outlines being read, the top-level ones first
+    let mut stack = vec![Outline::default()];
+
+    loop {
+        match reader.read_event_into(&mut buffer)? {
+            Event::Start(element) if element.name().as_ref() == b"outline" => {
+                stack.push(outline(&element)?);
+            }
+            Event::Empty(element) if element.name().as_ref() == b"outline" => {
+                let outline = outline(&element)?;
+                if let Some(parent) = stack.last_mut() {
+                    parent.children.push(outline);
+                }
+            }
+            Event::End(element) if element.name().as_ref() == b"outline" => {
+                if stack.len() > 1
+                    && let Some(outline) = stack.pop()
+                    && let Some(parent) = stack.last_mut()
+                {
+                    parent.children.push(outline);
+                }
+            }
+            Event::Start(element) if element.name().as_ref() == b"title" => in_title = true,
+            Event::End(element) if element.name().as_ref() == b"title" => in_title = false,
+            Event::Text(text) if in_title => title = Some(text.unescape()?.trim().to_string()),
+            Event::Eof => break,
+            _ => {}
+        }
+        buffer.clear();
+    }
+
+    let outlines = stack.into_iter().next().unwrap_or_default().children;
+    Ok((title, outlines))
+}
+
+fn outline(element: &BytesStart) -> Result<Outline, Error> {
+    let mut outline = Outline::default();
+    for attribute in element.attributes() {
+        let attribute = attribute?;
+        let value = attribute.unescape_value()?.to_string();
+        match attribute.key.as_ref() {
+            b"text" => outline.text = value,
+            b"type" => outline.kind = Some(value),
+            _ => {}
+        }
+    }
+    Ok(outline)
+}
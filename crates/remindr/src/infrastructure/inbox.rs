@@ -0,0 +1,73 @@
+//! The Inbox document, a root document that quick captures, web clips and notes from the
+//! command line are appended to.
+
+use anyhow::Error;
+use serde_json::{Value, json};
+
+use crate::{
+    Utils,
+    domain::{database::document::DocumentModel, ports::DocumentRepositoryPort},
+};
+
+/// Title of the root document captures are appended to.
+pub const INBOX_TITLE: &str = "Inbox";
+
+/// A text block holding `text`.
+pub fn text_block(text: String) -> Value {
+    json!({
+        "id": Utils::generate_uuid(),
+        "type": "text",
+        "metadata": { "content": text },
+    })
+}
+
+/// The inbox document, a root document with the inbox title.
+pub async fn find_inbox(
+    documents: &dyn DocumentRepositoryPort,
+) -> Result<Option<DocumentModel>, Error> {
+    Ok(documents
+        .get_documents()
+        .await?
+        .into_iter()
+        .find(|document| document.folder_id.is_none() && document.title == INBOX_TITLE))
+}
+
+/// Appends `text` as a new block of the inbox document and returns the inbox id.
+pub async fn append_to_inbox(
+    documents: &dyn DocumentRepositoryPort,
+    text: String,
+) -> Result<i32, Error> {
+    append_blocks_to_inbox(documents, vec![text_block(text)]).await
+}
+
+/// Appends blocks at the end of the inbox document in the storage, created when missing,
+/// and returns the inbox id.
+///
+/// The app appends through the editor while the inbox is loaded, see
+/// `quick_capture::capture_blocks`, as its unsaved edits would overwrite the blocks.
+pub async fn append_blocks_to_inbox(
+    documents: &dyn DocumentRepositoryPort,
+    blocks: Vec<Value>,
+) -> Result<i32, Error> {
+    match find_inbox(documents).await? {
+        Some(mut inbox) => {
+            match inbox.content.as_array_mut() {
+                Some(content) => content.extend(blocks),
+                None => inbox.content = Value::Array(blocks),
+            }
+            let id = inbox.id;
+            documents.update_document(inbox).await?;
+            Ok(id)
+        }
+        None => {
+            documents
+                .insert_document(DocumentModel {
+                    id: 0,
+                    title: INBOX_TITLE.to_string(),
+                    content: Value::Array(blocks),
+                    folder_id: None,
+                })
+                .await
+        }
+    }
+}
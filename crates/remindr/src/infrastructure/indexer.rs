@@ -0,0 +1,190 @@
+use anyhow::Error;
+use serde_json::Value;
+use sqlx::{SqliteConnection, SqlitePool, query};
+use tokio::sync::{
+    mpsc::{UnboundedSender, unbounded_channel},
+    oneshot,
+};
+
+use crate::{
+    domain::database::{
+        document::{linked_documents, plain_text},
+        task::TaskModel,
+    },
+    infrastructure::repositories::{retry_busy, task_repository::index_tasks},
+};
+
+/// Change to a document that the derived tables have to follow.
+#[derive(Debug, Clone)]
+pub enum IndexEvent {
+    Saved {
+        id: i32,
+        title: String,
+        content: Value,
+    },
+    Deleted(i32),
+}
+
+impl IndexEvent {
+    fn document_id(&self) -> i32 {
+        match self {
+            IndexEvent::Saved { id, .. } => *id,
+            IndexEvent::Deleted(id) => *id,
+        }
+    }
+}
+
+enum Message {
+    Event(IndexEvent),
+    /// Answered once the events sent before are indexed.
+    Flush(oneshot::Sender<()>),
+}
+
+/// Background worker keeping the task, link and search tables in line with the saved
+/// documents, so that saving a document only writes the document itself.
+#[derive(Clone)]
+pub struct Indexer {
+    sender: UnboundedSender<Message>,
+}
+
+impl Indexer {
+    /// Starts the worker on the tokio runtime. It stops once every handle is dropped.
+    pub fn spawn(pool: SqlitePool) -> Self {
+        let (sender, mut receiver) = unbounded_channel::<Message>();
+
+        tokio::spawn(async move {
+            while let Some(message) = receiver.recv().await {
+                let mut messages = vec![message];
+                while let Ok(message) = receiver.try_recv() {
+                    messages.push(message);
+                }
+
+                let mut events = Vec::new();
+                let mut flushes = Vec::new();
+                for message in messages {
+                    match message {
+                        Message::Event(event) => events.push(event),
+                        Message::Flush(done) => flushes.push(done),
+                    }
+                }
+
+                for event in coalesce(events) {
+                    if let Err(e) = apply(&pool, &event).await {
+                        tracing::error!(
+                            "failed to index document {}: {:?}",
+                            event.document_id(),
+                            e
+                        );
+                    }
+                }
+                for done in flushes {
+                    let _ = done.send(());
+                }
+            }
+        });
+
+        Self { sender }
+    }
+
+    pub fn notify(&self, event: IndexEvent) {
+        // The worker only stops with the last handle, so it is still there
+        let _ = self.sender.send(Message::Event(event));
+    }
+
+    /// Waits for the events sent so far to be indexed, before a short-lived process exits.
+    pub async fn flush(&self) {
+        let (done, flushed) = oneshot::channel();
+        if self.sender.send(Message::Flush(done)).is_ok() {
+            let _ = flushed.await;
+        }
+    }
+}
+
+/// Keeps the last event of each document, as autosave can send many while the worker is
+/// busy.
+fn coalesce(events: Vec<IndexEvent>) -> Vec<IndexEvent> {
+    let mut latest: Vec<IndexEvent> = Vec::new();
+    for event in events {
+        latest.retain(|other| other.document_id() != event.document_id());
+        latest.push(event);
+    }
+    latest
+}
+
+async fn apply(pool: &SqlitePool, event: &IndexEvent) -> Result<(), Error> {
+    retry_busy(|| async move {
+        let mut transaction = pool.begin().await?;
+        match event {
+            IndexEvent::Saved { id, title, content } => {
+                index_tasks(
+                    &mut transaction,
+                    *id,
+                    TaskModel::from_document(*id, content),
+                )
+                .await?;
+                index_links(&mut transaction, *id, linked_documents(content)).await?;
+                index_text(&mut transaction, *id, title, &plain_text(content)).await?;
+            }
+            IndexEvent::Deleted(id) => {
+                query("DELETE FROM tasks WHERE document_id = ?")
+                    .bind(id)
+                    .execute(&mut *transaction)
+                    .await?;
+                query("DELETE FROM links WHERE source_id = ? OR target_id = ?")
+                    .bind(id)
+                    .bind(id)
+                    .execute(&mut *transaction)
+                    .await?;
+                query("DELETE FROM documents_fts WHERE rowid = ?")
+                    .bind(id)
+                    .execute(&mut *transaction)
+                    .await?;
+            }
+        }
+        transaction.commit().await?;
+
+        Ok(())
+    })
+    .await
+}
+
+async fn index_links(
+    connection: &mut SqliteConnection,
+    document_id: i32,
+    targets: Vec<i32>,
+) -> Result<(), Error> {
+    query("DELETE FROM links WHERE source_id = ?")
+        .bind(document_id)
+        .execute(&mut *connection)
+        .await?;
+
+    for target in targets {
+        query("INSERT OR IGNORE INTO links (source_id, target_id) VALUES (?, ?)")
+            .bind(document_id)
+            .bind(target)
+            .execute(&mut *connection)
+            .await?;
+    }
+
+    Ok(())
+}
+
+async fn index_text(
+    connection: &mut SqliteConnection,
+    document_id: i32,
+    title: &str,
+    body: &str,
+) -> Result<(), Error> {
+    query("DELETE FROM documents_fts WHERE rowid = ?")
+        .bind(document_id)
+        .execute(&mut *connection)
+        .await?;
+    query("INSERT INTO documents_fts (rowid, title, body) VALUES (?, ?, ?)")
+        .bind(document_id)
+        .bind(title)
+        .bind(body)
+        .execute(&mut *connection)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use std::{fs, path::PathBuf, str::FromStr};
+
+use tracing::Level;
+use tracing_appender::{
+    non_blocking::WorkerGuard,
+    rolling::{RollingFileAppender, Rotation},
+};
+use tracing_subscriber::{EnvFilter, fmt, layer::SubscriberExt, util::SubscriberInitExt};
+
+use crate::app::remindr::Remindr;
+
+/// Days of logs kept, one file per day.
+const MAX_LOG_FILES: usize = 7;
+
+/// Filter used when `RUST_LOG` isn't set.
+const DEFAULT_FILTER: &str = "info,remindr=debug";
+
+/// Line of a log file, with the level of the event it belongs to.
+#[derive(Debug, Clone)]
+pub struct LogLine {
+    pub level: Level,
+    pub text: String,
+}
+
+/// Sends the events to stderr and to a daily log file in `logs/` next to the settings.
+/// Events are written to the file from a background thread until the returned guard is
+/// dropped.
+pub fn init() -> Option<WorkerGuard> {
+    let filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER));
+    let stderr = fmt::layer().with_writer(std::io::stderr);
+
+    let appender = log_dir().and_then(|dir| {
+        RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix("remindr")
+            .filename_suffix("log")
+            .max_log_files(MAX_LOG_FILES)
+            .build(dir)
+            .map_err(|e| eprintln!("[logging] failed to create log file: {:?}", e))
+            .ok()
+    });
+    let Some(appender) = appender else {
+        tracing_subscriber::registry()
+            .with(filter)
+            .with(stderr)
+            .init();
+        return None;
+    };
+
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(stderr)
+        .with(fmt::layer().with_ansi(false).with_writer(writer))
+        .init();
+
+    Some(guard)
+}
+
+pub fn log_dir() -> Option<PathBuf> {
+    Remindr::new()
+        .get_config_dir("remindr")
+        .ok()
+        .map(|config_dir| config_dir.join("logs"))
+}
+
+/// Last lines of the most recent log file. A line without a level, e.g. the rest of a
+/// multi-line event, takes the level of the line before it.
+pub fn tail(count: usize) -> Vec<LogLine> {
+    let Some(content) = latest_file().and_then(|path| fs::read_to_string(path).ok()) else {
+        return Vec::new();
+    };
+
+    let mut level = Level::INFO;
+    let mut lines = content
+        .lines()
+        .map(|text| {
+            if let Some(line_level) = text
+                .split_whitespace()
+                .nth(1)
+                .and_then(|token| Level::from_str(token).ok())
+            {
+                level = line_level;
+            }
+            LogLine {
+                level,
+                text: text.to_string(),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    lines.drain(..lines.len().saturating_sub(count));
+    lines
+}
+
+/// Log file written to, the files being named after their day.
+pub fn latest_file() -> Option<PathBuf> {
+    fs::read_dir(log_dir()?)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "log"))
+        .max()
+}
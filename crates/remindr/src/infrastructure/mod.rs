@@ -1,2 +1,15 @@
+pub mod assistant;
+pub mod backup;
+pub mod clipboard;
+pub mod database;
+pub mod email;
 pub mod entities;
+pub mod export;
+#[cfg(feature = "http-api")]
+pub mod http_api;
+pub mod import;
+pub mod inbox;
+pub mod indexer;
+pub mod logging;
 pub mod repositories;
+pub mod vault;
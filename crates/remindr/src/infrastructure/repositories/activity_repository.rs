@@ -0,0 +1,57 @@
+use anyhow::Error;
+use chrono::{Local, NaiveDate};
+use sqlx::{SqlitePool, query, query_as};
+
+use crate::{domain::database::activity::ActivityModel, infrastructure::entities::ActivityEntity};
+
+/// Kept in the database even when the documents are stored in a vault, as the activity
+/// only describes the use of this device and is never synced.
+#[derive(Clone)]
+pub struct ActivityRepository {
+    pool: SqlitePool,
+}
+
+impl ActivityRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Activity of each day since the given one, oldest first. Days without any edit are
+    /// missing.
+    pub async fn get_activity(&self, since: NaiveDate) -> Result<Vec<ActivityModel>, Error> {
+        query_as::<_, ActivityEntity>(
+            "SELECT day, COUNT(*) AS documents_edited, SUM(blocks_created) AS blocks_created \
+             FROM activity WHERE day >= ? GROUP BY day ORDER BY day ASC",
+        )
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(anyhow::Error::from)
+        .map(|days| {
+            days.into_iter()
+                .map(ActivityEntity::into)
+                .collect::<Vec<ActivityModel>>()
+        })
+    }
+
+    /// Counts a save of the document today, along with the blocks it added.
+    pub async fn record_activity(
+        &self,
+        document_id: i32,
+        blocks_created: i32,
+    ) -> Result<(), Error> {
+        query(
+            "INSERT INTO activity (day, document_id, blocks_created) VALUES (?, ?, ?) \
+             ON CONFLICT (day, document_id) \
+             DO UPDATE SET blocks_created = blocks_created + excluded.blocks_created",
+        )
+        .bind(Local::now().date_naive())
+        .bind(document_id)
+        .bind(blocks_created)
+        .execute(&self.pool)
+        .await
+        .map_err(anyhow::Error::from)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,142 @@
+use std::path::PathBuf;
+
+use anyhow::Error;
+use sqlx::{SqlitePool, query, query_as};
+
+use crate::{
+    domain::database::attachment::AttachmentModel,
+    infrastructure::{
+        entities::AttachmentEntity,
+        repositories::StorageBackend,
+        vault::file_vault::{FileVault, attachment_file_name},
+    },
+};
+
+/// Temporary directory the attachments stored in the database are extracted to when opened.
+const EXTRACTED_ATTACHMENTS_DIR: &str = "remindr-attachments";
+
+#[derive(Clone)]
+pub struct AttachmentRepository {
+    backend: StorageBackend,
+}
+
+impl AttachmentRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            backend: StorageBackend::Sqlite(pool),
+        }
+    }
+
+    pub fn with_vault(vault: FileVault) -> Self {
+        Self {
+            backend: StorageBackend::Vault(vault),
+        }
+    }
+
+    /// Stores a file, as a blob in the database or as a copy in the attachments directory
+    /// of the vault.
+    pub async fn insert_attachment(
+        &self,
+        file_name: String,
+        data: Vec<u8>,
+    ) -> Result<AttachmentModel, Error> {
+        let pool = match &self.backend {
+            StorageBackend::Sqlite(pool) => pool,
+            StorageBackend::Vault(vault) => {
+                return vault.insert_attachment(file_name, data).await;
+            }
+        };
+
+        let size = data.len() as i64;
+        let result = query("INSERT INTO attachments (file_name, size, data) VALUES (?, ?, ?)")
+            .bind(&file_name)
+            .bind(size)
+            .bind(data)
+            .execute(pool)
+            .await
+            .map_err(anyhow::Error::from)?;
+
+        Ok(AttachmentModel {
+            id: result.last_insert_rowid() as i32,
+            file_name,
+            size,
+        })
+    }
+
+    pub async fn get_attachment(&self, id: i32) -> Result<AttachmentModel, Error> {
+        let pool = match &self.backend {
+            StorageBackend::Sqlite(pool) => pool,
+            StorageBackend::Vault(vault) => return vault.get_attachment(id).await,
+        };
+
+        query_as::<_, AttachmentEntity>("SELECT id, file_name, size FROM attachments WHERE id = ?")
+            .bind(id)
+            .fetch_one(pool)
+            .await
+            .map(AttachmentEntity::into)
+            .map_err(anyhow::Error::from)
+    }
+
+    /// Every stored attachment, to find the ones no block refers to anymore.
+    pub async fn get_attachments(&self) -> Result<Vec<AttachmentModel>, Error> {
+        let pool = match &self.backend {
+            StorageBackend::Sqlite(pool) => pool,
+            StorageBackend::Vault(vault) => return vault.get_attachments().await,
+        };
+
+        query_as::<_, AttachmentEntity>("SELECT id, file_name, size FROM attachments ORDER BY id")
+            .fetch_all(pool)
+            .await
+            .map(|attachments| {
+                attachments
+                    .into_iter()
+                    .map(AttachmentEntity::into)
+                    .collect()
+            })
+            .map_err(anyhow::Error::from)
+    }
+
+    pub async fn delete_attachments(&self, ids: Vec<i32>) -> Result<(), Error> {
+        let pool = match &self.backend {
+            StorageBackend::Sqlite(pool) => pool,
+            StorageBackend::Vault(vault) => return vault.delete_attachments(ids).await,
+        };
+
+        let mut transaction = pool.begin().await?;
+        for id in ids {
+            query("DELETE FROM attachments WHERE id = ?")
+                .bind(id)
+                .execute(&mut *transaction)
+                .await?;
+        }
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    /// Path of a file holding the attachment, to open it with another application.
+    ///
+    /// Attachments of the database are extracted to a temporary directory first.
+    pub async fn attachment_path(&self, id: i32) -> Result<PathBuf, Error> {
+        let pool = match &self.backend {
+            StorageBackend::Sqlite(pool) => pool,
+            StorageBackend::Vault(vault) => return vault.attachment_path(id).await,
+        };
+
+        let (file_name, data) = query_as::<_, (String, Vec<u8>)>(
+            "SELECT file_name, data FROM attachments WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_one(pool)
+        .await
+        .map_err(anyhow::Error::from)?;
+
+        let directory = std::env::temp_dir()
+            .join(EXTRACTED_ATTACHMENTS_DIR)
+            .join(id.to_string());
+        tokio::fs::create_dir_all(&directory).await?;
+        let path = directory.join(attachment_file_name(&file_name));
+        tokio::fs::write(&path, data).await?;
+
+        Ok(path)
+    }
+}
@@ -0,0 +1,63 @@
+use anyhow::Error;
+use sqlx::{SqlitePool, query, query_as};
+
+use crate::{
+    domain::database::collection::CollectionView,
+    infrastructure::{
+        entities::CollectionViewEntity, repositories::StorageBackend, vault::file_vault::FileVault,
+    },
+};
+
+#[derive(Clone)]
+pub struct CollectionRepository {
+    backend: StorageBackend,
+}
+
+impl CollectionRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            backend: StorageBackend::Sqlite(pool),
+        }
+    }
+
+    pub fn with_vault(vault: FileVault) -> Self {
+        Self {
+            backend: StorageBackend::Vault(vault),
+        }
+    }
+
+    /// View of a folder displayed as a collection, the default one if it was never changed.
+    pub async fn get_view(&self, folder_id: i32) -> Result<CollectionView, Error> {
+        let pool = match &self.backend {
+            StorageBackend::Sqlite(pool) => pool,
+            StorageBackend::Vault(vault) => return vault.get_collection_view(folder_id).await,
+        };
+
+        query_as::<_, CollectionViewEntity>(
+            "SELECT folder_id, view FROM collection_views WHERE folder_id = ?",
+        )
+        .bind(folder_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(anyhow::Error::from)
+        .map(|view| view.map(CollectionViewEntity::into).unwrap_or_default())
+    }
+
+    pub async fn save_view(&self, folder_id: i32, view: CollectionView) -> Result<(), Error> {
+        let pool = match &self.backend {
+            StorageBackend::Sqlite(pool) => pool,
+            StorageBackend::Vault(vault) => {
+                return vault.save_collection_view(folder_id, view).await;
+            }
+        };
+
+        query("INSERT OR REPLACE INTO collection_views (folder_id, view) VALUES (?, ?)")
+            .bind(folder_id)
+            .bind(serde_json::to_value(view)?)
+            .execute(pool)
+            .await
+            .map_err(anyhow::Error::from)?;
+
+        Ok(())
+    }
+}
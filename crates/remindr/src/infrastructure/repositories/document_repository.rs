@@ -1,16 +1,51 @@
 use anyhow::Error;
-use sqlx::{SqlitePool, query, query_as};
+use sqlx::{SqlitePool, query, query_as, query_scalar};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::instrument;
 
-use crate::{domain::database::document::DocumentModel, infrastructure::entities::DocumentEntity};
+use crate::{
+    domain::{
+        database::document::{DocumentLocation, DocumentModel, StoredDocument},
+        ports::{BoxFuture, DocumentRepositoryPort},
+    },
+    infrastructure::{
+        entities::DocumentEntity,
+        indexer::{IndexEvent, Indexer},
+        repositories::retry_busy,
+    },
+};
 
 #[derive(Clone)]
 pub struct DocumentRepository {
     pool: SqlitePool,
+    /// Keeps the tasks, links and search tables of the database in line with the saves.
+    indexer: Indexer,
 }
 
 impl DocumentRepository {
-    pub fn new(pool: SqlitePool) -> Self {
-        Self { pool }
+    pub fn new(pool: SqlitePool, indexer: Indexer) -> Self {
+        Self { pool, indexer }
+    }
+
+    fn index(&self, event: IndexEvent) {
+        self.indexer.notify(event);
+    }
+
+    /// Indexes a document again after its content was replaced outside the repository,
+    /// e.g. restored from a backup.
+    pub async fn reindex_document(&self, id: i32) -> Result<(), Error> {
+        let document = self.get_document_by_id(id).await?;
+        self.index(IndexEvent::Saved {
+            id,
+            title: document.title,
+            content: document.content,
+        });
+        Ok(())
+    }
+
+    /// Waits for the documents saved so far to be indexed.
+    pub async fn flush_index(&self) {
+        self.indexer.flush().await;
     }
 
     pub async fn get_documents(&self) -> Result<Vec<DocumentModel>, Error> {
@@ -28,6 +63,84 @@ impl DocumentRepository {
         })
     }
 
+    /// Every document as stored, its content unparsed, to find the ones that can't be opened.
+    pub async fn get_stored_documents(&self) -> Result<Vec<StoredDocument>, Error> {
+        let rows = query_as::<_, (i32, String, String)>(
+            "SELECT id, title, CAST(content AS TEXT) FROM documents ORDER BY id ASC",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|(id, title, content)| StoredDocument {
+                location: DocumentLocation::Row(id),
+                title,
+                content,
+            })
+            .collect())
+    }
+
+    /// Documents that are not filed in any folder.
+    pub async fn get_unfiled_documents(&self) -> Result<Vec<DocumentModel>, Error> {
+        query_as::<_, DocumentEntity>(
+            "SELECT id, title, content, folder_id FROM documents WHERE folder_id IS NULL ORDER BY id ASC",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(anyhow::Error::from)
+        .map(|documents| {
+            documents
+                .into_iter()
+                .map(DocumentEntity::into)
+                .collect::<Vec<DocumentModel>>()
+        })
+    }
+
+    /// Documents whose title or text contain every word of `text`, as a prefix, the best
+    /// matches first.
+    pub async fn search_documents(&self, text: &str) -> Result<Vec<DocumentModel>, Error> {
+        let words = text.split_whitespace().collect::<Vec<_>>();
+        if words.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Each word is quoted so that FTS operators typed by the user are searched as text
+        let pattern = words
+            .iter()
+            .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        query_as::<_, DocumentEntity>(
+            "SELECT documents.id, documents.title, documents.content, documents.folder_id \
+             FROM documents_fts JOIN documents ON documents.id = documents_fts.rowid \
+             WHERE documents_fts MATCH ? ORDER BY rank",
+        )
+        .bind(pattern)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(anyhow::Error::from)
+        .map(|documents| {
+            documents
+                .into_iter()
+                .map(DocumentEntity::into)
+                .collect::<Vec<DocumentModel>>()
+        })
+    }
+
+    /// Document with the given id, `None` when there is none.
+    pub async fn find_document(&self, id: i32) -> Result<Option<DocumentModel>, Error> {
+        query_as::<_, DocumentEntity>(
+            "SELECT id, title, content, folder_id FROM documents WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map(|document| document.map(DocumentEntity::into))
+        .map_err(anyhow::Error::from)
+    }
+
+    #[instrument(skip(self), err)]
     pub async fn get_document_by_id(&self, id: i32) -> Result<DocumentModel, Error> {
         query_as::<_, DocumentEntity>(
             "SELECT id, title, content, folder_id FROM documents WHERE id = ?",
@@ -39,50 +152,156 @@ impl DocumentRepository {
         .map_err(anyhow::Error::from)
     }
 
+    /// When the document was last saved, none if unknown, e.g. for the documents saved
+    /// before the time was recorded.
+    pub async fn updated_at(&self, id: i32) -> Result<Option<SystemTime>, Error> {
+        let seconds =
+            query_scalar::<_, Option<i64>>("SELECT updated_at FROM documents WHERE id = ?")
+                .bind(id)
+                .fetch_one(&self.pool)
+                .await?;
+        Ok(seconds.map(|seconds| UNIX_EPOCH + Duration::from_secs(seconds.max(0) as u64)))
+    }
+
+    #[instrument(skip_all, err)]
     pub async fn insert_document(&self, document: DocumentModel) -> Result<i32, Error> {
-        let res = query("INSERT INTO documents (title, content, folder_id) VALUES (?, ?, ?)")
-            .bind(document.title)
-            .bind(document.content)
-            .bind(document.folder_id)
-            .execute(&self.pool)
-            .await
-            .map_err(anyhow::Error::from)?;
+        let (title, content, folder_id) = (&document.title, &document.content, document.folder_id);
+        let res = retry_busy(|| async move {
+            query("INSERT INTO documents (title, content, folder_id) VALUES (?, ?, ?)")
+                .bind(title)
+                .bind(content)
+                .bind(folder_id)
+                .execute(&self.pool)
+                .await
+                .map_err(anyhow::Error::from)
+        })
+        .await?;
 
-        let last = res.last_insert_rowid();
-        Ok(last as i32)
+        let id = res.last_insert_rowid() as i32;
+
+        self.index(IndexEvent::Saved {
+            id,
+            title: document.title,
+            content: document.content,
+        });
+        Ok(id)
     }
 
+    #[instrument(skip_all, fields(id = document.id), err)]
     pub async fn update_document(&self, document: DocumentModel) -> Result<(), Error> {
-        query("UPDATE documents SET title = $1, content = $2, folder_id = $3 WHERE id = $4")
-            .bind(document.title)
-            .bind(document.content)
-            .bind(document.folder_id)
-            .bind(document.id)
+        let (id, title, content, folder_id) = (
+            document.id,
+            &document.title,
+            &document.content,
+            document.folder_id,
+        );
+        retry_busy(|| async move {
+            query(
+                "UPDATE documents SET title = $1, content = $2, folder_id = $3, \
+                 updated_at = CAST(strftime('%s', 'now') AS INTEGER) WHERE id = $4",
+            )
+            .bind(title)
+            .bind(content)
+            .bind(folder_id)
+            .bind(id)
             .execute(&self.pool)
             .await
-            .map_err(anyhow::Error::from)?;
+            .map_err(anyhow::Error::from)
+        })
+        .await?;
 
+        self.index(IndexEvent::Saved {
+            id,
+            title: document.title,
+            content: document.content,
+        });
         Ok(())
     }
 
+    #[instrument(skip(self), err)]
     pub async fn move_document(&self, id: i32, folder_id: Option<i32>) -> Result<(), Error> {
-        query("UPDATE documents SET folder_id = ? WHERE id = ?")
-            .bind(folder_id)
-            .bind(id)
-            .execute(&self.pool)
-            .await
-            .map_err(anyhow::Error::from)?;
+        retry_busy(|| async move {
+            query("UPDATE documents SET folder_id = ? WHERE id = ?")
+                .bind(folder_id)
+                .bind(id)
+                .execute(&self.pool)
+                .await
+                .map_err(anyhow::Error::from)
+        })
+        .await?;
 
         Ok(())
     }
 
+    #[instrument(skip(self), err)]
     pub async fn delete_document(&self, id: i32) -> Result<(), Error> {
-        query("DELETE FROM documents WHERE id = ?")
-            .bind(id)
-            .execute(&self.pool)
-            .await
-            .map_err(anyhow::Error::from)?;
+        retry_busy(|| async move {
+            query("DELETE FROM documents WHERE id = ?")
+                .bind(id)
+                .execute(&self.pool)
+                .await
+                .map_err(anyhow::Error::from)
+        })
+        .await?;
 
+        self.index(IndexEvent::Deleted(id));
         Ok(())
     }
 }
+
+impl DocumentRepositoryPort for DocumentRepository {
+    fn get_documents(&self) -> BoxFuture<'_, Result<Vec<DocumentModel>, Error>> {
+        Box::pin(self.get_documents())
+    }
+
+    fn find_document(&self, id: i32) -> BoxFuture<'_, Result<Option<DocumentModel>, Error>> {
+        Box::pin(self.find_document(id))
+    }
+
+    fn insert_document(&self, document: DocumentModel) -> BoxFuture<'_, Result<i32, Error>> {
+        Box::pin(self.insert_document(document))
+    }
+
+    fn update_document(&self, document: DocumentModel) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(self.update_document(document))
+    }
+
+    fn move_document(&self, id: i32, folder_id: Option<i32>) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(self.move_document(id, folder_id))
+    }
+
+    fn delete_document(&self, id: i32) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(self.delete_document(id))
+    }
+
+    fn get_document_by_id(&self, id: i32) -> BoxFuture<'_, Result<DocumentModel, Error>> {
+        Box::pin(self.get_document_by_id(id))
+    }
+
+    fn get_unfiled_documents(&self) -> BoxFuture<'_, Result<Vec<DocumentModel>, Error>> {
+        Box::pin(self.get_unfiled_documents())
+    }
+
+    fn search_documents<'a>(
+        &'a self,
+        text: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<DocumentModel>, Error>> {
+        Box::pin(self.search_documents(text))
+    }
+
+    fn get_stored_documents(&self) -> BoxFuture<'_, Result<Vec<StoredDocument>, Error>> {
+        Box::pin(self.get_stored_documents())
+    }
+
+    fn updated_at(&self, id: i32) -> BoxFuture<'_, Result<Option<SystemTime>, Error>> {
+        Box::pin(self.updated_at(id))
+    }
+
+    fn reindex_document(&self, id: i32) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(self.reindex_document(id))
+    }
+
+    fn flush_index(&self) -> BoxFuture<'_, ()> {
+        Box::pin(self.flush_index())
+    }
+}
@@ -1,7 +1,13 @@
 use anyhow::Error;
-use sqlx::{SqlitePool, query, query_as};
+use sqlx::{SqlitePool, query, query_as, query_scalar};
 
-use crate::{domain::database::folder::FolderModel, infrastructure::entities::FolderEntity};
+use crate::{
+    domain::{
+        database::folder::FolderModel,
+        ports::{BoxFuture, FolderRepositoryPort},
+    },
+    infrastructure::entities::FolderEntity,
+};
 
 const MAX_FOLDER_DEPTH: u32 = 3;
 
@@ -37,16 +43,18 @@ impl FolderRepository {
             .map_err(anyhow::Error::from)
     }
 
+    /// Folder with the given id, `None` when there is none.
+    pub async fn find_folder(&self, id: i32) -> Result<Option<FolderModel>, Error> {
+        query_as::<_, FolderEntity>("SELECT id, name, parent_id FROM folders WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map(|folder| folder.map(FolderEntity::into))
+            .map_err(anyhow::Error::from)
+    }
+
     pub async fn insert_folder(&self, name: String, parent_id: Option<i32>) -> Result<i32, Error> {
-        if let Some(pid) = parent_id {
-            let depth = self.compute_depth(pid).await?;
-            if depth >= MAX_FOLDER_DEPTH {
-                return Err(anyhow::anyhow!(
-                    "Cannot create folder: maximum depth of {} reached",
-                    MAX_FOLDER_DEPTH
-                ));
-            }
-        }
+        check_insert_depth(self, parent_id).await?;
 
         let res = query("INSERT INTO folders (name, parent_id) VALUES (?, ?)")
             .bind(&name)
@@ -80,15 +88,7 @@ impl FolderRepository {
     }
 
     pub async fn move_folder(&self, id: i32, new_parent_id: Option<i32>) -> Result<(), Error> {
-        if let Some(pid) = new_parent_id {
-            let depth = self.compute_depth(pid).await?;
-            if depth >= MAX_FOLDER_DEPTH {
-                return Err(anyhow::anyhow!(
-                    "Cannot move folder: maximum depth of {} would be exceeded",
-                    MAX_FOLDER_DEPTH
-                ));
-            }
-        }
+        check_move_depth(self, new_parent_id).await?;
 
         query("UPDATE folders SET parent_id = ? WHERE id = ?")
             .bind(new_parent_id)
@@ -100,6 +100,37 @@ impl FolderRepository {
         Ok(())
     }
 
+    /// Id of the template new documents of the folder start from, `None` when they start
+    /// empty.
+    pub async fn get_template(&self, folder_id: i32) -> Result<Option<i32>, Error> {
+        query_scalar::<_, i32>("SELECT template_id FROM folder_templates WHERE folder_id = ?")
+            .bind(folder_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(anyhow::Error::from)
+    }
+
+    pub async fn set_template(
+        &self,
+        folder_id: i32,
+        template_id: Option<i32>,
+    ) -> Result<(), Error> {
+        let statement = match template_id {
+            Some(template_id) => query(
+                "INSERT OR REPLACE INTO folder_templates (folder_id, template_id) VALUES (?, ?)",
+            )
+            .bind(folder_id)
+            .bind(template_id),
+            None => query("DELETE FROM folder_templates WHERE folder_id = ?").bind(folder_id),
+        };
+        statement
+            .execute(&self.pool)
+            .await
+            .map_err(anyhow::Error::from)?;
+
+        Ok(())
+    }
+
     /// Delete a folder but keep its children by moving them to the folder's parent.
     /// Sub-folders and documents are reparented to parent_id of the deleted folder.
     pub async fn delete_folder_keep_children(&self, id: i32) -> Result<(), Error> {
@@ -125,24 +156,107 @@ impl FolderRepository {
         // Now delete the empty folder
         self.delete_folder(id).await
     }
+}
+
+/// Fails when a folder can't be created in `parent_id` without exceeding the maximum depth.
+pub(crate) async fn check_insert_depth(
+    folders: &dyn FolderRepositoryPort,
+    parent_id: Option<i32>,
+) -> Result<(), Error> {
+    if let Some(pid) = parent_id
+        && compute_depth(folders, pid).await? >= MAX_FOLDER_DEPTH
+    {
+        return Err(anyhow::anyhow!(
+            "Cannot create folder: maximum depth of {} reached",
+            MAX_FOLDER_DEPTH
+        ));
+    }
+    Ok(())
+}
+
+/// Fails when a folder can't be moved into `new_parent_id` without exceeding the maximum
+/// depth.
+pub(crate) async fn check_move_depth(
+    folders: &dyn FolderRepositoryPort,
+    new_parent_id: Option<i32>,
+) -> Result<(), Error> {
+    if let Some(pid) = new_parent_id
+        && compute_depth(folders, pid).await? >= MAX_FOLDER_DEPTH
+    {
+        return Err(anyhow::anyhow!(
+            "Cannot move folder: maximum depth of {} would be exceeded",
+            MAX_FOLDER_DEPTH
+        ));
+    }
+    Ok(())
+}
+
+/// Compute the depth of a folder by walking up the parent chain.
+/// Root folders have depth 1, their children depth 2, etc.
+async fn compute_depth(folders: &dyn FolderRepositoryPort, folder_id: i32) -> Result<u32, Error> {
+    let mut depth = 1u32;
+    let mut current_id = folder_id;
 
-    /// Compute the depth of a folder by walking up the parent chain.
-    /// Root folders have depth 1, their children depth 2, etc.
-    async fn compute_depth(&self, folder_id: i32) -> Result<u32, Error> {
-        let mut depth = 1u32;
-        let mut current_id = folder_id;
-
-        loop {
-            let folder = self.get_folder_by_id(current_id).await?;
-            match folder.parent_id {
-                Some(pid) => {
-                    depth += 1;
-                    current_id = pid;
-                }
-                None => break,
+    loop {
+        let folder = folders.get_folder_by_id(current_id).await?;
+        match folder.parent_id {
+            Some(pid) => {
+                depth += 1;
+                current_id = pid;
             }
+            None => break,
         }
+    }
+
+    Ok(depth)
+}
+
+impl FolderRepositoryPort for FolderRepository {
+    fn get_folders(&self) -> BoxFuture<'_, Result<Vec<FolderModel>, Error>> {
+        Box::pin(self.get_folders())
+    }
+
+    fn find_folder(&self, id: i32) -> BoxFuture<'_, Result<Option<FolderModel>, Error>> {
+        Box::pin(self.find_folder(id))
+    }
+
+    fn insert_folder(
+        &self,
+        name: String,
+        parent_id: Option<i32>,
+    ) -> BoxFuture<'_, Result<i32, Error>> {
+        Box::pin(self.insert_folder(name, parent_id))
+    }
+
+    fn update_folder(&self, folder: FolderModel) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(self.update_folder(folder))
+    }
+
+    fn delete_folder(&self, id: i32) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(self.delete_folder(id))
+    }
+
+    fn move_folder(&self, id: i32, new_parent_id: Option<i32>) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(self.move_folder(id, new_parent_id))
+    }
+
+    fn get_template(&self, folder_id: i32) -> BoxFuture<'_, Result<Option<i32>, Error>> {
+        Box::pin(self.get_template(folder_id))
+    }
+
+    fn set_template(
+        &self,
+        folder_id: i32,
+        template_id: Option<i32>,
+    ) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(self.set_template(folder_id, template_id))
+    }
+
+    fn delete_folder_keep_children(&self, id: i32) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(self.delete_folder_keep_children(id))
+    }
 
-        Ok(depth)
+    fn get_folder_by_id(&self, id: i32) -> BoxFuture<'_, Result<FolderModel, Error>> {
+        Box::pin(self.get_folder_by_id(id))
     }
 }
@@ -0,0 +1,348 @@
+use std::{
+    collections::HashMap,
+    future::ready,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU32, Ordering},
+    },
+};
+
+use anyhow::{Error, anyhow};
+
+use crate::domain::{
+    database::{document::DocumentModel, folder::FolderModel},
+    ports::{BoxFuture, DocumentRepositoryPort, FolderRepositoryPort},
+};
+
+/// Documents kept in memory, to test code using [`DocumentRepositoryPort`] without a
+/// database file. Clones share the same documents.
+#[derive(Clone, Default)]
+pub struct InMemoryDocumentRepository {
+    documents: Arc<Mutex<Vec<DocumentModel>>>,
+    /// Updates still to fail, to test how the failed saves are handled.
+    failing_updates: Arc<AtomicU32>,
+}
+
+impl InMemoryDocumentRepository {
+    pub fn with_documents(documents: Vec<DocumentModel>) -> Self {
+        Self {
+            documents: Arc::new(Mutex::new(documents)),
+            ..Default::default()
+        }
+    }
+
+    /// Makes the next `count` updates fail, as if the storage were unavailable.
+    pub fn fail_updates(&self, count: u32) {
+        self.failing_updates.store(count, Ordering::SeqCst);
+    }
+
+    fn replace(&self, id: i32, change: impl FnOnce(&mut DocumentModel)) -> Result<(), Error> {
+        let mut documents = self.documents.lock().unwrap();
+        match documents.iter_mut().find(|existing| existing.id == id) {
+            Some(existing) => {
+                change(existing);
+                Ok(())
+            }
+            None => Err(anyhow!("Document {} not found", id)),
+        }
+    }
+}
+
+impl DocumentRepositoryPort for InMemoryDocumentRepository {
+    fn get_documents(&self) -> BoxFuture<'_, Result<Vec<DocumentModel>, Error>> {
+        let mut documents = self.documents.lock().unwrap().clone();
+        documents.sort_by_key(|document| document.id);
+        Box::pin(ready(Ok(documents)))
+    }
+
+    fn find_document(&self, id: i32) -> BoxFuture<'_, Result<Option<DocumentModel>, Error>> {
+        let documents = self.documents.lock().unwrap();
+        let document = documents.iter().find(|document| document.id == id).cloned();
+        Box::pin(ready(Ok(document)))
+    }
+
+    /// Inserts the document with the next free id.
+    fn insert_document(&self, mut document: DocumentModel) -> BoxFuture<'_, Result<i32, Error>> {
+        let mut documents = self.documents.lock().unwrap();
+        document.id = next_id(documents.iter().map(|document| document.id));
+        let id = document.id;
+        documents.push(document);
+        Box::pin(ready(Ok(id)))
+    }
+
+    fn update_document(&self, document: DocumentModel) -> BoxFuture<'_, Result<(), Error>> {
+        let failing = self
+            .failing_updates
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |count| {
+                count.checked_sub(1)
+            })
+            .is_ok();
+        if failing {
+            return Box::pin(ready(Err(anyhow!("Storage unavailable"))));
+        }
+
+        let result = self.replace(document.id, |existing| *existing = document);
+        Box::pin(ready(result))
+    }
+
+    fn move_document(&self, id: i32, folder_id: Option<i32>) -> BoxFuture<'_, Result<(), Error>> {
+        let result = self.replace(id, |existing| existing.folder_id = folder_id);
+        Box::pin(ready(result))
+    }
+
+    fn delete_document(&self, id: i32) -> BoxFuture<'_, Result<(), Error>> {
+        self.documents
+            .lock()
+            .unwrap()
+            .retain(|document| document.id != id);
+        Box::pin(ready(Ok(())))
+    }
+}
+
+/// Folders kept in memory, to test code using [`FolderRepositoryPort`] without a database
+/// file. Clones share the same folders.
+#[derive(Clone, Default)]
+pub struct InMemoryFolderRepository {
+    folders: Arc<Mutex<Vec<FolderModel>>>,
+    templates: Arc<Mutex<HashMap<i32, i32>>>,
+}
+
+impl InMemoryFolderRepository {
+    pub fn with_folders(folders: Vec<FolderModel>) -> Self {
+        Self {
+            folders: Arc::new(Mutex::new(folders)),
+            ..Default::default()
+        }
+    }
+
+    fn check_parent(folders: &[FolderModel], parent_id: Option<i32>) -> Result<(), Error> {
+        match parent_id {
+            Some(parent_id) if !folders.iter().any(|existing| existing.id == parent_id) => {
+                Err(anyhow!("Folder {} not found", parent_id))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn replace(&self, id: i32, change: impl FnOnce(&mut FolderModel)) -> Result<(), Error> {
+        let mut folders = self.folders.lock().unwrap();
+        match folders.iter_mut().find(|existing| existing.id == id) {
+            Some(existing) => {
+                change(existing);
+                Ok(())
+            }
+            None => Err(anyhow!("Folder {} not found", id)),
+        }
+    }
+}
+
+impl FolderRepositoryPort for InMemoryFolderRepository {
+    /// Folders by name, like the database returns them.
+    fn get_folders(&self) -> BoxFuture<'_, Result<Vec<FolderModel>, Error>> {
+        let mut folders = self.folders.lock().unwrap().clone();
+        folders.sort_by(|a, b| a.name.cmp(&b.name));
+        Box::pin(ready(Ok(folders)))
+    }
+
+    fn find_folder(&self, id: i32) -> BoxFuture<'_, Result<Option<FolderModel>, Error>> {
+        let folders = self.folders.lock().unwrap();
+        let folder = folders.iter().find(|folder| folder.id == id).cloned();
+        Box::pin(ready(Ok(folder)))
+    }
+
+    /// Inserts the folder with the next free id.
+    fn insert_folder(
+        &self,
+        name: String,
+        parent_id: Option<i32>,
+    ) -> BoxFuture<'_, Result<i32, Error>> {
+        let mut folders = self.folders.lock().unwrap();
+        let result = Self::check_parent(&folders, parent_id).map(|()| {
+            let id = next_id(folders.iter().map(|folder| folder.id));
+            folders.push(FolderModel {
+                id,
+                name,
+                parent_id,
+            });
+            id
+        });
+        Box::pin(ready(result))
+    }
+
+    fn update_folder(&self, folder: FolderModel) -> BoxFuture<'_, Result<(), Error>> {
+        let result = self.replace(folder.id, |existing| existing.name = folder.name);
+        Box::pin(ready(result))
+    }
+
+    fn delete_folder(&self, id: i32) -> BoxFuture<'_, Result<(), Error>> {
+        self.folders
+            .lock()
+            .unwrap()
+            .retain(|folder| folder.id != id);
+        self.templates.lock().unwrap().remove(&id);
+        Box::pin(ready(Ok(())))
+    }
+
+    fn move_folder(&self, id: i32, new_parent_id: Option<i32>) -> BoxFuture<'_, Result<(), Error>> {
+        let parent = Self::check_parent(&self.folders.lock().unwrap(), new_parent_id);
+        let result =
+            parent.and_then(|()| self.replace(id, |existing| existing.parent_id = new_parent_id));
+        Box::pin(ready(result))
+    }
+
+    fn get_template(&self, folder_id: i32) -> BoxFuture<'_, Result<Option<i32>, Error>> {
+        let template_id = self.templates.lock().unwrap().get(&folder_id).copied();
+        Box::pin(ready(Ok(template_id)))
+    }
+
+    fn set_template(
+        &self,
+        folder_id: i32,
+        template_id: Option<i32>,
+    ) -> BoxFuture<'_, Result<(), Error>> {
+        let mut templates = self.templates.lock().unwrap();
+        match template_id {
+            Some(template_id) => templates.insert(folder_id, template_id),
+            None => templates.remove(&folder_id),
+        };
+        Box::pin(ready(Ok(())))
+    }
+
+    /// Moves the subfolders to the parent of the folder before deleting it, the documents
+    /// being kept by the document repository.
+    fn delete_folder_keep_children(&self, id: i32) -> BoxFuture<'_, Result<(), Error>> {
+        let mut folders = self.folders.lock().unwrap();
+        let Some(parent_id) = folders
+            .iter()
+            .find(|folder| folder.id == id)
+            .map(|folder| folder.parent_id)
+        else {
+            return Box::pin(ready(Err(anyhow!("Folder {} not found", id))));
+        };
+
+        for folder in folders
+            .iter_mut()
+            .filter(|folder| folder.parent_id == Some(id))
+        {
+            folder.parent_id = parent_id;
+        }
+        folders.retain(|folder| folder.id != id);
+        self.templates.lock().unwrap().remove(&id);
+        Box::pin(ready(Ok(())))
+    }
+}
+
+/// Id following the largest one, like an SQLite rowid.
+fn next_id(ids: impl Iterator<Item = i32>) -> i32 {
+    ids.max().unwrap_or(0) + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn document(id: i32, title: &str) -> DocumentModel {
+        DocumentModel {
+            id,
+            title: title.to_string(),
+            content: json!([]),
+            folder_id: None,
+        }
+    }
+
+    fn folder(id: i32, name: &str, parent_id: Option<i32>) -> FolderModel {
+        FolderModel {
+            id,
+            name: name.to_string(),
+            parent_id,
+        }
+    }
+
+    #[tokio::test]
+    async fn saving_a_new_document_gives_it_the_next_id() {
+        let repository = InMemoryDocumentRepository::with_documents(vec![document(4, "Notes")]);
+
+        repository.save(document(0, "Ideas")).await.unwrap();
+
+        let titles = repository
+            .list()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|document| (document.id, document.title))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            titles,
+            vec![(4, "Notes".to_string()), (5, "Ideas".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn saving_an_existing_document_replaces_it() {
+        let repository = InMemoryDocumentRepository::with_documents(vec![document(1, "Draft")]);
+
+        repository.save(document(1, "Final")).await.unwrap();
+
+        let saved = repository.get(1).await.unwrap().unwrap();
+        assert_eq!(saved.title, "Final");
+        assert_eq!(repository.list().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn saving_a_missing_document_fails() {
+        let repository = InMemoryDocumentRepository::default();
+
+        assert!(repository.save(document(7, "Lost")).await.is_err());
+        assert!(repository.get(7).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn clones_share_the_documents() {
+        let repository = InMemoryDocumentRepository::default();
+        let clone = repository.clone();
+
+        clone.save(document(0, "Shared")).await.unwrap();
+
+        assert!(repository.get(1).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn folders_are_listed_by_name() {
+        let repository = InMemoryFolderRepository::with_folders(vec![
+            folder(1, "Work", None),
+            folder(2, "Archive", None),
+        ]);
+
+        let names = repository
+            .list()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|folder| folder.name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["Archive", "Work"]);
+    }
+
+    #[tokio::test]
+    async fn folders_can_be_moved_under_an_existing_parent_only() {
+        let repository = InMemoryFolderRepository::with_folders(vec![
+            folder(1, "Work", None),
+            folder(2, "Projects", None),
+        ]);
+
+        repository
+            .save(folder(2, "Projects", Some(1)))
+            .await
+            .unwrap();
+        assert_eq!(repository.get(2).await.unwrap().unwrap().parent_id, Some(1));
+
+        assert!(
+            repository
+                .save(folder(2, "Projects", Some(9)))
+                .await
+                .is_err()
+        );
+    }
+}
@@ -1,2 +1,72 @@
+pub mod activity_repository;
+pub mod attachment_repository;
+pub mod collection_repository;
 pub mod document_repository;
 pub mod folder_repository;
+pub mod memory;
+pub mod reminder_repository;
+pub mod smart_folder_repository;
+pub mod synced_block_repository;
+pub mod tag_repository;
+pub mod task_repository;
+
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use anyhow::Error;
+use sqlx::SqlitePool;
+
+use crate::infrastructure::vault::file_vault::FileVault;
+
+/// Attempts made at a write still failing with `SQLITE_BUSY` once the busy timeout ran out.
+const BUSY_ATTEMPTS: u32 = 3;
+
+/// Delay before the first retry of a busy write, doubled at each attempt.
+const BUSY_BACKOFF: Duration = Duration::from_millis(100);
+
+static BUSY_RETRIES: AtomicUsize = AtomicUsize::new(0);
+
+/// Storage the repositories read from and write to.
+#[derive(Clone)]
+pub(crate) enum StorageBackend {
+    Sqlite(SqlitePool),
+    Vault(FileVault),
+}
+
+/// Runs `operation` again while the database is locked by another connection, which can
+/// outlast the busy timeout when sync and indexing write at the same time.
+pub(crate) async fn retry_busy<T, F, Fut>(mut operation: F) -> Result<T, Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, Error>>,
+{
+    let mut attempt = 1;
+    loop {
+        match operation().await {
+            Err(e) if attempt < BUSY_ATTEMPTS && is_busy(&e) => {
+                BUSY_RETRIES.fetch_add(1, Ordering::Relaxed);
+                tracing::warn!("database busy, retrying (attempt {})", attempt);
+                tokio::time::sleep(BUSY_BACKOFF * 2u32.pow(attempt - 1)).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Writes retried by [`retry_busy`] since the start.
+pub fn busy_retries() -> usize {
+    BUSY_RETRIES.load(Ordering::Relaxed)
+}
+
+/// Whether `error` is `SQLITE_BUSY` or `SQLITE_LOCKED`, extended codes included.
+fn is_busy(error: &Error) -> bool {
+    error
+        .downcast_ref::<sqlx::Error>()
+        .and_then(|e| e.as_database_error())
+        .and_then(|e| e.code())
+        .and_then(|code| code.parse::<i32>().ok())
+        .is_some_and(|code| matches!(code & 0xff, 5 | 6))
+}
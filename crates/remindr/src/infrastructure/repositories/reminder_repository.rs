@@ -0,0 +1,120 @@
+use anyhow::Error;
+use sqlx::{SqlitePool, query, query_as};
+
+use crate::{
+    domain::database::reminder::ReminderModel,
+    infrastructure::{
+        entities::ReminderEntity, repositories::StorageBackend, vault::file_vault::FileVault,
+    },
+};
+
+#[derive(Clone)]
+pub struct ReminderRepository {
+    backend: StorageBackend,
+}
+
+impl ReminderRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            backend: StorageBackend::Sqlite(pool),
+        }
+    }
+
+    pub fn with_vault(vault: FileVault) -> Self {
+        Self {
+            backend: StorageBackend::Vault(vault),
+        }
+    }
+
+    /// Reminders not completed yet, the earliest first.
+    pub async fn get_pending_reminders(&self) -> Result<Vec<ReminderModel>, Error> {
+        let pool = match &self.backend {
+            StorageBackend::Sqlite(pool) => pool,
+            StorageBackend::Vault(vault) => {
+                let mut reminders = vault
+                    .get_reminders()
+                    .await?
+                    .into_iter()
+                    .filter(|reminder| !reminder.completed)
+                    .collect::<Vec<_>>();
+                reminders.sort_by_key(ReminderModel::trigger_at);
+                return Ok(reminders);
+            }
+        };
+
+        query_as::<_, ReminderEntity>(
+            "SELECT id, document_id, title, due_at, recurrence, snoozed_until, completed \
+             FROM reminders WHERE completed = 0 ORDER BY COALESCE(snoozed_until, due_at) ASC",
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(anyhow::Error::from)
+        .map(|reminders| {
+            reminders
+                .into_iter()
+                .map(ReminderEntity::into)
+                .collect::<Vec<ReminderModel>>()
+        })
+    }
+
+    pub async fn insert_reminder(&self, reminder: ReminderModel) -> Result<i32, Error> {
+        let pool = match &self.backend {
+            StorageBackend::Sqlite(pool) => pool,
+            StorageBackend::Vault(vault) => return vault.insert_reminder(reminder).await,
+        };
+
+        let result = query(
+            "INSERT INTO reminders (document_id, title, due_at, recurrence, snoozed_until, completed) \
+             VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(reminder.document_id)
+        .bind(reminder.title)
+        .bind(reminder.due_at)
+        .bind(reminder.recurrence)
+        .bind(reminder.snoozed_until)
+        .bind(reminder.completed)
+        .execute(pool)
+        .await
+        .map_err(anyhow::Error::from)?;
+
+        Ok(result.last_insert_rowid() as i32)
+    }
+
+    pub async fn update_reminder(&self, reminder: ReminderModel) -> Result<(), Error> {
+        let pool = match &self.backend {
+            StorageBackend::Sqlite(pool) => pool,
+            StorageBackend::Vault(vault) => return vault.update_reminder(reminder).await,
+        };
+
+        query(
+            "UPDATE reminders SET title = ?, due_at = ?, recurrence = ?, snoozed_until = ?, \
+             completed = ? WHERE id = ?",
+        )
+        .bind(reminder.title)
+        .bind(reminder.due_at)
+        .bind(reminder.recurrence)
+        .bind(reminder.snoozed_until)
+        .bind(reminder.completed)
+        .bind(reminder.id)
+        .execute(pool)
+        .await
+        .map_err(anyhow::Error::from)?;
+
+        Ok(())
+    }
+
+    pub async fn delete_reminder(&self, id: i32) -> Result<(), Error> {
+        let pool = match &self.backend {
+            StorageBackend::Sqlite(pool) => pool,
+            StorageBackend::Vault(vault) => return vault.delete_reminder(id).await,
+        };
+
+        query("DELETE FROM reminders WHERE id = ?")
+            .bind(id)
+            .execute(pool)
+            .await
+            .map_err(anyhow::Error::from)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,106 @@
+use anyhow::Error;
+use sqlx::{SqlitePool, query, query_as};
+
+use crate::{
+    domain::database::smart_folder::SmartFolderModel,
+    infrastructure::{
+        entities::SmartFolderEntity, repositories::StorageBackend, vault::file_vault::FileVault,
+    },
+};
+
+#[derive(Clone)]
+pub struct SmartFolderRepository {
+    backend: StorageBackend,
+}
+
+impl SmartFolderRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            backend: StorageBackend::Sqlite(pool),
+        }
+    }
+
+    pub fn with_vault(vault: FileVault) -> Self {
+        Self {
+            backend: StorageBackend::Vault(vault),
+        }
+    }
+
+    pub async fn get_smart_folders(&self) -> Result<Vec<SmartFolderModel>, Error> {
+        let pool = match &self.backend {
+            StorageBackend::Sqlite(pool) => pool,
+            StorageBackend::Vault(vault) => return vault.get_smart_folders().await,
+        };
+
+        query_as::<_, SmartFolderEntity>(
+            "SELECT id, name, query FROM smart_folders ORDER BY id ASC",
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(anyhow::Error::from)
+        .map(|smart_folders| {
+            smart_folders
+                .into_iter()
+                .map(SmartFolderEntity::into)
+                .collect::<Vec<SmartFolderModel>>()
+        })
+    }
+
+    pub async fn insert_smart_folder(
+        &self,
+        name: String,
+        query_text: String,
+    ) -> Result<SmartFolderModel, Error> {
+        let pool = match &self.backend {
+            StorageBackend::Sqlite(pool) => pool,
+            StorageBackend::Vault(vault) => {
+                return vault.insert_smart_folder(name, query_text).await;
+            }
+        };
+
+        let result = query("INSERT INTO smart_folders (name, query) VALUES (?, ?)")
+            .bind(&name)
+            .bind(&query_text)
+            .execute(pool)
+            .await
+            .map_err(anyhow::Error::from)?;
+
+        Ok(SmartFolderModel {
+            id: result.last_insert_rowid() as i32,
+            name,
+            query: query_text,
+        })
+    }
+
+    pub async fn update_smart_folder(&self, smart_folder: SmartFolderModel) -> Result<(), Error> {
+        let pool = match &self.backend {
+            StorageBackend::Sqlite(pool) => pool,
+            StorageBackend::Vault(vault) => return vault.update_smart_folder(smart_folder).await,
+        };
+
+        query("UPDATE smart_folders SET name = ?, query = ? WHERE id = ?")
+            .bind(smart_folder.name)
+            .bind(smart_folder.query)
+            .bind(smart_folder.id)
+            .execute(pool)
+            .await
+            .map_err(anyhow::Error::from)?;
+
+        Ok(())
+    }
+
+    pub async fn delete_smart_folder(&self, id: i32) -> Result<(), Error> {
+        let pool = match &self.backend {
+            StorageBackend::Sqlite(pool) => pool,
+            StorageBackend::Vault(vault) => return vault.delete_smart_folder(id).await,
+        };
+
+        query("DELETE FROM smart_folders WHERE id = ?")
+            .bind(id)
+            .execute(pool)
+            .await
+            .map_err(anyhow::Error::from)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,94 @@
+use anyhow::Error;
+use sqlx::{SqlitePool, query, query_as};
+
+use crate::{
+    domain::database::synced_block::SyncedBlockModel,
+    infrastructure::{
+        entities::SyncedBlockEntity, repositories::StorageBackend, vault::file_vault::FileVault,
+    },
+};
+
+#[derive(Clone)]
+pub struct SyncedBlockRepository {
+    backend: StorageBackend,
+}
+
+impl SyncedBlockRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            backend: StorageBackend::Sqlite(pool),
+        }
+    }
+
+    pub fn with_vault(vault: FileVault) -> Self {
+        Self {
+            backend: StorageBackend::Vault(vault),
+        }
+    }
+
+    pub async fn get_synced_blocks(&self) -> Result<Vec<SyncedBlockModel>, Error> {
+        let pool = match &self.backend {
+            StorageBackend::Sqlite(pool) => pool,
+            StorageBackend::Vault(vault) => return vault.get_synced_blocks().await,
+        };
+
+        query_as::<_, SyncedBlockEntity>("SELECT id, content FROM synced_blocks ORDER BY id ASC")
+            .fetch_all(pool)
+            .await
+            .map_err(anyhow::Error::from)
+            .map(|blocks| {
+                blocks
+                    .into_iter()
+                    .map(SyncedBlockEntity::into)
+                    .collect::<Vec<SyncedBlockModel>>()
+            })
+    }
+
+    pub async fn get_synced_block(&self, id: i32) -> Result<SyncedBlockModel, Error> {
+        let pool = match &self.backend {
+            StorageBackend::Sqlite(pool) => pool,
+            StorageBackend::Vault(vault) => return vault.get_synced_block(id).await,
+        };
+
+        query_as::<_, SyncedBlockEntity>("SELECT id, content FROM synced_blocks WHERE id = ?")
+            .bind(id)
+            .fetch_one(pool)
+            .await
+            .map(SyncedBlockEntity::into)
+            .map_err(anyhow::Error::from)
+    }
+
+    pub async fn insert_synced_block(&self, content: String) -> Result<SyncedBlockModel, Error> {
+        let pool = match &self.backend {
+            StorageBackend::Sqlite(pool) => pool,
+            StorageBackend::Vault(vault) => return vault.insert_synced_block(content).await,
+        };
+
+        let result = query("INSERT INTO synced_blocks (content) VALUES (?)")
+            .bind(&content)
+            .execute(pool)
+            .await
+            .map_err(anyhow::Error::from)?;
+
+        Ok(SyncedBlockModel {
+            id: result.last_insert_rowid() as i32,
+            content,
+        })
+    }
+
+    pub async fn update_synced_block(&self, block: SyncedBlockModel) -> Result<(), Error> {
+        let pool = match &self.backend {
+            StorageBackend::Sqlite(pool) => pool,
+            StorageBackend::Vault(vault) => return vault.update_synced_block(block).await,
+        };
+
+        query("UPDATE synced_blocks SET content = ? WHERE id = ?")
+            .bind(block.content)
+            .bind(block.id)
+            .execute(pool)
+            .await
+            .map_err(anyhow::Error::from)?;
+
+        Ok(())
+    }
+}
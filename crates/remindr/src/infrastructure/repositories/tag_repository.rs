@@ -0,0 +1,82 @@
+use anyhow::Error;
+use sqlx::{SqlitePool, query, query_as};
+
+use crate::{
+    domain::database::tag::DocumentTagModel,
+    infrastructure::{
+        entities::DocumentTagEntity, repositories::StorageBackend, vault::file_vault::FileVault,
+    },
+};
+
+#[derive(Clone)]
+pub struct TagRepository {
+    backend: StorageBackend,
+}
+
+impl TagRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            backend: StorageBackend::Sqlite(pool),
+        }
+    }
+
+    pub fn with_vault(vault: FileVault) -> Self {
+        Self {
+            backend: StorageBackend::Vault(vault),
+        }
+    }
+
+    pub async fn get_document_tags(&self) -> Result<Vec<DocumentTagModel>, Error> {
+        let pool = match &self.backend {
+            StorageBackend::Sqlite(pool) => pool,
+            StorageBackend::Vault(vault) => return vault.get_document_tags().await,
+        };
+
+        query_as::<_, DocumentTagEntity>(
+            "SELECT document_id, tag FROM document_tags ORDER BY tag ASC",
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(anyhow::Error::from)
+        .map(|tags| {
+            tags.into_iter()
+                .map(DocumentTagEntity::into)
+                .collect::<Vec<DocumentTagModel>>()
+        })
+    }
+
+    /// Adds a tag to each of the given documents, ignoring the ones already tagged.
+    pub async fn add_tag(&self, document_ids: Vec<i32>, tag: String) -> Result<(), Error> {
+        let pool = match &self.backend {
+            StorageBackend::Sqlite(pool) => pool,
+            StorageBackend::Vault(vault) => return vault.add_tag(document_ids, tag).await,
+        };
+
+        for document_id in document_ids {
+            query("INSERT OR IGNORE INTO document_tags (document_id, tag) VALUES (?, ?)")
+                .bind(document_id)
+                .bind(&tag)
+                .execute(pool)
+                .await
+                .map_err(anyhow::Error::from)?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn remove_tag(&self, document_id: i32, tag: String) -> Result<(), Error> {
+        let pool = match &self.backend {
+            StorageBackend::Sqlite(pool) => pool,
+            StorageBackend::Vault(vault) => return vault.remove_tag(document_id, tag).await,
+        };
+
+        query("DELETE FROM document_tags WHERE document_id = ? AND tag = ?")
+            .bind(document_id)
+            .bind(tag)
+            .execute(pool)
+            .await
+            .map_err(anyhow::Error::from)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,87 @@
+use anyhow::Error;
+use sqlx::{SqliteConnection, SqlitePool, query, query_as};
+
+use crate::{
+    domain::database::task::TaskModel,
+    infrastructure::{
+        entities::TaskEntity, repositories::StorageBackend, vault::file_vault::FileVault,
+    },
+};
+
+#[derive(Clone)]
+pub struct TaskRepository {
+    backend: StorageBackend,
+}
+
+impl TaskRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            backend: StorageBackend::Sqlite(pool),
+        }
+    }
+
+    pub fn with_vault(vault: FileVault) -> Self {
+        Self {
+            backend: StorageBackend::Vault(vault),
+        }
+    }
+
+    /// Open tasks of every document, the ones due soonest first and undated ones last.
+    pub async fn get_open_tasks(&self) -> Result<Vec<TaskModel>, Error> {
+        let pool = match &self.backend {
+            StorageBackend::Sqlite(pool) => pool,
+            StorageBackend::Vault(vault) => {
+                // Vault documents are plain files, their tasks are read from them directly
+                let mut tasks = vault
+                    .get_documents()
+                    .await?
+                    .iter()
+                    .flat_map(|document| TaskModel::from_document(document.id, &document.content))
+                    .collect::<Vec<_>>();
+                tasks.sort_by_key(|task| (task.due_date.is_none(), task.due_date));
+                return Ok(tasks);
+            }
+        };
+
+        query_as::<_, TaskEntity>(
+            "SELECT document_id, block_id, content, due_date FROM tasks \
+             ORDER BY due_date IS NULL, due_date ASC, document_id ASC",
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(anyhow::Error::from)
+        .map(|tasks| {
+            tasks
+                .into_iter()
+                .map(TaskEntity::into)
+                .collect::<Vec<TaskModel>>()
+        })
+    }
+}
+
+/// Replaces the indexed tasks of a document, called whenever the document is saved.
+pub(crate) async fn index_tasks(
+    connection: &mut SqliteConnection,
+    document_id: i32,
+    tasks: Vec<TaskModel>,
+) -> Result<(), Error> {
+    query("DELETE FROM tasks WHERE document_id = ?")
+        .bind(document_id)
+        .execute(&mut *connection)
+        .await?;
+
+    for task in tasks {
+        query(
+            "INSERT OR REPLACE INTO tasks (document_id, block_id, content, due_date) \
+             VALUES (?, ?, ?, ?)",
+        )
+        .bind(task.document_id)
+        .bind(task.block_id)
+        .bind(task.content)
+        .bind(task.due_date)
+        .execute(&mut *connection)
+        .await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,1646 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt, fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
+
+use anyhow::{Context, Error, anyhow};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    domain::{
+        database::{
+            attachment::AttachmentModel,
+            collection::CollectionView,
+            document::{DocumentLocation, DocumentModel, StoredDocument},
+            folder::FolderModel,
+            reminder::ReminderModel,
+            smart_folder::SmartFolderModel,
+            synced_block::SyncedBlockModel,
+            tag::DocumentTagModel,
+        },
+        ports::{BoxFuture, DocumentRepositoryPort, FolderRepositoryPort},
+    },
+    infrastructure::repositories::folder_repository::{check_insert_depth, check_move_depth},
+};
+
+/// Extension of the document files stored in a vault.
+const DOCUMENT_EXTENSION: &str = "json";
+
+/// Hidden file inside each folder directory holding the folder id.
+const FOLDER_MARKER: &str = ".folder.json";
+
+/// Hidden file at the root of the vault holding the reminders of every document.
+const REMINDERS_FILE: &str = ".reminders.json";
+
+/// Hidden file at the root of the vault holding the view of the folders displayed as
+/// collections.
+const COLLECTIONS_FILE: &str = ".collections.json";
+
+/// Hidden file at the root of the vault holding the template new documents of each folder
+/// start from.
+const FOLDER_TEMPLATES_FILE: &str = ".folder_templates.json";
+
+/// Hidden file at the root of the vault holding the saved searches shown as smart folders.
+const SMART_FOLDERS_FILE: &str = ".smart_folders.json";
+
+/// Hidden file at the root of the vault holding the content of the synced blocks.
+const SYNCED_BLOCKS_FILE: &str = ".synced_blocks.json";
+
+/// Hidden directory at the root of the vault holding one sub-directory per attachment, named
+/// after its id.
+const ATTACHMENTS_DIR: &str = ".attachments";
+
+/// Returned when saving a document whose file was modified outside Remindr since it was
+/// last read, so that the other version is not silently overwritten.
+#[derive(Debug)]
+pub struct ExternalChangeConflict {
+    pub id: i32,
+}
+
+impl fmt::Display for ExternalChangeConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Document {} was modified outside Remindr", self.id)
+    }
+}
+
+impl std::error::Error for ExternalChangeConflict {}
+
+/// On-disk representation of a document.
+#[derive(Serialize, Deserialize)]
+struct VaultDocument {
+    #[serde(default)]
+    id: Option<i32>,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    content: Value,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct FolderMarker {
+    id: i32,
+}
+
+/// A document read from the file the index points to.
+struct IndexedDocument {
+    model: DocumentModel,
+    tags: Vec<String>,
+    path: PathBuf,
+}
+
+/// Where each folder and document of the vault lives, by id.
+///
+/// Reads and writes look entries up here rather than walking the vault; the watcher's pass
+/// picks up the files added, moved or deleted outside Remindr.
+#[derive(Default)]
+struct VaultIndex {
+    folders: HashMap<i32, PathBuf>,
+    documents: HashMap<i32, PathBuf>,
+}
+
+impl VaultIndex {
+    /// Id of the folder stored in `directory`, `None` for the root of the vault.
+    fn folder_id(&self, directory: &Path) -> Option<i32> {
+        self.folders
+            .iter()
+            .find(|(_, path)| path.as_path() == directory)
+            .map(|(id, _)| *id)
+    }
+
+    fn folder(&self, id: i32, path: &Path) -> FolderModel {
+        FolderModel {
+            id,
+            name: file_name(path),
+            parent_id: path.parent().and_then(|parent| self.folder_id(parent)),
+        }
+    }
+
+    fn next_id(&self) -> i32 {
+        self.folders
+            .keys()
+            .chain(self.documents.keys())
+            .max()
+            .copied()
+            .unwrap_or(0)
+            + 1
+    }
+
+    /// Points the entries at or under `from` to their new location under `to`.
+    fn moved(&mut self, from: &Path, to: &Path) {
+        for path in self.folders.values_mut().chain(self.documents.values_mut()) {
+            if let Ok(relative) = path.strip_prefix(from) {
+                *path = if relative.as_os_str().is_empty() {
+                    to.to_path_buf()
+                } else {
+                    to.join(relative)
+                };
+            }
+        }
+    }
+
+    /// Drops the entries at or under `path`.
+    fn removed(&mut self, path: &Path) {
+        self.folders.retain(|_, other| !other.starts_with(path));
+        self.documents.retain(|_, other| !other.starts_with(path));
+    }
+}
+
+/// Storage backend keeping each document as a JSON file inside a user-chosen folder.
+///
+/// Folders map to directories, so the vault can be versioned with Git or synced with Dropbox.
+/// Ids live inside the files (and in a hidden marker for folders); files created outside
+/// Remindr are given one the first time the vault is opened or polled after they appear.
+#[derive(Clone)]
+pub struct FileVault {
+    root: PathBuf,
+    /// Modification time of each document file as last written or observed by Remindr.
+    known_files: Arc<Mutex<HashMap<PathBuf, SystemTime>>>,
+    /// Location of every folder and document, refreshed by `poll_external_changes`.
+    index: Arc<Mutex<VaultIndex>>,
+}
+
+impl FileVault {
+    pub fn open(root: impl Into<PathBuf>) -> Result<Self, Error> {
+        let root = root.into();
+        fs::create_dir_all(&root).with_context(|| format!("Failed to create {:?}", root))?;
+        // Canonical, so paths can be compared with the ones reported by external tools
+        let root = root.canonicalize()?;
+
+        let vault = Self {
+            root,
+            known_files: Arc::default(),
+            index: Arc::default(),
+        };
+        let modified = vault.record_modified_files()?;
+        vault.refresh_index(&modified)?;
+
+        Ok(vault)
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Id, title and file of every document in the vault.
+    pub fn document_paths(&self) -> Result<Vec<(i32, String, PathBuf)>, Error> {
+        Ok(self
+            .documents()
+            .into_iter()
+            .map(|document| (document.model.id, document.model.title, document.path))
+            .collect())
+    }
+
+    /// Every document file of the vault as written, including the ones that can't be read.
+    pub async fn get_stored_documents(&self) -> Result<Vec<StoredDocument>, Error> {
+        self.blocking(|vault| {
+            let mut files = Vec::new();
+            collect_document_files(&vault.root, &mut files)?;
+            files.sort();
+
+            files
+                .into_iter()
+                .map(|path| {
+                    let text = fs::read_to_string(&path)
+                        .with_context(|| format!("Failed to read {:?}", path))?;
+                    let title = serde_json::from_str::<VaultDocument>(&text)
+                        .ok()
+                        .map(|document| document.title)
+                        .filter(|title| !title.is_empty())
+                        .unwrap_or_else(|| {
+                            path.file_stem()
+                                .map(|stem| stem.to_string_lossy().to_string())
+                                .unwrap_or_default()
+                        });
+                    let relative = path.strip_prefix(&vault.root).unwrap_or(&path);
+                    Ok(StoredDocument {
+                        location: DocumentLocation::File(relative.to_path_buf()),
+                        title,
+                        content: stored_content(&text),
+                    })
+                })
+                .collect()
+        })
+        .await
+    }
+
+    /// File of a document, relative to the root of the vault.
+    pub async fn document_location(&self, id: i32) -> Result<DocumentLocation, Error> {
+        self.blocking(move |vault| {
+            let path = vault.document_path(id)?;
+            let relative = path.strip_prefix(&vault.root).unwrap_or(&path);
+            Ok(DocumentLocation::File(relative.to_path_buf()))
+        })
+        .await
+    }
+
+    /// Writes a document file back as it was stored, e.g. from a backup, and returns the id
+    /// of its document.
+    pub async fn restore_file(&self, file: PathBuf, data: Vec<u8>) -> Result<i32, Error> {
+        self.blocking(move |vault| {
+            let path = vault.root.join(file);
+            fs::write(&path, data).with_context(|| format!("Failed to write {:?}", path))?;
+            vault.remember_file(&path);
+
+            // The broken file may not have been readable, and so missing from the index
+            vault.refresh_index(&HashSet::from([path.clone()]))?;
+            vault
+                .index
+                .lock()
+                .unwrap()
+                .documents
+                .iter()
+                .find(|(_, other)| **other == path)
+                .map(|(id, _)| *id)
+                .context("The restored file isn't a document")
+        })
+        .await
+    }
+
+    /// When the file of the document was last modified.
+    pub async fn updated_at(&self, id: i32) -> Result<Option<SystemTime>, Error> {
+        self.blocking(move |vault| {
+            let path = vault.document_path(id)?;
+            Ok(fs::metadata(&path).and_then(|meta| meta.modified()).ok())
+        })
+        .await
+    }
+
+    pub async fn get_documents(&self) -> Result<Vec<DocumentModel>, Error> {
+        self.blocking(|vault| {
+            Ok(vault
+                .documents()
+                .into_iter()
+                .map(|document| document.model)
+                .collect())
+        })
+        .await
+    }
+
+    pub async fn get_document(&self, id: i32) -> Result<DocumentModel, Error> {
+        self.blocking(move |vault| Ok(vault.document(id)?.model))
+            .await
+    }
+
+    pub async fn insert_document(&self, document: DocumentModel) -> Result<i32, Error> {
+        self.blocking(move |vault| {
+            // Held until the document is indexed, so concurrent inserts get different ids
+            let mut index = vault.index.lock().unwrap();
+            let id = index.next_id();
+            let directory = match document.folder_id {
+                Some(folder_id) => index
+                    .folders
+                    .get(&folder_id)
+                    .cloned()
+                    .ok_or_else(|| anyhow!("Folder {} not found in vault", folder_id))?,
+                None => vault.root.clone(),
+            };
+
+            let path = unique_path(&directory, &document.title, Some(DOCUMENT_EXTENSION));
+            vault.write_document(
+                &path,
+                &VaultDocument {
+                    id: Some(id),
+                    title: document.title,
+                    content: document.content,
+                    tags: Vec::new(),
+                },
+            )?;
+            index.documents.insert(id, path);
+
+            Ok(id)
+        })
+        .await
+    }
+
+    /// Writes the title and content of a document in place.
+    ///
+    /// Files keep their name and location so external history stays readable; moves go
+    /// through `move_document`.
+    pub async fn update_document(&self, document: DocumentModel) -> Result<(), Error> {
+        self.blocking(move |vault| {
+            let path = vault.document_path(document.id)?;
+
+            if vault.modified_externally(&path) {
+                // The conflict is reported once: the next save overwrites the file
+                vault.remember_file(&path);
+                return Err(ExternalChangeConflict { id: document.id }.into());
+            }
+
+            // Read again so the tags edited since the last save are kept
+            let tags = read_document(&path)?.tags;
+            vault.write_document(
+                &path,
+                &VaultDocument {
+                    id: Some(document.id),
+                    title: document.title,
+                    content: document.content,
+                    tags,
+                },
+            )
+        })
+        .await
+    }
+
+    pub async fn move_document(&self, id: i32, folder_id: Option<i32>) -> Result<(), Error> {
+        self.blocking(move |vault| {
+            let directory = vault.directory(folder_id)?;
+            vault.move_file(&vault.document_path(id)?, &directory)
+        })
+        .await
+    }
+
+    pub async fn delete_document(&self, id: i32) -> Result<(), Error> {
+        self.blocking(move |vault| {
+            let path = vault.document_path(id)?;
+            fs::remove_file(&path).with_context(|| format!("Failed to delete {:?}", path))?;
+            vault.known_files.lock().unwrap().remove(&path);
+            vault.index.lock().unwrap().documents.remove(&id);
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn get_document_tags(&self) -> Result<Vec<DocumentTagModel>, Error> {
+        self.blocking(|vault| {
+            Ok(vault
+                .documents()
+                .into_iter()
+                .flat_map(|document| {
+                    let document_id = document.model.id;
+                    document
+                        .tags
+                        .into_iter()
+                        .map(move |tag| DocumentTagModel { document_id, tag })
+                })
+                .collect())
+        })
+        .await
+    }
+
+    /// Tags are stored in the document files, next to the content.
+    pub async fn add_tag(&self, document_ids: Vec<i32>, tag: String) -> Result<(), Error> {
+        self.blocking(move |vault| {
+            for id in document_ids {
+                vault.edit_tags(&vault.document_path(id)?, |tags| {
+                    if !tags.contains(&tag) {
+                        tags.push(tag.clone());
+                    }
+                })?;
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn remove_tag(&self, document_id: i32, tag: String) -> Result<(), Error> {
+        self.blocking(move |vault| {
+            vault.edit_tags(&vault.document_path(document_id)?, |tags| {
+                tags.retain(|other| *other != tag)
+            })
+        })
+        .await
+    }
+
+    /// Reminders of documents that are still in the vault.
+    pub async fn get_reminders(&self) -> Result<Vec<ReminderModel>, Error> {
+        self.blocking(|vault| {
+            let reminders = vault.read_reminders()?;
+            let index = vault.index.lock().unwrap();
+            Ok(reminders
+                .into_iter()
+                .filter(|reminder| index.documents.contains_key(&reminder.document_id))
+                .collect())
+        })
+        .await
+    }
+
+    pub async fn insert_reminder(&self, mut reminder: ReminderModel) -> Result<i32, Error> {
+        self.blocking(move |vault| {
+            let mut reminders = vault.read_reminders()?;
+            reminder.id = reminders.iter().map(|other| other.id).max().unwrap_or(0) + 1;
+            let id = reminder.id;
+            reminders.push(reminder);
+            vault.write_reminders(&reminders)?;
+            Ok(id)
+        })
+        .await
+    }
+
+    pub async fn update_reminder(&self, reminder: ReminderModel) -> Result<(), Error> {
+        self.blocking(move |vault| {
+            let mut reminders = vault.read_reminders()?;
+            let existing = reminders
+                .iter_mut()
+                .find(|other| other.id == reminder.id)
+                .ok_or_else(|| anyhow!("Reminder {} not found in vault", reminder.id))?;
+            *existing = reminder;
+            vault.write_reminders(&reminders)
+        })
+        .await
+    }
+
+    pub async fn delete_reminder(&self, id: i32) -> Result<(), Error> {
+        self.blocking(move |vault| {
+            let mut reminders = vault.read_reminders()?;
+            reminders.retain(|reminder| reminder.id != id);
+            vault.write_reminders(&reminders)
+        })
+        .await
+    }
+
+    pub async fn insert_attachment(
+        &self,
+        file_name: String,
+        data: Vec<u8>,
+    ) -> Result<AttachmentModel, Error> {
+        self.blocking(move |vault| {
+            let directory = vault.root.join(ATTACHMENTS_DIR);
+            fs::create_dir_all(&directory)
+                .with_context(|| format!("Failed to create {:?}", directory))?;
+
+            let id = fs::read_dir(&directory)?
+                .filter_map(Result::ok)
+                .filter_map(|entry| entry.file_name().to_string_lossy().parse::<i32>().ok())
+                .max()
+                .unwrap_or(0)
+                + 1;
+
+            let directory = directory.join(id.to_string());
+            fs::create_dir_all(&directory)
+                .with_context(|| format!("Failed to create {:?}", directory))?;
+            let path = directory.join(attachment_file_name(&file_name));
+            fs::write(&path, &data).with_context(|| format!("Failed to write {:?}", path))?;
+
+            Ok(AttachmentModel {
+                id,
+                file_name,
+                size: data.len() as i64,
+            })
+        })
+        .await
+    }
+
+    pub async fn get_attachment(&self, id: i32) -> Result<AttachmentModel, Error> {
+        self.blocking(move |vault| {
+            let path = vault.find_attachment(id)?;
+            Ok(AttachmentModel {
+                id,
+                file_name: file_name(&path),
+                size: fs::metadata(&path)?.len() as i64,
+            })
+        })
+        .await
+    }
+
+    pub async fn attachment_path(&self, id: i32) -> Result<PathBuf, Error> {
+        self.blocking(move |vault| vault.find_attachment(id)).await
+    }
+
+    pub async fn get_attachments(&self) -> Result<Vec<AttachmentModel>, Error> {
+        self.blocking(|vault| {
+            let directory = vault.root.join(ATTACHMENTS_DIR);
+            if !directory.exists() {
+                return Ok(Vec::new());
+            }
+
+            let mut attachments = Vec::new();
+            for entry in fs::read_dir(&directory)
+                .with_context(|| format!("Failed to read {:?}", directory))?
+                .filter_map(Result::ok)
+            {
+                let Ok(id) = file_name(&entry.path()).parse::<i32>() else {
+                    continue;
+                };
+                if let Ok(path) = vault.find_attachment(id) {
+                    attachments.push(AttachmentModel {
+                        id,
+                        file_name: file_name(&path),
+                        size: fs::metadata(&path)?.len() as i64,
+                    });
+                }
+            }
+            attachments.sort_by_key(|attachment| attachment.id);
+            Ok(attachments)
+        })
+        .await
+    }
+
+    pub async fn delete_attachments(&self, ids: Vec<i32>) -> Result<(), Error> {
+        self.blocking(move |vault| {
+            for id in ids {
+                let directory = vault.root.join(ATTACHMENTS_DIR).join(id.to_string());
+                if directory.exists() {
+                    fs::remove_dir_all(&directory)
+                        .with_context(|| format!("Failed to delete {:?}", directory))?;
+                }
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn get_synced_blocks(&self) -> Result<Vec<SyncedBlockModel>, Error> {
+        self.blocking(|vault| vault.read_synced_blocks()).await
+    }
+
+    pub async fn get_synced_block(&self, id: i32) -> Result<SyncedBlockModel, Error> {
+        self.blocking(move |vault| {
+            vault
+                .read_synced_blocks()?
+                .into_iter()
+                .find(|block| block.id == id)
+                .ok_or_else(|| anyhow!("Synced block {} not found in vault", id))
+        })
+        .await
+    }
+
+    pub async fn insert_synced_block(&self, content: String) -> Result<SyncedBlockModel, Error> {
+        self.blocking(move |vault| {
+            let mut blocks = vault.read_synced_blocks()?;
+            let block = SyncedBlockModel {
+                id: blocks.iter().map(|other| other.id).max().unwrap_or(0) + 1,
+                content,
+            };
+            blocks.push(block.clone());
+            vault.write_synced_blocks(&blocks)?;
+            Ok(block)
+        })
+        .await
+    }
+
+    pub async fn update_synced_block(&self, block: SyncedBlockModel) -> Result<(), Error> {
+        self.blocking(move |vault| {
+            let mut blocks = vault.read_synced_blocks()?;
+            let existing = blocks
+                .iter_mut()
+                .find(|other| other.id == block.id)
+                .ok_or_else(|| anyhow!("Synced block {} not found in vault", block.id))?;
+            *existing = block;
+            vault.write_synced_blocks(&blocks)
+        })
+        .await
+    }
+
+    pub async fn get_collection_view(&self, folder_id: i32) -> Result<CollectionView, Error> {
+        self.blocking(move |vault| {
+            Ok(vault
+                .read_collection_views()?
+                .remove(&folder_id)
+                .unwrap_or_default())
+        })
+        .await
+    }
+
+    pub async fn save_collection_view(
+        &self,
+        folder_id: i32,
+        view: CollectionView,
+    ) -> Result<(), Error> {
+        self.blocking(move |vault| {
+            let mut views = vault.read_collection_views()?;
+            views.insert(folder_id, view);
+
+            let path = vault.root.join(COLLECTIONS_FILE);
+            fs::write(&path, serde_json::to_string_pretty(&views)?)
+                .with_context(|| format!("Failed to write {:?}", path))
+        })
+        .await
+    }
+
+    pub async fn get_folder_template(&self, folder_id: i32) -> Result<Option<i32>, Error> {
+        self.blocking(move |vault| Ok(vault.read_folder_templates()?.remove(&folder_id)))
+            .await
+    }
+
+    pub async fn set_folder_template(
+        &self,
+        folder_id: i32,
+        template_id: Option<i32>,
+    ) -> Result<(), Error> {
+        self.blocking(move |vault| {
+            let mut templates = vault.read_folder_templates()?;
+            match template_id {
+                Some(template_id) => templates.insert(folder_id, template_id),
+                None => templates.remove(&folder_id),
+            };
+
+            let path = vault.root.join(FOLDER_TEMPLATES_FILE);
+            fs::write(&path, serde_json::to_string_pretty(&templates)?)
+                .with_context(|| format!("Failed to write {:?}", path))
+        })
+        .await
+    }
+
+    pub async fn get_smart_folders(&self) -> Result<Vec<SmartFolderModel>, Error> {
+        self.blocking(|vault| vault.read_smart_folders()).await
+    }
+
+    pub async fn insert_smart_folder(
+        &self,
+        name: String,
+        query: String,
+    ) -> Result<SmartFolderModel, Error> {
+        self.blocking(move |vault| {
+            let mut smart_folders = vault.read_smart_folders()?;
+            let smart_folder = SmartFolderModel {
+                id: smart_folders
+                    .iter()
+                    .map(|other| other.id)
+                    .max()
+                    .unwrap_or(0)
+                    + 1,
+                name,
+                query,
+            };
+            smart_folders.push(smart_folder.clone());
+            vault.write_smart_folders(&smart_folders)?;
+            Ok(smart_folder)
+        })
+        .await
+    }
+
+    pub async fn update_smart_folder(&self, smart_folder: SmartFolderModel) -> Result<(), Error> {
+        self.blocking(move |vault| {
+            let mut smart_folders = vault.read_smart_folders()?;
+            let existing = smart_folders
+                .iter_mut()
+                .find(|other| other.id == smart_folder.id)
+                .ok_or_else(|| anyhow!("Smart folder {} not found in vault", smart_folder.id))?;
+            *existing = smart_folder;
+            vault.write_smart_folders(&smart_folders)
+        })
+        .await
+    }
+
+    pub async fn delete_smart_folder(&self, id: i32) -> Result<(), Error> {
+        self.blocking(move |vault| {
+            let mut smart_folders = vault.read_smart_folders()?;
+            smart_folders.retain(|smart_folder| smart_folder.id != id);
+            vault.write_smart_folders(&smart_folders)
+        })
+        .await
+    }
+
+    pub async fn get_folders(&self) -> Result<Vec<FolderModel>, Error> {
+        self.blocking(|vault| {
+            let index = vault.index.lock().unwrap();
+            let mut folders = index
+                .folders
+                .iter()
+                .map(|(id, path)| index.folder(*id, path))
+                .collect::<Vec<_>>();
+            folders.sort_by(|a, b| a.name.cmp(&b.name));
+            Ok(folders)
+        })
+        .await
+    }
+
+    pub async fn get_folder(&self, id: i32) -> Result<FolderModel, Error> {
+        self.blocking(move |vault| {
+            let path = vault.folder_path(id)?;
+            Ok(vault.index.lock().unwrap().folder(id, &path))
+        })
+        .await
+    }
+
+    pub async fn insert_folder(&self, name: String, parent_id: Option<i32>) -> Result<i32, Error> {
+        self.blocking(move |vault| {
+            // Held until the folder is indexed, so concurrent inserts get different ids
+            let mut index = vault.index.lock().unwrap();
+            let id = index.next_id();
+            let parent = match parent_id {
+                Some(parent_id) => index
+                    .folders
+                    .get(&parent_id)
+                    .cloned()
+                    .ok_or_else(|| anyhow!("Folder {} not found in vault", parent_id))?,
+                None => vault.root.clone(),
+            };
+
+            let path = unique_path(&parent, &name, None);
+            fs::create_dir_all(&path).with_context(|| format!("Failed to create {:?}", path))?;
+            write_folder_marker(&path, id)?;
+            index.folders.insert(id, path);
+
+            Ok(id)
+        })
+        .await
+    }
+
+    pub async fn rename_folder(&self, id: i32, name: String) -> Result<(), Error> {
+        self.blocking(move |vault| {
+            let path = vault.folder_path(id)?;
+            if file_name(&path) == sanitize_file_name(&name) {
+                return Ok(());
+            }
+
+            let parent = path.parent().unwrap_or(&vault.root);
+            let target = unique_path(parent, &name, None);
+
+            fs::rename(&path, &target).with_context(|| format!("Failed to rename {:?}", path))?;
+            vault.forget_files_under(&path);
+            vault.remember_files_under(&target);
+            vault.index.lock().unwrap().moved(&path, &target);
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn move_folder(&self, id: i32, parent_id: Option<i32>) -> Result<(), Error> {
+        self.blocking(move |vault| {
+            let path = vault.folder_path(id)?;
+            let parent = vault.directory(parent_id)?;
+            if parent.starts_with(&path) {
+                return Err(anyhow!("Cannot move a folder into itself"));
+            }
+
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let target = unique_path(&parent, &name, None);
+
+            fs::rename(&path, &target).with_context(|| format!("Failed to move {:?}", path))?;
+            vault.forget_files_under(&path);
+            vault.remember_files_under(&target);
+            vault.index.lock().unwrap().moved(&path, &target);
+            Ok(())
+        })
+        .await
+    }
+
+    /// Deletes a folder and its sub-folders. Their documents are moved to the vault root,
+    /// matching the `ON DELETE SET NULL` behavior of the database backend.
+    pub async fn delete_folder(&self, id: i32) -> Result<(), Error> {
+        self.blocking(move |vault| {
+            let path = vault.folder_path(id)?;
+
+            let documents = vault
+                .index
+                .lock()
+                .unwrap()
+                .documents
+                .values()
+                .filter(|document| document.starts_with(&path))
+                .cloned()
+                .collect::<Vec<_>>();
+            for document in documents {
+                vault.move_file(&document, &vault.root)?;
+            }
+
+            fs::remove_dir_all(&path).with_context(|| format!("Failed to delete {:?}", path))?;
+            vault.forget_files_under(&path);
+            vault.index.lock().unwrap().removed(&path);
+            Ok(())
+        })
+        .await
+    }
+
+    /// Deletes a folder but moves its documents and sub-folders to its parent.
+    pub async fn delete_folder_keep_children(&self, id: i32) -> Result<(), Error> {
+        self.blocking(move |vault| {
+            let path = vault.folder_path(id)?;
+            let parent = path.parent().unwrap_or(&vault.root).to_path_buf();
+
+            for entry in fs::read_dir(&path)? {
+                let entry = entry?;
+                let name = entry.file_name().to_string_lossy().to_string();
+                if name == FOLDER_MARKER {
+                    continue;
+                }
+
+                if entry.file_type()?.is_dir() {
+                    let target = unique_path(&parent, &name, None);
+                    fs::rename(entry.path(), &target)?;
+                    vault.forget_files_under(&entry.path());
+                    vault.remember_files_under(&target);
+                    vault.index.lock().unwrap().moved(&entry.path(), &target);
+                } else {
+                    vault.move_file(&entry.path(), &parent)?;
+                }
+            }
+
+            fs::remove_dir_all(&path).with_context(|| format!("Failed to delete {:?}", path))?;
+            vault.index.lock().unwrap().removed(&path);
+            Ok(())
+        })
+        .await
+    }
+
+    /// Returns the ids of documents whose file was modified outside Remindr since the
+    /// previous call, after updating the index with the files added, moved or deleted.
+    pub fn poll_external_changes(&self) -> Result<Vec<i32>, Error> {
+        let modified = self.record_modified_files()?;
+        // Refreshed even when no document changed, to pick up folders created outside Remindr
+        self.refresh_index(&modified)?;
+        if modified.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        tracing::debug!("files changed outside Remindr: {:?}", modified);
+
+        Ok(self
+            .index
+            .lock()
+            .unwrap()
+            .documents
+            .iter()
+            .filter(|(_, path)| modified.contains(*path))
+            .map(|(id, _)| *id)
+            .collect())
+    }
+
+    /// Records the current modification time of every document file and returns the files
+    /// whose time differs from the one last recorded.
+    fn record_modified_files(&self) -> Result<HashSet<PathBuf>, Error> {
+        let mut files = Vec::new();
+        collect_document_files(&self.root, &mut files)?;
+
+        let mut known_files = self.known_files.lock().unwrap();
+        let mut modified_files = HashSet::new();
+        for path in files {
+            let Ok(modified) = fs::metadata(&path).and_then(|m| m.modified()) else {
+                continue;
+            };
+            if known_files.get(&path) != Some(&modified) {
+                known_files.insert(path.clone(), modified);
+                modified_files.insert(path);
+            }
+        }
+
+        Ok(modified_files)
+    }
+
+    async fn blocking<T: Send + 'static>(
+        &self,
+        f: impl FnOnce(&FileVault) -> Result<T, Error> + Send + 'static,
+    ) -> Result<T, Error> {
+        let vault = self.clone();
+        tokio::task::spawn_blocking(move || f(&vault)).await?
+    }
+
+    /// Walks the vault to index its folders and documents, and assigns ids to the ones that
+    /// have none yet.
+    ///
+    /// Only the `modified` documents and the ones missing from the index are read again.
+    fn refresh_index(&self, modified: &HashSet<PathBuf>) -> Result<(), Error> {
+        let mut folders = Vec::new();
+        let mut files = Vec::new();
+        collect_entries(&self.root, &mut folders, &mut files)?;
+
+        let mut index = self.index.lock().unwrap();
+        let indexed_ids = index
+            .documents
+            .iter()
+            .map(|(id, path)| (path.clone(), *id))
+            .collect::<HashMap<_, _>>();
+
+        let mut documents = Vec::with_capacity(files.len());
+        for path in files {
+            let id = match indexed_ids.get(&path) {
+                Some(id) if !modified.contains(&path) => Some(*id),
+                _ => match read_document(&path) {
+                    Ok(document) => document.id,
+                    Err(e) => {
+                        tracing::warn!("skipping {:?}: {:?}", path, e);
+                        continue;
+                    }
+                },
+            };
+            documents.push((path, id));
+        }
+
+        let mut last_id = folders
+            .iter()
+            .chain(&documents)
+            .filter_map(|(_, id)| *id)
+            .max()
+            .unwrap_or(0);
+
+        let (resolved_folders, new_folders) = resolve_ids(folders, &index.folders, &mut last_id);
+        for (id, path) in new_folders {
+            write_folder_marker(&path, id)?;
+        }
+
+        let (resolved_documents, new_documents) =
+            resolve_ids(documents, &index.documents, &mut last_id);
+        for (id, path) in new_documents {
+            let mut document = read_document(&path)?;
+            document.id = Some(id);
+            self.write_document(&path, &document)?;
+        }
+
+        *index = VaultIndex {
+            folders: resolved_folders,
+            documents: resolved_documents,
+        };
+
+        Ok(())
+    }
+
+    fn folder_path(&self, id: i32) -> Result<PathBuf, Error> {
+        self.index
+            .lock()
+            .unwrap()
+            .folders
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| anyhow!("Folder {} not found in vault", id))
+    }
+
+    /// Directory of a folder, the root of the vault for `None`.
+    fn directory(&self, folder_id: Option<i32>) -> Result<PathBuf, Error> {
+        match folder_id {
+            Some(folder_id) => self.folder_path(folder_id),
+            None => Ok(self.root.clone()),
+        }
+    }
+
+    fn document_path(&self, id: i32) -> Result<PathBuf, Error> {
+        self.index
+            .lock()
+            .unwrap()
+            .documents
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| anyhow!("Document {} not found in vault", id))
+    }
+
+    /// Reads the file of an indexed document.
+    fn document(&self, id: i32) -> Result<IndexedDocument, Error> {
+        let (path, folder_id) = {
+            let index = self.index.lock().unwrap();
+            let path = index
+                .documents
+                .get(&id)
+                .cloned()
+                .ok_or_else(|| anyhow!("Document {} not found in vault", id))?;
+            let folder_id = path.parent().and_then(|parent| index.folder_id(parent));
+            (path, folder_id)
+        };
+
+        let document =
+            read_document(&path).with_context(|| format!("Failed to read {:?}", path))?;
+        Ok(IndexedDocument {
+            model: DocumentModel {
+                id,
+                title: document.title,
+                content: document.content,
+                folder_id,
+            },
+            tags: document.tags,
+            path,
+        })
+    }
+
+    /// Every indexed document that can still be read, by id.
+    fn documents(&self) -> Vec<IndexedDocument> {
+        let mut ids = self
+            .index
+            .lock()
+            .unwrap()
+            .documents
+            .keys()
+            .copied()
+            .collect::<Vec<_>>();
+        ids.sort();
+
+        ids.into_iter()
+            .filter_map(|id| match self.document(id) {
+                Ok(document) => Some(document),
+                Err(e) => {
+                    tracing::warn!("skipping document {}: {:?}", id, e);
+                    None
+                }
+            })
+            .collect()
+    }
+
+    fn write_document(&self, path: &Path, document: &VaultDocument) -> Result<(), Error> {
+        let json = serde_json::to_string_pretty(document)?;
+        fs::write(path, json).with_context(|| format!("Failed to write {:?}", path))?;
+        self.remember_file(path);
+        Ok(())
+    }
+
+    /// Rewrites the tags of a document file, reading it again so external edits are kept.
+    fn edit_tags(&self, path: &Path, edit: impl FnOnce(&mut Vec<String>)) -> Result<(), Error> {
+        let mut document = read_document(path)?;
+        edit(&mut document.tags);
+        self.write_document(path, &document)
+    }
+
+    fn read_reminders(&self) -> Result<Vec<ReminderModel>, Error> {
+        let path = self.root.join(REMINDERS_FILE);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content =
+            fs::read_to_string(&path).with_context(|| format!("Failed to read {:?}", path))?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    fn write_reminders(&self, reminders: &[ReminderModel]) -> Result<(), Error> {
+        let path = self.root.join(REMINDERS_FILE);
+        fs::write(&path, serde_json::to_string_pretty(reminders)?)
+            .with_context(|| format!("Failed to write {:?}", path))
+    }
+
+    fn read_collection_views(&self) -> Result<HashMap<i32, CollectionView>, Error> {
+        let path = self.root.join(COLLECTIONS_FILE);
+        if !path.exists() {
+            return Ok(HashMap::new());
+        }
+
+        let content =
+            fs::read_to_string(&path).with_context(|| format!("Failed to read {:?}", path))?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    fn read_folder_templates(&self) -> Result<HashMap<i32, i32>, Error> {
+        let path = self.root.join(FOLDER_TEMPLATES_FILE);
+        if !path.exists() {
+            return Ok(HashMap::new());
+        }
+
+        let content =
+            fs::read_to_string(&path).with_context(|| format!("Failed to read {:?}", path))?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    fn read_smart_folders(&self) -> Result<Vec<SmartFolderModel>, Error> {
+        let path = self.root.join(SMART_FOLDERS_FILE);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content =
+            fs::read_to_string(&path).with_context(|| format!("Failed to read {:?}", path))?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    fn write_smart_folders(&self, smart_folders: &[SmartFolderModel]) -> Result<(), Error> {
+        let path = self.root.join(SMART_FOLDERS_FILE);
+        fs::write(&path, serde_json::to_string_pretty(smart_folders)?)
+            .with_context(|| format!("Failed to write {:?}", path))
+    }
+
+    fn read_synced_blocks(&self) -> Result<Vec<SyncedBlockModel>, Error> {
+        let path = self.root.join(SYNCED_BLOCKS_FILE);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content =
+            fs::read_to_string(&path).with_context(|| format!("Failed to read {:?}", path))?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    fn write_synced_blocks(&self, blocks: &[SyncedBlockModel]) -> Result<(), Error> {
+        let path = self.root.join(SYNCED_BLOCKS_FILE);
+        fs::write(&path, serde_json::to_string_pretty(blocks)?)
+            .with_context(|| format!("Failed to write {:?}", path))
+    }
+
+    fn find_attachment(&self, id: i32) -> Result<PathBuf, Error> {
+        let directory = self.root.join(ATTACHMENTS_DIR).join(id.to_string());
+        fs::read_dir(&directory)
+            .with_context(|| format!("Failed to read {:?}", directory))?
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .find(|path| path.is_file())
+            .ok_or_else(|| anyhow!("Attachment {} not found in vault", id))
+    }
+
+    fn move_file(&self, path: &Path, directory: &Path) -> Result<(), Error> {
+        if path.parent() == Some(directory) {
+            return Ok(());
+        }
+
+        let stem = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let target = unique_path(directory, &stem, Some(DOCUMENT_EXTENSION));
+
+        fs::rename(path, &target).with_context(|| format!("Failed to move {:?}", path))?;
+        self.known_files.lock().unwrap().remove(path);
+        self.remember_file(&target);
+        self.index.lock().unwrap().moved(path, &target);
+        Ok(())
+    }
+
+    fn modified_externally(&self, path: &Path) -> bool {
+        let Ok(modified) = fs::metadata(path).and_then(|m| m.modified()) else {
+            return false;
+        };
+        self.known_files
+            .lock()
+            .unwrap()
+            .get(path)
+            .is_some_and(|known| *known != modified)
+    }
+
+    fn remember_file(&self, path: &Path) {
+        if let Ok(modified) = fs::metadata(path).and_then(|m| m.modified()) {
+            self.known_files
+                .lock()
+                .unwrap()
+                .insert(path.to_path_buf(), modified);
+        }
+    }
+
+    fn remember_files_under(&self, directory: &Path) {
+        let mut files = Vec::new();
+        if collect_document_files(directory, &mut files).is_ok() {
+            for file in files {
+                self.remember_file(&file);
+            }
+        }
+    }
+
+    fn forget_files_under(&self, directory: &Path) {
+        self.known_files
+            .lock()
+            .unwrap()
+            .retain(|path, _| !path.starts_with(directory));
+    }
+}
+
+impl DocumentRepositoryPort for FileVault {
+    fn get_documents(&self) -> BoxFuture<'_, Result<Vec<DocumentModel>, Error>> {
+        Box::pin(self.get_documents())
+    }
+
+    fn find_document(&self, id: i32) -> BoxFuture<'_, Result<Option<DocumentModel>, Error>> {
+        Box::pin(self.blocking(move |vault| {
+            if !vault.index.lock().unwrap().documents.contains_key(&id) {
+                return Ok(None);
+            }
+            Ok(Some(vault.document(id)?.model))
+        }))
+    }
+
+    fn insert_document(&self, document: DocumentModel) -> BoxFuture<'_, Result<i32, Error>> {
+        Box::pin(self.insert_document(document))
+    }
+
+    fn update_document(&self, document: DocumentModel) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(self.update_document(document))
+    }
+
+    fn move_document(&self, id: i32, folder_id: Option<i32>) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(self.move_document(id, folder_id))
+    }
+
+    fn delete_document(&self, id: i32) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(self.delete_document(id))
+    }
+
+    fn get_document_by_id(&self, id: i32) -> BoxFuture<'_, Result<DocumentModel, Error>> {
+        Box::pin(self.get_document(id))
+    }
+
+    fn get_stored_documents(&self) -> BoxFuture<'_, Result<Vec<StoredDocument>, Error>> {
+        Box::pin(self.get_stored_documents())
+    }
+
+    fn document_location(&self, id: i32) -> BoxFuture<'_, Result<DocumentLocation, Error>> {
+        Box::pin(self.document_location(id))
+    }
+
+    fn updated_at(&self, id: i32) -> BoxFuture<'_, Result<Option<SystemTime>, Error>> {
+        Box::pin(self.updated_at(id))
+    }
+}
+
+impl FolderRepositoryPort for FileVault {
+    fn get_folders(&self) -> BoxFuture<'_, Result<Vec<FolderModel>, Error>> {
+        Box::pin(self.get_folders())
+    }
+
+    fn find_folder(&self, id: i32) -> BoxFuture<'_, Result<Option<FolderModel>, Error>> {
+        Box::pin(self.blocking(move |vault| {
+            let index = vault.index.lock().unwrap();
+            Ok(index.folders.get(&id).map(|path| index.folder(id, path)))
+        }))
+    }
+
+    fn insert_folder(
+        &self,
+        name: String,
+        parent_id: Option<i32>,
+    ) -> BoxFuture<'_, Result<i32, Error>> {
+        Box::pin(async move {
+            check_insert_depth(self, parent_id).await?;
+            self.insert_folder(name, parent_id).await
+        })
+    }
+
+    fn update_folder(&self, folder: FolderModel) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(self.rename_folder(folder.id, folder.name))
+    }
+
+    fn delete_folder(&self, id: i32) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(self.delete_folder(id))
+    }
+
+    fn move_folder(&self, id: i32, new_parent_id: Option<i32>) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(async move {
+            check_move_depth(self, new_parent_id).await?;
+            self.move_folder(id, new_parent_id).await
+        })
+    }
+
+    fn get_template(&self, folder_id: i32) -> BoxFuture<'_, Result<Option<i32>, Error>> {
+        Box::pin(self.get_folder_template(folder_id))
+    }
+
+    fn set_template(
+        &self,
+        folder_id: i32,
+        template_id: Option<i32>,
+    ) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(self.set_folder_template(folder_id, template_id))
+    }
+
+    fn delete_folder_keep_children(&self, id: i32) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(self.delete_folder_keep_children(id))
+    }
+
+    fn get_folder_by_id(&self, id: i32) -> BoxFuture<'_, Result<FolderModel, Error>> {
+        Box::pin(self.get_folder(id))
+    }
+}
+
+/// Lists the folders (with the id of their marker, if any) and the document files under
+/// `directory`, parents before children.
+fn collect_entries(
+    directory: &Path,
+    folders: &mut Vec<(PathBuf, Option<i32>)>,
+    documents: &mut Vec<PathBuf>,
+) -> Result<(), Error> {
+    let mut entries = fs::read_dir(directory)
+        .with_context(|| format!("Failed to read {:?}", directory))?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .collect::<Vec<_>>();
+    entries.sort();
+
+    for path in entries {
+        if file_name(&path).starts_with('.') {
+            continue;
+        }
+
+        if path.is_dir() {
+            folders.push((path.clone(), read_folder_marker(&path)));
+            collect_entries(&path, folders, documents)?;
+        } else if path
+            .extension()
+            .is_some_and(|ext| ext == DOCUMENT_EXTENSION)
+        {
+            documents.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Gives each entry its id, and a new one to the entries without any.
+///
+/// When several entries share an id (e.g. a copied file), the one indexed with it keeps it,
+/// or else the first one; the others get a new id. Returns the entries by id, and the ones
+/// whose new id must be written.
+fn resolve_ids(
+    entries: Vec<(PathBuf, Option<i32>)>,
+    indexed: &HashMap<i32, PathBuf>,
+    last_id: &mut i32,
+) -> (HashMap<i32, PathBuf>, Vec<(i32, PathBuf)>) {
+    let (indexed_entries, other_entries): (Vec<_>, Vec<_>) = entries
+        .into_iter()
+        .partition(|(path, id)| id.is_some_and(|id| indexed.get(&id) == Some(path)));
+
+    let mut resolved = HashMap::with_capacity(indexed_entries.len() + other_entries.len());
+    let mut unassigned = Vec::new();
+    for (path, id) in indexed_entries.into_iter().chain(other_entries) {
+        match id.filter(|id| !resolved.contains_key(id)) {
+            Some(id) => {
+                resolved.insert(id, path);
+            }
+            None => unassigned.push(path),
+        }
+    }
+
+    let mut assigned = Vec::with_capacity(unassigned.len());
+    for path in unassigned {
+        *last_id += 1;
+        resolved.insert(*last_id, path.clone());
+        assigned.push((*last_id, path));
+    }
+
+    (resolved, assigned)
+}
+
+fn collect_document_files(directory: &Path, files: &mut Vec<PathBuf>) -> Result<(), Error> {
+    for entry in fs::read_dir(directory)? {
+        let path = entry?.path();
+        if file_name(&path).starts_with('.') {
+            continue;
+        }
+
+        if path.is_dir() {
+            collect_document_files(&path, files)?;
+        } else if path
+            .extension()
+            .is_some_and(|ext| ext == DOCUMENT_EXTENSION)
+        {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// JSON text of the blocks of a document file, or the whole file when it isn't a document.
+pub(crate) fn stored_content(text: &str) -> String {
+    match serde_json::from_str::<VaultDocument>(text) {
+        Ok(document) if document.content.is_null() => "[]".to_string(),
+        Ok(document) => document.content.to_string(),
+        Err(_) => text.to_string(),
+    }
+}
+
+fn read_document(path: &Path) -> Result<VaultDocument, Error> {
+    let content = fs::read_to_string(path)?;
+    let mut document = serde_json::from_str::<VaultDocument>(&content)?;
+    if document.title.is_empty() {
+        document.title = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+    }
+    if document.content.is_null() {
+        document.content = Value::Array(Vec::new());
+    }
+
+    Ok(document)
+}
+
+fn read_folder_marker(directory: &Path) -> Option<i32> {
+    let content = fs::read_to_string(directory.join(FOLDER_MARKER)).ok()?;
+    serde_json::from_str::<FolderMarker>(&content)
+        .ok()
+        .map(|marker| marker.id)
+}
+
+fn write_folder_marker(directory: &Path, id: i32) -> Result<(), Error> {
+    let path = directory.join(FOLDER_MARKER);
+    fs::write(&path, serde_json::to_string(&FolderMarker { id })?)
+        .with_context(|| format!("Failed to write {:?}", path))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// Builds a path in `directory` for `name` that does not exist yet.
+pub(crate) fn unique_path(directory: &Path, name: &str, extension: Option<&str>) -> PathBuf {
+    let base = sanitize_file_name(name);
+    let with_extension = |name: String| match extension {
+        Some(extension) => format!("{}.{}", name, extension),
+        None => name,
+    };
+
+    let mut path = directory.join(with_extension(base.clone()));
+    let mut suffix = 2;
+    while path.exists() {
+        path = directory.join(with_extension(format!("{} ({})", base, suffix)));
+        suffix += 1;
+    }
+
+    path
+}
+
+/// Name an attachment is written under, keeping only the last component of `name`.
+pub(crate) fn attachment_file_name(name: &str) -> String {
+    sanitize_file_name(&file_name(Path::new(name)))
+}
+
+fn sanitize_file_name(name: &str) -> String {
+    let sanitized = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '-',
+            c if c.is_control() => '-',
+            c => c,
+        })
+        .collect::<String>();
+    let sanitized = sanitized.trim().trim_start_matches('.').trim();
+
+    if sanitized.is_empty() {
+        "Untitled".to_string()
+    } else {
+        sanitized.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use serde_json::json;
+
+    use super::*;
+
+    /// Vault directory removed at the end of the test.
+    struct TempVault {
+        root: PathBuf,
+    }
+
+    impl TempVault {
+        fn new(name: &str) -> Self {
+            let root =
+                std::env::temp_dir().join(format!("remindr-vault-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&root);
+            fs::create_dir_all(&root).unwrap();
+            Self { root }
+        }
+
+        fn write(&self, file: &str, content: &str) -> PathBuf {
+            let path = self.root.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, content).unwrap();
+            path
+        }
+
+        fn open(&self) -> FileVault {
+            FileVault::open(&self.root).unwrap()
+        }
+    }
+
+    impl Drop for TempVault {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.root);
+        }
+    }
+
+    fn document(title: &str, folder_id: Option<i32>) -> DocumentModel {
+        DocumentModel {
+            id: 0,
+            title: title.to_string(),
+            content: json!([]),
+            folder_id,
+        }
+    }
+
+    /// Rewrites a file as another app would, with a modification time Remindr hasn't seen.
+    fn edit_externally(path: &Path, content: &str) {
+        fs::write(path, content).unwrap();
+        fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(10))
+            .unwrap();
+    }
+
+    fn file_id(path: &Path) -> Option<i32> {
+        read_document(path).unwrap().id
+    }
+
+    #[test]
+    fn the_indexed_entry_keeps_a_shared_id() {
+        let indexed = HashMap::from([(1, PathBuf::from("b.json"))]);
+        let entries = vec![
+            (PathBuf::from("a.json"), Some(1)),
+            (PathBuf::from("b.json"), Some(1)),
+            (PathBuf::from("c.json"), None),
+        ];
+        let mut last_id = 1;
+
+        let (resolved, assigned) = resolve_ids(entries, &indexed, &mut last_id);
+
+        assert_eq!(resolved[&1], PathBuf::from("b.json"));
+        assert_eq!(
+            assigned,
+            vec![(2, PathBuf::from("a.json")), (3, PathBuf::from("c.json"))]
+        );
+        assert_eq!(last_id, 3);
+    }
+
+    #[tokio::test]
+    async fn files_without_an_id_and_copies_are_given_a_new_one() {
+        let temp = TempVault::new("ids");
+        let original = temp.write("Work/b.json", r#"{"id":5,"title":"B","content":[]}"#);
+        let added = temp.write("a.json", r#"{"title":"A","content":[]}"#);
+        let vault = temp.open();
+
+        let added_id = file_id(&added).unwrap();
+        assert_ne!(added_id, 5);
+        assert_eq!(vault.get_document(added_id).await.unwrap().title, "A");
+
+        let copy = temp.root.join("b copy.json");
+        fs::copy(&original, &copy).unwrap();
+        vault.poll_external_changes().unwrap();
+
+        assert_eq!(file_id(&original), Some(5));
+        let copy_id = file_id(&copy).unwrap();
+        assert!(copy_id != 5 && copy_id != added_id);
+        assert_eq!(vault.get_documents().await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn colliding_names_are_numbered() {
+        let temp = TempVault::new("names");
+        let vault = temp.open();
+
+        vault.insert_document(document("Plan", None)).await.unwrap();
+        vault.insert_document(document("Plan", None)).await.unwrap();
+        vault
+            .insert_document(document("Q1/Q2: goals", None))
+            .await
+            .unwrap();
+        assert!(temp.root.join("Plan.json").exists());
+        assert!(temp.root.join("Plan (2).json").exists());
+        assert!(temp.root.join("Q1-Q2- goals.json").exists());
+
+        vault.insert_folder("Work".to_string(), None).await.unwrap();
+        let home = vault.insert_folder("Home".to_string(), None).await.unwrap();
+        vault.rename_folder(home, "Work".to_string()).await.unwrap();
+        assert!(temp.root.join("Work (2)").is_dir());
+        assert_eq!(vault.get_folder(home).await.unwrap().name, "Work (2)");
+    }
+
+    #[tokio::test]
+    async fn external_edits_are_picked_up_by_the_next_poll() {
+        let temp = TempVault::new("refresh");
+        let path = temp.write("note.json", r#"{"id":1,"title":"Draft","content":[]}"#);
+        let vault = temp.open();
+        assert!(vault.poll_external_changes().unwrap().is_empty());
+
+        edit_externally(&path, r#"{"id":1,"title":"Edited","content":[]}"#);
+        temp.write("new.json", r#"{"title":"New","content":[]}"#);
+
+        // The added file is given the next id
+        let mut changed = vault.poll_external_changes().unwrap();
+        changed.sort();
+        assert_eq!(changed, vec![1, 2]);
+        assert_eq!(vault.get_document(1).await.unwrap().title, "Edited");
+        assert_eq!(vault.get_document(2).await.unwrap().title, "New");
+    }
+
+    #[tokio::test]
+    async fn saving_over_an_external_edit_is_a_conflict() {
+        let temp = TempVault::new("conflict");
+        let vault = temp.open();
+        let id = vault.insert_document(document("Note", None)).await.unwrap();
+        let path = temp.root.join("Note.json");
+
+        let external = format!(r#"{{"id":{},"title":"Theirs","content":[]}}"#, id);
+        edit_externally(&path, &external);
+        let ours = DocumentModel {
+            id,
+            ..document("Ours", None)
+        };
+
+        let error = vault.update_document(ours.clone()).await.unwrap_err();
+        assert!(error.is::<ExternalChangeConflict>());
+        assert_eq!(read_document(&path).unwrap().title, "Theirs");
+
+        // Reported once, the next save overwrites the file
+        vault.update_document(ours).await.unwrap();
+        assert_eq!(read_document(&path).unwrap().title, "Ours");
+    }
+
+    #[tokio::test]
+    async fn folders_are_not_created_deeper_than_the_maximum() {
+        let temp = TempVault::new("depth");
+        let folders: &dyn FolderRepositoryPort = &temp.open();
+
+        let mut parent_id = None;
+        for name in ["One", "Two", "Three"] {
+            parent_id = Some(
+                folders
+                    .insert_folder(name.to_string(), parent_id)
+                    .await
+                    .unwrap(),
+            );
+        }
+
+        assert!(
+            folders
+                .insert_folder("Four".to_string(), parent_id)
+                .await
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,196 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+use anyhow::{Context, Error, bail};
+
+use crate::infrastructure::vault::file_vault::FileVault;
+
+/// Thin wrapper over the `git` command line for the work tree containing a vault.
+pub struct GitRepository {
+    work_tree: PathBuf,
+}
+
+impl GitRepository {
+    /// Returns the repository containing `path`, if any and if `git` is installed.
+    pub fn discover(path: &Path) -> Option<Self> {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(path)
+            .args(["rev-parse", "--show-toplevel"])
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+
+        let work_tree = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Some(Self {
+            work_tree: PathBuf::from(work_tree).canonicalize().ok()?,
+        })
+    }
+
+    /// Files under `path` that differ from the last commit, untracked ones included.
+    pub fn changed_files(&self, path: &Path) -> Result<Vec<PathBuf>, Error> {
+        let output = self.run(
+            &[
+                "status",
+                "--porcelain=v1",
+                "-z",
+                "--untracked-files=all",
+                "--",
+            ],
+            Some(path),
+        )?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        let mut files = Vec::new();
+        let mut entries = stdout.split('\0').filter(|entry| !entry.is_empty());
+        while let Some(entry) = entries.next() {
+            let (status, file) = entry.split_at(entry.len().min(3));
+            files.push(self.work_tree.join(file));
+
+            // Renames and copies are followed by their original path
+            if status.contains('R') || status.contains('C') {
+                entries.next();
+            }
+        }
+
+        Ok(files)
+    }
+
+    /// Stages every change under `path` and commits it.
+    pub fn commit(&self, path: &Path, message: &str) -> Result<(), Error> {
+        self.run(&["add", "--all", "--"], Some(path))?;
+        self.run(&["commit", "--quiet", "-m", message, "--"], Some(path))?;
+        Ok(())
+    }
+
+    /// Unified diff of `file` against the last commit. Untracked files are shown as added.
+    pub fn diff(&self, file: &Path) -> Result<String, Error> {
+        let tracked = self
+            .run(&["ls-files", "--error-unmatch", "--"], Some(file))
+            .is_ok();
+
+        if tracked {
+            let output = self.run(&["diff", "HEAD", "--"], Some(file))?;
+            return Ok(String::from_utf8_lossy(&output.stdout).to_string());
+        }
+
+        // `--no-index` exits with 1 when the files differ, which is always the case here
+        let output = Command::new("git")
+            .current_dir(&self.work_tree)
+            .args(["diff", "--no-index", "--", "/dev/null"])
+            .arg(file)
+            .output()
+            .context("Failed to run git")?;
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    fn run(&self, args: &[&str], path: Option<&Path>) -> Result<Output, Error> {
+        let mut command = Command::new("git");
+        command.current_dir(&self.work_tree).args(args);
+        if let Some(path) = path {
+            command.arg(path);
+        }
+
+        let output = command.output().context("Failed to run git")?;
+        if !output.status.success() {
+            bail!(
+                "git {} failed: {}",
+                args.first().copied().unwrap_or_default(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        Ok(output)
+    }
+}
+
+impl FileVault {
+    pub fn git(&self) -> Option<GitRepository> {
+        GitRepository::discover(self.root())
+    }
+
+    /// Ids of the documents with changes since the last commit.
+    pub fn dirty_documents(&self) -> Result<HashSet<i32>, Error> {
+        let Some(git) = self.git() else {
+            return Ok(HashSet::new());
+        };
+
+        let changed = git.changed_files(self.root())?;
+        Ok(self
+            .document_paths()?
+            .into_iter()
+            .filter(|(_, _, path)| changed.contains(path))
+            .map(|(id, _, _)| id)
+            .collect())
+    }
+
+    /// Commits every change in the vault with a message listing the affected documents.
+    /// Returns the message, or `None` when there was nothing to commit.
+    pub fn commit_changes(&self) -> Result<Option<String>, Error> {
+        let Some(git) = self.git() else {
+            bail!("The vault folder is not a git repository");
+        };
+
+        let changed = git.changed_files(self.root())?;
+        if changed.is_empty() {
+            return Ok(None);
+        }
+
+        let documents = self.document_paths()?;
+        let mut titles = changed
+            .iter()
+            .filter_map(
+                |file| match documents.iter().find(|(_, _, path)| path == file) {
+                    Some((_, title, _)) => Some(title.clone()),
+                    // Deleted documents and other files are listed by name
+                    None => file
+                        .file_stem()
+                        .map(|stem| stem.to_string_lossy().to_string())
+                        .filter(|stem| !stem.starts_with('.')),
+                },
+            )
+            .collect::<Vec<_>>();
+        titles.sort();
+        titles.dedup();
+
+        let message = commit_message(&titles);
+        git.commit(self.root(), &message)?;
+
+        Ok(Some(message))
+    }
+
+    /// Diff of a document since the last commit.
+    pub fn document_diff(&self, id: i32) -> Result<String, Error> {
+        let Some(git) = self.git() else {
+            bail!("The vault folder is not a git repository");
+        };
+
+        let (_, _, path) = self
+            .document_paths()?
+            .into_iter()
+            .find(|(document_id, _, _)| *document_id == id)
+            .context("Document not found in vault")?;
+
+        git.diff(&path)
+    }
+}
+
+fn commit_message(titles: &[String]) -> String {
+    match titles {
+        [] => "Update vault".to_string(),
+        [title] => format!("Update \"{}\"", title),
+        titles => {
+            let list = titles
+                .iter()
+                .map(|title| format!("- {}", title))
+                .collect::<Vec<_>>()
+                .join("\n");
+            format!("Update {} documents\n\n{}", titles.len(), list)
+        }
+    }
+}
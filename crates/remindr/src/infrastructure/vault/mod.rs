@@ -0,0 +1,2 @@
+pub mod file_vault;
+pub mod git;
@@ -1,5 +1,7 @@
+use std::cell::RefCell;
+
 use chrono::Utc;
-use uuid::{NoContext, Timestamp, Uuid};
+use uuid::{Builder, Uuid};
 
 pub mod app;
 pub mod domain;
@@ -7,13 +9,90 @@ pub mod infrastructure;
 
 pub struct Utils;
 
+thread_local! {
+    static SEEDED_UUIDS: RefCell<Option<SeededUuids>> = const { RefCell::new(None) };
+}
+
 impl Utils {
+    /// Time ordered UUIDv7, increasing within the process even for ids generated in the
+    /// same millisecond, so that blocks sort by creation and indexes on ids stay compact.
     pub fn generate_uuid() -> Uuid {
-        let now = Utc::now();
-        let seconds: u64 = now.timestamp().try_into().unwrap_or(0);
-        let timestamp = Timestamp::from_unix(NoContext, seconds, 0);
+        SEEDED_UUIDS
+            .with_borrow_mut(|seeded| seeded.as_mut().map(SeededUuids::next))
+            .unwrap_or_else(Uuid::now_v7)
+    }
+
+    /// Makes `generate_uuid` return the same sequence of ids on this thread for the same
+    /// seed, until the returned guard is dropped. For tests and snapshots.
+    pub fn seed_uuids(seed: u64) -> SeededUuidsGuard {
+        SEEDED_UUIDS.set(Some(SeededUuids::new(seed)));
+        SeededUuidsGuard(())
+    }
+
+    /// Describes how long ago a timestamp, in seconds since the epoch, was.
+    pub fn relative_time(timestamp: i64) -> String {
+        let elapsed = (Utc::now().timestamp() - timestamp).max(0);
+        let (count, unit) = match elapsed {
+            0..60 => return "just now".to_string(),
+            60..3_600 => (elapsed / 60, "minute"),
+            3_600..86_400 => (elapsed / 3_600, "hour"),
+            86_400..2_592_000 => (elapsed / 86_400, "day"),
+            2_592_000..31_536_000 => (elapsed / 2_592_000, "month"),
+            _ => (elapsed / 31_536_000, "year"),
+        };
+
+        format!(
+            "{} {}{} ago",
+            count,
+            unit,
+            if count == 1 { "" } else { "s" }
+        )
+    }
+}
+
+/// Ids of `Utils::seed_uuids`: UUIDv7 of consecutive milliseconds from a fixed time, their
+/// random bits drawn from the seed.
+struct SeededUuids {
+    millis: u64,
+    state: u64,
+}
+
+impl SeededUuids {
+    /// 2024-01-01, for the ids to look like real ones.
+    const START_MILLIS: u64 = 1_704_067_200_000;
+
+    fn new(seed: u64) -> Self {
+        Self {
+            millis: Self::START_MILLIS,
+            state: seed,
+        }
+    }
 
-        Uuid::new_v7(timestamp)
+    fn next(&mut self) -> Uuid {
+        self.millis += 1;
+        let mut random = [0; 10];
+        random[..8].copy_from_slice(&self.next_random().to_le_bytes());
+        random[8..].copy_from_slice(&self.next_random().to_le_bytes()[..2]);
+        Builder::from_unix_timestamp_millis(self.millis, &random).into_uuid()
+    }
+
+    /// SplitMix64.
+    fn next_random(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+/// Returns `Utils::generate_uuid` to random ids when dropped.
+#[must_use = "ids are only seeded until the guard is dropped"]
+pub struct SeededUuidsGuard(());
+
+impl Drop for SeededUuidsGuard {
+    fn drop(&mut self) {
+        SEEDED_UUIDS.set(None);
     }
 }
 
@@ -23,3 +102,51 @@ pub enum LoadingState<T> {
     Loaded(T),
     Error(String),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeded_uuids_repeat() {
+        let first = {
+            let _seed = Utils::seed_uuids(7);
+            (0..3).map(|_| Utils::generate_uuid()).collect::<Vec<_>>()
+        };
+        let second = {
+            let _seed = Utils::seed_uuids(7);
+            (0..3).map(|_| Utils::generate_uuid()).collect::<Vec<_>>()
+        };
+        assert_eq!(first, second);
+
+        let _seed = Utils::seed_uuids(8);
+        assert_ne!(Utils::generate_uuid(), first[0]);
+    }
+
+    #[test]
+    fn uuids_are_time_ordered_v7() {
+        for ids in [
+            (0..100).map(|_| Utils::generate_uuid()).collect::<Vec<_>>(),
+            {
+                let _seed = Utils::seed_uuids(1);
+                (0..100).map(|_| Utils::generate_uuid()).collect()
+            },
+        ] {
+            assert!(ids.iter().all(|id| id.get_version_num() == 7));
+            assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+        }
+    }
+
+    #[test]
+    fn dropping_the_guard_unseeds() {
+        let seeded = {
+            let _seed = Utils::seed_uuids(3);
+            Utils::generate_uuid()
+        };
+        let seed = Utils::seed_uuids(3);
+        let again = Utils::generate_uuid();
+        drop(seed);
+        assert_eq!(seeded, again);
+        assert_ne!(Utils::generate_uuid(), seeded);
+    }
+}
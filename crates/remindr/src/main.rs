@@ -1,74 +1,43 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use anyhow::Error;
-use gpui::{
-    App, AppContext, BorrowAppContext, Bounds, KeyBinding, Menu, MenuItem, Pixels, Size,
-    SystemMenuType, TitlebarOptions, WindowBounds, WindowHandle, WindowKind, WindowOptions,
-    actions, point, px, size,
-};
-use gpui_component::{
-    Root,
-    theme::{self, ThemeRegistry},
-};
+use gpui::{App, BorrowAppContext, KeyBinding, Menu, MenuItem, SystemMenuType, actions};
+use gpui_component::theme::{self, ThemeRegistry};
 use gpui_component_assets::Assets;
 use remindr::{
     app::{
-        apply_theme, apply_theme_global,
-        components::rich_text,
+        apply_theme_global, backup,
+        components::{
+            command_palette, nav_inspector, node_renderer,
+            quick_capture::{self, QuickCapture},
+            quick_switcher,
+            rich_text,
+            unlock_window::UnlockWindow,
+        },
+        email_import, local_api, main_window,
+        tray,
         remindr::Remindr,
-        screens::AppRouter,
+        screens::document_screen,
         states::{
-            document_state::DocumentState, repository_state::RepositoryState,
+            completion_state::CompletionState, deleted_blocks_state::DeletedBlocksState,
+            deletion_state::DeletionState,
+            document_list_state::DocumentListState,
+            document_state::DocumentState, error_state::ErrorState,
+            preview_state::PreviewState, progress_state::ProgressState,
+            reminder_state::ReminderState, repository_state::RepositoryState,
             settings_state::Settings,
+            synced_block_state::SyncedBlockState, toast_state::ToastState,
+            window_state::WindowState,
         },
     },
-    infrastructure::repositories::{
-        document_repository::DocumentRepository, folder_repository::FolderRepository,
-    },
+    infrastructure::{database, logging},
 };
-use sqlx::{SqlitePool, migrate};
 
 actions!(window, [Quit]);
 
-const MIN_WINDOW_SIZE: Size<Pixels> = Size {
-    width: px(640.),
-    height: px(480.),
-};
-
-fn create_window_options(bounds: Bounds<Pixels>) -> WindowOptions {
-    WindowOptions {
-        window_bounds: Some(WindowBounds::Windowed(bounds)),
-        window_min_size: Some(MIN_WINDOW_SIZE),
-        kind: WindowKind::Normal,
-        titlebar: Some(TitlebarOptions {
-            appears_transparent: true,
-            title: Some("Remindr".into()),
-            traffic_light_position: Some(point(px(9.0), px(9.0))),
-        }),
-        ..Default::default()
-    }
-}
-
-fn compute_window_bounds(cx: &App) -> Bounds<Pixels> {
-    let mut window_size = size(MIN_WINDOW_SIZE.width, MIN_WINDOW_SIZE.height);
-    if let Some(display) = cx.primary_display() {
-        let display_size = display.bounds().size;
-        window_size.width = display_size.width * 0.85;
-        window_size.height = display_size.height * 0.85;
-    }
-    Bounds::centered(None, window_size, cx)
-}
-
-fn open_main_window(cx: &mut App) -> anyhow::Result<WindowHandle<Root>> {
-    let bounds = compute_window_bounds(cx);
-    cx.open_window(create_window_options(bounds), |window, cx| {
-        let view = cx.new(AppRouter::new);
-        cx.new(|cx| Root::new(view, window, cx))
-    })
-}
-
 #[tokio::main]
 async fn main() -> Result<(), Error> {
+    let _log_guard = logging::init();
     let app = gpui_platform::application().with_assets(Assets);
     let remindr = Remindr::new();
 
@@ -77,17 +46,24 @@ async fn main() -> Result<(), Error> {
     let _ = remindr.init().await;
     let database_path = remindr.init_default_database().await;
 
-    let pool = if let Ok(database_path) = database_path {
-        let database_url = format!("sqlite://{}", database_path.to_str().unwrap());
-        SqlitePool::connect(&database_url).await?
-    } else {
+    let Ok(database_path) = database_path else {
         panic!("Failed to initialize database");
     };
 
-    migrate!("./migrations")
-        .run(&pool)
-        .await
-        .map_err(|err| Error::msg(err.to_string()))?;
+    // An encrypted database is opened once the passphrase has been entered
+    let encrypted = settings
+        .as_ref()
+        .map(|settings| settings.security.encrypted)
+        .unwrap_or(false);
+    let pool_config = settings
+        .as_ref()
+        .map(|settings| settings.database.pool_config())
+        .unwrap_or_default();
+    let pool = if encrypted {
+        None
+    } else {
+        Some(database::open(&database_path, None, &pool_config).await?)
+    };
 
     app.on_reopen(|cx| {
         if let Some(window) = cx.active_window() {
@@ -96,8 +72,8 @@ async fn main() -> Result<(), Error> {
                     window.activate_window();
                 })
                 .ok();
-        } else {
-            open_main_window(cx).ok();
+        } else if cx.has_global::<RepositoryState>() {
+            main_window::open(None, None, cx).ok();
         }
     });
 
@@ -106,21 +82,58 @@ async fn main() -> Result<(), Error> {
         gpui_router::init(cx);
         theme::init(cx);
         rich_text::init(cx);
+        node_renderer::init(cx);
+        command_palette::init(cx);
+        nav_inspector::init(cx);
+        quick_capture::init(cx);
+        tray::init(cx);
+        quick_switcher::init(cx);
+        document_screen::init(cx);
 
         // Set settings as global (must be done before apply_theme)
         if let Ok(settings) = settings {
             cx.set_global(settings);
         }
 
-        cx.set_global(RepositoryState {
-            documents: DocumentRepository::new(pool.clone()),
-            folders: FolderRepository::new(pool.clone()),
-        });
-
+        cx.set_global(ErrorState::default());
+        cx.set_global(ToastState::default());
+        cx.set_global(ProgressState::default());
         cx.set_global(DocumentState::default());
+        cx.set_global(DeletionState::default());
+        cx.set_global(DocumentListState);
+        cx.set_global(DeletedBlocksState::default());
+        cx.set_global(CompletionState::default());
+        cx.set_global(PreviewState::default());
+        cx.set_global(ReminderState::default());
+        cx.set_global(SyncedBlockState::default());
+        cx.set_global(WindowState::load());
         cx.activate(true);
 
-        let window = open_main_window(cx).expect("failed to open window");
+        match pool {
+            Some(pool) => {
+                let repositories =
+                    RepositoryState::for_settings(pool, cx.try_global::<Settings>());
+                cx.set_global(repositories);
+                local_api::start(cx);
+                main_window::show(None, None, cx);
+                DocumentState::prompt_recovery(cx);
+            }
+            None => UnlockWindow::open(
+                database_path,
+                |cx| {
+                    local_api::start(cx);
+                    main_window::show(None, None, cx);
+                    DocumentState::prompt_recovery(cx);
+                },
+                cx,
+            ),
+        }
+
+        DocumentState::watch_vault(cx);
+        ReminderState::watch(cx);
+        email_import::watch(cx);
+        backup::watch(cx);
+        ErrorState::watch(cx);
 
         // Load custom themes from the themes directory (~/.config/remindr/themes)
         let themes_dir = remindr
@@ -136,14 +149,6 @@ async fn main() -> Result<(), Error> {
                 });
             }
 
-        window
-            .update(cx, |_, window, cx| {
-                window.activate_window();
-                window.set_window_title("Remindr");
-                apply_theme(window, cx);
-            })
-            .expect("failed to update window");
-
         // Watch settings.json for external changes
         if let Ok(config_dir) = remindr.get_config_dir("remindr") {
             let settings_file = config_dir.join("settings.json");
@@ -178,7 +183,21 @@ async fn main() -> Result<(), Error> {
         }
 
         set_app_menus(cx);
-        cx.on_action(|_: &Quit, cx| cx.quit());
+        // Quitting otherwise than through the action leaves little time to save
+        cx.on_app_quit(|cx| {
+            let deletions = DeletionState::delete_pending(cx);
+            let flush = DocumentState::flush_unsaved(None, cx);
+            async move {
+                if let Err(e) = deletions.await {
+                    tracing::error!("failed to delete documents when quitting: {:?}", e);
+                }
+                if let Err(e) = flush.await {
+                    tracing::error!("failed to save the changes when quitting: {:?}", e);
+                }
+            }
+        })
+        .detach();
+        cx.on_action(|_: &Quit, cx| DocumentState::quit(cx));
         cx.bind_keys([KeyBinding::new("cmd-q", Quit, None)]);
     });
 
@@ -187,6 +206,8 @@ async fn main() -> Result<(), Error> {
 
 fn set_app_menus(cx: &mut App) {
     cx.set_dock_menu(vec![
+        MenuItem::action("Quick capture", QuickCapture),
+        MenuItem::separator(),
         MenuItem::os_submenu("Services", SystemMenuType::Services),
         MenuItem::separator(),
         MenuItem::action("Quit", Quit),
@@ -195,6 +216,8 @@ fn set_app_menus(cx: &mut App) {
     cx.set_menus(vec![Menu {
         name: "set_menus".into(),
         items: vec![
+            MenuItem::action("Quick capture", QuickCapture),
+            MenuItem::separator(),
             MenuItem::os_submenu("Services", SystemMenuType::Services),
             MenuItem::separator(),
             MenuItem::action("Quit", Quit),
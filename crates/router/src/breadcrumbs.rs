@@ -0,0 +1,79 @@
+use crate::{NavLink, use_matches};
+use gpui::*;
+
+/// Breadcrumbs of the routes matched by the current location.
+pub fn breadcrumbs() -> impl IntoElement {
+    Breadcrumbs::new()
+}
+
+/// Breadcrumbs of the routes matched by the current location, from the outermost.
+///
+/// Every matched route with a [title](crate::Route::title) or an [icon](crate::Route::icon)
+/// gets a crumb, which navigates back to it on click, except for the last one.
+#[derive(IntoElement)]
+pub struct Breadcrumbs {
+    base: Div,
+    separator: SharedString,
+}
+
+impl Default for Breadcrumbs {
+    fn default() -> Self {
+        Self {
+            base: div(),
+            separator: "/".into(),
+        }
+    }
+}
+
+impl Styled for Breadcrumbs {
+    fn style(&mut self) -> &mut StyleRefinement {
+        self.base.style()
+    }
+}
+
+impl Breadcrumbs {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the text between two crumbs, `/` by default.
+    pub fn separator(mut self, separator: impl Into<SharedString>) -> Self {
+        self.separator = separator.into();
+        self
+    }
+}
+
+impl RenderOnce for Breadcrumbs {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let matches = use_matches(cx).to_vec();
+        let last = matches.len().saturating_sub(1);
+
+        let mut crumbs = Vec::with_capacity(matches.len() * 2);
+        for (ix, matched) in matches.into_iter().enumerate() {
+            if ix > 0 {
+                crumbs.push(div().child(self.separator.clone()).into_any_element());
+            }
+
+            let content = div()
+                .flex()
+                .items_center()
+                .gap_1()
+                .children(
+                    matched
+                        .icon
+                        .map(|icon| svg().path(icon).size_4().flex_none()),
+                )
+                .children(matched.title);
+            crumbs.push(if ix == last {
+                content.into_any_element()
+            } else {
+                NavLink::new()
+                    .to(matched.pathname)
+                    .child(content)
+                    .into_any_element()
+            });
+        }
+
+        self.base.flex().items_center().gap_1().children(crumbs)
+    }
+}
@@ -1,6 +1,10 @@
-use crate::{Location, RouterState};
-use gpui::{App, SharedString};
+use crate::{
+    LoaderData, Location, MatchedRoute, ParamError, RouterState, loader::LoaderStore,
+    params::ParamCache,
+};
+use gpui::{App, SharedString, Window};
 use hashbrown::HashMap;
+use std::{fmt::Display, str::FromStr};
 
 /// Returns a function that lets you navigate programmatically in response to user interactions or effects.
 pub fn use_navigate(cx: &mut App) -> impl FnMut(SharedString) + '_ {
@@ -23,10 +27,55 @@ pub fn use_params(cx: &App) -> &HashMap<SharedString, SharedString> {
     &cx.global::<RouterState>().params
 }
 
+/// Returns a route parameter parsed as a `T`, like `use_param::<i32>("id", cx)` for a route
+/// defined as `/user/{id}`.
+/// The value is parsed once and cached until the parameter changes.
+pub fn use_param<T>(name: &str, cx: &mut App) -> Result<T, ParamError>
+where
+    T: FromStr + Clone + 'static,
+    T::Err: Display,
+{
+    let value = cx.global::<RouterState>().params.get(name).cloned();
+    cx.global_mut::<ParamCache>()
+        .get(name, value.as_ref(), |value| {
+            value.parse::<T>().map_err(|err| err.to_string())
+        })
+}
+
+/// Returns the routes with metadata matched by the current location, from the outermost.
+pub fn use_matches(cx: &App) -> &[MatchedRoute] {
+    &cx.global::<RouterState>().matches
+}
+
+/// Sets the title of the window to the title of the innermost matched route, followed by
+/// `app_name`, or to `app_name` alone if no matched route has a title.
+/// Call it while rendering the window so that its title follows the location.
+pub fn use_window_title(app_name: &str, window: &mut Window, cx: &mut App) {
+    let state = cx.global_mut::<RouterState>();
+    let title: SharedString = match state.matches.iter().rev().find_map(|m| m.title.as_ref()) {
+        Some(title) => format!("{} - {}", title, app_name).into(),
+        None => app_name.to_string().into(),
+    };
+
+    let handle = window.window_handle();
+    if state.window_titles.get(&handle) != Some(&title) {
+        window.set_window_title(&title);
+        state.window_titles.insert(handle, title);
+    }
+}
+
+/// Returns the data the [loader](crate::Route::loader) of a matched route returned for the
+/// current location, or its pending or failed state.
+/// Returns `None` if no loader returns a `T` for the current location.
+pub fn use_loader_data<T: 'static>(cx: &App) -> Option<LoaderData<T>> {
+    let pathname = &cx.global::<RouterState>().location.pathname;
+    cx.global::<LoaderStore>().get::<T>(pathname)
+}
+
 #[cfg(test)]
 pub mod tests {
-    use super::use_navigate;
-    use crate::RouterState;
+    use super::{use_navigate, use_param};
+    use crate::{ParamError, RouterState};
     use gpui::TestAppContext;
 
     #[gpui::test]
@@ -63,4 +112,34 @@ pub mod tests {
             );
         });
     }
+
+    #[gpui::test]
+    async fn test_use_param(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            crate::init(cx);
+            let params = &mut RouterState::global_mut(cx).params;
+            params.insert("id".into(), "42".into());
+            params.insert("tab".into(), "history".into());
+
+            assert_eq!(use_param::<i32>("id", cx), Ok(42));
+            assert_eq!(use_param::<String>("tab", cx), Ok("history".to_string()));
+            assert_eq!(
+                use_param::<i32>("page", cx),
+                Err(ParamError::Missing {
+                    name: "page".into()
+                })
+            );
+            assert!(matches!(
+                use_param::<u8>("tab", cx),
+                Err(ParamError::Invalid { value, .. }) if value == "history"
+            ));
+
+            // The cached value follows the parameter
+            RouterState::global_mut(cx)
+                .params
+                .insert("id".into(), "-1".into());
+            assert_eq!(use_param::<i32>("id", cx), Ok(-1));
+            assert!(use_param::<u32>("id", cx).is_err());
+        });
+    }
 }
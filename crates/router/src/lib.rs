@@ -1,9 +1,13 @@
 //! A router for GPUI applications, providing declarative routing capabilities.
 
+mod breadcrumbs;
 mod hooks;
 mod layout;
+mod loader;
 mod nav_link;
 mod outlet;
+mod params;
+mod pattern;
 mod route;
 mod router;
 #[cfg(test)]
@@ -12,10 +16,14 @@ mod routes;
 mod state;
 
 pub use gpui_router_macros::*;
+pub use breadcrumbs::*;
 pub use hooks::*;
 pub use layout::*;
+pub use loader::LoaderData;
 pub use nav_link::*;
 pub use outlet::*;
+pub use params::ParamError;
+pub use pattern::ParamKind;
 pub use route::*;
 pub use router::*;
 pub use routes::*;
@@ -24,4 +32,7 @@ pub use state::*;
 /// Initializes the router system within a GPUI application context.
 pub fn init(cx: &mut gpui::App) {
     RouterState::init(cx);
+    loader::LoaderStore::init(cx);
+    params::ParamCache::init(cx);
+    pattern::RouteMapCache::init(cx);
 }
@@ -0,0 +1,123 @@
+use crate::RouterState;
+use gpui::{App, AsyncApp, Global, SharedString};
+use hashbrown::HashMap;
+use std::any::{Any, TypeId};
+use std::fmt::Display;
+use std::rc::Rc;
+
+/// Starts the loader of a route for the current location, given the pattern of the route.
+pub(crate) type RouteLoader = Box<dyn Fn(&SharedString, &mut App)>;
+
+/// The state of the data loaded for a route, returned by
+/// [`use_loader_data`](crate::use_loader_data).
+#[derive(Debug)]
+pub enum LoaderData<T: ?Sized> {
+    /// The loader is still running.
+    Pending,
+    /// The loader returned its data.
+    Loaded(Rc<T>),
+    /// The loader failed, with its error as a message.
+    Failed(SharedString),
+}
+
+impl<T: ?Sized> Clone for LoaderData<T> {
+    fn clone(&self) -> Self {
+        match self {
+            LoaderData::Pending => LoaderData::Pending,
+            LoaderData::Loaded(data) => LoaderData::Loaded(data.clone()),
+            LoaderData::Failed(error) => LoaderData::Failed(error.clone()),
+        }
+    }
+}
+
+struct LoaderEntry {
+    /// The location the data was loaded for.
+    pathname: SharedString,
+    type_id: TypeId,
+    data: LoaderData<dyn Any>,
+}
+
+/// The data loaded by the routes, one entry per route pattern.
+///
+/// An entry is loaded again once its route renders for another location, so the store
+/// never holds more entries than there are routes with a loader.
+#[derive(Default)]
+pub(crate) struct LoaderStore {
+    entries: HashMap<SharedString, LoaderEntry>,
+}
+
+impl Global for LoaderStore {}
+
+impl LoaderStore {
+    pub(crate) fn init(cx: &mut App) {
+        cx.set_global(Self::default());
+    }
+
+    /// The data of type `T` loaded for the location.
+    pub(crate) fn get<T: 'static>(&self, pathname: &str) -> Option<LoaderData<T>> {
+        let entry = self
+            .entries
+            .values()
+            .find(|entry| entry.pathname == pathname && entry.type_id == TypeId::of::<T>())?;
+        Some(match &entry.data {
+            LoaderData::Pending => LoaderData::Pending,
+            LoaderData::Loaded(data) => LoaderData::Loaded(data.clone().downcast::<T>().ok()?),
+            LoaderData::Failed(error) => LoaderData::Failed(error.clone()),
+        })
+    }
+}
+
+/// Wraps the loader given to [`Route::loader`](crate::Route::loader), which runs once per
+/// location with the parameters of the route.
+pub(crate) fn route_loader<F, T, E>(loader: F) -> RouteLoader
+where
+    F: AsyncFn(HashMap<SharedString, SharedString>, &mut AsyncApp) -> Result<T, E> + 'static,
+    T: 'static,
+    E: Display,
+{
+    let loader = Rc::new(loader);
+    Box::new(move |pattern: &SharedString, cx: &mut App| {
+        let router = cx.global::<RouterState>();
+        let pathname = router.location.pathname.clone();
+        let params = router.params.clone();
+
+        let store = cx.global_mut::<LoaderStore>();
+        if store
+            .entries
+            .get(pattern)
+            .is_some_and(|entry| entry.pathname == pathname)
+        {
+            return;
+        }
+        store.entries.insert(
+            pattern.clone(),
+            LoaderEntry {
+                pathname: pathname.clone(),
+                type_id: TypeId::of::<T>(),
+                data: LoaderData::Pending,
+            },
+        );
+
+        let loader = loader.clone();
+        let pattern = pattern.clone();
+        cx.spawn(async move |cx| {
+            let data = match (*loader)(params, cx).await {
+                Ok(data) => LoaderData::Loaded(Rc::new(data) as Rc<dyn Any>),
+                Err(error) => LoaderData::Failed(error.to_string().into()),
+            };
+            cx.update(|cx| {
+                // The route may have rendered for another location in the meantime
+                let store = cx.global_mut::<LoaderStore>();
+                let Some(entry) = store.entries.get_mut(&pattern) else {
+                    return;
+                };
+                if entry.pathname != pathname {
+                    return;
+                }
+                entry.data = data;
+                cx.refresh_windows();
+            });
+        })
+        .detach();
+    })
+}
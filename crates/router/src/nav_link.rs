@@ -1,6 +1,10 @@
 use crate::use_navigate;
+use gpui::prelude::FluentBuilder;
 use gpui::*;
 use smallvec::SmallVec;
+use std::rc::Rc;
+
+type PrefetchHandler = Rc<dyn Fn(&SharedString, &mut Window, &mut App)>;
 
 /// A navigation link that changes the route when clicked.
 pub fn nav_link() -> impl IntoElement {
@@ -13,6 +17,7 @@ pub struct NavLink {
     base: Div,
     children: SmallVec<[AnyElement; 1]>,
     to: SharedString,
+    on_prefetch: Option<PrefetchHandler>,
     // is_active: bool,
 }
 
@@ -22,6 +27,7 @@ impl Default for NavLink {
             base: div(),
             children: Default::default(),
             to: Default::default(),
+            on_prefetch: None,
         }
     }
 }
@@ -55,6 +61,24 @@ impl NavLink {
         self
     }
 
+    /// Called with the destination when the pointer enters the link, before a click
+    /// navigates, to warm the data of the destination and show it sooner.
+    ///
+    /// # Examples
+    /// ```
+    /// NavLink::new()
+    ///     .to("/docs/42")
+    ///     .on_prefetch(|to, _, cx| DocumentCache::warm(to, cx))
+    ///     .child("Meeting notes")
+    /// ```
+    pub fn on_prefetch(
+        mut self,
+        on_prefetch: impl Fn(&SharedString, &mut Window, &mut App) + 'static,
+    ) -> Self {
+        self.on_prefetch = Some(Rc::new(on_prefetch));
+        self
+    }
+
     /// Sets the style for the active state of the navigation link.
     pub fn active(self, _f: impl FnOnce(StyleRefinement) -> StyleRefinement) -> Self {
         unimplemented!()
@@ -63,8 +87,16 @@ impl NavLink {
 
 impl RenderOnce for NavLink {
     fn render(self, _window: &mut Window, _cx: &mut App) -> impl IntoElement {
+        let to = self.to.clone();
         self.base
             .id(ElementId::from(self.to.clone()))
+            .when_some(self.on_prefetch, |this, on_prefetch| {
+                this.on_hover(move |hovered, window, cx| {
+                    if *hovered {
+                        on_prefetch(&to, window, cx);
+                    }
+                })
+            })
             .on_click(move |_, window, cx| {
                 let mut navigate = use_navigate(cx);
                 navigate(self.to.clone());
@@ -0,0 +1,99 @@
+use gpui::{App, Global, SharedString};
+use hashbrown::HashMap;
+use std::any::{Any, TypeId};
+use std::fmt::{self, Display};
+use std::rc::Rc;
+
+/// Why a route parameter couldn't be read as a typed value with
+/// [`use_param`](crate::use_param).
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum ParamError {
+    /// The matched route has no parameter with this name.
+    Missing { name: SharedString },
+    /// The value of the parameter doesn't parse as the type asked for.
+    Invalid {
+        name: SharedString,
+        value: SharedString,
+        message: SharedString,
+    },
+}
+
+impl Display for ParamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamError::Missing { name } => write!(f, "missing route parameter `{}`", name),
+            ParamError::Invalid {
+                name,
+                value,
+                message,
+            } => write!(
+                f,
+                "invalid route parameter `{}` = {:?}: {}",
+                name, value, message
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParamError {}
+
+struct ParsedParam {
+    /// The raw value the result was parsed from, `None` if the parameter was missing.
+    value: Option<SharedString>,
+    /// The `Result<T, ParamError>` for the type asked for.
+    result: Rc<dyn Any>,
+}
+
+/// The typed values of the route parameters, parsed once per value and type.
+#[derive(Default)]
+pub(crate) struct ParamCache {
+    entries: HashMap<(SharedString, TypeId), ParsedParam>,
+}
+
+impl Global for ParamCache {}
+
+impl ParamCache {
+    pub(crate) fn init(cx: &mut App) {
+        cx.set_global(Self::default());
+    }
+
+    /// Returns the value of the parameter as a `T`, parsing it again only if the raw value
+    /// changed since the last time.
+    pub(crate) fn get<T, F>(
+        &mut self,
+        name: &str,
+        value: Option<&SharedString>,
+        parse: F,
+    ) -> Result<T, ParamError>
+    where
+        T: Clone + 'static,
+        F: FnOnce(&str) -> Result<T, String>,
+    {
+        let key = (SharedString::from(name.to_string()), TypeId::of::<T>());
+        if let Some(parsed) = self.entries.get(&key)
+            && parsed.value.as_ref() == value
+            && let Some(result) = parsed.result.downcast_ref::<Result<T, ParamError>>()
+        {
+            return result.clone();
+        }
+
+        let result = match value {
+            Some(value) => parse(value).map_err(|message| ParamError::Invalid {
+                name: key.0.clone(),
+                value: value.clone(),
+                message: message.into(),
+            }),
+            None => Err(ParamError::Missing {
+                name: key.0.clone(),
+            }),
+        };
+        self.entries.insert(
+            key,
+            ParsedParam {
+                value: value.cloned(),
+                result: Rc::new(result.clone()),
+            },
+        );
+        result
+    }
+}
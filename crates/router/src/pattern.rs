@@ -0,0 +1,188 @@
+//! Route patterns, with optional segments and typed parameters on top of the syntax of
+//! `matchit`.
+//!
+//! - `{name}` matches one segment and `{*name}` the rest of the path.
+//! - `{name?}` is an optional segment, only allowed at the end of a pattern:
+//!   `/docs/{id}/{tab?}` matches both `/docs/1` and `/docs/1/history`.
+//! - `{name:int}` and `{name:uuid}` only match values of that type, and so does a
+//!   wildcard like `{*name:int}`. Types combine with optional segments: `{page:int?}`.
+//!
+//! When several patterns match a path, static segments win over parameters and
+//! parameters over wildcards, segment by segment from the start of the path. The types
+//! are checked once the pattern is chosen: a path whose value doesn't fit matches no
+//! route, so two patterns only differing by the type of a parameter can't be combined.
+
+use gpui::{App, Global};
+use hashbrown::HashMap;
+use matchit::{Params, Router as MatchitRouter};
+use std::rc::Rc;
+
+/// Paths of a set of routes, with the types their parameters must have.
+pub(crate) type RouteMap = MatchitRouter<Vec<(String, ParamKind)>>;
+
+/// The route maps built so far, keyed by the patterns of their routes.
+///
+/// Routes are declared again on every render, so their route map is only built the first
+/// time a set of patterns renders, rather than on every frame.
+#[derive(Default)]
+pub(crate) struct RouteMapCache {
+    maps: HashMap<String, Rc<RouteMap>>,
+}
+
+impl Global for RouteMapCache {}
+
+impl RouteMapCache {
+    /// Beyond this many route maps, the routes are likely generated on the fly and the cache
+    /// starts over rather than growing forever.
+    const MAX_MAPS: usize = 64;
+
+    pub(crate) fn init(cx: &mut App) {
+        cx.set_global(Self::default());
+    }
+
+    /// Returns the route map of the patterns, built the first time they're asked for.
+    pub(crate) fn route_map(patterns: Vec<String>, cx: &mut App) -> Rc<RouteMap> {
+        let key = patterns.join("\n");
+        let cache = cx.global_mut::<RouteMapCache>();
+        if let Some(map) = cache.maps.get(&key) {
+            return map.clone();
+        }
+
+        let mut map = RouteMap::new();
+        for pattern in patterns {
+            insert(&mut map, &pattern);
+        }
+        let map = Rc::new(map);
+        if cache.maps.len() >= Self::MAX_MAPS {
+            cache.maps.clear();
+        }
+        cache.maps.insert(key, map.clone());
+        map
+    }
+
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.maps.len()
+    }
+}
+
+/// Type a parameter of a route pattern must have, written after its name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParamKind {
+    /// Any value, the default.
+    Any,
+    /// `{name:int}`, an integer like `42` or `-1`.
+    Int,
+    /// `{name:uuid}`, a UUID like `67e55044-10b1-426f-9247-bb680e5fe0c8`.
+    Uuid,
+}
+
+impl ParamKind {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "" | "str" => Some(ParamKind::Any),
+            "int" => Some(ParamKind::Int),
+            "uuid" => Some(ParamKind::Uuid),
+            _ => None,
+        }
+    }
+
+    /// Whether a value of the path fits the type.
+    pub fn accepts(&self, value: &str) -> bool {
+        match self {
+            ParamKind::Any => true,
+            ParamKind::Int => value.parse::<i64>().is_ok(),
+            ParamKind::Uuid => {
+                value.len() == 36
+                    && value.char_indices().all(|(ix, c)| match ix {
+                        8 | 13 | 18 | 23 => c == '-',
+                        _ => c.is_ascii_hexdigit(),
+                    })
+            }
+        }
+    }
+}
+
+/// A pattern as `matchit` takes it, once its optional segments are expanded and the types
+/// of its parameters removed.
+#[derive(Debug, PartialEq)]
+pub(crate) struct ExpandedPattern {
+    /// The pattern without its optional segments, then with each of them in turn.
+    pub paths: Vec<String>,
+    pub kinds: Vec<(String, ParamKind)>,
+}
+
+/// Expands a pattern. Panics on unknown types and on optional segments followed by
+/// required ones, like the other mistakes in route definitions.
+pub(crate) fn expand(pattern: &str) -> ExpandedPattern {
+    let mut required = String::new();
+    let mut optional = Vec::new();
+    let mut kinds = Vec::new();
+
+    for (ix, segment) in pattern.split('/').enumerate() {
+        let separator = if ix == 0 { "" } else { "/" };
+        let Some(param) = segment
+            .strip_prefix('{')
+            .and_then(|param| param.strip_suffix('}'))
+        else {
+            if !optional.is_empty() {
+                panic!("Optional segments must end the route pattern {:?}", pattern);
+            }
+            required.push_str(separator);
+            required.push_str(segment);
+            continue;
+        };
+
+        let (param, is_optional) = match param.strip_suffix('?') {
+            Some(param) => (param, true),
+            None => (param, false),
+        };
+        let (name, kind) = param.split_once(':').unwrap_or((param, ""));
+        let kind = ParamKind::parse(kind)
+            .unwrap_or_else(|| panic!("Unknown parameter type in the route pattern {:?}", pattern));
+        kinds.push((name.trim_start_matches('*').to_string(), kind));
+
+        let segment = format!("{}{{{}}}", separator, name);
+        if is_optional {
+            optional.push(segment);
+        } else if optional.is_empty() {
+            required.push_str(&segment);
+        } else {
+            panic!("Optional segments must end the route pattern {:?}", pattern);
+        }
+    }
+
+    let mut paths = Vec::with_capacity(optional.len() + 1);
+    let mut path = required;
+    for segment in optional {
+        paths.push(if path.is_empty() {
+            "/".to_string()
+        } else {
+            path.clone()
+        });
+        path.push_str(&segment);
+    }
+    paths.push(path);
+    ExpandedPattern { paths, kinds }
+}
+
+/// Adds a pattern to a route map, with each of its expanded paths.
+pub(crate) fn insert(map: &mut RouteMap, pattern: &str) {
+    let expanded = expand(pattern);
+    for path in expanded.paths {
+        map.insert(path, expanded.kinds.clone()).unwrap();
+    }
+}
+
+/// Parameters of the path in the route map, if a route matches it and its values fit
+/// the types of the parameters.
+pub(crate) fn match_path<'m, 'p>(map: &'m RouteMap, path: &'p str) -> Option<Params<'m, 'p>> {
+    let matched = map.at(path).ok()?;
+    let fits = matched.value.iter().all(|(name, kind)| {
+        matched
+            .params
+            .get(name)
+            .is_none_or(|value| kind.accepts(value))
+    });
+    fits.then_some(matched.params)
+}
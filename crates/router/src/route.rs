@@ -1,6 +1,10 @@
-use crate::{Layout, RouterState};
+use crate::{
+    Layout, MatchedRoute, RouterState,
+    loader::{self, RouteLoader},
+    pattern::{self, RouteMapCache},
+};
 use gpui::*;
-use matchit::Router as MatchitRouter;
+use hashbrown::HashMap;
 use smallvec::SmallVec;
 use std::fmt::{Debug, Display};
 
@@ -20,6 +24,9 @@ pub struct Route {
     pub(crate) element: Option<RouteElementFactory>,
     pub(crate) routes: SmallVec<[Box<Route>; 1]>,
     pub(crate) layout: Option<Box<dyn Layout>>,
+    loader: Option<RouteLoader>,
+    title: Option<SharedString>,
+    icon: Option<SharedString>,
 }
 
 impl Default for Route {
@@ -30,6 +37,9 @@ impl Default for Route {
             element: None,
             routes: SmallVec::new(),
             layout: None,
+            loader: None,
+            title: None,
+            icon: None,
         }
     }
 }
@@ -41,6 +51,9 @@ impl Debug for Route {
             .field("path", &self.path)
             .field("layout", &self.layout.is_some())
             .field("element", &self.element.is_some())
+            .field("loader", &self.loader.is_some())
+            .field("title", &self.title)
+            .field("icon", &self.icon)
             .field("routes", &self.routes.len())
             .finish()
     }
@@ -63,6 +76,10 @@ impl Route {
     }
 
     /// The path to match against the current location.
+    ///
+    /// Besides `{name}` parameters and `{*name}` wildcards, patterns can end with
+    /// optional segments like `{tab?}` and restrict parameters to a type like
+    /// `{id:int}`, see [`ParamKind`](crate::ParamKind).
     pub fn path(mut self, path: impl Into<SharedString>) -> Self {
         self.path = Some(path.into());
         self
@@ -103,6 +120,42 @@ impl Route {
         self
     }
 
+    /// Loads data for the route before its element renders, once per location.
+    ///
+    /// The loader receives the parameters of the route. Until it returns, and once it has
+    /// failed, the element can show the state of the data it gets with
+    /// [`use_loader_data`](crate::use_loader_data), whose type is the one the loader returns.
+    ///
+    /// # Examples
+    /// ```
+    /// Route::new()
+    ///     .path("docs/{id:int}")
+    ///     .loader(async |params, cx| fetch_document(&params["id"], cx).await)
+    ///     .element(|_, cx| match use_loader_data::<Document>(cx) { ... })
+    /// ```
+    pub fn loader<F, T, E>(mut self, loader: F) -> Self
+    where
+        F: AsyncFn(HashMap<SharedString, SharedString>, &mut AsyncApp) -> Result<T, E> + 'static,
+        T: 'static,
+        E: Display,
+    {
+        self.loader = Some(loader::route_loader(loader));
+        self
+    }
+
+    /// The title of the route, shown by [`Breadcrumbs`](crate::Breadcrumbs) and in the
+    /// window title with [`use_window_title`](crate::use_window_title).
+    pub fn title(mut self, title: impl Into<SharedString>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// The path of the icon of the route, shown by [`Breadcrumbs`](crate::Breadcrumbs).
+    pub fn icon(mut self, icon: impl Into<SharedString>) -> Self {
+        self.icon = Some(icon.into());
+        self
+    }
+
     /// Sets the route as an index route.
     /// Panics if a path is already set.
     pub fn index(self) -> Self {
@@ -126,9 +179,9 @@ impl Route {
         self
     }
 
-    pub(crate) fn build_route_map(&self, basename: &str) -> MatchitRouter<()> {
+    /// Collects the patterns of the routes rendering an element, from this one down.
+    pub(crate) fn patterns(&self, basename: &str, patterns: &mut Vec<String>) {
         let basename = basename.trim_end_matches('/');
-        let mut router_map = MatchitRouter::new();
 
         let path = match self.path {
             Some(ref path) => format!("{}/{}", basename, path),
@@ -142,25 +195,74 @@ impl Route {
         };
 
         if self.element.is_some() {
-            router_map.insert(path, ()).unwrap();
-            return router_map;
+            patterns.push(path.to_string());
+            return;
         }
 
-        // Recursively build the route map
+        // Recursively collect the patterns
         for route in self.routes.iter() {
-            router_map.merge(route.build_route_map(path)).unwrap();
+            route.patterns(path, patterns);
         }
+    }
 
-        router_map
+    pub(crate) fn in_pattern(&self, basename: &str, path: &str, cx: &mut App) -> bool {
+        let mut patterns = Vec::new();
+        self.patterns(basename, &mut patterns);
+        pattern::match_path(&RouteMapCache::route_map(patterns, cx), path).is_some()
     }
 
-    pub(crate) fn in_pattern(&self, basename: &str, path: &str) -> bool {
-        self.build_route_map(basename).at(path).is_ok()
+    /// Collects the metadata of the route and of its children rendered for the path, which
+    /// the route matches.
+    pub(crate) fn collect_matches(
+        &self,
+        basename: &str,
+        path: &str,
+        matches: &mut Vec<MatchedRoute>,
+        cx: &mut App,
+    ) {
+        let basename = basename.trim_end_matches('/');
+        let pattern = match self.path {
+            Some(ref path) => format!("{}/{}", basename, path),
+            None => basename.to_string(),
+        };
+
+        if self.title.is_some() || self.icon.is_some() {
+            // A layout matches as many segments of the path as its pattern has
+            let pathname = if self.element.is_some() {
+                path.to_string()
+            } else {
+                let depth = pattern.split('/').filter(|s| !s.is_empty()).count();
+                let segments = path.split('/').filter(|s| !s.is_empty()).take(depth);
+                format!("/{}", segments.collect::<Vec<_>>().join("/"))
+            };
+            matches.push(MatchedRoute {
+                pathname: pathname.into(),
+                title: self.title.clone(),
+                icon: self.icon.clone(),
+            });
+        }
+
+        if self.element.is_none()
+            && let Some(route) = self
+                .routes
+                .iter()
+                .find(|route| route.in_pattern(&pattern, path, cx))
+        {
+            route.collect_matches(&pattern, path, matches, cx);
+        }
     }
 }
 
 impl RenderOnce for Route {
     fn render(mut self, window: &mut Window, cx: &mut App) -> impl IntoElement {
+        if let Some(ref loader) = self.loader {
+            let pattern = match self.path {
+                Some(ref path) => format!("{}/{}", self.basename.trim_end_matches('/'), path),
+                None => self.basename.to_string(),
+            };
+            loader(&pattern.into(), cx);
+        }
+
         if let Some(element_fn) = self.element {
             return element_fn(window, cx);
         }
@@ -175,7 +277,7 @@ impl RenderOnce for Route {
             let routes = std::mem::take(&mut self.routes);
             let route = routes
                 .into_iter()
-                .find(|route| route.in_pattern(&basename, &pathname));
+                .find(|route| route.in_pattern(&basename, &pathname, cx));
             if let Some(route) = route {
                 layout.outlet(
                     route
@@ -1,8 +1,15 @@
 #[cfg(test)]
 pub mod tests {
-    use crate::{Route, RouterState, Routes};
+    use crate::pattern::{self, RouteMap, RouteMapCache};
+    use crate::{
+        LoaderData, NavLink, ParamKind, Route, RouterState, Routes, use_loader_data, use_matches,
+        use_params, use_window_title,
+    };
     use gpui::prelude::*;
-    use gpui::{TestAppContext, VisualTestContext, Window};
+    use gpui::{
+        Modifiers, SharedString, TestAppContext, VisualTestContext, Window, div, point, px,
+    };
+    use std::{cell::RefCell, rc::Rc};
 
     struct Basic {}
 
@@ -95,4 +102,320 @@ pub mod tests {
             "About element should not be evaluated during route configuration"
         );
     }
+
+    struct Loaded {}
+
+    impl Render for Loaded {
+        fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+            Routes::new().basename("/").child(
+                Route::new()
+                    .path("docs/{id}")
+                    .loader(async |params, _| params["id"].parse::<i32>())
+                    .element(|_, cx| match use_loader_data::<i32>(cx) {
+                        Some(LoaderData::Loaded(id)) => format!("document {}", id),
+                        Some(LoaderData::Failed(error)) => error.to_string(),
+                        _ => "loading".to_string(),
+                    }),
+            )
+        }
+    }
+
+    #[gpui::test]
+    async fn test_route_loader(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            crate::init(cx);
+            RouterState::global_mut(cx).with_path("/docs/7".into());
+        });
+        cx.add_window(|_, _cx| Loaded {});
+        cx.run_until_parked();
+        cx.update(|cx| {
+            assert!(matches!(
+                use_loader_data::<i32>(cx),
+                Some(LoaderData::Loaded(id)) if *id == 7
+            ));
+            assert!(use_loader_data::<String>(cx).is_none());
+            RouterState::global_mut(cx).with_path("/docs/draft".into());
+            // No data is loaded for the new location until the route renders
+            assert!(use_loader_data::<i32>(cx).is_none());
+            cx.refresh_windows();
+        });
+        cx.run_until_parked();
+        cx.update(|cx| {
+            assert!(matches!(
+                use_loader_data::<i32>(cx),
+                Some(LoaderData::Failed(error)) if error == "invalid digit found in string"
+            ));
+        });
+    }
+
+    struct Titled {}
+
+    impl Render for Titled {
+        fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+            use_window_title("Remindr", window, cx);
+            Routes::new().basename("/").child(
+                Route::new()
+                    .path("docs")
+                    .title("Documents")
+                    .icon("icons/folder.svg")
+                    .child(Route::new().path("new").element(|_, _| "new"))
+                    .child(
+                        Route::new()
+                            .path("{id}")
+                            .title("Document")
+                            .element(|_, _| "document"),
+                    ),
+            )
+        }
+    }
+
+    #[gpui::test]
+    async fn test_route_metadata(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            crate::init(cx);
+            RouterState::global_mut(cx).with_path("/docs/7".into());
+        });
+        cx.add_window(|_, _cx| Titled {});
+        cx.run_until_parked();
+        cx.update(|cx| {
+            let matches = use_matches(cx);
+            assert_eq!(matches.len(), 2);
+            assert_eq!(matches[0].pathname, "/docs");
+            assert_eq!(matches[0].icon.as_deref(), Some("icons/folder.svg"));
+            assert_eq!(matches[1].pathname, "/docs/7");
+            assert_eq!(matches[1].title.as_deref(), Some("Document"));
+            let titles = &RouterState::global(cx).window_titles;
+            assert_eq!(
+                titles.values().collect::<Vec<_>>(),
+                [&SharedString::from("Document - Remindr")]
+            );
+
+            // Routes without metadata get no crumb
+            RouterState::global_mut(cx).with_path("/docs/new".into());
+            cx.refresh_windows();
+        });
+        cx.run_until_parked();
+        cx.update(|cx| {
+            let titles = use_matches(cx)
+                .iter()
+                .map(|matched| matched.title.clone())
+                .collect::<Vec<_>>();
+            assert_eq!(titles, [Some("Documents".into())]);
+        });
+    }
+
+    struct Mounted {
+        prefix: Rc<RefCell<SharedString>>,
+    }
+
+    impl Render for Mounted {
+        fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+            let prefix = self.prefix.clone();
+            Routes::new()
+                .mount(move |_| prefix.borrow().clone())
+                .child(
+                    Route::new()
+                        .index()
+                        .title("Plugin")
+                        .element(|_, _| "plugin"),
+                )
+                .child(
+                    Route::new()
+                        .path("notes/{id:int}")
+                        .title("Note")
+                        .element(|_, _| "note"),
+                )
+        }
+    }
+
+    #[gpui::test]
+    async fn test_mounted_routes(cx: &mut TestAppContext) {
+        let prefix = Rc::new(RefCell::new(SharedString::from("/plugins/editor")));
+        cx.update(|cx| {
+            crate::init(cx);
+            RouterState::global_mut(cx).with_path("/plugins/editor/notes/3".into());
+        });
+        cx.add_window({
+            let prefix = prefix.clone();
+            move |_, _cx| Mounted { prefix }
+        });
+        cx.run_until_parked();
+        cx.update(|cx| {
+            assert_eq!(use_matches(cx)[0].title.as_deref(), Some("Note"));
+            assert_eq!(use_params(cx).get("id").map(|id| id.as_ref()), Some("3"));
+        });
+
+        // The same routes, mounted somewhere else
+        *prefix.borrow_mut() = "calendar/".into();
+        cx.update(|cx| {
+            RouterState::global_mut(cx).with_path("/calendar".into());
+            cx.refresh_windows();
+        });
+        cx.run_until_parked();
+        cx.update(|cx| {
+            assert_eq!(use_matches(cx)[0].pathname, "/calendar");
+            assert_eq!(use_matches(cx)[0].title.as_deref(), Some("Plugin"));
+        });
+    }
+
+    struct Prefetching {
+        prefetched: Rc<RefCell<Vec<SharedString>>>,
+    }
+
+    impl Render for Prefetching {
+        fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+            let prefetched = self.prefetched.clone();
+            div().size_full().child(
+                NavLink::new()
+                    .to("/about")
+                    .size_full()
+                    .on_prefetch(move |to, _, _| prefetched.borrow_mut().push(to.clone()))
+                    .child("About"),
+            )
+        }
+    }
+
+    #[gpui::test]
+    async fn test_nav_link_prefetch(cx: &mut TestAppContext) {
+        cx.update(crate::init);
+        let prefetched = Rc::new(RefCell::new(Vec::new()));
+        let (_, cx) = cx.add_window_view({
+            let prefetched = prefetched.clone();
+            move |_, _| Prefetching { prefetched }
+        });
+
+        cx.simulate_mouse_move(point(px(10.), px(10.)), None, Modifiers::none());
+        assert_eq!(*prefetched.borrow(), ["/about"]);
+        // Hovering the link doesn't navigate
+        cx.update(|_, cx| assert_eq!(RouterState::global(cx).location.pathname, "/"));
+    }
+
+    fn route_map(patterns: &[&str]) -> RouteMap {
+        let mut map = RouteMap::new();
+        for pattern in patterns {
+            pattern::insert(&mut map, pattern);
+        }
+        map
+    }
+
+    fn params(map: &RouteMap, path: &str) -> Option<Vec<(String, String)>> {
+        pattern::match_path(map, path).map(|params| {
+            params
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect()
+        })
+    }
+
+    #[test]
+    fn test_optional_segments() {
+        assert_eq!(
+            pattern::expand("/docs/{id}/{tab?}").paths,
+            ["/docs/{id}", "/docs/{id}/{tab}"]
+        );
+        assert_eq!(pattern::expand("/{page?}").paths, ["/", "/{page}"]);
+
+        let map = route_map(&["/docs/{id}/{tab?}"]);
+        assert_eq!(
+            params(&map, "/docs/1"),
+            Some(vec![("id".into(), "1".into())])
+        );
+        assert_eq!(
+            params(&map, "/docs/1/history"),
+            Some(vec![
+                ("id".into(), "1".into()),
+                ("tab".into(), "history".into())
+            ])
+        );
+        assert_eq!(params(&map, "/docs/1/history/2"), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Optional segments must end")]
+    fn test_optional_segments_end_the_pattern() {
+        pattern::expand("/docs/{tab?}/{id}");
+    }
+
+    #[test]
+    fn test_typed_params() {
+        let map = route_map(&[
+            "/docs/{id:int}/{tab?}",
+            "/users/{id:uuid}",
+            "/files/{*path:int}",
+        ]);
+        assert!(params(&map, "/docs/-3").is_some());
+        assert!(params(&map, "/docs/abc").is_none());
+        assert!(params(&map, "/docs/abc/history").is_none());
+        assert!(params(&map, "/users/67e55044-10b1-426f-9247-bb680e5fe0c8").is_some());
+        assert!(params(&map, "/users/67e55044").is_none());
+        assert!(params(&map, "/files/12").is_some());
+        assert!(params(&map, "/files/1/2").is_none());
+        assert!(ParamKind::Int.accepts("42"));
+        assert!(!ParamKind::Uuid.accepts("67e55044-10b1-426f-9247-bb680e5fe0cz"));
+    }
+
+    #[gpui::test]
+    fn test_matching_precedence(cx: &mut TestAppContext) {
+        let map = route_map(&[
+            "/docs/new",
+            "/docs/{id:int}/{tab?}",
+            "/docs/{id}/history",
+            "/{*not_match}",
+        ]);
+        // Static segments first, then parameters, then wildcards
+        assert_eq!(params(&map, "/docs/new"), Some(vec![]));
+        assert_eq!(
+            params(&map, "/docs/2/history"),
+            Some(vec![("id".into(), "2".into())])
+        );
+        assert_eq!(
+            params(&map, "/docs/2/comments"),
+            Some(vec![
+                ("id".into(), "2".into()),
+                ("tab".into(), "comments".into())
+            ])
+        );
+        assert_eq!(
+            params(&map, "/settings"),
+            Some(vec![("not_match".into(), "settings".into())])
+        );
+        // The type is checked once the route is chosen, without falling back to another
+        assert_eq!(params(&map, "/docs/draft"), None);
+
+        cx.update(|cx| {
+            crate::init(cx);
+            let route = Route::new()
+                .path("docs/{id:int}/{tab?}")
+                .element(|_, _| "document");
+            assert!(route.in_pattern("/", "/docs/2", cx));
+            assert!(!route.in_pattern("/", "/docs/draft", cx));
+        });
+    }
+
+    #[gpui::test]
+    async fn test_route_maps_are_cached(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            crate::init(cx);
+            RouterState::global_mut(cx).with_path("/docs/new".into());
+        });
+        cx.add_window(|_, _cx| Titled {});
+        cx.run_until_parked();
+        let built = cx.update(|cx| cx.global::<RouteMapCache>().len());
+        assert!(built > 0);
+
+        // Rendering the same routes again builds nothing
+        cx.update(|cx| {
+            RouterState::global_mut(cx).with_path("/docs/7".into());
+            cx.refresh_windows();
+        });
+        cx.run_until_parked();
+        cx.update(|cx| {
+            assert_eq!(cx.global::<RouteMapCache>().len(), built);
+            let patterns = || vec!["/docs/new".to_string(), "/docs/{id}".to_string()];
+            assert!(Rc::ptr_eq(
+                &RouteMapCache::route_map(patterns(), cx),
+                &RouteMapCache::route_map(patterns(), cx)
+            ));
+        });
+    }
 }
@@ -1,14 +1,17 @@
 use crate::Route;
 use crate::RouterState;
+use crate::pattern::{self, RouteMapCache};
 use gpui::prelude::*;
 use gpui::{App, Empty, SharedString, Window};
-use matchit::Router as MatchitRouter;
 use smallvec::SmallVec;
 
+type RoutesPrefix = Box<dyn Fn(&App) -> SharedString>;
+
 /// Renders a branch of [`Route`](crate::Route) that best matches the current path.
 #[derive(IntoElement)]
 pub struct Routes {
     basename: SharedString,
+    prefix: Option<RoutesPrefix>,
     routes: SmallVec<[Route; 1]>,
 }
 
@@ -22,6 +25,7 @@ impl Routes {
     pub fn new() -> Self {
         Self {
             basename: SharedString::from("/"),
+            prefix: None,
             routes: SmallVec::new(),
         }
     }
@@ -32,6 +36,23 @@ impl Routes {
         self
     }
 
+    /// Mounts the routes under a prefix resolved each time they render, after the basename.
+    ///
+    /// This lets a window or a plugin host its own routes wherever the app places it, like
+    /// `/settings/{section}` or `/plugins/{name}`, without knowing that place in advance.
+    ///
+    /// # Examples
+    /// ```
+    /// Routes::new()
+    ///     .mount(move |cx| plugin.read(cx).mount_path.clone())
+    ///     .child(Route::new().index().element(|_, _| "home"))
+    ///     .child(Route::new().path("about").element(|_, _| "about"))
+    /// ```
+    pub fn mount(mut self, prefix: impl Fn(&App) -> SharedString + 'static) -> Self {
+        self.prefix = Some(Box::new(prefix));
+        self
+    }
+
     /// Adds a `Route` as a child to the `Routes`.
     pub fn child(mut self, child: Route) -> Self {
         self.routes.push(child);
@@ -58,28 +79,48 @@ impl RenderOnce for Routes {
             panic!("RouterState not initialized");
         }
 
-        let mut route_map = MatchitRouter::new();
+        let basename = match self.prefix {
+            Some(ref prefix) => {
+                let prefix = prefix(cx);
+                let prefix = prefix.trim_matches('/');
+                if prefix.is_empty() {
+                    self.basename.clone()
+                } else {
+                    format!("{}/{}", self.basename.trim_end_matches('/'), prefix).into()
+                }
+            }
+            None => self.basename.clone(),
+        };
+
+        let mut patterns = Vec::new();
         for route in self.routes.iter() {
-            route_map
-                .merge(route.build_route_map(&self.basename))
-                .unwrap();
+            route.patterns(&basename, &mut patterns);
         }
+        let route_map = RouteMapCache::route_map(patterns, cx);
 
         let pathname = cx.global::<RouterState>().location.pathname.clone();
-        let matched = route_map.at(&pathname);
-
-        if let Ok(matched) = matched {
-            for (key, value) in matched.params.iter() {
-                cx.global_mut::<RouterState>()
+        if let Some(params) = pattern::match_path(&route_map, &pathname) {
+            // Parameters of the previous location are gone, like an optional segment
+            let state = cx.global_mut::<RouterState>();
+            state.params.clear();
+            for (key, value) in params.iter() {
+                state
                     .params
                     .insert(key.to_owned().into(), value.to_owned().into());
             }
             let route = self
                 .routes
                 .into_iter()
-                .find(|route| route.in_pattern(&self.basename, &pathname));
+                .find(|route| route.in_pattern(&basename, &pathname, cx));
+
+            let mut matches = Vec::new();
+            if let Some(ref route) = route {
+                route.collect_matches(&basename, &pathname, &mut matches, cx);
+            }
+            cx.global_mut::<RouterState>().matches = matches;
+
             if let Some(route) = route {
-                return route.basename(self.basename).into_any_element();
+                return route.basename(basename).into_any_element();
             }
         }
 
@@ -1,4 +1,4 @@
-use gpui::{App, Global, SharedString};
+use gpui::{AnyWindowHandle, App, Global, SharedString};
 use hashbrown::HashMap;
 use matchit::Params;
 
@@ -37,6 +37,17 @@ pub struct PathMatch {
     pub params: Params<'static, 'static>,
 }
 
+/// A route matched by the current location, with the metadata it was given.
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct MatchedRoute {
+    /// The portion of the current pathname the route matched, to navigate back to it.
+    pub pathname: SharedString,
+    /// The title of the route, see [`Route::title`](crate::Route::title).
+    pub title: Option<SharedString>,
+    /// The icon of the route, see [`Route::icon`](crate::Route::icon).
+    pub icon: Option<SharedString>,
+}
+
 /// The global state of the router, including the current location, path match, and parameters.
 /// This state is stored globally within the GPUI application context.
 #[derive(PartialEq, Clone)]
@@ -47,6 +58,11 @@ pub struct RouterState {
     pub path_match: Option<PathMatch>,
     /// The dynamic parameters for the current location.
     pub params: HashMap<SharedString, SharedString>,
+    /// The routes with metadata matched by the current location, from the outermost layout
+    /// to the route whose element renders.
+    pub matches: Vec<MatchedRoute>,
+    /// The titles last given to the windows by [`use_window_title`](crate::use_window_title).
+    pub(crate) window_titles: HashMap<AnyWindowHandle, SharedString>,
 }
 
 impl Global for RouterState {}
@@ -59,6 +75,8 @@ impl RouterState {
             location: Location::default(),
             path_match: None,
             params: HashMap::new(),
+            matches: Vec::new(),
+            window_titles: HashMap::new(),
         };
         cx.set_global::<RouterState>(state);
     }